@@ -2,6 +2,7 @@
 # ruff: noqa: E501, F401, F403, F405
 
 import builtins
+import collections.abc
 import typing
 from . import sub_mod
 __all__ = [
@@ -15,7 +16,7 @@ class ClassB:
     def collision(self, a: sub_mod.ClassA) -> sub_mod.ClassA: ...
     def collision_with_def(self, a: sub_mod.ClassA = sub_mod.ClassA.Option1) -> sub_mod.ClassA: ...
     def test_optional(self, a: typing.Optional[sub_mod.ClassA]) -> typing.Optional[sub_mod.ClassA]: ...
-    def with_callback(self, callback: typing.Callable[[sub_mod.ClassA], sub_mod.ClassA]) -> None: ...
+    def with_callback(self, callback: collections.abc.Callable[[sub_mod.ClassA], sub_mod.ClassA]) -> None: ...
     def classes_b(self) -> builtins.list[sub_mod.ClassA]: ...
     def classes_b_manual(self, other: typing.Generator[sub_mod.ClassA, None, None]) -> builtins.list[sub_mod.ClassA]: ...
     def who_am_i(self, other: typing.Sequence[ClassB]) -> builtins.list[ClassB]: ...
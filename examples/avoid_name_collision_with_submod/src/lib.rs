@@ -103,20 +103,7 @@ impl FromPyObject<'_, '_> for ClassACallback {
 
 impl PyStubType for ClassACallback {
     fn type_output() -> TypeInfo {
-        TypeInfo {
-            name: "typing.Callable[[ClassA], ClassA]".to_string(),
-            source_module: None,
-            import: [].into(),
-            type_refs: [(
-                "ClassA".to_string(),
-                TypeIdentifierRef {
-                    // ClassA is the Python name for PyClassA (Rust enum) in sub_mod
-                    module: "avoid_name_collision_with_submod.sub_mod".into(),
-                    import_kind: ImportKind::Module,
-                },
-            )]
-            .into(),
-        }
+        TypeInfo::callable([PyClassA::type_input()], PyClassA::type_output())
     }
 }
 pyo3_stub_gen::impl_py_runtime_type!(ClassACallback);
@@ -58,10 +58,12 @@ __all__ = [
     "PartialManualSubmit",
     "Placeholder",
     "Problem",
+    "RangeError",
     "RuntimeNumberOrString",
     "SequenceOfInts",
     "Shape1",
     "Shape2",
+    "Shape3",
     "SimpleAlias",
     "SimpleContainer",
     "SingleTypeAlias",
@@ -76,6 +78,7 @@ __all__ = [
     "ahash_dict",
     "as_tuple",
     "async_num",
+    "check_range",
     "create_a",
     "create_containers",
     "create_dict",
@@ -89,8 +92,10 @@ __all__ = [
     "fn_with_python_param",
     "fn_with_python_stub",
     "func_with_kwargs",
+    "func_with_kwargs_override",
     "func_with_star_arg",
     "func_with_star_arg_typed",
+    "func_with_typed_var_args",
     "get_chrono_duration",
     "get_date",
     "get_datetime_fixed_offset",
@@ -108,11 +113,13 @@ __all__ = [
     "get_utc_offset",
     "ipv4_localhost",
     "ipv6_localhost",
+    "is_circle",
     "is_loopback",
     "manual_overload_as_tuple",
     "manual_overload_example_1",
     "manual_overload_example_2",
     "naive_time_difference",
+    "nonzero_quotients",
     "overload_example_1",
     "overload_example_2",
     "parse_ip",
@@ -278,6 +285,7 @@ class ComparableStruct:
     r"""
     Test struct for eq and ord comparison methods
     """
+    __hash__: typing.ClassVar[None]  # type: ignore[assignment]
     @property
     def value(self) -> builtins.int: ...
     def __eq__(self, other: builtins.object, /) -> builtins.bool: ...
@@ -293,14 +301,14 @@ class CustomComplexEnum:
     """
     @typing.final
     class VARIANT_A(CustomComplexEnum):
-        __match_args__ = ("value",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["value"]]] = ("value",)
         @property
         def value(self) -> builtins.int: ...
         def __new__(cls, value: builtins.int) -> CustomComplexEnum.VARIANT_A: ...
     
     @typing.final
     class VARIANT_B(CustomComplexEnum):
-        __match_args__ = ("_0",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["_0"]]] = ("_0",)
         @property
         def _0(self) -> builtins.str: ...
         def __new__(cls, _0: builtins.str) -> CustomComplexEnum.VARIANT_B: ...
@@ -384,6 +392,10 @@ class HashableStruct:
     def __hash__(self) -> builtins.int: ...
     def __str__(self) -> builtins.str: ...
     def __new__(cls, name: builtins.str) -> HashableStruct: ...
+    separators: typing.ClassVar[tuple[builtins.str, builtins.str]]
+    """
+    Delimiters around the name in the string representation
+    """
 
 @typing.final
 class InstanceValue:
@@ -459,7 +471,7 @@ class NumberComplex:
         r"""
         Float variant
         """
-        __match_args__ = ("_0",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["_0"]]] = ("_0",)
         @property
         def _0(self) -> builtins.float: ...
         def __new__(cls, _0: builtins.float) -> NumberComplex.FLOAT: ...
@@ -471,11 +483,14 @@ class NumberComplex:
         r"""
         Integer variant
         """
-        __match_args__ = ("int",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["int"]]] = ("int",)
         @property
         def int(self) -> builtins.int:
             r"""
             The integer value
+            ```python
+            default = 2
+            ```
             """
         def __new__(cls, int: builtins.int = 2) -> NumberComplex.INTEGER: ...
     
@@ -537,6 +552,23 @@ class Problem:
         such as dict value types. The marker should expand to the correct Python type.
         """
 
+@typing.final
+class RangeError(builtins.ValueError):
+    r"""
+    A custom exception with attributes
+    
+    The getters are written in the stub file, so that `except RangeError as e: e.lower` type-checks.
+    """
+    @property
+    def lower(self) -> builtins.int:
+        r"""
+        Lower bound of the range
+        """
+    @property
+    def upper(self) -> builtins.int: ...
+    def __new__(cls, lower: builtins.int, upper: builtins.int) -> RangeError: ...
+    def __str__(self) -> builtins.str: ...
+
 class Shape1:
     r"""
     Example from PyO3 documentation for complex enum
@@ -544,14 +576,14 @@ class Shape1:
     """
     @typing.final
     class Circle(Shape1):
-        __match_args__ = ("radius",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["radius"]]] = ("radius",)
         @property
         def radius(self) -> builtins.float: ...
         def __new__(cls, radius: builtins.float) -> Shape1.Circle: ...
     
     @typing.final
     class Rectangle(Shape1):
-        __match_args__ = ("width", "height",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["width"], typing.Literal["height"]]] = ("width", "height")
         @property
         def width(self) -> builtins.float: ...
         @property
@@ -560,7 +592,7 @@ class Shape1:
     
     @typing.final
     class RegularPolygon(Shape1):
-        __match_args__ = ("_0", "_1",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["_0"], typing.Literal["_1"]]] = ("_0", "_1")
         @property
         def _0(self) -> builtins.int: ...
         @property
@@ -571,7 +603,6 @@ class Shape1:
     
     @typing.final
     class Nothing(Shape1):
-        __match_args__ = ()
         def __new__(cls) -> Shape1.Nothing: ...
     
     ...
@@ -583,14 +614,14 @@ class Shape2:
     """
     @typing.final
     class Circle(Shape2):
-        __match_args__ = ("radius",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["radius"]]] = ("radius",)
         @property
         def radius(self) -> builtins.float: ...
         def __new__(cls, radius: builtins.float = 1.0) -> Shape2.Circle: ...
     
     @typing.final
     class Rectangle(Shape2):
-        __match_args__ = ("width", "height",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["width"], typing.Literal["height"]]] = ("width", "height")
         @property
         def width(self) -> builtins.float: ...
         @property
@@ -599,7 +630,7 @@ class Shape2:
     
     @typing.final
     class RegularPolygon(Shape2):
-        __match_args__ = ("side_count", "radius",)
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["side_count"], typing.Literal["radius"]]] = ("side_count", "radius")
         @property
         def side_count(self) -> builtins.int: ...
         @property
@@ -608,11 +639,31 @@ class Shape2:
     
     @typing.final
     class Nothing(Shape2):
-        __match_args__ = ()
         def __new__(cls) -> Shape2.Nothing: ...
     
     ...
 
+class Shape3:
+    r"""
+    Complex enum with a variant whose positional patterns are not exposed to type checkers
+    """
+    @typing.final
+    class Circle(Shape3):
+        __match_args__: typing.ClassVar[builtins.tuple[typing.Literal["radius"]]] = ("radius",)
+        @property
+        def radius(self) -> builtins.float: ...
+        def __new__(cls, radius: builtins.float) -> Shape3.Circle: ...
+    
+    @typing.final
+    class Rectangle(Shape3):
+        @property
+        def width(self) -> builtins.float: ...
+        @property
+        def height(self) -> builtins.float: ...
+        def __new__(cls, width: builtins.float, height: builtins.float) -> Shape3.Rectangle: ...
+    
+    ...
+
 @typing.final
 class TypeIgnoreTest:
     r"""
@@ -691,6 +742,11 @@ def as_tuple(xs: collections.abc.Sequence[int], /, *, tuple_out: typing.Literal[
 
 async def async_num() -> builtins.int: ...
 
+def check_range(value: builtins.int, lower: builtins.int, upper: builtins.int) -> builtins.int:
+    r"""
+    Returns `value` if it is in `[lower, upper]`, and raises `RangeError` otherwise
+    """
+
 def create_a(x: builtins.int = 2) -> A: ...
 
 def create_containers(count: builtins.int) -> builtins.list[DataContainer]:
@@ -734,6 +790,11 @@ def func_with_kwargs(**kwargs: typing.Any) -> builtins.bool:
     Takes a variable number of keyword arguments and does nothing
     """
 
+def func_with_kwargs_override(name: builtins.str, /, *, strict: builtins.bool = False, **kwargs: int) -> builtins.int:
+    r"""
+    Takes keyword arguments with an overridden type alongside positional-only and keyword-only arguments.
+    """
+
 def func_with_star_arg(*args: typing.Any) -> builtins.str:
     r"""
     Takes a variable number of arguments and returns their string representation.
@@ -744,6 +805,11 @@ def func_with_star_arg_typed(*args: str) -> builtins.str:
     Takes a variable number of arguments and returns their string representation.
     """
 
+def func_with_typed_var_args(*args: int, **kwargs: str) -> builtins.int:
+    r"""
+    Takes typed variable positional and keyword arguments and returns their count.
+    """
+
 def get_chrono_duration(seconds: builtins.int) -> datetime.timedelta:
     r"""
     Returns a chrono::Duration from seconds
@@ -829,6 +895,11 @@ def ipv6_localhost() -> ipaddress.IPv6Address:
     Returns the IPv6 loopback address (::1).
     """
 
+def is_circle(shape: Shape1) -> typing_extensions.TypeIs[Shape1.Circle]:
+    r"""
+    Returns whether the shape is a circle, narrowing its type to `Shape1.Circle`.
+    """
+
 def is_loopback(addr: ipaddress.IPv4Address | ipaddress.IPv6Address) -> builtins.bool:
     r"""
     Returns whether the given IP address is a loopback address.
@@ -869,6 +940,11 @@ def naive_time_difference(time1: datetime.time, time2: datetime.time) -> datetim
     Calculate the difference between two NaiveTimes as duration
     """
 
+def nonzero_quotients(v: typing.Sequence[builtins.int], divisor: builtins.int) -> builtins.list[builtins.int]:
+    r"""
+    Nonzero quotients of `v` divided by `divisor`
+    """
+
 @typing.overload
 def overload_example_1(x: int) -> int: ...
 
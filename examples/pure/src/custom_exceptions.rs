@@ -1,5 +1,5 @@
 use pyo3::{
-    exceptions::{PyRuntimeError, PyTypeError},
+    exceptions::{PyRuntimeError, PyTypeError, PyValueError},
     prelude::*,
     types::*,
 };
@@ -58,3 +58,41 @@ impl NotIntError {
         Ok(self.item.bind(py).is_instance_of::<PyString>())
     }
 }
+
+/// A custom exception with attributes
+///
+/// The getters are written in the stub file, so that `except RangeError as e: e.lower` type-checks.
+#[gen_stub_pyclass]
+#[pyclass(frozen, extends=PyValueError)]
+#[derive(Debug)]
+pub struct RangeError {
+    /// Lower bound of the range
+    #[pyo3(get)]
+    lower: i64,
+    #[pyo3(get)]
+    upper: i64,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl RangeError {
+    #[new]
+    fn new(lower: i64, upper: i64) -> RangeError {
+        RangeError { lower, upper }
+    }
+
+    fn __str__(&self) -> String {
+        format!("Expected a value in [{}, {}]", self.lower, self.upper)
+    }
+}
+
+/// Returns `value` if it is in `[lower, upper]`, and raises `RangeError` otherwise
+#[gen_stub_pyfunction]
+#[pyfunction]
+pub fn check_range(value: i64, lower: i64, upper: i64) -> PyResult<i64> {
+    if (lower..=upper).contains(&value) {
+        Ok(value)
+    } else {
+        Err(PyErr::new::<RangeError, _>((lower, upper)))
+    }
+}
@@ -30,11 +30,11 @@ mod readme {}
 use ahash::RandomState;
 use pyo3::{prelude::*, types::*};
 use pyo3_stub_gen::{
-    define_stub_info_gatherer, derive::*, module_doc, module_variable,
+    class_verbatim, define_stub_info_gatherer, derive::*, module_doc, module_variable,
     runtime::PyModuleTypeAliasExt, type_alias,
 };
 use rust_decimal::Decimal;
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, num::NonZeroU32, path::PathBuf};
 
 /// Returns the sum of two numbers as a string.
 #[gen_stub_pyfunction]
@@ -63,6 +63,18 @@ fn create_dict(n: usize) -> HashMap<usize, Vec<usize>> {
     dict
 }
 
+// `NonZeroU32` is converted from and into `int` by PyO3, but has no `PyStubType` in pyo3-stub-gen
+pyo3_stub_gen::register_stub_type!(NonZeroU32 => "builtins.int", imports = ["builtins"]);
+
+/// Nonzero quotients of `v` divided by `divisor`
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn nonzero_quotients(v: Vec<u32>, divisor: NonZeroU32) -> Vec<NonZeroU32> {
+    v.into_iter()
+        .filter_map(|x| NonZeroU32::new(x / divisor))
+        .collect()
+}
+
 /// Add two decimal numbers with high precision
 #[gen_stub_pyfunction]
 #[pyfunction]
@@ -362,6 +374,7 @@ pub enum NumberComplex {
     #[pyo3(constructor = (int=2))]
     Integer {
         /// The integer value
+        #[gen_stub(default = 2)]
         int: i32,
     },
 }
@@ -377,6 +390,14 @@ enum Shape1 {
     Nothing {},
 }
 
+/// Returns whether the shape is a circle, narrowing its type to `Shape1.Circle`.
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[gen_stub(type_is = "Shape1.Circle")]
+fn is_circle(shape: &Shape1) -> bool {
+    matches!(shape, Shape1::Circle { .. })
+}
+
 /// Example from PyO3 documentation for complex enum
 /// https://pyo3.rs/v0.25.1/class.html#complex-enums
 #[gen_stub_pyclass_complex_enum]
@@ -399,6 +420,20 @@ enum Shape2 {
     Nothing {},
 }
 
+/// Complex enum with a variant whose positional patterns are not exposed to type checkers
+#[gen_stub_pyclass_complex_enum]
+#[pyclass]
+enum Shape3 {
+    Circle {
+        radius: f64,
+    },
+    #[gen_stub(no_match_args)]
+    Rectangle {
+        width: f64,
+        height: f64,
+    },
+}
+
 #[gen_stub_pymethods]
 #[pymethods]
 impl Number {
@@ -497,8 +532,25 @@ impl HashableStruct {
     fn new(name: String) -> Self {
         Self { name }
     }
+
+    #[classattr]
+    #[gen_stub(skip)]
+    fn separators() -> (String, String) {
+        ("(".to_string(), ")".to_string())
+    }
 }
 
+class_verbatim!(
+    HashableStruct,
+    r#"
+    separators: typing.ClassVar[tuple[builtins.str, builtins.str]]
+    """
+    Delimiters around the name in the string representation
+    """
+    "#,
+    imports = ["typing", "builtins"]
+);
+
 /// Takes a variable number of arguments and returns their string representation.
 #[gen_stub_pyfunction]
 #[pyfunction]
@@ -525,6 +577,28 @@ fn func_with_kwargs(kwargs: Option<&Bound<PyDict>>) -> bool {
     kwargs.is_some()
 }
 
+/// Takes typed variable positional and keyword arguments and returns their count.
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[pyo3(signature = (*args, **kwargs))]
+#[gen_stub(var_args_type = "int", kwargs_value_type = "str")]
+fn func_with_typed_var_args(args: &Bound<PyTuple>, kwargs: Option<&Bound<PyDict>>) -> usize {
+    args.len() + kwargs.map_or(0, |kwargs| kwargs.len())
+}
+
+/// Takes keyword arguments with an overridden type alongside positional-only and keyword-only arguments.
+#[gen_stub_pyfunction]
+#[pyfunction]
+#[pyo3(signature = (name, /, *, strict = false, **kwargs))]
+fn func_with_kwargs_override(
+    name: &str,
+    strict: bool,
+    #[gen_stub(override_type(type_repr = "int"))] kwargs: Option<&Bound<PyDict>>,
+) -> usize {
+    let _ = strict;
+    name.len() + kwargs.map_or(0, |kwargs| kwargs.len())
+}
+
 module_doc!("pure", "Document for {} ...", env!("CARGO_PKG_NAME"));
 
 /// Initializes the Python module
@@ -540,6 +614,7 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<NumberComplex>()?;
     m.add_class::<Shape1>()?;
     m.add_class::<Shape2>()?;
+    m.add_class::<Shape3>()?;
     m.add_class::<ManualSubmit>()?;
     m.add_class::<PartialManualSubmit>()?;
     m.add_class::<OverrideType>()?;
@@ -580,6 +655,7 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(manual_overload_example_2, m)?)?;
     m.add_function(wrap_pyfunction!(manual_overload_as_tuple, m)?)?;
     m.add_function(wrap_pyfunction!(add_decimals, m)?)?;
+    m.add_function(wrap_pyfunction!(nonzero_quotients, m)?)?;
     m.add_function(wrap_pyfunction!(process_container, m)?)?;
     m.add_function(wrap_pyfunction!(sum_list, m)?)?;
     m.add_function(wrap_pyfunction!(create_containers, m)?)?;
@@ -587,6 +663,8 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(func_with_star_arg, m)?)?;
     m.add_function(wrap_pyfunction!(func_with_star_arg_typed, m)?)?;
     m.add_function(wrap_pyfunction!(func_with_kwargs, m)?)?;
+    m.add_function(wrap_pyfunction!(func_with_typed_var_args, m)?)?;
+    m.add_function(wrap_pyfunction!(func_with_kwargs_override, m)?)?;
 
     // Test cases for type: ignore functionality
     m.add_function(wrap_pyfunction!(test_type_ignore_specific, m)?)?;
@@ -599,6 +677,8 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     // Test case for custom exceptions
     m.add("MyError", m.py().get_type::<MyError>())?;
     m.add_class::<NotIntError>()?;
+    m.add_class::<RangeError>()?;
+    m.add_function(wrap_pyfunction!(check_range, m)?)?;
 
     // Test case for runtime type alias (type_alias! macro)
     m.add_type_alias::<RuntimeNumberOrString>()?;
@@ -634,6 +714,7 @@ fn pure(m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(ipv6_localhost, m)?)?;
     m.add_function(wrap_pyfunction!(parse_ip, m)?)?;
     m.add_function(wrap_pyfunction!(is_loopback, m)?)?;
+    m.add_function(wrap_pyfunction!(is_circle, m)?)?;
 
     // Test cases for f64 special values (INFINITY, NEG_INFINITY, NAN)
     m.add_class::<FloatValues>()?;
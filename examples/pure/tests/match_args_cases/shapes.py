@@ -0,0 +1,52 @@
+"""Class patterns of the complex enums, type checked by mypy in `test_match_args.py`
+
+A line ending with `# E` is expected to be an error, and `# N: <type>` to reveal the type.
+"""
+
+from typing_extensions import reveal_type
+
+from pure import Shape1, Shape2, Shape3
+
+
+def shape1(shape: Shape1) -> None:
+    match shape:
+        case Shape1.Circle(radius):
+            reveal_type(radius)  # N: builtins.float
+        case Shape1.Rectangle(width, height):
+            reveal_type(height)  # N: builtins.float
+        case Shape1.RegularPolygon(count, radius):
+            reveal_type(count)  # N: builtins.int
+        case Shape1.Nothing():
+            pass
+
+
+def shape1_empty(shape: Shape1) -> None:
+    # Empty variants do not declare `__match_args__`
+    match shape:
+        case Shape1.Nothing(value):  # E
+            pass
+
+
+def shape2(shape: Shape2) -> None:
+    match shape:
+        case Shape2.Circle(radius):
+            reveal_type(radius)  # N: builtins.float
+        case Shape2.RegularPolygon(side_count, radius):
+            reveal_type(side_count)  # N: builtins.int
+        case Shape2.Rectangle(width=width):
+            reveal_type(width)  # N: builtins.float
+
+
+def shape3(shape: Shape3) -> None:
+    match shape:
+        case Shape3.Circle(radius):
+            reveal_type(radius)  # N: builtins.float
+        case Shape3.Rectangle(width=width, height=height):
+            reveal_type(height)  # N: builtins.float
+
+
+def shape3_no_match_args(shape: Shape3) -> None:
+    # `Rectangle` has `#[gen_stub(no_match_args)]`
+    match shape:
+        case Shape3.Rectangle(width, height):  # E
+            pass
@@ -0,0 +1,56 @@
+"""`__match_args__` of the complex enums `Shape1`, `Shape2` and `Shape3`,
+checked against class patterns by mypy and against the runtime by stubtest
+"""
+
+import re
+import subprocess
+from pathlib import Path
+
+PROJECT_DIR = Path(__file__).parent.parent
+CASES = Path(__file__).parent / "match_args_cases" / "shapes.py"
+EXPECTATION = re.compile(r"#\s*(?:E|N: (.+))$")
+REPORT = re.compile(r".*?:(\d+): (error|note): (.*)$")
+REVEALED = re.compile(r'Revealed type is "(.*)"')
+
+
+def expected_reports() -> dict[int, str]:
+    """Expected errors as `"error"` and revealed types by line"""
+    expected = {}
+    for lineno, line in enumerate(CASES.read_text().splitlines(), 1):
+        if m := EXPECTATION.search(line):
+            expected[lineno] = m[1] or "error"
+    return expected
+
+
+def test_mypy_match_args():
+    result = subprocess.run(
+        ["mypy", "--show-error-codes", "--no-error-summary", str(CASES)],
+        cwd=PROJECT_DIR,
+        capture_output=True,
+        text=True,
+    )
+    reported = {}
+    for line in result.stdout.splitlines():
+        if not (m := REPORT.match(line)):
+            continue
+        lineno, severity, message = int(m[1]), m[2], m[3]
+        if severity == "error":
+            reported[lineno] = "error"
+        elif revealed := REVEALED.match(message):
+            reported[lineno] = revealed[1]
+    assert reported == expected_reports(), result.stdout
+
+
+def test_stubtest_match_args():
+    result = subprocess.run(
+        ["stubtest", "pure", "--ignore-missing-stub", "--ignore-disjoint-bases"],
+        cwd=PROJECT_DIR,
+        capture_output=True,
+        text=True,
+    )
+    errors = [
+        line
+        for line in result.stdout.splitlines()
+        if re.match(r"error: pure\.Shape[123]\b", line)
+    ]
+    assert errors == [], result.stdout
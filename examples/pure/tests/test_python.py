@@ -10,6 +10,8 @@ from pure import (
     NumberComplex,
     Shape1,
     Shape2,
+    Shape3,
+    is_circle,
     ComparableStruct,
     HashableStruct,
     add_decimals,
@@ -20,6 +22,9 @@ from pure import (
     fn_override_type,
     fn_with_python_param,
     fn_with_python_stub,
+    nonzero_quotients,
+    check_range,
+    RangeError,
 )
 import pytest
 import pathlib
@@ -113,6 +118,9 @@ def test_complex_enum_shape1():
     assert count_vertices(Shape1, circle) == 0
     assert count_vertices(Shape1, square) == 4
 
+    assert is_circle(circle)
+    assert not is_circle(square)
+
 
 # Test code for complex enum case from PyO3 document
 # https://pyo3.rs/v0.25.1/class.html#complex-enums
@@ -135,6 +143,38 @@ def test_complex_enum_shape2():
     assert hexagon.radius == 1
 
 
+def test_complex_enum_match_args():
+    def describe(shape: Shape1) -> str:
+        match shape:
+            case Shape1.Circle(radius):
+                return f"circle {radius}"
+            case Shape1.Rectangle(width, height):
+                return f"rectangle {width}x{height}"
+            case Shape1.RegularPolygon(n, radius):
+                return f"polygon {n} {radius}"
+            case Shape1.Nothing():
+                return "nothing"
+            case _:
+                raise AssertionError
+
+    assert describe(Shape1.Circle(radius=1.0)) == "circle 1.0"
+    assert describe(Shape1.Rectangle(2.0, 3.0)) == "rectangle 2.0x3.0"
+    assert describe(Shape1.RegularPolygon(6, 1.0)) == "polygon 6 1.0"
+    assert describe(Shape1.Nothing()) == "nothing"
+
+    match Shape3.Circle(radius=2.0):
+        case Shape3.Circle(radius):
+            assert radius == 2.0
+        case _:
+            raise AssertionError
+    # `Rectangle` has `#[gen_stub(no_match_args)]`, only keyword patterns are typed
+    match Shape3.Rectangle(width=2.0, height=3.0):
+        case Shape3.Rectangle(width=width, height=height):
+            assert (width, height) == (2.0, 3.0)
+        case _:
+            raise AssertionError
+
+
 def test_path():
     out = echo_path(pathlib.Path("test"))
     assert out == pathlib.Path("test")
@@ -229,6 +269,13 @@ def test_comparable_struct_comparison_methods():
     assert not (a >= b)
 
 
+def test_comparable_struct_unhashable():
+    """pyclass(eq) without hash sets __hash__ to None as declared in the stub"""
+    assert ComparableStruct.__hash__ is None
+    with pytest.raises(TypeError):
+        hash(ComparableStruct(5))
+
+
 def test_hashable_struct_hash_str_methods():
     """Test that the HashableStruct has hash and str methods"""
     obj1 = HashableStruct("test")
@@ -295,3 +342,19 @@ def test_fn_with_python_stub():
 
     result = fn_with_python_stub(callback)
     assert result == callback
+
+
+def test_registered_foreign_type():
+    assert nonzero_quotients([1, 4, 9], 3) == [1, 3]
+    with pytest.raises((TypeError, ValueError)):
+        nonzero_quotients([1], 0)
+
+
+def test_exception_with_attributes():
+    assert check_range(3, 1, 5) == 3
+    with pytest.raises(RangeError) as exc_info:
+        check_range(7, 1, 5)
+    e = exc_info.value
+    assert isinstance(e, ValueError)
+    assert (e.lower, e.upper) == (1, 5)
+    assert str(e) == "Expected a value in [1, 5]"
@@ -17,21 +17,27 @@
 //!                 r#type: <String as ::pyo3_stub_gen::PyStubType>::type_output,
 //!                 doc: "",
 //!                 default: None,
+//!                 default_symbol: None,
 //!                 deprecated: None,
+//!                 cached: false,
 //!             },
 //!             MemberInfo {
 //!                 name: "ndim",
 //!                 r#type: <usize as ::pyo3_stub_gen::PyStubType>::type_output,
 //!                 doc: "",
 //!                 default: None,
+//!                 default_symbol: None,
 //!                 deprecated: None,
+//!                 cached: false,
 //!             },
 //!             MemberInfo {
 //!                 name: "description",
 //!                 r#type: <Option<String> as ::pyo3_stub_gen::PyStubType>::type_output,
 //!                 doc: "",
 //!                 default: None,
+//!                 default_symbol: None,
 //!                 deprecated: None,
+//!                 cached: false,
 //!             },
 //!         ],
 //!         setters: &[],
@@ -42,6 +48,16 @@
 //!         has_hash: false,
 //!         has_str: false,
 //!         subclass: false,
+//!         buffer: false,
+//!         picklable: false,
+//!         slots: None,
+//!         item_access: &[],
+//!         iterator: None,
+//!         type_ignored: None,
+//!         dataclass_transform: None,
+//!         file: file!(),
+//!         line: line!(),
+//!         column: column!(),
 //!     }
 //! }
 //! ```
@@ -77,6 +93,7 @@
 
 mod arg;
 mod attr;
+mod converter;
 mod member;
 mod method;
 mod parameter;
@@ -86,6 +103,7 @@ mod pyclass_complex_enum;
 mod pyclass_enum;
 mod pyfunction;
 mod pymethods;
+mod pymodule;
 mod renaming;
 mod signature;
 mod stub_type;
@@ -105,9 +123,11 @@ use signature::*;
 use stub_type::*;
 use util::*;
 
+pub use util::with_warnings;
+
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse2, ItemEnum, ItemFn, ItemImpl, ItemStruct, LitStr, Result};
+use syn::{parse2, ItemEnum, ItemFn, ItemImpl, ItemStruct, Result};
 
 pub fn pyclass(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
     let attr = parse2::<attr::PyClassAttr>(attr)?;
@@ -115,81 +135,111 @@ pub fn pyclass(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
     let inner = PyClassInfo::from_item_with_attr(item_struct.clone(), &attr)?;
     pyclass::prune_attrs(&mut item_struct);
 
+    let rust_path = rust_path_of_type(&item_struct.ident);
+    let submit = quote! {
+        ::pyo3_stub_gen::submit! {
+            #inner
+        }
+        #rust_path
+    };
+
     if attr.skip_stub_type {
         Ok(quote! {
             #item_struct
-            pyo3_stub_gen::inventory::submit! {
-                #inner
-            }
+            #submit
         })
     } else {
         let derive_stub_type = StubType::from(&inner);
         Ok(quote! {
             #item_struct
             #derive_stub_type
-            pyo3_stub_gen::inventory::submit! {
-                #inner
-            }
+            #submit
         })
     }
 }
 
 pub fn pyclass_enum(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
     let attr = parse2::<attr::PyClassAttr>(attr)?;
-    let inner = PyEnumInfo::from_item_with_attr(parse2::<ItemEnum>(item.clone())?, &attr)?;
+    let item_enum = parse2::<ItemEnum>(item.clone())?;
+    let rust_path = rust_path_of_type(&item_enum.ident);
+    let inner = PyEnumInfo::from_item_with_attr(item_enum, &attr)?;
+
+    let submit = quote! {
+        ::pyo3_stub_gen::submit! {
+            #inner
+        }
+        #rust_path
+    };
 
     if attr.skip_stub_type {
         Ok(quote! {
             #item
-            pyo3_stub_gen::inventory::submit! {
-                #inner
-            }
+            #submit
         })
     } else {
         let derive_stub_type = StubType::from(&inner);
         Ok(quote! {
             #item
             #derive_stub_type
-            pyo3_stub_gen::inventory::submit! {
-                #inner
-            }
+            #submit
         })
     }
 }
 
 pub fn pyclass_complex_enum(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
     let attr = parse2::<attr::PyClassAttr>(attr)?;
-    let inner = PyComplexEnumInfo::from_item_with_attr(parse2::<ItemEnum>(item.clone())?, &attr)?;
+    let mut item_enum = parse2::<ItemEnum>(item)?;
+    let inner = PyComplexEnumInfo::from_item_with_attr(item_enum.clone(), &attr)?;
+    pyclass_complex_enum::prune_attrs(&mut item_enum);
+
+    let rust_path = rust_path_of_type(&item_enum.ident);
+    let submit = quote! {
+        ::pyo3_stub_gen::submit! {
+            #inner
+        }
+        #rust_path
+    };
 
     if attr.skip_stub_type {
         Ok(quote! {
-            #item
-            pyo3_stub_gen::inventory::submit! {
-                #inner
-            }
+            #item_enum
+            #submit
         })
     } else {
         let derive_stub_type = StubType::from(&inner);
         Ok(quote! {
-            #item
+            #item_enum
             #derive_stub_type
-            pyo3_stub_gen::inventory::submit! {
-                #inner
-            }
+            #submit
         })
     }
 }
 
 pub fn pymethods(item: TokenStream2) -> Result<TokenStream2> {
     let mut item_impl = parse2::<ItemImpl>(item)?;
+    // `#[gen_stub(skip)]` on the impl block hides all of its members from stub files
+    if attr::parse_gen_stub_skip(&item_impl.attrs, AttributeLocation::Field)? {
+        pymethods::prune_attrs(&mut item_impl);
+        return Ok(quote! { #item_impl });
+    }
     let inner = PyMethodsInfo::try_from(item_impl.clone())?;
     pymethods::prune_attrs(&mut item_impl);
-    Ok(quote! {
-        #item_impl
+    let dict_keys = inner.dict_keys_infos();
+    let submit = quote! {
         #[automatically_derived]
-        pyo3_stub_gen::inventory::submit! {
+        ::pyo3_stub_gen::submit! {
             #inner
         }
+        #(
+            #[automatically_derived]
+            ::pyo3_stub_gen::submit! {
+                #dict_keys
+            }
+        )*
+    };
+    Ok(quote! {
+        #item_impl
+        #submit
     })
 }
 
@@ -205,6 +255,26 @@ pub fn pyfunction(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2
     Ok(quote! { #infos })
 }
 
+pub fn converter(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
+    let item_fn = parse2::<ItemFn>(item)?;
+    let attr = parse2::<ConverterAttr>(attr)?;
+    let info = converter::ConverterInfo::from_parts(item_fn, attr)?;
+    Ok(quote! { #info })
+}
+
+pub fn stub_type(item: TokenStream2) -> Result<TokenStream2> {
+    stub_type::derive_stub_type(parse2(item)?)
+}
+
+pub fn pymodule(item: TokenStream2) -> Result<TokenStream2> {
+    let item_fn = parse2::<ItemFn>(item)?;
+    let infos = pymodule::PyModuleInfos::try_from(&item_fn)?;
+    Ok(quote! {
+        #item_fn
+        #infos
+    })
+}
+
 pub fn gen_function_from_python_impl(input: TokenStream2) -> Result<TokenStream2> {
     let parsed: parse_python::GenFunctionFromPythonInput = parse2(input)?;
     let inner = parse_python::parse_gen_function_from_python_input(parsed)?;
@@ -212,8 +282,8 @@ pub fn gen_function_from_python_impl(input: TokenStream2) -> Result<TokenStream2
 }
 
 pub fn gen_methods_from_python_impl(input: TokenStream2) -> Result<TokenStream2> {
-    let stub_str: LitStr = parse2(input)?;
-    let inner = parse_python::parse_python_methods_stub(&stub_str)?;
+    let parsed: parse_python::GenMethodsFromPythonInput = parse2(input)?;
+    let inner = parse_python::parse_gen_methods_from_python_input(parsed)?;
     Ok(quote! { #inner })
 }
 
@@ -223,6 +293,12 @@ pub fn gen_type_alias_from_python_impl(input: TokenStream2) -> Result<TokenStrea
     Ok(quote! { #inner })
 }
 
+pub fn gen_typing_helpers_from_python_impl(input: TokenStream2) -> Result<TokenStream2> {
+    let parsed: parse_python::GenTypingHelpersFromPythonInput = parse2(input)?;
+    let inner = parse_python::parse_python_typing_helpers(&parsed)?;
+    Ok(quote! { #inner })
+}
+
 pub fn prune_gen_stub(item: TokenStream2) -> Result<TokenStream2> {
     fn prune_attrs<T: syn::parse::Parse + quote::ToTokens>(
         item: &TokenStream2,
@@ -235,6 +311,7 @@ pub fn prune_gen_stub(item: TokenStream2) -> Result<TokenStream2> {
     }
     prune_attrs::<ItemStruct>(&item, pyclass::prune_attrs)
         .or_else(|_| prune_attrs::<ItemImpl>(&item, pymethods::prune_attrs))
+        .or_else(|_| prune_attrs::<ItemEnum>(&item, pyclass_complex_enum::prune_attrs))
         .or_else(|_| prune_attrs::<ItemFn>(&item, pyfunction::prune_attrs))
 }
 
@@ -327,6 +404,205 @@ mod tests {
         insta::assert_snapshot!(formatted);
     }
 
+    #[test]
+    fn test_function_in_multiple_modules() {
+        // A function added to several modules is submitted once for each module
+        let attr = quote! { module = ["pkg", "pkg.sub"] };
+
+        let item = quote! {
+            #[pyfunction]
+            pub fn shared(x: i32) -> i32 {
+                x
+            }
+        };
+
+        let formatted = format_tokens(pyfunction(attr, item).unwrap());
+        assert_eq!(formatted.matches("PyFunctionInfo {").count(), 2);
+        // The Rust path for intra-doc links is submitted once
+        assert_eq!(formatted.matches("RustPathInfo {").count(), 1);
+        let compact: String = formatted.split_whitespace().collect();
+        assert!(compact.contains(r#"module:Some("pkg"),"#));
+        assert!(compact.contains(r#"module:Some("pkg.sub"),"#));
+
+        let attr = quote! { module = "pkg", module = "pkg" };
+        let item = quote! {
+            #[pyfunction]
+            pub fn shared() {}
+        };
+        assert!(pyfunction(attr, item).is_err());
+    }
+
+    #[test]
+    fn test_from_py_with_converter() {
+        let attr = quote! { input = "int | str", imports = ("decimal",) };
+        let item = quote! {
+            fn parse_amount(obj: &Bound<'_, PyAny>) -> PyResult<i64> {
+                todo!()
+            }
+        };
+        let formatted = format_tokens(converter(attr.clone(), item).unwrap());
+        let compact: String = formatted.split_whitespace().collect();
+        assert!(compact
+            .contains("converter:||::pyo3_stub_gen::type_info::converter_id(&parse_amount),"));
+        assert!(compact.contains(r#"name:"int|str".to_string(),"#));
+        assert!(compact.contains(r#"::std::collections::HashSet::from(["decimal".into()])"#));
+
+        // Methods and associated functions cannot register the converter next to themselves
+        let item = quote! {
+            fn parse_amount(&self, obj: &Bound<'_, PyAny>) -> PyResult<i64> {
+                todo!()
+            }
+        };
+        assert!(converter(attr.clone(), item).is_err());
+        let item = quote! {
+            fn parse_amount(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+                todo!()
+            }
+        };
+        assert!(converter(attr, item).is_err());
+
+        // Arguments converted by `from_py_with` look up the input type of the converter
+        let item = quote! {
+            #[pyfunction]
+            fn total(#[pyo3(from_py_with = parse_amount)] amount: i64, count: i64) -> i64 {
+                amount * count
+            }
+        };
+        let formatted = format_tokens(pyfunction(TokenStream2::new(), item).unwrap());
+        let compact: String = formatted.split_whitespace().collect();
+        assert_eq!(compact.matches("converter_input(").count(), 1);
+        assert!(compact.contains("::pyo3_stub_gen::type_info::converter_id(&parse_amount),"));
+
+        // The string form of older PyO3
+        let item = quote! {
+            #[pyfunction]
+            fn total(#[pyo3(from_py_with = "parse_amount")] amount: i64) -> i64 {
+                amount
+            }
+        };
+        let formatted = format_tokens(pyfunction(TokenStream2::new(), item).unwrap());
+        let compact: String = formatted.split_whitespace().collect();
+        assert!(compact.contains("::pyo3_stub_gen::type_info::converter_id(&parse_amount),"));
+    }
+
+    #[test]
+    fn test_function_raises() {
+        let item = quote! {
+            #[pyfunction]
+            #[gen_stub(raises(crate::MyError, ValueError, pyo3::exceptions::PyKeyError))]
+            pub fn risky() {}
+        };
+
+        let formatted: String = format_tokens(pyfunction(quote! {}, item).unwrap())
+            .split_whitespace()
+            .collect();
+        assert!(formatted.contains("id:::std::any::TypeId::of::<crate::MyError>"));
+        assert!(formatted.contains(r#"name:"crate::MyError""#));
+        assert!(formatted.contains(r#"RaisesInfo::Builtin("ValueError")"#));
+        assert!(formatted.contains(r#"RaisesInfo::Builtin("KeyError")"#));
+        assert!(!formatted.contains("#[gen_stub("));
+    }
+
+    #[test]
+    fn test_function_named_tuple() {
+        let item = quote! {
+            #[pyfunction]
+            #[gen_stub(named_tuple(name = "Stats", fields("min", "count")))]
+            pub fn stats(values: Vec<f64>) -> PyResult<(f64, usize)> {}
+        };
+
+        let formatted: String = format_tokens(pyfunction(quote! { module = "pkg" }, item).unwrap())
+            .split_whitespace()
+            .collect();
+        assert!(formatted.contains(r#"TypeInfo::locally_defined("Stats","pkg".into())"#));
+        assert!(formatted.contains(r#"NamedTupleInfo{module:Some("pkg"),name:"Stats""#));
+        assert!(formatted.contains(
+            r#"name:"count",r#type:||::pyo3_stub_gen::StubTypeOf::<usize>::new().type_output()"#
+        ));
+        // The tuple is returned as an instance of the class at runtime
+        assert!(formatted.contains(
+            "pubfnstats(values:Vec<f64>)->PyResult<::pyo3_stub_gen::runtime::NamedTuple<(f64,usize)>>"
+        ));
+        assert!(formatted.contains(
+            r#"__pyo3_stub_gen_inner(values).map(|values|::pyo3_stub_gen::runtime::NamedTuple::new(Some("pkg"),"Stats",&["min","count"],values))"#
+        ));
+
+        let item = quote! {
+            #[pyfunction]
+            #[gen_stub(named_tuple(name = "Stats", fields("min", "count")))]
+            pub async fn stats(values: Vec<f64>) -> (f64, usize) {}
+        };
+        assert!(pyfunction(quote! {}, item).is_err());
+
+        let item = quote! {
+            #[pyfunction]
+            #[gen_stub(named_tuple(name = "Stats", fields("min", "max", "mean")))]
+            pub fn stats(values: Vec<f64>) -> (f64, f64) {}
+        };
+        assert!(pyfunction(quote! {}, item).is_err());
+
+        let item = quote! {
+            #[pyfunction]
+            #[gen_stub(named_tuple(name = "Stats", fields("min")))]
+            pub fn stats(values: Vec<f64>) -> f64 {}
+        };
+        assert!(pyfunction(quote! {}, item).is_err());
+    }
+
+    #[test]
+    fn test_function_positional_only() {
+        let item = quote! {
+            #[pyfunction]
+            #[pyo3(signature = (x, y = 1, /, z = 2, *, w))]
+            pub fn positional(x: i32, y: i32, z: i32, w: i32) {}
+        };
+
+        let formatted: String = format_tokens(pyfunction(quote! {}, item).unwrap())
+            .split_whitespace()
+            .collect();
+        let kinds: Vec<&str> = formatted
+            .match_indices("ParameterKind::")
+            .map(|(i, _)| {
+                let kind = &formatted[i + "ParameterKind::".len()..];
+                &kind[..kind.find(',').unwrap()]
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "PositionalOnly",
+                "PositionalOnly",
+                "PositionalOrKeyword",
+                "KeywordOnly"
+            ]
+        );
+
+        let item = quote! {
+            #[pyfunction]
+            #[pyo3(signature = (*, x, /))]
+            pub fn invalid(x: i32) {}
+        };
+        assert!(pyfunction(quote! {}, item).is_err());
+    }
+
+    #[test]
+    fn test_skip_pymethods() {
+        let item = quote! {
+            #[gen_stub(skip)]
+            #[pymethods]
+            impl Internal {
+                fn reset(&mut self) {}
+            }
+        };
+
+        let formatted: String = format_tokens(pymethods(item).unwrap())
+            .split_whitespace()
+            .collect();
+        assert!(formatted.contains("fnreset(&mutself){}"));
+        assert!(!formatted.contains("submit!"));
+        assert!(!formatted.contains("#[gen_stub("));
+    }
+
     #[test]
     fn test_function_with_enum_default_value() {
         // Test a function with enum default value in signature
@@ -368,4 +644,42 @@ mod tests {
 
         insta::assert_snapshot!(formatted);
     }
+
+    #[test]
+    fn test_function_with_computed_default_value() {
+        let item = quote! {
+            #[pyfunction(signature = (n = usize::MAX, x = A::default().x, c = Config::default()))]
+            pub fn computed_defaults(
+                n: usize,
+                #[gen_stub(override_type(type_repr = "float"))] x: f64,
+                #[gen_stub(override_type(type_repr = "dict[str, int]"))] c: Config,
+            ) {}
+        };
+
+        let formatted: String = format_tokens(pyfunction(quote! {}, item).unwrap())
+            .split_whitespace()
+            .collect();
+        assert!(formatted.contains("letv:usize=usize::MAX;::pyo3_stub_gen::util::fmt_py_obj(v)"));
+        assert!(formatted.contains("letv:f64=A::default().x;::pyo3_stub_gen::util::fmt_py_obj(v)"));
+        // `Config` may not implement `IntoPyObject`, so the expression is written as is
+        assert!(!formatted.contains("letv:Config"));
+        assert!(formatted.contains(r#""Config::default()".to_string()"#));
+    }
+
+    #[test]
+    fn test_method_default_value_with_self() {
+        let item = quote! {
+            #[pymethods]
+            impl A {
+                #[pyo3(signature = (n = Self::LIMIT))]
+                fn limited(&self, n: usize) {}
+            }
+        };
+
+        let formatted: String = format_tokens(pymethods(item).unwrap())
+            .split_whitespace()
+            .collect();
+        // `Self` is not available in the function evaluating the default value
+        assert!(formatted.contains("letv:usize=<A>::LIMIT;::pyo3_stub_gen::util::fmt_py_obj(v)"));
+    }
 }
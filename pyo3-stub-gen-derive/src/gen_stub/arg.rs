@@ -4,7 +4,15 @@ use syn::{
     TypeReference,
 };
 
-use crate::gen_stub::{attr::parse_gen_stub_override_type, util::TypeOrOverride};
+use indexmap::IndexSet;
+
+use crate::gen_stub::{
+    attr::{
+        parse_gen_stub_dict_keys, parse_gen_stub_override_type, parse_pyo3_from_py_with,
+        DictKeysAttr,
+    },
+    util::TypeOrOverride,
+};
 
 pub fn parse_args(iter: impl IntoIterator<Item = FnArg>) -> Result<Vec<ArgInfo>> {
     let mut args = Vec::new();
@@ -75,6 +83,10 @@ pub fn parse_args(iter: impl IntoIterator<Item = FnArg>) -> Result<Vec<ArgInfo>>
 pub struct ArgInfo {
     pub(crate) name: String,
     pub(crate) r#type: TypeOrOverride,
+    /// Known keys of a dict argument given by `#[gen_stub(dict_keys(...))]`
+    pub(crate) dict_keys: Option<DictKeysAttr>,
+    /// Converter function of `#[pyo3(from_py_with = ...)]`, whose input type is used instead of the Rust type
+    pub(crate) from_py_with: Option<syn::Path>,
 }
 
 impl TryFrom<FnArg> for ArgInfo {
@@ -85,7 +97,37 @@ impl TryFrom<FnArg> for ArgInfo {
             if let syn::Pat::Ident(mut ident) = *pat {
                 ident.mutability = None;
                 let name = ident.to_token_stream().to_string();
-                if let Some(attr) = parse_gen_stub_override_type(&attrs)? {
+                let dict_keys = parse_gen_stub_dict_keys(&attrs)?;
+                let from_py_with = parse_pyo3_from_py_with(&attrs);
+                let override_type = parse_gen_stub_override_type(&attrs)?;
+                let typed_dict = dict_keys
+                    .as_ref()
+                    .and_then(|dict_keys| dict_keys.typed_dict.as_ref());
+                if let (Some(typed_dict), Some(_)) = (typed_dict, &override_type) {
+                    return Err(syn::Error::new(
+                        typed_dict.span(),
+                        "`typed_dict` and `override_type(...)` cannot be used together",
+                    ));
+                }
+                if let Some(typed_dict) = typed_dict {
+                    // The `TypedDict` is written to the same module, so its bare name is resolvable
+                    let mut type_repr = typed_dict.value();
+                    if is_option(&ty) {
+                        type_repr.push_str(" | None");
+                    }
+                    return Ok(Self {
+                        name,
+                        r#type: TypeOrOverride::OverrideType {
+                            r#type: (*ty).clone(),
+                            type_repr,
+                            imports: IndexSet::new(),
+                            rust_type_markers: vec![],
+                        },
+                        dict_keys,
+                        from_py_with,
+                    });
+                }
+                if let Some(attr) = override_type {
                     return Ok(Self {
                         name,
                         r#type: TypeOrOverride::OverrideType {
@@ -94,6 +136,8 @@ impl TryFrom<FnArg> for ArgInfo {
                             imports: attr.imports,
                             rust_type_markers: vec![],
                         },
+                        dict_keys,
+                        from_py_with,
                     });
                 }
                 return Ok(Self {
@@ -101,6 +145,8 @@ impl TryFrom<FnArg> for ArgInfo {
                     r#type: TypeOrOverride::RustType {
                         r#type: (*ty).clone(),
                     },
+                    dict_keys,
+                    from_py_with,
                 });
             }
 
@@ -108,9 +154,17 @@ impl TryFrom<FnArg> for ArgInfo {
                 return Ok(Self {
                     name: "_".to_owned(),
                     r#type: TypeOrOverride::RustType { r#type: *ty },
+                    dict_keys: None,
+                    from_py_with: parse_pyo3_from_py_with(&attrs),
                 });
             }
         }
         Err(syn::Error::new(span, "Expected typed argument"))
     }
 }
+
+/// `Option<T>`, which accepts `None` as well
+fn is_option(ty: &Type) -> bool {
+    matches!(ty, Type::Path(TypePath { path, .. })
+        if path.segments.last().is_some_and(|last| last.ident == "Option"))
+}
@@ -1,13 +1,18 @@
 use indexmap::IndexSet;
 
-use super::{RenamingRule, Signature};
+use super::{
+    util::{quote_option, warn},
+    RenamingRule, Signature,
+};
 use proc_macro2::{Delimiter, TokenStream as TokenStream2, TokenTree};
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
+    ext::IdentExt,
     parenthesized,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    Attribute, Expr, ExprLit, Ident, Lit, LitStr, Meta, MetaList, Result, Token, Type,
+    spanned::Spanned,
+    Attribute, Expr, ExprLit, Ident, Lit, LitBool, LitStr, Meta, MetaList, Result, Token, Type,
 };
 
 /// Represents the target of type ignore comments during parsing
@@ -19,6 +24,24 @@ pub enum IgnoreTarget {
     SpecifiedLits(Vec<LitStr>),
 }
 
+impl ToTokens for IgnoreTarget {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        match self {
+            IgnoreTarget::All => {
+                tokens.append_all(quote! { ::pyo3_stub_gen::type_info::IgnoreTarget::All })
+            }
+            IgnoreTarget::SpecifiedLits(rules) => {
+                let rule_strs: Vec<String> = rules.iter().map(|lit| lit.value()).collect();
+                tokens.append_all(quote! {
+                    ::pyo3_stub_gen::type_info::IgnoreTarget::Specified(
+                        &[#(#rule_strs),*] as &[&str]
+                    )
+                })
+            }
+        }
+    }
+}
+
 pub fn extract_documents(attrs: &[Attribute]) -> Vec<String> {
     let mut docs = Vec::new();
     for attr in attrs {
@@ -142,6 +165,14 @@ pub enum Attr {
 
     // Standalone #[gen_stub(...)] attribute
     GenStubModule(String),
+    GenStubBuffer,
+    GenStubPicklable,
+    GenStubSlots,
+    GenStubNoMatchArgs,
+    GenStubItemAccess(ItemAccessAttr),
+    GenStubIterator(IteratorAttr),
+    GenStubDataclassTransform(DataclassTransformAttr),
+    GenStubTypeIgnore(IgnoreTarget),
 
     // Attributes appears in components within `#[pymethods]`
     // <https://docs.rs/pyo3/latest/pyo3/attr.pymethods.html>
@@ -155,17 +186,191 @@ pub enum Attr {
 
 pub fn parse_pyo3_attrs(attrs: &[Attribute]) -> Result<Vec<Attr>> {
     let mut out = Vec::new();
-    for attr in attrs {
+    for attr in &expand_cfg_attrs(attrs)? {
         let mut new = parse_pyo3_attr(attr)?;
         out.append(&mut new);
-        // Also parse standalone #[gen_stub(module = "...")] attributes
-        if let Some(gen_stub_attr) = parse_gen_stub_module_attr(attr)? {
-            out.push(gen_stub_attr);
-        }
+        // Also parse standalone #[gen_stub(module = "...")] and #[gen_stub(buffer)] attributes
+        out.append(&mut parse_gen_stub_standalone_attrs(attr)?);
+    }
+    Ok(out)
+}
+
+/// Replace `#[cfg_attr(predicate, attrs...)]` by `attrs` if `predicate` holds, and drop it if not.
+///
+/// Since a proc-macro cannot see the configuration of the crate being compiled,
+/// a predicate is evaluated only by `all()`, `any()`, `not()` and the predicates assumed
+/// by `#[gen_stub(assume_cfg("..."))]`, true or false if wrapped in `not()`. Other predicates are not evaluated.
+/// A `cfg_attr` which cannot be evaluated is kept as is, with a warning if it wraps a PyO3 attribute.
+pub fn expand_cfg_attrs(attrs: &[Attribute]) -> Result<Vec<Attribute>> {
+    let assumed = parse_gen_stub_assume_cfg(attrs)?;
+    let mut out = Vec::new();
+    for attr in attrs {
+        expand_cfg_attr(attr, &assumed, &mut out);
     }
     Ok(out)
 }
 
+fn expand_cfg_attr(attr: &Attribute, assumed: &[(String, bool)], out: &mut Vec<Attribute>) {
+    if !attr.path().is_ident("cfg_attr") {
+        out.push(attr.clone());
+        return;
+    }
+    // Leave malformed `cfg_attr` to rustc
+    let Ok(args) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+        out.push(attr.clone());
+        return;
+    };
+    let mut args = args.into_iter();
+    let Some(predicate) = args.next() else {
+        return;
+    };
+    let inner: Vec<Attribute> = args
+        .map(|meta| Attribute {
+            pound_token: attr.pound_token,
+            style: attr.style,
+            bracket_token: attr.bracket_token,
+            meta,
+        })
+        .collect();
+    match eval_cfg(&predicate, assumed) {
+        Some(true) => {
+            for attr in &inner {
+                expand_cfg_attr(attr, assumed, out);
+            }
+        }
+        Some(false) => {}
+        None => {
+            if inner.iter().any(is_pyo3_attr) {
+                warn(
+                    attr.span(),
+                    format!(
+                        "`{}` is ignored in the stub since `{}` cannot be evaluated by pyo3-stub-gen. \
+                         Add `#[gen_stub(assume_cfg(\"...\"))]` to choose the branch.",
+                        attr.meta.to_token_stream(),
+                        predicate.to_token_stream(),
+                    ),
+                );
+            }
+            out.push(attr.clone());
+        }
+    }
+}
+
+/// Predicates assumed by `#[gen_stub(assume_cfg("feature = \"abi3\"", ...))]` normalized by [cfg_key],
+/// and whether they are assumed true, i.e. not wrapped in `not()`
+fn parse_gen_stub_assume_cfg(attrs: &[Attribute]) -> Result<Vec<(String, bool)>> {
+    let mut assumed = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("gen_stub") {
+            continue;
+        }
+        let Meta::List(MetaList { tokens, .. }) = &attr.meta else {
+            continue;
+        };
+        use TokenTree::*;
+        // See note in `parse_pyo3_attr` about invisible groups.
+        let tokens: Vec<TokenTree> = flatten_invisible_groups(tokens.clone()).collect();
+        for tt in tokens.split(|tt| matches!(tt, Punct(p) if p.as_char() == ',')) {
+            if let [Ident(ident), Group(group)] = tt {
+                if ident == "assume_cfg" && group.delimiter() == Delimiter::Parenthesis {
+                    let predicates = syn::parse::Parser::parse2(
+                        Punctuated::<LitStr, Token![,]>::parse_terminated,
+                        group.stream(),
+                    )?;
+                    for predicate in predicates {
+                        let meta: Meta = predicate.parse().map_err(|_| {
+                            syn::Error::new(
+                                predicate.span(),
+                                "`assume_cfg` expects a `cfg` predicate, e.g. \"feature = \\\"abi3\\\"\"",
+                            )
+                        })?;
+                        assumed.push(match &meta {
+                            Meta::List(list) if list.path.is_ident("not") => {
+                                (cfg_key(&list.parse_args()?), false)
+                            }
+                            _ => (cfg_key(&meta), true),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Ok(assumed)
+}
+
+/// Whitespace-insensitive representation of a `cfg` predicate
+fn cfg_key(meta: &Meta) -> String {
+    meta.to_token_stream().to_string()
+}
+
+/// Evaluate a `cfg` predicate, or `None` if it depends on the configuration
+fn eval_cfg(predicate: &Meta, assumed: &[(String, bool)]) -> Option<bool> {
+    let key = cfg_key(predicate);
+    if let Some((_, value)) = assumed.iter().find(|(assumed, _)| *assumed == key) {
+        return Some(*value);
+    }
+    let Meta::List(list) = predicate else {
+        return None;
+    };
+    let Ok(args) = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+        return None;
+    };
+    let values = args.iter().map(|arg| eval_cfg(arg, assumed));
+    if list.path.is_ident("all") {
+        let mut result = Some(true);
+        for value in values {
+            match value {
+                Some(false) => return Some(false),
+                None => result = None,
+                Some(true) => {}
+            }
+        }
+        result
+    } else if list.path.is_ident("any") {
+        let mut result = Some(false);
+        for value in values {
+            match value {
+                Some(true) => return Some(true),
+                None => result = None,
+                Some(false) => {}
+            }
+        }
+        result
+    } else if list.path.is_ident("not") && args.len() == 1 {
+        eval_cfg(&args[0], assumed).map(|value| !value)
+    } else {
+        None
+    }
+}
+
+fn is_pyo3_attr(attr: &Attribute) -> bool {
+    let path = attr.path();
+    path.segments.first().is_some_and(|seg| seg.ident == "pyo3")
+        || [
+            "pyclass",
+            "pymethods",
+            "pyfunction",
+            "pymodule",
+            "getter",
+            "setter",
+            "new",
+            "staticmethod",
+            "classmethod",
+            "classattr",
+        ]
+        .iter()
+        .any(|name| path.is_ident(name))
+}
+
+/// Property name given by `#[getter(name)]` or `#[setter(name)]`, also accepting a raw identifier
+/// like `r#type` or a string literal
+fn parse_accessor_name(attr: &Attribute) -> Option<String> {
+    if let Ok(ident) = attr.parse_args_with(Ident::parse_any) {
+        return Some(ident.unraw().to_string());
+    }
+    attr.parse_args::<LitStr>().ok().map(|lit| lit.value())
+}
+
 pub fn parse_pyo3_attr(attr: &Attribute) -> Result<Vec<Attr>> {
     let mut pyo3_attrs = Vec::new();
     let path = attr.path();
@@ -175,11 +380,15 @@ pub fn parse_pyo3_attr(attr: &Attribute) -> Result<Vec<Attr>> {
             .first()
             .is_some_and(|seg| seg.ident.eq("pyo3"))
         && path.segments.last().is_some_and(|seg| {
-            seg.ident.eq("pyclass") || seg.ident.eq("pymethods") || seg.ident.eq("pyfunction")
+            seg.ident.eq("pyclass")
+                || seg.ident.eq("pymethods")
+                || seg.ident.eq("pyfunction")
+                || seg.ident.eq("pymodule")
         });
     if path.is_ident("pyclass")
         || path.is_ident("pymethods")
         || path.is_ident("pyfunction")
+        || path.is_ident("pymodule")
         || path.is_ident("pyo3")
         || is_full_path_pyo3_attr
     {
@@ -277,17 +486,9 @@ pub fn parse_pyo3_attr(attr: &Attribute) -> Result<Vec<Attr>> {
     } else if path.is_ident("classattr") {
         pyo3_attrs.push(Attr::ClassAttr);
     } else if path.is_ident("getter") {
-        if let Ok(inner) = attr.parse_args::<Ident>() {
-            pyo3_attrs.push(Attr::Getter(Some(inner.to_string())));
-        } else {
-            pyo3_attrs.push(Attr::Getter(None));
-        }
+        pyo3_attrs.push(Attr::Getter(parse_accessor_name(attr)));
     } else if path.is_ident("setter") {
-        if let Ok(inner) = attr.parse_args::<Ident>() {
-            pyo3_attrs.push(Attr::Setter(Some(inner.to_string())));
-        } else {
-            pyo3_attrs.push(Attr::Setter(None));
-        }
+        pyo3_attrs.push(Attr::Setter(parse_accessor_name(attr)));
     }
 
     Ok(pyo3_attrs)
@@ -309,11 +510,23 @@ fn flatten_invisible_groups(tokens: TokenStream2) -> impl Iterator<Item = TokenT
         })
 }
 
-/// Parse standalone `#[gen_stub(module = "...")]` attribute for module override
-pub fn parse_gen_stub_module_attr(attr: &Attribute) -> Result<Option<Attr>> {
+/// Parse standalone `#[gen_stub(...)]` attributes on a class
+///
+/// - `#[gen_stub(module = "...")]` for module override
+/// - `#[gen_stub(buffer)]` for classes implementing the buffer protocol
+/// - `#[gen_stub(picklable)]` for classes implementing `__getstate__`/`__setstate__`/`__reduce__`
+/// - `#[gen_stub(slots)]` for classes declaring `__slots__` of the fields
+/// - `#[gen_stub(no_match_args)]` for complex enums or their variants without `__match_args__`
+/// - `#[gen_stub(getitem(...), setitem(...), delitem(...))]` for typed item access
+/// - `#[gen_stub(iterator(item = "..."))]` for classes implementing `__iter__`/`__next__`
+/// - `#[gen_stub(dataclass_transform(eq_default = ..., kw_only_default = ...))]` for base classes
+///   whose subclasses are constructed field-wise
+/// - `#[gen_stub(type_ignore)]` or `#[gen_stub(type_ignore = [...])]` for `# type: ignore` on the class line
+pub fn parse_gen_stub_standalone_attrs(attr: &Attribute) -> Result<Vec<Attr>> {
+    let mut out = Vec::new();
     let path = attr.path();
     if path.is_ident("gen_stub") {
-        // Parse the inner tokens to find module = "..."
+        // Parse the inner tokens to find module = "..." or buffer
         if let Meta::List(MetaList { tokens, .. }) = &attr.meta {
             use TokenTree::*;
             // See note in `parse_pyo3_attr` about invisible groups.
@@ -329,28 +542,124 @@ pub fn parse_gen_stub_module_attr(attr: &Attribute) -> Result<Option<Attr>> {
             }) {
                 match tt {
                     [Ident(ident), Punct(_), Literal(lit)] if ident == "module" => {
-                        return Ok(Some(Attr::GenStubModule(
+                        out.push(Attr::GenStubModule(
                             lit.to_string().trim_matches('"').to_string(),
+                        ));
+                    }
+                    [Ident(ident)] if ident == "buffer" => {
+                        out.push(Attr::GenStubBuffer);
+                    }
+                    [Ident(ident)] if ident == "picklable" => {
+                        out.push(Attr::GenStubPicklable);
+                    }
+                    [Ident(ident)] if ident == "slots" => {
+                        out.push(Attr::GenStubSlots);
+                    }
+                    [Ident(ident)] if ident == "no_match_args" => {
+                        out.push(Attr::GenStubNoMatchArgs);
+                    }
+                    [Ident(ident)] if ident == "type_ignore" => {
+                        out.push(Attr::GenStubTypeIgnore(IgnoreTarget::All));
+                    }
+                    [Ident(ident), Punct(eq), Group(group)]
+                        if ident == "type_ignore"
+                            && eq.as_char() == '='
+                            && group.delimiter() == Delimiter::Bracket =>
+                    {
+                        let rules = syn::parse::Parser::parse2(
+                            Punctuated::<LitStr, Token![,]>::parse_terminated,
+                            group.stream(),
+                        )?;
+                        if rules.is_empty() {
+                            return Err(syn::Error::new(
+                                ident.span(),
+                                "type_ignore with empty array is not allowed. Use type_ignore without equals for catch-all, or specify rules in the array.",
+                            ));
+                        }
+                        out.push(Attr::GenStubTypeIgnore(IgnoreTarget::SpecifiedLits(
+                            rules.into_iter().collect(),
                         )));
                     }
+                    [Ident(ident), Group(group)]
+                        if group.delimiter() == Delimiter::Parenthesis
+                            && (ident == "getitem" || ident == "setitem" || ident == "delitem") =>
+                    {
+                        let kind = match ident.to_string().as_str() {
+                            "getitem" => ItemAccessKind::Get,
+                            "setitem" => ItemAccessKind::Set,
+                            _ => ItemAccessKind::Del,
+                        };
+                        let item_access = syn::parse::Parser::parse2(
+                            |input: ParseStream| ItemAccessAttr::parse_with_kind(input, kind),
+                            group.stream(),
+                        )?;
+                        out.push(Attr::GenStubItemAccess(item_access));
+                    }
+                    [Ident(ident), Group(group)]
+                        if ident == "iterator" && group.delimiter() == Delimiter::Parenthesis =>
+                    {
+                        let iterator = syn::parse2::<IteratorAttr>(group.stream())?;
+                        out.push(Attr::GenStubIterator(iterator));
+                    }
+                    [Ident(ident)] if ident == "dataclass_transform" => {
+                        out.push(Attr::GenStubDataclassTransform(
+                            DataclassTransformAttr::default(),
+                        ));
+                    }
+                    [Ident(ident), Group(group)]
+                        if ident == "dataclass_transform"
+                            && group.delimiter() == Delimiter::Parenthesis =>
+                    {
+                        let dataclass_transform =
+                            syn::parse2::<DataclassTransformAttr>(group.stream())?;
+                        out.push(Attr::GenStubDataclassTransform(dataclass_transform));
+                    }
                     _ => {}
                 }
             }
         }
     }
-    Ok(None)
+    Ok(out)
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum StubGenAttr {
     /// Default value for getter
     Default(Expr),
-    /// Skip a function in #[pymethods]
+    /// Python expression shown instead of the evaluated default value
+    DefaultSymbol(LitStr),
+    /// Skip a function in #[pymethods], a field, or an entire #[pymethods] block
     Skip,
+    /// Skip only the getter of a field with `#[pyo3(get)]`
+    SkipGetter,
+    /// Skip only the setter of a field with `#[pyo3(set)]`
+    SkipSetter,
+    /// Show a `#[getter]` as `functools.cached_property`
+    CachedProperty,
     /// Override the python type for a function argument or return type
     OverrideType(OverrideTypeAttribute),
     /// Type checker rules to ignore for this function/method
     TypeIgnore(IgnoreTarget),
+    /// Element type of `*args` for a function/method
+    VarArgsType(OverrideTypeAttribute),
+    /// Value type of `**kwargs` for a function/method
+    KwargsValueType(OverrideTypeAttribute),
+    /// `TypedDict` of `**kwargs` for a function/method, shown as `**kwargs: Unpack[...]`
+    KwargsUnpack(OverrideTypeAttribute),
+    /// Exceptions raised by a function
+    Raises(Vec<Type>),
+    /// `sys.platform` the function is available on, e.g. `"linux"` or `"!win32"`
+    Platform(LitStr),
+    /// Python versions the function is available on, e.g. `">=3.12"`
+    PyVersion(LitStr),
+    /// The function releases the GIL while running, e.g. by `py.detach`
+    ReleasesGil,
+    /// `typing.NamedTuple` shown as the return type of a function returning a tuple
+    NamedTuple(NamedTupleAttr),
+    /// Known keys of a dict argument and their value types
+    DictKeys(Vec<(String, LitStr)>),
+    /// `TypedDict` of the keys given by `dict_keys(...)` used as the type of the argument
+    TypedDict(LitStr),
 }
 
 pub fn prune_attrs(attrs: &mut Vec<Attribute>) {
@@ -366,6 +675,48 @@ pub fn parse_gen_stub_override_type(attrs: &[Attribute]) -> Result<Option<Overri
     Ok(None)
 }
 
+/// Parse the converter function of `#[pyo3(from_py_with = converter)]` on an argument,
+/// also written as a string `from_py_with = "converter"` in older PyO3
+pub fn parse_pyo3_from_py_with(attrs: &[Attribute]) -> Option<syn::Path> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pyo3"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::MetaNameValue, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .find(|arg| arg.path.is_ident("from_py_with"))
+        .and_then(|arg| match arg.value {
+            Expr::Path(expr) => Some(expr.path),
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => lit.parse().ok(),
+            _ => None,
+        })
+}
+
+/// Parse `#[gen_stub(dict_keys(host = "str", port = "int"), typed_dict = "...")]` on an argument
+pub fn parse_gen_stub_dict_keys(attrs: &[Attribute]) -> Result<Option<DictKeysAttr>> {
+    let mut keys = None;
+    let mut typed_dict = None;
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None)? {
+        match attr {
+            StubGenAttr::DictKeys(attr) => keys = Some(attr),
+            StubGenAttr::TypedDict(attr) => typed_dict = Some(attr),
+            _ => {}
+        }
+    }
+    match (keys, typed_dict) {
+        (Some(keys), typed_dict) => Ok(Some(DictKeysAttr { keys, typed_dict })),
+        (None, Some(typed_dict)) => Err(syn::Error::new(
+            typed_dict.span(),
+            "`typed_dict` requires the keys given by `dict_keys(...)`",
+        )),
+        (None, None) => Ok(None),
+    }
+}
+
 pub fn parse_gen_stub_override_return_type(
     attrs: &[Attribute],
 ) -> Result<Option<OverrideTypeAttribute>> {
@@ -378,33 +729,97 @@ pub fn parse_gen_stub_override_return_type(
 }
 
 pub fn parse_gen_stub_default(attrs: &[Attribute]) -> Result<Option<Expr>> {
-    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, None)? {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Field, None)? {
         if let StubGenAttr::Default(default) = attr {
             return Ok(Some(default));
         }
     }
     Ok(None)
 }
-pub fn parse_gen_stub_skip(attrs: &[Attribute]) -> Result<bool> {
-    let skip = parse_gen_stub_attrs(
-        attrs,
-        AttributeLocation::Field,
-        Some(&["override_return_type", "default"]),
-    )?
-    .iter()
-    .any(|attr| matches!(attr, StubGenAttr::Skip));
+pub fn parse_gen_stub_default_symbol(attrs: &[Attribute]) -> Result<Option<String>> {
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Field, None)? {
+        if let StubGenAttr::DefaultSymbol(symbol) = attr {
+            return Ok(Some(symbol.value()));
+        }
+    }
+    Ok(None)
+}
+pub(crate) fn parse_gen_stub_cached_property(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<bool> {
+    Ok(parse_gen_stub_attrs(attrs, location, None)?
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::CachedProperty)))
+}
+pub(crate) fn parse_gen_stub_skip(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<bool> {
+    let skip = parse_gen_stub_attrs(attrs, location, Some(&["override_return_type", "default"]))?
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::Skip));
     Ok(skip)
 }
 
-pub fn parse_gen_stub_type_ignore(attrs: &[Attribute]) -> Result<Option<IgnoreTarget>> {
-    // Try Function location first (for regular functions)
-    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Function, None)? {
-        if let StubGenAttr::TypeIgnore(target) = attr {
-            return Ok(Some(target));
+/// Parse `#[gen_stub(skip)]`, `#[gen_stub(skip_getter)]` and `#[gen_stub(skip_setter)]` on a field
+///
+/// Returns whether the getter and the setter are skipped respectively.
+pub fn parse_gen_stub_skip_accessors(attrs: &[Attribute]) -> Result<(bool, bool)> {
+    let mut skip_getter = false;
+    let mut skip_setter = false;
+    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Field, None)? {
+        match attr {
+            StubGenAttr::Skip => {
+                skip_getter = true;
+                skip_setter = true;
+            }
+            StubGenAttr::SkipGetter => skip_getter = true,
+            StubGenAttr::SkipSetter => skip_setter = true,
+            _ => {}
         }
     }
-    // Try Field location (for methods in #[pymethods] blocks)
-    for attr in parse_gen_stub_attrs(attrs, AttributeLocation::Field, None)? {
+    Ok((skip_getter, skip_setter))
+}
+
+/// Parse `#[gen_stub(skip)]` on a variant of a complex enum
+///
+/// Returns whether the variant is hidden from the stub.
+/// `name = "..."` is rejected, since the name of the nested class has to be set by `#[pyo3(name = "...")]` to match the runtime.
+pub fn parse_gen_stub_variant_attrs(attrs: &[Attribute]) -> Result<bool> {
+    let mut skip = false;
+    for attr in attrs {
+        if !attr.path().is_ident("gen_stub") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("name") {
+                return Err(meta.error(
+                    "`#[gen_stub(name = \"...\")]` is not supported on a variant, use `#[pyo3(name = \"...\")]` to rename the class at runtime as well",
+                ));
+            } else if meta.path.is_ident("module") {
+                // Handled by `parse_pyo3_attrs`
+                meta.value()?.parse::<LitStr>()?;
+            } else if meta.path.is_ident("no_match_args") {
+                // Handled by `parse_pyo3_attrs`
+            } else {
+                return Err(meta.error(
+                    "Unsupported keyword on a variant, valid is `skip` or `no_match_args`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(skip)
+}
+
+pub(crate) fn parse_gen_stub_type_ignore(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<Option<IgnoreTarget>> {
+    for attr in parse_gen_stub_attrs(attrs, location, None)? {
         if let StubGenAttr::TypeIgnore(target) = attr {
             return Ok(Some(target));
         }
@@ -412,6 +827,118 @@ pub fn parse_gen_stub_type_ignore(attrs: &[Attribute]) -> Result<Option<IgnoreTa
     Ok(None)
 }
 
+/// Parse `#[gen_stub(raises(MyError, ValueError))]` on a function or method
+pub(crate) fn parse_gen_stub_raises(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<Vec<Type>> {
+    let mut raises = Vec::new();
+    for attr in parse_gen_stub_attrs(attrs, location, None)? {
+        if let StubGenAttr::Raises(types) = attr {
+            raises.extend(types);
+        }
+    }
+    Ok(raises)
+}
+
+/// Parse `#[gen_stub(platform = "linux")]` on a function or method
+pub(crate) fn parse_gen_stub_platform(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<Option<LitStr>> {
+    for attr in parse_gen_stub_attrs(attrs, location, None)? {
+        if let StubGenAttr::Platform(platform) = attr {
+            return Ok(Some(platform));
+        }
+    }
+    Ok(None)
+}
+
+/// Parse `#[gen_stub(py_version = ">=3.12")]` on a function or method
+pub(crate) fn parse_gen_stub_py_version(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<Option<LitStr>> {
+    for attr in parse_gen_stub_attrs(attrs, location, None)? {
+        if let StubGenAttr::PyVersion(py_version) = attr {
+            return Ok(Some(py_version));
+        }
+    }
+    Ok(None)
+}
+
+/// Parse `#[gen_stub(releases_gil)]` on a function or method
+pub(crate) fn parse_gen_stub_releases_gil(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<bool> {
+    Ok(parse_gen_stub_attrs(attrs, location, None)?
+        .iter()
+        .any(|attr| matches!(attr, StubGenAttr::ReleasesGil)))
+}
+
+/// Parse `#[gen_stub(named_tuple(name = "Stats", fields("min", "max")))]` on a function or method
+pub(crate) fn parse_gen_stub_named_tuple(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<Option<NamedTupleAttr>> {
+    for attr in parse_gen_stub_attrs(attrs, location, None)? {
+        if let StubGenAttr::NamedTuple(named_tuple) = attr {
+            return Ok(Some(named_tuple));
+        }
+    }
+    Ok(None)
+}
+
+/// Parse `#[gen_stub(var_args_type = ..., kwargs_value_type = ...)]` on a function or method
+///
+/// Returns the element type of `*args` and the value type of `**kwargs` respectively.
+/// `kwargs_unpack = "Options"` gives the latter as `typing_extensions.Unpack[Options]`.
+pub(crate) fn parse_gen_stub_var_arg_types(
+    attrs: &[Attribute],
+    location: AttributeLocation,
+) -> Result<(Option<OverrideTypeAttribute>, Option<OverrideTypeAttribute>)> {
+    let mut var_args = None;
+    let mut kwargs_value = None;
+    let mut kwargs_unpack = None;
+    for attr in parse_gen_stub_attrs(attrs, location, None)? {
+        match attr {
+            StubGenAttr::VarArgsType(attr) => var_args = Some(attr),
+            StubGenAttr::KwargsValueType(attr) => kwargs_value = Some(attr),
+            StubGenAttr::KwargsUnpack(attr) => kwargs_unpack = Some(attr),
+            _ => {}
+        }
+    }
+    if let Some(typed_dict) = kwargs_unpack {
+        if kwargs_value.is_some() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "`kwargs_value_type` and `kwargs_unpack` cannot be used together",
+            ));
+        }
+        kwargs_value = Some(OverrideTypeAttribute::subscripted(
+            "typing_extensions.Unpack",
+            typed_dict,
+        ));
+    }
+    Ok((var_args, kwargs_value))
+}
+
+/// `>=3.12` or `<3.12`, the comparisons of `sys.version_info` understood by type checkers
+fn is_valid_py_version(py_version: &str) -> bool {
+    let Some(version) = py_version
+        .strip_prefix(">=")
+        .or_else(|| py_version.strip_prefix('<'))
+    else {
+        return false;
+    };
+    let parts: Vec<&str> = version.trim().split('.').collect();
+    parts.len() == 2
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
 fn parse_gen_stub_attrs(
     attrs: &[Attribute],
     location: AttributeLocation,
@@ -437,26 +964,82 @@ fn parse_gen_stub_attr(
             while !input.is_empty() {
                 let ident: Ident = input.parse()?;
                 let ignored_ident = ignored_idents.iter().any(|other| ident == other);
-                if (ident == "override_type"
+                if ident == "assume_cfg" {
+                    // Consumed by `expand_cfg_attrs` before the attributes reach here
+                    let content;
+                    parenthesized!(content in input);
+                    Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                } else if (ident == "override_type"
                     && (location == AttributeLocation::Argument || ignored_ident))
                     || (ident == "override_return_type"
-                        && (location == AttributeLocation::Function || location == AttributeLocation::Field || ignored_ident))
+                        && (location.is_function() || location == AttributeLocation::Field || ignored_ident))
                 {
                     let content;
                     parenthesized!(content in input);
                     let override_attr: OverrideTypeAttribute = content.parse()?;
                     gen_stub_attrs.push(StubGenAttr::OverrideType(override_attr));
-                } else if ident == "skip" && (location == AttributeLocation::Field || ignored_ident)
+                } else if ident == "async_iterator"
+                    && (location.is_function() || location == AttributeLocation::Field || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    let override_attr = OverrideTypeAttribute::parse_async_iterator(&content)?;
+                    gen_stub_attrs.push(StubGenAttr::OverrideType(override_attr));
+                } else if (ident == "type_guard" || ident == "type_is")
+                    && (location.is_function() || location == AttributeLocation::Field || ignored_ident)
+                {
+                    // Handle two cases:
+                    // 1. type_guard = "Circle" -> narrowed type without additional imports
+                    // 2. type_guard(type_repr = "...", imports = (...)) -> same as override_type
+                    let narrowed = if input.peek(Token![=]) {
+                        input.parse::<Token![=]>()?;
+                        let type_repr: LitStr = input.parse()?;
+                        OverrideTypeAttribute {
+                            type_repr: type_repr.value(),
+                            imports: IndexSet::new(),
+                        }
+                    } else {
+                        let content;
+                        parenthesized!(content in input);
+                        content.parse()?
+                    };
+                    let form = if ident == "type_guard" {
+                        "typing.TypeGuard"
+                    } else {
+                        "typing_extensions.TypeIs"
+                    };
+                    gen_stub_attrs.push(StubGenAttr::OverrideType(
+                        OverrideTypeAttribute::subscripted(form, narrowed),
+                    ));
+                } else if ident == "skip"
+                    && (location == AttributeLocation::Field || location == AttributeLocation::Method || ignored_ident)
                 {
                     gen_stub_attrs.push(StubGenAttr::Skip);
+                } else if (ident == "skip_getter" || ident == "skip_setter")
+                    && (location == AttributeLocation::Field || ignored_ident)
+                {
+                    if ident == "skip_getter" {
+                        gen_stub_attrs.push(StubGenAttr::SkipGetter);
+                    } else {
+                        gen_stub_attrs.push(StubGenAttr::SkipSetter);
+                    }
+                } else if ident == "cached_property"
+                    && (location == AttributeLocation::Field || location.is_function() || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::CachedProperty);
                 } else if ident == "default"
                     && input.peek(Token![=])
-                    && (location == AttributeLocation::Field || location == AttributeLocation::Function || ignored_ident)
+                    && (location == AttributeLocation::Field || location.is_function() || ignored_ident)
                 {
                     input.parse::<Token![=]>()?;
                     gen_stub_attrs.push(StubGenAttr::Default(input.parse()?));
+                } else if ident == "default_symbol"
+                    && (location == AttributeLocation::Field || location.is_function() || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    gen_stub_attrs.push(StubGenAttr::DefaultSymbol(input.parse()?));
                 } else if ident == "type_ignore"
-                    && (location == AttributeLocation::Function || location == AttributeLocation::Field || ignored_ident)
+                    && (location.is_function() || ignored_ident)
                 {
                     // Handle two cases:
                     // 1. type_ignore (without equals) -> IgnoreTarget::All
@@ -483,47 +1066,187 @@ fn parse_gen_stub_attr(
                         // No equals sign means catch-all
                         gen_stub_attrs.push(StubGenAttr::TypeIgnore(IgnoreTarget::All));
                     }
+                } else if (ident == "var_args_type"
+                    || ident == "kwargs_value_type"
+                    || ident == "kwargs_unpack")
+                    && (location.is_function() || ignored_ident)
+                {
+                    // Handle two cases:
+                    // 1. var_args_type = "int" -> type without additional imports
+                    // 2. var_args_type(type_repr = "...", imports = (...)) -> same as override_type
+                    let override_attr = if input.peek(Token![=]) {
+                        input.parse::<Token![=]>()?;
+                        let type_repr: LitStr = input.parse()?;
+                        OverrideTypeAttribute {
+                            type_repr: type_repr.value(),
+                            imports: IndexSet::new(),
+                        }
+                    } else {
+                        let content;
+                        parenthesized!(content in input);
+                        content.parse()?
+                    };
+                    if ident == "var_args_type" {
+                        gen_stub_attrs.push(StubGenAttr::VarArgsType(override_attr));
+                    } else if ident == "kwargs_value_type" {
+                        gen_stub_attrs.push(StubGenAttr::KwargsValueType(override_attr));
+                    } else {
+                        gen_stub_attrs.push(StubGenAttr::KwargsUnpack(override_attr));
+                    }
+                } else if ident == "raises"
+                    && (location.is_function() || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+                    if types.is_empty() {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            "`raises(...)` must list at least one exception",
+                        ));
+                    }
+                    gen_stub_attrs.push(StubGenAttr::Raises(types.into_iter().collect()));
+                } else if ident == "platform"
+                    && (location.is_function() || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    let platform: LitStr = input.parse()?;
+                    let value = platform.value();
+                    let name = value.strip_prefix('!').unwrap_or(&value);
+                    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+                        return Err(syn::Error::new(
+                            platform.span(),
+                            "`platform` must be a value of `sys.platform`, e.g. \"linux\", optionally prefixed by `!`",
+                        ));
+                    }
+                    gen_stub_attrs.push(StubGenAttr::Platform(platform));
+                } else if ident == "py_version"
+                    && (location.is_function() || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    let py_version: LitStr = input.parse()?;
+                    if !is_valid_py_version(&py_version.value()) {
+                        return Err(syn::Error::new(
+                            py_version.span(),
+                            "`py_version` must be `>=` or `<` followed by a Python version, e.g. \">=3.12\"",
+                        ));
+                    }
+                    gen_stub_attrs.push(StubGenAttr::PyVersion(py_version));
+                } else if ident == "releases_gil"
+                    && (location.is_function() || ignored_ident)
+                {
+                    gen_stub_attrs.push(StubGenAttr::ReleasesGil);
+                } else if ident == "named_tuple"
+                    && (location.is_function() || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    gen_stub_attrs.push(StubGenAttr::NamedTuple(content.parse()?));
+                } else if ident == "dict_keys"
+                    && (location == AttributeLocation::Argument || ignored_ident)
+                {
+                    let content;
+                    parenthesized!(content in input);
+                    gen_stub_attrs.push(StubGenAttr::DictKeys(parse_dict_keys(&content)?));
+                } else if ident == "typed_dict"
+                    && (location == AttributeLocation::Argument || ignored_ident)
+                {
+                    input.parse::<Token![=]>()?;
+                    let name: LitStr = input.parse()?;
+                    if !is_python_identifier(&name.value()) {
+                        return Err(syn::Error::new(
+                            name.span(),
+                            "`typed_dict` must be a Python identifier",
+                        ));
+                    }
+                    gen_stub_attrs.push(StubGenAttr::TypedDict(name));
                 } else if ident == "override_type" {
                     return Err(syn::Error::new(
                         ident.span(),
                         "`override_type(...)` is only valid in argument position".to_string(),
                     ));
+                } else if ident == "dict_keys" || ident == "typed_dict" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("`{ident}` is only valid in argument position"),
+                    ));
                 } else if ident == "override_return_type" {
                     return Err(syn::Error::new(
                         ident.span(),
                         "`override_return_type(...)` is only valid in function or method position"
                             .to_string(),
                     ));
-                } else if ident == "skip" {
+                } else if ident == "async_iterator" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`async_iterator(...)` is only valid in function or method position"
+                            .to_string(),
+                    ));
+                } else if ident == "type_guard" || ident == "type_is" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("`{ident}` is only valid in function or method position"),
+                    ));
+                } else if ident == "skip" || ident == "skip_getter" || ident == "skip_setter" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("`{ident}` is only valid in field position"),
+                    ));
+                } else if ident == "cached_property" {
                     return Err(syn::Error::new(
                         ident.span(),
-                        "`skip` is only valid in field position".to_string(),
+                        "`cached_property` is only valid in field or function position".to_string(),
                     ));
                 } else if ident == "default" {
                     return Err(syn::Error::new(
                         ident.span(),
                         "`default=xxx` is only valid in field or function position".to_string(),
                     ));
+                } else if ident == "default_symbol" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`default_symbol=\"...\"` is only valid in field or function position".to_string(),
+                    ));
                 } else if ident == "type_ignore" {
                     return Err(syn::Error::new(
                         ident.span(),
                         "`type_ignore` or `type_ignore=[...]` is only valid in function or method position".to_string(),
                     ));
+                } else if ident == "var_args_type"
+                    || ident == "kwargs_value_type"
+                    || ident == "kwargs_unpack"
+                    || ident == "raises"
+                    || ident == "platform"
+                    || ident == "py_version"
+                    || ident == "releases_gil"
+                    || ident == "named_tuple"
+                {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("`{ident}` is only valid in function or method position"),
+                    ));
                 } else if location == AttributeLocation::Argument {
                     return Err(syn::Error::new(
                         ident.span(),
-                        format!("Unsupported keyword `{ident}`, valid is `override_type(...)`"),
+                        format!("Unsupported keyword `{ident}`, valid is `override_type(...)`, `dict_keys(...)`, or `typed_dict = \"...\"`"),
                     ));
                 } else if location == AttributeLocation::Field {
                     return Err(syn::Error::new(
                         ident.span(),
-                        format!("Unsupported keyword `{ident}`, valid is `default=xxx`, `skip`, `override_return_type(...)`, `type_ignore`, or `type_ignore=[...]`"),
+                        format!("Unsupported keyword `{ident}`, valid is `default=xxx`, `default_symbol=\"...\"`, `skip`, `skip_getter`, `skip_setter`, `cached_property`, `override_return_type(...)`, `async_iterator(...)`, `type_guard`, `type_is`, or `assume_cfg(...)`"),
+                    ));
+                } else if location == AttributeLocation::Method {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!(
+                            "Unsupported keyword `{ident}`, valid is `skip`, `default=xxx`, `default_symbol=\"...\"`, `cached_property`, `override_return_type(...)`, `async_iterator(...)`, `type_guard`, `type_is`, `type_ignore`, `type_ignore=[...]`, `var_args_type`, `kwargs_value_type`, `kwargs_unpack`, `raises(...)`, `platform`, `py_version`, `releases_gil`, `named_tuple(...)`, or `assume_cfg(...)`"
+                        ),
                     ));
                 } else if location == AttributeLocation::Function {
                     return Err(syn::Error::new(
                         ident.span(),
                         format!(
-                            "Unsupported keyword `{ident}`, valid is `default=xxx`, `override_return_type(...)`, `type_ignore`, or `type_ignore=[...]`"
+                            "Unsupported keyword `{ident}`, valid is `default=xxx`, `default_symbol=\"...\"`, `override_return_type(...)`, `async_iterator(...)`, `type_guard`, `type_is`, `type_ignore`, `type_ignore=[...]`, `var_args_type`, `kwargs_value_type`, `kwargs_unpack`, `raises(...)`, `platform`, `py_version`, `releases_gil`, `named_tuple(...)`, or `assume_cfg(...)`"
                         ),
                     ));
                 } else {
@@ -549,6 +1272,15 @@ pub(crate) enum AttributeLocation {
     Argument,
     Field,
     Function,
+    /// Function in `#[pymethods]`, which accepts the keys of [AttributeLocation::Function] and `skip`
+    Method,
+}
+
+impl AttributeLocation {
+    /// Whether the attribute is on a function or a method
+    fn is_function(self) -> bool {
+        matches!(self, Self::Function | Self::Method)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -557,32 +1289,34 @@ pub struct OverrideTypeAttribute {
     pub(crate) imports: IndexSet<String>,
 }
 
-mod kw {
-    syn::custom_keyword!(type_repr);
-    syn::custom_keyword!(imports);
-    syn::custom_keyword!(override_type);
+/// `input = "...", imports = (...)` of `#[gen_stub_converter(...)]` on a `from_py_with` converter
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConverterAttr {
+    pub(crate) input: String,
+    pub(crate) imports: IndexSet<String>,
 }
 
-impl Parse for OverrideTypeAttribute {
+impl Parse for ConverterAttr {
     fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
         let mut type_repr = None;
         let mut imports = IndexSet::new();
 
         while !input.is_empty() {
             let lookahead = input.lookahead1();
 
-            if lookahead.peek(kw::type_repr) {
-                input.parse::<kw::type_repr>()?;
+            if lookahead.peek(kw::input) {
+                input.parse::<kw::input>()?;
                 input.parse::<Token![=]>()?;
-                type_repr = Some(input.parse::<LitStr>()?);
+                type_repr = Some(input.parse::<LitStr>()?.value());
             } else if lookahead.peek(kw::imports) {
                 input.parse::<kw::imports>()?;
                 input.parse::<Token![=]>()?;
 
                 let content;
                 parenthesized!(content in input);
-                let parsed_imports = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
-                imports = parsed_imports.into_iter().collect();
+                let parsed = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                imports = parsed.into_iter().map(|import| import.value()).collect();
             } else {
                 return Err(lookahead.error());
             }
@@ -592,15 +1326,459 @@ impl Parse for OverrideTypeAttribute {
             }
         }
 
-        Ok(OverrideTypeAttribute {
-            type_repr: type_repr
-                .ok_or_else(|| input.error("missing type_repr"))?
-                .value(),
+        Ok(ConverterAttr {
+            input: type_repr.ok_or_else(|| syn::Error::new(span, "missing `input = \"...\"`"))?,
+            imports,
+        })
+    }
+}
+
+mod kw {
+    syn::custom_keyword!(input);
+    syn::custom_keyword!(type_repr);
+    syn::custom_keyword!(imports);
+    syn::custom_keyword!(override_type);
+    syn::custom_keyword!(key);
+    syn::custom_keyword!(value);
+    syn::custom_keyword!(item);
+    syn::custom_keyword!(send);
+    syn::custom_keyword!(name);
+    syn::custom_keyword!(fields);
+}
+
+/// `name = "...", fields("...", ...)` of `named_tuple(...)` on a function
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedTupleAttr {
+    pub(crate) name: LitStr,
+    pub(crate) fields: Vec<LitStr>,
+}
+
+impl Parse for NamedTupleAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let mut name = None;
+        let mut fields = None;
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::name) {
+                input.parse::<kw::name>()?;
+                input.parse::<Token![=]>()?;
+                name = Some(input.parse::<LitStr>()?);
+            } else if lookahead.peek(kw::fields) {
+                input.parse::<kw::fields>()?;
+                let content;
+                parenthesized!(content in input);
+                let parsed = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                fields = Some(parsed.into_iter().collect::<Vec<_>>());
+            } else {
+                return Err(lookahead.error());
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let name = name.ok_or_else(|| syn::Error::new(span, "missing `name`"))?;
+        let fields = fields.ok_or_else(|| syn::Error::new(span, "missing `fields(...)`"))?;
+        if !is_python_identifier(&name.value()) {
+            return Err(syn::Error::new(
+                name.span(),
+                "`name` of `named_tuple` must be a Python identifier",
+            ));
+        }
+        for (i, field) in fields.iter().enumerate() {
+            let value = field.value();
+            // `typing.NamedTuple` rejects field names starting with an underscore
+            if !is_python_identifier(&value) || value.starts_with('_') {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "fields of `named_tuple` must be Python identifiers not starting with `_`",
+                ));
+            }
+            if fields[..i].iter().any(|other| other.value() == value) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    format!("field `{value}` is specified more than once"),
+                ));
+            }
+        }
+        Ok(NamedTupleAttr { name, fields })
+    }
+}
+
+/// Keys and value types given by `dict_keys(...)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictKeysAttr {
+    pub(crate) keys: Vec<(String, LitStr)>,
+    pub(crate) typed_dict: Option<LitStr>,
+}
+
+/// `host = "str", port = "int"` in `dict_keys(...)`, where keys may be Rust keywords like `type`
+fn parse_dict_keys(input: ParseStream) -> Result<Vec<(String, LitStr)>> {
+    let span = input.span();
+    let mut keys: Vec<(String, LitStr)> = Vec::new();
+    while !input.is_empty() {
+        let key = Ident::parse_any(input)?;
+        input.parse::<Token![=]>()?;
+        let type_repr: LitStr = input.parse()?;
+        let name = key.unraw().to_string();
+        if keys.iter().any(|(other, _)| *other == name) {
+            return Err(syn::Error::new(
+                key.span(),
+                format!("key `{name}` is specified more than once"),
+            ));
+        }
+        keys.push((name, type_repr));
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    if keys.is_empty() {
+        return Err(syn::Error::new(
+            span,
+            "`dict_keys(...)` must list at least one key",
+        ));
+    }
+    Ok(keys)
+}
+
+fn is_python_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemAccessKind {
+    Get,
+    Set,
+    Del,
+}
+
+/// `getitem(...)`, `setitem(...)` or `delitem(...)` in `#[gen_stub(...)]` on a class
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemAccessAttr {
+    pub(crate) kind: ItemAccessKind,
+    pub(crate) key: String,
+    /// Value type, which is absent for `delitem`
+    pub(crate) value: Option<String>,
+    pub(crate) imports: IndexSet<String>,
+}
+
+impl ItemAccessAttr {
+    /// Parse `key = "...", value = "...", imports = (...)`
+    fn parse_with_kind(input: ParseStream, kind: ItemAccessKind) -> Result<Self> {
+        let span = input.span();
+        let mut key = None;
+        let mut value = None;
+        let mut imports = IndexSet::new();
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::key) {
+                input.parse::<kw::key>()?;
+                input.parse::<Token![=]>()?;
+                key = Some(input.parse::<LitStr>()?.value());
+            } else if lookahead.peek(kw::value) {
+                let value_kw = input.parse::<kw::value>()?;
+                if kind == ItemAccessKind::Del {
+                    return Err(syn::Error::new(
+                        value_kw.span,
+                        "`delitem(...)` does not take `value`",
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                value = Some(input.parse::<LitStr>()?.value());
+            } else if lookahead.peek(kw::imports) {
+                input.parse::<kw::imports>()?;
+                input.parse::<Token![=]>()?;
+
+                let content;
+                parenthesized!(content in input);
+                let parsed_imports = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                imports = parsed_imports.into_iter().map(|i| i.value()).collect();
+            } else {
+                return Err(lookahead.error());
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let key = key.ok_or_else(|| syn::Error::new(span, "missing `key`"))?;
+        if value.is_none() && kind != ItemAccessKind::Del {
+            return Err(syn::Error::new(span, "missing `value`"));
+        }
+        Ok(ItemAccessAttr {
+            kind,
+            key,
+            value,
+            imports,
+        })
+    }
+}
+
+/// `item = "...", imports = (...)` of `iterator(...)` on a class or `async_iterator(...)` on a function
+///
+/// `send = "..."` and `return = "..."` make a class a `collections.abc.Generator` instead of an `Iterator`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IteratorAttr {
+    pub(crate) item: String,
+    pub(crate) send: Option<String>,
+    pub(crate) r#return: Option<String>,
+    pub(crate) imports: IndexSet<String>,
+}
+
+impl Parse for IteratorAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut item = None;
+        let mut send = None;
+        let mut r#return = None;
+        let mut imports = IndexSet::new();
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::item) {
+                input.parse::<kw::item>()?;
+                input.parse::<Token![=]>()?;
+                item = Some(input.parse::<LitStr>()?);
+            } else if lookahead.peek(kw::send) {
+                input.parse::<kw::send>()?;
+                input.parse::<Token![=]>()?;
+                send = Some(input.parse::<LitStr>()?.value());
+            } else if lookahead.peek(Token![return]) {
+                input.parse::<Token![return]>()?;
+                input.parse::<Token![=]>()?;
+                r#return = Some(input.parse::<LitStr>()?.value());
+            } else if lookahead.peek(kw::imports) {
+                input.parse::<kw::imports>()?;
+                input.parse::<Token![=]>()?;
+
+                let content;
+                parenthesized!(content in input);
+                let parsed_imports = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                imports.extend(parsed_imports.iter().map(|i| i.value()));
+            } else {
+                return Err(lookahead.error());
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let item = item.ok_or_else(|| input.error("missing item"))?.value();
+        Ok(IteratorAttr {
+            item,
+            send,
+            r#return,
+            imports,
+        })
+    }
+}
+
+impl ToTokens for IteratorAttr {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            item,
+            send,
+            r#return,
+            imports,
+        } = self;
+        let send = quote_option(send);
+        let r#return = quote_option(r#return);
+        let imports = imports.iter();
+        tokens.append_all(quote! {
+            ::pyo3_stub_gen::type_info::IteratorInfo {
+                item: #item,
+                send: #send,
+                r#return: #r#return,
+                imports: &[ #(#imports),* ],
+            }
+        })
+    }
+}
+
+/// `eq_default = true, kw_only_default = false, ...` of `dataclass_transform(...)` on a class
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataclassTransformAttr {
+    pub(crate) eq_default: Option<bool>,
+    pub(crate) order_default: Option<bool>,
+    pub(crate) kw_only_default: Option<bool>,
+    pub(crate) frozen_default: Option<bool>,
+}
+
+impl Parse for DataclassTransformAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut out = Self::default();
+        while !input.is_empty() {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value = input.parse::<LitBool>()?.value;
+            let slot = match name.to_string().as_str() {
+                "eq_default" => &mut out.eq_default,
+                "order_default" => &mut out.order_default,
+                "kw_only_default" => &mut out.kw_only_default,
+                "frozen_default" => &mut out.frozen_default,
+                _ => return Err(syn::Error::new(
+                    name.span(),
+                    "expected `eq_default`, `order_default`, `kw_only_default` or `frozen_default`",
+                )),
+            };
+            *slot = Some(value);
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl ToTokens for DataclassTransformAttr {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            eq_default,
+            order_default,
+            kw_only_default,
+            frozen_default,
+        } = self;
+        let eq_default = quote_option(eq_default);
+        let order_default = quote_option(order_default);
+        let kw_only_default = quote_option(kw_only_default);
+        let frozen_default = quote_option(frozen_default);
+        tokens.append_all(quote! {
+            ::pyo3_stub_gen::type_info::DataclassTransformInfo {
+                eq_default: #eq_default,
+                order_default: #order_default,
+                kw_only_default: #kw_only_default,
+                frozen_default: #frozen_default,
+            }
+        })
+    }
+}
+
+impl ToTokens for ItemAccessAttr {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self {
+            kind,
+            key,
+            value,
+            imports,
+        } = self;
+        let imports = imports.iter();
+        let value = value.as_deref().unwrap_or_default();
+        tokens.append_all(match kind {
+            ItemAccessKind::Get => quote! {
+                ::pyo3_stub_gen::type_info::ItemAccessInfo::Get {
+                    key: #key,
+                    value: #value,
+                    imports: &[ #(#imports),* ],
+                }
+            },
+            ItemAccessKind::Set => quote! {
+                ::pyo3_stub_gen::type_info::ItemAccessInfo::Set {
+                    key: #key,
+                    value: #value,
+                    imports: &[ #(#imports),* ],
+                }
+            },
+            ItemAccessKind::Del => quote! {
+                ::pyo3_stub_gen::type_info::ItemAccessInfo::Del {
+                    key: #key,
+                    imports: &[ #(#imports),* ],
+                }
+            },
+        })
+    }
+}
+
+impl Parse for OverrideTypeAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut type_repr = None;
+        let mut imports = IndexSet::new();
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::type_repr) {
+                input.parse::<kw::type_repr>()?;
+                input.parse::<Token![=]>()?;
+                type_repr = Some(input.parse::<LitStr>()?);
+            } else if lookahead.peek(kw::imports) {
+                input.parse::<kw::imports>()?;
+                input.parse::<Token![=]>()?;
+
+                let content;
+                parenthesized!(content in input);
+                let parsed_imports = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                imports = parsed_imports.into_iter().collect();
+            } else {
+                return Err(lookahead.error());
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(OverrideTypeAttribute {
+            type_repr: type_repr
+                .ok_or_else(|| input.error("missing type_repr"))?
+                .value(),
             imports: imports.iter().map(|i| i.value()).collect(),
         })
     }
 }
 
+impl OverrideTypeAttribute {
+    /// Parse the content of `async_iterator(item = "...", imports = (...))`
+    ///
+    /// The return type is overridden by `collections.abc.AsyncIterator[item]`.
+    fn parse_async_iterator(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let IteratorAttr {
+            item,
+            send,
+            r#return,
+            mut imports,
+        } = input.parse()?;
+        if send.is_some() || r#return.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`send` and `return` are only supported by `iterator(...)` on a class",
+            ));
+        }
+        imports.insert("collections.abc".to_string());
+        Ok(OverrideTypeAttribute {
+            type_repr: format!("collections.abc.AsyncIterator[{item}]"),
+            imports,
+        })
+    }
+
+    /// `form[inner]` importing the module of `form`,
+    /// e.g. `typing.TypeGuard[inner]` for the return type of a type narrowing function
+    fn subscripted(form: &str, inner: OverrideTypeAttribute) -> Self {
+        let OverrideTypeAttribute {
+            type_repr,
+            mut imports,
+        } = inner;
+        let (module, _) = form.rsplit_once('.').expect("form must be qualified");
+        imports.insert(module.to_string());
+        OverrideTypeAttribute {
+            type_repr: format!("{form}[{type_repr}]"),
+            imports,
+        }
+    }
+}
+
 /// Common attributes for `#[gen_stub_pyclass(...)]`, `#[gen_stub_pyclass_enum(...)]`,
 /// and `#[gen_stub_pyclass_complex_enum(...)]` macros
 #[derive(Default)]
@@ -688,6 +1866,55 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_pyo3_attr_cfg_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[gen_stub(assume_cfg("feature = \"abi3\""))]
+            #[cfg_attr(feature = "abi3", pyo3(name = "limited"))]
+            #[cfg_attr(not(feature = "abi3"), pyo3(name = "full"))]
+            #[cfg_attr(all(), pyo3(get))]
+            fn f() {}
+            "#,
+        )?;
+        assert_eq!(
+            parse_pyo3_attrs(&item.attrs)?,
+            vec![Attr::Name("limited".to_string()), Attr::Get,]
+        );
+
+        // Without `assume_cfg`, `cfg_attr` depending on the configuration is kept with a warning
+        let item: ItemFn = parse_str(
+            r#"
+            #[cfg_attr(feature = "abi3", pyo3(name = "limited"))]
+            #[cfg_attr(any(), pyo3(name = "never"))]
+            fn f() {}
+            "#,
+        )?;
+        assert_eq!(parse_pyo3_attrs(&item.attrs)?, vec![]);
+        let warnings = with_warnings(Ok(TokenStream2::new()))?.to_string();
+        assert!(warnings.contains("deprecated"));
+        assert!(warnings.contains("assume_cfg"));
+        assert!(!warnings.contains("never"));
+
+        // Only the assumed predicates are evaluated, and `not()` assumes one false
+        let item: ItemFn = parse_str(
+            r#"
+            #[gen_stub(assume_cfg("not(feature = \"abi3\")"))]
+            #[cfg_attr(feature = "abi3", pyo3(name = "limited"))]
+            #[cfg_attr(not(feature = "abi3"), pyo3(name = "full"))]
+            #[cfg_attr(unix, pyo3(get))]
+            fn f() {}
+            "#,
+        )?;
+        assert_eq!(
+            parse_pyo3_attrs(&item.attrs)?,
+            vec![Attr::Name("full".to_string())]
+        );
+        let warnings = with_warnings(Ok(TokenStream2::new()))?.to_string();
+        assert!(warnings.contains("unix"));
+        Ok(())
+    }
+
     #[test]
     fn test_parse_pyo3_attr_full_path() -> Result<()> {
         let item: ItemStruct = parse_str(
@@ -773,7 +2000,7 @@ mod test {
     fn test_parse_gen_stub_module_attr_from_macro_substitution() -> Result<()> {
         let attrs =
             attrs_with_invisible_group(quote!(gen_stub), quote!(module), quote!("explicit.mod"));
-        // `parse_pyo3_attrs` also dispatches to `parse_gen_stub_module_attr`.
+        // `parse_pyo3_attrs` also dispatches to `parse_gen_stub_standalone_attrs`.
         let parsed = parse_pyo3_attrs(&attrs)?;
         assert_eq!(
             parsed,
@@ -783,43 +2010,269 @@ mod test {
     }
 
     #[test]
-    fn test_parse_gen_stub_field_attr() -> Result<()> {
+    fn test_parse_gen_stub_buffer_attr() -> Result<()> {
         let item: ItemStruct = parse_str(
             r#"
-            pub struct PyPlaceholder {
-                #[gen_stub(default = String::from("foo"), skip)]
-                pub field0: String,
-                #[gen_stub(skip)]
-                pub field1: String,
-                #[gen_stub(default = 1+2)]
-                pub field2: usize,
-            }
+            #[pyclass]
+            #[gen_stub(buffer, module = "my_module")]
+            pub struct PyBuffer {}
             "#,
         )?;
-        let fields: Vec<_> = item.fields.into_iter().collect();
-        let field0_attrs = parse_gen_stub_attrs(&fields[0].attrs, AttributeLocation::Field, None)?;
-        if let StubGenAttr::Default(expr) = &field0_attrs[0] {
-            assert_eq!(
-                expr.to_token_stream().to_string(),
-                "String :: from (\"foo\")"
-            );
-        } else {
-            panic!("attr should be Default");
-        };
-        assert_eq!(&StubGenAttr::Skip, &field0_attrs[1]);
-        let field1_attrs = parse_gen_stub_attrs(&fields[1].attrs, AttributeLocation::Field, None)?;
-        assert_eq!(vec![StubGenAttr::Skip], field1_attrs);
-        let field2_attrs = parse_gen_stub_attrs(&fields[2].attrs, AttributeLocation::Field, None)?;
-        if let StubGenAttr::Default(expr) = &field2_attrs[0] {
-            assert_eq!(expr.to_token_stream().to_string(), "1 + 2");
-        } else {
-            panic!("attr should be Default");
-        };
+        let parsed = parse_pyo3_attrs(&item.attrs)?;
+        assert_eq!(
+            parsed,
+            vec![
+                Attr::GenStubBuffer,
+                Attr::GenStubModule("my_module".to_string())
+            ]
+        );
         Ok(())
     }
+
     #[test]
-    fn test_parse_gen_stub_override_type_attr() -> Result<()> {
-        let item: ItemFn = parse_str(
+    fn test_parse_gen_stub_picklable_attr() -> Result<()> {
+        let item: ItemStruct = parse_str(
+            r#"
+            #[pyclass(module = "my_module")]
+            #[gen_stub(picklable)]
+            pub struct PySnapshot {}
+            "#,
+        )?;
+        let parsed = parse_pyo3_attrs(&item.attrs)?;
+        assert_eq!(
+            parsed,
+            vec![
+                Attr::Module("my_module".to_string()),
+                Attr::GenStubPicklable
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_slots_attr() -> Result<()> {
+        let item: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(slots, picklable)]
+            pub struct Point {}
+            "#,
+        )?;
+        let parsed = parse_pyo3_attrs(&item.attrs)?;
+        assert_eq!(parsed, vec![Attr::GenStubSlots, Attr::GenStubPicklable]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_class_type_ignore_attr() -> Result<()> {
+        let item: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(type_ignore)]
+            #[gen_stub(type_ignore = ["misc", "override"])]
+            pub struct PyDynamic {}
+            "#,
+        )?;
+        let parsed = parse_pyo3_attrs(&item.attrs)?;
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], Attr::GenStubTypeIgnore(IgnoreTarget::All));
+        let Attr::GenStubTypeIgnore(IgnoreTarget::SpecifiedLits(rules)) = &parsed[1] else {
+            panic!("unexpected {:?}", parsed[1]);
+        };
+        let rules: Vec<String> = rules.iter().map(LitStr::value).collect();
+        assert_eq!(rules, ["misc", "override"]);
+
+        let item: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(type_ignore = [])]
+            pub struct PyDynamic {}
+            "#,
+        )?;
+        assert!(parse_pyo3_attrs(&item.attrs).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_dataclass_transform_attr() -> Result<()> {
+        let item: ItemStruct = parse_str(
+            r#"
+            #[pyclass(subclass)]
+            #[gen_stub(dataclass_transform(eq_default = true, kw_only_default = false))]
+            pub struct Model {}
+            "#,
+        )?;
+        let parsed = parse_pyo3_attrs(&item.attrs)?;
+        assert_eq!(
+            parsed,
+            vec![
+                Attr::Subclass,
+                Attr::GenStubDataclassTransform(DataclassTransformAttr {
+                    eq_default: Some(true),
+                    kw_only_default: Some(false),
+                    ..Default::default()
+                })
+            ]
+        );
+
+        let item: ItemStruct = parse_str(
+            r#"
+            #[gen_stub(dataclass_transform(init_default = true))]
+            pub struct Model {}
+            "#,
+        )?;
+        assert!(parse_pyo3_attrs(&item.attrs).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_iterator_attr() -> Result<()> {
+        let item: ItemStruct = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(iterator(item = "numpy.typing.NDArray[numpy.float64]", imports = ("numpy", "numpy.typing")))]
+            pub struct PyRows {}
+            "#,
+        )?;
+        let parsed = parse_pyo3_attrs(&item.attrs)?;
+        assert_eq!(
+            parsed,
+            vec![Attr::GenStubIterator(IteratorAttr {
+                item: "numpy.typing.NDArray[numpy.float64]".to_string(),
+                send: None,
+                r#return: None,
+                imports: IndexSet::from(["numpy".to_string(), "numpy.typing".to_string()]),
+            })]
+        );
+
+        let item: ItemStruct = parse_str(
+            r#"
+            #[gen_stub(iterator(item = "int", send = "str", return = "bool"))]
+            pub struct Accumulator {}
+            "#,
+        )?;
+        assert_eq!(
+            parse_pyo3_attrs(&item.attrs)?,
+            vec![Attr::GenStubIterator(IteratorAttr {
+                item: "int".to_string(),
+                send: Some("str".to_string()),
+                r#return: Some("bool".to_string()),
+                imports: IndexSet::new(),
+            })]
+        );
+
+        let item: ItemStruct = parse_str(
+            r#"
+            #[gen_stub(iterator(imports = ("numpy")))]
+            pub struct PyRows {}
+            "#,
+        )?;
+        assert!(parse_pyo3_attrs(&item.attrs).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_item_access_attr() -> Result<()> {
+        let item: ItemStruct = parse_str(
+            r#"
+            #[pyclass(mapping)]
+            #[gen_stub(getitem(key = "str", value = "int"), delitem(key = "str"))]
+            #[gen_stub(setitem(key = "str", value = "collections.abc.Sequence[int]", imports = ("collections.abc")))]
+            pub struct PyMapping {}
+            "#,
+        )?;
+        let parsed = parse_pyo3_attrs(&item.attrs)?;
+        assert_eq!(
+            parsed,
+            vec![
+                Attr::GenStubItemAccess(ItemAccessAttr {
+                    kind: ItemAccessKind::Get,
+                    key: "str".to_string(),
+                    value: Some("int".to_string()),
+                    imports: IndexSet::new(),
+                }),
+                Attr::GenStubItemAccess(ItemAccessAttr {
+                    kind: ItemAccessKind::Del,
+                    key: "str".to_string(),
+                    value: None,
+                    imports: IndexSet::new(),
+                }),
+                Attr::GenStubItemAccess(ItemAccessAttr {
+                    kind: ItemAccessKind::Set,
+                    key: "str".to_string(),
+                    value: Some("collections.abc.Sequence[int]".to_string()),
+                    imports: IndexSet::from(["collections.abc".to_string()]),
+                }),
+            ]
+        );
+
+        let item: ItemStruct = parse_str(
+            r#"
+            #[gen_stub(delitem(key = "str", value = "int"))]
+            pub struct PyMapping {}
+            "#,
+        )?;
+        assert!(parse_pyo3_attrs(&item.attrs).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_field_attr() -> Result<()> {
+        let item: ItemStruct = parse_str(
+            r#"
+            pub struct PyPlaceholder {
+                #[gen_stub(default = String::from("foo"), skip)]
+                pub field0: String,
+                #[gen_stub(skip)]
+                pub field1: String,
+                #[gen_stub(default = 1+2)]
+                pub field2: usize,
+            }
+            "#,
+        )?;
+        let fields: Vec<_> = item.fields.into_iter().collect();
+        let field0_attrs = parse_gen_stub_attrs(&fields[0].attrs, AttributeLocation::Field, None)?;
+        if let StubGenAttr::Default(expr) = &field0_attrs[0] {
+            assert_eq!(
+                expr.to_token_stream().to_string(),
+                "String :: from (\"foo\")"
+            );
+        } else {
+            panic!("attr should be Default");
+        };
+        assert_eq!(&StubGenAttr::Skip, &field0_attrs[1]);
+        let field1_attrs = parse_gen_stub_attrs(&fields[1].attrs, AttributeLocation::Field, None)?;
+        assert_eq!(vec![StubGenAttr::Skip], field1_attrs);
+        let field2_attrs = parse_gen_stub_attrs(&fields[2].attrs, AttributeLocation::Field, None)?;
+        if let StubGenAttr::Default(expr) = &field2_attrs[0] {
+            assert_eq!(expr.to_token_stream().to_string(), "1 + 2");
+        } else {
+            panic!("attr should be Default");
+        };
+        Ok(())
+    }
+    #[test]
+    fn test_parse_gen_stub_default_symbol() -> Result<()> {
+        let item: ItemStruct = parse_str(
+            r#"
+            pub struct PyPlaceholder {
+                #[gen_stub(default = A::default().x, default_symbol = "A().x")]
+                pub field0: usize,
+            }
+            "#,
+        )?;
+        let fields: Vec<_> = item.fields.into_iter().collect();
+        let field0_attrs = parse_gen_stub_attrs(&fields[0].attrs, AttributeLocation::Field, None)?;
+        assert_eq!(field0_attrs.len(), 2);
+        assert_eq!(
+            parse_gen_stub_default_symbol(&fields[0].attrs)?.as_deref(),
+            Some("A().x")
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_parse_gen_stub_override_type_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
             r#"
             #[gen_stub_pyfunction]
             #[pyfunction]
@@ -864,4 +2317,366 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_parse_gen_stub_var_arg_types_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[pyo3(signature = (*args, **kwargs))]
+            #[gen_stub(var_args_type = "int", kwargs_value_type(type_repr = "collections.abc.Sequence[str]", imports = ("collections.abc")))]
+            fn func(args: &Bound<PyTuple>, kwargs: Option<&Bound<PyDict>>) {}
+            "#,
+        )?;
+        let (var_args, kwargs_value) =
+            parse_gen_stub_var_arg_types(&item.attrs, AttributeLocation::Function)?;
+        assert_eq!(
+            var_args,
+            Some(OverrideTypeAttribute {
+                type_repr: "int".into(),
+                imports: IndexSet::new()
+            })
+        );
+        assert_eq!(
+            kwargs_value,
+            Some(OverrideTypeAttribute {
+                type_repr: "collections.abc.Sequence[str]".into(),
+                imports: IndexSet::from(["collections.abc".into()])
+            })
+        );
+
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[pyo3(signature = (**kwargs))]
+            #[gen_stub(kwargs_unpack = "SearchOptions")]
+            fn search(kwargs: Option<&Bound<PyDict>>) {}
+            "#,
+        )?;
+        let (_, kwargs_value) =
+            parse_gen_stub_var_arg_types(&item.attrs, AttributeLocation::Function)?;
+        assert_eq!(
+            kwargs_value,
+            Some(OverrideTypeAttribute {
+                type_repr: "typing_extensions.Unpack[SearchOptions]".into(),
+                imports: IndexSet::from(["typing_extensions".into()])
+            })
+        );
+
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(kwargs_unpack = "SearchOptions", kwargs_value_type = "int")]
+            fn search(kwargs: Option<&Bound<PyDict>>) {}
+            "#,
+        )?;
+        assert!(parse_gen_stub_var_arg_types(&item.attrs, AttributeLocation::Function).is_err());
+
+        // `skip` is accepted on methods, but not on functions
+        let item: ItemFn = parse_str(
+            r#"
+            #[gen_stub(skip, var_args_type = "int")]
+            fn method(&self, args: &Bound<PyTuple>) {}
+            "#,
+        )?;
+        let (var_args, _) = parse_gen_stub_var_arg_types(&item.attrs, AttributeLocation::Method)?;
+        assert_eq!(var_args.map(|attr| attr.type_repr), Some("int".into()));
+        assert!(parse_gen_stub_var_arg_types(&item.attrs, AttributeLocation::Function).is_err());
+
+        let item: ItemFn = parse_str(
+            r#"
+            #[gen_stub(skip_getter)]
+            fn method(&self) {}
+            "#,
+        )?;
+        assert!(parse_gen_stub_attrs(&item.attrs, AttributeLocation::Method, None).is_err());
+
+        let item: ItemFn = parse_str(
+            r#"
+            fn func(#[gen_stub(var_args_type = "int")] args: &Bound<PyTuple>) {}
+            "#,
+        )?;
+        if let syn::FnArg::Typed(PatType { attrs, .. }) = &item.sig.inputs[0] {
+            assert!(parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_async_iterator_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(async_iterator(item = "numpy.typing.NDArray[numpy.uint8]", imports = ("numpy", "numpy.typing")))]
+            fn stream(py: Python<'_>) -> PyResult<Py<PyAny>> {}
+            "#,
+        )?;
+        assert_eq!(
+            parse_gen_stub_override_return_type(&item.attrs)?,
+            Some(OverrideTypeAttribute {
+                type_repr: "collections.abc.AsyncIterator[numpy.typing.NDArray[numpy.uint8]]"
+                    .into(),
+                imports: IndexSet::from([
+                    "numpy".into(),
+                    "numpy.typing".into(),
+                    "collections.abc".into()
+                ])
+            })
+        );
+
+        let item: ItemFn = parse_str(
+            r#"
+            fn stream(#[gen_stub(async_iterator(item = "bytes"))] arg: Py<PyAny>) {}
+            "#,
+        )?;
+        if let syn::FnArg::Typed(PatType { attrs, .. }) = &item.sig.inputs[0] {
+            assert!(parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_type_narrowing_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(type_guard = "str")]
+            fn is_str(obj: &Bound<'_, PyAny>) -> bool {}
+            "#,
+        )?;
+        assert_eq!(
+            parse_gen_stub_override_return_type(&item.attrs)?,
+            Some(OverrideTypeAttribute {
+                type_repr: "typing.TypeGuard[str]".into(),
+                imports: IndexSet::from(["typing".into()])
+            })
+        );
+
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(type_is(type_repr = "shapes.Circle", imports = ("shapes")))]
+            fn is_circle(shape: &Shape) -> bool {}
+            "#,
+        )?;
+        assert_eq!(
+            parse_gen_stub_override_return_type(&item.attrs)?,
+            Some(OverrideTypeAttribute {
+                type_repr: "typing_extensions.TypeIs[shapes.Circle]".into(),
+                imports: IndexSet::from(["shapes".into(), "typing_extensions".into()])
+            })
+        );
+
+        let item: ItemFn = parse_str(
+            r#"
+            fn is_str(#[gen_stub(type_is = "str")] arg: Py<PyAny>) {}
+            "#,
+        )?;
+        if let syn::FnArg::Typed(PatType { attrs, .. }) = &item.sig.inputs[0] {
+            assert!(parse_gen_stub_attrs(attrs, AttributeLocation::Argument, None).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_platform_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(platform = "!win32")]
+            fn getuid() -> u32 {}
+            "#,
+        )?;
+        assert_eq!(
+            parse_gen_stub_platform(&item.attrs, AttributeLocation::Function)?
+                .map(|lit| lit.value()),
+            Some("!win32".to_string())
+        );
+
+        for platform in ["", "!", "linux or darwin"] {
+            let item: ItemFn = parse_str(&format!(
+                r#"
+                #[pyfunction]
+                #[gen_stub(platform = "{platform}")]
+                fn getuid() -> u32 {{}}
+                "#
+            ))?;
+            assert!(parse_gen_stub_platform(&item.attrs, AttributeLocation::Function).is_err());
+        }
+
+        // Function-only keys are rejected on fields instead of ignored
+        let item: ItemStruct = parse_str(
+            r#"
+            struct Config {
+                #[gen_stub(platform = "linux")]
+                path: String,
+            }
+            "#,
+        )?;
+        let fields: Vec<_> = item.fields.into_iter().collect();
+        let err =
+            parse_gen_stub_attrs(&fields[0].attrs, AttributeLocation::Field, None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`platform` is only valid in function or method position"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_py_version_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(py_version = ">=3.12")]
+            fn as_buffer() -> u32 {}
+            "#,
+        )?;
+        assert_eq!(
+            parse_gen_stub_py_version(&item.attrs, AttributeLocation::Function)?
+                .map(|lit| lit.value()),
+            Some(">=3.12".to_string())
+        );
+
+        for py_version in ["3.12", "==3.12", ">=3", ">=3.12.1", "<3.x"] {
+            let item: ItemFn = parse_str(&format!(
+                r#"
+                #[pyfunction]
+                #[gen_stub(py_version = "{py_version}")]
+                fn as_buffer() -> u32 {{}}
+                "#
+            ))?;
+            assert!(parse_gen_stub_py_version(&item.attrs, AttributeLocation::Function).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_releases_gil_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(releases_gil, raises(ValueError))]
+            fn checksum(py: Python<'_>, data: Vec<u8>) -> u32 {}
+            "#,
+        )?;
+        assert!(parse_gen_stub_releases_gil(
+            &item.attrs,
+            AttributeLocation::Function
+        )?);
+
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            fn checksum(data: Vec<u8>) -> u32 {}
+            "#,
+        )?;
+        assert!(!parse_gen_stub_releases_gil(
+            &item.attrs,
+            AttributeLocation::Function
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_named_tuple_attr() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(named_tuple(name = "Stats", fields("min", "max")))]
+            fn stats(values: Vec<f64>) -> (f64, f64) {}
+            "#,
+        )?;
+        let named_tuple =
+            parse_gen_stub_named_tuple(&item.attrs, AttributeLocation::Function)?.unwrap();
+        assert_eq!(named_tuple.name.value(), "Stats");
+        assert_eq!(
+            named_tuple
+                .fields
+                .iter()
+                .map(|field| field.value())
+                .collect::<Vec<_>>(),
+            ["min", "max"]
+        );
+
+        for attr in [
+            r#"named_tuple(fields("min"))"#,
+            r#"named_tuple(name = "Stats")"#,
+            r#"named_tuple(name = "1Stats", fields("min"))"#,
+            r#"named_tuple(name = "Stats", fields("_min"))"#,
+            r#"named_tuple(name = "Stats", fields("min", "min"))"#,
+        ] {
+            let item: ItemFn = parse_str(&format!(
+                r#"
+                #[pyfunction]
+                #[gen_stub({attr})]
+                fn stats(values: Vec<f64>) -> (f64, f64) {{}}
+                "#
+            ))?;
+            assert!(parse_gen_stub_named_tuple(&item.attrs, AttributeLocation::Function).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gen_stub_dict_keys_attr() -> Result<()> {
+        fn arg_attrs(item: &ItemFn) -> &[Attribute] {
+            match &item.sig.inputs[0] {
+                syn::FnArg::Typed(PatType { attrs, .. }) => attrs,
+                _ => unreachable!(),
+            }
+        }
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            fn connect(
+                #[gen_stub(dict_keys(host = "str", r#type = "int | None"), typed_dict = "ServerConfig")]
+                config: Bound<'_, PyDict>,
+            ) {}
+            "#,
+        )?;
+        let dict_keys = parse_gen_stub_dict_keys(arg_attrs(&item))?.unwrap();
+        assert_eq!(
+            dict_keys
+                .keys
+                .iter()
+                .map(|(name, type_repr)| (name.as_str(), type_repr.value()))
+                .collect::<Vec<_>>(),
+            [
+                ("host", "str".to_string()),
+                ("type", "int | None".to_string())
+            ]
+        );
+        assert_eq!(
+            dict_keys.typed_dict.map(|name| name.value()).as_deref(),
+            Some("ServerConfig")
+        );
+
+        for attr in [
+            r#"dict_keys()"#,
+            r#"dict_keys(host = "str", host = "int")"#,
+            r#"dict_keys(host = str)"#,
+            r#"typed_dict = "ServerConfig""#,
+            r#"dict_keys(host = "str"), typed_dict = "Server Config""#,
+        ] {
+            let item: ItemFn = parse_str(&format!(
+                r#"
+                #[pyfunction]
+                fn connect(#[gen_stub({attr})] config: Bound<'_, PyDict>) {{}}
+                "#
+            ))?;
+            assert!(parse_gen_stub_dict_keys(arg_attrs(&item)).is_err());
+        }
+
+        // Only valid on arguments
+        let item: ItemFn = parse_str(
+            r#"
+            #[pyfunction]
+            #[gen_stub(dict_keys(host = "str"))]
+            fn connect(config: Bound<'_, PyDict>) {}
+            "#,
+        )?;
+        assert!(parse_gen_stub_attrs(&item.attrs, AttributeLocation::Function, None).is_err());
+        Ok(())
+    }
 }
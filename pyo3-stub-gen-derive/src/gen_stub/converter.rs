@@ -0,0 +1,78 @@
+//! `#[gen_stub_converter]` on a converter function of `#[pyo3(from_py_with = ...)]`
+
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{ItemFn, Result, Signature};
+
+use super::attr::ConverterAttr;
+
+/// Python type accepted by a converter function, looked up by the arguments converted by it
+pub struct ConverterInfo {
+    item_fn: ItemFn,
+    attr: ConverterAttr,
+}
+
+impl ConverterInfo {
+    pub fn from_parts(item_fn: ItemFn, attr: ConverterAttr) -> Result<Self> {
+        // The registration is emitted next to the function, which is not allowed in `impl` blocks
+        if let Some(receiver) = item_fn.sig.receiver() {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "`#[gen_stub_converter]` must be on a free function, not a method",
+            ));
+        }
+        if refers_self(&item_fn.sig) {
+            return Err(syn::Error::new_spanned(
+                &item_fn.sig,
+                "`#[gen_stub_converter]` must be on a free function, not an associated function",
+            ));
+        }
+        if !item_fn.sig.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &item_fn.sig.generics,
+                "`#[gen_stub_converter]` does not support generic converter functions",
+            ));
+        }
+        Ok(Self { item_fn, attr })
+    }
+}
+
+/// Whether `Self` appears in the signature, i.e. it is an associated function of an `impl` block
+fn refers_self(sig: &Signature) -> bool {
+    fn contains_self(tokens: TokenStream2) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => ident == "Self",
+            TokenTree::Group(group) => contains_self(group.stream()),
+            _ => false,
+        })
+    }
+    contains_self(sig.to_token_stream())
+}
+
+impl ToTokens for ConverterInfo {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let Self { item_fn, attr } = self;
+        let ident = &item_fn.sig.ident;
+        let input = &attr.input;
+        let imports = attr.imports.iter();
+        let submit = quote! {
+            #[automatically_derived]
+            ::pyo3_stub_gen::submit! {
+                ::pyo3_stub_gen::type_info::PyConverterInfo {
+                    converter: || ::pyo3_stub_gen::type_info::converter_id(&#ident),
+                    input: || ::pyo3_stub_gen::TypeInfo {
+                        name: #input.to_string(),
+                        source_module: None,
+                        import: ::std::collections::HashSet::from([#(#imports.into(),)*]),
+                        type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
+                    },
+                }
+            }
+        };
+        tokens.append_all(quote! {
+            #item_fn
+            #submit
+        });
+    }
+}
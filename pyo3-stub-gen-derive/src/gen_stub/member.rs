@@ -1,15 +1,20 @@
 use crate::gen_stub::{
-    attr::{parse_gen_stub_default, parse_gen_stub_override_type, OverrideTypeAttribute},
+    attr::{
+        parse_gen_stub_cached_property, parse_gen_stub_default, parse_gen_stub_default_symbol,
+        parse_gen_stub_override_type, AttributeLocation, OverrideTypeAttribute,
+    },
     extract_documents,
-    util::TypeOrOverride,
+    util::{marker_types_and_fns, stub_type_of, TypeDirection, TypeOrOverride},
 };
 
-use super::{extract_return_type, parse_pyo3_attrs, Attr};
+use super::{extract_return_type, parse_pyo3_attrs, renaming::RenamingRule, Attr};
 
 use crate::gen_stub::arg::ArgInfo;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{Attribute, Error, Expr, Field, FnArg, ImplItemConst, ImplItemFn, Result};
+use syn::{
+    ext::IdentExt, Attribute, Error, Expr, Field, FnArg, Ident, ImplItemConst, ImplItemFn, Result,
+};
 
 /// Determines which `PyStubType` method to use when generating the type annotation.
 #[derive(Debug, Clone, Copy)]
@@ -32,7 +37,10 @@ pub struct MemberInfo {
     name: String,
     r#type: TypeOrOverride,
     default: Option<Expr>,
+    default_symbol: Option<String>,
     deprecated: Option<crate::gen_stub::attr::DeprecatedInfo>,
+    /// `#[gen_stub(cached_property)]`, only allowed on getter methods
+    cached: bool,
     kind: MemberKind,
 }
 
@@ -62,48 +70,73 @@ impl MemberInfo {
             .iter()
             .any(|attr| matches!(attr, Attr::Set)))
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Default value specified by `#[gen_stub(default = ...)]`
+    pub fn default(&self) -> Option<&Expr> {
+        self.default.as_ref()
+    }
+}
+
+/// Python name of the property defined by a `#[getter]` or `#[setter]` method, following PyO3:
+///
+/// 1. `#[pyo3(name = "...")]`
+/// 2. `#[getter(name)]` or `#[setter(name)]`, given as `explicit_name`
+/// 3. Method name without `prefix`, e.g. `get_value` to `value`
+///
+/// `#[pyclass(rename_all = "...")]` applies only to fields, not to these methods.
+/// PyO3 rejects specifying both 1. and 2. ("name may only be specified once").
+fn accessor_name(
+    pyo3_attrs: &[Attr],
+    explicit_name: Option<String>,
+    fn_ident: &Ident,
+    prefix: &str,
+) -> String {
+    let pyo3_name = pyo3_attrs.iter().find_map(|attr| match attr {
+        Attr::Name(name) => Some(name.clone()),
+        _ => None,
+    });
+    pyo3_name.or(explicit_name).unwrap_or_else(|| {
+        let fn_name = fn_ident.unraw().to_string();
+        match fn_name.strip_prefix(prefix) {
+            Some(stripped) => stripped.to_string(),
+            None => fn_name,
+        }
+    })
+}
+
+/// Reject `#[gen_stub(cached_property)]` on members other than `#[getter]` methods
+fn reject_cached_property(attrs: &[Attribute], span: impl ToTokens) -> Result<()> {
+    if parse_gen_stub_cached_property(attrs, AttributeLocation::Field)? {
+        return Err(Error::new_spanned(
+            span,
+            "`cached_property` is only supported on `#[getter]` methods",
+        ));
+    }
+    Ok(())
 }
 
 impl MemberInfo {
     /// Create a new `MemberInfo` from a getter function.
     ///
-    /// The property name is determined by the following precedence:
-    /// 1. `#[pyo3(name = "...")]` - explicit name via pyo3 attribute
-    /// 2. `#[getter(name)]` - explicit name via getter attribute
-    /// 3. Function name with `get_` prefix stripped
-    ///
-    /// Note: pyo3 does not allow specifying both `#[getter(name)]` and
-    /// `#[pyo3(name = "...")]` at the same time (compile error: "name may only be specified once").
+    /// The property name is determined as `accessor_name` describes, stripping the `get_` prefix.
     pub fn new_getter(item: ImplItemFn) -> Result<Self> {
         assert!(Self::is_getter(&item.attrs)?);
         let ImplItemFn { attrs, sig, .. } = &item;
         let default = parse_gen_stub_default(attrs)?;
+        let default_symbol = parse_gen_stub_default_symbol(attrs)?;
         let doc = extract_documents(attrs).join("\n");
         let pyo3_attrs = parse_pyo3_attrs(attrs)?;
-
-        // First, get the name from #[getter] or #[getter(name)]
-        let mut name = None;
-        for attr in &pyo3_attrs {
-            if let Attr::Getter(getter_name) = attr {
-                let fn_name = sig.ident.to_string();
-                let fn_getter_name = match fn_name.strip_prefix("get_") {
-                    Some(s) => s.to_owned(),
-                    None => fn_name,
-                };
-                name = Some(getter_name.clone().unwrap_or(fn_getter_name));
-                break;
-            }
-        }
-
-        // Then, check for #[pyo3(name = "...")] which takes precedence
-        for attr in &pyo3_attrs {
-            if let Attr::Name(pyo3_name) = attr {
-                name = Some(pyo3_name.clone());
-                break;
-            }
-        }
-
-        let name = name.ok_or_else(|| Error::new_spanned(&item, "Not a getter"))?;
+        let getter_name = pyo3_attrs.iter().find_map(|attr| match attr {
+            Attr::Getter(name) => Some(name.clone()),
+            _ => None,
+        });
+        let name = getter_name
+            .map(|getter_name| accessor_name(&pyo3_attrs, getter_name, &sig.ident, "get_"))
+            .ok_or_else(|| Error::new_spanned(&item, "Not a getter"))?;
         let r#type = extract_return_type(&sig.output, attrs)?
             .ok_or_else(|| Error::new_spanned(&item, "Getter must return a type"))?;
         Ok(MemberInfo {
@@ -111,90 +144,72 @@ impl MemberInfo {
             name,
             r#type,
             default,
+            default_symbol,
             deprecated: crate::gen_stub::attr::extract_deprecated(attrs),
+            cached: parse_gen_stub_cached_property(attrs, AttributeLocation::Field)?,
             kind: MemberKind::Getter,
         })
     }
     /// Create a new `MemberInfo` from a setter function.
     ///
-    /// The property name is determined by the following precedence:
-    /// 1. `#[pyo3(name = "...")]` - explicit name via pyo3 attribute
-    /// 2. `#[setter(name)]` - explicit name via setter attribute
-    /// 3. Function name with `set_` prefix stripped
-    ///
-    /// Note: pyo3 does not allow specifying both `#[setter(name)]` and
-    /// `#[pyo3(name = "...")]` at the same time (compile error: "name may only be specified once").
+    /// The property name is determined as `accessor_name` describes, stripping the `set_` prefix.
     pub fn new_setter(item: ImplItemFn) -> Result<Self> {
         assert!(Self::is_setter(&item.attrs)?);
         let ImplItemFn { attrs, sig, .. } = &item;
+        reject_cached_property(attrs, &sig.ident)?;
         let default = parse_gen_stub_default(attrs)?;
+        let default_symbol = parse_gen_stub_default_symbol(attrs)?;
         let doc = extract_documents(attrs).join("\n");
         let pyo3_attrs = parse_pyo3_attrs(attrs)?;
-
-        // First, get the name from #[setter] or #[setter(name)]
-        let mut name = None;
-        let mut r#type = None;
-        for attr in &pyo3_attrs {
-            if let Attr::Setter(setter_name) = attr {
-                let fn_name = sig.ident.to_string();
-                let fn_setter_name = match fn_name.strip_prefix("set_") {
-                    Some(s) => s.to_owned(),
-                    None => fn_name,
-                };
-                name = Some(setter_name.clone().unwrap_or(fn_setter_name));
-                r#type = Some(
-                    sig.inputs
-                        .get(1)
-                        .ok_or(syn::Error::new_spanned(&item, "Setter must input a type"))
-                        .and_then(|arg| {
-                            if let FnArg::Typed(t) = arg {
-                                Ok(match parse_gen_stub_override_type(&t.attrs)? {
-                                    Some(OverrideTypeAttribute { type_repr, imports }) => {
-                                        TypeOrOverride::OverrideType {
-                                            r#type: *t.ty.clone(),
-                                            type_repr,
-                                            imports,
-                                            rust_type_markers: vec![],
-                                        }
-                                    }
-                                    _ => TypeOrOverride::RustType {
-                                        r#type: *t.ty.clone(),
-                                    },
-                                })
-                            } else {
-                                Err(syn::Error::new_spanned(&item, "Setter must input a type"))
+        let setter_name = pyo3_attrs.iter().find_map(|attr| match attr {
+            Attr::Setter(name) => Some(name.clone()),
+            _ => None,
+        });
+        let name = setter_name
+            .map(|setter_name| accessor_name(&pyo3_attrs, setter_name, &sig.ident, "set_"))
+            .ok_or_else(|| Error::new_spanned(&item, "Not a setter"))?;
+        let r#type = sig
+            .inputs
+            .get(1)
+            .ok_or(syn::Error::new_spanned(&item, "Setter must input a type"))
+            .and_then(|arg| {
+                if let FnArg::Typed(t) = arg {
+                    Ok(match parse_gen_stub_override_type(&t.attrs)? {
+                        Some(OverrideTypeAttribute { type_repr, imports }) => {
+                            TypeOrOverride::OverrideType {
+                                r#type: *t.ty.clone(),
+                                type_repr,
+                                imports,
+                                rust_type_markers: vec![],
                             }
-                        })?,
-                );
-                break;
-            }
-        }
-
-        // Then, check for #[pyo3(name = "...")] which takes precedence
-        for attr in &pyo3_attrs {
-            if let Attr::Name(pyo3_name) = attr {
-                name = Some(pyo3_name.clone());
-                break;
-            }
-        }
-
-        let name = name.ok_or_else(|| Error::new_spanned(&item, "Not a setter"))?;
-        let r#type = r#type.ok_or_else(|| Error::new_spanned(&item, "Setter type not found"))?;
+                        }
+                        _ => TypeOrOverride::RustType {
+                            r#type: *t.ty.clone(),
+                        },
+                    })
+                } else {
+                    Err(syn::Error::new_spanned(&item, "Setter must input a type"))
+                }
+            })?;
         Ok(MemberInfo {
             doc,
             name,
             r#type,
             default,
+            default_symbol,
             deprecated: crate::gen_stub::attr::extract_deprecated(attrs),
+            cached: false,
             kind: MemberKind::Setter,
         })
     }
     pub fn new_classattr_fn(item: ImplItemFn) -> Result<Self> {
         assert!(Self::is_classattr(&item.attrs)?);
         let ImplItemFn { attrs, sig, .. } = &item;
+        reject_cached_property(attrs, &sig.ident)?;
         let default = parse_gen_stub_default(attrs)?;
+        let default_symbol = parse_gen_stub_default_symbol(attrs)?;
         let doc = extract_documents(attrs).join("\n");
-        let mut name = sig.ident.to_string();
+        let mut name = sig.ident.unraw().to_string();
         for attr in parse_pyo3_attrs(attrs)? {
             if let Attr::Name(_name) = attr {
                 name = _name;
@@ -205,7 +220,9 @@ impl MemberInfo {
             name,
             r#type: extract_return_type(&sig.output, attrs)?.expect("Getter must return a type"),
             default,
+            default_symbol,
             deprecated: crate::gen_stub::attr::extract_deprecated(attrs),
+            cached: false,
             kind: MemberKind::Getter,
         })
     }
@@ -218,6 +235,8 @@ impl MemberInfo {
             expr,
             ..
         } = item;
+        reject_cached_property(&attrs, &ident)?;
+        let default_symbol = parse_gen_stub_default_symbol(&attrs)?;
         let doc = extract_documents(&attrs).join("\n");
         let mut name = ident.to_string();
         for attr in parse_pyo3_attrs(&attrs)? {
@@ -230,35 +249,72 @@ impl MemberInfo {
             name,
             r#type: TypeOrOverride::RustType { r#type: ty },
             default: Some(expr),
+            default_symbol,
             deprecated: crate::gen_stub::attr::extract_deprecated(&attrs),
+            cached: false,
             kind: MemberKind::Getter,
         })
     }
 }
 
 impl MemberInfo {
-    pub fn from_field(field: Field, kind: MemberKind) -> Result<Self> {
+    /// Create a new `MemberInfo` from a field with `#[pyo3(get)]` or `#[pyo3(set)]`.
+    ///
+    /// The property name is `#[pyo3(name = "...")]` if given,
+    /// otherwise the field name renamed by `rename_all` of `#[pyclass]`.
+    pub fn from_field(
+        field: Field,
+        kind: MemberKind,
+        rename_all: Option<RenamingRule>,
+    ) -> Result<Self> {
         let Field {
             ident, ty, attrs, ..
         } = field;
-        let mut field_name = None;
-        for attr in parse_pyo3_attrs(&attrs)? {
-            if let Attr::Name(name) = attr {
-                field_name = Some(name);
-            }
-        }
+        reject_cached_property(&attrs, &ty)?;
+        let name = Self::field_name(&ident, &attrs, rename_all)?;
         let doc = extract_documents(&attrs).join("\n");
         let default = parse_gen_stub_default(&attrs)?;
+        let default_symbol = parse_gen_stub_default_symbol(&attrs)?;
         let deprecated = crate::gen_stub::attr::extract_deprecated(&attrs);
         Ok(Self {
-            name: field_name.unwrap_or(ident.unwrap().to_string()),
+            name,
             r#type: TypeOrOverride::RustType { r#type: ty },
             doc,
             default,
+            default_symbol,
             deprecated,
+            cached: false,
             kind,
         })
     }
+
+    /// Python name of a field, `#[pyo3(name = "...")]` or the field name renamed by `rename_all`
+    pub fn field_name(
+        ident: &Option<Ident>,
+        attrs: &[Attribute],
+        rename_all: Option<RenamingRule>,
+    ) -> Result<String> {
+        let mut field_name = None;
+        for attr in parse_pyo3_attrs(attrs)? {
+            if let Attr::Name(name) = attr {
+                field_name = Some(name);
+            }
+        }
+        if let Some(field_name) = field_name {
+            return Ok(field_name);
+        }
+        let Some(ident) = ident else {
+            return Err(Error::new(
+                Span::call_site(),
+                "A field of a tuple struct needs `#[pyo3(name = \"...\")]` to be exposed to Python",
+            ));
+        };
+        let name = ident.unraw().to_string();
+        Ok(match rename_all {
+            Some(rule) => rule.apply(&name),
+            None => name,
+        })
+    }
 }
 
 impl ToTokens for MemberInfo {
@@ -268,7 +324,9 @@ impl ToTokens for MemberInfo {
             r#type,
             doc,
             default,
+            default_symbol,
             deprecated,
+            cached,
             kind,
         } = self;
         let use_type_input = kind.use_type_input();
@@ -296,6 +354,9 @@ impl ToTokens for MemberInfo {
                     _fmt
                 })}
             });
+        let default_symbol = default_symbol
+            .as_ref()
+            .map_or(quote! {None}, |symbol| quote! {Some(#symbol)});
         let deprecated_info = deprecated
             .as_ref()
             .map(|deprecated| {
@@ -312,16 +373,26 @@ impl ToTokens for MemberInfo {
         } else {
             quote! { type_output }
         };
+        let default_direction = if use_type_input {
+            TypeDirection::Input
+        } else {
+            TypeDirection::Output
+        };
         match r#type {
-            TypeOrOverride::RustType { r#type: ty } => tokens.append_all(quote! {
-                ::pyo3_stub_gen::type_info::MemberInfo {
-                    name: #name,
-                    r#type: <#ty as ::pyo3_stub_gen::PyStubType>::#type_fn,
-                    doc: #doc,
-                    default: #default,
-                    deprecated: #deprecated_info,
-                }
-            }),
+            TypeOrOverride::RustType { r#type: ty } => {
+                let stub_type = stub_type_of(ty);
+                tokens.append_all(quote! {
+                    ::pyo3_stub_gen::type_info::MemberInfo {
+                        name: #name,
+                        r#type: || #stub_type.#type_fn(),
+                        doc: #doc,
+                        default: #default,
+                        default_symbol: #default_symbol,
+                        deprecated: #deprecated_info,
+                        cached: #cached,
+                    }
+                })
+            }
             TypeOrOverride::OverrideType {
                 type_repr,
                 imports,
@@ -330,6 +401,9 @@ impl ToTokens for MemberInfo {
             } => {
                 let imports = imports.iter().collect::<Vec<&String>>();
 
+                let (marker_types, marker_fns) =
+                    marker_types_and_fns(rust_type_markers, default_direction);
+
                 // Generate code to process RustType markers
                 let (type_name_code, type_refs_code) = if rust_type_markers.is_empty() {
                     (
@@ -337,20 +411,17 @@ impl ToTokens for MemberInfo {
                         quote! { ::std::collections::HashMap::new() },
                     )
                 } else {
-                    // Parse rust_type_markers as syn::Type
-                    let marker_types: Vec<syn::Type> = rust_type_markers
+                    let rust_names = rust_type_markers
                         .iter()
-                        .filter_map(|s| syn::parse_str(s).ok())
-                        .collect();
-
-                    let rust_names = rust_type_markers.iter().collect::<Vec<_>>();
+                        .map(|marker| &marker.rust_type)
+                        .collect::<Vec<_>>();
 
                     (
                         quote! {
                             {
                                 let mut type_name = #type_repr.to_string();
                                 #(
-                                    let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#type_fn();
+                                    let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                     type_name = type_name.replace(#rust_names, &type_info.name);
                                 )*
                                 type_name
@@ -360,7 +431,7 @@ impl ToTokens for MemberInfo {
                             {
                                 let mut type_refs = ::std::collections::HashMap::new();
                                 #(
-                                    let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#type_fn();
+                                    let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                     if let Some(module) = type_info.source_module {
                                         type_refs.insert(
                                             type_info.name.split('[').next().unwrap_or(&type_info.name).split('.').last().unwrap_or(&type_info.name).to_string(),
@@ -381,10 +452,12 @@ impl ToTokens for MemberInfo {
                 tokens.append_all(quote! {
                     ::pyo3_stub_gen::type_info::MemberInfo {
                         name: #name,
-                        r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_name_code, source_module: None, import: ::std::collections::HashSet::from([#(#imports.into(),)*]), type_refs: #type_refs_code },
+                        r#type: || ::pyo3_stub_gen::TypeInfo { name: #type_name_code, source_module: None, import: { let mut import = ::std::collections::HashSet::from([#(#imports.into(),)*]); #(import.extend(<#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns().import);)* import }, type_refs: #type_refs_code, any_fallback: None, },
                         doc: #doc,
                         default: #default,
+                        default_symbol: #default_symbol,
                         deprecated: #deprecated_info,
+                        cached: #cached,
                     }
                 })
             }
@@ -396,6 +469,167 @@ impl From<MemberInfo> for ArgInfo {
     fn from(value: MemberInfo) -> Self {
         let MemberInfo { name, r#type, .. } = value;
 
-        Self { name, r#type }
+        Self {
+            name,
+            r#type,
+            dict_keys: None,
+            from_py_with: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::{parse_quote, ItemStruct};
+
+    #[test]
+    fn test_accessor_names() -> Result<()> {
+        let getters: [(ImplItemFn, &str); 6] = [
+            (
+                parse_quote!(
+                    #[getter]
+                    fn get_value(&self) -> i32 {
+                        0
+                    }
+                ),
+                "value",
+            ),
+            (
+                parse_quote!(
+                    #[getter]
+                    fn value(&self) -> i32 {
+                        0
+                    }
+                ),
+                "value",
+            ),
+            (
+                parse_quote!(
+                    #[getter]
+                    fn r#type(&self) -> i32 {
+                        0
+                    }
+                ),
+                "type",
+            ),
+            (
+                parse_quote!(
+                    #[getter(custom)]
+                    fn get_value(&self) -> i32 {
+                        0
+                    }
+                ),
+                "custom",
+            ),
+            (
+                parse_quote!(
+                    #[getter(r#type)]
+                    fn get_kind(&self) -> i32 {
+                        0
+                    }
+                ),
+                "type",
+            ),
+            (
+                parse_quote!(
+                    #[getter]
+                    #[pyo3(name = "renamed")]
+                    fn get_value(&self) -> i32 {
+                        0
+                    }
+                ),
+                "renamed",
+            ),
+        ];
+        for (item, name) in getters {
+            assert_eq!(MemberInfo::new_getter(item)?.name, name);
+        }
+        let setters: [(ImplItemFn, &str); 4] = [
+            (
+                parse_quote!(
+                    #[setter]
+                    fn set_value(&mut self, v: i32) {}
+                ),
+                "value",
+            ),
+            (
+                parse_quote!(
+                    #[setter]
+                    fn value(&mut self, v: i32) {}
+                ),
+                "value",
+            ),
+            (
+                parse_quote!(
+                    #[setter(custom)]
+                    fn set_value(&mut self, v: i32) {}
+                ),
+                "custom",
+            ),
+            (
+                parse_quote!(
+                    #[setter]
+                    #[pyo3(name = "renamed")]
+                    fn set_value(&mut self, v: i32) {}
+                ),
+                "renamed",
+            ),
+        ];
+        for (item, name) in setters {
+            assert_eq!(MemberInfo::new_setter(item)?.name, name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_field_names() -> Result<()> {
+        let item: ItemStruct = parse_quote! {
+            struct A {
+                max_value: i32,
+                #[pyo3(name = "minimum")]
+                min_value: i32,
+                r#type: i32,
+            }
+        };
+        let names = |rename_all| -> Result<Vec<String>> {
+            item.fields
+                .iter()
+                .map(|field| {
+                    MemberInfo::from_field(field.clone(), MemberKind::Getter, rename_all)
+                        .map(|member| member.name)
+                })
+                .collect()
+        };
+        assert_eq!(names(None)?, ["max_value", "minimum", "type"]);
+        assert_eq!(
+            names(Some(RenamingRule::CamelCase))?,
+            ["maxValue", "minimum", "type"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cached_property() -> Result<()> {
+        let getter = MemberInfo::new_getter(parse_quote!(
+            #[getter]
+            #[gen_stub(cached_property)]
+            fn area(&self) -> f64 {
+                0.0
+            }
+        ))?;
+        assert!(getter.cached);
+        assert!(getter
+            .to_token_stream()
+            .to_string()
+            .contains("cached : true"));
+
+        let setter = MemberInfo::new_setter(parse_quote!(
+            #[setter]
+            #[gen_stub(cached_property)]
+            fn set_area(&mut self, v: f64) {}
+        ));
+        assert!(setter.is_err());
+        Ok(())
     }
 }
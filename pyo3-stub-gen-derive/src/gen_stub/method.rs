@@ -1,15 +1,22 @@
-use crate::gen_stub::util::TypeOrOverride;
+use crate::gen_stub::util::{
+    marker_types_and_fns, quote_option, stub_type_of, TypeDirection, TypeOrOverride,
+};
 
 use super::{
-    arg::parse_args, attr::IgnoreTarget, extract_deprecated, extract_documents,
-    extract_return_type, parameter::Parameters, parse_gen_stub_type_ignore, parse_pyo3_attrs,
-    ArgInfo, Attr, DeprecatedInfo, Signature,
+    arg::parse_args,
+    attr::IgnoreTarget,
+    extract_deprecated, extract_documents, extract_return_type,
+    parameter::{DefaultExpr, Parameters},
+    parse_gen_stub_cached_property, parse_gen_stub_named_tuple, parse_gen_stub_platform,
+    parse_gen_stub_py_version, parse_gen_stub_raises, parse_gen_stub_releases_gil,
+    parse_gen_stub_type_ignore, parse_gen_stub_var_arg_types, parse_pyo3_attrs, ArgInfo, Attr,
+    AttributeLocation, DeprecatedInfo, Signature,
 };
 
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Group, TokenStream as TokenStream2, TokenTree};
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
-    Error, GenericArgument, ImplItemFn, PathArguments, Result, Type, TypePath, TypeReference,
+    Error, Expr, GenericArgument, ImplItemFn, PathArguments, Result, Type, TypePath, TypeReference,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -56,6 +63,40 @@ fn replace_inner(ty: &mut Type, self_: &Type) {
     }
 }
 
+/// Replace `Self` in the tokens of a default value expression, e.g. `Self::LIMIT` to `<A>::LIMIT`,
+/// since it is evaluated in a nested function outside of the `impl` block
+fn replace_self_tokens(tokens: TokenStream2, self_: &Type) -> TokenStream2 {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut replaced = TokenStream2::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "Self" => {
+                if matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ':') {
+                    replaced.append_all(quote! { <#self_> });
+                } else {
+                    self_.to_tokens(&mut replaced);
+                }
+            }
+            TokenTree::Group(group) => {
+                let mut new = Group::new(
+                    group.delimiter(),
+                    replace_self_tokens(group.stream(), self_),
+                );
+                new.set_span(group.span());
+                replaced.append(new);
+            }
+            token => replaced.append(token),
+        }
+    }
+    replaced
+}
+
+fn replace_self_in_expr(expr: &mut Expr, self_: &Type) {
+    if let Ok(replaced) = syn::parse2(replace_self_tokens(expr.to_token_stream(), self_)) {
+        *expr = replaced;
+    }
+}
+
 impl MethodInfo {
     pub fn replace_self(&mut self, self_: &Type) {
         for param in self.parameters.iter_mut() {
@@ -75,6 +116,9 @@ impl MethodInfo {
                 ..
             }) = arg_info;
             replace_inner(ty, self_);
+            if let Some(DefaultExpr::Rust(expr)) = &mut param.default_expr {
+                replace_self_in_expr(expr, self_);
+            }
         }
         if let Some(
             TypeOrOverride::RustType { r#type: ret }
@@ -92,7 +136,45 @@ impl TryFrom<ImplItemFn> for MethodInfo {
         let ImplItemFn { attrs, sig, .. } = item;
         let doc = extract_documents(&attrs).join("\n");
         let deprecated = extract_deprecated(&attrs);
-        let type_ignored = parse_gen_stub_type_ignore(&attrs)?;
+        let type_ignored = parse_gen_stub_type_ignore(&attrs, AttributeLocation::Method)?;
+        let (var_args_type, kwargs_value_type) =
+            parse_gen_stub_var_arg_types(&attrs, AttributeLocation::Method)?;
+        if let Some(raises) = parse_gen_stub_raises(&attrs, AttributeLocation::Method)?.first() {
+            return Err(Error::new_spanned(
+                raises,
+                "`raises(...)` is only supported on `#[gen_stub_pyfunction]`",
+            ));
+        }
+        if let Some(platform) = parse_gen_stub_platform(&attrs, AttributeLocation::Method)? {
+            return Err(Error::new_spanned(
+                platform,
+                "`platform` is only supported on `#[gen_stub_pyfunction]`",
+            ));
+        }
+        if let Some(py_version) = parse_gen_stub_py_version(&attrs, AttributeLocation::Method)? {
+            return Err(Error::new_spanned(
+                py_version,
+                "`py_version` is only supported on `#[gen_stub_pyfunction]`",
+            ));
+        }
+        if parse_gen_stub_releases_gil(&attrs, AttributeLocation::Method)? {
+            return Err(Error::new_spanned(
+                &sig.ident,
+                "`releases_gil` is only supported on `#[gen_stub_pyfunction]`",
+            ));
+        }
+        if let Some(named_tuple) = parse_gen_stub_named_tuple(&attrs, AttributeLocation::Method)? {
+            return Err(Error::new(
+                named_tuple.name.span(),
+                "`named_tuple` is only supported on `#[gen_stub_pyfunction]`",
+            ));
+        }
+        if parse_gen_stub_cached_property(&attrs, AttributeLocation::Method)? {
+            return Err(Error::new_spanned(
+                &sig.ident,
+                "`cached_property` is only supported on `#[getter]` methods",
+            ));
+        }
         let pyo3_attrs = parse_pyo3_attrs(&attrs)?;
         let mut method_name = None;
         let mut text_sig = Signature::overriding_operator(&sig);
@@ -116,11 +198,12 @@ impl TryFrom<ImplItemFn> for MethodInfo {
 
         // Build parameters from args and signature
         let args = parse_args(sig.inputs)?;
-        let parameters = if let Some(text_sig) = text_sig {
+        let mut parameters = if let Some(text_sig) = text_sig {
             Parameters::new_with_sig(&args, &text_sig)?
         } else {
             Parameters::new(&args)
         };
+        parameters.set_var_arg_types(var_args_type, kwargs_value_type);
 
         Ok(MethodInfo {
             name,
@@ -153,8 +236,8 @@ impl ToTokens for MethodInfo {
         let ret_tt = if let Some(ret) = ret {
             match ret {
                 TypeOrOverride::RustType { r#type } => {
-                    let ty = r#type.clone();
-                    quote! { <#ty as pyo3_stub_gen::PyStubType>::type_output }
+                    let stub_type = stub_type_of(r#type);
+                    quote! { || #stub_type.type_output() }
                 }
                 TypeOrOverride::OverrideType {
                     type_repr,
@@ -164,6 +247,9 @@ impl ToTokens for MethodInfo {
                 } => {
                     let imports = imports.iter().collect::<Vec<&String>>();
 
+                    let (marker_types, marker_fns) =
+                        marker_types_and_fns(rust_type_markers, TypeDirection::Output);
+
                     // Generate code to process RustType markers
                     let (type_name_code, type_refs_code) = if rust_type_markers.is_empty() {
                         (
@@ -171,19 +257,17 @@ impl ToTokens for MethodInfo {
                             quote! { ::std::collections::HashMap::new() },
                         )
                     } else {
-                        let marker_types: Vec<syn::Type> = rust_type_markers
+                        let rust_names = rust_type_markers
                             .iter()
-                            .filter_map(|s| syn::parse_str(s).ok())
-                            .collect();
-
-                        let rust_names = rust_type_markers.iter().collect::<Vec<_>>();
+                            .map(|marker| &marker.rust_type)
+                            .collect::<Vec<_>>();
 
                         (
                             quote! {
                                 {
                                     let mut type_name = #type_repr.to_string();
                                     #(
-                                        let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::type_input();
+                                        let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                         type_name = type_name.replace(#rust_names, &type_info.name);
                                     )*
                                     type_name
@@ -193,7 +277,7 @@ impl ToTokens for MethodInfo {
                                 {
                                     let mut type_refs = ::std::collections::HashMap::new();
                                     #(
-                                        let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::type_input();
+                                        let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                         if let Some(module) = type_info.source_module {
                                             type_refs.insert(
                                                 type_info.name.split('[').next().unwrap_or(&type_info.name).split('.').last().unwrap_or(&type_info.name).to_string(),
@@ -212,7 +296,7 @@ impl ToTokens for MethodInfo {
                     };
 
                     quote! {
-                        || ::pyo3_stub_gen::TypeInfo { name: #type_name_code, source_module: None, import: ::std::collections::HashSet::from([#(#imports.into(),)*]), type_refs: #type_refs_code }
+                        || ::pyo3_stub_gen::TypeInfo { name: #type_name_code, source_module: None, import: { let mut import = ::std::collections::HashSet::from([#(#imports.into(),)*]); #(import.extend(<#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns().import);)* import }, type_refs: #type_refs_code, any_fallback: None, }
                     }
                 }
             }
@@ -229,23 +313,7 @@ impl ToTokens for MethodInfo {
             .as_ref()
             .map(|d| quote! { Some(#d) })
             .unwrap_or_else(|| quote! { None });
-        let type_ignored_tt = if let Some(target) = type_ignored {
-            match target {
-                IgnoreTarget::All => {
-                    quote! { Some(::pyo3_stub_gen::type_info::IgnoreTarget::All) }
-                }
-                IgnoreTarget::SpecifiedLits(rules) => {
-                    let rule_strs: Vec<String> = rules.iter().map(|lit| lit.value()).collect();
-                    quote! {
-                        Some(::pyo3_stub_gen::type_info::IgnoreTarget::Specified(
-                            &[#(#rule_strs),*] as &[&str]
-                        ))
-                    }
-                }
-            }
-        } else {
-            quote! { None }
-        };
+        let type_ignored_tt = quote_option(type_ignored);
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::MethodInfo {
                 name: #name,
@@ -10,7 +10,13 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{Expr, Result};
 
-use super::{remove_lifetime, signature::SignatureArg, util::TypeOrOverride, ArgInfo, Signature};
+use super::{
+    attr::OverrideTypeAttribute,
+    remove_lifetime,
+    signature::SignatureArg,
+    util::{marker_types_and_fns, quote_option, stub_type_of, TypeDirection, TypeOrOverride},
+    ArgInfo, Signature,
+};
 
 /// Represents a default value expression from either Rust or Python source
 #[derive(Debug, Clone)]
@@ -52,6 +58,7 @@ impl ToTokens for ParameterWithKind {
                             }
                         };
                         // Use source_module from the type for module qualification at stub generation time
+                        let stub_type = stub_type_of(r#type);
                         quote! {
                             ::pyo3_stub_gen::type_info::ParameterDefault::Expr {
                                 value: {
@@ -62,7 +69,7 @@ impl ToTokens for ParameterWithKind {
                                 },
                                 source_module: Some({
                                     fn _get_module() -> Option<::pyo3_stub_gen::ModuleRef> {
-                                        <#r#type as ::pyo3_stub_gen::PyStubType>::type_output().source_module
+                                        #stub_type.type_output().source_module
                                     }
                                     _get_module
                                 }),
@@ -70,10 +77,12 @@ impl ToTokens for ParameterWithKind {
                         }
                     }
                     TypeOrOverride::OverrideType {
-                        rust_type_markers, ..
+                        r#type,
+                        rust_type_markers,
+                        ..
                     } => {
                         // For OverrideType, convert the default value expression directly to a string
-                        // since r#type may be a dummy type and we can't use it for type annotations
+                        // unless it computes a value in Rust, since r#type may be a dummy type
                         let mut value_str = expr.to_token_stream().to_string();
                         // Convert Rust bool literals to Python bool literals
                         if value_str == "false" {
@@ -103,7 +112,11 @@ impl ToTokens for ParameterWithKind {
                             referenced_type.and_then(|ref_type| {
                                 rust_type_markers.iter().find(|marker| {
                                     // Extract the type name from the marker (e.g., "MyEnum" from "crate::MyEnum")
-                                    let marker_name = marker.rsplit("::").next().unwrap_or(marker);
+                                    let marker_name = marker
+                                        .rust_type
+                                        .rsplit("::")
+                                        .next()
+                                        .unwrap_or(&marker.rust_type);
                                     marker_name == ref_type
                                 })
                             })
@@ -112,7 +125,8 @@ impl ToTokens for ParameterWithKind {
                         // Use source_module from the matching marker if found,
                         // otherwise None to avoid using the wrong module
                         let source_module = if let Some(marker) = matching_marker {
-                            if let Ok(marker_type) = syn::parse_str::<syn::Type>(marker) {
+                            if let Ok(marker_type) = syn::parse_str::<syn::Type>(&marker.rust_type)
+                            {
                                 quote! {
                                     Some({
                                         fn _get_module() -> Option<::pyo3_stub_gen::ModuleRef> {
@@ -128,11 +142,26 @@ impl ToTokens for ParameterWithKind {
                             quote! { None }
                         };
 
+                        // Expressions like `usize::MAX` or `A::default().x` are evaluated at gather time
+                        // only if the Rust type of the argument is known to be converted into a Python object,
+                        // since it may be a dummy type. Otherwise the expression is written as is.
+                        let value = if matching_marker.is_none()
+                            && is_into_py_object(r#type)
+                            && is_rust_computation(expr)
+                        {
+                            quote! {
+                                let v: #r#type = #expr;
+                                ::pyo3_stub_gen::util::fmt_py_obj(v)
+                            }
+                        } else {
+                            quote! { #value_str.to_string() }
+                        };
+
                         quote! {
                             ::pyo3_stub_gen::type_info::ParameterDefault::Expr {
                                 value: {
                                     fn _fmt() -> String {
-                                        #value_str.to_string()
+                                        #value
                                     }
                                     _fmt
                                 },
@@ -162,11 +191,22 @@ impl ToTokens for ParameterWithKind {
 
         let param_info = match &self.arg_info.r#type {
             TypeOrOverride::RustType { r#type } => {
+                let stub_type = stub_type_of(r#type);
+                // The converter of `from_py_with` accepts another Python type if it is annotated by `#[gen_stub_converter]`
+                let type_info = match &self.arg_info.from_py_with {
+                    Some(converter) => quote! {
+                        || ::pyo3_stub_gen::type_info::converter_input(
+                            ::pyo3_stub_gen::type_info::converter_id(&#converter),
+                            || #stub_type.type_input(),
+                        )
+                    },
+                    None => quote! { || #stub_type.type_input() },
+                };
                 quote! {
                     ::pyo3_stub_gen::type_info::ParameterInfo {
                         name: #name,
                         kind: #kind,
-                        type_info: <#r#type as ::pyo3_stub_gen::PyStubType>::type_input,
+                        type_info: #type_info,
                         default: #default_tokens,
                     }
                 }
@@ -179,6 +219,9 @@ impl ToTokens for ParameterWithKind {
             } => {
                 let imports = imports.iter().collect::<Vec<&String>>();
 
+                let (marker_types, marker_fns) =
+                    marker_types_and_fns(rust_type_markers, TypeDirection::Input);
+
                 // Generate code to process RustType markers
                 let (type_name_code, type_refs_code) = if rust_type_markers.is_empty() {
                     (
@@ -186,20 +229,17 @@ impl ToTokens for ParameterWithKind {
                         quote! { ::std::collections::HashMap::new() },
                     )
                 } else {
-                    // Parse rust_type_markers as syn::Type
-                    let marker_types: Vec<syn::Type> = rust_type_markers
+                    let rust_names = rust_type_markers
                         .iter()
-                        .filter_map(|s| syn::parse_str(s).ok())
-                        .collect();
-
-                    let rust_names = rust_type_markers.iter().collect::<Vec<_>>();
+                        .map(|marker| &marker.rust_type)
+                        .collect::<Vec<_>>();
 
                     (
                         quote! {
                             {
                                 let mut type_name = #type_repr.to_string();
                                 #(
-                                    let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::type_input();
+                                    let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                     // Replace Rust type name with Python type name in the expression
                                     type_name = type_name.replace(#rust_names, &type_info.name);
                                 )*
@@ -210,7 +250,7 @@ impl ToTokens for ParameterWithKind {
                             {
                                 let mut type_refs = ::std::collections::HashMap::new();
                                 #(
-                                    let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::type_input();
+                                    let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                     // Add mapping from Python name to module
                                     if let Some(module) = type_info.source_module {
                                         type_refs.insert(
@@ -236,8 +276,9 @@ impl ToTokens for ParameterWithKind {
                         type_info: || ::pyo3_stub_gen::TypeInfo {
                             name: #type_name_code,
                             source_module: None,
-                            import: ::std::collections::HashSet::from([#(#imports.into(),)*]),
+                            import: { let mut import = ::std::collections::HashSet::from([#(#imports.into(),)*]); #(import.extend(<#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns().import);)* import },
                             type_refs: #type_refs_code,
+                            any_fallback: None,
                         },
                         default: #default_tokens,
                     }
@@ -285,6 +326,44 @@ impl ToTokens for ParameterKind {
     }
 }
 
+/// Whether the type is a primitive or a string, which always implements `IntoPyObject`
+fn is_into_py_object(ty: &syn::Type) -> bool {
+    const TYPES: &[&str] = &[
+        "bool", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128",
+        "usize", "f32", "f64", "char", "String",
+    ];
+    match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path
+            .path
+            .get_ident()
+            .is_some_and(|ident| TYPES.iter().any(|ty| ident == ty)),
+        syn::Type::Reference(reference) => {
+            matches!(&*reference.elem, syn::Type::Path(path) if path.path.is_ident("str"))
+        }
+        syn::Type::Group(group) => is_into_py_object(&group.elem),
+        syn::Type::Paren(paren) => is_into_py_object(&paren.elem),
+        _ => false,
+    }
+}
+
+/// Whether the default expression computes a value in Rust,
+/// e.g. an associated constant `usize::MAX`, a call `A::default().x`, or arithmetic on them
+fn is_rust_computation(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(_) | Expr::MethodCall(_) | Expr::Field(_) | Expr::Index(_) | Expr::Macro(_) => {
+            true
+        }
+        Expr::Path(path) => path.qself.is_some() || path.path.segments.len() > 1,
+        Expr::Unary(unary) => is_rust_computation(&unary.expr),
+        Expr::Binary(binary) => {
+            is_rust_computation(&binary.left) || is_rust_computation(&binary.right)
+        }
+        Expr::Paren(paren) => is_rust_computation(&paren.expr),
+        Expr::Group(group) => is_rust_computation(&group.expr),
+        _ => false,
+    }
+}
+
 /// Collection of parameters with their kinds determined
 ///
 /// This newtype wraps `Vec<ParameterWithKind>` and provides constructors that
@@ -351,35 +430,28 @@ impl Parameters {
             })
             .collect();
 
-        // Track parameter kinds based on position and delimiters
-        // By default, parameters are PositionalOrKeyword unless `/` or `*` appear
-        let mut positional_only = false;
+        // Parameters before `/` are positional-only, and those after `*` or `*args` are keyword-only.
+        // Others are PositionalOrKeyword.
+        let positional_only_end = sig.positional_only_end()?;
         let mut after_star = false;
         let mut parameters: Vec<ParameterWithKind> = Vec::new();
 
-        for sig_arg in sig.args() {
+        for (index, sig_arg) in sig.args().enumerate() {
+            let kind = if positional_only_end.is_some_and(|end| index < end) {
+                ParameterKind::PositionalOnly
+            } else if after_star {
+                ParameterKind::KeywordOnly
+            } else {
+                ParameterKind::PositionalOrKeyword
+            };
             match sig_arg {
-                SignatureArg::Slash(_) => {
-                    // `/` delimiter - mark all previous parameters as positional-only
-                    for param in &mut parameters {
-                        param.kind = ParameterKind::PositionalOnly;
-                    }
-                    positional_only = false;
-                }
+                SignatureArg::Slash(_) => {}
                 SignatureArg::Star(_) => {
                     // Bare `*` - parameters after this are keyword-only
-                    positional_only = false;
                     after_star = true;
                 }
                 SignatureArg::Ident(ident) => {
                     let name = ident.to_string();
-                    let kind = if positional_only {
-                        ParameterKind::PositionalOnly
-                    } else if after_star {
-                        ParameterKind::KeywordOnly
-                    } else {
-                        ParameterKind::PositionalOrKeyword
-                    };
 
                     let arg_info = args_map
                         .get(&name)
@@ -396,13 +468,6 @@ impl Parameters {
                 }
                 SignatureArg::Assign(ident, _eq, value) => {
                     let name = ident.to_string();
-                    let kind = if positional_only {
-                        ParameterKind::PositionalOnly
-                    } else if after_star {
-                        ParameterKind::KeywordOnly
-                    } else {
-                        ParameterKind::PositionalOrKeyword
-                    };
 
                     let arg_info = args_map
                         .get(&name)
@@ -418,7 +483,6 @@ impl Parameters {
                     });
                 }
                 SignatureArg::Args(_, ident) => {
-                    positional_only = false;
                     after_star = true; // After *args, everything is keyword-only
                     let name = ident.to_string();
 
@@ -447,7 +511,6 @@ impl Parameters {
                     });
                 }
                 SignatureArg::Keywords(_, _, ident) => {
-                    positional_only = false;
                     let name = ident.to_string();
 
                     let mut arg_info = args_map
@@ -459,7 +522,19 @@ impl Parameters {
 
                     // For VarKeyword, if the type is auto-inferred from Rust (RustType),
                     // replace it with typing.Any. If it's OverrideType, keep the user's specification.
-                    if matches!(arg_info.r#type, TypeOrOverride::RustType { .. }) {
+                    // `typed_dict = "..."` types the keywords by unpacking the `TypedDict`.
+                    if let Some(typed_dict) = arg_info
+                        .dict_keys
+                        .as_ref()
+                        .and_then(|dict_keys| dict_keys.typed_dict.as_ref())
+                    {
+                        arg_info.r#type = TypeOrOverride::OverrideType {
+                            r#type: syn::parse_quote!(()), // Dummy type, won't be used
+                            type_repr: format!("typing_extensions.Unpack[{}]", typed_dict.value()),
+                            imports: ["typing_extensions".to_string()].into_iter().collect(),
+                            rust_type_markers: vec![],
+                        };
+                    } else if matches!(arg_info.r#type, TypeOrOverride::RustType { .. }) {
                         arg_info.r#type = TypeOrOverride::OverrideType {
                             r#type: syn::parse_quote!(()), // Dummy type, won't be used
                             type_repr: "typing.Any".to_string(),
@@ -479,6 +554,71 @@ impl Parameters {
 
         Ok(Self(parameters))
     }
+
+    /// Override the element type of `*args` and the value type of `**kwargs`
+    ///
+    /// Specified by `#[gen_stub(var_args_type = ..., kwargs_value_type = ...)]`,
+    /// these take precedence over both the `typing.Any` fallback and `override_type` on the argument.
+    pub(crate) fn set_var_arg_types(
+        &mut self,
+        var_args: Option<OverrideTypeAttribute>,
+        kwargs_value: Option<OverrideTypeAttribute>,
+    ) {
+        for param in &mut self.0 {
+            let attr = match param.kind {
+                ParameterKind::VarPositional => var_args.as_ref(),
+                ParameterKind::VarKeyword => kwargs_value.as_ref(),
+                _ => None,
+            };
+            if let Some(OverrideTypeAttribute { type_repr, imports }) = attr {
+                param.arg_info.r#type = TypeOrOverride::OverrideType {
+                    r#type: syn::parse_quote!(()), // Dummy type, won't be used
+                    type_repr: type_repr.clone(),
+                    imports: imports.clone(),
+                    rust_type_markers: vec![],
+                };
+            }
+        }
+    }
+
+    /// `DictKeysInfo` of each parameter with `#[gen_stub(dict_keys(...))]`, submitted separately from the function
+    ///
+    /// `struct_id` is the class of a method, and `module` is the module of a function.
+    pub(crate) fn dict_keys_infos(
+        &self,
+        struct_id: Option<&syn::Type>,
+        module: &Option<String>,
+        function: &str,
+    ) -> Vec<TokenStream2> {
+        let struct_id_tt = match struct_id {
+            Some(struct_id) => quote! { Some(std::any::TypeId::of::<#struct_id>) },
+            None => quote! { None },
+        };
+        let module_tt = quote_option(module);
+        self.0
+            .iter()
+            .filter_map(|param| {
+                let dict_keys = param.arg_info.dict_keys.as_ref()?;
+                let parameter = &param.arg_info.name;
+                let typed_dict = quote_option(&dict_keys.typed_dict);
+                let keys = dict_keys.keys.iter().map(|(name, type_repr)| {
+                    quote! {
+                        ::pyo3_stub_gen::type_info::DictKeyInfo { name: #name, type_repr: #type_repr }
+                    }
+                });
+                Some(quote! {
+                    ::pyo3_stub_gen::type_info::DictKeysInfo {
+                        struct_id: #struct_id_tt,
+                        module: #module_tt,
+                        function: #function,
+                        parameter: #parameter,
+                        typed_dict: #typed_dict,
+                        keys: &[ #( #keys ),* ],
+                    }
+                })
+            })
+            .collect()
+    }
 }
 
 impl ToTokens for Parameters {
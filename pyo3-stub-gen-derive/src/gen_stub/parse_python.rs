@@ -3,27 +3,29 @@
 //! This module provides functionality to parse Python stub syntax (type hints)
 //! and convert them into Rust metadata structures for stub generation.
 
+mod ast;
 mod pyfunction;
 mod pymethods;
 mod type_alias;
+mod typing_helper;
 
 pub use pyfunction::{
     parse_gen_function_from_python_input, parse_python_function_stub, parse_python_overload_stubs,
     GenFunctionFromPythonInput,
 };
-pub use pymethods::parse_python_methods_stub;
+pub use pymethods::{parse_gen_methods_from_python_input, GenMethodsFromPythonInput};
 pub use type_alias::{parse_python_type_alias_stub, GenTypeAliasFromPythonInput};
+pub use typing_helper::{parse_python_typing_helpers, GenTypingHelpersFromPythonInput};
 
 use indexmap::IndexSet;
-use rustpython_parser::ast;
-use syn::{Result, Type};
+use syn::{Error, LitStr, Result, Type};
 
 use super::{
     arg::ArgInfo,
     attr::DeprecatedInfo,
     parameter::DefaultExpr,
     parameter::{ParameterKind, ParameterWithKind, Parameters},
-    util::TypeOrOverride,
+    util::{RustTypeMarker, TypeDirection, TypeOrOverride},
 };
 
 /// Remove common leading whitespace from all lines (similar to Python's textwrap.dedent)
@@ -52,13 +54,31 @@ fn dedent(text: &str) -> String {
         .join("\n")
 }
 
+/// Dedent and parse Python stub given as a string literal
+fn parse_stub(input: &LitStr) -> Result<Vec<ast::Stmt>> {
+    let stub_content = input.value();
+
+    // Remove common indentation to allow indented Python code in raw strings
+    let dedented_content = dedent(&stub_content);
+
+    ast::parse_suite(&dedented_content)
+        .map_err(|e| Error::new(input.span(), format!("Failed to parse Python stub: {}", e)))
+}
+
+/// Collect imported module names from import statement
+fn collect_imports(stmt: &ast::Stmt, imports: &mut Vec<String>) {
+    match stmt {
+        ast::Stmt::Import(modules) => imports.extend(modules.iter().cloned()),
+        ast::Stmt::ImportFrom(Some(module)) => imports.push(module.clone()),
+        _ => {}
+    }
+}
+
 /// Extract docstring from function definition
-fn extract_docstring(func_def: &ast::StmtFunctionDef) -> String {
-    if let Some(ast::Stmt::Expr(expr_stmt)) = func_def.body.first() {
-        if let ast::Expr::Constant(constant) = &*expr_stmt.value {
-            if let ast::Constant::Str(s) = &constant.value {
-                return s.to_string();
-            }
+fn extract_docstring(func_def: &ast::FunctionDef) -> String {
+    if let Some(ast::Stmt::Expr(expr)) = func_def.body.first() {
+        if let Some(s) = expr.as_str() {
+            return s.to_string();
         }
     }
     String::new()
@@ -69,23 +89,20 @@ fn extract_deprecated_from_decorators(decorators: &[ast::Expr]) -> Option<Deprec
     for decorator in decorators {
         // Check for @deprecated or @deprecated("message")
         match decorator {
-            ast::Expr::Name(name) if name.id.as_str() == "deprecated" => {
+            ast::Expr::Name(name) if name == "deprecated" => {
                 return Some(DeprecatedInfo {
                     since: None,
                     note: None,
                 });
             }
-            ast::Expr::Call(call) => {
-                if let ast::Expr::Name(name) = &*call.func {
-                    if name.id.as_str() == "deprecated" {
+            ast::Expr::Call { func, args } => {
+                if let ast::Expr::Name(name) = &**func {
+                    if name == "deprecated" {
                         // Try to extract the message from the first argument
-                        let note = call.args.first().and_then(|arg| match arg {
-                            ast::Expr::Constant(constant) => match &constant.value {
-                                ast::Constant::Str(s) => Some(s.to_string()),
-                                _ => None,
-                            },
-                            _ => None,
-                        });
+                        let note = args
+                            .first()
+                            .and_then(|arg| arg.as_str())
+                            .map(|s| s.to_string());
                         return Some(DeprecatedInfo { since: None, note });
                     }
                 }
@@ -100,10 +117,10 @@ fn extract_deprecated_from_decorators(decorators: &[ast::Expr]) -> Option<Deprec
 fn has_overload_decorator(decorator_list: &[ast::Expr]) -> bool {
     decorator_list.iter().any(|decorator| {
         match decorator {
-            ast::Expr::Name(name) => name.id.as_str() == "overload",
-            ast::Expr::Attribute(attr) => {
+            ast::Expr::Name(name) => name == "overload",
+            ast::Expr::Attribute { attr, .. } => {
                 // Handle typing.overload or t.overload
-                attr.attr.as_str() == "overload"
+                attr == "overload"
             }
             _ => false,
         }
@@ -123,15 +140,15 @@ pub(super) fn build_parameters_from_ast(
 
     // Helper to process a single argument with default value
     let process_arg_with_default =
-        |arg: &ast::ArgWithDefault, kind: ParameterKind| -> Result<Option<ParameterWithKind>> {
-            let arg_name = arg.def.arg.to_string();
+        |arg: &ast::Arg, kind: ParameterKind| -> Result<Option<ParameterWithKind>> {
+            let arg_name = arg.name.clone();
 
             // Skip 'self' and 'cls' arguments (they are added automatically in generation)
             if arg_name == "self" || arg_name == "cls" {
                 return Ok(None);
             }
 
-            let type_override = if let Some(annotation) = &arg.def.annotation {
+            let type_override = if let Some(annotation) = &arg.annotation {
                 type_annotation_to_type_override(annotation, imports, dummy_type.clone())?
             } else {
                 // No type annotation - use Any
@@ -146,6 +163,8 @@ pub(super) fn build_parameters_from_ast(
             let arg_info = ArgInfo {
                 name: arg_name,
                 r#type: type_override,
+                dict_keys: None,
+                from_py_with: None,
             };
 
             // Convert default value from Python AST to Python string
@@ -162,9 +181,9 @@ pub(super) fn build_parameters_from_ast(
             }))
         };
 
-    // Helper to process vararg or kwarg (ast::Arg, not ast::ArgWithDefault)
+    // Helper to process vararg or kwarg, which never have default values
     let process_var_arg = |arg: &ast::Arg, kind: ParameterKind| -> Result<ParameterWithKind> {
-        let arg_name = arg.arg.to_string();
+        let arg_name = arg.name.clone();
 
         let type_override = if let Some(annotation) = &arg.annotation {
             type_annotation_to_type_override(annotation, imports, dummy_type.clone())?
@@ -181,6 +200,8 @@ pub(super) fn build_parameters_from_ast(
         let arg_info = ArgInfo {
             name: arg_name,
             r#type: type_override,
+            dict_keys: None,
+            from_py_with: None,
         };
 
         Ok(ParameterWithKind {
@@ -226,7 +247,7 @@ pub(super) fn build_parameters_from_ast(
 
 /// Extract return type from function definition
 fn extract_return_type(
-    returns: &Option<Box<ast::Expr>>,
+    returns: &Option<ast::Expr>,
     imports: &[String],
 ) -> Result<Option<TypeOrOverride>> {
     // Dummy type for TypeOrOverride (not used in ToTokens for OverrideType)
@@ -246,39 +267,54 @@ fn extract_return_type(
 
 /// Recursively collect all RustType markers from a Python AST expression
 ///
-/// Returns a vector of Rust type names found in RustType["TypeName"] markers
-fn collect_rust_type_markers(expr: &ast::Expr) -> Result<Vec<String>> {
+/// Returns the markers found as RustType["TypeName"], RustTypeInput["TypeName"] or RustTypeOutput["TypeName"]
+fn collect_rust_type_markers(expr: &ast::Expr) -> Result<Vec<RustTypeMarker>> {
     let mut markers = Vec::new();
     collect_rust_type_markers_impl(expr, &mut markers)?;
     Ok(markers)
 }
 
-fn collect_rust_type_markers_impl(expr: &ast::Expr, markers: &mut Vec<String>) -> Result<()> {
+fn collect_rust_type_markers_impl(
+    expr: &ast::Expr,
+    markers: &mut Vec<RustTypeMarker>,
+) -> Result<()> {
     // Check if this expression itself is a RustType marker
-    if let Some(type_name) = extract_rust_type_marker(expr)? {
-        markers.push(type_name);
+    if let Some(marker) = extract_rust_type_marker(expr)? {
+        // Validate here to report unparsable markers instead of dropping them in code generation
+        parse_rust_type_marker(&marker)?;
+        // Markers are replaced by the Rust type name, so the name cannot be shared by different directions
+        if let Some(other) = markers.iter().find(|other| {
+            other.rust_type == marker.rust_type && other.direction != marker.direction
+        }) {
+            return Err(Error::new(
+                proc_macro2::Span::call_site(),
+                format!(
+                    "pyo3_stub_gen.{}[\"{}\"] and pyo3_stub_gen.{}[\"{}\"] cannot be used in the same type annotation",
+                    other.form(),
+                    other.rust_type,
+                    marker.form(),
+                    marker.rust_type
+                ),
+            ));
+        }
+        markers.push(marker);
         return Ok(());
     }
 
     // Recursively check children
     match expr {
-        ast::Expr::Subscript(subscript) => {
-            collect_rust_type_markers_impl(&subscript.value, markers)?;
-            collect_rust_type_markers_impl(&subscript.slice, markers)?;
-        }
-        ast::Expr::Tuple(tuple) => {
-            for elt in &tuple.elts {
-                collect_rust_type_markers_impl(elt, markers)?;
-            }
+        ast::Expr::Subscript { value, slice } => {
+            collect_rust_type_markers_impl(value, markers)?;
+            collect_rust_type_markers_impl(slice, markers)?;
         }
-        ast::Expr::List(list) => {
-            for elt in &list.elts {
+        ast::Expr::Tuple(elts) | ast::Expr::List(elts) => {
+            for elt in elts {
                 collect_rust_type_markers_impl(elt, markers)?;
             }
         }
-        ast::Expr::BinOp(binop) => {
-            collect_rust_type_markers_impl(&binop.left, markers)?;
-            collect_rust_type_markers_impl(&binop.right, markers)?;
+        ast::Expr::BitOr { left, right } => {
+            collect_rust_type_markers_impl(left, markers)?;
+            collect_rust_type_markers_impl(right, markers)?;
         }
         _ => {}
     }
@@ -292,14 +328,19 @@ fn type_annotation_to_type_override(
     dummy_type: Type,
 ) -> Result<TypeOrOverride> {
     // Check for pyo3_stub_gen.RustType["TypeName"] marker
-    if let Some(type_name) = extract_rust_type_marker(expr)? {
-        let rust_type: Type = syn::parse_str(&type_name).map_err(|e| {
-            syn::Error::new(
-                proc_macro2::Span::call_site(),
-                format!("Failed to parse Rust type '{}': {}", type_name, e),
-            )
-        })?;
-        return Ok(TypeOrOverride::RustType { r#type: rust_type });
+    if let Some(marker) = extract_rust_type_marker(expr)? {
+        let rust_type = parse_rust_type_marker(&marker)?;
+        if marker.direction.is_none() {
+            return Ok(TypeOrOverride::RustType { r#type: rust_type });
+        }
+        // RustTypeInput and RustTypeOutput are replaced like the ones in compound expressions,
+        // to use the given direction regardless of the position
+        return Ok(TypeOrOverride::OverrideType {
+            r#type: dummy_type,
+            type_repr: marker.rust_type.clone(),
+            imports: imports.iter().map(|s| s.to_string()).collect(),
+            rust_type_markers: vec![marker],
+        });
     }
 
     let type_str = expr_to_type_string(expr)?;
@@ -318,30 +359,122 @@ fn type_annotation_to_type_override(
     })
 }
 
+/// Parse the content of `pyo3_stub_gen.RustType["..."]` as a Rust type
+///
+/// Accepts the full Rust type grammar, e.g. references, tuples, arrays and
+/// fully-qualified paths like `<T as Trait>::Assoc`. On failure, the error names
+/// the innermost fragment which cannot be parsed, e.g. `Py ClassA` in
+/// `HashMap<String, Vec<Py ClassA>>`.
+fn parse_rust_type_marker(marker: &RustTypeMarker) -> Result<Type> {
+    let type_name = marker.rust_type.trim();
+    let form = marker.form();
+    let error = |msg: String| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Invalid Rust type in pyo3_stub_gen.{form}[\"{type_name}\"]: {msg}"),
+        )
+    };
+    if type_name.is_empty() {
+        return Err(error("type is empty".to_string()));
+    }
+    let tokens: proc_macro2::TokenStream = type_name
+        .parse()
+        .map_err(|e| error(format!("cannot tokenize: {e}")))?;
+    let parser = |input: syn::parse::ParseStream| {
+        let ty: Type = input.parse()?;
+        if !input.is_empty() {
+            let rest: proc_macro2::TokenStream = input.parse()?;
+            return Err(input.error(format!("unexpected `{rest}` after the type")));
+        }
+        Ok(ty)
+    };
+    syn::parse::Parser::parse2(parser, tokens).map_err(|e| {
+        let fragment = unparsable_fragment(type_name);
+        if fragment == type_name {
+            error(e.to_string())
+        } else {
+            error(format!("cannot parse `{fragment}`: {e}"))
+        }
+    })
+}
+
+/// Find the innermost fragment of `type_name` which cannot be parsed as a Rust type
+fn unparsable_fragment(type_name: &str) -> &str {
+    for arg in delimited_arguments(type_name) {
+        if syn::parse_str::<syn::GenericArgument>(arg).is_err() {
+            return unparsable_fragment(arg);
+        }
+    }
+    type_name.trim()
+}
+
+/// Split the content of the outermost `<...>`, `(...)` or `[...]` of `type_name` at top-level `,` and `;`
+fn delimited_arguments(type_name: &str) -> Vec<&str> {
+    let type_name = type_name.trim();
+    let Some(open) = type_name.find(['<', '(', '[']) else {
+        return Vec::new();
+    };
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut start = open + 1;
+    let mut prev = '\0';
+    for (i, c) in type_name.char_indices().skip_while(|(i, _)| *i <= open) {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            // `->` in function pointer types is not a closing delimiter
+            '>' if prev == '-' => {}
+            '>' | ')' | ']' if depth == 0 => {
+                args.push(&type_name[start..i]);
+                return args.into_iter().filter(|a| !a.trim().is_empty()).collect();
+            }
+            '>' | ')' | ']' => depth -= 1,
+            ',' | ';' if depth == 0 => {
+                args.push(&type_name[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    // Unclosed delimiter: check the rest as a whole
+    args.push(&type_name[start..]);
+    args.into_iter().filter(|a| !a.trim().is_empty()).collect()
+}
+
 /// Extract type name from pyo3_stub_gen.RustType["TypeName"]
 ///
-/// Returns Some(type_name) if the expression matches the pattern, None otherwise.
+/// `RustTypeInput["TypeName"]` and `RustTypeOutput["TypeName"]` always use
+/// `PyStubType::type_input` and `PyStubType::type_output` respectively,
+/// while `RustType["TypeName"]` follows the position it appears in.
+///
+/// Returns Some(marker) if the expression matches the pattern, None otherwise.
 /// Returns an error if the pattern matches but the type name is not a string literal.
-fn extract_rust_type_marker(expr: &ast::Expr) -> Result<Option<String>> {
+fn extract_rust_type_marker(expr: &ast::Expr) -> Result<Option<RustTypeMarker>> {
     // Match pattern: pyo3_stub_gen.RustType[...]
-    if let ast::Expr::Subscript(subscript) = expr {
-        if let ast::Expr::Attribute(attr) = &*subscript.value {
-            // Check attribute name is "RustType"
-            if attr.attr.as_str() == "RustType" {
-                // Check module name is "pyo3_stub_gen"
-                if let ast::Expr::Name(name) = &*attr.value {
-                    if name.id.as_str() == "pyo3_stub_gen" {
-                        // Extract type name from subscript (must be a string literal)
-                        if let ast::Expr::Constant(constant) = &*subscript.slice {
-                            if let ast::Constant::Str(s) = &constant.value {
-                                return Ok(Some(s.to_string()));
-                            }
-                        }
-                        return Err(syn::Error::new(
-                            proc_macro2::Span::call_site(),
-                            "pyo3_stub_gen.RustType requires a string literal (e.g., RustType[\"MyType\"])",
-                        ));
+    if let ast::Expr::Subscript { value, slice } = expr {
+        if let ast::Expr::Attribute { value, attr } = &**value {
+            let direction = match attr.as_str() {
+                "RustType" => None,
+                "RustTypeInput" => Some(TypeDirection::Input),
+                "RustTypeOutput" => Some(TypeDirection::Output),
+                _ => return Ok(None),
+            };
+            // Check module name is "pyo3_stub_gen"
+            if let ast::Expr::Name(name) = &**value {
+                if name == "pyo3_stub_gen" {
+                    // Extract type name from subscript (must be a string literal)
+                    if let Some(s) = slice.as_str() {
+                        return Ok(Some(RustTypeMarker {
+                            rust_type: s.to_string(),
+                            direction,
+                        }));
                     }
+                    return Err(syn::Error::new(
+                        proc_macro2::Span::call_site(),
+                        format!(
+                            "pyo3_stub_gen.{attr} requires a string literal (e.g., {attr}[\"MyType\"])"
+                        ),
+                    ));
                 }
             }
         }
@@ -388,33 +521,31 @@ fn escape_python_string(s: &str) -> String {
 /// that can be used directly in stub files.
 fn python_ast_to_python_string(expr: &ast::Expr) -> Result<String> {
     match expr {
-        ast::Expr::Constant(constant) => match &constant.value {
+        ast::Expr::Constant(constant) => match constant {
             ast::Constant::None => Ok("None".to_string()),
             ast::Constant::Bool(true) => Ok("True".to_string()),
             ast::Constant::Bool(false) => Ok("False".to_string()),
             ast::Constant::Int(i) => Ok(i.to_string()),
             ast::Constant::Float(f) => Ok(f.to_string()),
             ast::Constant::Str(s) => Ok(escape_python_string(s)),
-            ast::Constant::Bytes(_) => Err(syn::Error::new(
+            ast::Constant::Bytes => Err(syn::Error::new(
                 proc_macro2::Span::call_site(),
                 "Bytes literals are not supported as default values",
             )),
             ast::Constant::Ellipsis => Ok("...".to_string()),
-            _ => Err(syn::Error::new(
+            ast::Constant::Other(other) => Err(syn::Error::new(
                 proc_macro2::Span::call_site(),
-                format!("Unsupported constant type: {:?}", constant.value),
+                format!("Unsupported constant type: {}", other),
             )),
         },
-        ast::Expr::List(list) => {
+        ast::Expr::List(elts) => {
             // Recursively convert list elements
-            let elements: Result<Vec<_>> =
-                list.elts.iter().map(python_ast_to_python_string).collect();
+            let elements: Result<Vec<_>> = elts.iter().map(python_ast_to_python_string).collect();
             Ok(format!("[{}]", elements?.join(", ")))
         }
-        ast::Expr::Tuple(tuple) => {
+        ast::Expr::Tuple(elts) => {
             // Recursively convert tuple elements
-            let elements: Result<Vec<_>> =
-                tuple.elts.iter().map(python_ast_to_python_string).collect();
+            let elements: Result<Vec<_>> = elts.iter().map(python_ast_to_python_string).collect();
             let elements = elements?;
             if elements.len() == 1 {
                 // Single-element tuple needs trailing comma
@@ -423,10 +554,10 @@ fn python_ast_to_python_string(expr: &ast::Expr) -> Result<String> {
                 Ok(format!("({})", elements.join(", ")))
             }
         }
-        ast::Expr::Dict(dict) => {
+        ast::Expr::Dict(items) => {
             // Recursively convert dict key-value pairs
             let mut pairs = Vec::new();
-            for (key_opt, value) in dict.keys.iter().zip(dict.values.iter()) {
+            for (key_opt, value) in items {
                 if let Some(key) = key_opt {
                     let key_str = python_ast_to_python_string(key)?;
                     let value_str = python_ast_to_python_string(value)?;
@@ -438,25 +569,17 @@ fn python_ast_to_python_string(expr: &ast::Expr) -> Result<String> {
             }
             Ok(format!("{{{}}}", pairs.join(", ")))
         }
-        ast::Expr::Name(name) => Ok(name.id.to_string()),
-        ast::Expr::Attribute(_) => {
+        ast::Expr::Name(name) => Ok(name.clone()),
+        ast::Expr::Attribute { .. } => {
             // Handle qualified names like `MyEnum.VARIANT`
             expr_to_type_string(expr)
         }
-        ast::Expr::UnaryOp(unary) => {
+        ast::Expr::Neg(operand) => {
             // Handle negative numbers
-            if matches!(unary.op, ast::UnaryOp::USub) {
-                if let ast::Expr::Constant(constant) = &*unary.operand {
-                    match &constant.value {
-                        ast::Constant::Int(i) => Ok(format!("-{}", i)),
-                        ast::Constant::Float(f) => Ok(format!("-{}", f)),
-                        _ => Ok("...".to_string()),
-                    }
-                } else {
-                    Ok("...".to_string())
-                }
-            } else {
-                Ok("...".to_string())
+            match &**operand {
+                ast::Expr::Constant(ast::Constant::Int(i)) => Ok(format!("-{}", i)),
+                ast::Expr::Constant(ast::Constant::Float(f)) => Ok(format!("-{}", f)),
+                _ => Ok("...".to_string()),
             }
         }
         _ => {
@@ -475,35 +598,29 @@ fn expr_to_type_string(expr: &ast::Expr) -> Result<String> {
 fn expr_to_type_string_inner(expr: &ast::Expr, in_subscript: bool) -> Result<String> {
     // Check for pyo3_stub_gen.RustType["TypeName"] marker first
     // If found, return just the type name (the marker will be handled elsewhere)
-    if let Some(type_name) = extract_rust_type_marker(expr)? {
-        return Ok(type_name);
+    if let Some(marker) = extract_rust_type_marker(expr)? {
+        return Ok(marker.rust_type);
     }
 
     Ok(match expr {
-        ast::Expr::Name(name) => name.id.to_string(),
-        ast::Expr::Attribute(attr) => {
-            format!(
-                "{}.{}",
-                expr_to_type_string_inner(&attr.value, false)?,
-                attr.attr
-            )
+        ast::Expr::Name(name) => name.clone(),
+        ast::Expr::Attribute { value, attr } => {
+            format!("{}.{}", expr_to_type_string_inner(value, false)?, attr)
         }
-        ast::Expr::Subscript(subscript) => {
-            let base = expr_to_type_string_inner(&subscript.value, false)?;
-            let slice = expr_to_type_string_inner(&subscript.slice, true)?;
+        ast::Expr::Subscript { value, slice } => {
+            let base = expr_to_type_string_inner(value, false)?;
+            let slice = expr_to_type_string_inner(slice, true)?;
             format!("{}[{}]", base, slice)
         }
-        ast::Expr::List(list) => {
-            let elements: Result<Vec<String>> = list
-                .elts
+        ast::Expr::List(elts) => {
+            let elements: Result<Vec<String>> = elts
                 .iter()
                 .map(|e| expr_to_type_string_inner(e, false))
                 .collect();
             format!("[{}]", elements?.join(", "))
         }
-        ast::Expr::Tuple(tuple) => {
-            let elements: Result<Vec<String>> = tuple
-                .elts
+        ast::Expr::Tuple(elts) => {
+            let elements: Result<Vec<String>> = elts
                 .iter()
                 .map(|e| expr_to_type_string_inner(e, in_subscript))
                 .collect();
@@ -515,7 +632,7 @@ fn expr_to_type_string_inner(expr: &ast::Expr, in_subscript: bool) -> Result<Str
                 format!("({})", elements.join(", "))
             }
         }
-        ast::Expr::Constant(constant) => match &constant.value {
+        ast::Expr::Constant(constant) => match constant {
             ast::Constant::Int(i) => i.to_string(),
             ast::Constant::Str(s) => format!("\"{}\"", s),
             ast::Constant::Bool(b) => if *b { "True" } else { "False" }.to_string(),
@@ -523,15 +640,11 @@ fn expr_to_type_string_inner(expr: &ast::Expr, in_subscript: bool) -> Result<Str
             ast::Constant::Ellipsis => "...".to_string(),
             _ => "Any".to_string(),
         },
-        ast::Expr::BinOp(binop) => {
+        ast::Expr::BitOr { left, right } => {
             // Handle union types with | operator
-            if matches!(binop.op, ast::Operator::BitOr) {
-                let left = expr_to_type_string_inner(&binop.left, false)?;
-                let right = expr_to_type_string_inner(&binop.right, false)?;
-                format!("{} | {}", left, right)
-            } else {
-                "Any".to_string()
-            }
+            let left = expr_to_type_string_inner(left, false)?;
+            let right = expr_to_type_string_inner(right, false)?;
+            format!("{} | {}", left, right)
         }
         _ => "Any".to_string(),
     })
@@ -540,18 +653,18 @@ fn expr_to_type_string_inner(expr: &ast::Expr, in_subscript: bool) -> Result<Str
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rustpython_parser as parser;
 
     /// Helper to parse a Python expression and convert it to Python string
     fn parse_and_convert(python_expr: &str) -> Result<String> {
-        let source = format!("x = {}", python_expr);
-        let parsed = parser::parse(&source, parser::Mode::Module, "<test>")
-            .map_err(|e| syn::Error::new(proc_macro2::Span::call_site(), format!("{}", e)))?;
-
-        if let parser::ast::Mod::Module(module) = parsed {
-            if let Some(parser::ast::Stmt::Assign(assign)) = module.body.first() {
-                return python_ast_to_python_string(&assign.value);
-            }
+        let source = format!("x: X = {}", python_expr);
+        let parsed = ast::parse_suite(&source)
+            .map_err(|e| syn::Error::new(proc_macro2::Span::call_site(), e))?;
+
+        if let Some(ast::Stmt::AnnAssign {
+            value: Some(value), ..
+        }) = parsed.first()
+        {
+            return python_ast_to_python_string(value);
         }
         Err(syn::Error::new(
             proc_macro2::Span::call_site(),
@@ -704,4 +817,114 @@ mod tests {
         assert_eq!(result, "-3.14");
         Ok(())
     }
+
+    fn marker(rust_type: &str) -> RustTypeMarker {
+        RustTypeMarker {
+            rust_type: rust_type.to_string(),
+            direction: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_rust_type_marker() -> Result<()> {
+        for ty in [
+            "HashMap<String, Vec<PyClassA>>",
+            "crate::module::MyType",
+            "&str",
+            "(i32, String)",
+            "[u8; 4]",
+            "<T as Trait>::Assoc",
+            "Option<fn(i32) -> Vec<u8>>",
+            " Vec<String> ",
+        ] {
+            parse_rust_type_marker(&marker(ty))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rust_type_marker_error() {
+        let err = parse_rust_type_marker(&marker("HashMap<String, Vec<Py ClassA>>")).unwrap_err();
+        assert!(
+            err.to_string().contains("cannot parse `Py ClassA`"),
+            "{err}"
+        );
+
+        let err = parse_rust_type_marker(&marker("Vec<String> extra")).unwrap_err();
+        assert!(err.to_string().contains("unexpected `extra`"), "{err}");
+
+        let err = parse_rust_type_marker(&marker("  ")).unwrap_err();
+        assert!(err.to_string().contains("type is empty"), "{err}");
+
+        let err = parse_rust_type_marker(&RustTypeMarker {
+            rust_type: "Vec<>>".to_string(),
+            direction: Some(TypeDirection::Output),
+        })
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid Rust type in pyo3_stub_gen.RustTypeOutput[\"Vec<>>\"]"),
+            "{err}"
+        );
+    }
+
+    /// Parse the annotation of `x` in `def f(x: ...)`
+    fn annotation(python: &str) -> Result<TypeOrOverride> {
+        let source = format!("def f(x: {python}): ...");
+        let parsed = ast::parse_suite(&source)
+            .map_err(|e| syn::Error::new(proc_macro2::Span::call_site(), e))?;
+        let Some(ast::Stmt::FunctionDef(func_def)) = parsed.first() else {
+            panic!("Failed to parse function");
+        };
+        let annotation = func_def.args.args[0].annotation.as_ref().unwrap();
+        type_annotation_to_type_override(annotation, &[], syn::parse_str("()")?)
+    }
+
+    #[test]
+    fn test_rust_type_marker_directions() -> Result<()> {
+        let TypeOrOverride::OverrideType {
+            type_repr,
+            rust_type_markers,
+            ..
+        } = annotation(r#"pyo3_stub_gen.RustTypeOutput["Vec<PyClassA>"]"#)?
+        else {
+            panic!("RustTypeOutput should be replaced in code generation");
+        };
+        assert_eq!(type_repr, "Vec<PyClassA>");
+        assert_eq!(
+            rust_type_markers,
+            [RustTypeMarker {
+                rust_type: "Vec<PyClassA>".to_string(),
+                direction: Some(TypeDirection::Output),
+            }]
+        );
+
+        let TypeOrOverride::OverrideType {
+            rust_type_markers, ..
+        } = annotation(
+            r#"typing.Optional[pyo3_stub_gen.RustTypeInput["A"] | pyo3_stub_gen.RustType["B"]]"#,
+        )?
+        else {
+            panic!("Compound expressions should be overridden");
+        };
+        let directions: Vec<_> = rust_type_markers
+            .iter()
+            .map(|marker| marker.direction)
+            .collect();
+        assert_eq!(directions, [Some(TypeDirection::Input), None]);
+
+        assert!(matches!(
+            annotation(r#"pyo3_stub_gen.RustType["A"]"#)?,
+            TypeOrOverride::RustType { .. }
+        ));
+
+        let err =
+            annotation(r#"pyo3_stub_gen.RustTypeInput["A"] | pyo3_stub_gen.RustTypeOutput["A"]"#)
+                .err()
+                .unwrap();
+        assert!(err
+            .to_string()
+            .contains("cannot be used in the same type annotation"));
+        Ok(())
+    }
 }
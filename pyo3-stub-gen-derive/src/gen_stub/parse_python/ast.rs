@@ -0,0 +1,237 @@
+//! Parser-independent AST of Python stub syntax
+//!
+//! Only the subset of Python used in stubs is modeled here: imports, function and class
+//! definitions, type aliases and the expressions appearing in annotations and default values.
+//! Everything else is kept as [`Stmt::Other`] or [`Expr::Other`].
+//!
+//! The Python source is parsed by the backend parser, currently
+//! [rustpython-parser](https://crates.io/crates/rustpython-parser), and converted into this AST.
+
+mod rustpython;
+
+use rustpython as backend;
+
+/// Parse a Python module into statements
+///
+/// The error is the message of the backend parser.
+pub fn parse_suite(source: &str) -> Result<Vec<Stmt>, String> {
+    backend::parse_suite(source)
+}
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    /// `import a.b, c`, with the imported module names
+    Import(Vec<String>),
+    /// `from a.b import c`, with the module name. It is `None` for relative imports like `from . import c`.
+    ImportFrom(Option<String>),
+    FunctionDef(FunctionDef),
+    ClassDef(ClassDef),
+    /// `target: annotation = value`
+    AnnAssign {
+        target: Expr,
+        annotation: Expr,
+        value: Option<Expr>,
+    },
+    /// Python 3.12+ `type name = value`
+    TypeAlias {
+        name: Expr,
+        value: Expr,
+    },
+    /// Expression statement, e.g. docstring
+    Expr(Expr),
+    Other,
+}
+
+/// `def` or `async def`
+#[derive(Debug, Clone)]
+pub struct FunctionDef {
+    pub name: String,
+    pub is_async: bool,
+    pub decorator_list: Vec<Expr>,
+    pub args: Arguments,
+    pub returns: Option<Expr>,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassDef {
+    pub name: String,
+    pub body: Vec<Stmt>,
+}
+
+/// Parameters of a function definition
+#[derive(Debug, Clone, Default)]
+pub struct Arguments {
+    /// Parameters before `/`
+    pub posonlyargs: Vec<Arg>,
+    pub args: Vec<Arg>,
+    /// `*args`
+    pub vararg: Option<Arg>,
+    /// Parameters after `*` or `*args`
+    pub kwonlyargs: Vec<Arg>,
+    /// `**kwargs`
+    pub kwarg: Option<Arg>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Arg {
+    pub name: String,
+    pub annotation: Option<Expr>,
+    /// Always `None` for `*args` and `**kwargs`
+    pub default: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Name(String),
+    /// `value.attr`
+    Attribute {
+        value: Box<Expr>,
+        attr: String,
+    },
+    /// `value[slice]`
+    Subscript {
+        value: Box<Expr>,
+        slice: Box<Expr>,
+    },
+    Tuple(Vec<Expr>),
+    List(Vec<Expr>),
+    /// Key is `None` for `**mapping` entries
+    Dict(Vec<(Option<Expr>, Expr)>),
+    /// `left | right`
+    BitOr {
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    /// `-operand`
+    Neg(Box<Expr>),
+    /// `func(args...)`, keyword arguments are dropped
+    Call {
+        func: Box<Expr>,
+        args: Vec<Expr>,
+    },
+    Constant(Constant),
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub enum Constant {
+    None,
+    Bool(bool),
+    Str(String),
+    Bytes,
+    /// Integer literal, kept as string since it may exceed any Rust integer type
+    Int(String),
+    Float(f64),
+    Ellipsis,
+    /// Other literals like complex numbers, described for error messages
+    Other(String),
+}
+
+impl Expr {
+    /// String literal value, if this is a string literal
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Expr::Constant(Constant::Str(s)) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Conversion of the backend AST, which every backend has to pass
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_imports() {
+        let stmts = parse_suite("import a.b, c\nfrom x.y import z\nfrom . import w\n").unwrap();
+        assert!(matches!(&stmts[0], Stmt::Import(names) if names == &["a.b", "c"]));
+        assert!(matches!(&stmts[1], Stmt::ImportFrom(Some(module)) if module == "x.y"));
+        assert!(matches!(&stmts[2], Stmt::ImportFrom(None)));
+    }
+
+    #[test]
+    fn test_function_def() {
+        let stmts = parse_suite(
+            r#"
+@overload
+async def f(a: int, /, b: str = "x", *args: int, c: float = -1.5, **kwargs: bytes) -> None:
+    """Doc"""
+"#,
+        )
+        .unwrap();
+        let Stmt::FunctionDef(def) = &stmts[0] else {
+            panic!("{:?}", stmts[0]);
+        };
+        assert_eq!(def.name, "f");
+        assert!(def.is_async);
+        assert!(matches!(&def.decorator_list[..], [Expr::Name(name)] if name == "overload"));
+        assert_eq!(def.args.posonlyargs[0].name, "a");
+        assert_eq!(def.args.args[0].name, "b");
+        assert_eq!(
+            def.args.args[0].default.as_ref().and_then(Expr::as_str),
+            Some("x")
+        );
+        assert_eq!(def.args.vararg.as_ref().unwrap().name, "args");
+        assert!(matches!(
+            &def.args.kwonlyargs[0].default,
+            Some(Expr::Neg(operand)) if matches!(**operand, Expr::Constant(Constant::Float(f)) if f == 1.5)
+        ));
+        assert_eq!(def.args.kwarg.as_ref().unwrap().name, "kwargs");
+        assert!(matches!(def.returns, Some(Expr::Constant(Constant::None))));
+        assert!(matches!(&def.body[..], [Stmt::Expr(doc)] if doc.as_str() == Some("Doc")));
+    }
+
+    #[test]
+    fn test_class_and_aliases() {
+        let stmts = parse_suite(
+            "class A:\n    x: int\n    def f(self) -> None: ...\nB: TypeAlias = int | None\ntype C = list[int]\n",
+        )
+        .unwrap();
+        let Stmt::ClassDef(class) = &stmts[0] else {
+            panic!("{:?}", stmts[0]);
+        };
+        assert_eq!(class.name, "A");
+        assert!(matches!(
+            class.body[..],
+            [Stmt::AnnAssign { .. }, Stmt::FunctionDef(_)]
+        ));
+        assert!(matches!(
+            &stmts[1],
+            Stmt::AnnAssign {
+                value: Some(Expr::BitOr { .. }),
+                ..
+            }
+        ));
+        assert!(matches!(
+            &stmts[2],
+            Stmt::TypeAlias { name: Expr::Name(name), value: Expr::Subscript { .. } } if name == "C"
+        ));
+    }
+
+    #[test]
+    fn test_expressions() {
+        let stmts = parse_suite(
+            "x = 1\ntyping.Callable[[int], dict[str, tuple[int, ...]]]\nf(1, k=2)\n{'a': 1, **b}\n",
+        )
+        .unwrap();
+        assert!(matches!(stmts[0], Stmt::Other));
+        let Stmt::Expr(Expr::Subscript { value, slice }) = &stmts[1] else {
+            panic!("{:?}", stmts[1]);
+        };
+        assert!(matches!(&**value, Expr::Attribute { attr, .. } if attr == "Callable"));
+        assert!(matches!(&**slice, Expr::Tuple(elts) if matches!(elts[0], Expr::List(_))));
+        // Keyword arguments are dropped
+        assert!(matches!(&stmts[2], Stmt::Expr(Expr::Call { args, .. }) if args.len() == 1));
+        assert!(matches!(
+            &stmts[3],
+            Stmt::Expr(Expr::Dict(entries)) if entries.len() == 2 && entries[1].0.is_none()
+        ));
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        assert!(parse_suite("def f(:\n").is_err());
+    }
+}
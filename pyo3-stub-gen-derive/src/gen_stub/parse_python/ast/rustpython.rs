@@ -0,0 +1,125 @@
+//! Backend using rustpython-parser
+
+use rustpython_parser::{ast, Parse};
+
+use super::{Arg, Arguments, ClassDef, Constant, Expr, FunctionDef, Stmt};
+
+pub fn parse_suite(source: &str) -> Result<Vec<Stmt>, String> {
+    let suite = ast::Suite::parse(source, "<stub>").map_err(|e| e.to_string())?;
+    Ok(suite.into_iter().map(stmt).collect())
+}
+
+fn stmt(s: ast::Stmt) -> Stmt {
+    match s {
+        ast::Stmt::Import(import) => Stmt::Import(
+            import
+                .names
+                .into_iter()
+                .map(|alias| alias.name.to_string())
+                .collect(),
+        ),
+        ast::Stmt::ImportFrom(import_from) => {
+            Stmt::ImportFrom(import_from.module.map(|module| module.to_string()))
+        }
+        ast::Stmt::FunctionDef(def) => Stmt::FunctionDef(FunctionDef {
+            name: def.name.to_string(),
+            is_async: false,
+            decorator_list: def.decorator_list.into_iter().map(expr).collect(),
+            args: arguments(*def.args),
+            returns: def.returns.map(|e| expr(*e)),
+            body: def.body.into_iter().map(stmt).collect(),
+        }),
+        ast::Stmt::AsyncFunctionDef(def) => Stmt::FunctionDef(FunctionDef {
+            name: def.name.to_string(),
+            is_async: true,
+            decorator_list: def.decorator_list.into_iter().map(expr).collect(),
+            args: arguments(*def.args),
+            returns: def.returns.map(|e| expr(*e)),
+            body: def.body.into_iter().map(stmt).collect(),
+        }),
+        ast::Stmt::ClassDef(def) => Stmt::ClassDef(ClassDef {
+            name: def.name.to_string(),
+            body: def.body.into_iter().map(stmt).collect(),
+        }),
+        ast::Stmt::AnnAssign(assign) => Stmt::AnnAssign {
+            target: expr(*assign.target),
+            annotation: expr(*assign.annotation),
+            value: assign.value.map(|e| expr(*e)),
+        },
+        ast::Stmt::TypeAlias(alias) => Stmt::TypeAlias {
+            name: expr(*alias.name),
+            value: expr(*alias.value),
+        },
+        ast::Stmt::Expr(e) => Stmt::Expr(expr(*e.value)),
+        _ => Stmt::Other,
+    }
+}
+
+fn arguments(args: ast::Arguments) -> Arguments {
+    let with_default = |arg: ast::ArgWithDefault| Arg {
+        name: arg.def.arg.to_string(),
+        annotation: arg.def.annotation.map(|e| expr(*e)),
+        default: arg.default.map(|e| expr(*e)),
+    };
+    let var_arg = |arg: Box<ast::Arg>| Arg {
+        name: arg.arg.to_string(),
+        annotation: arg.annotation.map(|e| expr(*e)),
+        default: None,
+    };
+    Arguments {
+        posonlyargs: args.posonlyargs.into_iter().map(with_default).collect(),
+        args: args.args.into_iter().map(with_default).collect(),
+        vararg: args.vararg.map(var_arg),
+        kwonlyargs: args.kwonlyargs.into_iter().map(with_default).collect(),
+        kwarg: args.kwarg.map(var_arg),
+    }
+}
+
+fn boxed(e: ast::Expr) -> Box<Expr> {
+    Box::new(expr(e))
+}
+
+fn expr(e: ast::Expr) -> Expr {
+    match e {
+        ast::Expr::Name(name) => Expr::Name(name.id.to_string()),
+        ast::Expr::Attribute(attr) => Expr::Attribute {
+            value: boxed(*attr.value),
+            attr: attr.attr.to_string(),
+        },
+        ast::Expr::Subscript(subscript) => Expr::Subscript {
+            value: boxed(*subscript.value),
+            slice: boxed(*subscript.slice),
+        },
+        ast::Expr::Tuple(tuple) => Expr::Tuple(tuple.elts.into_iter().map(expr).collect()),
+        ast::Expr::List(list) => Expr::List(list.elts.into_iter().map(expr).collect()),
+        ast::Expr::Dict(dict) => Expr::Dict(
+            dict.keys
+                .into_iter()
+                .zip(dict.values)
+                .map(|(key, value)| (key.map(expr), expr(value)))
+                .collect(),
+        ),
+        ast::Expr::BinOp(binop) if matches!(binop.op, ast::Operator::BitOr) => Expr::BitOr {
+            left: boxed(*binop.left),
+            right: boxed(*binop.right),
+        },
+        ast::Expr::UnaryOp(unary) if matches!(unary.op, ast::UnaryOp::USub) => {
+            Expr::Neg(boxed(*unary.operand))
+        }
+        ast::Expr::Call(call) => Expr::Call {
+            func: boxed(*call.func),
+            args: call.args.into_iter().map(expr).collect(),
+        },
+        ast::Expr::Constant(constant) => Expr::Constant(match constant.value {
+            ast::Constant::None => Constant::None,
+            ast::Constant::Bool(b) => Constant::Bool(b),
+            ast::Constant::Str(s) => Constant::Str(s),
+            ast::Constant::Bytes(_) => Constant::Bytes,
+            ast::Constant::Int(i) => Constant::Int(i.to_string()),
+            ast::Constant::Float(f) => Constant::Float(f),
+            ast::Constant::Ellipsis => Constant::Ellipsis,
+            other => Constant::Other(format!("{:?}", other)),
+        }),
+        _ => Expr::Other,
+    }
+}
@@ -1,11 +1,10 @@
 //! Parse Python function stub syntax and generate PyFunctionInfo
 
-use rustpython_parser::{ast, Parse};
 use syn::{parse::Parse as SynParse, parse::ParseStream, Error, LitStr, Result};
 
 use super::{
-    build_parameters_from_ast, dedent, extract_deprecated_from_decorators, extract_docstring,
-    extract_return_type, has_overload_decorator,
+    ast, build_parameters_from_ast, collect_imports, extract_deprecated_from_decorators,
+    extract_docstring, extract_return_type, has_overload_decorator, parse_stub,
 };
 use crate::gen_stub::pyfunction::PyFunctionInfo;
 
@@ -51,7 +50,7 @@ impl SynParse for GenFunctionFromPythonInput {
 
 /// Intermediate representation for Python function stub
 pub struct PythonFunctionStub {
-    pub func_def: ast::StmtFunctionDef,
+    pub func_def: ast::FunctionDef,
     pub imports: Vec<String>,
     pub is_async: bool,
     pub is_overload: bool,
@@ -61,7 +60,7 @@ impl TryFrom<PythonFunctionStub> for PyFunctionInfo {
     type Error = syn::Error;
 
     fn try_from(stub: PythonFunctionStub) -> Result<Self> {
-        let func_name = stub.func_def.name.to_string();
+        let func_name = stub.func_def.name.clone();
 
         // Extract docstring
         let doc = extract_docstring(&stub.func_def);
@@ -76,19 +75,25 @@ impl TryFrom<PythonFunctionStub> for PyFunctionInfo {
         let deprecated = extract_deprecated_from_decorators(&stub.func_def.decorator_list);
 
         // Note: type_ignored (# type: ignore comments) cannot be extracted from Python AST
-        // as rustpython-parser doesn't preserve comments
+        // as comments are not preserved
 
         // Construct PyFunctionInfo
         Ok(PyFunctionInfo {
             name: func_name,
             parameters, // Use pre-built Parameters from Python AST
             r#return: return_type,
+            error: None,
+            raises: Vec::new(),
             doc,
             module: None,
             is_async: stub.is_async,
             deprecated,
             type_ignored: None,
             is_overload: stub.is_overload,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
+            named_tuple: None,
             index: 0, // Will be set by caller when generating multiple overloads
         })
     }
@@ -96,69 +101,29 @@ impl TryFrom<PythonFunctionStub> for PyFunctionInfo {
 
 /// Parse Python stub string and return PyFunctionInfo
 pub fn parse_python_function_stub(input: LitStr) -> Result<PyFunctionInfo> {
-    let stub_content = input.value();
-
-    // Remove common indentation to allow indented Python code in raw strings
-    let dedented_content = dedent(&stub_content);
-
-    // Parse Python code using rustpython-parser
-    let parsed = ast::Suite::parse(&dedented_content, "<stub>")
-        .map_err(|e| Error::new(input.span(), format!("Failed to parse Python stub: {}", e)))?;
+    let parsed = parse_stub(&input)?;
 
     // Extract imports and function definitions
     let mut imports = Vec::new();
-    let mut function: Option<(ast::StmtFunctionDef, bool)> = None;
+    let mut function: Option<ast::FunctionDef> = None;
 
     for stmt in parsed {
-        match stmt {
-            ast::Stmt::Import(import_stmt) => {
-                for alias in &import_stmt.names {
-                    imports.push(alias.name.to_string());
-                }
-            }
-            ast::Stmt::ImportFrom(import_from_stmt) => {
-                if let Some(module) = &import_from_stmt.module {
-                    imports.push(module.to_string());
-                }
-            }
-            ast::Stmt::FunctionDef(func_def) => {
-                if function.is_some() {
-                    return Err(Error::new(
-                        input.span(),
-                        "Multiple function definitions found. Only one function is allowed per gen_function_from_python! call",
-                    ));
-                }
-                function = Some((func_def, false));
-            }
-            ast::Stmt::AsyncFunctionDef(func_def) => {
-                if function.is_some() {
-                    return Err(Error::new(
-                        input.span(),
-                        "Multiple function definitions found. Only one function is allowed per gen_function_from_python! call",
-                    ));
-                }
-                // Convert AsyncFunctionDef to FunctionDef for uniform processing
-                let sync_func = ast::StmtFunctionDef {
-                    range: func_def.range,
-                    name: func_def.name,
-                    type_params: func_def.type_params,
-                    args: func_def.args,
-                    body: func_def.body,
-                    decorator_list: func_def.decorator_list,
-                    returns: func_def.returns,
-                    type_comment: func_def.type_comment,
-                };
-                function = Some((sync_func, true));
-            }
-            _ => {
-                // Ignore other statements
+        collect_imports(&stmt, &mut imports);
+        if let ast::Stmt::FunctionDef(func_def) = stmt {
+            if function.is_some() {
+                return Err(Error::new(
+                    input.span(),
+                    "Multiple function definitions found. Only one function is allowed per gen_function_from_python! call",
+                ));
             }
+            function = Some(func_def);
         }
     }
 
     // Check that exactly one function is defined
-    let (func_def, is_async) = function
+    let func_def = function
         .ok_or_else(|| Error::new(input.span(), "No function definition found in Python stub"))?;
+    let is_async = func_def.is_async;
 
     // Check if function has @overload decorator
     let is_overload = has_overload_decorator(&func_def.decorator_list);
@@ -179,49 +144,16 @@ pub fn parse_python_overload_stubs(
     input: LitStr,
     expected_function_name: &str,
 ) -> Result<Vec<PyFunctionInfo>> {
-    let stub_content = input.value();
-    let dedented_content = dedent(&stub_content);
-
-    // Parse Python code using rustpython-parser
-    let parsed = ast::Suite::parse(&dedented_content, "<stub>")
-        .map_err(|e| Error::new(input.span(), format!("Failed to parse Python stub: {}", e)))?;
+    let parsed = parse_stub(&input)?;
 
     // Extract imports and function definitions
     let mut imports = Vec::new();
-    let mut functions: Vec<(ast::StmtFunctionDef, bool)> = Vec::new();
+    let mut functions: Vec<ast::FunctionDef> = Vec::new();
 
     for stmt in parsed {
-        match stmt {
-            ast::Stmt::Import(import_stmt) => {
-                for alias in &import_stmt.names {
-                    imports.push(alias.name.to_string());
-                }
-            }
-            ast::Stmt::ImportFrom(import_from_stmt) => {
-                if let Some(module) = &import_from_stmt.module {
-                    imports.push(module.to_string());
-                }
-            }
-            ast::Stmt::FunctionDef(func_def) => {
-                functions.push((func_def, false));
-            }
-            ast::Stmt::AsyncFunctionDef(func_def) => {
-                // Convert AsyncFunctionDef to FunctionDef for uniform processing
-                let sync_func = ast::StmtFunctionDef {
-                    range: func_def.range,
-                    name: func_def.name,
-                    type_params: func_def.type_params,
-                    args: func_def.args,
-                    body: func_def.body,
-                    decorator_list: func_def.decorator_list,
-                    returns: func_def.returns,
-                    type_comment: func_def.type_comment,
-                };
-                functions.push((sync_func, true));
-            }
-            _ => {
-                // Ignore other statements
-            }
+        collect_imports(&stmt, &mut imports);
+        if let ast::Stmt::FunctionDef(func_def) = stmt {
+            functions.push(func_def);
         }
     }
 
@@ -235,8 +167,9 @@ pub fn parse_python_overload_stubs(
 
     // Validate all functions
     let mut result = Vec::new();
-    for (func_def, is_async) in functions {
-        let func_name = func_def.name.to_string();
+    for func_def in functions {
+        let func_name = func_def.name.clone();
+        let is_async = func_def.is_async;
 
         // Validate: function name must match expected name
         if func_name != expected_function_name {
@@ -316,6 +249,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -325,13 +259,19 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "A simple function",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -369,6 +309,7 @@ mod test {
                             "collections.abc".into(),
                         ]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -381,13 +322,19 @@ mod test {
                     "collections.abc".into(),
                 ]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Process a callback function",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -425,6 +372,7 @@ mod test {
                             "typing".into(),
                         ]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -437,13 +385,19 @@ mod test {
                     "typing".into(),
                 ]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Example function with complex types",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -476,6 +430,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -487,6 +442,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -498,6 +454,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -507,13 +464,19 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from(["typing".into()]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -545,17 +508,23 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
             ],
             r#return: ::pyo3_stub_gen::type_info::no_return_type_output,
+            error: None,
+            raises: &[],
             doc: "Print a greeting",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -587,6 +556,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -596,13 +566,19 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Fetch data from URL",
             module: None,
             is_async: true,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -635,6 +611,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -644,7 +621,10 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "This function is deprecated",
             module: None,
             is_async: false,
@@ -654,6 +634,9 @@ mod test {
             }),
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -686,6 +669,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -695,7 +679,10 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "This function is deprecated",
             module: None,
             is_async: false,
@@ -705,6 +692,9 @@ mod test {
             }),
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -731,17 +721,22 @@ mod test {
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "x",
                     kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
-                    type_info: <MyRustType as ::pyo3_stub_gen::PyStubType>::type_input,
+                    type_info: || ::pyo3_stub_gen::StubTypeOf::<MyRustType>::new().type_input(),
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
             ],
-            r#return: <MyRustType as pyo3_stub_gen::PyStubType>::type_output,
+            r#return: || ::pyo3_stub_gen::StubTypeOf::<MyRustType>::new().type_output(),
+            error: None,
+            raises: &[],
             doc: "Process data using Rust type marker",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -768,17 +763,24 @@ mod test {
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "x",
                     kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
-                    type_info: <crate::MyType as ::pyo3_stub_gen::PyStubType>::type_input,
+                    type_info: || {
+                        ::pyo3_stub_gen::StubTypeOf::<crate::MyType>::new().type_input()
+                    },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
             ],
-            r#return: <Vec<String> as pyo3_stub_gen::PyStubType>::type_output,
+            r#return: || ::pyo3_stub_gen::StubTypeOf::<Vec<String>>::new().type_output(),
+            error: None,
+            raises: &[],
             doc: "Test with type paths",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -788,6 +790,25 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_rust_type_marker_in_return_override() -> Result<()> {
+        // `Vec<i32>` is `typing.Sequence` as an argument and `builtins.list` as a return value
+        let stub_str: LitStr = syn::parse2(quote! {
+            r#"
+            def process(x: pyo3_stub_gen.RustType["Vec<i32>"] | None) -> pyo3_stub_gen.RustType["Vec<i32>"] | None:
+                """Test with markers in compound types"""
+            "#
+        })?;
+        let info = parse_python_function_stub(stub_str)?;
+        let out = format_as_value(info.to_token_stream());
+        let (parameters, r#return) = out.split_once("r#return:").unwrap();
+        assert!(parameters.contains("<Vec<i32> as ::pyo3_stub_gen::PyStubType>::type_input()"));
+        assert!(!parameters.contains("type_output()"));
+        assert!(r#return.contains("<Vec<i32> as ::pyo3_stub_gen::PyStubType>::type_output()"));
+        assert!(!r#return.contains("type_input()"));
+        Ok(())
+    }
+
     #[test]
     fn test_keyword_only_args() -> Result<()> {
         let stub_str: LitStr = syn::parse2(quote! {
@@ -812,6 +833,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -823,6 +845,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -834,6 +857,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -845,6 +869,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::Expr {
                         value: {
@@ -862,13 +887,19 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from(["typing".into()]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Test keyword-only parameters",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -900,6 +931,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -911,6 +943,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -922,6 +955,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -931,13 +965,19 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Test positional-only parameters",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -970,6 +1010,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -979,13 +1020,19 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Integer overload",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: true,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -1024,6 +1071,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -1033,13 +1081,19 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Integer overload",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: true,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -1060,6 +1114,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -1069,13 +1124,19 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Float overload",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: true,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -1109,6 +1170,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -1120,6 +1182,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from(["typing".into()]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -1129,13 +1192,20 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from(["typing".into()]),
                 type_refs: ::std::collections::HashMap::new(),
+
+                any_fallback: None,
             },
+            error: None,
+            raises: &[],
             doc: "Return as tuple",
             module: None,
             is_async: false,
             deprecated: None,
             type_ignored: None,
             is_overload: true,
+            platform: None,
+            py_version: None,
+            releases_gil: false,
             file: file!(),
             line: line!(),
             column: column!(),
@@ -1,12 +1,15 @@
 //! Parse Python class method stub syntax and generate MethodInfo
 
-use rustpython_parser::{ast, Parse};
-use syn::{Error, LitStr, Result, Type};
+use quote::ToTokens;
+use syn::{
+    parse::{Parse as SynParse, ParseStream},
+    Error, LitStr, Result, Type,
+};
 
 use super::pyfunction::PythonFunctionStub;
 use super::{
-    build_parameters_from_ast, dedent, extract_deprecated_from_decorators, extract_docstring,
-    extract_return_type, has_overload_decorator,
+    ast, build_parameters_from_ast, collect_imports, extract_deprecated_from_decorators,
+    extract_docstring, extract_return_type, has_overload_decorator, parse_stub,
 };
 use crate::gen_stub::{method::MethodInfo, method::MethodType, pymethods::PyMethodsInfo};
 
@@ -20,7 +23,7 @@ impl TryFrom<PythonMethodStub> for MethodInfo {
     type Error = syn::Error;
 
     fn try_from(stub: PythonMethodStub) -> Result<Self> {
-        let func_name = stub.func_stub.func_def.name.to_string();
+        let func_name = stub.func_stub.func_def.name.clone();
 
         // Extract docstring
         let doc = extract_docstring(&stub.func_stub.func_def);
@@ -58,50 +61,29 @@ impl TryFrom<PythonMethodStub> for MethodInfo {
 
 /// Intermediate representation for Python class stub (for methods)
 pub struct PythonClassStub {
-    pub class_def: ast::StmtClassDef,
+    pub class_def: ast::ClassDef,
     pub imports: Vec<String>,
 }
 
 impl PythonClassStub {
     /// Parse Python class definition from a literal string
     pub fn new(input: &LitStr) -> Result<Self> {
-        let stub_content = input.value();
-
-        // Remove common indentation to allow indented Python code in raw strings
-        let dedented_content = dedent(&stub_content);
-
-        // Parse Python code using rustpython-parser
-        let parsed = ast::Suite::parse(&dedented_content, "<stub>")
-            .map_err(|e| Error::new(input.span(), format!("Failed to parse Python stub: {}", e)))?;
+        let parsed = parse_stub(input)?;
 
         // Extract imports and class definition
         let mut imports = Vec::new();
-        let mut class_def: Option<ast::StmtClassDef> = None;
+        let mut class_def: Option<ast::ClassDef> = None;
 
         for stmt in parsed {
-            match stmt {
-                ast::Stmt::Import(import_stmt) => {
-                    for alias in &import_stmt.names {
-                        imports.push(alias.name.to_string());
-                    }
-                }
-                ast::Stmt::ImportFrom(import_from_stmt) => {
-                    if let Some(module) = &import_from_stmt.module {
-                        imports.push(module.to_string());
-                    }
-                }
-                ast::Stmt::ClassDef(cls_def) => {
-                    if class_def.is_some() {
-                        return Err(Error::new(
-                            input.span(),
-                            "Multiple class definitions found. Only one class is allowed per gen_methods_from_python! call",
-                        ));
-                    }
-                    class_def = Some(cls_def);
-                }
-                _ => {
-                    // Ignore other statements
+            collect_imports(&stmt, &mut imports);
+            if let ast::Stmt::ClassDef(cls_def) = stmt {
+                if class_def.is_some() {
+                    return Err(Error::new(
+                        input.span(),
+                        "Multiple class definitions found. Only one class is allowed per gen_methods_from_python! call",
+                    ));
                 }
+                class_def = Some(cls_def);
             }
         }
 
@@ -117,74 +99,37 @@ impl TryFrom<PythonClassStub> for PyMethodsInfo {
     type Error = syn::Error;
 
     fn try_from(stub: PythonClassStub) -> Result<Self> {
-        let class_name = stub.class_def.name.to_string();
+        let class_name = stub.class_def.name.clone();
         let mut methods = Vec::new();
 
         // Extract methods from class body
         for stmt in &stub.class_def.body {
-            match stmt {
-                ast::Stmt::FunctionDef(func_def) => {
-                    // Determine method type
-                    let method_type = determine_method_type(func_def, &func_def.args);
-
-                    // Check if method has @overload decorator
-                    let is_overload = has_overload_decorator(&func_def.decorator_list);
-
-                    // Create PythonFunctionStub
-                    let func_stub = PythonFunctionStub {
-                        func_def: func_def.clone(),
-                        imports: stub.imports.clone(),
-                        is_async: false,
-                        is_overload,
-                    };
-
-                    // Create PythonMethodStub and convert to MethodInfo
-                    let method_stub = PythonMethodStub {
-                        func_stub,
-                        method_type,
-                    };
-                    let method = MethodInfo::try_from(method_stub)?;
-                    methods.push(method);
-                }
-                ast::Stmt::AsyncFunctionDef(func_def) => {
-                    // Check if method has @overload decorator
-                    let is_overload = has_overload_decorator(&func_def.decorator_list);
-
-                    // Convert AsyncFunctionDef to FunctionDef for uniform processing
-                    let sync_func = ast::StmtFunctionDef {
-                        range: func_def.range,
-                        name: func_def.name.clone(),
-                        type_params: func_def.type_params.clone(),
-                        args: func_def.args.clone(),
-                        body: func_def.body.clone(),
-                        decorator_list: func_def.decorator_list.clone(),
-                        returns: func_def.returns.clone(),
-                        type_comment: func_def.type_comment.clone(),
-                    };
-
-                    // Determine method type
-                    let method_type = determine_method_type(&sync_func, &sync_func.args);
-
-                    // Create PythonFunctionStub
-                    let func_stub = PythonFunctionStub {
-                        func_def: sync_func,
-                        imports: stub.imports.clone(),
-                        is_async: true,
-                        is_overload,
-                    };
-
-                    // Create PythonMethodStub and convert to MethodInfo
-                    let method_stub = PythonMethodStub {
-                        func_stub,
-                        method_type,
-                    };
-                    let method = MethodInfo::try_from(method_stub)?;
-                    methods.push(method);
-                }
-                _ => {
-                    // Ignore other statements (e.g., docstrings, pass)
-                }
-            }
+            // Ignore other statements (e.g., docstrings, pass)
+            let ast::Stmt::FunctionDef(func_def) = stmt else {
+                continue;
+            };
+
+            // Determine method type
+            let method_type = determine_method_type(func_def, &func_def.args);
+
+            // Check if method has @overload decorator
+            let is_overload = has_overload_decorator(&func_def.decorator_list);
+
+            // Create PythonFunctionStub
+            let func_stub = PythonFunctionStub {
+                func_def: func_def.clone(),
+                imports: stub.imports.clone(),
+                is_async: func_def.is_async,
+                is_overload,
+            };
+
+            // Create PythonMethodStub and convert to MethodInfo
+            let method_stub = PythonMethodStub {
+                func_stub,
+                method_type,
+            };
+            let method = MethodInfo::try_from(method_stub)?;
+            methods.push(method);
         }
 
         if methods.is_empty() {
@@ -204,6 +149,7 @@ impl TryFrom<PythonClassStub> for PyMethodsInfo {
 
         Ok(PyMethodsInfo {
             struct_id,
+            python_class: None,
             attrs: Vec::new(),
             getters: Vec::new(),
             setters: Vec::new(),
@@ -218,12 +164,88 @@ pub fn parse_python_methods_stub(input: &LitStr) -> Result<PyMethodsInfo> {
     PyMethodsInfo::try_from(stub).map_err(|e| Error::new(input.span(), format!("{}", e)))
 }
 
+/// Input of `gen_methods_from_python!`, with optional parameters before the Python stub:
+///
+/// - `rust_type = path::To::Type`: Rust type of the class, if it is not the Python class name in scope
+/// - `by_name = true`: attach the methods to the class of the Python class name when gathering stubs,
+///   optionally narrowed down by `module = "..."`
+pub struct GenMethodsFromPythonInput {
+    rust_type: Option<Type>,
+    by_name: bool,
+    module: Option<String>,
+    python_stub: LitStr,
+}
+
+impl SynParse for GenMethodsFromPythonInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut rust_type = None;
+        let mut by_name = false;
+        let mut module = None;
+        while input.peek(syn::Ident) {
+            let key: syn::Ident = input.parse()?;
+            let _: syn::token::Eq = input.parse()?;
+            match key.to_string().as_str() {
+                "rust_type" => rust_type = Some(input.parse()?),
+                "by_name" => by_name = input.parse::<syn::LitBool>()?.value(),
+                "module" => module = Some(input.parse::<LitStr>()?.value()),
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!(
+                            "Unknown parameter: {}. Expected 'rust_type', 'by_name', 'module' or a string literal",
+                            key
+                        ),
+                    ));
+                }
+            }
+            let _: syn::token::Comma = input.parse()?;
+        }
+        let python_stub: LitStr = input.parse()?;
+
+        if by_name && rust_type.is_some() {
+            return Err(Error::new(
+                python_stub.span(),
+                "Cannot specify both 'rust_type' and 'by_name'. Use 'by_name' only if the Rust type is not available.",
+            ));
+        }
+        if module.is_some() && !by_name {
+            return Err(Error::new(
+                python_stub.span(),
+                "The 'module' parameter can only be used with 'by_name = true'",
+            ));
+        }
+        Ok(Self {
+            rust_type,
+            by_name,
+            module,
+            python_stub,
+        })
+    }
+}
+
+/// Parse input of `gen_methods_from_python!` and return PyMethodsInfo
+pub fn parse_gen_methods_from_python_input(
+    input: GenMethodsFromPythonInput,
+) -> Result<PyMethodsInfo> {
+    let mut info = parse_python_methods_stub(&input.python_stub)?;
+    if let Some(rust_type) = input.rust_type {
+        info.struct_id = rust_type;
+    }
+    if input.by_name {
+        let class_name = info.struct_id.to_token_stream().to_string();
+        // `struct_id` is not used to find the class
+        info.struct_id = syn::parse_quote!(());
+        info.python_class = Some((class_name, input.module));
+    }
+    Ok(info)
+}
+
 /// Determine method type from decorators and arguments
-fn determine_method_type(func_def: &ast::StmtFunctionDef, args: &ast::Arguments) -> MethodType {
+fn determine_method_type(func_def: &ast::FunctionDef, args: &ast::Arguments) -> MethodType {
     // Check for @staticmethod decorator
     for decorator in &func_def.decorator_list {
         if let ast::Expr::Name(name) = decorator {
-            match name.id.as_str() {
+            match name.as_str() {
                 "staticmethod" => return MethodType::Static,
                 "classmethod" => return MethodType::Class,
                 _ => {}
@@ -232,13 +254,13 @@ fn determine_method_type(func_def: &ast::StmtFunctionDef, args: &ast::Arguments)
     }
 
     // Check if it's __new__ (constructor)
-    if func_def.name.as_str() == "__new__" {
+    if func_def.name == "__new__" {
         return MethodType::New;
     }
 
     // Check first argument to determine if it's instance/class method
     if let Some(first_arg) = args.args.first() {
-        let arg_name = first_arg.def.arg.as_str();
+        let arg_name = first_arg.name.as_str();
         if arg_name == "self" {
             return MethodType::Instance;
         } else if arg_name == "cls" {
@@ -254,7 +276,7 @@ fn determine_method_type(func_def: &ast::StmtFunctionDef, args: &ast::Arguments)
 mod test {
     use super::*;
     use proc_macro2::TokenStream as TokenStream2;
-    use quote::{quote, ToTokens};
+    use quote::quote;
 
     #[test]
     fn test_single_method_class() -> Result<()> {
@@ -281,6 +303,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -290,6 +313,7 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
             doc: "Increment by one",
             r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
@@ -348,6 +372,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -357,6 +382,7 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
             doc: "Create something",
             r#type: ::pyo3_stub_gen::type_info::MethodType::Static,
@@ -395,6 +421,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -404,6 +431,7 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
             doc: "Create from string",
             r#type: ::pyo3_stub_gen::type_info::MethodType::Class,
@@ -438,6 +466,7 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
             doc: "Constructor",
             r#type: ::pyo3_stub_gen::type_info::MethodType::New,
@@ -481,6 +510,7 @@ mod test {
                             "collections.abc".into(),
                         ]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -493,6 +523,7 @@ mod test {
                     "collections.abc".into(),
                 ]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
             doc: "Process a callback",
             r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
@@ -530,6 +561,7 @@ mod test {
                         source_module: None,
                         import: ::std::collections::HashSet::from([]),
                         type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: None,
                     },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
@@ -539,6 +571,7 @@ mod test {
                 source_module: None,
                 import: ::std::collections::HashSet::from([]),
                 type_refs: ::std::collections::HashMap::new(),
+                any_fallback: None,
             },
             doc: "Fetch data asynchronously",
             r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
@@ -571,11 +604,13 @@ mod test {
                 ::pyo3_stub_gen::type_info::ParameterInfo {
                     name: "other",
                     kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
-                    type_info: <SomeRustType as ::pyo3_stub_gen::PyStubType>::type_input,
+                    type_info: || {
+                        ::pyo3_stub_gen::StubTypeOf::<SomeRustType>::new().type_input()
+                    },
                     default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                 },
             ],
-            r#return: <PyProblem as pyo3_stub_gen::PyStubType>::type_output,
+            r#return: || ::pyo3_stub_gen::StubTypeOf::<PyProblem>::new().type_output(),
             doc: "In-place addition using Rust type marker",
             r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
             is_async: false,
@@ -587,6 +622,26 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_rust_type_marker_in_method_return_override() -> Result<()> {
+        // `Vec<i32>` is `typing.Sequence` as an argument and `builtins.list` as a return value
+        let stub_str: LitStr = syn::parse2(quote! {
+            r#"
+            class PyProblem:
+                def values(self, x: pyo3_stub_gen.RustType["Vec<i32>"] | None) -> pyo3_stub_gen.RustType["Vec<i32>"] | None:
+                    """Test with markers in compound types"""
+            "#
+        })?;
+        let py_methods_info = parse_python_methods_stub(&stub_str)?;
+        let out = format_as_value(py_methods_info.methods[0].to_token_stream());
+        let (parameters, r#return) = out.split_once("r#return:").unwrap();
+        assert!(parameters.contains("<Vec<i32> as ::pyo3_stub_gen::PyStubType>::type_input()"));
+        assert!(!parameters.contains("type_output()"));
+        assert!(r#return.contains("<Vec<i32> as ::pyo3_stub_gen::PyStubType>::type_output()"));
+        assert!(!r#return.contains("type_input()"));
+        Ok(())
+    }
+
     #[test]
     fn test_keyword_only_params_with_defaults() -> Result<()> {
         let stub_str: LitStr = syn::parse2(quote! {
@@ -621,6 +676,7 @@ mod test {
         insta::assert_snapshot!(format_as_value(out), @r###"
         ::pyo3_stub_gen::type_info::PyMethodsInfo {
             struct_id: std::any::TypeId::of::<Placeholder>,
+            python_class: None,
             attrs: &[],
             getters: &[],
             setters: &[],
@@ -639,6 +695,7 @@ mod test {
                                     "typing".into(),
                                 ]),
                                 type_refs: ::std::collections::HashMap::new(),
+                                any_fallback: None,
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                         },
@@ -653,6 +710,7 @@ mod test {
                                     "typing".into(),
                                 ]),
                                 type_refs: ::std::collections::HashMap::new(),
+                                any_fallback: None,
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                         },
@@ -667,6 +725,7 @@ mod test {
                                     "typing".into(),
                                 ]),
                                 type_refs: ::std::collections::HashMap::new(),
+                                any_fallback: None,
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                         },
@@ -681,6 +740,7 @@ mod test {
                                     "typing".into(),
                                 ]),
                                 type_refs: ::std::collections::HashMap::new(),
+                                any_fallback: None,
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                         },
@@ -695,6 +755,7 @@ mod test {
                                     "typing".into(),
                                 ]),
                                 type_refs: ::std::collections::HashMap::new(),
+                                any_fallback: None,
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::Expr {
                                 value: {
@@ -717,6 +778,7 @@ mod test {
                                     "typing".into(),
                                 ]),
                                 type_refs: ::std::collections::HashMap::new(),
+                                any_fallback: None,
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::Expr {
                                 value: {
@@ -739,6 +801,7 @@ mod test {
                                     "typing".into(),
                                 ]),
                                 type_refs: ::std::collections::HashMap::new(),
+                                any_fallback: None,
                             },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::Expr {
                                 value: {
@@ -751,7 +814,7 @@ mod test {
                             },
                         },
                     ],
-                    r#return: <Placeholder as pyo3_stub_gen::PyStubType>::type_output,
+                    r#return: || ::pyo3_stub_gen::StubTypeOf::<Placeholder>::new().type_output(),
                     doc: "\n        Configure placeholder with keyword-only parameters.\n\n        This demonstrates keyword-only parameters (after *) which should be\n        preserved in the generated stub file.\n        ",
                     r#type: ::pyo3_stub_gen::type_info::MethodType::Instance,
                     is_async: false,
@@ -768,6 +831,37 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_methods_for_class_in_other_crate() -> Result<()> {
+        let stub = r#"
+            class Foo:
+                def bar(self) -> int: ...
+            "#;
+
+        let input: GenMethodsFromPythonInput =
+            syn::parse2(quote! { rust_type = other::PyFoo, #stub })?;
+        let info = parse_gen_methods_from_python_input(input)?;
+        assert_eq!(
+            info.struct_id.to_token_stream().to_string(),
+            "other :: PyFoo"
+        );
+        assert!(info.python_class.is_none());
+
+        let input: GenMethodsFromPythonInput =
+            syn::parse2(quote! { by_name = true, module = "pkg.sub", #stub })?;
+        let info = parse_gen_methods_from_python_input(input)?;
+        assert_eq!(info.struct_id.to_token_stream().to_string(), "()");
+        assert_eq!(
+            info.python_class,
+            Some(("Foo".to_string(), Some("pkg.sub".to_string())))
+        );
+
+        assert!(
+            syn::parse2::<GenMethodsFromPythonInput>(quote! { module = "pkg", #stub }).is_err()
+        );
+        Ok(())
+    }
+
     fn format_as_value(tt: TokenStream2) -> String {
         let ttt = quote! { const _: () = #tt; };
         let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
@@ -2,10 +2,10 @@
 
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use rustpython_parser::{ast, Parse};
 use syn::{parse::Parse as SynParse, parse::ParseStream, Error, LitStr, Result};
 
-use super::{collect_rust_type_markers, dedent, expr_to_type_string};
+use super::{ast, collect_imports, collect_rust_type_markers, expr_to_type_string, parse_stub};
+use crate::gen_stub::util::{marker_types_and_fns, RustTypeMarker, TypeDirection};
 
 /// Input for gen_type_alias_from_python! macro
 pub struct GenTypeAliasFromPythonInput {
@@ -34,18 +34,16 @@ pub struct PythonTypeAliasStub {
     pub name: String,
     pub type_expr: String,
     pub imports: Vec<String>,
-    pub rust_type_markers: Vec<String>,
+    pub rust_type_markers: Vec<RustTypeMarker>,
     pub doc: String,
 }
 
 /// Extract next-line docstring for type alias (Pyright's convention)
 fn extract_type_alias_docstring(stmts: &[ast::Stmt], current_index: usize) -> String {
     // Check if next statement is a string literal
-    if let Some(ast::Stmt::Expr(expr_stmt)) = stmts.get(current_index + 1) {
-        if let ast::Expr::Constant(constant) = &*expr_stmt.value {
-            if let ast::Constant::Str(s) = &constant.value {
-                return s.to_string();
-            }
+    if let Some(ast::Stmt::Expr(expr)) = stmts.get(current_index + 1) {
+        if let Some(s) = expr.as_str() {
+            return s.to_string();
         }
     }
     String::new()
@@ -53,44 +51,28 @@ fn extract_type_alias_docstring(stmts: &[ast::Stmt], current_index: usize) -> St
 
 /// Parse Python type alias stub string and return Vec<TypeAliasInfo> as TokenStream
 pub fn parse_python_type_alias_stub(input: &GenTypeAliasFromPythonInput) -> Result<TokenStream2> {
-    let stub_content = input.python_stub.value();
-
-    // Remove common indentation
-    let dedented_content = dedent(&stub_content);
-
-    // Parse Python code
-    let parsed = ast::Suite::parse(&dedented_content, "<stub>").map_err(|e| {
-        Error::new(
-            input.python_stub.span(),
-            format!("Failed to parse Python stub: {}", e),
-        )
-    })?;
+    let parsed = parse_stub(&input.python_stub)?;
 
     // Extract imports and type alias definitions
     let mut imports = Vec::new();
     let mut type_aliases = Vec::new();
 
     for (idx, stmt) in parsed.iter().enumerate() {
+        collect_imports(stmt, &mut imports);
         match stmt {
-            ast::Stmt::Import(import_stmt) => {
-                for alias in &import_stmt.names {
-                    imports.push(alias.name.to_string());
-                }
-            }
-            ast::Stmt::ImportFrom(import_from_stmt) => {
-                if let Some(module) = &import_from_stmt.module {
-                    imports.push(module.to_string());
-                }
-            }
-            ast::Stmt::AnnAssign(ann_assign) => {
+            ast::Stmt::AnnAssign {
+                target,
+                annotation,
+                value,
+            } => {
                 // Type alias pattern: Name: TypeAlias = Type
-                if let ast::Expr::Name(name_expr) = &*ann_assign.target {
-                    let alias_name = name_expr.id.to_string();
+                if let ast::Expr::Name(name) = target {
+                    let alias_name = name.clone();
 
                     // Check if annotation is TypeAlias
-                    let is_type_alias = match &*ann_assign.annotation {
-                        ast::Expr::Name(ann_name) => ann_name.id.as_str() == "TypeAlias",
-                        ast::Expr::Attribute(attr) => attr.attr.as_str() == "TypeAlias",
+                    let is_type_alias = match annotation {
+                        ast::Expr::Name(ann_name) => ann_name == "TypeAlias",
+                        ast::Expr::Attribute { attr, .. } => attr == "TypeAlias",
                         _ => false,
                     };
 
@@ -102,7 +84,7 @@ pub fn parse_python_type_alias_stub(input: &GenTypeAliasFromPythonInput) -> Resu
                     }
 
                     // Extract the actual type from the value
-                    if let Some(value) = &ann_assign.value {
+                    if let Some(value) = value {
                         let type_str = expr_to_type_string(value)?;
                         let rust_type_markers = collect_rust_type_markers(value)?;
                         let doc = extract_type_alias_docstring(&parsed, idx);
@@ -121,12 +103,12 @@ pub fn parse_python_type_alias_stub(input: &GenTypeAliasFromPythonInput) -> Resu
                     }
                 }
             }
-            ast::Stmt::TypeAlias(type_alias_stmt) => {
+            ast::Stmt::TypeAlias { name, value } => {
                 // Python 3.12+ type statement: type Name = Type
-                if let ast::Expr::Name(name_expr) = &*type_alias_stmt.name {
-                    let alias_name = name_expr.id.to_string();
-                    let type_str = expr_to_type_string(&type_alias_stmt.value)?;
-                    let rust_type_markers = collect_rust_type_markers(&type_alias_stmt.value)?;
+                if let ast::Expr::Name(name) = name {
+                    let alias_name = name.clone();
+                    let type_str = expr_to_type_string(value)?;
+                    let rust_type_markers = collect_rust_type_markers(value)?;
                     let doc = extract_type_alias_docstring(&parsed, idx);
                     type_aliases.push(PythonTypeAliasStub {
                         name: alias_name,
@@ -172,23 +154,23 @@ pub fn parse_python_type_alias_stub(input: &GenTypeAliasFromPythonInput) -> Resu
                 })
                 .collect();
 
+            let (marker_types, marker_fns) =
+                marker_types_and_fns(&alias.rust_type_markers, TypeDirection::Input);
+
             // Generate code for processing RustType markers at runtime
             let (type_name_code, type_refs_code) = if has_rust_markers {
-                // Parse rust_type_markers as syn::Type
-                let marker_types: Vec<syn::Type> = alias
+                let rust_names = alias
                     .rust_type_markers
                     .iter()
-                    .filter_map(|s| syn::parse_str(s).ok())
-                    .collect();
-
-                let rust_names = alias.rust_type_markers.iter().collect::<Vec<_>>();
+                    .map(|marker| &marker.rust_type)
+                    .collect::<Vec<_>>();
 
                 (
                     quote! {
                         {
                             let mut type_name = #type_repr.to_string();
                             #(
-                                let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::type_input();
+                                let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                 type_name = type_name.replace(#rust_names, &type_info.name);
                             )*
                             type_name
@@ -198,7 +180,7 @@ pub fn parse_python_type_alias_stub(input: &GenTypeAliasFromPythonInput) -> Resu
                         {
                             let mut type_refs = ::std::collections::HashMap::new();
                             #(
-                                let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::type_input();
+                                let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                 if let Some(module) = type_info.source_module {
                                     type_refs.insert(
                                         type_info.name.split('[').next().unwrap_or(&type_info.name)
@@ -224,7 +206,7 @@ pub fn parse_python_type_alias_stub(input: &GenTypeAliasFromPythonInput) -> Resu
             };
 
             quote! {
-                pyo3_stub_gen::inventory::submit! {
+                ::pyo3_stub_gen::submit! {
                     pyo3_stub_gen::type_info::TypeAliasInfo {
                         name: #name,
                         module: #module,
@@ -234,11 +216,14 @@ pub fn parse_python_type_alias_stub(input: &GenTypeAliasFromPythonInput) -> Resu
                             import: {
                                 let mut set = std::collections::HashSet::new();
                                 #(set.insert(#import_refs);)*
+                                #(set.extend(<#marker_types as pyo3_stub_gen::PyStubType>::#marker_fns().import);)*
                                 set
                             },
                             type_refs: #type_refs_code,
+                            any_fallback: None,
                         },
                         doc: #doc,
+                        py_version: None,
                     }
                 }
             }
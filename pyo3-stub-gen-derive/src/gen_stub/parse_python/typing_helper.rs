@@ -0,0 +1,143 @@
+//! Parse typing-only Python definitions and generate TypingHelperInfo
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse::Parse as SynParse, parse::ParseStream, Error, LitStr, Result};
+
+use super::{ast, dedent, parse_stub};
+
+/// Input for gen_typing_helpers_from_python! macro
+pub struct GenTypingHelpersFromPythonInput {
+    pub module: String,
+    pub python_stub: LitStr,
+}
+
+impl SynParse for GenTypingHelpersFromPythonInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // First parameter is module name (string literal)
+        let module: LitStr = input.parse()?;
+        let _: syn::token::Comma = input.parse()?;
+
+        // Second parameter is Python code of the definitions
+        let python_stub: LitStr = input.parse()?;
+
+        Ok(Self {
+            module: module.value(),
+            python_stub,
+        })
+    }
+}
+
+/// Parse typing-only definitions and return a TypingHelperInfo submission as TokenStream
+pub fn parse_python_typing_helpers(
+    input: &GenTypingHelpersFromPythonInput,
+) -> Result<TokenStream2> {
+    let parsed = parse_stub(&input.python_stub)?;
+    if parsed
+        .iter()
+        .all(|stmt| matches!(stmt, ast::Stmt::Import(_) | ast::Stmt::ImportFrom(_)))
+    {
+        return Err(Error::new(
+            input.python_stub.span(),
+            "No definitions found in Python stub",
+        ));
+    }
+    let (code, imports) = split_imports(&parsed, &dedent(&input.python_stub.value()));
+
+    let module = &input.module;
+    Ok(quote! {
+        ::pyo3_stub_gen::submit! {
+            ::pyo3_stub_gen::type_info::TypingHelperInfo {
+                module: #module,
+                code: #code,
+                imports: &[#(#imports),*],
+            }
+        }
+    })
+}
+
+/// Modules of `import` statements, and the code without them since they are written at the top of the stub file.
+///
+/// Imports with `as`, `;` or line continuation are kept in the code as written,
+/// and `from ... import ...` is always kept since it imports names rather than modules.
+fn split_imports(parsed: &[ast::Stmt], code: &str) -> (String, Vec<String>) {
+    let imports = parsed
+        .iter()
+        .filter_map(|stmt| match stmt {
+            ast::Stmt::Import(modules) => Some(modules.iter().cloned()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let code = code
+        .lines()
+        .filter(|line| {
+            let line = line.trim_end();
+            !(line.starts_with("import ")
+                && !line.contains(" as ")
+                && !line.contains(';')
+                && !line.ends_with('\\'))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    (code, imports)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_imports() -> Result<()> {
+        let input: GenTypingHelpersFromPythonInput = syn::parse2(quote! {
+            "my_module",
+            r#"
+            import typing
+            import numpy as np
+            from typing_extensions import Self
+
+            T = typing.TypeVar("T")
+
+            class SupportsRead(typing.Protocol[T]):
+                def read(self, n: int = -1, /) -> T: ...
+                def array(self) -> np.ndarray: ...
+                def copy(self) -> Self: ...
+            "#
+        })?;
+        let parsed = parse_stub(&input.python_stub)?;
+        let (code, imports) = split_imports(&parsed, &dedent(&input.python_stub.value()));
+        assert_eq!(imports, ["typing", "numpy"]);
+        insta::assert_snapshot!(code.trim(), @r#"
+        import numpy as np
+        from typing_extensions import Self
+
+        T = typing.TypeVar("T")
+
+        class SupportsRead(typing.Protocol[T]):
+            def read(self, n: int = -1, /) -> T: ...
+            def array(self) -> np.ndarray: ...
+            def copy(self) -> Self: ...
+        "#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_python() -> Result<()> {
+        let input: GenTypingHelpersFromPythonInput = syn::parse2(quote! {
+            "my_module",
+            r#"
+            class SupportsRead(typing.Protocol[T]
+                def read(self) -> T: ...
+            "#
+        })?;
+        let err = parse_python_typing_helpers(&input).unwrap_err();
+        assert!(err.to_string().starts_with("Failed to parse Python stub"));
+
+        let input: GenTypingHelpersFromPythonInput = syn::parse2(quote! {
+            "my_module", "import typing"
+        })?;
+        let err = parse_python_typing_helpers(&input).unwrap_err();
+        assert_eq!(err.to_string(), "No definitions found in Python stub");
+        Ok(())
+    }
+}
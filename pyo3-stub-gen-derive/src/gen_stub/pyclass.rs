@@ -1,6 +1,13 @@
 use super::{
-    extract_documents, member::MemberKind, parse_pyo3_attrs, util::quote_option, Attr, MemberInfo,
-    PyClassAttr, StubType,
+    attr::{
+        parse_gen_stub_skip_accessors, DataclassTransformAttr, IgnoreTarget, ItemAccessAttr,
+        IteratorAttr,
+    },
+    extract_documents,
+    member::MemberKind,
+    parse_pyo3_attrs,
+    util::quote_option,
+    Attr, MemberInfo, PyClassAttr, StubType,
 };
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
@@ -19,6 +26,14 @@ pub struct PyClassInfo {
     has_hash: bool,
     has_str: bool,
     subclass: bool,
+    buffer: bool,
+    picklable: bool,
+    /// Python names of all fields if `#[gen_stub(slots)]` is given
+    slots: Option<Vec<String>>,
+    item_access: Vec<ItemAccessAttr>,
+    iterator: Option<IteratorAttr>,
+    type_ignored: Option<IgnoreTarget>,
+    dataclass_transform: Option<DataclassTransformAttr>,
 }
 
 impl From<&PyClassInfo> for StubType {
@@ -58,6 +73,14 @@ impl PyClassInfo {
         let mut has_hash = false;
         let mut has_str = false;
         let mut subclass = false;
+        let mut buffer = false;
+        let mut picklable = false;
+        let mut has_slots = false;
+        let mut item_access = Vec::new();
+        let mut iterator = None;
+        let mut type_ignored = None;
+        let mut dataclass_transform = None;
+        let mut rename_all = None;
         for attr_item in parse_pyo3_attrs(&attrs)? {
             match attr_item {
                 Attr::Name(name) => pyclass_name = Some(name),
@@ -75,6 +98,14 @@ impl PyClassInfo {
                 Attr::Hash => has_hash = true,
                 Attr::Str => has_str = true,
                 Attr::Subclass => subclass = true,
+                Attr::GenStubBuffer => buffer = true,
+                Attr::GenStubPicklable => picklable = true,
+                Attr::GenStubSlots => has_slots = true,
+                Attr::GenStubItemAccess(attr) => item_access.push(attr),
+                Attr::GenStubIterator(attr) => iterator = Some(attr),
+                Attr::GenStubTypeIgnore(target) => type_ignored = Some(target),
+                Attr::GenStubDataclassTransform(attr) => dataclass_transform = Some(attr),
+                Attr::RenameAll(rule) => rename_all = Some(rule),
                 _ => {}
             }
         }
@@ -107,14 +138,31 @@ impl PyClassInfo {
         let pyclass_name = pyclass_name.unwrap_or_else(|| ident.to_string());
         let mut getters = Vec::new();
         let mut setters = Vec::new();
+        let mut slots = has_slots.then(Vec::new);
         for field in fields {
-            let has_get = is_get_all || MemberInfo::is_get(&field)?;
-            let has_set = is_set_all || MemberInfo::is_set(&field)?;
+            if let Some(slots) = &mut slots {
+                slots.push(MemberInfo::field_name(
+                    &field.ident,
+                    &field.attrs,
+                    rename_all,
+                )?);
+            }
+            let (skip_getter, skip_setter) = parse_gen_stub_skip_accessors(&field.attrs)?;
+            let has_get = !skip_getter && (is_get_all || MemberInfo::is_get(&field)?);
+            let has_set = !skip_setter && (is_set_all || MemberInfo::is_set(&field)?);
             if has_get {
-                getters.push(MemberInfo::from_field(field.clone(), MemberKind::Getter)?)
+                getters.push(MemberInfo::from_field(
+                    field.clone(),
+                    MemberKind::Getter,
+                    rename_all,
+                )?)
             }
             if has_set {
-                setters.push(MemberInfo::from_field(field, MemberKind::Setter)?)
+                setters.push(MemberInfo::from_field(
+                    field,
+                    MemberKind::Setter,
+                    rename_all,
+                )?)
             }
         }
         let doc = extract_documents(&attrs).join("\n");
@@ -131,6 +179,13 @@ impl PyClassInfo {
             has_hash,
             has_str,
             subclass,
+            buffer,
+            picklable,
+            slots,
+            item_access,
+            iterator,
+            type_ignored,
+            dataclass_transform,
         })
     }
 }
@@ -158,8 +213,22 @@ impl ToTokens for PyClassInfo {
             has_hash,
             has_str,
             subclass,
+            buffer,
+            picklable,
+            slots,
+            item_access,
+            iterator,
+            type_ignored,
+            dataclass_transform,
         } = self;
         let module = quote_option(module);
+        let slots = match slots {
+            Some(names) => quote! { Some(&[ #( #names ),* ]) },
+            None => quote! { None },
+        };
+        let iterator = quote_option(iterator);
+        let type_ignored = quote_option(type_ignored);
+        let dataclass_transform = quote_option(dataclass_transform);
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyClassInfo {
                 pyclass_name: #pyclass_name,
@@ -174,6 +243,16 @@ impl ToTokens for PyClassInfo {
                 has_hash: #has_hash,
                 has_str: #has_str,
                 subclass: #subclass,
+                buffer: #buffer,
+                picklable: #picklable,
+                slots: #slots,
+                item_access: &[ #( #item_access ),* ],
+                iterator: #iterator,
+                type_ignored: #type_ignored,
+                dataclass_transform: #dataclass_transform,
+                file: file!(),
+                line: line!(),
+                column: column!(),
             }
         })
     }
@@ -221,24 +300,32 @@ mod test {
             getters: &[
                 ::pyo3_stub_gen::type_info::MemberInfo {
                     name: "name",
-                    r#type: <String as ::pyo3_stub_gen::PyStubType>::type_output,
+                    r#type: || ::pyo3_stub_gen::StubTypeOf::<String>::new().type_output(),
                     doc: "",
                     default: None,
+                    default_symbol: None,
                     deprecated: None,
+                    cached: false,
                 },
                 ::pyo3_stub_gen::type_info::MemberInfo {
                     name: "ndim",
-                    r#type: <usize as ::pyo3_stub_gen::PyStubType>::type_output,
+                    r#type: || ::pyo3_stub_gen::StubTypeOf::<usize>::new().type_output(),
                     doc: "",
                     default: None,
+                    default_symbol: None,
                     deprecated: None,
+                    cached: false,
                 },
                 ::pyo3_stub_gen::type_info::MemberInfo {
                     name: "description",
-                    r#type: <Option<String> as ::pyo3_stub_gen::PyStubType>::type_output,
+                    r#type: || {
+                        ::pyo3_stub_gen::StubTypeOf::<Option<String>>::new().type_output()
+                    },
                     doc: "",
                     default: None,
+                    default_symbol: None,
                     deprecated: None,
+                    cached: false,
                 },
             ],
             setters: &[],
@@ -250,11 +337,108 @@ mod test {
             has_hash: false,
             has_str: false,
             subclass: false,
+            buffer: false,
+            picklable: false,
+            slots: None,
+            item_access: &[],
+            iterator: None,
+            type_ignored: None,
+            dataclass_transform: None,
+            file: file!(),
+            line: line!(),
+            column: column!(),
         }
         "###);
         Ok(())
     }
 
+    #[test]
+    fn test_pyclass_skip_accessors() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(get_all)]
+            pub struct Counter {
+                #[pyo3(set)]
+                #[gen_stub(skip_setter)]
+                pub count: usize,
+                #[pyo3(set)]
+                pub step: usize,
+                #[gen_stub(skip_getter)]
+                pub hidden: usize,
+                #[gen_stub(skip)]
+                pub internal: usize,
+            }
+            "#,
+        )?;
+        let info = PyClassInfo::try_from(input)?;
+        let names = |members: &[MemberInfo]| {
+            members
+                .iter()
+                .map(|member| member.to_token_stream().to_string())
+                .map(|tt| tt.split('"').nth(1).unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&info.getters), vec!["count", "step"]);
+        assert_eq!(names(&info.setters), vec!["step"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pyclass_exception() -> Result<()> {
+        // Exception with attributes, which `create_exception!` cannot declare
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(frozen, extends=PyValueError)]
+            pub struct RangeError {
+                #[pyo3(get)]
+                lower: i64,
+                #[pyo3(get)]
+                upper: i64,
+            }
+            "#,
+        )?;
+        let out = format_as_value(PyClassInfo::try_from(input)?.to_token_stream());
+        assert!(
+            out.contains("bases: &[<PyValueError as ::pyo3_stub_gen::PyStubType>::type_output]"),
+            "{out}"
+        );
+        assert!(out.contains(r#"name: "lower""#), "{out}");
+        assert!(out.contains(r#"name: "upper""#), "{out}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_pyclass_slots() -> Result<()> {
+        let input: ItemStruct = parse_str(
+            r#"
+            #[pyclass(rename_all = "camelCase")]
+            #[gen_stub(slots)]
+            pub struct Point {
+                #[pyo3(get)]
+                pub x_pos: f64,
+                #[pyo3(name = "y")]
+                pub y_pos: f64,
+                pub r#type: String,
+            }
+            "#,
+        )?;
+        let info = PyClassInfo::try_from(input)?;
+        assert_eq!(
+            info.slots,
+            Some(vec![
+                "xPos".to_string(),
+                "y".to_string(),
+                "type".to_string()
+            ])
+        );
+        let out = info.to_token_stream().to_string();
+        assert!(
+            out.contains(r#"slots : Some (& ["xPos" , "y" , "type"])"#),
+            "{out}"
+        );
+        Ok(())
+    }
+
     fn format_as_value(tt: TokenStream2) -> String {
         let ttt = quote! { const _: () = #tt; };
         let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
@@ -44,6 +44,7 @@ impl PyComplexEnumInfo {
         let mut gen_stub_standalone_module = None;
         let mut renaming_rule = None;
         let mut bases = Vec::new();
+        let mut match_args = true;
         for attr_item in parse_pyo3_attrs(&attrs)? {
             match attr_item {
                 Attr::Name(name) => pyclass_name = Some(name),
@@ -51,6 +52,7 @@ impl PyComplexEnumInfo {
                 Attr::GenStubModule(name) => gen_stub_standalone_module = Some(name),
                 Attr::RenameAll(name) => renaming_rule = Some(name),
                 Attr::Extends(typ) => bases.push(typ),
+                Attr::GenStubNoMatchArgs => match_args = false,
                 _ => {}
             }
         }
@@ -85,7 +87,11 @@ impl PyComplexEnumInfo {
 
         let mut items = Vec::new();
         for variant in variants {
-            items.push(VariantInfo::from_variant(variant, &renaming_rule)?)
+            items.extend(VariantInfo::from_variant(
+                variant,
+                &renaming_rule,
+                match_args,
+            )?)
         }
 
         Ok(Self {
@@ -126,11 +132,24 @@ impl ToTokens for PyComplexEnumInfo {
                 variants: &[ #( #variants ),* ],
                 module: #module,
                 doc: #doc,
+                file: file!(),
+                line: line!(),
+                column: column!(),
             }
         })
     }
 }
 
+pub fn prune_attrs(item_enum: &mut ItemEnum) {
+    super::attr::prune_attrs(&mut item_enum.attrs);
+    for variant in item_enum.variants.iter_mut() {
+        super::attr::prune_attrs(&mut variant.attrs);
+        for field in variant.fields.iter_mut() {
+            super::attr::prune_attrs(&mut field.attrs);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -165,10 +184,14 @@ mod test {
                     fields: &[
                         ::pyo3_stub_gen::type_info::MemberInfo {
                             name: "_0",
-                            r#type: <String as ::pyo3_stub_gen::PyStubType>::type_output,
+                            r#type: || {
+                                ::pyo3_stub_gen::StubTypeOf::<String>::new().type_output()
+                            },
                             doc: "",
                             default: None,
+                            default_symbol: None,
                             deprecated: None,
+                            cached: false,
                         },
                     ],
                     module: None,
@@ -178,27 +201,34 @@ mod test {
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "_0",
                             kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
-                            type_info: <String as ::pyo3_stub_gen::PyStubType>::type_input,
+                            type_info: || {
+                                ::pyo3_stub_gen::StubTypeOf::<String>::new().type_input()
+                            },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                         },
                     ],
+                    match_args: true,
                 },
                 ::pyo3_stub_gen::type_info::VariantInfo {
                     pyclass_name: "twonum",
                     fields: &[
                         ::pyo3_stub_gen::type_info::MemberInfo {
                             name: "_0",
-                            r#type: <i32 as ::pyo3_stub_gen::PyStubType>::type_output,
+                            r#type: || ::pyo3_stub_gen::StubTypeOf::<i32>::new().type_output(),
                             doc: "",
                             default: None,
+                            default_symbol: None,
                             deprecated: None,
+                            cached: false,
                         },
                         ::pyo3_stub_gen::type_info::MemberInfo {
                             name: "_1",
-                            r#type: <f64 as ::pyo3_stub_gen::PyStubType>::type_output,
+                            r#type: || ::pyo3_stub_gen::StubTypeOf::<f64>::new().type_output(),
                             doc: "",
                             default: None,
+                            default_symbol: None,
                             deprecated: None,
+                            cached: false,
                         },
                     ],
                     module: None,
@@ -208,13 +238,13 @@ mod test {
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "_0",
                             kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
-                            type_info: <i32 as ::pyo3_stub_gen::PyStubType>::type_input,
+                            type_info: || ::pyo3_stub_gen::StubTypeOf::<i32>::new().type_input(),
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                         },
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "_1",
                             kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
-                            type_info: <f64 as ::pyo3_stub_gen::PyStubType>::type_input,
+                            type_info: || ::pyo3_stub_gen::StubTypeOf::<f64>::new().type_input(),
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::Expr {
                                 value: {
                                     fn _fmt() -> String {
@@ -227,7 +257,8 @@ mod test {
                                 },
                                 source_module: Some({
                                     fn _get_module() -> Option<::pyo3_stub_gen::ModuleRef> {
-                                        <f64 as ::pyo3_stub_gen::PyStubType>::type_output()
+                                        ::pyo3_stub_gen::StubTypeOf::<f64>::new()
+                                            .type_output()
                                             .source_module
                                     }
                                     _get_module
@@ -235,16 +266,19 @@ mod test {
                             },
                         },
                     ],
+                    match_args: true,
                 },
                 ::pyo3_stub_gen::type_info::VariantInfo {
                     pyclass_name: "ndim",
                     fields: &[
                         ::pyo3_stub_gen::type_info::MemberInfo {
                             name: "count",
-                            r#type: <usize as ::pyo3_stub_gen::PyStubType>::type_output,
+                            r#type: || ::pyo3_stub_gen::StubTypeOf::<usize>::new().type_output(),
                             doc: "",
                             default: None,
+                            default_symbol: None,
                             deprecated: None,
+                            cached: false,
                         },
                     ],
                     module: None,
@@ -254,10 +288,13 @@ mod test {
                         ::pyo3_stub_gen::type_info::ParameterInfo {
                             name: "count",
                             kind: ::pyo3_stub_gen::type_info::ParameterKind::PositionalOrKeyword,
-                            type_info: <usize as ::pyo3_stub_gen::PyStubType>::type_input,
+                            type_info: || {
+                                ::pyo3_stub_gen::StubTypeOf::<usize>::new().type_input()
+                            },
                             default: ::pyo3_stub_gen::type_info::ParameterDefault::None,
                         },
                     ],
+                    match_args: true,
                 },
                 ::pyo3_stub_gen::type_info::VariantInfo {
                     pyclass_name: "description",
@@ -266,15 +303,146 @@ mod test {
                     doc: "",
                     form: &pyo3_stub_gen::type_info::VariantForm::Unit,
                     constr_args: &[],
+                    match_args: true,
                 },
             ],
             module: Some("my_module"),
             doc: "",
+            file: file!(),
+            line: line!(),
+            column: column!(),
         }
         "###);
         Ok(())
     }
 
+    #[test]
+    fn test_variant_default_matches_constructor() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            #[pyclass]
+            pub enum Number {
+                #[pyo3(constructor = (int=2))]
+                Integer {
+                    #[gen_stub(default = 2)]
+                    int: i32,
+                },
+            }
+            "#,
+        )?;
+        let out = PyComplexEnumInfo::try_from(input)?
+            .to_token_stream()
+            .to_string();
+        assert!(out.contains("let v : i32 = 2"), "{out}");
+
+        for invalid in [
+            // Differs from the constructor
+            r#"
+            #[pyclass]
+            pub enum Number {
+                #[pyo3(constructor = (int=3))]
+                Integer {
+                    #[gen_stub(default = 2)]
+                    int: i32,
+                },
+            }
+            "#,
+            // No default in the constructor
+            r#"
+            #[pyclass]
+            pub enum Number {
+                Integer {
+                    #[gen_stub(default = 2)]
+                    int: i32,
+                },
+            }
+            "#,
+        ] {
+            let input: ItemEnum = parse_str(invalid)?;
+            assert!(PyComplexEnumInfo::try_from(input).is_err(), "{invalid}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_variant_skip() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            #[pyclass(rename_all = "UPPERCASE")]
+            pub enum Shape {
+                Circle { radius: f64 },
+                #[gen_stub(skip)]
+                Internal { id: u64 },
+            }
+            "#,
+        )?;
+        let out = PyComplexEnumInfo::try_from(input)?
+            .to_token_stream()
+            .to_string();
+        assert!(out.contains(r#"pyclass_name : "CIRCLE""#), "{out}");
+        assert!(!out.contains("INTERNAL"), "{out}");
+
+        // The name of the class is given by `#[pyo3(name = "...")]` so that it matches the runtime
+        let input: ItemEnum = parse_str(
+            r#"
+            #[pyclass]
+            pub enum Shape {
+                #[gen_stub(name = "Square")]
+                Rect { side: f64 },
+            }
+            "#,
+        )?;
+        let err = PyComplexEnumInfo::try_from(input).unwrap_err();
+        assert!(err.to_string().contains("#[pyo3(name"), "{err}");
+
+        let input: ItemEnum = parse_str(
+            r#"
+            #[pyclass]
+            pub enum Shape {
+                #[gen_stub(hidden)]
+                Circle { radius: f64 },
+            }
+            "#,
+        )?;
+        assert!(PyComplexEnumInfo::try_from(input).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_match_args() -> Result<()> {
+        let input: ItemEnum = parse_str(
+            r#"
+            #[pyclass]
+            pub enum Shape {
+                Circle { radius: f64 },
+                #[gen_stub(no_match_args)]
+                Square { side: f64 },
+            }
+            "#,
+        )?;
+        let out = PyComplexEnumInfo::try_from(input)?
+            .to_token_stream()
+            .to_string();
+        assert_eq!(out.matches("match_args : true").count(), 1, "{out}");
+        assert_eq!(out.matches("match_args : false").count(), 1, "{out}");
+
+        let input: ItemEnum = parse_str(
+            r#"
+            #[pyclass]
+            #[gen_stub(no_match_args)]
+            pub enum Shape {
+                Circle { radius: f64 },
+                Square { side: f64 },
+            }
+            "#,
+        )?;
+        let out = PyComplexEnumInfo::try_from(input)?
+            .to_token_stream()
+            .to_string();
+        assert!(!out.contains("match_args : true"), "{out}");
+        Ok(())
+    }
+
     fn format_as_value(tt: TokenStream2) -> String {
         let ttt = quote! { const _: () = #tt; };
         let formatted = prettyplease::unparse(&syn::parse_file(&ttt.to_string()).unwrap());
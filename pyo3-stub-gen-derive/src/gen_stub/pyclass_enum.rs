@@ -137,6 +137,9 @@ impl ToTokens for PyEnumInfo {
                 variants: &[ #(#variants),* ],
                 module: #module,
                 doc: #doc,
+                file: file!(),
+                line: line!(),
+                column: column!(),
             }
         })
     }
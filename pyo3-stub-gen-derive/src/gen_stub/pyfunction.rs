@@ -2,33 +2,255 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
     parse::{Parse, ParseStream},
-    Error, FnArg, ItemFn, Result,
+    parse_quote, Error, FnArg, GenericArgument, ItemFn, Pat, PathArguments, Result, ReturnType,
+    Type, TypePath,
 };
 
-use crate::gen_stub::util::TypeOrOverride;
+use crate::gen_stub::util::{
+    builtin_exception_name, extract_error_type, marker_types_and_fns, stub_type_of, TypeDirection,
+    TypeOrOverride,
+};
 
 use super::{
-    attr::IgnoreTarget, extract_deprecated, extract_documents, extract_return_type,
-    parameter::Parameters, parse_args, parse_gen_stub_type_ignore, parse_pyo3_attrs, parse_python,
-    quote_option, Attr, DeprecatedInfo,
+    attr::{AttributeLocation, IgnoreTarget, NamedTupleAttr},
+    extract_deprecated, extract_documents, extract_return_type,
+    parameter::Parameters,
+    parse_args, parse_gen_stub_named_tuple, parse_gen_stub_platform, parse_gen_stub_py_version,
+    parse_gen_stub_raises, parse_gen_stub_releases_gil, parse_gen_stub_type_ignore,
+    parse_gen_stub_var_arg_types, parse_pyo3_attrs, parse_python, quote_option, Attr,
+    DeprecatedInfo,
 };
 
+#[derive(Clone)]
 pub struct PyFunctionInfo {
     pub(crate) name: String,
     pub(crate) parameters: Parameters,
     pub(crate) r#return: Option<TypeOrOverride>,
+    /// `E` of `Result<T, E>` returned by the function
+    pub(crate) error: Option<Type>,
+    /// Exceptions listed by `#[gen_stub(raises(...))]`
+    pub(crate) raises: Vec<Type>,
     pub(crate) doc: String,
     pub(crate) module: Option<String>,
     pub(crate) is_async: bool,
     pub(crate) deprecated: Option<DeprecatedInfo>,
     pub(crate) type_ignored: Option<IgnoreTarget>,
     pub(crate) is_overload: bool,
+    /// `sys.platform` given by `#[gen_stub(platform = "...")]`
+    pub(crate) platform: Option<String>,
+    /// Python versions given by `#[gen_stub(py_version = "...")]`
+    pub(crate) py_version: Option<String>,
+    /// Given by `#[gen_stub(releases_gil)]`
+    pub(crate) releases_gil: bool,
+    /// `typing.NamedTuple` returned instead of the tuple, given by `#[gen_stub(named_tuple(...))]`
+    pub(crate) named_tuple: Option<NamedTuple>,
     pub(crate) index: usize,
 }
 
+/// `typing.NamedTuple` whose fields are typed by the elements of the returned Rust tuple
+#[derive(Clone)]
+pub(crate) struct NamedTuple {
+    name: String,
+    fields: Vec<(String, Type)>,
+}
+
+impl NamedTuple {
+    fn new(
+        attr: NamedTupleAttr,
+        output: &ReturnType,
+        r#return: Option<&TypeOrOverride>,
+    ) -> Result<Self> {
+        let elements = match r#return {
+            Some(TypeOrOverride::RustType { r#type }) => tuple_elements(r#type),
+            Some(TypeOrOverride::OverrideType { .. }) => {
+                return Err(Error::new(
+                    attr.name.span(),
+                    "`named_tuple` cannot be used together with an overridden return type",
+                ))
+            }
+            None => None,
+        };
+        let Some(elements) = elements else {
+            return Err(Error::new_spanned(
+                output,
+                "`named_tuple` requires the function to return a tuple",
+            ));
+        };
+        if elements.len() != attr.fields.len() {
+            return Err(Error::new(
+                attr.name.span(),
+                format!(
+                    "`named_tuple` has {} fields, but the function returns a tuple of {} elements",
+                    attr.fields.len(),
+                    elements.len()
+                ),
+            ));
+        }
+        Ok(Self {
+            name: attr.name.value(),
+            fields: attr
+                .fields
+                .iter()
+                .map(|field| field.value())
+                .zip(elements)
+                .collect(),
+        })
+    }
+
+    /// `NamedTupleInfo` submitted to `module` together with the function
+    fn info_tokens(&self, module: &Option<String>) -> TokenStream2 {
+        let name = &self.name;
+        let module_tt = quote_option(module);
+        let fields = self.fields.iter().map(|(field, ty)| {
+            let stub_type = stub_type_of(ty);
+            quote! {
+                ::pyo3_stub_gen::type_info::NamedTupleFieldInfo {
+                    name: #field,
+                    r#type: || #stub_type.type_output(),
+                }
+            }
+        });
+        quote! {
+            ::pyo3_stub_gen::type_info::NamedTupleInfo {
+                module: #module_tt,
+                name: #name,
+                doc: "",
+                fields: &[ #( #fields ),* ],
+            }
+        }
+    }
+}
+
+/// Elements of a tuple type, looking through `Result<T, E>` which is not unwrapped like `PyResult<T>`
+/// Make the function return `pyo3_stub_gen::runtime::NamedTuple` of the returned tuple,
+/// which is converted into the `collections.namedtuple` class at runtime.
+///
+/// The original function is kept as an inner function called with the same arguments,
+/// so that `return` and `?` in its body work as written.
+fn return_named_tuple(
+    item_fn: &mut ItemFn,
+    named_tuple: &NamedTuple,
+    module: &Option<String>,
+) -> Result<()> {
+    if let Some(asyncness) = &item_fn.sig.asyncness {
+        return Err(Error::new_spanned(
+            asyncness,
+            "`named_tuple` is not supported on async functions",
+        ));
+    }
+    let mut inner = item_fn.clone();
+    inner.attrs.clear();
+    inner.vis = syn::Visibility::Inherited;
+    inner.sig.ident = syn::Ident::new("__pyo3_stub_gen_inner", item_fn.sig.ident.span());
+
+    let mut args = Vec::new();
+    for (arg, inner_arg) in item_fn
+        .sig
+        .inputs
+        .iter_mut()
+        .zip(inner.sig.inputs.iter_mut())
+    {
+        // `self` is rejected by `#[pyfunction]`
+        let (FnArg::Typed(arg), FnArg::Typed(inner_arg)) = (arg, inner_arg) else {
+            continue;
+        };
+        match arg.pat.as_mut() {
+            Pat::Ident(pat) => {
+                // `mut` is only needed in the inner function using the argument
+                pat.mutability = None;
+                args.push(pat.ident.clone());
+            }
+            pat => {
+                return Err(Error::new_spanned(
+                    pat,
+                    "`named_tuple` requires arguments to be identifiers",
+                ))
+            }
+        }
+        inner_arg.attrs.clear();
+    }
+
+    let ReturnType::Type(_, ty) = &mut item_fn.sig.output else {
+        unreachable!("`NamedTuple::new` checks that the function returns a tuple")
+    };
+    let in_result = wrap_returned_tuple(ty);
+    let name = &named_tuple.name;
+    let fields = named_tuple.fields.iter().map(|(field, _)| field);
+    let module = quote_option(module);
+    let new = quote! {
+        ::pyo3_stub_gen::runtime::NamedTuple::new(#module, #name, &[#(#fields),*], values)
+    };
+    let body = if in_result {
+        quote! { __pyo3_stub_gen_inner(#(#args),*).map(|values| #new) }
+    } else {
+        quote! {{ let values = __pyo3_stub_gen_inner(#(#args),*); #new }}
+    };
+    item_fn.block = parse_quote! {{
+        #inner
+        #body
+    }};
+    Ok(())
+}
+
+/// Replace the tuple `T` returned as `T` or `Result<T, E>` by `NamedTuple<T>`,
+/// returning whether it is in `Result`
+fn wrap_returned_tuple(ty: &mut Type) -> bool {
+    match ty {
+        Type::Tuple(tuple) => {
+            let tuple = tuple.clone();
+            *ty = parse_quote! { ::pyo3_stub_gen::runtime::NamedTuple<#tuple> };
+            false
+        }
+        Type::Paren(paren) => wrap_returned_tuple(&mut paren.elem),
+        Type::Group(group) => wrap_returned_tuple(&mut group.elem),
+        Type::Path(TypePath { path, .. }) => {
+            if let Some(PathArguments::AngleBracketed(inner)) =
+                path.segments.last_mut().map(|last| &mut last.arguments)
+            {
+                if let Some(GenericArgument::Type(ty)) = inner
+                    .args
+                    .iter_mut()
+                    .find(|arg| matches!(arg, GenericArgument::Type(_)))
+                {
+                    wrap_returned_tuple(ty);
+                }
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+fn tuple_elements(ty: &Type) -> Option<Vec<Type>> {
+    match ty {
+        Type::Tuple(tuple) if !tuple.elems.is_empty() => {
+            Some(tuple.elems.iter().cloned().collect())
+        }
+        Type::Paren(paren) => tuple_elements(&paren.elem),
+        Type::Group(group) => tuple_elements(&group.elem),
+        Type::Path(TypePath { path, .. }) => {
+            let last = path.segments.last()?;
+            if last.ident != "Result" {
+                return None;
+            }
+            let PathArguments::AngleBracketed(inner) = &last.arguments else {
+                return None;
+            };
+            inner.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(ty) => tuple_elements(ty),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct PyFunctionAttr {
     pub(crate) module: Option<String>,
+    /// Modules other than `module` the function is also added to,
+    /// given by `module = ["pkg", "pkg.sub"]` or a repeated `module`
+    pub(crate) additional_modules: Vec<String>,
     pub(crate) python: Option<syn::LitStr>,
     pub(crate) python_overload: Option<syn::LitStr>,
     pub(crate) no_default_overload: bool,
@@ -36,7 +258,7 @@ pub(crate) struct PyFunctionAttr {
 
 impl Parse for PyFunctionAttr {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut module = None;
+        let mut modules: Vec<syn::LitStr> = Vec::new();
         let mut python = None;
         let mut python_overload = None;
         let mut no_default_overload = false;
@@ -48,8 +270,21 @@ impl Parse for PyFunctionAttr {
             match key.to_string().as_str() {
                 "module" => {
                     let _: syn::token::Eq = input.parse()?;
-                    let value: syn::LitStr = input.parse()?;
-                    module = Some(value.value());
+                    if input.peek(syn::token::Bracket) {
+                        let content;
+                        syn::bracketed!(content in input);
+                        let values = content
+                            .parse_terminated(<syn::LitStr as Parse>::parse, syn::token::Comma)?;
+                        if values.is_empty() {
+                            return Err(Error::new(
+                                key.span(),
+                                "`module` list must contain at least one module",
+                            ));
+                        }
+                        modules.extend(values);
+                    } else {
+                        modules.push(input.parse()?);
+                    }
                 }
                 "python" => {
                     let _: syn::token::Eq = input.parse()?;
@@ -99,8 +334,21 @@ impl Parse for PyFunctionAttr {
             ));
         }
 
+        for (i, module) in modules.iter().enumerate() {
+            if modules[..i].iter().any(|m| m.value() == module.value()) {
+                return Err(Error::new(
+                    module.span(),
+                    format!("Module `{}` is specified more than once", module.value()),
+                ));
+            }
+        }
+        let mut modules = modules.into_iter().map(|m| m.value());
+        let module = modules.next();
+        let additional_modules = modules.collect();
+
         Ok(Self {
             module,
+            additional_modules,
             python,
             python_overload,
             no_default_overload,
@@ -113,9 +361,16 @@ impl TryFrom<ItemFn> for PyFunctionInfo {
     fn try_from(item: ItemFn) -> Result<Self> {
         let doc = extract_documents(&item.attrs).join("\n");
         let deprecated = extract_deprecated(&item.attrs);
-        let type_ignored = parse_gen_stub_type_ignore(&item.attrs)?;
+        let type_ignored = parse_gen_stub_type_ignore(&item.attrs, AttributeLocation::Function)?;
+        let (var_args_type, kwargs_value_type) =
+            parse_gen_stub_var_arg_types(&item.attrs, AttributeLocation::Function)?;
         let args = parse_args(item.sig.inputs)?;
         let r#return = extract_return_type(&item.sig.output, &item.attrs)?;
+        let error = extract_error_type(&item.sig.output);
+        let raises = parse_gen_stub_raises(&item.attrs, AttributeLocation::Function)?;
+        let named_tuple = parse_gen_stub_named_tuple(&item.attrs, AttributeLocation::Function)?
+            .map(|attr| NamedTuple::new(attr, &item.sig.output, r#return.as_ref()))
+            .transpose()?;
         let mut name = None;
         let mut sig = None;
         let mut pyo3_module = None;
@@ -130,22 +385,29 @@ impl TryFrom<ItemFn> for PyFunctionInfo {
         let name = name.unwrap_or_else(|| item.sig.ident.to_string());
 
         // Build parameters from args and signature
-        let parameters = if let Some(sig) = sig {
+        let mut parameters = if let Some(sig) = sig {
             Parameters::new_with_sig(&args, &sig)?
         } else {
             Parameters::new(&args)
         };
+        parameters.set_var_arg_types(var_args_type, kwargs_value_type);
 
         Ok(Self {
             name,
             parameters,
             r#return,
+            error,
+            raises,
             doc,
             module: pyo3_module,
             is_async: item.sig.asyncness.is_some(),
             deprecated,
             type_ignored,
             is_overload: false, // Default to false, will be set by macro if needed
+            platform: None,     // Set by `PyFunctionInfos::from_parts` for all variants
+            py_version: None,   // Same as `platform`
+            releases_gil: false, // Same as `platform`
+            named_tuple,
             index: 0, // Default to 0, will be set by macro if multiple functions are generated
         })
     }
@@ -155,6 +417,8 @@ impl ToTokens for PyFunctionInfo {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let Self {
             r#return: ret,
+            error,
+            raises,
             name,
             doc,
             parameters,
@@ -163,13 +427,25 @@ impl ToTokens for PyFunctionInfo {
             deprecated,
             type_ignored,
             is_overload,
+            platform,
+            py_version,
+            releases_gil,
+            named_tuple,
             index,
         } = self;
-        let ret_tt = if let Some(ret) = ret {
+        let ret_tt = if let Some(named_tuple) = named_tuple {
+            let name = &named_tuple.name;
+            let module_tt = if let Some(module) = module {
+                quote! { #module.into() }
+            } else {
+                quote! { Default::default() }
+            };
+            quote! { || ::pyo3_stub_gen::TypeInfo::locally_defined(#name, #module_tt) }
+        } else if let Some(ret) = ret {
             match ret {
                 TypeOrOverride::RustType { r#type } => {
-                    let ty = r#type.clone();
-                    quote! { <#ty as pyo3_stub_gen::PyStubType>::type_output }
+                    let stub_type = stub_type_of(r#type);
+                    quote! { || #stub_type.type_output() }
                 }
                 TypeOrOverride::OverrideType {
                     type_repr,
@@ -179,6 +455,9 @@ impl ToTokens for PyFunctionInfo {
                 } => {
                     let imports = imports.iter().collect::<Vec<&String>>();
 
+                    let (marker_types, marker_fns) =
+                        marker_types_and_fns(rust_type_markers, TypeDirection::Output);
+
                     // Generate code to process RustType markers
                     let (type_name_code, type_refs_code) = if rust_type_markers.is_empty() {
                         (
@@ -186,19 +465,17 @@ impl ToTokens for PyFunctionInfo {
                             quote! { ::std::collections::HashMap::new() },
                         )
                     } else {
-                        let marker_types: Vec<syn::Type> = rust_type_markers
+                        let rust_names = rust_type_markers
                             .iter()
-                            .filter_map(|s| syn::parse_str(s).ok())
-                            .collect();
-
-                        let rust_names = rust_type_markers.iter().collect::<Vec<_>>();
+                            .map(|marker| &marker.rust_type)
+                            .collect::<Vec<_>>();
 
                         (
                             quote! {
                                 {
                                     let mut type_name = #type_repr.to_string();
                                     #(
-                                        let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::type_input();
+                                        let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                         type_name = type_name.replace(#rust_names, &type_info.name);
                                     )*
                                     type_name
@@ -208,7 +485,7 @@ impl ToTokens for PyFunctionInfo {
                                 {
                                     let mut type_refs = ::std::collections::HashMap::new();
                                     #(
-                                        let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::type_input();
+                                        let type_info = <#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns();
                                         if let Some(module) = type_info.source_module {
                                             type_refs.insert(
                                                 type_info.name.split('[').next().unwrap_or(&type_info.name).split('.').last().unwrap_or(&type_info.name).to_string(),
@@ -227,7 +504,7 @@ impl ToTokens for PyFunctionInfo {
                     };
 
                     quote! {
-                        || ::pyo3_stub_gen::TypeInfo { name: #type_name_code, source_module: None, import: ::std::collections::HashSet::from([#(#imports.into(),)*]), type_refs: #type_refs_code }
+                        || ::pyo3_stub_gen::TypeInfo { name: #type_name_code, source_module: None, import: { let mut import = ::std::collections::HashSet::from([#(#imports.into(),)*]); #(import.extend(<#marker_types as ::pyo3_stub_gen::PyStubType>::#marker_fns().import);)* import }, type_refs: #type_refs_code, any_fallback: None, }
                     }
                 }
             }
@@ -235,40 +512,48 @@ impl ToTokens for PyFunctionInfo {
             quote! { ::pyo3_stub_gen::type_info::no_return_type_output }
         };
         // let sig_tt = quote_option(sig);
+        let error_tt = if let Some(error) = error {
+            quote! { Some(::std::any::TypeId::of::<#error>) }
+        } else {
+            quote! { None }
+        };
+        let raises_tt = raises.iter().map(|ty| match builtin_exception_name(ty) {
+            Some(name) => quote! { ::pyo3_stub_gen::type_info::RaisesInfo::Builtin(#name) },
+            None => {
+                let name = ty.to_token_stream().to_string().replace(' ', "");
+                quote! {
+                    ::pyo3_stub_gen::type_info::RaisesInfo::Type {
+                        id: ::std::any::TypeId::of::<#ty>,
+                        name: #name,
+                    }
+                }
+            }
+        });
         let module_tt = quote_option(module);
         let deprecated_tt = deprecated
             .as_ref()
             .map(|d| quote! { Some(#d) })
             .unwrap_or_else(|| quote! { None });
-        let type_ignored_tt = if let Some(target) = type_ignored {
-            match target {
-                IgnoreTarget::All => {
-                    quote! { Some(::pyo3_stub_gen::type_info::IgnoreTarget::All) }
-                }
-                IgnoreTarget::SpecifiedLits(rules) => {
-                    let rule_strs: Vec<String> = rules.iter().map(|lit| lit.value()).collect();
-                    quote! {
-                        Some(::pyo3_stub_gen::type_info::IgnoreTarget::Specified(
-                            &[#(#rule_strs),*] as &[&str]
-                        ))
-                    }
-                }
-            }
-        } else {
-            quote! { None }
-        };
+        let type_ignored_tt = quote_option(type_ignored);
+        let platform_tt = quote_option(platform);
+        let py_version_tt = quote_option(py_version);
 
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyFunctionInfo {
                 name: #name,
                 parameters: #parameters,
                 r#return: #ret_tt,
+                error: #error_tt,
+                raises: &[ #( #raises_tt ),* ],
                 doc: #doc,
                 module: #module_tt,
                 is_async: #is_async,
                 deprecated: #deprecated_tt,
                 type_ignored: #type_ignored_tt,
                 is_overload: #is_overload,
+                platform: #platform_tt,
+                py_version: #py_version_tt,
+                releases_gil: #releases_gil,
                 file: file!(),
                 line: line!(),
                 column: column!(),
@@ -324,9 +609,25 @@ impl PyFunctionInfos {
             }
         }
 
+        let platform = parse_gen_stub_platform(&item_fn.attrs, AttributeLocation::Function)?
+            .map(|lit| lit.value());
+        let py_version = parse_gen_stub_py_version(&item_fn.attrs, AttributeLocation::Function)?
+            .map(|lit| lit.value());
+        let releases_gil =
+            parse_gen_stub_releases_gil(&item_fn.attrs, AttributeLocation::Function)?;
+
         // Handle python stub syntax early (doesn't need base_info)
         if let Some(python) = attr.python {
+            if parse_gen_stub_named_tuple(&item_fn.attrs, AttributeLocation::Function)?.is_some() {
+                return Err(Error::new(
+                    python.span(),
+                    "`named_tuple` cannot be used with `python`, declare the return type in the Python stub instead",
+                ));
+            }
             let mut python_info = parse_python::parse_python_function_stub(python)?;
+            python_info.platform = platform;
+            python_info.py_version = py_version;
+            python_info.releases_gil = releases_gil;
             // Priority: inline > standalone > pyo3 (pyo3 already in python_info from python stub)
             python_info.module = if let Some(inline_mod) = attr.module {
                 Some(inline_mod) // Priority 1
@@ -338,7 +639,7 @@ impl PyFunctionInfos {
             prune_attrs(&mut item_fn);
             return Ok(Self {
                 item_fn,
-                infos: vec![python_info],
+                infos: with_additional_modules(vec![python_info], &attr.additional_modules),
             });
         }
 
@@ -383,14 +684,43 @@ impl PyFunctionInfos {
             // No python or python_overload, use auto-generated
             vec![base_info]
         };
+        let infos = infos
+            .into_iter()
+            .map(|info| PyFunctionInfo {
+                platform: platform.clone(),
+                py_version: py_version.clone(),
+                releases_gil,
+                ..info
+            })
+            .collect();
 
         // Prune attributes from ItemFn
         prune_attrs(&mut item_fn);
 
+        if let Some((named_tuple, module)) = infos.iter().find_map(|info| {
+            let named_tuple = info.named_tuple.as_ref()?;
+            Some((named_tuple, &info.module))
+        }) {
+            return_named_tuple(&mut item_fn, named_tuple, module)?;
+        }
+
+        let infos = with_additional_modules(infos, &attr.additional_modules);
         Ok(Self { item_fn, infos })
     }
 }
 
+/// Copy `infos` into each of `modules`, so that the function is written to all of their stubs
+fn with_additional_modules(infos: Vec<PyFunctionInfo>, modules: &[String]) -> Vec<PyFunctionInfo> {
+    let mut all = infos.clone();
+    for module in modules {
+        all.extend(infos.iter().cloned().map(|mut info| {
+            info.module = Some(module.clone());
+            info
+        }));
+    }
+    all
+}
+
 impl ToTokens for PyFunctionInfos {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let item_fn = &self.item_fn;
@@ -398,16 +728,54 @@ impl ToTokens for PyFunctionInfos {
 
         // Generate multiple submit! blocks
         let submits = infos.iter().map(|info| {
+            let named_tuple = info.named_tuple.as_ref().map(|named_tuple| {
+                let named_tuple_info = named_tuple.info_tokens(&info.module);
+                quote! {
+                    #[automatically_derived]
+                    ::pyo3_stub_gen::submit! {
+                        #named_tuple_info
+                    }
+                }
+            });
+            let dict_keys = info
+                .parameters
+                .dict_keys_infos(None, &info.module, &info.name);
             quote! {
                 #[automatically_derived]
-                pyo3_stub_gen::inventory::submit! {
+                ::pyo3_stub_gen::submit! {
                     #info
                 }
+                #named_tuple
+                #(
+                    #[automatically_derived]
+                    ::pyo3_stub_gen::submit! {
+                        #dict_keys
+                    }
+                )*
             }
         });
 
+        // Once for functions in several modules, since links to them would be ambiguous otherwise
+        let rust_path = infos.first().map(|info| {
+            let ident = &item_fn.sig.ident;
+            let name = &info.name;
+            let module = quote_option(&info.module);
+            quote! {
+                #[automatically_derived]
+                ::pyo3_stub_gen::submit! {
+                    ::pyo3_stub_gen::type_info::RustPathInfo {
+                        rust_path: || ::std::any::type_name_of_val(&#ident),
+                        target: ::pyo3_stub_gen::type_info::RustPathTarget::Function {
+                            module: #module,
+                            name: #name,
+                        },
+                    }
+                }
+            }
+        });
+        let submits = quote! { #(#submits)* #rust_path };
         tokens.append_all(quote! {
-            #(#submits)*
+            #submits
             #item_fn
         })
     }
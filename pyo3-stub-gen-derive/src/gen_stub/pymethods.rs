@@ -2,11 +2,16 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{Error, FnArg, ImplItem, ItemImpl, Result, Type};
 
-use super::{attr::parse_gen_stub_skip, MemberInfo, MethodInfo};
+use super::{
+    attr::{parse_gen_stub_skip, AttributeLocation},
+    quote_option, MemberInfo, MethodInfo,
+};
 
 #[derive(Debug)]
 pub struct PyMethodsInfo {
     pub(crate) struct_id: Type,
+    /// Python class name and its module to attach the methods to instead of `struct_id`
+    pub(crate) python_class: Option<(String, Option<String>)>,
     pub(crate) attrs: Vec<MemberInfo>,
     pub(crate) getters: Vec<MemberInfo>,
     pub(crate) setters: Vec<MemberInfo>,
@@ -24,7 +29,7 @@ impl TryFrom<ItemImpl> for PyMethodsInfo {
         for inner in item.items.into_iter() {
             match inner {
                 ImplItem::Const(item_const) => {
-                    if parse_gen_stub_skip(&item_const.attrs)? {
+                    if parse_gen_stub_skip(&item_const.attrs, AttributeLocation::Field)? {
                         continue;
                     }
                     if MemberInfo::is_classattr(&item_const.attrs)? {
@@ -32,7 +37,7 @@ impl TryFrom<ItemImpl> for PyMethodsInfo {
                     }
                 }
                 ImplItem::Fn(item_fn) => {
-                    if parse_gen_stub_skip(&item_fn.attrs)? {
+                    if parse_gen_stub_skip(&item_fn.attrs, AttributeLocation::Method)? {
                         continue;
                     }
                     if MemberInfo::is_getter(&item_fn.attrs)? {
@@ -56,6 +61,7 @@ impl TryFrom<ItemImpl> for PyMethodsInfo {
         }
         Ok(Self {
             struct_id,
+            python_class: None,
             attrs,
             getters,
             setters,
@@ -68,14 +74,24 @@ impl ToTokens for PyMethodsInfo {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let Self {
             struct_id,
+            python_class,
             attrs,
             getters,
             setters,
             methods,
         } = self;
+        let python_class_tt = if let Some((name, module)) = python_class {
+            let module = quote_option(module);
+            quote! {
+                Some(::pyo3_stub_gen::type_info::PythonClassRef { name: #name, module: #module })
+            }
+        } else {
+            quote! { None }
+        };
         tokens.append_all(quote! {
             ::pyo3_stub_gen::type_info::PyMethodsInfo {
                 struct_id: std::any::TypeId::of::<#struct_id>,
+                python_class: #python_class_tt,
                 attrs: &[ #(#attrs),* ],
                 getters: &[ #(#getters),* ],
                 setters: &[ #(#setters),* ],
@@ -88,6 +104,20 @@ impl ToTokens for PyMethodsInfo {
     }
 }
 
+impl PyMethodsInfo {
+    /// `DictKeysInfo` of the method parameters with `#[gen_stub(dict_keys(...))]`
+    pub(crate) fn dict_keys_infos(&self) -> Vec<TokenStream2> {
+        self.methods
+            .iter()
+            .flat_map(|method| {
+                method
+                    .parameters
+                    .dict_keys_infos(Some(&self.struct_id), &None, &method.name)
+            })
+            .collect()
+    }
+}
+
 // `#[gen_stub(xxx)]` is not a valid proc_macro_attribute
 // it's only designed to receive user's setting.
 // We need to remove all `#[gen_stub(xxx)]` before print the item_impl back
@@ -0,0 +1,297 @@
+use indexmap::IndexMap;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{
+    Expr, ExprCall, ExprMacro, ExprMethodCall, FnArg, GenericArgument, Ident, ItemFn, Lit, Pat,
+    Result, Stmt, Type,
+};
+
+use super::{parse_pyo3_attrs, Attr};
+
+/// Module created in the body of `#[pymodule]` function, e.g. `let sub = PyModule::new(py, "sub")?;`
+#[derive(Debug, Default)]
+struct ModuleVar {
+    /// Python name of the module
+    name: String,
+    classes: Vec<Type>,
+    /// Paths of the Rust functions
+    functions: Vec<syn::Path>,
+    /// Variable of the module which this module is added to by `add_submodule`
+    parent: Option<Ident>,
+}
+
+/// Structure of modules registered in `#[pymodule]` function
+#[derive(Debug)]
+pub struct PyModuleInfos {
+    root: String,
+    /// Python modules with their dotted path relative to the root module
+    modules: Vec<(String, ModuleVar)>,
+}
+
+impl TryFrom<&ItemFn> for PyModuleInfos {
+    type Error = syn::Error;
+
+    fn try_from(item: &ItemFn) -> Result<Self> {
+        let mut root = item.sig.ident.to_string();
+        for attr in parse_pyo3_attrs(&item.attrs)? {
+            if let Attr::Name(name) = attr {
+                root = name;
+            }
+        }
+        let Some(root_var) = item.sig.inputs.iter().find_map(|arg| match arg {
+            FnArg::Typed(pat) => match &*pat.pat {
+                Pat::Ident(pat) => Some(pat.ident.clone()),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        }) else {
+            return Err(syn::Error::new_spanned(
+                &item.sig,
+                "#[gen_stub_pymodule] requires the module argument, e.g. `m: &Bound<PyModule>`",
+            ));
+        };
+
+        let mut vars: IndexMap<Ident, ModuleVar> = IndexMap::new();
+        vars.insert(
+            root_var.clone(),
+            ModuleVar {
+                name: root.clone(),
+                ..Default::default()
+            },
+        );
+        for stmt in &item.block.stmts {
+            match stmt {
+                Stmt::Local(local) => {
+                    let (Pat::Ident(pat), Some(init)) = (&local.pat, &local.init) else {
+                        continue;
+                    };
+                    if let Some(name) = new_module_name(&init.expr) {
+                        vars.insert(
+                            pat.ident.clone(),
+                            ModuleVar {
+                                name,
+                                ..Default::default()
+                            },
+                        );
+                    }
+                }
+                Stmt::Expr(expr, _) => {
+                    let Expr::MethodCall(call) = strip_try(expr) else {
+                        continue;
+                    };
+                    let Some(receiver) = ident_of(&call.receiver) else {
+                        continue;
+                    };
+                    if !vars.contains_key(&receiver) {
+                        continue;
+                    }
+                    match call.method.to_string().as_str() {
+                        "add_class" => {
+                            if let Some(ty) = turbofish_type(call) {
+                                vars[&receiver].classes.push(ty);
+                            }
+                        }
+                        "add_function" | "add_wrapped" => {
+                            if let Some(name) = call.args.first().and_then(wrapped_function_path) {
+                                vars[&receiver].functions.push(name);
+                            }
+                        }
+                        "add_submodule" => {
+                            if let Some(child) = call.args.first().and_then(ident_of) {
+                                if let Some(child) = vars.get_mut(&child) {
+                                    child.parent = Some(receiver);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Resolve paths from the root module, skipping modules never added to the root
+        let mut paths: IndexMap<Ident, String> = IndexMap::new();
+        paths.insert(root_var, String::new());
+        loop {
+            let resolved = vars
+                .iter()
+                .filter(|(ident, _)| !paths.contains_key(*ident))
+                .find_map(|(ident, var)| {
+                    let parent_path = paths.get(var.parent.as_ref()?)?;
+                    let path = if parent_path.is_empty() {
+                        var.name.clone()
+                    } else {
+                        format!("{parent_path}.{}", var.name)
+                    };
+                    Some((ident.clone(), path))
+                });
+            let Some((ident, path)) = resolved else {
+                break;
+            };
+            paths.insert(ident, path);
+        }
+        let modules = vars
+            .into_iter()
+            .filter_map(|(ident, var)| Some((paths.get(&ident)?.clone(), var)))
+            .collect();
+        Ok(Self { root, modules })
+    }
+}
+
+impl ToTokens for PyModuleInfos {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let root = &self.root;
+        for (path, var) in &self.modules {
+            let classes = &var.classes;
+            let functions = &var.functions;
+            tokens.append_all(quote! {
+                #[automatically_derived]
+                ::pyo3_stub_gen::submit! {
+                    ::pyo3_stub_gen::type_info::PyModuleInfo {
+                        root: #root,
+                        path: #path,
+                        classes: &[ #(::std::any::TypeId::of::<#classes>),* ],
+                        functions: &[ #(|| ::std::any::type_name_of_val(&#functions)),* ],
+                    }
+                }
+            })
+        }
+    }
+}
+
+fn strip_try(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Try(expr) => strip_try(&expr.expr),
+        Expr::Reference(expr) => strip_try(&expr.expr),
+        Expr::Paren(expr) => strip_try(&expr.expr),
+        expr => expr,
+    }
+}
+
+fn ident_of(expr: &Expr) -> Option<Ident> {
+    match strip_try(expr) {
+        Expr::Path(path) => path.path.get_ident().cloned(),
+        _ => None,
+    }
+}
+
+/// Name given to `PyModule::new(py, "name")`
+fn new_module_name(expr: &Expr) -> Option<String> {
+    let Expr::Call(ExprCall { func, args, .. }) = strip_try(expr) else {
+        return None;
+    };
+    let Expr::Path(func) = &**func else {
+        return None;
+    };
+    let segments: Vec<_> = func.path.segments.iter().map(|s| &s.ident).collect();
+    let [.., ty, method] = segments.as_slice() else {
+        return None;
+    };
+    if *ty != "PyModule" || !(*method == "new" || *method == "new_bound") {
+        return None;
+    }
+    match args.iter().nth(1) {
+        Some(Expr::Lit(lit)) => match &lit.lit {
+            Lit::Str(name) => Some(name.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `T` of `add_class::<T>()`
+fn turbofish_type(call: &ExprMethodCall) -> Option<Type> {
+    call.turbofish
+        .as_ref()?
+        .args
+        .iter()
+        .find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        })
+}
+
+/// Path of the Rust function `f` in `wrap_pyfunction!(f, m)`
+fn wrapped_function_path(expr: &Expr) -> Option<syn::Path> {
+    let Expr::Macro(ExprMacro { mac, .. }) = strip_try(expr) else {
+        return None;
+    };
+    if !mac.path.segments.last()?.ident.eq("wrap_pyfunction") {
+        return None;
+    }
+    mac.parse_body_with(|input: syn::parse::ParseStream| {
+        let path = input.parse()?;
+        input.parse::<proc_macro2::TokenStream>()?;
+        Ok(path)
+    })
+    .ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    fn summary(infos: &PyModuleInfos) -> Vec<(String, Vec<String>, Vec<String>)> {
+        infos
+            .modules
+            .iter()
+            .map(|(path, var)| {
+                (
+                    path.clone(),
+                    var.classes
+                        .iter()
+                        .map(|ty| ty.to_token_stream().to_string())
+                        .collect(),
+                    var.functions
+                        .iter()
+                        .map(|path| path.to_token_stream().to_string())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_module_tree() -> Result<()> {
+        let item: ItemFn = parse_str(
+            r#"
+            #[pymodule]
+            #[pyo3(name = "_core")]
+            fn core_module(m: &Bound<PyModule>) -> PyResult<()> {
+                m.add_class::<A>()?;
+                m.add_function(wrap_pyfunction!(sum, m)?)?;
+                let sub = PyModule::new(m.py(), "sub")?;
+                sub.add_class::<crate::B>()?;
+                let deep = PyModule::new(m.py(), "deep")?;
+                deep.add_function(wrap_pyfunction!(self::helper, &deep)?)?;
+                sub.add_submodule(&deep)?;
+                m.add_submodule(&sub)?;
+                let detached = PyModule::new(m.py(), "detached")?;
+                detached.add_class::<C>()?;
+                Ok(())
+            }
+            "#,
+        )?;
+        let infos = PyModuleInfos::try_from(&item)?;
+        assert_eq!(infos.root, "_core");
+        assert_eq!(
+            summary(&infos),
+            vec![
+                (
+                    "".to_string(),
+                    vec!["A".to_string()],
+                    vec!["sum".to_string()]
+                ),
+                ("sub".to_string(), vec!["crate :: B".to_string()], vec![]),
+                (
+                    "sub.deep".to_string(),
+                    vec![],
+                    vec!["self :: helper".to_string()]
+                ),
+            ]
+        );
+        Ok(())
+    }
+}
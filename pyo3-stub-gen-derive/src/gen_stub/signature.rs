@@ -53,6 +53,37 @@ impl Signature {
     pub(crate) fn args(&self) -> impl Iterator<Item = &SignatureArg> {
         self.args.iter()
     }
+
+    /// Index of the positional-only marker `/` in [Self::args], i.e. the number of positional-only parameters
+    ///
+    /// Returns `None` if there is no `/`. Like Python, `/` must follow at least one parameter,
+    /// appear at most once, and precede `*`, `*args` and `**kwargs`.
+    pub(crate) fn positional_only_end(&self) -> Result<Option<usize>> {
+        let mut end = None;
+        let mut after_star = false;
+        for (index, arg) in self.args.iter().enumerate() {
+            match arg {
+                SignatureArg::Slash(slash) => {
+                    let message = if end.is_some() {
+                        "`/` may appear only once in signature"
+                    } else if after_star {
+                        "`/` must be ahead of `*`, `*args` and `**kwargs` in signature"
+                    } else if index == 0 {
+                        "`/` must follow at least one parameter in signature"
+                    } else {
+                        end = Some(index);
+                        continue;
+                    };
+                    return Err(syn::Error::new_spanned(slash, message));
+                }
+                SignatureArg::Star(_) | SignatureArg::Args(..) | SignatureArg::Keywords(..) => {
+                    after_star = true;
+                }
+                SignatureArg::Ident(_) | SignatureArg::Assign(..) => {}
+            }
+        }
+        Ok(end)
+    }
 }
 
 impl Parse for Signature {
@@ -75,3 +106,24 @@ impl Signature {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_positional_only_end() -> Result<()> {
+        let sig: Signature = syn::parse_str("(x, /, y, *, z)")?;
+        assert_eq!(sig.positional_only_end()?, Some(1));
+        let sig: Signature = syn::parse_str("(a, b = 1, /, *args)")?;
+        assert_eq!(sig.positional_only_end()?, Some(2));
+        let sig: Signature = syn::parse_str("(x, y)")?;
+        assert_eq!(sig.positional_only_end()?, None);
+
+        for invalid in ["(/, x)", "(x, /, y, /)", "(*, x, /)", "(*args, x, /)"] {
+            let sig: Signature = syn::parse_str(invalid)?;
+            assert!(sig.positional_only_end().is_err(), "{invalid}");
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::Type;
+use syn::{parse_quote, Data, DeriveInput, Error, LitStr, Result, Type};
 
 pub struct StubType {
     pub(crate) ty: Type,
@@ -34,3 +34,137 @@ impl ToTokens for StubType {
         })
     }
 }
+
+/// `#[derive(PyStubType)]` of a newtype wrapper
+///
+/// - `#[stub(transparent)]` forwards to the type of the only field
+/// - Otherwise the type is mapped to a class named `#[stub(name = "...")]` or the Rust name,
+///   defined in `#[stub(module = "...")]`, like `#[gen_stub_pyclass]` does
+pub fn derive_stub_type(item: DeriveInput) -> Result<TokenStream2> {
+    let mut transparent = false;
+    let mut name = None;
+    let mut module = None;
+    for attr in &item.attrs {
+        if !attr.path().is_ident("stub") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("transparent") {
+                transparent = true;
+            } else if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("module") {
+                module = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error(
+                    "Unsupported stub attribute, expected `transparent`, `name`, or `module`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    let ident = &item.ident;
+    if !transparent {
+        if !item.generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                &item.generics,
+                "Generic types are supported only with `#[stub(transparent)]`",
+            ));
+        }
+        let stub_type = StubType {
+            ty: parse_quote!(#ident),
+            name: name.unwrap_or_else(|| ident.to_string()),
+            module,
+        };
+        return Ok(stub_type.into_token_stream());
+    }
+    if name.is_some() || module.is_some() {
+        return Err(Error::new_spanned(
+            ident,
+            "`name` and `module` cannot be used with `#[stub(transparent)]`",
+        ));
+    }
+
+    let Data::Struct(data) = &item.data else {
+        return Err(Error::new_spanned(
+            ident,
+            "`#[stub(transparent)]` is supported only for structs",
+        ));
+    };
+    let mut fields = data.fields.iter();
+    let (Some(field), None) = (fields.next(), fields.next()) else {
+        return Err(Error::new_spanned(
+            &data.fields,
+            "`#[stub(transparent)]` requires a struct with exactly one field",
+        ));
+    };
+    let inner = &field.ty;
+    let mut generics = item.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#inner: ::pyo3_stub_gen::PyStubType));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::pyo3_stub_gen::PyStubType for #ident #ty_generics #where_clause {
+            fn type_output() -> ::pyo3_stub_gen::TypeInfo {
+                <#inner as ::pyo3_stub_gen::PyStubType>::type_output()
+            }
+            fn type_input() -> ::pyo3_stub_gen::TypeInfo {
+                <#inner as ::pyo3_stub_gen::PyStubType>::type_input()
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syn::parse_str;
+
+    fn derive(item: &str) -> Result<String> {
+        let tokens = derive_stub_type(parse_str(item)?)?;
+        Ok(tokens.to_string().split_whitespace().collect())
+    }
+
+    #[test]
+    fn test_transparent() -> Result<()> {
+        let out = derive(
+            r#"
+            #[stub(transparent)]
+            struct Meters(f64);
+            "#,
+        )?;
+        assert!(out.contains(
+            "impl::pyo3_stub_gen::PyStubTypeforMeterswheref64:::pyo3_stub_gen::PyStubType"
+        ));
+        assert!(out.contains("<f64as::pyo3_stub_gen::PyStubType>::type_input()"));
+
+        let out = derive(
+            r#"
+            #[stub(transparent)]
+            struct Wrapper<T> { inner: Vec<T> }
+            "#,
+        )?;
+        assert!(out.contains("impl<T>::pyo3_stub_gen::PyStubTypeforWrapper<T>whereVec<T>:::pyo3_stub_gen::PyStubType"));
+
+        assert!(derive("#[stub(transparent)] struct Point(f64, f64);").is_err());
+        assert!(derive("#[stub(transparent)] enum Unit { A }").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_name() -> Result<()> {
+        let out = derive(
+            r#"
+            #[stub(name = "Handle", module = "pkg._core")]
+            struct PyHandle(Inner);
+            "#,
+        )?;
+        assert!(out.contains(r#"TypeInfo::locally_defined("Handle","pkg._core".into())"#));
+        assert!(derive("#[stub(unknown)] struct A(u8);").is_err());
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
 use indexmap::IndexSet;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::{quote, ToTokens};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro_warning::FormattedWarning;
+use quote::{format_ident, quote, ToTokens};
+use std::cell::RefCell;
 use syn::{
-    Attribute, GenericArgument, PathArguments, PathSegment, Result, ReturnType, Type, TypePath,
+    Attribute, GenericArgument, Ident, PathArguments, PathSegment, Result, ReturnType, Type,
+    TypePath,
 };
 
 use crate::gen_stub::attr::parse_gen_stub_override_return_type;
@@ -53,6 +56,12 @@ pub fn remove_lifetime(ty: &mut Type) {
     }
 }
 
+/// Refer to the stub type of `ty` implementing `PyStubType` or registered by `register_stub_type!`,
+/// which fails to compile if `ty` is neither
+pub fn stub_type_of(ty: &Type) -> TokenStream2 {
+    quote! { ::pyo3_stub_gen::StubTypeOf::<#ty>::new() }
+}
+
 /// Extract `T` from `PyResult<T>` and apply `override_type` attribute if present.
 ///
 /// For `PyResult<&'a T>` case, `'a` will be removed, i.e. returns `&T` for this case.
@@ -95,6 +104,130 @@ fn unwrap_pyresult(ty: &Type) -> &Type {
     ty
 }
 
+/// Python built-in exceptions, which have `pyo3::exceptions::Py<Name>` counterparts
+const BUILTIN_EXCEPTIONS: &[&str] = &[
+    "ArithmeticError",
+    "AssertionError",
+    "AttributeError",
+    "BaseException",
+    "BlockingIOError",
+    "BrokenPipeError",
+    "BufferError",
+    "ChildProcessError",
+    "ConnectionAbortedError",
+    "ConnectionError",
+    "ConnectionRefusedError",
+    "ConnectionResetError",
+    "EOFError",
+    "Exception",
+    "FileExistsError",
+    "FileNotFoundError",
+    "FloatingPointError",
+    "GeneratorExit",
+    "ImportError",
+    "IndexError",
+    "InterruptedError",
+    "IsADirectoryError",
+    "KeyError",
+    "KeyboardInterrupt",
+    "LookupError",
+    "MemoryError",
+    "ModuleNotFoundError",
+    "NameError",
+    "NotADirectoryError",
+    "NotImplementedError",
+    "OSError",
+    "OverflowError",
+    "PermissionError",
+    "ProcessLookupError",
+    "RecursionError",
+    "ReferenceError",
+    "RuntimeError",
+    "StopAsyncIteration",
+    "StopIteration",
+    "SyntaxError",
+    "SystemError",
+    "SystemExit",
+    "TimeoutError",
+    "TypeError",
+    "UnboundLocalError",
+    "UnicodeDecodeError",
+    "UnicodeEncodeError",
+    "UnicodeError",
+    "UnicodeTranslateError",
+    "ValueError",
+    "ZeroDivisionError",
+];
+
+/// Python name of a built-in exception listed in `#[gen_stub(raises(...))]`.
+///
+/// Both the Python name `ValueError` and PyO3's `PyValueError` (possibly with a path) are accepted.
+/// A path such as `crate::ValueError` is taken as a user-defined exception.
+pub fn builtin_exception_name(ty: &Type) -> Option<&'static str> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let last = path.segments.last()?;
+    if !last.arguments.is_none() {
+        return None;
+    }
+    let ident = last.ident.to_string();
+    let name = match ident.strip_prefix("Py") {
+        Some(name) => name,
+        None if path.segments.len() == 1 && path.leading_colon.is_none() => &ident,
+        None => return None,
+    };
+    BUILTIN_EXCEPTIONS
+        .iter()
+        .copied()
+        .find(|builtin| *builtin == name)
+}
+
+/// Extract `E` from `Result<T, E>` to document the exception raised by the function.
+///
+/// `PyErr` is skipped since it does not tell which exception is raised,
+/// and so is `E` with lifetimes since its `TypeId` cannot be taken.
+pub fn extract_error_type(ret: &ReturnType) -> Option<Type> {
+    let ReturnType::Type(_, ty) = ret else {
+        return None;
+    };
+    let Type::Path(TypePath { path, .. }) = ty.as_ref() else {
+        return None;
+    };
+    let last = path.segments.last()?;
+    if last.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(inner) = &last.arguments else {
+        return None;
+    };
+    let error = inner
+        .args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .nth(1)?;
+    if let Type::Path(TypePath { path, .. }) = error {
+        if path.segments.last()?.ident == "PyErr" {
+            return None;
+        }
+    }
+    if has_lifetime(error.to_token_stream()) {
+        return None;
+    }
+    Some(error.clone())
+}
+
+fn has_lifetime(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Punct(punct) => punct.as_char() == '\'',
+        proc_macro2::TokenTree::Group(group) => has_lifetime(group.stream()),
+        _ => false,
+    })
+}
+
 #[derive(Debug, Clone)]
 pub enum TypeOrOverride {
     RustType {
@@ -104,12 +237,106 @@ pub enum TypeOrOverride {
         r#type: Type,
         type_repr: String,
         imports: IndexSet<String>,
-        /// List of Rust type names found in RustType markers within this type expression.
+        /// List of Rust types found in RustType markers within this type expression.
         /// Used to generate code that collects type_refs from those types.
-        rust_type_markers: Vec<String>,
+        rust_type_markers: Vec<RustTypeMarker>,
     },
 }
 
+/// Which of `PyStubType::type_input` and `PyStubType::type_output` gives the Python type of a Rust type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeDirection {
+    Input,
+    Output,
+}
+
+impl TypeDirection {
+    fn type_fn(self) -> Ident {
+        match self {
+            TypeDirection::Input => format_ident!("type_input"),
+            TypeDirection::Output => format_ident!("type_output"),
+        }
+    }
+}
+
+/// Rust type in `pyo3_stub_gen.RustType["..."]`, `pyo3_stub_gen.RustTypeInput["..."]`
+/// or `pyo3_stub_gen.RustTypeOutput["..."]` of Python stubs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustTypeMarker {
+    pub rust_type: String,
+    /// `None` for `RustType`, which follows where the marker appears
+    pub direction: Option<TypeDirection>,
+}
+
+impl RustTypeMarker {
+    /// Name of the marker in Python stubs
+    pub fn form(&self) -> &'static str {
+        match self.direction {
+            None => "RustType",
+            Some(TypeDirection::Input) => "RustTypeInput",
+            Some(TypeDirection::Output) => "RustTypeOutput",
+        }
+    }
+}
+
+/// Rust types of `markers` and the `PyStubType` methods giving their Python types,
+/// where `RustType[...]` markers use the one of `default`
+pub fn marker_types_and_fns(
+    markers: &[RustTypeMarker],
+    default: TypeDirection,
+) -> (Vec<Type>, Vec<Ident>) {
+    markers
+        .iter()
+        .filter_map(|marker| {
+            // Markers are validated when collected from Python stubs
+            let ty = syn::parse_str(&marker.rust_type).ok()?;
+            Some((ty, marker.direction.unwrap_or(default).type_fn()))
+        })
+        .unzip()
+}
+
+/// `RustPathInfo` of a `#[pyclass]` or enum, to resolve intra-doc links like `[Foo]` in docstrings
+pub fn rust_path_of_type(ident: &syn::Ident) -> TokenStream2 {
+    quote! {
+        ::pyo3_stub_gen::submit! {
+            ::pyo3_stub_gen::type_info::RustPathInfo {
+                rust_path: ::std::any::type_name::<#ident>,
+                target: ::pyo3_stub_gen::type_info::RustPathTarget::Type(std::any::TypeId::of::<#ident>),
+            }
+        }
+    }
+}
+
+thread_local! {
+    static WARNINGS: RefCell<Vec<(Span, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Record a warning shown at `span` by [with_warnings], since proc-macros cannot emit warnings on stable Rust
+pub fn warn(span: Span, message: String) {
+    WARNINGS.with_borrow_mut(|warnings| {
+        // Attributes may be parsed several times for one item
+        if !warnings.iter().any(|(_, other)| *other == message) {
+            warnings.push((span, message));
+        }
+    });
+}
+
+/// Append the warnings recorded by [warn] during the expansion to its output by [proc_macro_warning]
+pub fn with_warnings(result: Result<TokenStream2>) -> Result<TokenStream2> {
+    let warnings = WARNINGS.with_borrow_mut(std::mem::take);
+    let mut tokens = result?;
+    for (span, message) in warnings {
+        let warning = FormattedWarning::new_deprecated("pyo3_stub_gen_warning", message, span);
+        // Scoped so that several warnings do not define the same function
+        tokens.extend(quote! {
+            const _: () = {
+                #warning
+            };
+        });
+    }
+    Ok(tokens)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -143,4 +370,40 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_builtin_exception_name() -> Result<()> {
+        let name =
+            |ty: &str| -> Result<Option<&str>> { Ok(builtin_exception_name(&parse_str(ty)?)) };
+        assert_eq!(name("ValueError")?, Some("ValueError"));
+        assert_eq!(name("PyValueError")?, Some("ValueError"));
+        assert_eq!(name("pyo3::exceptions::PyKeyError")?, Some("KeyError"));
+        assert_eq!(name("crate::errors::ValueError")?, None);
+        assert_eq!(name("MyError")?, None);
+        assert_eq!(name("PyMyError")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_error_type() -> Result<()> {
+        let ret: ReturnType = parse_str("-> Result<i32, MyError>")?;
+        assert_eq!(extract_error_type(&ret), Some(parse_str("MyError")?));
+
+        let ret: ReturnType = parse_str("-> std::result::Result<(), crate::errors::MyError>")?;
+        assert_eq!(
+            extract_error_type(&ret),
+            Some(parse_str("crate::errors::MyError")?)
+        );
+
+        let ret: ReturnType = parse_str("-> Result<i32, PyErr>")?;
+        assert_eq!(extract_error_type(&ret), None);
+
+        let ret: ReturnType = parse_str("-> PyResult<i32>")?;
+        assert_eq!(extract_error_type(&ret), None);
+
+        let ret: ReturnType = parse_str("-> Result<i32, Error<'a>>")?;
+        assert_eq!(extract_error_type(&ret), None);
+
+        Ok(())
+    }
 }
@@ -1,14 +1,16 @@
 use crate::gen_stub::arg::ArgInfo;
-use crate::gen_stub::attr::{extract_documents, parse_pyo3_attrs, Attr};
+use crate::gen_stub::attr::{
+    extract_documents, parse_gen_stub_variant_attrs, parse_pyo3_attrs, Attr,
+};
 use crate::gen_stub::member::{MemberInfo, MemberKind};
 use crate::gen_stub::parameter::Parameters;
 use crate::gen_stub::renaming::RenamingRule;
-use crate::gen_stub::signature::Signature;
+use crate::gen_stub::signature::{Signature, SignatureArg};
 use crate::gen_stub::util::quote_option;
 use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::spanned::Spanned;
-use syn::{Fields, Result, Variant};
+use syn::{Error, Fields, Result, Variant};
 
 #[derive(Debug, Clone, Copy)]
 pub enum VariantForm {
@@ -37,10 +39,18 @@ pub struct VariantInfo {
     form: VariantForm,
     constr_args: Vec<ArgInfo>,
     constr_sig: Option<Signature>,
+    match_args: bool,
 }
 
 impl VariantInfo {
-    pub fn from_variant(variant: Variant, renaming_rule: &Option<RenamingRule>) -> Result<Self> {
+    /// Returns `None` for the variant hidden by `#[gen_stub(skip)]`
+    ///
+    /// `match_args` is `false` if the enum has `#[gen_stub(no_match_args)]`
+    pub fn from_variant(
+        variant: Variant,
+        renaming_rule: &Option<RenamingRule>,
+        match_args: bool,
+    ) -> Result<Option<Self>> {
         let Variant {
             ident,
             fields,
@@ -48,9 +58,14 @@ impl VariantInfo {
             ..
         } = variant;
 
+        if parse_gen_stub_variant_attrs(&attrs)? {
+            return Ok(None);
+        }
+
         let mut pyclass_name = None;
         let mut module = None;
         let mut constr_sig = None;
+        let mut match_args = match_args;
         for attr in parse_pyo3_attrs(&attrs)? {
             match attr {
                 Attr::Name(name) => pyclass_name = Some(name),
@@ -60,6 +75,7 @@ impl VariantInfo {
                 Attr::Constructor(sig) => {
                     constr_sig = Some(sig);
                 }
+                Attr::GenStubNoMatchArgs => match_args = false,
                 _ => {}
             }
         }
@@ -75,7 +91,7 @@ impl VariantInfo {
             Fields::Unit => VariantForm::Unit,
             Fields::Named(fields) => {
                 for field in fields.named {
-                    members.push(MemberInfo::from_field(field, MemberKind::Getter)?)
+                    members.push(MemberInfo::from_field(field, MemberKind::Getter, None)?)
                 }
                 VariantForm::Struct
             }
@@ -83,16 +99,21 @@ impl VariantInfo {
                 for (i, field) in fields.unnamed.iter().enumerate() {
                     let mut named_field = field.clone();
                     named_field.ident = Some(Ident::new(&format!("_{i}"), field.ident.span()));
-                    members.push(MemberInfo::from_field(named_field, MemberKind::Getter)?)
+                    members.push(MemberInfo::from_field(
+                        named_field,
+                        MemberKind::Getter,
+                        None,
+                    )?)
                 }
                 VariantForm::Tuple
             }
         };
 
+        check_constructor_defaults(&members, constr_sig.as_ref())?;
         let constr_args = members.iter().map(|f| f.clone().into()).collect();
 
         let doc = extract_documents(&attrs).join("\n");
-        Ok(Self {
+        Ok(Some(Self {
             pyclass_name,
             fields: members,
             module,
@@ -100,8 +121,51 @@ impl VariantInfo {
             form,
             constr_args,
             constr_sig,
-        })
+            match_args,
+        }))
+    }
+}
+
+/// Check that `#[gen_stub(default = ...)]` on variant fields agrees with `#[pyo3(constructor = (...))]`
+///
+/// PyO3 takes the defaults of the variant constructor only from the `constructor` attribute,
+/// so a field default without the same default in the constructor would make the stub drift from the runtime.
+fn check_constructor_defaults(
+    members: &[MemberInfo],
+    constr_sig: Option<&Signature>,
+) -> Result<()> {
+    for member in members {
+        let Some(default) = member.default() else {
+            continue;
+        };
+        let constr_default = constr_sig.and_then(|sig| {
+            sig.args().find_map(|arg| match arg {
+                SignatureArg::Assign(ident, _eq, value) if ident == member.name() => Some(value),
+                _ => None,
+            })
+        });
+        let Some(constr_default) = constr_default else {
+            return Err(Error::new(
+                default.span(),
+                format!(
+                    "`#[gen_stub(default = ...)]` on `{}` requires the same default in `#[pyo3(constructor = (...))]`",
+                    member.name()
+                ),
+            ));
+        };
+        if constr_default.to_token_stream().to_string() != default.to_token_stream().to_string() {
+            return Err(Error::new(
+                default.span(),
+                format!(
+                    "default of `{}` differs from `#[pyo3(constructor = (...))]`: `{}` vs `{}`",
+                    member.name(),
+                    default.to_token_stream(),
+                    constr_default.to_token_stream()
+                ),
+            ));
+        }
     }
+    Ok(())
 }
 
 impl ToTokens for VariantInfo {
@@ -114,6 +178,7 @@ impl ToTokens for VariantInfo {
             form,
             constr_args,
             constr_sig,
+            match_args,
         } = self;
 
         let parameters = if let Some(sig) = constr_sig {
@@ -137,6 +202,7 @@ impl ToTokens for VariantInfo {
                 doc: #doc,
                 form: &pyo3_stub_gen::type_info::VariantForm::#form,
                 constr_args: #parameters,
+                match_args: #match_args,
             }
         })
     }
@@ -20,7 +20,7 @@ use proc_macro::TokenStream;
 /// ```
 #[proc_macro_attribute]
 pub fn gen_stub_pyclass(attr: TokenStream, item: TokenStream) -> TokenStream {
-    gen_stub::pyclass(attr.into(), item.into())
+    gen_stub::with_warnings(gen_stub::pyclass(attr.into(), item.into()))
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -40,7 +40,7 @@ pub fn gen_stub_pyclass(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 pub fn gen_stub_pyclass_enum(attr: TokenStream, item: TokenStream) -> TokenStream {
-    gen_stub::pyclass_enum(attr.into(), item.into())
+    gen_stub::with_warnings(gen_stub::pyclass_enum(attr.into(), item.into()))
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -60,7 +60,7 @@ pub fn gen_stub_pyclass_enum(attr: TokenStream, item: TokenStream) -> TokenStrea
 /// ```
 #[proc_macro_attribute]
 pub fn gen_stub_pyclass_complex_enum(attr: TokenStream, item: TokenStream) -> TokenStream {
-    gen_stub::pyclass_complex_enum(attr.into(), item.into())
+    gen_stub::with_warnings(gen_stub::pyclass_complex_enum(attr.into(), item.into()))
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -83,7 +83,7 @@ pub fn gen_stub_pyclass_complex_enum(attr: TokenStream, item: TokenStream) -> To
 /// ```
 #[proc_macro_attribute]
 pub fn gen_stub_pymethods(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    gen_stub::pymethods(item.into())
+    gen_stub::with_warnings(gen_stub::pymethods(item.into()))
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -112,7 +112,91 @@ pub fn gen_stub_pymethods(_attr: TokenStream, item: TokenStream) -> TokenStream
 /// ```
 #[proc_macro_attribute]
 pub fn gen_stub_pyfunction(attr: TokenStream, item: TokenStream) -> TokenStream {
-    gen_stub::pyfunction(attr.into(), item.into())
+    gen_stub::with_warnings(gen_stub::pyfunction(attr.into(), item.into()))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Give the Python type accepted by a converter function of `#[pyo3(from_py_with = ...)]`
+///
+/// Arguments converted by the function are written with `input` in stub files instead of their Rust type.
+/// Modules referenced by `input` are listed in `imports`.
+///
+/// ```
+/// # use pyo3::prelude::*;
+/// #[pyo3_stub_gen_derive::gen_stub_converter(input = "int | str")]
+/// fn parse_amount(obj: &Bound<'_, PyAny>) -> PyResult<i64> {
+///     match obj.extract::<i64>() {
+///         Ok(value) => Ok(value),
+///         Err(_) => Ok(obj.extract::<String>()?.parse()?),
+///     }
+/// }
+///
+/// #[pyo3_stub_gen_derive::gen_stub_pyfunction]
+/// #[pyfunction]
+/// fn total(#[pyo3(from_py_with = parse_amount)] amount: i64, count: i64) -> i64 {
+///     amount * count
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn gen_stub_converter(attr: TokenStream, item: TokenStream) -> TokenStream {
+    gen_stub::converter(attr.into(), item.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implement `PyStubType` for a newtype wrapper
+///
+/// `#[stub(transparent)]` forwards to the type of the only field:
+///
+/// ```
+/// #[derive(pyo3_stub_gen_derive::PyStubType)]
+/// #[stub(transparent)]
+/// pub struct Meters(f64);
+/// ```
+///
+/// Without `transparent`, the type is mapped to a class like `#[gen_stub_pyclass]` does,
+/// named `name` or the Rust name, in `module`:
+///
+/// ```
+/// #[pyo3::pyclass(name = "Handle", module = "my_module")]
+/// #[derive(pyo3_stub_gen_derive::PyStubType)]
+/// #[stub(name = "Handle", module = "my_module")]
+/// pub struct PyHandle(u64);
+/// ```
+#[proc_macro_derive(PyStubType, attributes(stub))]
+pub fn derive_stub_type(item: TokenStream) -> TokenStream {
+    gen_stub::stub_type(item.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Embed the structure of modules registered in `#[pymodule]` function
+///
+/// `add_class::<T>()`, `add_function(wrap_pyfunction!(f, m)?)` and `add_submodule(&sub)` calls
+/// directly in the function body are recorded, where `sub` is created by `PyModule::new(py, "sub")`.
+/// Functions of `#[gen_stub_pyfunction]` without `module = "..."` are generated into the modules they are added to,
+/// matched by their Python names. Stub generation fails if a class, or a function with `module = "..."`,
+/// is registered to a module other than the one its stub is generated into, e.g. due to missing `#[pyclass(module = "...")]`.
+///
+/// ```
+/// # use pyo3::prelude::*;
+/// #[pyo3_stub_gen_derive::gen_stub_pyclass]
+/// #[pyclass(module = "my_module.sub")]
+/// struct A {}
+///
+/// #[pyo3_stub_gen_derive::gen_stub_pymodule]
+/// #[pymodule]
+/// fn my_module(m: &Bound<PyModule>) -> PyResult<()> {
+///     let sub = PyModule::new(m.py(), "sub")?;
+///     sub.add_class::<A>()?;
+///     m.add_submodule(&sub)?;
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn gen_stub_pymodule(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    gen_stub::with_warnings(gen_stub::pymodule(item.into()))
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -211,6 +295,23 @@ pub fn gen_function_from_python(input: TokenStream) -> TokenStream {
 ///     }
 /// }
 /// ```
+///
+/// The class is the Rust type named after the Python class by default.
+/// `rust_type = path::To::Type` gives another Rust type, and `by_name = true` attaches the methods
+/// to the class of the Python name when gathering stubs, optionally with `module = "..."`:
+///
+/// ```ignore
+/// submit! {
+///     gen_methods_from_python! {
+///         by_name = true,
+///         module = "other_crate.linalg",
+///         r#"
+///         class Vector:
+///             def norm(self) -> float: ...
+///         "#
+///     }
+/// }
+/// ```
 #[proc_macro]
 pub fn gen_methods_from_python(input: TokenStream) -> TokenStream {
     gen_stub::gen_methods_from_python_impl(input.into())
@@ -256,3 +357,31 @@ pub fn gen_type_alias_from_python(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Generate TypingHelperInfo from typing-only Python definitions
+///
+/// This proc-macro parses Python definitions without Rust counterparts, e.g. `TypeVar` or `Protocol`,
+/// and registers them to the module. They are emitted near the top of the stub file as written,
+/// and are not added to `__all__`. Use `export_verbatim!` to export them explicitly.
+/// Modules of `import` statements are imported at the top of the stub file.
+/// The code is parsed at compile time, so that a syntax error is reported by the compiler.
+///
+/// ```ignore
+/// pyo3_stub_gen::derive::gen_typing_helpers_from_python!(
+///     "mymodule",
+///     r#"
+///     import typing
+///
+///     T = typing.TypeVar("T")
+///
+///     class SupportsRead(typing.Protocol[T]):
+///         def read(self, n: int = -1, /) -> T: ...
+///     "#
+/// );
+/// ```
+#[proc_macro]
+pub fn gen_typing_helpers_from_python(input: TokenStream) -> TokenStream {
+    gen_stub::gen_typing_helpers_from_python_impl(input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
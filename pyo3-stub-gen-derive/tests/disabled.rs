@@ -0,0 +1,59 @@
+//! The macros compile but submit nothing with `--cfg pyo3_stub_gen_disabled`:
+//!
+//! ```bash
+//! RUSTFLAGS="--cfg pyo3_stub_gen_disabled" cargo test -p pyo3-stub-gen-derive --test disabled
+//! ```
+#![cfg(pyo3_stub_gen_disabled)]
+
+use pyo3::prelude::*;
+use pyo3_stub_gen::{derive::*, type_info::*, PyStubType};
+
+#[gen_stub_pyclass]
+#[pyclass]
+struct Counter {
+    #[pyo3(get)]
+    count: usize,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Counter {
+    #[gen_stub(override_return_type(type_repr = "int"))]
+    fn increment(&mut self) -> usize {
+        self.count += 1;
+        self.count
+    }
+}
+
+#[gen_stub_pyclass_enum]
+#[pyclass(eq, eq_int)]
+#[derive(PartialEq)]
+enum Color {
+    Red,
+}
+
+#[gen_stub_pyfunction]
+#[pyfunction]
+fn double(x: usize) -> usize {
+    x * 2
+}
+
+pyo3_stub_gen::submit! {
+    gen_function_from_python! {
+        r#"
+            def triple(x: int) -> int: ...
+        "#
+    }
+}
+
+#[test]
+fn test_nothing_submitted() {
+    assert_eq!(inventory::iter::<PyClassInfo>().count(), 0);
+    assert_eq!(inventory::iter::<PyMethodsInfo>().count(), 0);
+    assert_eq!(inventory::iter::<PyEnumInfo>().count(), 0);
+    assert_eq!(inventory::iter::<PyFunctionInfo>().count(), 0);
+    // Types are still annotated in stubs of other crates
+    assert_eq!(Counter::type_output().name, "Counter");
+    assert_eq!(Color::type_output().name, "Color");
+    assert_eq!(double(1), 2);
+}
@@ -0,0 +1,12 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen_derive::*;
+
+#[gen_stub_pyclass]
+#[pyclass]
+struct Config {
+    #[pyo3(get)]
+    #[gen_stub(platform = "linux")]
+    path: String,
+}
+
+fn main() {}
\ No newline at end of file
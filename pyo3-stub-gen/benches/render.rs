@@ -0,0 +1,135 @@
+//! Time to render the stub of a module with ~1300 classes
+//!
+//! ```shell
+//! cargo bench -p pyo3-stub-gen --bench render
+//! ```
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use indexmap::IndexMap;
+use pyo3_stub_gen::{
+    generate::{
+        ClassDef, Import, MemberDef, MethodDef, Module, Parameter, ParameterDefault, Parameters,
+    },
+    type_info::{MethodType, ParameterKind},
+    StubGenConfig, TypeInfo,
+};
+use std::{any::TypeId, hint::black_box};
+
+/// `TypeId`s of all the 3-tuples of the given types, to key distinct classes in [Module::class]
+macro_rules! tuple_type_ids {
+    ([$($a:ty),*], $b:tt, $c:tt) => {
+        [$(tuple_type_ids!(@b $a, $b, $c)),*].concat()
+    };
+    (@b $a:ty, [$($b:ty),*], $c:tt) => {
+        [$(tuple_type_ids!(@c $a, $b, $c)),*].concat()
+    };
+    (@c $a:ty, $b:ty, [$($c:ty),*]) => {
+        vec![$(TypeId::of::<($a, $b, $c)>()),*]
+    };
+}
+
+fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+fn class(index: usize) -> ClassDef {
+    let name = leak(format!("Class{index}"));
+    let member = |name: &'static str, r#type: TypeInfo| MemberDef {
+        name,
+        r#type,
+        doc: "Documented member",
+        default: None,
+        default_symbol: None,
+        deprecated: None,
+        cached: false,
+        type_ignored: None,
+    };
+    let mut parameters = Parameters::new();
+    parameters.positional_or_keyword.push(Parameter {
+        name: "value",
+        kind: ParameterKind::PositionalOrKeyword,
+        type_info: TypeInfo::with_module("decimal.Decimal", "decimal".into()),
+        default: ParameterDefault::None,
+    });
+    let method = MethodDef {
+        name: "update",
+        parameters,
+        r#return: TypeInfo::builtin("list[int]"),
+        doc: "Update the value\n\nArgs:\n    value: new value",
+        r#type: MethodType::Instance,
+        is_async: false,
+        deprecated: None,
+        type_ignored: None,
+        is_overload: false,
+    };
+    ClassDef {
+        name,
+        module: Some("bench"),
+        doc: "A class to measure rendering",
+        attrs: Vec::new(),
+        getter_setters: IndexMap::from([
+            (
+                "name".to_string(),
+                (Some(member("name", TypeInfo::builtin("str"))), None),
+            ),
+            (
+                "path".to_string(),
+                (
+                    Some(member(
+                        "path",
+                        TypeInfo::with_module("pathlib.Path", "pathlib".into()),
+                    )),
+                    Some(member(
+                        "path",
+                        TypeInfo::with_module("pathlib.Path", "pathlib".into()),
+                    )),
+                ),
+            ),
+        ]),
+        methods: IndexMap::from([("update".to_string(), vec![method])]),
+        bases: Vec::new(),
+        classes: Vec::new(),
+        match_args: None,
+        subclass: false,
+        r#final: false,
+        buffer: false,
+        picklable: false,
+        unhashable: false,
+        iterator_item: None,
+        verbatim: Vec::new(),
+        type_ignored: None,
+        dataclass_transform: None,
+        file: "src/lib.rs",
+        line: index as u32,
+        column: 0,
+    }
+}
+
+fn module() -> Module {
+    let type_ids = tuple_type_ids!(
+        [u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32],
+        [u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32],
+        [u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32]
+    );
+    let mut module = Module {
+        name: "bench".to_string(),
+        default_module_name: "bench".to_string(),
+        ..Default::default()
+    };
+    for (index, type_id) in type_ids.into_iter().enumerate() {
+        module.class.insert(type_id.into(), class(index));
+    }
+    module
+}
+
+fn render(c: &mut Criterion) {
+    let module = module();
+    let config = StubGenConfig::default();
+    c.bench_function("import", |b| b.iter(|| black_box(&module).import()));
+    c.bench_function("format_with_config", |b| {
+        b.iter(|| black_box(&module).format_with_config(&config))
+    });
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
@@ -3,15 +3,16 @@
 use crate::docgen::{
     export::ExportResolver,
     ir::{
-        DeprecatedInfo, DocAttribute, DocClass, DocFunction, DocItem, DocModule, DocPackage,
-        DocParameter, DocSignature, DocSubmodule, DocTypeAlias, DocTypeExpr, DocVariable,
+        DeprecatedInfo, DocAttribute, DocClass, DocClassParameter, DocDictKey, DocFunction,
+        DocInheritedMembers, DocItem, DocModule, DocPackage, DocParameter, DocSignature,
+        DocSubmodule, DocTypeAlias, DocTypeExpr, DocVariable,
     },
     types::TypeRenderer,
-    util::{is_hidden_module, prefix_stripper},
+    util::{is_hidden_module, parse_args_section, prefix_stripper, split_rust_arguments_section},
 };
 use crate::generate::StubInfo;
-use crate::Result;
-use std::collections::BTreeMap;
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 /// Helper to check if item already exists in the list
@@ -25,6 +26,18 @@ fn matches_item_name(item: &DocItem, name: &str) -> bool {
     }
 }
 
+/// Rename an item re-exported by [crate::reexport_as!]
+fn rename_item(item: &mut DocItem, name: &str) {
+    let target = match item {
+        DocItem::Function(f) => &mut f.name,
+        DocItem::Class(c) => &mut c.name,
+        DocItem::TypeAlias(t) => &mut t.name,
+        DocItem::Variable(v) => &mut v.name,
+        DocItem::Module(m) => &mut m.name,
+    };
+    *target = name.to_string();
+}
+
 /// Context for building documentation items, containing shared rendering components
 struct DocBuildContext<'a> {
     link_resolver: crate::docgen::link::LinkResolver<'a>,
@@ -48,6 +61,7 @@ pub struct DocPackageBuilder<'a> {
     stub_info: &'a StubInfo,
     export_resolver: ExportResolver<'a>,
     export_map: BTreeMap<String, String>,
+    intersphinx: BTreeMap<String, String>,
 }
 
 impl<'a> DocPackageBuilder<'a> {
@@ -55,16 +69,31 @@ impl<'a> DocPackageBuilder<'a> {
         let export_resolver = ExportResolver::new(&stub_info.modules);
         let export_map = export_resolver.build_export_map();
 
+        let intersphinx = stub_info
+            .config
+            .doc_gen
+            .as_ref()
+            .map(|config| config.intersphinx.clone())
+            .unwrap_or_default();
+
         Self {
             stub_info,
             export_resolver,
             export_map,
+            intersphinx,
         }
     }
 
+    /// Rust source location of an item, if `source-comments` is enabled
+    fn source_location(&self, file: &str, line: u32) -> Option<String> {
+        (self.stub_info.config.source_comments && !file.is_empty())
+            .then(|| format!("{file}:{line}"))
+    }
+
     /// Create a build context for a specific module
     fn create_context<'b>(&'b self, module: &'b str) -> DocBuildContext<'b> {
-        let link_resolver = crate::docgen::link::LinkResolver::new(&self.export_map);
+        let link_resolver = crate::docgen::link::LinkResolver::new(&self.export_map)
+            .with_intersphinx(&self.intersphinx);
         DocBuildContext {
             link_resolver,
             module,
@@ -98,6 +127,7 @@ impl<'a> DocPackageBuilder<'a> {
             modules,
             export_map,
             config: json_config,
+            free_threading: self.stub_info.config.free_threading,
         })
     }
 
@@ -119,6 +149,13 @@ impl<'a> DocPackageBuilder<'a> {
             }
         }
 
+        // Process old names of moved classes
+        for (alias_name, alias_def) in &module.deprecated_aliases {
+            if exports.contains(*alias_name) {
+                items.push(self.build_deprecated_alias(name, alias_def)?);
+            }
+        }
+
         // Process classes (sorted by name for deterministic output)
         let mut classes: Vec<_> = module.class.values().collect();
         classes.sort_by_key(|c| c.name);
@@ -148,17 +185,24 @@ impl<'a> DocPackageBuilder<'a> {
         for re_export in &module.module_re_exports {
             if let Some(source_module) = self.stub_info.modules.get(&re_export.source_module) {
                 for item_name in &re_export.items {
+                    let exported_name = re_export.exported_name(item_name);
                     // Skip if already added (prefer directly-defined items)
-                    if items.iter().any(|item| matches_item_name(item, item_name)) {
+                    if items
+                        .iter()
+                        .any(|item| matches_item_name(item, exported_name))
+                    {
                         continue;
                     }
 
                     // Build re-exported item (link targets will be corrected later)
-                    if let Some(item) = self.build_reexported_item(
+                    if let Some(mut item) = self.build_reexported_item(
                         &re_export.source_module,
                         source_module,
                         item_name,
                     )? {
+                        if exported_name != item_name {
+                            rename_item(&mut item, exported_name);
+                        }
                         items.push(item);
                     }
                 }
@@ -195,11 +239,12 @@ impl<'a> DocPackageBuilder<'a> {
         // This ensures both directly-defined and re-exported items have correct references
         for item in &mut items {
             self.correct_link_targets(item, name);
+            self.link_item_docs(item, name);
         }
 
         Ok(DocModule {
             name: name.to_string(),
-            doc: module.doc.clone(),
+            doc: self.link_doc(&module.doc, name),
             items,
             submodules: module
                 .submodules
@@ -220,7 +265,7 @@ impl<'a> DocPackageBuilder<'a> {
         sorted_defs.sort_by_key(|func| (func.file, func.line, func.column, func.index));
 
         // Requirement #1: Include ALL overload signatures
-        let signatures: Vec<DocSignature> = sorted_defs
+        let mut signatures: Vec<DocSignature> = sorted_defs
             .iter()
             .map(|def| self.build_signature(module, def))
             .collect::<Result<_>>()?;
@@ -230,6 +275,7 @@ impl<'a> DocPackageBuilder<'a> {
             .first()
             .map(|d| d.doc.to_string())
             .unwrap_or_default();
+        let doc = self.move_arguments_section(doc, &mut signatures);
 
         let deprecated = sorted_defs.first().and_then(|d| {
             d.deprecated.as_ref().map(|dep| DeprecatedInfo {
@@ -238,24 +284,67 @@ impl<'a> DocPackageBuilder<'a> {
             })
         });
 
+        // Exceptions listed by `#[gen_stub(raises(...))]`, followed by the one for the Rust error type.
+        // Overloads given in Python syntax do not know the Rust error type
+        let mut exceptions: Vec<crate::TypeInfo> = Vec::new();
+        let listed = sorted_defs
+            .iter()
+            .flat_map(|def| def.raises.iter().cloned());
+        let inferred = sorted_defs
+            .iter()
+            .find_map(|def| def.error)
+            .and_then(|error| self.exception_type(error));
+        for exception in listed.chain(inferred) {
+            if !exceptions.iter().any(|e| e.name == exception.name) {
+                exceptions.push(exception);
+            }
+        }
+        let ctx = self.create_context(module);
+        let type_renderer = ctx.type_renderer();
+        let raises = exceptions
+            .iter()
+            .map(|exception| type_renderer.render_type(exception))
+            .collect();
+
         Ok(DocItem::Function(DocFunction {
             name: sorted_defs[0].name.to_string(),
             doc,
             signatures,
             is_async: sorted_defs[0].is_async,
             deprecated,
+            raises,
+            releases_gil: sorted_defs.iter().any(|def| def.releases_gil),
+            source: self.source_location(sorted_defs[0].file, sorted_defs[0].line),
         }))
     }
 
+    /// Class of `error`, e.g. an exception created by [crate::create_exception!]
+    fn exception_type(&self, error: std::any::TypeId) -> Option<crate::TypeInfo> {
+        self.stub_info
+            .modules
+            .iter()
+            .find_map(|(module_name, module)| {
+                let class = module.class.get(&crate::generate::ClassId::Rust(error))?;
+                Some(crate::TypeInfo::locally_defined(
+                    class.name,
+                    module_name.as_str().into(),
+                ))
+            })
+    }
+
+    /// Signature of the function `function`, or of the method of the class `class`
     fn build_signature_from_params(
         &self,
         module: &str,
+        class: Option<&str>,
+        function: &str,
         parameters: &crate::generate::Parameters,
         return_type: &crate::TypeInfo,
     ) -> Result<DocSignature> {
         let ctx = self.create_context(module);
         let type_renderer = ctx.type_renderer();
         let default_parser = ctx.default_parser();
+        let source_module = self.stub_info.modules.get(module);
 
         let params: Vec<DocParameter> = parameters
             .positional_only
@@ -274,31 +363,95 @@ impl<'a> DocPackageBuilder<'a> {
                         Some(default_parser.parse(value, &param.type_info))
                     }
                 },
+                doc: String::new(),
+                keys: source_module
+                    .and_then(|source| source.dict_keys(class, function, param.name))
+                    .map(|dict_keys| {
+                        dict_keys
+                            .keys
+                            .iter()
+                            .map(|(name, type_)| DocDictKey {
+                                name: name.to_string(),
+                                type_: type_renderer.render_type(type_),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
             })
             .collect();
 
         let ret_type = Some(type_renderer.render_type(return_type));
+        let return_iterator = self
+            .iterator_base(module, return_type)
+            .map(|base| type_renderer.render_type(base));
 
         Ok(DocSignature {
             parameters: params,
             return_type: ret_type,
+            return_iterator,
+        })
+    }
+
+    /// `collections.abc.Iterator[...]` or `Generator[...]` base of the class `type_info`
+    /// if it is declared by `#[gen_stub(iterator(...))]`
+    fn iterator_base<'b>(
+        &'b self,
+        module: &'b str,
+        type_info: &crate::TypeInfo,
+    ) -> Option<&'b crate::TypeInfo> {
+        type_info.source_module.as_ref()?;
+        let (_, class) = self.find_base_class(module, type_info)?;
+        class.iterator_item.as_ref()?;
+        class.bases.iter().find(|base| {
+            base.name.starts_with("collections.abc.Iterator[")
+                || base.name.starts_with("collections.abc.Generator[")
         })
     }
 
+    /// Move the `# Arguments` section of a Rust doc comment into descriptions of the parameters,
+    /// unless disabled by `parse-rust-arguments`
+    fn move_arguments_section(&self, doc: String, signatures: &mut [DocSignature]) -> String {
+        let enabled = self
+            .stub_info
+            .config
+            .doc_gen
+            .as_ref()
+            .is_none_or(|config| config.parse_rust_arguments);
+        if !enabled {
+            return doc;
+        }
+        let Some((doc, descriptions)) = split_rust_arguments_section(&doc) else {
+            return doc;
+        };
+        for param in signatures.iter_mut().flat_map(|sig| &mut sig.parameters) {
+            if let Some(description) = descriptions.get(&param.name) {
+                param.doc = description.clone();
+            }
+        }
+        doc
+    }
+
     fn build_signature(
         &self,
         module: &str,
         def: &crate::generate::FunctionDef,
     ) -> Result<DocSignature> {
-        self.build_signature_from_params(module, &def.parameters, &def.r#return)
+        self.build_signature_from_params(module, None, def.name, &def.parameters, &def.r#return)
     }
 
     fn build_signature_from_method(
         &self,
         module: &str,
+        class: &str,
         def: &crate::generate::MethodDef,
     ) -> Result<DocSignature> {
-        self.build_signature_from_params(module, &def.parameters, &def.r#return)
+        self.build_signature_from_params(
+            module,
+            Some(class),
+            def.name,
+            &def.parameters,
+            &def.r#return,
+        )
     }
 
     fn build_type_alias(
@@ -314,10 +467,34 @@ impl<'a> DocPackageBuilder<'a> {
             name: alias.name.to_string(),
             doc: alias.doc.to_string(),
             definition: type_renderer.render_type(&alias.type_),
+            deprecated: None,
+        }))
+    }
+
+    fn build_deprecated_alias(
+        &self,
+        module: &str,
+        alias: &crate::generate::DeprecatedAliasDef,
+    ) -> Result<DocItem> {
+        let ctx = self.create_context(module);
+        let type_renderer = ctx.type_renderer();
+
+        Ok(DocItem::TypeAlias(DocTypeAlias {
+            name: alias.name.to_string(),
+            doc: String::new(),
+            definition: type_renderer.render_type(&alias.target),
+            deprecated: Some(DeprecatedInfo {
+                since: alias.deprecated.since.map(|s| s.to_string()),
+                note: alias.deprecated.note.map(|s| s.to_string()),
+            }),
         }))
     }
 
     fn build_class(&self, module: &str, class: &crate::generate::ClassDef) -> Result<DocItem> {
+        Ok(DocItem::Class(self.build_class_def(module, class)?))
+    }
+
+    fn build_class_def(&self, module: &str, class: &crate::generate::ClassDef) -> Result<DocClass> {
         let ctx = self.create_context(module);
         let type_renderer = ctx.type_renderer();
 
@@ -329,10 +506,15 @@ impl<'a> DocPackageBuilder<'a> {
 
         let mut methods = Vec::new();
         for (method_name, method_overloads) in &class.methods {
-            let signatures: Vec<DocSignature> = method_overloads
+            let mut signatures: Vec<DocSignature> = method_overloads
                 .iter()
-                .map(|method| self.build_signature_from_method(module, method))
+                .map(|method| self.build_signature_from_method(module, class.name, method))
                 .collect::<Result<_>>()?;
+            let doc = method_overloads
+                .first()
+                .map(|m| m.doc.to_string())
+                .unwrap_or_default();
+            let doc = self.move_arguments_section(doc, &mut signatures);
 
             let deprecated = method_overloads.first().and_then(|d| {
                 d.deprecated.as_ref().map(|dep| DeprecatedInfo {
@@ -343,16 +525,16 @@ impl<'a> DocPackageBuilder<'a> {
 
             methods.push(DocFunction {
                 name: method_name.to_string(),
-                doc: method_overloads
-                    .first()
-                    .map(|m| m.doc.to_string())
-                    .unwrap_or_default(),
+                doc,
                 signatures,
                 is_async: method_overloads
                     .first()
                     .map(|m| m.is_async)
                     .unwrap_or(false),
                 deprecated,
+                raises: Vec::new(),
+                releases_gil: false,
+                source: None,
             });
         }
 
@@ -397,14 +579,161 @@ impl<'a> DocPackageBuilder<'a> {
             }
         }
 
-        Ok(DocItem::Class(DocClass {
+        let parameters = Self::build_class_parameters(class.doc, &methods);
+
+        let inherited_members = self
+            .stub_info
+            .config
+            .doc_gen
+            .as_ref()
+            .is_some_and(|config| config.inherited_members);
+        let inherited = if inherited_members {
+            self.build_inherited_members(module, class, &methods, &attributes)
+        } else {
+            Vec::new()
+        };
+
+        let classes = class
+            .classes
+            .iter()
+            .map(|nested| self.build_class_def(module, nested))
+            .collect::<Result<_>>()?;
+
+        Ok(DocClass {
             name: class.name.to_string(),
             doc: class.doc.to_string(),
             bases,
             methods,
             attributes,
+            parameters,
+            picklable: class.picklable,
+            inherited,
+            classes,
             deprecated: None, // ClassDef doesn't have deprecated field
-        }))
+            source: self.source_location(class.file, class.line),
+        })
+    }
+
+    /// Public members of the base classes not overridden by `class`, following the bases recursively
+    fn build_inherited_members(
+        &self,
+        module: &str,
+        class: &crate::generate::ClassDef,
+        methods: &[DocFunction],
+        attributes: &[DocAttribute],
+    ) -> Vec<DocInheritedMembers> {
+        let ctx = self.create_context(module);
+        let type_renderer = ctx.type_renderer();
+
+        let mut defined: BTreeSet<String> = methods
+            .iter()
+            .map(|method| method.name.clone())
+            .chain(attributes.iter().map(|attr| attr.name.clone()))
+            .collect();
+        let mut visited = BTreeSet::new();
+        let mut inherited = Vec::new();
+        let mut stack: Vec<(&str, &crate::TypeInfo)> = class
+            .bases
+            .iter()
+            .rev()
+            .map(|base| (module, base))
+            .collect();
+        while let Some((base_module, base)) = stack.pop() {
+            let Some((found_module, base_class)) = self.find_base_class(base_module, base) else {
+                continue;
+            };
+            if !visited.insert((found_module, base_class.name)) {
+                continue;
+            }
+            let mut new_members = |names: Vec<&str>| -> Vec<String> {
+                names
+                    .into_iter()
+                    .filter(|name| !name.starts_with('_') && defined.insert(name.to_string()))
+                    .map(str::to_string)
+                    .collect()
+            };
+            let methods = new_members(base_class.methods.keys().map(String::as_str).collect());
+            let attributes = new_members(
+                base_class
+                    .attrs
+                    .iter()
+                    .map(|attr| attr.name)
+                    .chain(base_class.getter_setters.keys().map(String::as_str))
+                    .collect(),
+            );
+            if !methods.is_empty() || !attributes.is_empty() {
+                inherited.push(DocInheritedMembers {
+                    base: type_renderer.render_type(base),
+                    methods,
+                    attributes,
+                });
+            }
+            stack.extend(
+                base_class
+                    .bases
+                    .iter()
+                    .rev()
+                    .map(|base| (found_module, base)),
+            );
+        }
+        inherited
+    }
+
+    /// Find the class definition of a base class, declared in the module of its type reference
+    /// or the module of the derived class if it is unknown
+    fn find_base_class<'b>(
+        &'b self,
+        derived_module: &'b str,
+        base: &crate::TypeInfo,
+    ) -> Option<(&'b str, &'b crate::generate::ClassDef)> {
+        let name = base.name.rsplit('.').next()?;
+        let base_module = base
+            .type_refs
+            .get(name)
+            .map(|type_ref| {
+                type_ref
+                    .module
+                    .get()
+                    .unwrap_or(self.stub_info.default_module_name.as_str())
+            })
+            .unwrap_or(derived_module);
+        let (module_name, module) = self.stub_info.modules.get_key_value(base_module)?;
+        module
+            .class
+            .values()
+            .find(|class| class.name == name)
+            .map(|class| (module_name.as_str(), class))
+    }
+
+    /// Parameters of the first `__new__` (or `__init__`) signature,
+    /// described by the `Args:` section of its docstring or the class docstring,
+    /// or the `# Arguments` section of its Rust doc comment
+    fn build_class_parameters(class_doc: &str, methods: &[DocFunction]) -> Vec<DocClassParameter> {
+        let Some(constructor) = ["__new__", "__init__"]
+            .iter()
+            .find_map(|name| methods.iter().find(|method| method.name == *name))
+        else {
+            return Vec::new();
+        };
+        let Some(signature) = constructor.signatures.first() else {
+            return Vec::new();
+        };
+        let mut descriptions = parse_args_section(class_doc);
+        descriptions.extend(parse_args_section(&constructor.doc));
+        signature
+            .parameters
+            .iter()
+            .map(|param| DocClassParameter {
+                name: param.name.clone(),
+                type_: param.type_.clone(),
+                default: param.default.clone(),
+                doc: descriptions
+                    .get(&param.name)
+                    .cloned()
+                    .unwrap_or_else(|| param.doc.clone()),
+                keys: param.keys.clone(),
+            })
+            .collect()
     }
 
     fn build_enum_as_class(
@@ -454,7 +783,12 @@ impl<'a> DocPackageBuilder<'a> {
             bases: Vec::new(), // Enums don't have bases in our structure
             methods: Vec::new(),
             attributes,
+            parameters: Vec::new(),
+            picklable: false,
+            inherited: Vec::new(),
+            classes: Vec::new(),
             deprecated: None,
+            source: self.source_location(enum_def.file, enum_def.line),
         }))
     }
 
@@ -502,6 +836,12 @@ impl<'a> DocPackageBuilder<'a> {
             return Ok(Some(self.build_type_alias(source_module_name, alias_def)?));
         }
 
+        if let Some(alias_def) = source_module.deprecated_aliases.get(item_name) {
+            return Ok(Some(
+                self.build_deprecated_alias(source_module_name, alias_def)?,
+            ));
+        }
+
         // Try variables
         if let Some(var_def) = source_module.variables.get(item_name) {
             return Ok(Some(self.build_variable(source_module_name, var_def)?));
@@ -518,22 +858,43 @@ impl<'a> DocPackageBuilder<'a> {
                     if let Some(ret) = &mut sig.return_type {
                         self.correct_type_expr(ret);
                     }
+                    if let Some(iterator) = &mut sig.return_iterator {
+                        self.correct_type_expr(iterator);
+                    }
                     for param in &mut sig.parameters {
                         self.correct_type_expr(&mut param.type_);
+                        for key in &mut param.keys {
+                            self.correct_type_expr(&mut key.type_);
+                        }
                     }
                 }
+                for exception in &mut func.raises {
+                    self.correct_type_expr(exception);
+                }
             }
             DocItem::Class(cls) => {
                 for base in &mut cls.bases {
                     self.correct_type_expr(base);
                 }
+                for param in &mut cls.parameters {
+                    self.correct_type_expr(&mut param.type_);
+                    for key in &mut param.keys {
+                        self.correct_type_expr(&mut key.type_);
+                    }
+                }
                 for method in &mut cls.methods {
                     for sig in &mut method.signatures {
                         if let Some(ret) = &mut sig.return_type {
                             self.correct_type_expr(ret);
                         }
+                        if let Some(iterator) = &mut sig.return_iterator {
+                            self.correct_type_expr(iterator);
+                        }
                         for param in &mut sig.parameters {
                             self.correct_type_expr(&mut param.type_);
+                            for key in &mut param.keys {
+                                self.correct_type_expr(&mut key.type_);
+                            }
                         }
                     }
                 }
@@ -542,6 +903,9 @@ impl<'a> DocPackageBuilder<'a> {
                         self.correct_type_expr(type_);
                     }
                 }
+                for inherited in &mut cls.inherited {
+                    self.correct_type_expr(&mut inherited.base);
+                }
             }
             DocItem::TypeAlias(alias) => {
                 self.correct_type_expr(&mut alias.definition);
@@ -555,6 +919,94 @@ impl<'a> DocPackageBuilder<'a> {
         }
     }
 
+    /// Rewrite Rust intra-doc links in the docstrings of an item
+    fn link_item_docs(&self, item: &mut DocItem, module: &str) {
+        match item {
+            DocItem::Function(func) => self.link_function_docs(func, module),
+            DocItem::Class(cls) => self.link_class_docs(cls, module),
+            DocItem::TypeAlias(alias) => alias.doc = self.link_doc(&alias.doc, module),
+            DocItem::Variable(var) => var.doc = self.link_doc(&var.doc, module),
+            DocItem::Module(submod) => submod.doc = self.link_doc(&submod.doc, module),
+        }
+    }
+
+    fn link_function_docs(&self, func: &mut DocFunction, module: &str) {
+        func.doc = self.link_doc(&func.doc, module);
+        for param in func
+            .signatures
+            .iter_mut()
+            .flat_map(|sig| &mut sig.parameters)
+        {
+            param.doc = self.plain_doc(&param.doc);
+        }
+    }
+
+    fn link_class_docs(&self, cls: &mut DocClass, module: &str) {
+        cls.doc = self.link_doc(&cls.doc, module);
+        for param in &mut cls.parameters {
+            param.doc = self.plain_doc(&param.doc);
+        }
+        for method in &mut cls.methods {
+            self.link_function_docs(method, module);
+        }
+        for attr in &mut cls.attributes {
+            attr.doc = self.link_doc(&attr.doc, module);
+        }
+        for nested in &mut cls.classes {
+            self.link_class_docs(nested, module);
+        }
+    }
+
+    /// Rewrite Rust intra-doc links to cross-references of the Python items,
+    /// i.e. MyST roles for Sphinx or links to the anchors for Markdown.
+    ///
+    /// Items not documented in a public module are written as code.
+    fn link_doc(&self, doc: &str, module: &str) -> String {
+        let markdown = self
+            .stub_info
+            .config
+            .doc_gen
+            .as_ref()
+            .is_some_and(|config| config.format == crate::docgen::DocFormat::Markdown);
+        let link_resolver = self.create_context(module).link_resolver;
+        self.stub_info.doc_links.rewrite(doc, |target, code| {
+            // Items of private modules like `pkg._core` are documented where they are re-exported
+            let suffix = format!(".{}", target.name);
+            let doc_module = link_resolver
+                .resolve_link(&target.fqn(), module)
+                .map(|(doc_module, _)| doc_module)
+                .or_else(|| {
+                    self.export_map
+                        .iter()
+                        .find(|(fqn, _)| fqn.ends_with(&suffix))
+                        .map(|(_, doc_module)| doc_module.clone())
+                });
+            let Some(doc_module) = doc_module else {
+                return format!("`{}`", target.name);
+            };
+            if markdown {
+                let text = if code {
+                    format!("`{}`", target.name)
+                } else {
+                    target.name.clone()
+                };
+                return format!("[{text}]({doc_module}.md#{doc_module}.{})", target.name);
+            }
+            let role = match target.kind {
+                crate::generate::DocLinkKind::Class => "py:class",
+                crate::generate::DocLinkKind::Function => "py:func",
+            };
+            format!("{{{role}}}`~{doc_module}.{}`", target.name)
+        })
+    }
+
+    /// Rewrite Rust intra-doc links to plain Python names, for descriptions rendered as text
+    fn plain_doc(&self, doc: &str) -> String {
+        self.stub_info
+            .doc_links
+            .rewrite(doc, |target, _| target.name.clone())
+    }
+
     /// Correct a type expression to use export_map for link targets
     fn correct_type_expr(&self, type_expr: &mut DocTypeExpr) {
         if let Some(link_target) = &mut type_expr.link_target {
@@ -600,3 +1052,97 @@ impl<'a> DocPackageBuilder<'a> {
         prefix_stripper::strip_internal_prefixes(display)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docgen::DocGenConfig;
+    use crate::generate::{ClassId, MethodDef, MethodType, Module, Parameters};
+    use crate::type_info::PyClassInfoBuilder;
+    use crate::{PyStubType, StubGenConfig, TypeInfo};
+
+    fn stub_info(inherited_members: bool) -> StubInfo {
+        let (_, mut base) = PyClassInfoBuilder::new("Base")
+            .module("pkg")
+            .getter("x", f64::type_output())
+            .getter("y", f64::type_output())
+            .build();
+        base.methods
+            .entry("area".to_string())
+            .or_default()
+            .push(MethodDef {
+                name: "area",
+                parameters: Parameters::new(),
+                r#return: f64::type_output(),
+                doc: "",
+                r#type: MethodType::Instance,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+            });
+        // Overrides `y` of the base class
+        let (_, sub) = PyClassInfoBuilder::new("Sub")
+            .module("pkg")
+            .base(TypeInfo::locally_defined("Base", "pkg".into()))
+            .getter("y", f64::type_output())
+            .eq()
+            .build();
+        let class = [base, sub]
+            .into_iter()
+            .map(|class| {
+                let id = ClassId::Runtime {
+                    module: "pkg".to_string(),
+                    name: class.name.to_string(),
+                };
+                (id, class)
+            })
+            .collect();
+        let module = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            class,
+            ..Default::default()
+        };
+        StubInfo {
+            modules: [("pkg".to_string(), module)].into(),
+            python_root: PathBuf::from("/tmp"),
+            is_mixed_layout: false,
+            config: StubGenConfig {
+                doc_gen: Some(DocGenConfig {
+                    inherited_members,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            pyproject_dir: None,
+            default_module_name: "pkg".to_string(),
+            project_name: "pkg".to_string(),
+            doc_links: Default::default(),
+        }
+    }
+
+    fn build_sub(stub_info: &StubInfo) -> Result<DocClass> {
+        let sub = stub_info.modules["pkg"]
+            .class
+            .values()
+            .find(|class| class.name == "Sub")
+            .unwrap();
+        DocPackageBuilder::new(stub_info).build_class_def("pkg", sub)
+    }
+
+    #[test]
+    fn test_inherited_members() -> Result<()> {
+        let sub = build_sub(&stub_info(true))?;
+        assert_eq!(sub.inherited.len(), 1);
+        let inherited = &sub.inherited[0];
+        assert_eq!(inherited.base.display, "Base");
+        // Private `__eq__` and the overridden `y` are not listed
+        assert_eq!(inherited.methods, ["area"]);
+        assert_eq!(inherited.attributes, ["x"]);
+
+        let sub = build_sub(&stub_info(false))?;
+        assert!(sub.inherited.is_empty());
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Configuration for documentation generation
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Configuration for documentation generation from pyproject.toml
@@ -40,6 +41,46 @@ pub struct DocGenConfig {
     /// Set to false to skip generating index.rst, useful when a hand-maintained index.rst exists.
     #[serde(rename = "generate-index", default = "default_generate_index")]
     pub generate_index: bool,
+
+    /// Output format (default: "sphinx")
+    /// "markdown" writes one GitHub-flavored Markdown page per module instead of the Sphinx files.
+    #[serde(default)]
+    pub format: DocFormat,
+
+    /// List methods and attributes inherited from base classes (default: false)
+    /// Members are grouped by the base class defining them and linked to its documentation.
+    #[serde(rename = "inherited-members", default)]
+    pub inherited_members: bool,
+
+    /// Documentation URLs of external modules, e.g. `numpy = "https://numpy.org/doc/stable/reference/generated/{fqn}.html"`
+    /// Types in a module starting with the key link to the URL, where `{fqn}` is replaced by the fully qualified name
+    /// of the type, or `#<fqn>` is appended if the URL has no `{fqn}`. The longest matching module is used.
+    #[serde(default)]
+    pub intersphinx: BTreeMap<String, String>,
+
+    /// Name of the API manifest file, e.g. "api_manifest.json" (default: not written)
+    /// The manifest lists the public API in a stable JSON schema to compare releases.
+    #[serde(rename = "manifest-output", default)]
+    pub manifest_output: Option<String>,
+
+    /// Move the `# Arguments` section of Rust doc comments into descriptions of the parameters (default: true)
+    /// Set to false for docstrings written in another convention, e.g. numpydoc.
+    #[serde(
+        rename = "parse-rust-arguments",
+        default = "default_parse_rust_arguments"
+    )]
+    pub parse_rust_arguments: bool,
+}
+
+/// Output format of the generated documentation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocFormat {
+    /// JSON data, the Sphinx extension and `.rst` pages rendered by it
+    #[default]
+    Sphinx,
+    /// Plain `.md` pages, e.g. for mkdocs or GitHub
+    Markdown,
 }
 
 impl Default for DocGenConfig {
@@ -53,6 +94,11 @@ impl Default for DocGenConfig {
             contents_table: false,
             separate_items: false,
             generate_index: default_generate_index(),
+            format: DocFormat::default(),
+            inherited_members: false,
+            intersphinx: BTreeMap::new(),
+            manifest_output: None,
+            parse_rust_arguments: default_parse_rust_arguments(),
         }
     }
 }
@@ -73,6 +119,10 @@ fn default_generate_index() -> bool {
     true
 }
 
+fn default_parse_rust_arguments() -> bool {
+    true
+}
+
 impl DocGenConfig {
     /// Validate configuration consistency
     pub fn validate(&self) -> anyhow::Result<()> {
@@ -86,6 +136,10 @@ impl DocGenConfig {
             "separate-items = true requires separate-pages = true. \
              Item pages need separate module pages to link from."
         );
+        anyhow::ensure!(
+            !(self.separate_items && self.format == DocFormat::Markdown),
+            "separate-items = true is not supported with format = \"markdown\"."
+        );
         Ok(())
     }
 
@@ -1,5 +1,7 @@
 //! Export resolution for determining which items are publicly accessible
 
+pub mod json;
+
 use crate::docgen::util::is_hidden_module;
 use crate::generate::Module;
 use std::collections::{BTreeMap, BTreeSet};
@@ -49,6 +51,12 @@ impl<'a> ExportResolver<'a> {
             }
         }
 
+        for alias in module.deprecated_aliases.keys() {
+            if !alias.starts_with('_') {
+                exports.insert((*alias).to_string());
+            }
+        }
+
         for var in module.variables.keys() {
             if !var.starts_with('_') {
                 exports.insert((*var).to_string());
@@ -63,7 +71,7 @@ impl<'a> ExportResolver<'a> {
 
         // Add items from module re-exports (from reexport_module_members!)
         for re_export in &module.module_re_exports {
-            exports.extend(re_export.items.iter().cloned());
+            exports.extend(re_export.exported_names());
         }
 
         // Add verbatim entries (allows explicitly exporting underscore items)
@@ -0,0 +1,292 @@
+//! Machine-readable API manifest
+//!
+//! Unlike `api_reference.json` read by the Sphinx extension, the manifest contains only the API surface,
+//! keyed by name with types as displayed in the documentation, so that the manifests of two releases
+//! can be compared by a plain JSON diff.
+
+use crate::docgen::ir::{
+    DeprecatedInfo, DocClass, DocDefaultValue, DocFunction, DocItem, DocModule, DocPackage,
+    DocSignature,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Version of the manifest schema, incremented on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// API surface of a package
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiManifest {
+    pub schema_version: u32,
+    pub package: String,
+    pub modules: BTreeMap<String, ModuleManifest>,
+}
+
+/// Public items of a module
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModuleManifest {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub classes: BTreeMap<String, ClassManifest>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub functions: BTreeMap<String, FunctionManifest>,
+    /// Type alias name to its definition
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub type_aliases: BTreeMap<String, String>,
+    /// Variable name to its type, if annotated
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, Option<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub submodules: Vec<String>,
+}
+
+/// A class or an enum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassManifest {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bases: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub methods: BTreeMap<String, FunctionManifest>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, AttributeManifest>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<DeprecatedInfo>,
+}
+
+/// A function or a method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionManifest {
+    /// Signatures of all overloads, e.g. `(a: int, b: int = 0) -> str`
+    pub signatures: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_async: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<DeprecatedInfo>,
+}
+
+/// A class attribute or property
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeManifest {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_property: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_readonly: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<DeprecatedInfo>,
+}
+
+impl From<&DocPackage> for ApiManifest {
+    fn from(package: &DocPackage) -> Self {
+        let mut package = package.clone();
+        package.normalize();
+        Self {
+            schema_version: SCHEMA_VERSION,
+            package: package.name.clone(),
+            modules: package
+                .modules
+                .iter()
+                .map(|(name, module)| (name.clone(), ModuleManifest::from(module)))
+                .collect(),
+        }
+    }
+}
+
+impl From<&DocModule> for ModuleManifest {
+    fn from(module: &DocModule) -> Self {
+        let mut manifest = ModuleManifest::default();
+        for item in &module.items {
+            match item {
+                DocItem::Class(class) => {
+                    insert_class(&mut manifest.classes, class.name.clone(), class);
+                }
+                DocItem::Function(function) => {
+                    manifest
+                        .functions
+                        .insert(function.name.clone(), FunctionManifest::from(function));
+                }
+                DocItem::TypeAlias(alias) => {
+                    manifest
+                        .type_aliases
+                        .insert(alias.name.clone(), alias.definition.display.clone());
+                }
+                DocItem::Variable(var) => {
+                    manifest.variables.insert(
+                        var.name.clone(),
+                        var.type_.as_ref().map(|t| t.display.clone()),
+                    );
+                }
+                DocItem::Module(submodule) => manifest.submodules.push(submodule.fqn.clone()),
+            }
+        }
+        manifest.submodules.sort();
+        manifest
+    }
+}
+
+/// Insert `class` as `name`, and the classes nested in it as `name.Nested`
+fn insert_class(classes: &mut BTreeMap<String, ClassManifest>, name: String, class: &DocClass) {
+    for nested in &class.classes {
+        insert_class(classes, format!("{}.{}", name, nested.name), nested);
+    }
+    classes.insert(name, ClassManifest::from(class));
+}
+
+impl From<&DocClass> for ClassManifest {
+    fn from(class: &DocClass) -> Self {
+        Self {
+            bases: class.bases.iter().map(|b| b.display.clone()).collect(),
+            methods: class
+                .methods
+                .iter()
+                .map(|m| (m.name.clone(), FunctionManifest::from(m)))
+                .collect(),
+            attributes: class
+                .attributes
+                .iter()
+                .map(|attr| {
+                    (
+                        attr.name.clone(),
+                        AttributeManifest {
+                            type_: attr.type_.as_ref().map(|t| t.display.clone()),
+                            is_property: attr.is_property,
+                            is_readonly: attr.is_readonly,
+                            deprecated: attr.deprecated.clone(),
+                        },
+                    )
+                })
+                .collect(),
+            deprecated: class.deprecated.clone(),
+        }
+    }
+}
+
+impl From<&DocFunction> for FunctionManifest {
+    fn from(function: &DocFunction) -> Self {
+        Self {
+            signatures: function.signatures.iter().map(signature).collect(),
+            is_async: function.is_async,
+            deprecated: function.deprecated.clone(),
+        }
+    }
+}
+
+/// Signature as `(a: int, b: int = 0) -> str`
+fn signature(sig: &DocSignature) -> String {
+    let params: Vec<_> = sig
+        .parameters
+        .iter()
+        .map(|param| {
+            let mut rendered = format!("{}: {}", param.name, param.type_.display);
+            match &param.default {
+                Some(DocDefaultValue::Simple { value }) => rendered += &format!(" = {}", value),
+                Some(DocDefaultValue::Expression(expr)) => {
+                    rendered += &format!(" = {}", expr.display)
+                }
+                None => {}
+            }
+            rendered
+        })
+        .collect();
+    match &sig.return_type {
+        Some(ret) => format!("({}) -> {}", params.join(", "), ret.display),
+        None => format!("({})", params.join(", ")),
+    }
+}
+
+/// Render the API manifest of `package` to JSON
+pub fn render_manifest(package: &DocPackage) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&ApiManifest::from(package))?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::docgen::ir::{DocParameter, DocTypeExpr};
+
+    fn type_expr(display: &str) -> DocTypeExpr {
+        DocTypeExpr {
+            display: display.to_string(),
+            link_target: None,
+            children: Vec::new(),
+            external_url: None,
+        }
+    }
+
+    #[test]
+    fn test_render_manifest() -> Result<()> {
+        let function = DocFunction {
+            name: "sum".to_string(),
+            doc: "Sum of two numbers".to_string(),
+            signatures: vec![DocSignature {
+                parameters: vec![
+                    DocParameter {
+                        name: "a".to_string(),
+                        type_: type_expr("int"),
+                        default: None,
+                        doc: String::new(),
+                        keys: Vec::new(),
+                    },
+                    DocParameter {
+                        name: "b".to_string(),
+                        type_: type_expr("int"),
+                        default: Some(DocDefaultValue::Simple {
+                            value: "0".to_string(),
+                        }),
+                        doc: String::new(),
+                        keys: Vec::new(),
+                    },
+                ],
+                return_type: Some(type_expr("int")),
+                return_iterator: None,
+            }],
+            is_async: false,
+            deprecated: Some(DeprecatedInfo {
+                since: Some("1.0".to_string()),
+                note: None,
+            }),
+            raises: Vec::new(),
+            releases_gil: false,
+            source: None,
+        };
+        let package = DocPackage {
+            name: "pkg".to_string(),
+            modules: BTreeMap::from([(
+                "pkg".to_string(),
+                DocModule {
+                    name: "pkg".to_string(),
+                    doc: String::new(),
+                    items: vec![DocItem::Function(function)],
+                    submodules: Vec::new(),
+                },
+            )]),
+            export_map: BTreeMap::new(),
+            config: Default::default(),
+            free_threading: false,
+        };
+        assert_eq!(
+            render_manifest(&package)?,
+            r#"{
+  "schema_version": 1,
+  "package": "pkg",
+  "modules": {
+    "pkg": {
+      "functions": {
+        "sum": {
+          "signatures": [
+            "(a: int, b: int = 0) -> int"
+          ],
+          "deprecated": {
+            "since": "1.0",
+            "note": null
+          }
+        }
+      }
+    }
+  }
+}"#
+        );
+        Ok(())
+    }
+}
@@ -14,6 +14,9 @@ pub struct DocPackage {
     pub export_map: BTreeMap<String, String>,
     /// Documentation generation configuration
     pub config: DocGenConfig,
+    /// The modules support free-threaded Python builds, set by the `free-threading` option
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub free_threading: bool,
 }
 
 /// A single module's documentation
@@ -54,6 +57,15 @@ pub struct DocFunction {
     pub signatures: Vec<DocSignature>,
     pub is_async: bool,
     pub deprecated: Option<DeprecatedInfo>,
+    /// Exception classes raised by the function, listed by `#[gen_stub(raises(...))]` or from the error type of `Result<T, E>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raises: Vec<DocTypeExpr>,
+    /// Releases the GIL while running, declared by `#[gen_stub(releases_gil)]`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub releases_gil: bool,
+    /// Rust source location as `<file>:<line>`, present when `source-comments` is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 /// A single function signature
@@ -61,6 +73,9 @@ pub struct DocFunction {
 pub struct DocSignature {
     pub parameters: Vec<DocParameter>,
     pub return_type: Option<DocTypeExpr>,
+    /// `Iterator[...]` or `Generator[...]` base of the returned class declared by `#[gen_stub(iterator(...))]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_iterator: Option<DocTypeExpr>,
 }
 
 /// A function parameter
@@ -69,6 +84,19 @@ pub struct DocParameter {
     pub name: String,
     pub type_: DocTypeExpr,
     pub default: Option<DocDefaultValue>,
+    /// Description from the `# Arguments` section of the Rust doc comment
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub doc: String,
+    /// Known keys of a dict parameter given by `#[gen_stub(dict_keys(...))]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<DocDictKey>,
+}
+
+/// A key of a dict parameter shown in the "Keys" table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocDictKey {
+    pub name: String,
+    pub type_: DocTypeExpr,
 }
 
 /// A type alias definition
@@ -77,6 +105,9 @@ pub struct DocTypeAlias {
     pub name: String,
     pub doc: String,
     pub definition: DocTypeExpr,
+    /// Present for the old names of classes declared by [crate::deprecated_alias!]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<DeprecatedInfo>,
 }
 
 /// A module-level variable
@@ -95,7 +126,46 @@ pub struct DocClass {
     pub bases: Vec<DocTypeExpr>,
     pub methods: Vec<DocFunction>,
     pub attributes: Vec<DocAttribute>,
+    /// Constructor parameters taken from the `__new__` or `__init__` signature
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<DocClassParameter>,
+    /// Declared as `#[gen_stub(picklable)]`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub picklable: bool,
+    /// Members inherited from base classes, present when `inherited-members` is enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inherited: Vec<DocInheritedMembers>,
+    /// Classes nested in the class in the declaration order, e.g. the variants of a complex enum
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<DocClass>,
     pub deprecated: Option<DeprecatedInfo>,
+    /// Rust source location as `<file>:<line>`, present when `source-comments` is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Members of a base class not overridden by the documented class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocInheritedMembers {
+    /// The base class defining the members
+    pub base: DocTypeExpr,
+    pub methods: Vec<String>,
+    pub attributes: Vec<String>,
+}
+
+/// A constructor parameter shown in the "Parameters" table of a class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocClassParameter {
+    pub name: String,
+    pub type_: DocTypeExpr,
+    pub default: Option<DocDefaultValue>,
+    /// Description from the `Args:` section of the constructor or class docstring,
+    /// or the `# Arguments` section of the Rust doc comment of the constructor
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub doc: String,
+    /// Known keys of a dict parameter given by `#[gen_stub(dict_keys(...))]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<DocDictKey>,
 }
 
 /// A class attribute
@@ -121,6 +191,9 @@ pub struct DocTypeExpr {
     pub link_target: Option<LinkTarget>,
     /// Generic parameters (recursively)
     pub children: Vec<DocTypeExpr>,
+    /// Documentation URL of an external type, resolved by the `intersphinx` configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_url: Option<String>,
 }
 
 /// Default value that may contain type references
@@ -259,6 +332,10 @@ impl DocClass {
         for method in &mut self.methods {
             method.normalize();
         }
+
+        for class in &mut self.classes {
+            class.normalize();
+        }
     }
 }
 
@@ -6,11 +6,21 @@ use std::collections::BTreeMap;
 /// Link resolver implementing Haddock-style resolution
 pub struct LinkResolver<'a> {
     export_map: &'a BTreeMap<String, String>,
+    intersphinx: Option<&'a BTreeMap<String, String>>,
 }
 
 impl<'a> LinkResolver<'a> {
     pub fn new(export_map: &'a BTreeMap<String, String>) -> Self {
-        Self { export_map }
+        Self {
+            export_map,
+            intersphinx: None,
+        }
+    }
+
+    /// Resolve types of external modules by the `intersphinx` configuration
+    pub fn with_intersphinx(mut self, intersphinx: &'a BTreeMap<String, String>) -> Self {
+        self.intersphinx = Some(intersphinx);
+        self
     }
 
     /// Get the export map for resolving type links
@@ -42,6 +52,30 @@ impl<'a> LinkResolver<'a> {
         None
     }
 
+    /// Resolve the documentation URL of an external type, e.g. `numpy.ndarray`,
+    /// by the longest module prefix in the `intersphinx` configuration
+    ///
+    /// Items of this package are never resolved here
+    pub fn resolve_external(&self, item_fqn: &str) -> Option<String> {
+        if self.export_map.contains_key(item_fqn) {
+            return None;
+        }
+        let (_, url) = self
+            .intersphinx?
+            .iter()
+            .filter(|(module, _)| {
+                item_fqn
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .max_by_key(|(module, _)| module.len())?;
+        Some(if url.contains("{fqn}") {
+            url.replace("{fqn}", item_fqn)
+        } else {
+            format!("{url}#{item_fqn}")
+        })
+    }
+
     /// Check if a module is private (has underscore segments)
     fn is_private_module(&self, module_name: &str) -> bool {
         module_name.split('.').any(|part| part.starts_with('_'))
@@ -75,3 +109,42 @@ impl<'a> LinkResolver<'a> {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve_external() {
+        let export_map = BTreeMap::from([("pkg.Array".to_string(), "pkg".to_string())]);
+        let intersphinx = BTreeMap::from([
+            (
+                "numpy".to_string(),
+                "https://numpy.org/doc/stable/reference/generated/{fqn}.html".to_string(),
+            ),
+            (
+                "numpy.typing".to_string(),
+                "https://numpy.org/doc/stable/reference/typing.html".to_string(),
+            ),
+            (
+                "pkg".to_string(),
+                "https://example.com/pkg.html".to_string(),
+            ),
+        ]);
+        let resolver = LinkResolver::new(&export_map).with_intersphinx(&intersphinx);
+        assert_eq!(
+            resolver.resolve_external("numpy.ndarray").as_deref(),
+            Some("https://numpy.org/doc/stable/reference/generated/numpy.ndarray.html")
+        );
+        assert_eq!(
+            resolver.resolve_external("numpy.typing.NDArray").as_deref(),
+            Some("https://numpy.org/doc/stable/reference/typing.html#numpy.typing.NDArray")
+        );
+        assert_eq!(resolver.resolve_external("numpyx.Array"), None);
+        assert_eq!(resolver.resolve_external("pkg.Array"), None);
+        assert_eq!(
+            LinkResolver::new(&export_map).resolve_external("numpy.ndarray"),
+            None
+        );
+    }
+}
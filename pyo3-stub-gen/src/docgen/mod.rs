@@ -1,6 +1,6 @@
 //! Documentation generation module for pyo3-stub-gen
 //!
-//! This module handles generating Sphinx-compatible API reference documentation
+//! This module handles generating Sphinx-compatible (or plain Markdown) API reference documentation
 //! from the rich type metadata that pyo3-stub-gen possesses.
 
 pub mod builder;
@@ -13,5 +13,5 @@ pub mod render;
 pub mod types;
 pub mod util;
 
-pub use config::DocGenConfig;
+pub use config::{DocFormat, DocGenConfig};
 pub use ir::DocPackage;
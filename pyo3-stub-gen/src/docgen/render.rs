@@ -1,10 +1,16 @@
 //! JSON rendering and Sphinx extension embedding
 
+pub mod markdown;
+
 use crate::docgen::config::DocGenConfig;
 use crate::docgen::ir::{DocItem, DocPackage};
-use crate::Result;
+use anyhow::Result;
 use std::path::Path;
 
+/// Note on the index page if `free-threading` is enabled
+const FREE_THREADING_NOTE: &str =
+    "These modules support free-threaded Python builds: importing them does not enable the GIL.";
+
 /// Render DocPackage to JSON string
 pub fn render_to_json(package: &DocPackage) -> Result<String> {
     // Normalize for deterministic output
@@ -155,6 +161,11 @@ pub fn generate_index_rst(
         );
     }
 
+    if package.free_threading {
+        content.push_str(FREE_THREADING_NOTE);
+        content.push_str("\n\n");
+    }
+
     // Create toctree
     content.push_str(".. toctree::\n");
     content.push_str("   :maxdepth: 2\n");
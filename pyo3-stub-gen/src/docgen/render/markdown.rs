@@ -0,0 +1,804 @@
+//! GitHub-flavored Markdown rendering
+//!
+//! Each module is written to `<module>.md`. Items are preceded by an HTML anchor of their fully qualified name,
+//! e.g. `<a id="pkg.MyClass.method"></a>`, which is the same id as mkdocstrings and Sphinx use,
+//! so that type references link to `<module>.md#<fqn>`.
+
+use super::FREE_THREADING_NOTE;
+use crate::docgen::config::DocGenConfig;
+use crate::docgen::ir::*;
+use anyhow::Result;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Note on a function with `#[gen_stub(releases_gil)]`
+const RELEASES_GIL_NOTE: &str =
+    "**GIL:** released while running, so other Python threads are not blocked.";
+
+/// Generate a Markdown page for each module
+pub fn generate_module_pages(package: &DocPackage, output_dir: &Path) -> Result<()> {
+    let mut package = package.clone();
+    package.normalize();
+    for (module_name, module) in &package.modules {
+        let filename = format!("{}.md", module_name);
+        std::fs::write(output_dir.join(filename), render_module(module)?)?;
+    }
+    Ok(())
+}
+
+/// Generate index.md listing all module pages
+pub fn generate_index_md(
+    package: &DocPackage,
+    output_dir: &Path,
+    config: &DocGenConfig,
+) -> Result<()> {
+    let mut content = String::new();
+
+    let title = match &config.index_title {
+        Some(title) if title.is_empty() => "API Reference".to_string(),
+        Some(title) => title.clone(),
+        None => format!("{} API Reference", package.name),
+    };
+    writeln!(content, "# {}\n", title)?;
+
+    match &config.intro_message {
+        Some(intro) if intro.is_empty() => {}
+        Some(intro) => writeln!(content, "{}\n", intro)?,
+        None => writeln!(
+            content,
+            "This is the API reference documentation generated from Rust code using [pyo3-stub-gen](https://github.com/Jij-Inc/pyo3-stub-gen).\n"
+        )?,
+    }
+
+    if package.free_threading {
+        writeln!(content, "{}\n", FREE_THREADING_NOTE)?;
+    }
+
+    writeln!(content, "## Modules\n")?;
+    for module_name in package.modules.keys() {
+        writeln!(content, "- [`{0}`]({0}.md)", module_name)?;
+    }
+
+    std::fs::write(output_dir.join("index.md"), content)?;
+    Ok(())
+}
+
+/// Render the page of a module
+fn render_module(module: &DocModule) -> Result<String> {
+    let mut out = String::new();
+    writeln!(out, "# `{}`\n", module.name)?;
+    write_doc(&mut out, &module.doc)?;
+
+    let submodules: Vec<_> = module
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            DocItem::Module(m) => Some(m),
+            _ => None,
+        })
+        .collect();
+    if !submodules.is_empty() {
+        writeln!(out, "## Submodules\n")?;
+        for submodule in submodules {
+            write!(out, "- [`{0}`]({0}.md)", submodule.fqn)?;
+            match submodule.doc.lines().find(|line| !line.trim().is_empty()) {
+                Some(summary) => writeln!(out, " — {}", summary.trim())?,
+                None => writeln!(out)?,
+            }
+        }
+        writeln!(out)?;
+    }
+
+    let sections: [(&str, fn(&DocItem) -> bool); 4] = [
+        ("Classes", |item| matches!(item, DocItem::Class(_))),
+        ("Functions", |item| matches!(item, DocItem::Function(_))),
+        ("Type Aliases", |item| matches!(item, DocItem::TypeAlias(_))),
+        ("Variables", |item| matches!(item, DocItem::Variable(_))),
+    ];
+    for (title, filter) in sections {
+        let items: Vec<_> = module.items.iter().filter(|item| filter(*item)).collect();
+        if items.is_empty() {
+            continue;
+        }
+        writeln!(out, "## {}\n", title)?;
+        for item in items {
+            let fqn = |name: &str| format!("{}.{}", module.name, name);
+            match item {
+                DocItem::Class(c) => write_class(&mut out, c, &fqn(&c.name), "###")?,
+                DocItem::Function(f) => write_function(&mut out, f, &fqn(&f.name), "###")?,
+                DocItem::TypeAlias(t) => {
+                    writeln!(out, "<a id=\"{}\"></a>\n", fqn(&t.name))?;
+                    writeln!(out, "### `{}`\n", t.name)?;
+                    writeln!(out, "{} = {}\n", escape(&t.name), type_expr(&t.definition))?;
+                    write_deprecated(&mut out, t.deprecated.as_ref())?;
+                    write_doc(&mut out, &t.doc)?;
+                }
+                DocItem::Variable(v) => {
+                    writeln!(out, "<a id=\"{}\"></a>\n", fqn(&v.name))?;
+                    writeln!(out, "### `{}`\n", v.name)?;
+                    if let Some(type_) = &v.type_ {
+                        writeln!(out, "{}: {}\n", escape(&v.name), type_expr(type_))?;
+                    }
+                    write_doc(&mut out, &v.doc)?;
+                }
+                DocItem::Module(_) => {}
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Write a class and the classes nested in it under a heading of `level`
+fn write_class(out: &mut String, class: &DocClass, fqn: &str, level: &str) -> Result<()> {
+    let member_level = format!("{level}#");
+    writeln!(out, "<a id=\"{}\"></a>\n", fqn)?;
+    writeln!(out, "{} class `{}`\n", level, class.name)?;
+    if !class.bases.is_empty() {
+        let bases: Vec<_> = class.bases.iter().map(type_expr).collect();
+        writeln!(out, "Bases: {}\n", bases.join(", "))?;
+    }
+    write_deprecated(out, class.deprecated.as_ref())?;
+    if let Some(source) = &class.source {
+        writeln!(out, "Defined in `{}`\n", source)?;
+    }
+    write_doc(out, &class.doc)?;
+    if !class.parameters.is_empty() {
+        write_parameters(
+            out,
+            class.parameters.iter().map(|param| {
+                (
+                    param.name.as_str(),
+                    &param.type_,
+                    param.default.as_ref(),
+                    param.doc.as_str(),
+                )
+            }),
+        )?;
+        write_keys_tables(
+            out,
+            class
+                .parameters
+                .iter()
+                .map(|param| (param.name.as_str(), param.keys.as_slice())),
+        )?;
+    }
+    if class.picklable {
+        writeln!(
+            out,
+            "Instances of this class can be pickled with `pickle`.\n"
+        )?;
+    }
+    for inherited in &class.inherited {
+        let members: Vec<_> = inherited
+            .methods
+            .iter()
+            .chain(&inherited.attributes)
+            .map(|name| match &inherited.base.link_target {
+                Some(target) => {
+                    let class_name = target.fqn.rsplit('.').next().unwrap_or(&target.fqn);
+                    format!(
+                        "[`{}`]({}.md#{}.{}.{})",
+                        name, target.doc_module, target.doc_module, class_name, name
+                    )
+                }
+                None => format!("`{}`", name),
+            })
+            .collect();
+        writeln!(
+            out,
+            "Inherited from {}: {}\n",
+            type_expr(&inherited.base),
+            members.join(", ")
+        )?;
+    }
+
+    for method in &class.methods {
+        let method_fqn = format!("{}.{}", fqn, method.name);
+        write_function(out, method, &method_fqn, &member_level)?;
+    }
+
+    for attr in &class.attributes {
+        writeln!(out, "<a id=\"{}.{}\"></a>\n", fqn, attr.name)?;
+        let kind = if attr.is_property {
+            "property"
+        } else {
+            "attribute"
+        };
+        writeln!(out, "{} {} `{}`\n", member_level, kind, attr.name)?;
+        if let Some(type_) = &attr.type_ {
+            writeln!(out, "{}: {}\n", escape(&attr.name), type_expr(type_))?;
+        }
+        write_deprecated(out, attr.deprecated.as_ref())?;
+        if attr.is_readonly {
+            writeln!(out, "Read-only property.\n")?;
+        }
+        write_doc(out, &attr.doc)?;
+    }
+
+    for nested in &class.classes {
+        let nested_fqn = format!("{}.{}", fqn, nested.name);
+        write_class(out, nested, &nested_fqn, &member_level)?;
+    }
+    Ok(())
+}
+
+/// Write a function or method with all overload signatures under a heading of `level`
+fn write_function(out: &mut String, function: &DocFunction, fqn: &str, level: &str) -> Result<()> {
+    writeln!(out, "<a id=\"{}\"></a>\n", fqn)?;
+    writeln!(out, "{} `{}`\n", level, function.name)?;
+    for signature in &function.signatures {
+        let params: Vec<_> = signature
+            .parameters
+            .iter()
+            .map(|param| {
+                let mut rendered = format!("{}: {}", escape(&param.name), type_expr(&param.type_));
+                if let Some(default) = &param.default {
+                    write!(rendered, " = {}", default_value(default)).unwrap();
+                }
+                rendered
+            })
+            .collect();
+        let prefix = if function.is_async { "async " } else { "" };
+        write!(
+            out,
+            "{}**{}**({})",
+            prefix,
+            escape(&function.name),
+            params.join(", ")
+        )?;
+        if let Some(return_type) = &signature.return_type {
+            write!(out, " -> {}", type_expr(return_type))?;
+        }
+        if let Some(iterator) = &signature.return_iterator {
+            write!(out, " ({})", type_expr(iterator))?;
+        }
+        writeln!(out, "\n")?;
+    }
+    write_deprecated(out, function.deprecated.as_ref())?;
+    if let Some(source) = &function.source {
+        writeln!(out, "Defined in `{}`\n", source)?;
+    }
+    if function.releases_gil {
+        writeln!(out, "{}\n", RELEASES_GIL_NOTE)?;
+    }
+    write_doc(out, &function.doc)?;
+    // Described by the `# Arguments` section of the Rust doc comment, once even if in several overloads
+    let mut described: Vec<&DocParameter> = Vec::new();
+    for param in function
+        .signatures
+        .iter()
+        .flat_map(|signature| &signature.parameters)
+    {
+        if !param.doc.is_empty() && !described.iter().any(|p| p.name == param.name) {
+            described.push(param);
+        }
+    }
+    if !described.is_empty() {
+        write_parameters(
+            out,
+            described.into_iter().map(|param| {
+                (
+                    param.name.as_str(),
+                    &param.type_,
+                    param.default.as_ref(),
+                    param.doc.as_str(),
+                )
+            }),
+        )?;
+    }
+    write_keys_tables(
+        out,
+        function
+            .signatures
+            .iter()
+            .flat_map(|signature| &signature.parameters)
+            .map(|param| (param.name.as_str(), param.keys.as_slice())),
+    )?;
+    if !function.raises.is_empty() {
+        let raises: Vec<_> = function.raises.iter().map(type_expr).collect();
+        writeln!(out, "**Raises:** {}\n", raises.join(", "))?;
+    }
+    Ok(())
+}
+
+/// Write a "Parameters" list of name, type, default value and description
+fn write_parameters<'a>(
+    out: &mut String,
+    parameters: impl Iterator<
+        Item = (
+            &'a str,
+            &'a DocTypeExpr,
+            Option<&'a DocDefaultValue>,
+            &'a str,
+        ),
+    >,
+) -> Result<()> {
+    writeln!(out, "**Parameters**\n")?;
+    for (name, type_, default, doc) in parameters {
+        write!(out, "- **{}** ({}", escape(name), type_expr(type_))?;
+        if let Some(default) = default {
+            write!(out, ", default: {}", default_value(default))?;
+        }
+        write!(out, ")")?;
+        if !doc.is_empty() {
+            write!(out, " – {}", doc)?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Write a "Keys" table for each dict parameter with known keys, once even if it appears in several overloads
+fn write_keys_tables<'a>(
+    out: &mut String,
+    parameters: impl Iterator<Item = (&'a str, &'a [DocDictKey])>,
+) -> Result<()> {
+    let mut written = Vec::new();
+    for (name, keys) in parameters {
+        if keys.is_empty() || written.contains(&name) {
+            continue;
+        }
+        written.push(name);
+        writeln!(out, "**Keys of `{}`**\n", name)?;
+        writeln!(out, "| Key | Type |")?;
+        writeln!(out, "| --- | --- |")?;
+        for key in keys {
+            // `|` of union types would end the cell
+            let type_ = type_expr(&key.type_).replace('|', "\\|");
+            writeln!(out, "| `{}` | {} |", key.name, type_)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn write_deprecated(out: &mut String, deprecated: Option<&DeprecatedInfo>) -> Result<()> {
+    let Some(deprecated) = deprecated else {
+        return Ok(());
+    };
+    let text = match (&deprecated.since, &deprecated.note) {
+        (Some(since), Some(note)) => format!("since {} — {}", since, note),
+        (Some(since), None) => format!("since {}", since),
+        (None, Some(note)) => note.clone(),
+        (None, None) => "This item is deprecated.".to_string(),
+    };
+    writeln!(out, "> **Deprecated**: {}\n", text)?;
+    Ok(())
+}
+
+/// Docstrings are already written in Markdown (MyST)
+fn write_doc(out: &mut String, doc: &str) -> Result<()> {
+    let doc = doc.trim();
+    if !doc.is_empty() {
+        writeln!(out, "{}\n", doc)?;
+    }
+    Ok(())
+}
+
+/// Render a type expression with links to the documented items, following `_build_type_expr` of the Sphinx extension
+fn type_expr(expr: &DocTypeExpr) -> String {
+    let base_name = |display: &str| -> String {
+        display
+            .split_once('[')
+            .map_or(display, |(base, _)| base)
+            .to_string()
+    };
+    let is_generic = expr
+        .display
+        .split_once('[')
+        .is_some_and(|(base, _)| !base.contains('|'))
+        && expr.display.ends_with(']');
+    let external = |text: &str| match &expr.external_url {
+        Some(url) => format!("[{}]({})", escape(text), url),
+        None => escape(text),
+    };
+    match (&expr.link_target, expr.children.is_empty()) {
+        (Some(target), false) => format!(
+            "{}\\[{}\\]",
+            link(&base_name(&expr.display), target),
+            join_children(&expr.children, ", ")
+        ),
+        (Some(target), true) => link(&expr.display, target),
+        (None, false) if is_generic => format!(
+            "{}\\[{}\\]",
+            external(&base_name(&expr.display)),
+            join_children(&expr.children, ", ")
+        ),
+        (None, false) => join_children(&expr.children, " | "),
+        (None, true) => external(&expr.display),
+    }
+}
+
+fn join_children(children: &[DocTypeExpr], sep: &str) -> String {
+    children.iter().map(type_expr).collect::<Vec<_>>().join(sep)
+}
+
+fn default_value(value: &DocDefaultValue) -> String {
+    match value {
+        DocDefaultValue::Simple { value } => escape(value),
+        DocDefaultValue::Expression(expr) => {
+            let mut rendered = String::new();
+            let mut pos = 0;
+            let mut refs: Vec<_> = expr.type_refs.iter().collect();
+            refs.sort_by_key(|r| r.offset);
+            for r in refs {
+                if r.offset < pos || r.offset + r.text.len() > expr.display.len() {
+                    continue;
+                }
+                rendered.push_str(&escape(&expr.display[pos..r.offset]));
+                match &r.link_target {
+                    Some(target) => rendered.push_str(&link(&r.text, target)),
+                    None => rendered.push_str(&escape(&r.text)),
+                }
+                pos = r.offset + r.text.len();
+            }
+            rendered.push_str(&escape(&expr.display[pos..]));
+            rendered
+        }
+    }
+}
+
+/// Link to the anchor of `target` in the page of the module where it is documented
+fn link(text: &str, target: &LinkTarget) -> String {
+    if target.kind == ItemKind::Module {
+        return format!("[{}]({}.md)", escape(text), target.fqn);
+    }
+    // `pkg._core.Class.attr` is documented as `pkg.Class.attr` if re-exported from `pkg`
+    let depth = if target.attribute.is_some() { 2 } else { 1 };
+    let segments: Vec<_> = target.fqn.rsplitn(depth + 1, '.').collect();
+    let name = segments[..depth.min(segments.len())]
+        .iter()
+        .rev()
+        .copied()
+        .collect::<Vec<_>>()
+        .join(".");
+    format!(
+        "[{}]({}.md#{}.{})",
+        escape(text),
+        target.doc_module,
+        target.doc_module,
+        name
+    )
+}
+
+/// Escape characters having a meaning in Markdown inline text
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn simple(display: &str) -> DocTypeExpr {
+        DocTypeExpr {
+            display: display.to_string(),
+            link_target: None,
+            children: Vec::new(),
+            external_url: None,
+        }
+    }
+
+    fn class_target(fqn: &str, doc_module: &str) -> LinkTarget {
+        LinkTarget {
+            fqn: fqn.to_string(),
+            doc_module: doc_module.to_string(),
+            kind: ItemKind::Class,
+            attribute: None,
+        }
+    }
+
+    #[test]
+    fn test_type_expr() {
+        let linked = DocTypeExpr {
+            display: "A".to_string(),
+            link_target: Some(class_target("pkg._core.A", "pkg")),
+            children: Vec::new(),
+            external_url: None,
+        };
+        assert_eq!(type_expr(&linked), "[A](pkg.md#pkg.A)");
+
+        let generic = DocTypeExpr {
+            display: "list[A | None]".to_string(),
+            link_target: None,
+            children: vec![DocTypeExpr {
+                display: "A | None".to_string(),
+                link_target: None,
+                children: vec![linked, simple("None")],
+                external_url: None,
+            }],
+            external_url: None,
+        };
+        assert_eq!(type_expr(&generic), r"list\[[A](pkg.md#pkg.A) | None\]");
+
+        let external = DocTypeExpr {
+            display: "ndarray".to_string(),
+            link_target: None,
+            children: Vec::new(),
+            external_url: Some(
+                "https://numpy.org/doc/stable/reference/generated/numpy.ndarray.html".to_string(),
+            ),
+        };
+        assert_eq!(
+            type_expr(&external),
+            "[ndarray](https://numpy.org/doc/stable/reference/generated/numpy.ndarray.html)"
+        );
+    }
+
+    #[test]
+    fn test_attribute_link() {
+        let target = LinkTarget {
+            fqn: "pkg.sub.Color.RED".to_string(),
+            doc_module: "pkg.sub".to_string(),
+            kind: ItemKind::Class,
+            attribute: Some("RED".to_string()),
+        };
+        assert_eq!(
+            link("Color.RED", &target),
+            "[Color.RED](pkg.sub.md#pkg.sub.Color.RED)"
+        );
+    }
+
+    #[test]
+    fn test_render_function() -> Result<()> {
+        let function = DocFunction {
+            name: "sum_as_string".to_string(),
+            doc: "Formats the sum of two numbers as string.\n".to_string(),
+            signatures: vec![DocSignature {
+                parameters: vec![
+                    DocParameter {
+                        name: "a".to_string(),
+                        type_: simple("int"),
+                        default: None,
+                        doc: String::new(),
+                        keys: Vec::new(),
+                    },
+                    DocParameter {
+                        name: "b".to_string(),
+                        type_: simple("int"),
+                        default: Some(DocDefaultValue::Simple {
+                            value: "0".to_string(),
+                        }),
+                        doc: String::new(),
+                        keys: Vec::new(),
+                    },
+                ],
+                return_type: Some(simple("str")),
+                return_iterator: None,
+            }],
+            is_async: false,
+            deprecated: None,
+            raises: Vec::new(),
+            releases_gil: false,
+            source: None,
+        };
+        let mut out = String::new();
+        write_function(&mut out, &function, "pkg.sum_as_string", "###")?;
+        assert_eq!(
+            out,
+            r#"<a id="pkg.sum_as_string"></a>
+
+### `sum_as_string`
+
+**sum\_as\_string**(a: int, b: int = 0) -> str
+
+Formats the sum of two numbers as string.
+
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_releases_gil() -> Result<()> {
+        let function = DocFunction {
+            name: "checksum".to_string(),
+            doc: "Checksum of the data.".to_string(),
+            signatures: vec![DocSignature {
+                parameters: Vec::new(),
+                return_type: Some(simple("int")),
+                return_iterator: None,
+            }],
+            is_async: false,
+            deprecated: None,
+            raises: Vec::new(),
+            releases_gil: true,
+            source: None,
+        };
+        let mut out = String::new();
+        write_function(&mut out, &function, "pkg.checksum", "###")?;
+        assert!(out.contains(
+            "**checksum**() -> int\n\n\
+             **GIL:** released while running, so other Python threads are not blocked.\n\n\
+             Checksum of the data."
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_return_iterator() -> Result<()> {
+        let function = DocFunction {
+            name: "countdown".to_string(),
+            doc: String::new(),
+            signatures: vec![DocSignature {
+                parameters: Vec::new(),
+                return_type: Some(simple("Countdown")),
+                return_iterator: Some(simple("Iterator[int]")),
+            }],
+            is_async: false,
+            deprecated: None,
+            raises: Vec::new(),
+            releases_gil: false,
+            source: None,
+        };
+        let mut out = String::new();
+        write_function(&mut out, &function, "pkg.countdown", "###")?;
+        assert!(out.contains("**countdown**() -> Countdown (Iterator\\[int\\])\n\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_parameter_docs() -> Result<()> {
+        let function = DocFunction {
+            name: "scale".to_string(),
+            doc: "Scale the value.".to_string(),
+            signatures: vec![DocSignature {
+                parameters: vec![
+                    DocParameter {
+                        name: "factor".to_string(),
+                        type_: simple("float"),
+                        default: Some(DocDefaultValue::Simple {
+                            value: "1.0".to_string(),
+                        }),
+                        doc: "Scale factor".to_string(),
+                        keys: Vec::new(),
+                    },
+                    DocParameter {
+                        name: "undocumented".to_string(),
+                        type_: simple("int"),
+                        default: None,
+                        doc: String::new(),
+                        keys: Vec::new(),
+                    },
+                ],
+                return_type: None,
+                return_iterator: None,
+            }],
+            is_async: false,
+            deprecated: None,
+            raises: Vec::new(),
+            releases_gil: false,
+            source: None,
+        };
+        let mut out = String::new();
+        write_function(&mut out, &function, "pkg.scale", "###")?;
+        assert!(out.ends_with(
+            "Scale the value.\n\n**Parameters**\n\n- **factor** (float, default: 1.0) – Scale factor\n\n"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_dict_keys() -> Result<()> {
+        let config = DocParameter {
+            name: "config".to_string(),
+            type_: simple("dict[str, Any]"),
+            default: None,
+            doc: String::new(),
+            keys: vec![
+                DocDictKey {
+                    name: "host".to_string(),
+                    type_: simple("str"),
+                },
+                DocDictKey {
+                    name: "port".to_string(),
+                    type_: simple("int | None"),
+                },
+            ],
+        };
+        let signature = DocSignature {
+            parameters: vec![config],
+            return_type: None,
+            return_iterator: None,
+        };
+        let function = DocFunction {
+            name: "connect".to_string(),
+            doc: String::new(),
+            // Keys shared by overloads are listed once
+            signatures: vec![signature.clone(), signature],
+            is_async: false,
+            deprecated: None,
+            raises: Vec::new(),
+            releases_gil: false,
+            source: None,
+        };
+        let mut out = String::new();
+        write_function(&mut out, &function, "pkg.connect", "###")?;
+        assert!(out.ends_with(
+            r#"**Keys of `config`**
+
+| Key | Type |
+| --- | --- |
+| `host` | str |
+| `port` | int \| None |
+
+"#
+        ));
+        assert_eq!(out.matches("**Keys of").count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_nested_class() -> Result<()> {
+        fn class(
+            name: &str,
+            doc: &str,
+            attributes: Vec<DocAttribute>,
+            classes: Vec<DocClass>,
+        ) -> DocClass {
+            DocClass {
+                name: name.to_string(),
+                doc: doc.to_string(),
+                bases: Vec::new(),
+                methods: Vec::new(),
+                attributes,
+                parameters: Vec::new(),
+                picklable: false,
+                inherited: Vec::new(),
+                classes,
+                deprecated: None,
+                source: None,
+            }
+        }
+        let int = DocAttribute {
+            name: "int".to_string(),
+            doc: "The integer value".to_string(),
+            type_: Some(simple("int")),
+            is_property: true,
+            is_readonly: true,
+            deprecated: None,
+        };
+        let number = class(
+            "Number",
+            "",
+            Vec::new(),
+            vec![class("INTEGER", "Integer variant", vec![int], Vec::new())],
+        );
+        let mut out = String::new();
+        write_class(&mut out, &number, "pkg.Number", "###")?;
+        assert_eq!(
+            out,
+            r#"<a id="pkg.Number"></a>
+
+### class `Number`
+
+<a id="pkg.Number.INTEGER"></a>
+
+#### class `INTEGER`
+
+Integer variant
+
+<a id="pkg.Number.INTEGER.int"></a>
+
+##### property `int`
+
+int: int
+
+Read-only property.
+
+The integer value
+
+"#
+        );
+        Ok(())
+    }
+}
@@ -193,7 +193,16 @@ def _build_type_expr(type_expr):
         xref += nodes.Text(display)
         return xref
 
-    # Case 3: Union type (has children but no link_target)
+    # Case 3: External type resolved by the `intersphinx` configuration
+    elif type_expr.get('external_url'):
+        base_name = display.split('[')[0] if children and '[' in display else display
+        reference = nodes.reference('', '', refuri=type_expr['external_url'], internal=False)
+        reference += nodes.Text(base_name)
+        if children:
+            return _build_generic_with_children(reference, children)
+        return reference
+
+    # Case 4: Union type (has children but no link_target)
     elif children:
         # Check if this is a union type by looking for '|' in display
         if '|' in display:
@@ -206,7 +215,7 @@ def _build_type_expr(type_expr):
             base_node = _parse_and_link_type(base_name)
             return _build_generic_with_children(base_node, children)
 
-    # Case 4: External type or simple builtin (no link, no children)
+    # Case 5: External type or simple builtin (no link, no children)
     else:
         # Parse the type expression and create intersphinx links for external types
         return _parse_and_link_type(display)
@@ -553,6 +562,11 @@ def _build_callable_signatures(signatures, name, module_name, fullname, env):
         if sig.get('return_type'):
             returns = desc_returns()
             returns += _build_type_expr(sig['return_type'])
+            # Iterator or Generator base of the returned class, e.g. `Countdown (Iterator[int])`
+            if sig.get('return_iterator'):
+                returns += nodes.Text(' (')
+                returns += _build_type_expr(sig['return_iterator'])
+                returns += nodes.Text(')')
             sig_node += returns
 
         sig_nodes.append(sig_node)
@@ -756,6 +770,170 @@ def _build_deprecated_note(deprecated):
     container += content_para
     return container
 
+def _build_source_note(source):
+    """Build a paragraph pointing to the Rust source location if present."""
+    if not source:
+        return None
+    para = nodes.paragraph(classes=['source-location'])
+    para += nodes.Text('Defined in ')
+    para += nodes.literal(text=source)
+    return para
+
+def _build_gil_note(releases_gil):
+    """Build a paragraph noting that the function releases the GIL while running."""
+    if not releases_gil:
+        return None
+    para = nodes.paragraph(classes=['releases-gil'])
+    para += nodes.strong(text='GIL:')
+    para += nodes.Text(' released while running, so other Python threads are not blocked.')
+    return para
+
+def _build_pickle_note(picklable):
+    """Build a paragraph noting pickle support if the class is picklable."""
+    if not picklable:
+        return None
+    para = nodes.paragraph(classes=['picklable'])
+    para += nodes.Text('Instances of this class can be pickled with ')
+    para += nodes.literal(text='pickle')
+    para += nodes.Text('.')
+    return para
+
+def _build_inherited_members(inherited):
+    """Build paragraphs listing members inherited from each base class, linked to their documentation."""
+    paragraphs = []
+    for entry in inherited or []:
+        base = entry['base']
+        link_target = base.get('link_target')
+        para = nodes.paragraph(classes=['inherited-members'])
+        para += nodes.Text('Inherited from ')
+        para += _build_type_expr(base)
+        para += nodes.Text(': ')
+        members = [(name, 'meth') for name in entry.get('methods', [])]
+        members += [(name, 'attr') for name in entry.get('attributes', [])]
+        for i, (name, reftype) in enumerate(members):
+            if i > 0:
+                para += nodes.Text(', ')
+            if link_target:
+                class_name = link_target['fqn'].split('.')[-1]
+                xref = pending_xref(
+                    '',
+                    refdomain='py',
+                    reftype=reftype,
+                    reftarget=f"{link_target['doc_module']}.{class_name}.{name}",
+                    refexplicit=True,
+                )
+                xref += nodes.literal(text=name)
+                para += xref
+            else:
+                para += nodes.literal(text=name)
+        paragraphs.append(para)
+    return paragraphs
+
+def _build_raises_field(raises):
+    """Build a "Raises" field list linking to the exception classes if present."""
+    if not raises:
+        return None
+    field_list = nodes.field_list()
+    field = nodes.field()
+    field += nodes.field_name(text='Raises')
+    body = nodes.field_body()
+    para = nodes.paragraph()
+    for i, exception in enumerate(raises):
+        if i > 0:
+            para += nodes.Text(', ')
+        para += _build_type_expr(exception)
+    body += para
+    field += body
+    field_list += field
+    return field_list
+
+def _build_parameters_field(parameters):
+    """Build a "Parameters" field list for the class constructor or described parameters if present."""
+    if not parameters:
+        return None
+    field_list = nodes.field_list()
+    field = nodes.field()
+    field += nodes.field_name(text='Parameters')
+    body = nodes.field_body()
+    bullets = nodes.bullet_list()
+    for param in parameters:
+        para = nodes.paragraph()
+        para += nodes.strong(text=param['name'])
+        para += nodes.Text(' (')
+        para += _build_type_expr(param['type_'])
+        if param.get('default'):
+            para += nodes.Text(', default: ')
+            para += _build_default_value(param['default'])
+        para += nodes.Text(')')
+        if param.get('doc'):
+            para += nodes.Text(' \u2013 ' + param['doc'])
+        item = nodes.list_item()
+        item += para
+        bullets += item
+    body += bullets
+    field += body
+    field_list += field
+    return field_list
+
+def _build_keys_tables(parameters):
+    """Build a "Keys of <param>" table for each dict parameter with known keys, once per parameter name."""
+    tables = []
+    written = set()
+    for param in parameters:
+        keys = param.get('keys')
+        if not keys or param['name'] in written:
+            continue
+        written.add(param['name'])
+        rubric = nodes.rubric()
+        rubric += nodes.Text('Keys of ')
+        rubric += nodes.literal(text=param['name'])
+        tables.append(rubric)
+
+        table = nodes.table()
+        tgroup = nodes.tgroup(cols=2)
+        tgroup += nodes.colspec(colwidth=1)
+        tgroup += nodes.colspec(colwidth=2)
+        thead = nodes.thead()
+        header = nodes.row()
+        for title in ('Key', 'Type'):
+            entry = nodes.entry()
+            entry += nodes.paragraph(text=title)
+            header += entry
+        thead += header
+        tgroup += thead
+        tbody = nodes.tbody()
+        for key in keys:
+            row = nodes.row()
+            name_entry = nodes.entry()
+            name_para = nodes.paragraph()
+            name_para += nodes.literal(text=key['name'])
+            name_entry += name_para
+            row += name_entry
+            type_entry = nodes.entry()
+            type_para = nodes.paragraph()
+            type_para += _build_type_expr(key['type_'])
+            type_entry += type_para
+            row += type_entry
+            tbody += row
+        tgroup += tbody
+        table += tgroup
+        tables.append(table)
+    return tables
+
+def _signature_parameters(signatures):
+    """Parameters of all overload signatures in order"""
+    return [param for sig in signatures for param in sig['parameters']]
+
+def _described_parameters(signatures):
+    """Parameters described by the `# Arguments` section of the Rust doc comment, once per parameter name"""
+    described = []
+    names = set()
+    for param in _signature_parameters(signatures):
+        if param.get('doc') and param['name'] not in names:
+            names.add(param['name'])
+            described.append(param)
+    return described
+
 def _build_function(env, func, module_name):
     """Build function with all overload signatures"""
     fullname = f"{module_name}.{func['name']}"
@@ -777,8 +955,21 @@ def _build_function(env, func, module_name):
     dep_note = _build_deprecated_note(func.get('deprecated'))
     if dep_note is not None:
         content += dep_note
+    source_note = _build_source_note(func.get('source'))
+    if source_note is not None:
+        content += source_note
+    gil_note = _build_gil_note(func.get('releases_gil'))
+    if gil_note is not None:
+        content += gil_note
     if func.get('doc'):
         _append_myst_doc(content, func['doc'], env)
+    parameters_field = _build_parameters_field(_described_parameters(func['signatures']))
+    if parameters_field is not None:
+        content += parameters_field
+    content.extend(_build_keys_tables(_signature_parameters(func['signatures'])))
+    raises_field = _build_raises_field(func.get('raises'))
+    if raises_field is not None:
+        content += raises_field
     if len(content.children) > 0:
         desc_node += content
 
@@ -808,10 +999,14 @@ def _build_type_alias(env, alias, module_name):
     sig_node += _build_type_expr(alias['definition'])
     desc_node += sig_node
 
-    # Docstring (using helper)
+    # Docstring and deprecation of old names (using helpers)
+    content = desc_content()
+    dep_note = _build_deprecated_note(alias.get('deprecated'))
+    if dep_note is not None:
+        content += dep_note
     if alias.get('doc'):
-        content = desc_content()
         _append_myst_doc(content, alias['doc'], env)
+    if len(content.children) > 0:
         desc_node += content
 
     # Register (using helper)
@@ -819,9 +1014,10 @@ def _build_type_alias(env, alias, module_name):
 
     return [index_node, desc_node]
 
-def _build_class(env, cls, module_name):
-    """Build class documentation"""
-    fullname = f"{module_name}.{cls['name']}"
+def _build_class(env, cls, module_name, qualname=None):
+    """Build class documentation, with `qualname` such as `Outer.Inner` for nested classes"""
+    qualname = qualname or cls['name']
+    fullname = f"{module_name}.{qualname}"
     sig_id = fullname
 
     # CREATE INDEX NODE for the class
@@ -859,7 +1055,19 @@ def _build_class(env, cls, module_name):
     dep_note = _build_deprecated_note(cls.get('deprecated'))
     if dep_note is not None:
         content += dep_note
+    source_note = _build_source_note(cls.get('source'))
+    if source_note is not None:
+        content += source_note
     _append_myst_doc(content, cls.get('doc'), env)
+    parameters_field = _build_parameters_field(cls.get('parameters'))
+    if parameters_field is not None:
+        content += parameters_field
+        content.extend(_build_keys_tables(cls['parameters']))
+    pickle_note = _build_pickle_note(cls.get('picklable'))
+    if pickle_note is not None:
+        content += pickle_note
+    for inherited_para in _build_inherited_members(cls.get('inherited')):
+        content += inherited_para
 
     # Register with Python domain (using helper)
     _register_py_object(env, fullname, 'class', sig_id)
@@ -889,6 +1097,10 @@ def _build_class(env, cls, module_name):
             method_content += dep_note
         if method.get('doc'):
             _append_myst_doc(method_content, method['doc'], env)
+        parameters_field = _build_parameters_field(_described_parameters(method['signatures']))
+        if parameters_field is not None:
+            method_content += parameters_field
+        method_content.extend(_build_keys_tables(_signature_parameters(method['signatures'])))
         if len(method_content.children) > 0:
             method_desc += method_content
 
@@ -943,6 +1155,10 @@ def _build_class(env, cls, module_name):
 
         content += attr_desc
 
+    # Render nested classes, e.g. the variants of a complex enum
+    for nested in cls.get('classes', []):
+        content.extend(_build_class(env, nested, module_name, f"{qualname}.{nested['name']}"))
+
     # Add the complete content block to desc_node
     desc_node += content
 
@@ -249,11 +249,13 @@ impl<'a> TypeRenderer<'a> {
                 // Simple type - try to create a link
                 let display = self.strip_module_prefix(name);
                 let link_target = self.try_create_link_for_name(name, type_info);
+                let external_url = self.external_url(name, &link_target);
 
                 DocTypeExpr {
                     display,
                     link_target,
                     children: Vec::new(),
+                    external_url,
                 }
             }
 
@@ -261,6 +263,7 @@ impl<'a> TypeRenderer<'a> {
                 // Generic type - render base and recursively render args
                 let base_display = self.strip_module_prefix(base);
                 let base_link = self.try_create_link_for_name(base, type_info);
+                let external_url = self.external_url(base, &base_link);
 
                 // Recursively render all arguments
                 let children: Vec<DocTypeExpr> = args
@@ -277,6 +280,7 @@ impl<'a> TypeRenderer<'a> {
                     display,
                     link_target: base_link,
                     children,
+                    external_url,
                 }
             }
 
@@ -296,6 +300,7 @@ impl<'a> TypeRenderer<'a> {
                     display,
                     link_target: None, // Union itself has no link
                     children,
+                    external_url: None,
                 }
             }
         }
@@ -333,6 +338,14 @@ impl<'a> TypeRenderer<'a> {
         })
     }
 
+    /// Documentation URL of a type not linked in this package, e.g. `numpy.ndarray`
+    fn external_url(&self, name: &str, link_target: &Option<LinkTarget>) -> Option<String> {
+        if link_target.is_some() {
+            return None;
+        }
+        self.link_resolver.resolve_external(name)
+    }
+
     /// Strip module prefixes from type names
     /// Remove "typing.", "builtins.", "package.submod."
     /// Keep only bare names: "Optional[ClassA]" not "typing.Optional[sub_mod.ClassA]"
@@ -1,5 +1,7 @@
 //! Utility functions for documentation generation
 
+use std::collections::BTreeMap;
+
 /// Module for prefix stripping utilities
 pub mod prefix_stripper {
     /// Strip standard library prefixes from type expressions
@@ -197,11 +199,181 @@ pub fn public_parent_module(module_name: &str) -> String {
     public_parts.join(".")
 }
 
+/// Parameter descriptions in a Google-style `Args:` section of a docstring.
+///
+/// Entries are written as `name: description` or `name (type): description`,
+/// and more indented lines continue the previous entry.
+/// `Arguments:` and `Parameters:` are accepted as the section header as well.
+pub fn parse_args_section(doc: &str) -> BTreeMap<String, String> {
+    let mut args = BTreeMap::new();
+    let mut lines = doc.lines();
+    let Some(header_indent) = lines.by_ref().find_map(|line| {
+        matches!(line.trim(), "Args:" | "Arguments:" | "Parameters:").then(|| indent_of(line))
+    }) else {
+        return args;
+    };
+
+    let mut entry_indent = None;
+    let mut current: Option<(String, String)> = None;
+    for line in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = indent_of(line);
+        if indent <= header_indent {
+            break;
+        }
+        let entry_indent = *entry_indent.get_or_insert(indent);
+        if indent > entry_indent {
+            if let Some((_, desc)) = current.as_mut() {
+                if !desc.is_empty() {
+                    desc.push(' ');
+                }
+                desc.push_str(line.trim());
+            }
+            continue;
+        }
+        if indent < entry_indent {
+            break;
+        }
+        if let Some((name, desc)) = current.take() {
+            args.insert(name, desc);
+        }
+        let Some((name, desc)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let name = name.split_once('(').map_or(name, |(name, _)| name).trim();
+        current = Some((name.to_string(), desc.trim().to_string()));
+    }
+    if let Some((name, desc)) = current {
+        args.insert(name, desc);
+    }
+    args
+}
+
+/// Split the `# Arguments` section of a Rust doc comment off the docstring.
+///
+/// Entries are list items written as ``* `name` - description`` or `- name: description`,
+/// and following lines of an item continue its description. The section ends at the next heading.
+/// `# Parameters` and `# Args` are accepted as the section header as well.
+/// Returns the docstring without the section and the descriptions, or `None` if there is no such section.
+pub fn split_rust_arguments_section(doc: &str) -> Option<(String, BTreeMap<String, String>)> {
+    let mut rest = Vec::new();
+    let mut args = BTreeMap::new();
+    let mut current: Option<(String, String)> = None;
+    let mut in_section = false;
+    let mut found = false;
+    let mut in_code = false;
+    for line in doc.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+        }
+        // Lines indented by 4 spaces or more are code blocks in Markdown
+        let heading = (!in_code && indent_of(line) < 4 && trimmed.starts_with('#'))
+            .then(|| trimmed.trim_start_matches('#'))
+            .filter(|title| title.starts_with(' '))
+            .map(str::trim);
+        if let Some(title) = heading {
+            if let Some((name, desc)) = current.take() {
+                args.insert(name, desc);
+            }
+            in_section = matches!(title, "Arguments" | "Parameters" | "Args");
+            if in_section {
+                found = true;
+                continue;
+            }
+        }
+        if !in_section {
+            rest.push(line);
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(item) = trimmed
+            .strip_prefix("* ")
+            .or_else(|| trimmed.strip_prefix("- "))
+        else {
+            if let Some((_, desc)) = current.as_mut() {
+                if !desc.is_empty() {
+                    desc.push(' ');
+                }
+                desc.push_str(trimmed);
+            }
+            continue;
+        };
+        if let Some((name, desc)) = current.take() {
+            args.insert(name, desc);
+        }
+        current = split_argument_item(item.trim());
+    }
+    if let Some((name, desc)) = current {
+        args.insert(name, desc);
+    }
+    if !found {
+        return None;
+    }
+    let doc = rest.join("\n").trim_end().to_string();
+    Some((doc, args))
+}
+
+/// Name and description of an item like ``* `name` - description``
+fn split_argument_item(item: &str) -> Option<(String, String)> {
+    let (name, desc) = if let Some(quoted) = item.strip_prefix('`') {
+        quoted.split_once('`')?
+    } else {
+        item.split_once(" - ")
+            .or_else(|| item.split_once(':'))
+            .unwrap_or((item, ""))
+    };
+    let desc = desc
+        .trim_start()
+        .trim_start_matches(['-', ':', '\u{2013}'])
+        .trim();
+    Some((name.trim().to_string(), desc.to_string()))
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::prefix_stripper::*;
     use super::*;
 
+    #[test]
+    fn test_parse_args_section() {
+        let doc = "Create a point.\n\nArgs:\n    x (float): Horizontal position.\n    y: Vertical position,\n        measured from the top.\n\nReturns:\n    z: not a parameter\n";
+        let args = parse_args_section(doc);
+        assert_eq!(args.len(), 2);
+        assert_eq!(args["x"], "Horizontal position.");
+        assert_eq!(args["y"], "Vertical position, measured from the top.");
+        assert!(parse_args_section("No sections here").is_empty());
+    }
+
+    #[test]
+    fn test_split_rust_arguments_section() {
+        let doc = "Add two numbers.\n\n# Arguments\n\n* `a` - The first number\n* `b` - The second number,\n  added to `a`.\n\n# Examples\n\n```python\n# not a heading\nadd(1, 2)\n```\n";
+        let (rest, args) = split_rust_arguments_section(doc).unwrap();
+        assert_eq!(
+            rest,
+            "Add two numbers.\n\n# Examples\n\n```python\n# not a heading\nadd(1, 2)\n```"
+        );
+        assert_eq!(args.len(), 2);
+        assert_eq!(args["a"], "The first number");
+        assert_eq!(args["b"], "The second number, added to `a`.");
+
+        let (rest, args) =
+            split_rust_arguments_section("Scale.\n\n## Parameters\n- factor: Scale factor")
+                .unwrap();
+        assert_eq!(rest, "Scale.");
+        assert_eq!(args["factor"], "Scale factor");
+
+        assert!(split_rust_arguments_section("Args:\n    x: Google style").is_none());
+    }
+
     #[test]
     fn test_strip_stdlib_prefixes() {
         assert_eq!(strip_stdlib_prefixes("typing.Optional"), "Optional");
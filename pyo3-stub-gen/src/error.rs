@@ -0,0 +1,246 @@
+//! Error type of the stub generator
+//!
+//! [StubGenError] distinguishes failures which tools embedding the generator may react to,
+//! e.g. a missing `pyproject.toml` or classes sharing a Python name.
+//! Other failures are kept as [anyhow::Error] in [StubGenError::Other].
+//!
+//! It converts from and into [anyhow::Error], so that `?` works in functions returning either of them:
+//!
+//! ```no_run
+//! use pyo3_stub_gen::{pyproject::find_pyproject_toml, StubGenError};
+//!
+//! fn find() -> anyhow::Result<()> {
+//!     match find_pyproject_toml(".") {
+//!         Ok(path) => println!("Found {}", path.display()),
+//!         Err(StubGenError::PyprojectNotFound { searched }) => {
+//!             println!("Not found in {} directories", searched.len())
+//!         }
+//!         Err(err) => return Err(err.into()),
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::{fmt, io, path::PathBuf};
+
+/// Error returned by [StubInfo](crate::StubInfo) and [PyProject](crate::pyproject::PyProject)
+///
+/// [Debug] shows the message with its causes like [anyhow::Error],
+/// so that it is readable when returned from `main`.
+#[derive(thiserror::Error)]
+#[non_exhaustive]
+pub enum StubGenError {
+    /// `pyproject.toml` is not found by [find_pyproject_toml](crate::pyproject::find_pyproject_toml)
+    #[error("pyproject.toml is not found. Searched paths:\n{}", display_paths(.searched))]
+    PyprojectNotFound {
+        /// Paths of `pyproject.toml` searched, from the start directory to the workspace root
+        searched: Vec<PathBuf>,
+    },
+
+    /// A file cannot be read
+    #[error("Failed to read {}", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// `pyproject.toml` cannot be parsed
+    #[error("Failed to parse {}", .path.display())]
+    InvalidPyproject {
+        path: PathBuf,
+        #[source]
+        source: Box<toml::de::Error>,
+    },
+
+    /// Classes and enums of different Rust types share a Python name in a module,
+    /// and are not listed in `allow-shadowing`
+    #[error(
+        "Python names are defined more than once, add them to `allow-shadowing` if intended:\n  {:<40} {}\n{}",
+        "name",
+        "defined at",
+        display_duplicates(.0)
+    )]
+    DuplicateClasses(Vec<DuplicateClass>),
+
+    /// Type aliases refer to types which are not defined in the modules they are expected in
+    #[error(
+        "Type aliases refer to undefined types:\n  {}\n\
+         Check that the targets are registered by `#[gen_stub_*]` macros in the intended modules, \
+         and are not hidden by `#[gen_stub(skip)]`",
+        display_unresolved(.0)
+    )]
+    UnresolvedTypes(Vec<UnresolvedType>),
+
+    /// The class to add methods to by its Python name, e.g. by `gen_methods_from_python!`, is not found
+    #[error(
+        "Class `{name}` to add methods by name is not found{}",
+        display_module(.module)
+    )]
+    ClassNotFound {
+        name: String,
+        /// Module the class is searched in, or all modules if `None`
+        module: Option<String>,
+    },
+
+    /// Other failures, e.g. invalid configurations or errors of `format-command`
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+/// Python name defined by several Rust types, reported by [StubGenError::DuplicateClasses]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateClass {
+    /// Fully qualified Python name, e.g. `pkg.Counter`
+    pub name: String,
+    /// Source locations of the definitions as `(file, line)`, sorted
+    pub locations: Vec<(&'static str, u32)>,
+}
+
+/// Type referred to by a type alias but not defined, reported by [StubGenError::UnresolvedTypes]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UnresolvedType {
+    /// Fully qualified name of the type alias, e.g. `pkg.Points`
+    pub alias: String,
+    /// Fully qualified name of the type, e.g. `pkg.Point`
+    pub target: String,
+    /// Module expected to define the type
+    pub module: String,
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("  {}", path.display()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn display_module(module: &Option<String>) -> String {
+    module
+        .as_deref()
+        .map(|module| format!(" in module `{module}`"))
+        .unwrap_or_default()
+}
+
+fn display_duplicates(duplicates: &[DuplicateClass]) -> String {
+    duplicates
+        .iter()
+        .flat_map(|duplicate| {
+            duplicate
+                .locations
+                .iter()
+                .map(move |(file, line)| format!("  {:<40} {file}:{line}", duplicate.name))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn display_unresolved(unresolved: &[UnresolvedType]) -> String {
+    unresolved
+        .iter()
+        .map(|ty| {
+            format!(
+                "type alias `{}` refers to `{}`, which is not defined in module `{}`",
+                ty.alias, ty.target, ty.module
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n  ")
+}
+
+impl fmt::Debug for StubGenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let StubGenError::Other(err) = self {
+            return fmt::Debug::fmt(err, f);
+        }
+        write!(f, "{self}")?;
+        let mut source = std::error::Error::source(self);
+        if source.is_some() {
+            write!(f, "\n\nCaused by:")?;
+        }
+        while let Some(err) = source {
+            write!(f, "\n    {err}")?;
+            source = err.source();
+        }
+        Ok(())
+    }
+}
+
+impl From<anyhow::Error> for StubGenError {
+    /// Take back [StubGenError] converted into [anyhow::Error] as is,
+    /// unless context is added to it, which is kept in [StubGenError::Other]
+    fn from(err: anyhow::Error) -> Self {
+        if (*err).is::<StubGenError>() {
+            err.downcast().unwrap_or_else(StubGenError::Other)
+        } else {
+            StubGenError::Other(err)
+        }
+    }
+}
+
+/// Allow `?` on I/O errors in functions returning [crate::Result], as with [anyhow::Result]
+impl From<io::Error> for StubGenError {
+    fn from(err: io::Error) -> Self {
+        StubGenError::Other(err.into())
+    }
+}
+
+/// Allow `?` on Python errors, e.g. of importing the module in [StubInfo::reconcile_runtime_modules](crate::StubInfo::reconcile_runtime_modules)
+impl From<pyo3::PyErr> for StubGenError {
+    fn from(err: pyo3::PyErr) -> Self {
+        StubGenError::Other(err.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_from_anyhow() {
+        let err = anyhow::Error::from(StubGenError::ClassNotFound {
+            name: "Foo".to_string(),
+            module: Some("pkg".to_string()),
+        });
+        let err = StubGenError::from(err);
+        assert!(matches!(err, StubGenError::ClassNotFound { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Class `Foo` to add methods by name is not found in module `pkg`"
+        );
+
+        // Context is not dropped by unwrapping
+        let err = Err::<(), _>(err).context("Failed to gather").unwrap_err();
+        let err = StubGenError::from(err);
+        assert!(matches!(err, StubGenError::Other(_)));
+        assert_eq!(err.to_string(), "Failed to gather");
+
+        let err = StubGenError::from(anyhow::anyhow!("other"));
+        assert!(matches!(err, StubGenError::Other(_)));
+    }
+
+    #[test]
+    fn test_debug() {
+        let err = StubGenError::Io {
+            path: "pyproject.toml".into(),
+            source: io::Error::new(io::ErrorKind::NotFound, "missing"),
+        };
+        assert_eq!(
+            format!("{err:?}"),
+            "Failed to read pyproject.toml\n\nCaused by:\n    missing"
+        );
+    }
+
+    #[test]
+    fn test_display_duplicates() {
+        let err = StubGenError::DuplicateClasses(vec![DuplicateClass {
+            name: "pkg.Counter".to_string(),
+            locations: vec![("src/lib.rs", 10), ("src/lib.rs", 20)],
+        }]);
+        let message = err.to_string();
+        assert!(message.contains(&format!("  {:<40} src/lib.rs:10\n", "pkg.Counter")));
+        assert!(message.ends_with("src/lib.rs:20"));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::type_info::ErrorExceptionInfo;
 use pyo3::exceptions::*;
+use std::any::TypeId;
 
 /// Wrapper of [pyo3::create_exception] macro to create a custom exception with [crate::PyStubType] support.
 ///
@@ -7,6 +9,21 @@ use pyo3::exceptions::*;
 /// [pyo3::create_exception!] macro creates a new exception type as [pyo3::PyErr],
 /// which does not implement [pyo3::PyClass] trait. So it is not a "class" in PyO3 sense,
 /// but we create a [crate::type_info::PyClassInfo] since it will be treated as a class eventually in Python side.
+///
+/// The exception is referenced as a type defined in `$module`, so that an exception
+/// whose base is another custom exception in a different submodule is qualified correctly:
+///
+/// ```ignore
+/// create_exception!(pkg.errors, BaseError, PyException);
+/// create_exception!(pkg.io, ReadError, BaseError); // class ReadError(errors.BaseError): ...
+/// ```
+///
+/// Use [crate::map_error_to_exception!] to list it in the "Raises" section of the generated docs
+/// for functions returning `Result<T, E>` with a Rust error type `E` converted into this exception.
+///
+/// The exception has no attributes other than those of `$base`.
+/// For an exception with attributes, use `#[gen_stub_pyclass]` on `#[pyclass(extends = PyException)]`
+/// with `#[pyo3(get)]` fields or `#[getter]` methods, which are written as properties in the stub file.
 #[macro_export]
 macro_rules! create_exception {
     ($module: expr, $name: ident, $base: ty) => {
@@ -18,13 +35,13 @@ macro_rules! create_exception {
         // Add PyStubType implementation for the created exception
         impl $crate::PyStubType for $name {
             fn type_output() -> $crate::TypeInfo {
-                $crate::TypeInfo::builtin(stringify!($name))
+                $crate::TypeInfo::locally_defined(stringify!($name), stringify!($module).into())
             }
         }
 
         $crate::impl_py_runtime_type!($name);
 
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::PyClassInfo {
                 pyclass_name: stringify!($name),
                 struct_id: std::any::TypeId::of::<$name>,
@@ -38,11 +55,59 @@ macro_rules! create_exception {
                 has_hash: false,
                 has_str: false,
                 subclass: true,
+                buffer: false,
+                picklable: false,
+                slots: None,
+                item_access: &[],
+                iterator: None,
+                type_ignored: None,
+                dataclass_transform: None,
+                file: file!(),
+                line: line!(),
+                column: column!(),
             }
         }
     };
 }
 
+/// Register the exception raised for a Rust error type.
+///
+/// Functions returning `Result<T, E>` list the exception in the "Raises" section of the generated docs.
+/// This is not needed if `E` itself is a `#[pyclass]` exception.
+///
+/// ```ignore
+/// create_exception!(my_module, MyError, PyRuntimeError);
+///
+/// struct MyRustError;
+///
+/// impl From<MyRustError> for PyErr {
+///     fn from(_: MyRustError) -> PyErr {
+///         MyError::new_err("failed")
+///     }
+/// }
+///
+/// map_error_to_exception!(MyRustError, MyError);
+/// ```
+#[macro_export]
+macro_rules! map_error_to_exception {
+    ($error: ty, $exception: ty) => {
+        $crate::submit! {
+            $crate::type_info::ErrorExceptionInfo {
+                error: std::any::TypeId::of::<$error>,
+                exception: std::any::TypeId::of::<$exception>,
+            }
+        }
+    };
+}
+
+/// Exception class raised for a Rust error type, which is the error type itself unless registered by [crate::map_error_to_exception!]
+pub(crate) fn exception_of(error: TypeId) -> TypeId {
+    inventory::iter::<ErrorExceptionInfo>()
+        .find(|info| (info.error)() == error)
+        .map(|info| (info.exception)())
+        .unwrap_or(error)
+}
+
 // Direct PyStubType and PyRuntimeType implementations for PyO3 exception types
 macro_rules! impl_exception_stub_type {
     ($name:ident, $type_name:literal) => {
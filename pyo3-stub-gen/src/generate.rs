@@ -1,39 +1,120 @@
 //! Generate Python typing stub file a.k.a. `*.pyi` file.
 
 mod class;
+mod dataclass_transform;
 mod deprecated;
+mod deprecated_alias;
+mod dict_keys;
+mod doc_links;
 pub(crate) mod docstring;
 mod enum_;
 mod function;
 mod member;
+#[cfg(feature = "rustpython-parser")]
+mod merge;
 mod method;
 mod module;
+mod module_graph;
+mod named_tuple;
+mod numpy_array;
 mod parameters;
+mod python_target;
 pub(crate) mod qualifier;
 mod stub_info;
 mod type_alias;
+mod type_param;
+mod typed_dict;
+mod typing_helper;
 mod variable;
 mod variant_methods;
 
 pub use class::*;
+pub use dataclass_transform::*;
+pub use deprecated_alias::*;
+pub use dict_keys::*;
+pub use doc_links::*;
 pub use docstring::normalize_docstring;
 pub use enum_::*;
 pub use function::*;
 pub use member::*;
 pub use method::*;
 pub use module::*;
+pub use module_graph::*;
+pub use named_tuple::*;
 pub use parameters::*;
 pub use stub_info::*;
 pub use type_alias::*;
+pub use type_param::*;
+pub use typed_dict::*;
+pub use typing_helper::*;
 pub use variable::*;
 
-use crate::stub_type::ImportRef;
-use std::collections::HashSet;
+use crate::{rule_name::RuleName, stub_type::ImportRef, type_info::IgnoreTarget};
+use itertools::Itertools;
+use std::{collections::HashSet, fmt};
+
+/// Serialize a map keyed by Rust type-ids, which are not serializable, as a sequence of its values
+#[cfg(feature = "serde")]
+fn serialize_values<K, V: serde::Serialize, S: serde::Serializer>(
+    map: &std::collections::BTreeMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(map.values())
+}
 
 fn indent() -> &'static str {
     "    "
 }
 
+/// Write `text` indented as the body of an `if` block
+fn write_indented(f: &mut fmt::Formatter, text: &str) -> fmt::Result {
+    for line in text.lines() {
+        if line.is_empty() {
+            writeln!(f)?;
+        } else {
+            writeln!(f, "{}{line}", indent())?;
+        }
+    }
+    Ok(())
+}
+
+/// Trailing `# type: ignore` comment of a line, including the leading spaces
+fn type_ignore_comment(target: &IgnoreTarget) -> String {
+    match target {
+        IgnoreTarget::All => "  # type: ignore".to_string(),
+        IgnoreTarget::Specified(rules) => {
+            let rules_str = rules
+                .iter()
+                .map(|r| {
+                    let result = r.parse::<RuleName>().unwrap();
+                    if let RuleName::Custom(custom) = &result {
+                        log::warn!("Unknown custom rule name '{custom}' used in type ignore. Ensure this is intended.");
+                    }
+                    result
+                })
+                .join(",");
+            format!("  # type: ignore[{rules_str}]")
+        }
+    }
+}
+
+/// Where a type appears in stubs, see `for_each_type_with_role_mut` of stub items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TypeRole {
+    /// Type of values passed from Python, i.e. parameters and setters
+    Input,
+    /// Others, e.g. return values, getters, class attributes and type aliases
+    Output,
+}
+
 pub trait Import {
     fn import(&self) -> HashSet<ImportRef>;
+
+    /// Add the imports to `imports`.
+    ///
+    /// Containers of many items override this to gather the imports into a single set
+    /// instead of allocating a set per item.
+    fn import_into(&self, imports: &mut HashSet<ImportRef>) {
+        imports.extend(self.import());
+    }
 }
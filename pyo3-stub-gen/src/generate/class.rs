@@ -4,9 +4,11 @@ use crate::generate::docstring::normalize_docstring;
 use crate::generate::variant_methods::get_variant_methods;
 use crate::{
     generate::{
-        docstring, indent, GetterDisplay, Import, MemberDef, MethodDef, Parameter,
-        ParameterDefault, Parameters, SetterDisplay,
+        docstring, indent, type_ignore_comment, DataclassTransformDef, GetterDisplay, Import,
+        MemberDef, MethodDef, Parameter, ParameterDefault, Parameters, SetterDisplay, TypeRole,
+        TypingHelperDef,
     },
+    pyproject::RenameRule,
     stub_type::ImportRef,
     type_info::*,
     TypeInfo,
@@ -16,6 +18,7 @@ use std::{fmt, vec};
 
 /// Definition of a Python class.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ClassDef {
     pub name: &'static str,
     pub module: Option<&'static str>,
@@ -25,29 +28,83 @@ pub struct ClassDef {
     pub methods: IndexMap<String, Vec<MethodDef>>,
     pub bases: Vec<TypeInfo>,
     pub classes: Vec<ClassDef>,
+    /// Positional pattern names of a complex enum variant, declared as `__match_args__`.
+    /// `None` for empty variants and those with `#[gen_stub(no_match_args)]`
     pub match_args: Option<Vec<String>>,
     pub subclass: bool,
+    /// Decorated by `@typing.final`, set by [ClassDef::mark_final] if `mark-final` is enabled
+    pub r#final: bool,
+    /// Implements the buffer protocol, i.e. subclass of `Buffer` imported by [BUFFER_IMPORT]
+    pub buffer: bool,
+    /// Supports pickling, i.e. `__getstate__`, `__setstate__` and `__reduce__` are declared
+    /// unless given by `#[pymethods]`. See [ClassDef::add_pickle_methods]
+    pub picklable: bool,
+    /// Defines `__eq__` without `__hash__`, i.e. `#[pyclass(eq)]` without `hash`.
+    /// Python sets `__hash__` to `None` in this case. See [ClassDef::add_unhashable_marker]
+    pub unhashable: bool,
+    /// Item type of `__next__`, specified by `#[gen_stub(iterator(item = "..."))]`.
+    /// The class derives from `collections.abc.Iterator[item]`. See [ClassDef::add_iterator_methods]
+    pub iterator_item: Option<TypeInfo>,
+    /// Hand-written members registered by [crate::class_verbatim!], emitted after the generated members
+    pub verbatim: Vec<TypingHelperDef>,
+    /// `# type: ignore` comment on the `class` line, specified by `#[gen_stub(type_ignore)]`
+    pub type_ignored: Option<IgnoreTarget>,
+    /// `@typing.dataclass_transform(...)` decorator, specified by `#[gen_stub(dataclass_transform(...))]`
+    pub dataclass_transform: Option<DataclassTransformDef>,
+    /// Source file location for ordering items by definition position
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
 }
 
+/// Version-gated import of `Buffer`, since `collections.abc.Buffer` is only available in Python 3.12+
+pub(crate) const BUFFER_IMPORT: &str = "\
+if sys.version_info >= (3, 12):
+    from collections.abc import Buffer
+else:
+    from typing_extensions import Buffer
+";
+
 impl Import for ClassDef {
     fn import(&self) -> HashSet<ImportRef> {
         let mut import = HashSet::new();
-        if !self.subclass {
+        self.import_into(&mut import);
+        import
+    }
+
+    fn import_into(&self, import: &mut HashSet<ImportRef>) {
+        if self.r#final {
             // for @typing.final
             import.insert("typing".into());
         }
+        if let Some(dataclass_transform) = &self.dataclass_transform {
+            dataclass_transform.import_into(import);
+        }
+        if self
+            .match_args
+            .as_ref()
+            .is_some_and(|args| !args.is_empty())
+        {
+            // for `typing.ClassVar[builtins.tuple[typing.Literal[...], ...]]` of `__match_args__`
+            import.insert("typing".into());
+            import.insert("builtins".into());
+        }
         for base in &self.bases {
-            import.extend(base.import.clone());
+            import.extend(base.import.iter().cloned());
+        }
+        if self.buffer {
+            // for `sys.version_info` in [BUFFER_IMPORT]
+            import.insert("sys".into());
         }
         for attr in &self.attrs {
-            import.extend(attr.import());
+            attr.import_into(import);
         }
         for (getter, setter) in self.getter_setters.values() {
             if let Some(getter) = getter {
-                import.extend(getter.import());
+                getter.import_into(import);
             }
             if let Some(setter) = setter {
-                import.extend(setter.import());
+                setter.import_into(import);
             }
         }
         for method in self.methods.values() {
@@ -56,13 +113,15 @@ impl Import for ClassDef {
                 import.insert("typing".into());
             }
             for method in method {
-                import.extend(method.import());
+                method.import_into(import);
             }
         }
         for class in &self.classes {
-            import.extend(class.import());
+            class.import_into(import);
+        }
+        for verbatim in &self.verbatim {
+            verbatim.import_into(import);
         }
-        import
     }
 }
 
@@ -92,6 +151,17 @@ impl From<&PyComplexEnumInfo> for ClassDef {
             match_args: None,
             attrs: Vec::new(),
             subclass: true, // Complex enums can be subclassed by their variants
+            r#final: false,
+            buffer: false,
+            picklable: false,
+            unhashable: false,
+            iterator_item: None,
+            verbatim: Vec::new(),
+            type_ignored: None,
+            dataclass_transform: None,
+            file: info.file,
+            line: info.line,
+            column: info.column,
         };
 
         enum_info
@@ -99,6 +169,42 @@ impl From<&PyComplexEnumInfo> for ClassDef {
 }
 
 impl ClassDef {
+    /// Python name of the class
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Docstring of the class, empty if not documented
+    pub fn doc(&self) -> &str {
+        self.doc
+    }
+
+    /// Base classes
+    pub fn bases(&self) -> &[TypeInfo] {
+        &self.bases
+    }
+
+    /// Methods by Python name, with multiple entries for `@overload`s
+    pub fn methods(&self) -> impl Iterator<Item = (&str, &[MethodDef])> {
+        self.methods
+            .iter()
+            .map(|(name, methods)| (name.as_str(), methods.as_slice()))
+    }
+
+    /// Attributes given by `#[pyo3(get, set)]` and class attributes
+    pub fn attributes(&self) -> &[MemberDef] {
+        &self.attrs
+    }
+
+    /// Properties by Python name, as pairs of the getter and the setter
+    pub fn properties(
+        &self,
+    ) -> impl Iterator<Item = (&str, Option<&MemberDef>, Option<&MemberDef>)> {
+        self.getter_setters
+            .iter()
+            .map(|(name, (getter, setter))| (name.as_str(), getter.as_ref(), setter.as_ref()))
+    }
+
     fn from_variant(enum_info: &PyComplexEnumInfo, info: &VariantInfo) -> Self {
         let methods = get_variant_methods(enum_info, info);
 
@@ -120,11 +226,47 @@ impl ClassDef {
             methods,
             classes: Vec::new(),
             bases: vec![TypeInfo::unqualified(enum_info.pyclass_name)],
-            match_args: Some(info.fields.iter().map(|f| f.name.to_string()).collect()),
+            match_args: (info.match_args && !info.fields.is_empty())
+                .then(|| info.fields.iter().map(|f| f.name.to_string()).collect()),
             attrs: Vec::new(),
             subclass: false,
+            r#final: false,
+            buffer: false,
+            picklable: false,
+            unhashable: false,
+            iterator_item: None,
+            verbatim: Vec::new(),
+            type_ignored: None,
+            dataclass_transform: None,
+            file: enum_info.file,
+            line: enum_info.line,
+            column: enum_info.column,
         }
     }
+
+    /// `__match_args__` declaration typed by the literal names,
+    /// since type checkers resolve positional patterns only from a tuple of string literals
+    fn match_args_txt(&self) -> Option<String> {
+        let match_args = self.match_args.as_ref().filter(|args| !args.is_empty())?;
+        let types = match_args
+            .iter()
+            .map(|a| format!(r#"typing.Literal["{a}"]"#))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let values = match_args
+            .iter()
+            .map(|a| format!(r#""{a}""#))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let values = if match_args.len() == 1 {
+            format!("{values},")
+        } else {
+            values
+        };
+        Some(format!(
+            "__match_args__: typing.ClassVar[builtins.tuple[{types}]] = ({values})"
+        ))
+    }
 }
 
 impl From<&PyClassInfo> for ClassDef {
@@ -154,7 +296,10 @@ impl From<&PyClassInfo> for ClassDef {
                 r#type: (setter.r#type)(),
                 doc: setter_doc,
                 default: setter.default.map(|f| f()),
+                default_symbol: setter.default_symbol,
                 deprecated: setter.deprecated.clone(),
+                cached: setter.cached,
+                type_ignored: None,
             });
         }
         let mut new = Self {
@@ -168,6 +313,27 @@ impl From<&PyClassInfo> for ClassDef {
             bases: info.bases.iter().map(|f| f()).collect(),
             match_args: None,
             subclass: info.subclass,
+            r#final: false,
+            buffer: info.buffer,
+            picklable: info.picklable,
+            unhashable: info.has_eq && !info.has_hash,
+            iterator_item: info.iterator.as_ref().map(|iterator| TypeInfo {
+                name: iterator.item.to_string(),
+                source_module: None,
+                import: iterator
+                    .imports
+                    .iter()
+                    .map(|&import| import.into())
+                    .collect(),
+                type_refs: Default::default(),
+                any_fallback: None,
+            }),
+            verbatim: Vec::new(),
+            type_ignored: info.type_ignored,
+            dataclass_transform: info.dataclass_transform.map(Into::into),
+            file: info.file,
+            line: info.line,
+            column: info.column,
         };
         if info.has_eq {
             new.add_eq_method();
@@ -181,6 +347,30 @@ impl From<&PyClassInfo> for ClassDef {
         if info.has_str {
             new.add_str_method();
         }
+        if info.buffer {
+            new.add_buffer_methods();
+        }
+        if let Some(slots) = info.slots {
+            new.add_slots(slots);
+        }
+        for item_access in info.item_access {
+            new.add_item_access_method(item_access);
+        }
+        if let (Some(iterator), Some(item)) = (&info.iterator, &new.iterator_item) {
+            let mut base = item.clone();
+            base.name = if iterator.send.is_some() || iterator.r#return.is_some() {
+                format!(
+                    "collections.abc.Generator[{}, {}, {}]",
+                    item.name,
+                    iterator.send.unwrap_or("None"),
+                    iterator.r#return.unwrap_or("None")
+                )
+            } else {
+                format!("collections.abc.Iterator[{}]", item.name)
+            };
+            base.import.insert("collections.abc".into());
+            new.bases.push(base);
+        }
         new
     }
 }
@@ -277,6 +467,389 @@ impl ClassDef {
             .push(method);
     }
 
+    fn add_buffer_methods(&mut self) {
+        self.bases.push(TypeInfo::unqualified("Buffer"));
+
+        let buffer = MethodDef {
+            name: "__buffer__",
+            parameters: Parameters {
+                positional_only: vec![Parameter {
+                    name: "flags",
+                    kind: ParameterKind::PositionalOnly,
+                    type_info: TypeInfo::builtin("int"),
+                    default: ParameterDefault::None,
+                }],
+                ..Parameters::new()
+            },
+            r#return: TypeInfo::builtin("memoryview"),
+            doc: "",
+            r#type: MethodType::Instance,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            is_overload: false,
+        };
+        self.methods
+            .entry("__buffer__".to_string())
+            .or_default()
+            .push(buffer);
+
+        let release_buffer = MethodDef {
+            name: "__release_buffer__",
+            parameters: Parameters {
+                positional_only: vec![Parameter {
+                    name: "buffer",
+                    kind: ParameterKind::PositionalOnly,
+                    type_info: TypeInfo::builtin("memoryview"),
+                    default: ParameterDefault::None,
+                }],
+                ..Parameters::new()
+            },
+            r#return: TypeInfo::none(),
+            doc: "",
+            r#type: MethodType::Instance,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            is_overload: false,
+        };
+        self.methods
+            .entry("__release_buffer__".to_string())
+            .or_default()
+            .push(release_buffer);
+    }
+
+    /// Declare the pickle protocol methods which are not defined explicitly.
+    ///
+    /// This must be called after all `#[pymethods]` are merged so that their signatures take precedence.
+    pub(crate) fn add_pickle_methods(&mut self) {
+        let any_tuple = TypeInfo {
+            name: "builtins.tuple[typing.Any, ...]".to_string(),
+            source_module: None,
+            import: ["builtins".into(), "typing".into()].into_iter().collect(),
+            type_refs: Default::default(),
+            any_fallback: None,
+        };
+        let pickle_methods = [
+            ("__getstate__", None, TypeInfo::any()),
+            ("__setstate__", Some("state"), TypeInfo::none()),
+            ("__reduce__", None, any_tuple),
+        ];
+        for (name, parameter, r#return) in pickle_methods {
+            if self.methods.contains_key(name) {
+                continue;
+            }
+            let method = MethodDef {
+                name,
+                parameters: Parameters {
+                    positional_only: parameter
+                        .into_iter()
+                        .map(|name| Parameter {
+                            name,
+                            kind: ParameterKind::PositionalOnly,
+                            type_info: TypeInfo::any(),
+                            default: ParameterDefault::None,
+                        })
+                        .collect(),
+                    ..Parameters::new()
+                },
+                r#return,
+                doc: "",
+                r#type: MethodType::Instance,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+            };
+            self.methods.insert(name.to_string(), vec![method]);
+        }
+    }
+
+    /// Annotate `__next__` to return the item type of `#[gen_stub(iterator(...))]`,
+    /// declaring it if not defined explicitly. `send` of a generator returns the item type as well.
+    ///
+    /// This must be called after all `#[pymethods]` are merged, since `__next__` is usually defined there
+    /// with a return type like `Option<Py<PyAny>>` which does not tell the item type.
+    pub(crate) fn add_iterator_methods(&mut self) {
+        let Some(item) = &self.iterator_item else {
+            return;
+        };
+        if let Some(methods) = self.methods.get_mut("send") {
+            for method in methods.iter_mut() {
+                method.r#return = item.clone();
+            }
+        }
+        let methods = self.methods.entry("__next__".to_string()).or_default();
+        for method in methods.iter_mut() {
+            method.r#return = item.clone();
+        }
+        if methods.is_empty() {
+            methods.push(MethodDef {
+                name: "__next__",
+                parameters: Parameters::new(),
+                r#return: item.clone(),
+                doc: "",
+                r#type: MethodType::Instance,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+            });
+        }
+    }
+
+    fn type_ignore_comment(&self) -> String {
+        self.type_ignored
+            .as_ref()
+            .map(type_ignore_comment)
+            .unwrap_or_default()
+    }
+
+    /// Declare `__hash__: typing.ClassVar[None]  # type: ignore[assignment]` so that type checkers reject unhashable instances
+    /// used as dict keys or set members.
+    ///
+    /// This must be called after all `#[pymethods]` are merged, since `__hash__` may be defined there.
+    pub(crate) fn add_unhashable_marker(&mut self) {
+        if self.methods.contains_key("__hash__")
+            || self.attrs.iter().any(|attr| attr.name == "__hash__")
+        {
+            return;
+        }
+        self.attrs.push(MemberDef {
+            name: "__hash__",
+            r#type: TypeInfo {
+                name: "typing.ClassVar[None]".to_string(),
+                source_module: None,
+                import: ["typing".into()].into_iter().collect(),
+                type_refs: Default::default(),
+                any_fallback: None,
+            },
+            doc: "",
+            default: None,
+            default_symbol: None,
+            deprecated: None,
+            cached: false,
+            // Overrides `object.__hash__` incompatibly, ignored as typeshed does
+            type_ignored: Some(IgnoreTarget::Specified(&["assignment"])),
+        });
+    }
+
+    /// Declare `__slots__: typing.ClassVar[tuple[str, ...]] = (...)` listing `names`,
+    /// specified by `#[gen_stub(slots)]`
+    pub(crate) fn add_slots(&mut self, names: &[&str]) {
+        let tuple = match names {
+            [name] => format!("({name:?},)"),
+            _ => format!(
+                "({})",
+                names
+                    .iter()
+                    .map(|name| format!("{name:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        self.attrs.push(MemberDef {
+            name: "__slots__",
+            r#type: TypeInfo {
+                name: "typing.ClassVar[builtins.tuple[builtins.str, ...]]".to_string(),
+                source_module: None,
+                import: ["builtins".into(), "typing".into()].into_iter().collect(),
+                type_refs: Default::default(),
+                any_fallback: None,
+            },
+            doc: "",
+            // The literal is the same in both modes of `MemberDefaults`
+            default_symbol: Some(Box::leak(tuple.clone().into_boxed_str())),
+            default: Some(tuple),
+            deprecated: None,
+            cached: false,
+            type_ignored: None,
+        });
+    }
+
+    /// Return `typing.Self` from `__new__` returning the class itself if subclassing is allowed,
+    /// set if `self-in-new` is enabled. Nested classes are handled as well.
+    ///
+    /// `typing.Self` is taken from `typing_extensions` for `python-target` older than 3.11.
+    pub(crate) fn return_self_from_new(&mut self) {
+        for class in &mut self.classes {
+            class.return_self_from_new();
+        }
+        if !self.subclass {
+            return;
+        }
+        let name = self.name;
+        let returns_class = |r#return: &TypeInfo| {
+            r#return.name == name
+                || r#return
+                    .name
+                    .rsplit_once('.')
+                    .is_some_and(|(_, last)| last == name)
+        };
+        for method in self.methods.values_mut().flatten() {
+            if method.r#type == MethodType::New && returns_class(&method.r#return) {
+                method.r#return = TypeInfo {
+                    name: "typing.Self".to_string(),
+                    source_module: None,
+                    import: ["typing".into()].into_iter().collect(),
+                    type_refs: Default::default(),
+                    any_fallback: None,
+                };
+            }
+        }
+    }
+
+    /// Decorate by `@typing.final` unless subclassing is allowed by `#[pyclass(subclass)]`.
+    /// Nested classes, e.g. variants of complex enums, are handled as well.
+    pub(crate) fn mark_final(&mut self) {
+        for class in &mut self.classes {
+            class.mark_final();
+        }
+        self.r#final = !self.subclass;
+    }
+
+    /// Prepend the constructor signature like `Foo(x, y=2)` to the docstring, as read by documentation tools like pdoc.
+    ///
+    /// The signature is taken from `__new__`, or `__init__` if not defined.
+    /// Nothing is added if the docstring already starts with the signature,
+    /// or the constructor is overloaded. Nested classes, e.g. variants of complex enums, are handled as well.
+    pub(crate) fn add_signature_to_doc(&mut self) {
+        for class in &mut self.classes {
+            class.add_signature_to_doc();
+        }
+        let constructor = ["__new__", "__init__"]
+            .iter()
+            .find_map(|name| self.methods.get(*name));
+        let Some([constructor]) = constructor.map(Vec::as_slice) else {
+            return;
+        };
+        let signature = format!("{}{}", self.name, constructor.parameters.text_signature());
+        let doc = self.doc.trim();
+        if doc.starts_with(&format!("{}(", self.name)) {
+            return;
+        }
+        let doc = if doc.is_empty() {
+            signature
+        } else {
+            format!("{signature}\n\n{doc}")
+        };
+        self.doc = Box::leak(doc.into_boxed_str());
+    }
+
+    /// Drop setters of properties without a getter with a warning, including those of nested classes.
+    ///
+    /// A write-only property cannot be declared in stub files, since `@x.setter` requires `@property` defined before.
+    /// It is left out, e.g. for a field with `#[gen_stub(skip_getter)]` and no `#[getter]` in `#[pymethods]`.
+    pub(crate) fn drop_write_only_properties(&mut self) {
+        self.getter_setters.retain(|name, (getter, setter)| {
+            if getter.is_none() && setter.is_some() {
+                log::warn!(
+                    "Property `{name}` of class `{}` has a setter without a getter, which cannot be written in stub files. \
+                     The setter is left out of the stub file.",
+                    self.name
+                );
+                return false;
+            }
+            true
+        });
+        for class in &mut self.classes {
+            class.drop_write_only_properties();
+        }
+    }
+
+    /// Rename methods by `rule` given by the `rename` configuration, including those of nested classes
+    pub(crate) fn rename_methods(&mut self, rule: RenameRule) -> anyhow::Result<()> {
+        let mut methods: IndexMap<String, Vec<MethodDef>> = IndexMap::new();
+        for (name, mut overloads) in std::mem::take(&mut self.methods) {
+            let renamed = rule.apply(&name);
+            if methods.contains_key(&renamed) {
+                anyhow::bail!(
+                    "Method `{name}` of class `{}` is renamed to `{renamed}` by `rename`, which is already defined",
+                    self.name
+                );
+            }
+            if renamed != name {
+                let leaked: &'static str = Box::leak(renamed.clone().into_boxed_str());
+                for method in &mut overloads {
+                    method.name = leaked;
+                }
+            }
+            methods.insert(renamed, overloads);
+        }
+        self.methods = methods;
+        for class in &mut self.classes {
+            class.rename_methods(rule)?;
+        }
+        Ok(())
+    }
+
+    fn add_item_access_method(&mut self, item_access: &ItemAccessInfo) {
+        fn type_info(name: &str, imports: &[&str]) -> TypeInfo {
+            TypeInfo {
+                name: name.to_string(),
+                source_module: None,
+                import: imports.iter().map(|&import| import.into()).collect(),
+                type_refs: Default::default(),
+                any_fallback: None,
+            }
+        }
+        fn parameter(name: &'static str, type_info: TypeInfo) -> Parameter {
+            Parameter {
+                name,
+                kind: ParameterKind::PositionalOnly,
+                type_info,
+                default: ParameterDefault::None,
+            }
+        }
+
+        let (name, positional_only, r#return) = match *item_access {
+            ItemAccessInfo::Get {
+                key,
+                value,
+                imports,
+            } => (
+                "__getitem__",
+                vec![parameter("key", type_info(key, imports))],
+                type_info(value, imports),
+            ),
+            ItemAccessInfo::Set {
+                key,
+                value,
+                imports,
+            } => (
+                "__setitem__",
+                vec![
+                    parameter("key", type_info(key, imports)),
+                    parameter("value", type_info(value, imports)),
+                ],
+                TypeInfo::none(),
+            ),
+            ItemAccessInfo::Del { key, imports } => (
+                "__delitem__",
+                vec![parameter("key", type_info(key, imports))],
+                TypeInfo::none(),
+            ),
+        };
+        let method = MethodDef {
+            name,
+            parameters: Parameters {
+                positional_only,
+                ..Parameters::new()
+            },
+            r#return,
+            doc: "",
+            r#type: MethodType::Instance,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            is_overload: false,
+        };
+        self.methods
+            .entry(name.to_string())
+            .or_default()
+            .push(method);
+    }
+
     /// Resolve all ModuleRef::Default to actual module name.
     /// Called after construction, before formatting.
     pub fn resolve_default_modules(&mut self, default_module_name: &str) {
@@ -330,6 +903,66 @@ impl ClassDef {
             class.resolve_default_modules(default_module_name);
         }
     }
+
+    fn write_verbatim(&self, f: &mut fmt::Formatter, indent: &str) -> fmt::Result {
+        for verbatim in &self.verbatim {
+            for line in verbatim.code.lines() {
+                if line.is_empty() {
+                    writeln!(f)?;
+                } else {
+                    writeln!(f, "{indent}{line}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply `f` to all types appearing in this class, including nested classes
+    pub(crate) fn for_each_type_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        self.for_each_type_with_role_mut(&mut |type_info, _| f(type_info));
+    }
+
+    /// Apply `f` to all types appearing in this class with where they appear, including nested classes
+    pub(crate) fn for_each_type_with_role_mut(
+        &mut self,
+        f: &mut impl FnMut(&mut TypeInfo, TypeRole),
+    ) {
+        for (getter, setter) in self.getter_setters.values_mut() {
+            if let Some(getter) = getter {
+                f(&mut getter.r#type, TypeRole::Output);
+            }
+            if let Some(setter) = setter {
+                f(&mut setter.r#type, TypeRole::Input);
+            }
+        }
+        for method in self.methods.values_mut().flatten() {
+            method.for_each_type_with_role_mut(f);
+        }
+        for base in &mut self.bases {
+            f(base, TypeRole::Output);
+        }
+        for attr in &mut self.attrs {
+            f(&mut attr.r#type, TypeRole::Output);
+        }
+        for class in &mut self.classes {
+            class.for_each_type_with_role_mut(f);
+        }
+    }
+
+    /// Apply `f` to every getter, setter and class attribute, including those of nested classes
+    pub(crate) fn for_each_member_mut(&mut self, f: &mut impl FnMut(&mut MemberDef)) {
+        for (getter, setter) in self.getter_setters.values_mut() {
+            for member in getter.iter_mut().chain(setter.iter_mut()) {
+                f(member);
+            }
+        }
+        for attr in &mut self.attrs {
+            f(attr);
+        }
+        for class in &mut self.classes {
+            class.for_each_member_mut(f);
+        }
+    }
 }
 
 impl fmt::Display for ClassDef {
@@ -341,25 +974,25 @@ impl fmt::Display for ClassDef {
             .reduce(|acc, path| format!("{acc}, {path}"))
             .map(|bases| format!("({bases})"))
             .unwrap_or_default();
-        if !self.subclass {
+        if let Some(dataclass_transform) = &self.dataclass_transform {
+            writeln!(f, "{dataclass_transform}")?;
+        }
+        if self.r#final {
             writeln!(f, "@typing.final")?;
         }
-        writeln!(f, "class {}{}:", self.name, bases)?;
+        writeln!(
+            f,
+            "class {}{}:{}",
+            self.name,
+            bases,
+            self.type_ignore_comment()
+        )?;
         let indent = indent();
         let doc = self.doc.trim();
         docstring::write_docstring(f, doc, indent)?;
 
-        if let Some(match_args) = &self.match_args {
-            if match_args.is_empty() {
-                writeln!(f, "{indent}__match_args__ = ()")?;
-            } else {
-                let match_args_txt = match_args
-                    .iter()
-                    .map(|a| format!(r##""{a}""##))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                writeln!(f, "{indent}__match_args__ = ({match_args_txt},)")?;
-            }
+        if let Some(match_args) = self.match_args_txt() {
+            writeln!(f, "{indent}{match_args}")?;
         }
         for attr in &self.attrs {
             attr.fmt(f)?;
@@ -375,7 +1008,11 @@ impl fmt::Display for ClassDef {
                     }
                 )?;
             }
-            if let Some(setter) = setter {
+            // `functools.cached_property` is assignable without a setter and has no `.setter`
+            if let Some(setter) = setter
+                .as_ref()
+                .filter(|_| !getter.as_ref().is_some_and(|getter| getter.cached))
+            {
                 write!(
                     f,
                     "{}",
@@ -404,7 +1041,12 @@ impl fmt::Display for ClassDef {
                 writeln!(f, "{indent}{line}")?;
             }
         }
-        if self.attrs.is_empty() && self.getter_setters.is_empty() && self.methods.is_empty() {
+        self.write_verbatim(f, indent)?;
+        if self.attrs.is_empty()
+            && self.getter_setters.is_empty()
+            && self.methods.is_empty()
+            && self.verbatim.is_empty()
+        {
             writeln!(f, "{indent}...")?;
         }
         writeln!(f)?;
@@ -427,26 +1069,26 @@ impl ClassDef {
             .map(|bases| format!("({bases})"))
             .unwrap_or_default();
 
-        if !self.subclass {
+        if let Some(dataclass_transform) = &self.dataclass_transform {
+            writeln!(f, "{dataclass_transform}")?;
+        }
+        if self.r#final {
             writeln!(f, "@typing.final")?;
         }
-        writeln!(f, "class {}{}:", self.name, bases)?;
+        writeln!(
+            f,
+            "class {}{}:{}",
+            self.name,
+            bases,
+            self.type_ignore_comment()
+        )?;
 
         let indent = indent();
         let doc = self.doc.trim();
         docstring::write_docstring(f, doc, indent)?;
 
-        if let Some(match_args) = &self.match_args {
-            if match_args.is_empty() {
-                writeln!(f, "{indent}__match_args__ = ()")?;
-            } else {
-                let match_args_txt = match_args
-                    .iter()
-                    .map(|a| format!(r##""{a}""##))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                writeln!(f, "{indent}__match_args__ = ({match_args_txt},)")?;
-            }
+        if let Some(match_args) = self.match_args_txt() {
+            writeln!(f, "{indent}{match_args}")?;
         }
 
         // Format attributes with qualified types
@@ -466,7 +1108,11 @@ impl ClassDef {
                     }
                 )?;
             }
-            if let Some(setter) = setter {
+            // `functools.cached_property` is assignable without a setter and has no `.setter`
+            if let Some(setter) = setter
+                .as_ref()
+                .filter(|_| !getter.as_ref().is_some_and(|getter| getter.cached))
+            {
                 write!(
                     f,
                     "{}",
@@ -515,7 +1161,12 @@ impl ClassDef {
             }
         }
 
-        if self.attrs.is_empty() && self.getter_setters.is_empty() && self.methods.is_empty() {
+        self.write_verbatim(f, indent)?;
+        if self.attrs.is_empty()
+            && self.getter_setters.is_empty()
+            && self.methods.is_empty()
+            && self.verbatim.is_empty()
+        {
             writeln!(f, "{indent}...")?;
         }
         writeln!(f)?;
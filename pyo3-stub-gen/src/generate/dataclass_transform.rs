@@ -0,0 +1,74 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{generate::Import, stub_type::ImportRef, type_info::DataclassTransformInfo};
+
+/// `@typing.dataclass_transform(...)` decorator of a class
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DataclassTransformDef {
+    pub info: DataclassTransformInfo,
+    /// Module the decorator is taken from, `typing` or `typing_extensions` selected by `python-target`
+    pub typing_module: &'static str,
+}
+
+impl From<DataclassTransformInfo> for DataclassTransformDef {
+    fn from(info: DataclassTransformInfo) -> Self {
+        Self {
+            info,
+            typing_module: "typing",
+        }
+    }
+}
+
+impl Import for DataclassTransformDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        HashSet::from([self.typing_module.into()])
+    }
+}
+
+impl fmt::Display for DataclassTransformDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let DataclassTransformInfo {
+            eq_default,
+            order_default,
+            kw_only_default,
+            frozen_default,
+        } = self.info;
+        let args = [
+            ("eq_default", eq_default),
+            ("order_default", order_default),
+            ("kw_only_default", kw_only_default),
+            ("frozen_default", frozen_default),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value.map(|value| format!("{name}={}", if value { "True" } else { "False" }))
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+        write!(f, "@{}.dataclass_transform({args})", self.typing_module)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dataclass_transform() {
+        let def = DataclassTransformDef::from(DataclassTransformInfo::default());
+        assert_eq!(def.to_string(), "@typing.dataclass_transform()");
+        let def = DataclassTransformDef {
+            info: DataclassTransformInfo {
+                eq_default: Some(true),
+                kw_only_default: Some(false),
+                ..Default::default()
+            },
+            typing_module: "typing_extensions",
+        };
+        assert_eq!(
+            def.to_string(),
+            "@typing_extensions.dataclass_transform(eq_default=True, kw_only_default=False)"
+        );
+    }
+}
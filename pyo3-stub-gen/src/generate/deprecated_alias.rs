@@ -0,0 +1,88 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    generate::Import,
+    stub_type::ImportRef,
+    type_info::{DeprecatedAliasInfo, DeprecatedInfo},
+    TypeInfo,
+};
+
+/// Old name of a class written as `OldName = NewClass` with a deprecation comment
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DeprecatedAliasDef {
+    pub name: &'static str,
+    pub target: TypeInfo,
+    pub deprecated: DeprecatedInfo,
+}
+
+impl From<&DeprecatedAliasInfo> for DeprecatedAliasDef {
+    fn from(info: &DeprecatedAliasInfo) -> Self {
+        Self {
+            name: info.name,
+            target: (info.target)(),
+            deprecated: info.deprecated.clone(),
+        }
+    }
+}
+
+impl Import for DeprecatedAliasDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        self.target.import.clone()
+    }
+}
+
+impl fmt::Display for DeprecatedAliasDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_for_module("", f)
+    }
+}
+
+impl DeprecatedAliasDef {
+    /// Format the alias with the target class qualified for `target_module`
+    pub fn fmt_for_module(&self, target_module: &str, f: &mut fmt::Formatter) -> fmt::Result {
+        let target = self.target.qualified_for_module(target_module);
+        match (self.deprecated.since, self.deprecated.note) {
+            (Some(since), Some(note)) => writeln!(f, "# Deprecated since {since}: {note}")?,
+            (Some(since), None) => writeln!(f, "# Deprecated since {since}")?,
+            (None, Some(note)) => writeln!(f, "# Deprecated: {note}")?,
+            (None, None) => writeln!(f, "# Deprecated")?,
+        }
+        writeln!(f, "{} = {target}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct InModule<'a>(&'a DeprecatedAliasDef, &'a str);
+
+    impl fmt::Display for InModule<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.fmt_for_module(self.1, f)
+        }
+    }
+
+    #[test]
+    fn test_deprecated_alias() {
+        let mut def = DeprecatedAliasDef {
+            name: "OldName",
+            target: TypeInfo::locally_defined("NewClass", "pkg.sub".into()),
+            deprecated: DeprecatedInfo {
+                since: None,
+                note: Some("moved in 2.0"),
+            },
+        };
+        assert_eq!(
+            InModule(&def, "pkg.sub").to_string(),
+            "# Deprecated: moved in 2.0\nOldName = NewClass\n"
+        );
+        assert!(def.import().contains(&"pkg.sub".into()));
+
+        def.deprecated.since = Some("2.0");
+        assert!(InModule(&def, "pkg.sub")
+            .to_string()
+            .starts_with("# Deprecated since 2.0: moved in 2.0\n"));
+    }
+}
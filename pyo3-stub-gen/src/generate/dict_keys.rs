@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+
+use crate::{
+    generate::TypedDictDef,
+    stub_type::ImportRef,
+    type_info::{DictKeyInfo, DictKeysInfo},
+    TypeInfo,
+};
+
+/// Known keys of a dict parameter given by `#[gen_stub(dict_keys(...))]`, listed in the generated documentation
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DictKeysDef {
+    /// Python name of the class for a method, `None` for a function
+    pub class: Option<&'static str>,
+    pub function: &'static str,
+    pub parameter: &'static str,
+    /// Keys and their value types
+    pub keys: Vec<(&'static str, TypeInfo)>,
+}
+
+impl DictKeysDef {
+    pub(crate) fn new(class: Option<&'static str>, info: &DictKeysInfo) -> Self {
+        Self {
+            class,
+            function: info.function,
+            parameter: info.parameter,
+            keys: info
+                .keys
+                .iter()
+                .map(|key| (key.name, key_type(key)))
+                .collect(),
+        }
+    }
+
+    /// `TypedDict` named `name` with the keys, all of which are required
+    pub(crate) fn to_typed_dict(&self, name: &'static str) -> TypedDictDef {
+        TypedDictDef {
+            name,
+            doc: "",
+            fields: self.keys.clone(),
+        }
+    }
+}
+
+/// Value type of a key written in Python syntax, importing the modules of dotted names like `datetime.timedelta`
+fn key_type(key: &DictKeyInfo) -> TypeInfo {
+    let import: HashSet<ImportRef> = key
+        .type_repr
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter_map(|name| name.rsplit_once('.'))
+        .filter(|(module, _)| module.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        .map(|(module, _)| module.into())
+        .collect();
+    TypeInfo {
+        name: key.type_repr.to_string(),
+        source_module: None,
+        import,
+        type_refs: Default::default(),
+        any_fallback: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_key_type() {
+        let key = DictKeyInfo {
+            name: "timeout",
+            type_repr: "datetime.timedelta | None",
+        };
+        let type_ = key_type(&key);
+        assert_eq!(type_.name, "datetime.timedelta | None");
+        assert_eq!(type_.import, HashSet::from(["datetime".into()]));
+
+        let key = DictKeyInfo {
+            name: "host",
+            type_repr: "str",
+        };
+        assert!(key_type(&key).import.is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+/// Kind of a Python item an intra-doc link refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum DocLinkKind {
+    /// Class or enum
+    Class,
+    Function,
+}
+
+/// Python item of a Rust `#[pyclass]`, enum or `#[pyfunction]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DocLinkTarget {
+    /// [std::any::type_name] of the Rust item, e.g. `my_crate::bar::Baz`
+    pub rust_path: &'static str,
+    pub module: String,
+    pub name: String,
+    pub kind: DocLinkKind,
+}
+
+impl DocLinkTarget {
+    /// Fully qualified Python name, e.g. `pkg.Baz`
+    pub fn fqn(&self) -> String {
+        format!("{}.{}", self.module, self.name)
+    }
+}
+
+/// Python items to rewrite Rust intra-doc links like `[Foo]` or ``[`crate::bar::Baz`]`` in docstrings.
+///
+/// Links to unknown or ambiguous paths are left as they are.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DocLinks {
+    pub targets: Vec<DocLinkTarget>,
+}
+
+impl DocLinks {
+    /// Python item of a link path, e.g. `Foo`, `crate::bar::Baz`, `struct@Foo` or `bar()`
+    pub fn resolve(&self, path: &str) -> Option<&DocLinkTarget> {
+        let path = path
+            .split_once('@')
+            .map_or(path, |(_, path)| path)
+            .trim_end_matches("()");
+        let mut path = path;
+        while let Some(rest) = ["crate::", "self::", "super::"]
+            .iter()
+            .find_map(|prefix| path.strip_prefix(prefix))
+        {
+            path = rest;
+        }
+        if path.is_empty() {
+            return None;
+        }
+        let mut found = self.targets.iter().filter(|target| {
+            target.rust_path == path
+                || target
+                    .rust_path
+                    .strip_suffix(path)
+                    .is_some_and(|prefix| prefix.ends_with("::"))
+        });
+        let first = found.next()?;
+        // Several Rust items of the same path, e.g. in different crates, cannot be told apart
+        found
+            .all(|other| other.module == first.module && other.name == first.name)
+            .then_some(first)
+    }
+
+    /// Rewrite the links of `doc` resolved to Python items by `render`,
+    /// which receives whether the link text is written as code like ``[`Foo`]``.
+    ///
+    /// Code blocks, inline code and subscripts like `list[Foo]` are not regarded as links.
+    pub fn rewrite(
+        &self,
+        doc: &str,
+        mut render: impl FnMut(&DocLinkTarget, bool) -> String,
+    ) -> String {
+        if self.targets.is_empty() || !doc.contains('[') {
+            return doc.to_string();
+        }
+        let mut out = String::with_capacity(doc.len());
+        let mut in_code_block = false;
+        for (i, line) in doc.split('\n').enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            if in_code_block || line.trim_start().starts_with("```") {
+                out.push_str(line);
+                continue;
+            }
+            self.rewrite_line(line, &mut out, &mut render);
+        }
+        out
+    }
+
+    fn rewrite_line(
+        &self,
+        line: &str,
+        out: &mut String,
+        render: &mut impl FnMut(&DocLinkTarget, bool) -> String,
+    ) {
+        let mut rest = line;
+        let mut prev: Option<char> = None;
+        while let Some(c) = rest.chars().next() {
+            if c == '`' {
+                // Copy inline code as is
+                let end = rest[1..].find('`').map_or(rest.len(), |end| end + 2);
+                out.push_str(&rest[..end]);
+                prev = Some('`');
+                rest = &rest[end..];
+                continue;
+            }
+            let after_word = prev.is_some_and(|p| p.is_alphanumeric() || p == '_' || p == ']');
+            if c == '[' && !after_word {
+                if let Some((len, rendered)) = self.link_at(rest, render) {
+                    out.push_str(&rendered);
+                    prev = Some(']');
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+            out.push(c);
+            prev = Some(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    /// Length and rewritten text of a link at the start of `text`
+    fn link_at(
+        &self,
+        text: &str,
+        render: &mut impl FnMut(&DocLinkTarget, bool) -> String,
+    ) -> Option<(usize, String)> {
+        let end = text.find(']')?;
+        let inner = &text[1..end];
+        // Inline links `[text](url)`, reference links `[text][ref]` and definitions `[ref]: url`
+        if matches!(text[end + 1..].chars().next(), Some('(' | '[' | ':')) {
+            return None;
+        }
+        let (path, code) = match inner.strip_prefix('`').and_then(|s| s.strip_suffix('`')) {
+            Some(path) => (path, true),
+            None => (inner, false),
+        };
+        let is_path = !path.is_empty()
+            && path
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '@' | '(' | ')'));
+        if !is_path {
+            return None;
+        }
+        let target = self.resolve(path)?;
+        Some((end + 1, render(target, code)))
+    }
+
+    /// Rewrite the links in docstrings of a rendered stub file to plain Python names
+    pub(crate) fn rewrite_stub(&self, stub: String) -> String {
+        if self.targets.is_empty() {
+            return stub;
+        }
+        let mut out = String::with_capacity(stub.len());
+        let mut in_docstring = false;
+        for line in stub.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if in_docstring && trimmed != "\"\"\"" {
+                out.push_str(&self.rewrite(line, |target, code| {
+                    if code {
+                        format!("`{}`", target.name)
+                    } else {
+                        target.name.clone()
+                    }
+                }));
+                continue;
+            }
+            match trimmed {
+                "r\"\"\"" => in_docstring = true,
+                "\"\"\"" => in_docstring = false,
+                _ => {}
+            }
+            out.push_str(line);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn links() -> DocLinks {
+        DocLinks {
+            targets: vec![
+                DocLinkTarget {
+                    rust_path: "my_crate::shapes::Circle",
+                    module: "pkg".to_string(),
+                    name: "Circle".to_string(),
+                    kind: DocLinkKind::Class,
+                },
+                DocLinkTarget {
+                    rust_path: "my_crate::area",
+                    module: "pkg.sub".to_string(),
+                    name: "area".to_string(),
+                    kind: DocLinkKind::Function,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let links = links();
+        let circle = Some("Circle");
+        assert_eq!(links.resolve("Circle").map(|t| t.name.as_str()), circle);
+        assert_eq!(
+            links
+                .resolve("crate::shapes::Circle")
+                .map(|t| t.name.as_str()),
+            circle
+        );
+        assert_eq!(
+            links.resolve("struct@Circle").map(|t| t.name.as_str()),
+            circle
+        );
+        assert_eq!(
+            links.resolve("area()").map(|t| t.fqn()),
+            Some("pkg.sub.area".to_string())
+        );
+        assert!(links.resolve("ircle").is_none());
+        assert!(links.resolve("Square").is_none());
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let links = links();
+        let doc = "See [Circle], [`crate::area`] and [Square].\n\
+                   Returns list[Circle], `[Circle]` or [a link](Circle).\n\
+                   ```\n[Circle]\n```";
+        let rewritten = links.rewrite(doc, |target, code| {
+            if code {
+                format!("`{}`", target.fqn())
+            } else {
+                target.fqn()
+            }
+        });
+        assert_eq!(
+            rewritten,
+            "See pkg.Circle, `pkg.sub.area` and [Square].\n\
+             Returns list[Circle], `[Circle]` or [a link](Circle).\n\
+             ```\n[Circle]\n```"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_stub() {
+        let stub = "class Circle:\n    r\"\"\"\n    Use [area].\n    \"\"\"\n    x: list[Circle]\n";
+        assert_eq!(
+            links().rewrite_stub(stub.to_string()),
+            "class Circle:\n    r\"\"\"\n    Use area.\n    \"\"\"\n    x: list[Circle]\n"
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use crate::generate::docstring::normalize_docstring;
-use crate::{generate::*, type_info::*};
+use crate::{generate::*, type_info::*, TypeInfo};
 use std::fmt;
 
 /// Definition of a Python enum.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct EnumDef {
     pub name: &'static str,
     pub module: Option<&'static str>,
@@ -13,6 +14,10 @@ pub struct EnumDef {
     pub attrs: Vec<MemberDef>,
     pub getters: Vec<MemberDef>,
     pub setters: Vec<MemberDef>,
+    /// Source file location for ordering items by definition position
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
 }
 
 impl From<&PyEnumInfo> for EnumDef {
@@ -48,6 +53,9 @@ impl From<&PyEnumInfo> for EnumDef {
             attrs: Vec::new(),
             getters: Vec::new(),
             setters: Vec::new(),
+            file: info.file,
+            line: info.line,
+            column: info.column,
         }
     }
 }
@@ -104,7 +112,7 @@ impl fmt::Display for EnumDef {
                     }
                 )?;
             }
-            for setter in &self.setters {
+            for setter in self.setters_shown() {
                 write!(
                     f,
                     "{}",
@@ -124,6 +132,50 @@ impl fmt::Display for EnumDef {
 }
 
 impl EnumDef {
+    /// Setters to be shown, i.e. except ones of `functools.cached_property`
+    /// which is assignable without a setter and has no `.setter`
+    fn setters_shown(&self) -> impl Iterator<Item = &MemberDef> {
+        self.setters.iter().filter(|setter| {
+            !self
+                .getters
+                .iter()
+                .any(|getter| getter.cached && getter.name == setter.name)
+        })
+    }
+
+    /// Apply `f` to all types appearing in this enum
+    pub(crate) fn for_each_type_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        self.for_each_type_with_role_mut(&mut |type_info, _| f(type_info));
+    }
+
+    /// Apply `f` to all types appearing in this enum, with where they appear
+    pub(crate) fn for_each_type_with_role_mut(
+        &mut self,
+        f: &mut impl FnMut(&mut TypeInfo, TypeRole),
+    ) {
+        for method in &mut self.methods {
+            method.for_each_type_with_role_mut(f);
+        }
+        for member in self.attrs.iter_mut().chain(self.getters.iter_mut()) {
+            f(&mut member.r#type, TypeRole::Output);
+        }
+        for member in &mut self.setters {
+            f(&mut member.r#type, TypeRole::Input);
+        }
+    }
+
+    /// Apply `f` to every getter, setter and class attribute
+    pub(crate) fn for_each_member_mut(&mut self, f: &mut impl FnMut(&mut MemberDef)) {
+        for member in self
+            .attrs
+            .iter_mut()
+            .chain(self.getters.iter_mut())
+            .chain(self.setters.iter_mut())
+        {
+            f(member);
+        }
+    }
+
     /// Format enum with module-qualified type names
     ///
     /// This method uses the target module context to qualify type identifiers
@@ -158,7 +210,7 @@ impl EnumDef {
                     }
                 )?;
             }
-            for setter in &self.setters {
+            for setter in self.setters_shown() {
                 write!(
                     f,
                     "{}",
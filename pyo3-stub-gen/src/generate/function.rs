@@ -1,20 +1,34 @@
 use crate::generate::docstring::normalize_docstring;
 use crate::stub_type::ImportRef;
-use crate::{generate::*, rule_name::RuleName, type_info::*, TypeInfo};
-use itertools::Itertools;
-use std::fmt;
+use crate::{generate::*, type_info::*, TypeInfo};
+use std::{any::TypeId, borrow::Cow, fmt};
 
 /// Definition of a Python function.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FunctionDef {
     pub name: &'static str,
     pub parameters: Parameters,
     pub r#return: TypeInfo,
+    /// Rust error type of `Result<T, E>`, documented as raised if it is an exception class
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub error: Option<TypeId>,
+    /// Exceptions listed by `#[gen_stub(raises(...))]`, written to the "Raises" section of the docstring
+    pub raises: Vec<TypeInfo>,
     pub doc: &'static str,
     pub is_async: bool,
     pub deprecated: Option<DeprecatedInfo>,
     pub type_ignored: Option<IgnoreTarget>,
     pub is_overload: bool,
+    /// `sys.platform` the function is available on, negated by a `!` prefix like `"!win32"`.
+    /// The function is written in an `if sys.platform == ...:` block.
+    pub platform: Option<&'static str>,
+    /// Python versions the function is available on, `">=3.12"` or `"<3.12"`.
+    /// The function is written in an `if sys.version_info >= ...:` block, or the `else` block
+    /// following the complementary version like `"<3.12"`.
+    pub py_version: Option<&'static str>,
+    /// Releases the GIL while running, noted in the generated documentation
+    pub releases_gil: bool,
     /// Source file location for deterministic ordering
     pub file: &'static str,
     pub line: u32,
@@ -25,13 +39,21 @@ pub struct FunctionDef {
 
 impl Import for FunctionDef {
     fn import(&self) -> HashSet<ImportRef> {
-        let mut import = self.r#return.import.clone();
-        import.extend(self.parameters.import());
+        let mut import = HashSet::new();
+        self.import_into(&mut import);
+        import
+    }
+
+    fn import_into(&self, import: &mut HashSet<ImportRef>) {
+        import.extend(self.r#return.import.iter().cloned());
+        self.parameters.import_into(import);
         // Add typing_extensions import if deprecated
         if self.deprecated.is_some() {
             import.insert("typing_extensions".into());
         }
-        import
+        if self.platform.is_some() || self.py_version.is_some() {
+            import.insert("sys".into());
+        }
     }
 }
 
@@ -47,11 +69,19 @@ impl From<&PyFunctionInfo> for FunctionDef {
             name: info.name,
             parameters: Parameters::from_infos(info.parameters),
             r#return: (info.r#return)(),
+            error: info
+                .error
+                .map(|error| crate::exception::exception_of(error())),
+            // Resolved by `StubInfoBuilder` since exception classes may be defined in other modules
+            raises: Vec::new(),
             doc,
             is_async: info.is_async,
             deprecated: info.deprecated.clone(),
             type_ignored: info.type_ignored,
             is_overload: info.is_overload,
+            platform: info.platform,
+            py_version: info.py_version,
+            releases_gil: info.releases_gil,
             file: info.file,
             line: info.line,
             column: info.column,
@@ -60,6 +90,96 @@ impl From<&PyFunctionInfo> for FunctionDef {
     }
 }
 
+/// Condition of the `if` block a function for `platform` is written in, e.g. `sys.platform != "win32"`
+pub(crate) fn platform_condition(platform: &str) -> String {
+    match platform.strip_prefix('!') {
+        Some(platform) => format!("sys.platform != \"{platform}\""),
+        None => format!("sys.platform == \"{platform}\""),
+    }
+}
+
+/// Condition of the `if` block a function for `py_version` is written in, e.g. `sys.version_info >= (3, 12)`
+pub(crate) fn py_version_condition(py_version: &str) -> String {
+    let (op, version) = split_py_version(py_version);
+    format!("sys.version_info {op} ({})", version.replace('.', ", "))
+}
+
+/// Whether `a` and `b` cover all Python versions exclusively like `">=3.12"` and `"<3.12"`,
+/// so that the latter is written in the `else` block of the former
+pub(crate) fn is_complementary_py_version(a: &str, b: &str) -> bool {
+    let (op_a, version_a) = split_py_version(a);
+    let (op_b, version_b) = split_py_version(b);
+    op_a == ">=" && op_b == "<" && version_a == version_b
+}
+
+/// Whether `py_version` is satisfied only on Python `major.minor` or later
+pub(crate) fn py_version_at_least(py_version: &str, major: u32, minor: u32) -> bool {
+    let (op, version) = split_py_version(py_version);
+    let mut parts = version
+        .split('.')
+        .map(|part| part.parse::<u32>().unwrap_or(0));
+    let version = (parts.next().unwrap_or(0), parts.next().unwrap_or(0));
+    op == ">=" && version >= (major, minor)
+}
+
+/// Whether `py_version` is `>=X.Y` or `<X.Y`
+pub(crate) fn is_valid_py_version(py_version: &str) -> bool {
+    let Some(version) = py_version
+        .strip_prefix(">=")
+        .or_else(|| py_version.strip_prefix('<'))
+    else {
+        return false;
+    };
+    let parts: Vec<&str> = version.trim().split('.').collect();
+    parts.len() == 2
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Split `">=3.12"` into `(">=", "3.12")`
+fn split_py_version(py_version: &str) -> (&'static str, &str) {
+    for op in [">=", "<"] {
+        if let Some(version) = py_version.strip_prefix(op) {
+            return (op, version.trim());
+        }
+    }
+    (">=", py_version.trim())
+}
+
+impl FunctionDef {
+    /// Python name of the function
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Docstring of the function, empty if not documented
+    pub fn doc(&self) -> &str {
+        self.doc
+    }
+
+    /// Parameters in the order of the signature
+    pub fn parameters(&self) -> impl Iterator<Item = &Parameter> {
+        self.parameters.iter_entries()
+    }
+
+    /// Return type
+    pub fn return_type(&self) -> &TypeInfo {
+        &self.r#return
+    }
+
+    /// Condition of the `if` block the function is written in, combining `platform` and `py_version`
+    pub(crate) fn condition(&self) -> Option<String> {
+        let conditions: Vec<String> = self
+            .platform
+            .map(platform_condition)
+            .into_iter()
+            .chain(self.py_version.map(py_version_condition))
+            .collect();
+        (!conditions.is_empty()).then(|| conditions.join(" and "))
+    }
+}
+
 impl fmt::Display for FunctionDef {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Add deprecated decorator if present
@@ -74,36 +194,16 @@ impl fmt::Display for FunctionDef {
             self.name, self.parameters, self.r#return
         )?;
 
-        // Calculate type: ignore comment once
-        let type_ignore_comment = if let Some(target) = &self.type_ignored {
-            match target {
-                IgnoreTarget::All => Some("  # type: ignore".to_string()),
-                IgnoreTarget::Specified(rules) => {
-                    let rules_str = rules
-                        .iter()
-                        .map(|r| {
-                            let result = r.parse::<RuleName>().unwrap();
-                            if let RuleName::Custom(custom) = &result {
-                                log::warn!("Unknown custom rule name '{custom}' used in type ignore. Ensure this is intended.");
-                            }
-                            result
-                        })
-                        .join(",");
-                    Some(format!("  # type: ignore[{rules_str}]"))
-                }
-            }
-        } else {
-            None
-        };
+        let type_ignore_comment = self.type_ignored.as_ref().map(type_ignore_comment);
 
-        let doc = self.doc;
+        let doc = self.doc_with_raises();
         if !doc.is_empty() {
             // Add type: ignore comment for functions with docstrings
             if let Some(comment) = &type_ignore_comment {
                 write!(f, "{comment}")?;
             }
             writeln!(f)?;
-            docstring::write_docstring(f, self.doc, indent())?;
+            docstring::write_docstring(f, &doc, indent())?;
         } else {
             write!(f, " ...")?;
             // Add type: ignore comment for functions without docstrings
@@ -141,9 +241,46 @@ impl FunctionDef {
         }
         self.r#return.resolve_default_module(default_module_name);
     }
+
+    /// Apply `f` to all parameter and return types
+    pub(crate) fn for_each_type_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        self.for_each_type_with_role_mut(&mut |type_info, _| f(type_info));
+    }
+
+    /// Apply `f` to all parameter and return types, with where they appear
+    pub(crate) fn for_each_type_with_role_mut(
+        &mut self,
+        f: &mut impl FnMut(&mut TypeInfo, TypeRole),
+    ) {
+        for param in self.parameters.iter_entries_mut() {
+            f(&mut param.type_info, TypeRole::Input);
+        }
+        f(&mut self.r#return, TypeRole::Output);
+    }
 }
 
 impl FunctionDef {
+    /// Docstring followed by a "Raises" section listing [FunctionDef::raises],
+    /// unless the docstring already has one
+    fn doc_with_raises(&self) -> Cow<'static, str> {
+        let has_raises_section = self.doc.lines().any(|line| line.trim_end() == "Raises:");
+        if self.raises.is_empty() || has_raises_section {
+            return Cow::Borrowed(self.doc);
+        }
+        let mut doc = self.doc.to_string();
+        if !doc.is_empty() {
+            doc.push_str("\n\n");
+        }
+        doc.push_str("Raises:");
+        for exception in &self.raises {
+            // Bare class name as in the `raise` statement, e.g. `ValueError` for `builtins.ValueError`
+            let name = exception.name.rsplit('.').next().unwrap_or(&exception.name);
+            doc.push_str("\n    ");
+            doc.push_str(name);
+        }
+        Cow::Owned(doc)
+    }
+
     /// Format function with module-qualified type names
     ///
     /// This method uses the target module context to qualify type identifiers
@@ -164,36 +301,16 @@ impl FunctionDef {
             self.name, params_str, return_type
         )?;
 
-        // Calculate type: ignore comment once
-        let type_ignore_comment = if let Some(target) = &self.type_ignored {
-            match target {
-                IgnoreTarget::All => Some("  # type: ignore".to_string()),
-                IgnoreTarget::Specified(rules) => {
-                    let rules_str = rules
-                        .iter()
-                        .map(|r| {
-                            let result = r.parse::<RuleName>().unwrap();
-                            if let RuleName::Custom(custom) = &result {
-                                log::warn!("Unknown custom rule name '{custom}' used in type ignore. Ensure this is intended.");
-                            }
-                            result
-                        })
-                        .join(",");
-                    Some(format!("  # type: ignore[{rules_str}]"))
-                }
-            }
-        } else {
-            None
-        };
+        let type_ignore_comment = self.type_ignored.as_ref().map(type_ignore_comment);
 
-        let doc = self.doc;
+        let doc = self.doc_with_raises();
         if !doc.is_empty() {
             // Add type: ignore comment for functions with docstrings
             if let Some(comment) = &type_ignore_comment {
                 write!(f, "{comment}")?;
             }
             writeln!(f)?;
-            docstring::write_docstring(f, self.doc, indent())?;
+            docstring::write_docstring(f, &doc, indent())?;
         } else {
             write!(f, " ...")?;
             // Add type: ignore comment for functions without docstrings
@@ -1,5 +1,5 @@
 use crate::generate::docstring::normalize_docstring;
-use crate::{generate::*, type_info::*, TypeInfo};
+use crate::{generate::*, pyproject::MemberDefaults, type_info::*, TypeInfo};
 use std::{
     borrow::Cow,
     collections::HashSet,
@@ -8,22 +8,37 @@ use std::{
 
 /// Definition of a class member.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MemberDef {
     pub name: &'static str,
     pub r#type: TypeInfo,
     pub doc: &'static str,
     pub default: Option<String>,
+    /// Python expression to show instead of the evaluated `default`, see [MemberDefaults]
+    pub default_symbol: Option<&'static str>,
     pub deprecated: Option<DeprecatedInfo>,
+    /// Shown as `@functools.cached_property` instead of `@property` if this is a getter
+    pub cached: bool,
+    /// Shown as `# type: ignore` after the declaration, e.g. for `__hash__` conflicting with `object.__hash__`
+    pub type_ignored: Option<IgnoreTarget>,
 }
 
 impl Import for MemberDef {
     fn import(&self) -> HashSet<ImportRef> {
-        let mut import = self.r#type.import.clone();
+        let mut import = HashSet::new();
+        self.import_into(&mut import);
+        import
+    }
+
+    fn import_into(&self, import: &mut HashSet<ImportRef>) {
+        import.extend(self.r#type.import.iter().cloned());
         // Add typing_extensions import if deprecated
         if self.deprecated.is_some() {
             import.insert("typing_extensions".into());
         }
-        import
+        if self.cached {
+            import.insert("functools".into());
+        }
     }
 }
 
@@ -40,7 +55,10 @@ impl From<&MemberInfo> for MemberDef {
             r#type: (info.r#type)(),
             doc,
             default: info.default.map(|f| f()),
+            default_symbol: info.default_symbol,
             deprecated: info.deprecated.clone(),
+            cached: info.cached,
+            type_ignored: None,
         }
     }
 }
@@ -61,13 +79,37 @@ impl fmt::Display for MemberDef {
         if let Some(default) = &self.default {
             write!(f, " = {default}")?;
         }
-        writeln!(f)?;
+        writeln!(f, "{}", self.type_ignore_comment())?;
         docstring::write_docstring(f, self.doc, indent)?;
         Ok(())
     }
 }
 
 impl MemberDef {
+    fn type_ignore_comment(&self) -> String {
+        self.type_ignored
+            .as_ref()
+            .map(type_ignore_comment)
+            .unwrap_or_default()
+    }
+
+    /// Choose between the evaluated default and `default_symbol` to be shown in stub files
+    ///
+    /// - [MemberDefaults::Evaluated]: the symbol is used only if the default cannot be evaluated into a Python literal
+    /// - [MemberDefaults::Symbolic]: the symbol is always used, and `...` if no symbol is given
+    pub fn resolve_default(&mut self, mode: MemberDefaults) {
+        let Some(default) = &self.default else {
+            return;
+        };
+        let use_symbol = match mode {
+            MemberDefaults::Evaluated => default == "...",
+            MemberDefaults::Symbolic => true,
+        };
+        if use_symbol {
+            self.default = Some(self.default_symbol.unwrap_or("...").to_string());
+        }
+    }
+
     /// Format member with module-qualified type names
     ///
     /// This method uses the target module context to qualify type identifiers
@@ -92,7 +134,7 @@ impl MemberDef {
         if let Some(default) = &self.default {
             write!(f, " = {default}")?;
         }
-        writeln!(f)?;
+        writeln!(f, "{}", self.type_ignore_comment())?;
         docstring::write_docstring(f, self.doc, indent)?;
         Ok(())
     }
@@ -116,9 +158,14 @@ impl fmt::Display for GetterDisplay<'_> {
         if let Some(deprecated) = &self.member.deprecated {
             writeln!(f, "{indent}{deprecated}")?;
         }
+        let decorator = if self.member.cached {
+            "functools.cached_property"
+        } else {
+            "property"
+        };
         write!(
             f,
-            "{indent}@property\n{indent}def {}(self) -> {}:",
+            "{indent}@{decorator}\n{indent}def {}(self) -> {}:",
             self.member.name, qualified_type
         )?;
         let doc = if let Some(default) = &self.member.default {
@@ -178,3 +225,58 @@ impl fmt::Display for SetterDisplay<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn member(default: &str, default_symbol: Option<&'static str>) -> MemberDef {
+        MemberDef {
+            name: "x",
+            r#type: TypeInfo::builtin("int"),
+            doc: "",
+            default: Some(default.to_string()),
+            default_symbol,
+            deprecated: None,
+            cached: false,
+            type_ignored: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_default_evaluated() {
+        let mut evaluated = member("30", Some("A().x"));
+        evaluated.resolve_default(MemberDefaults::Evaluated);
+        assert_eq!(evaluated.default.as_deref(), Some("30"));
+
+        let mut unrepresentable = member("...", Some("A().x"));
+        unrepresentable.resolve_default(MemberDefaults::Evaluated);
+        assert_eq!(unrepresentable.default.as_deref(), Some("A().x"));
+    }
+
+    #[test]
+    fn test_resolve_default_symbolic() {
+        let mut with_symbol = member("30", Some("A().x"));
+        with_symbol.resolve_default(MemberDefaults::Symbolic);
+        assert_eq!(with_symbol.default.as_deref(), Some("A().x"));
+
+        let mut without_symbol = member("30", None);
+        without_symbol.resolve_default(MemberDefaults::Symbolic);
+        assert_eq!(without_symbol.default.as_deref(), Some("..."));
+    }
+
+    #[test]
+    fn test_cached_property() {
+        let mut getter = member("...", None);
+        assert!(!getter.import().contains(&ImportRef::from("functools")));
+        getter.cached = true;
+        assert!(getter.import().contains(&ImportRef::from("functools")));
+        let display = GetterDisplay {
+            member: &getter,
+            target_module: "pkg",
+        }
+        .to_string();
+        assert!(display.contains("@functools.cached_property\n"));
+        assert!(display.contains("def x(self) -> builtins.int:"));
+    }
+}
@@ -0,0 +1,413 @@
+//! Merge generated stubs with hand-edits of the existing stub files for `merge` in `[tool.pyo3-stub-gen]`
+
+use anyhow::{bail, Context, Result};
+use rustpython_parser::{
+    ast::{self, Ranged},
+    Parse,
+};
+use std::collections::BTreeMap;
+
+/// Line starting a region of an existing stub file kept by [merge_stub]
+const KEEP_START: &str = "# <keep>";
+/// Line ending a region of an existing stub file kept by [merge_stub]
+const KEEP_END: &str = "# </keep>";
+
+/// Top-level statement of a stub file, spanning whole lines including its decorators
+struct Statement {
+    /// Name defined by the statement, e.g. of a class, a function or a variable
+    name: Option<String>,
+    start: usize,
+    end: usize,
+    is_import: bool,
+    is_overload: bool,
+    /// Statements in the body of a class
+    members: Vec<Statement>,
+}
+
+/// Lines between [KEEP_START] and [KEEP_END] including themselves
+struct KeepBlock<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Where a kept block is placed in the generated stub
+enum Anchor {
+    /// After the imports at the top of the file
+    Imports,
+    /// After the statement defining the name
+    After(String),
+    /// At the end of the body of the class
+    Inside(String),
+}
+
+/// Merge the `generated` stub with the `existing` one written to the same file.
+///
+/// - Blocks between `# <keep>` and `# </keep>` lines are kept after the same top-level statement as in the existing file,
+///   or at the end of the same class if they are in its body. Blocks whose statement is no longer generated are appended.
+/// - Functions decorated by `@overload` in the existing file replace the generated function of the same name
+///   at the top level or in the same class, unless it is already overloaded in the generated stub.
+pub(crate) fn merge_stub(existing: &str, generated: &str) -> Result<String> {
+    let blocks = keep_blocks(existing)?;
+    let existing_stmts: Vec<Statement> = statements(existing)
+        .context("Failed to parse the existing stub")?
+        .into_iter()
+        // Statements in kept blocks are not generated, so they cannot be anchors
+        .filter(|stmt| !in_blocks(&blocks, stmt))
+        .collect();
+    let mut overloads: BTreeMap<String, Vec<&Statement>> = BTreeMap::new();
+    for (name, stmt) in qualified(&existing_stmts) {
+        if stmt.is_overload && !in_blocks(&blocks, stmt) {
+            overloads.entry(name).or_default().push(stmt);
+        }
+    }
+    if blocks.is_empty() && overloads.is_empty() {
+        return Ok(generated.to_string());
+    }
+
+    let generated_stmts = statements(generated).context("Failed to parse the generated stub")?;
+    let find = |name: &str| {
+        generated_stmts
+            .iter()
+            .rev()
+            .find(|stmt| stmt.name.as_deref() == Some(name))
+    };
+
+    // Replace or insert `(start, end, text)` of the generated stub
+    let mut edits: Vec<(usize, usize, String)> = Vec::new();
+    let generated_defs = qualified(&generated_stmts);
+    for (name, stmts) in overloads {
+        let defs: Vec<_> = generated_defs
+            .iter()
+            .filter(|(qualname, _)| *qualname == name)
+            .map(|(_, stmt)| *stmt)
+            .collect();
+        if defs.is_empty() || defs.iter().any(|stmt| stmt.is_overload) {
+            continue;
+        }
+        log::info!(
+            "Replace the generated `{name}` with {} overload(s) of the existing stub",
+            stmts.len()
+        );
+        let text: String = stmts
+            .iter()
+            .map(|stmt| &existing[stmt.start..stmt.end])
+            .collect();
+        for (i, def) in defs.iter().enumerate() {
+            let text = if i == 0 { text.clone() } else { String::new() };
+            edits.push((def.start, def.end, text));
+        }
+    }
+    for block in &blocks {
+        let offset = match anchor(block, &existing_stmts) {
+            Anchor::Imports => generated_stmts
+                .iter()
+                .rev()
+                .find(|stmt| stmt.is_import)
+                .map_or(0, |stmt| stmt.end),
+            Anchor::After(name) | Anchor::Inside(name) => match find(&name) {
+                Some(stmt) => stmt.end,
+                None => {
+                    log::warn!("`{name}` is no longer generated, append the block kept after it to the end");
+                    generated.len()
+                }
+            },
+        };
+        let mut text = block.text.to_string();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        edits.push((offset, offset, text));
+    }
+    edits.sort_by_key(|(start, end, _)| (*start, *end));
+
+    let mut merged = String::with_capacity(generated.len());
+    let mut cursor = 0;
+    for (start, end, text) in edits {
+        merged.push_str(&generated[cursor..start]);
+        merged.push_str(&text);
+        cursor = end;
+    }
+    merged.push_str(&generated[cursor..]);
+    Ok(merged)
+}
+
+/// Statements and the members of classes recursively, with names qualified by the classes, e.g. `A.f`
+fn qualified(stmts: &[Statement]) -> Vec<(String, &Statement)> {
+    let mut found = Vec::new();
+    for stmt in stmts {
+        let Some(name) = &stmt.name else { continue };
+        found.push((name.clone(), stmt));
+        found.extend(
+            qualified(&stmt.members)
+                .into_iter()
+                .map(|(member, stmt)| (format!("{name}.{member}"), stmt)),
+        );
+    }
+    found
+}
+
+fn in_blocks(blocks: &[KeepBlock], stmt: &Statement) -> bool {
+    blocks
+        .iter()
+        .any(|block| (block.start..block.end).contains(&stmt.start))
+}
+
+fn anchor(block: &KeepBlock, stmts: &[Statement]) -> Anchor {
+    if let Some(name) = stmts
+        .iter()
+        .find(|stmt| (stmt.start..stmt.end).contains(&block.start))
+        .and_then(|stmt| stmt.name.clone())
+    {
+        return Anchor::Inside(name);
+    }
+    stmts
+        .iter()
+        .rev()
+        .filter(|stmt| stmt.end <= block.start)
+        .find_map(|stmt| stmt.name.clone())
+        .map_or(Anchor::Imports, Anchor::After)
+}
+
+fn keep_blocks(source: &str) -> Result<Vec<KeepBlock<'_>>> {
+    let mut blocks = Vec::new();
+    let mut open = None;
+    let mut offset = 0;
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        match line.trim() {
+            KEEP_START => {
+                if open.is_some() {
+                    bail!(
+                        "`{KEEP_START}` at line {} is inside another kept block",
+                        index + 1
+                    );
+                }
+                open = Some((index, offset));
+            }
+            KEEP_END => {
+                let Some((_, start)) = open.take() else {
+                    bail!(
+                        "`{KEEP_END}` at line {} has no `{KEEP_START}` before it",
+                        index + 1
+                    );
+                };
+                let end = offset + line.len();
+                blocks.push(KeepBlock {
+                    text: &source[start..end],
+                    start,
+                    end,
+                });
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    if let Some((index, _)) = open {
+        bail!(
+            "`{KEEP_START}` at line {} is not closed by `{KEEP_END}`",
+            index + 1
+        );
+    }
+    Ok(blocks)
+}
+
+fn statements(source: &str) -> Result<Vec<Statement>> {
+    let suite = ast::Suite::parse(source, "<stub>").map_err(|err| anyhow::anyhow!("{err}"))?;
+    Ok(suite.iter().map(|stmt| statement(source, stmt)).collect())
+}
+
+fn statement(source: &str, stmt: &ast::Stmt) -> Statement {
+    let (name, decorators) = match stmt {
+        ast::Stmt::FunctionDef(def) => (Some(def.name.to_string()), def.decorator_list.as_slice()),
+        ast::Stmt::AsyncFunctionDef(def) => {
+            (Some(def.name.to_string()), def.decorator_list.as_slice())
+        }
+        ast::Stmt::ClassDef(def) => (Some(def.name.to_string()), def.decorator_list.as_slice()),
+        ast::Stmt::AnnAssign(assign) => (target_name(&assign.target), [].as_slice()),
+        ast::Stmt::Assign(assign) => (assign.targets.first().and_then(target_name), [].as_slice()),
+        ast::Stmt::TypeAlias(alias) => (target_name(&alias.name), [].as_slice()),
+        _ => (None, [].as_slice()),
+    };
+    let start = decorators
+        .iter()
+        .map(|decorator| decorator.start())
+        .fold(stmt.start(), Ord::min);
+    let members = match stmt {
+        ast::Stmt::ClassDef(def) => def
+            .body
+            .iter()
+            .map(|stmt| statement(source, stmt))
+            .collect(),
+        _ => Vec::new(),
+    };
+    Statement {
+        name,
+        start: line_start(source, start.into()),
+        end: line_end(source, stmt.end().into()),
+        is_import: matches!(stmt, ast::Stmt::Import(_) | ast::Stmt::ImportFrom(_)),
+        is_overload: decorators.iter().any(is_overload),
+        members,
+    }
+}
+
+fn target_name(target: &ast::Expr) -> Option<String> {
+    match target {
+        ast::Expr::Name(name) => Some(name.id.to_string()),
+        _ => None,
+    }
+}
+
+/// `@overload` or `@typing.overload`
+fn is_overload(decorator: &ast::Expr) -> bool {
+    match decorator {
+        ast::Expr::Name(name) => name.id.as_str() == "overload",
+        ast::Expr::Attribute(attr) => attr.attr.as_str() == "overload",
+        _ => false,
+    }
+}
+
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Offset after the newline ending the line of `offset`
+fn line_end(source: &str, offset: usize) -> usize {
+    source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i + 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_keep_blocks() -> Result<()> {
+        let existing = r#"
+import builtins
+# <keep>
+import numpy
+# </keep>
+
+def foo(x: builtins.int) -> builtins.int: ...
+# <keep>
+def extra() -> None: ...
+# </keep>
+
+class A:
+    x: builtins.int
+    # <keep>
+    def hand(self) -> None: ...
+    # </keep>
+
+def gone() -> None: ...
+# <keep>
+ORPHAN: builtins.int
+# </keep>
+"#;
+        let generated = r#"
+import builtins
+import typing
+
+class A:
+    x: builtins.str
+
+def foo(x: builtins.int) -> builtins.str: ...
+"#;
+        let merged = merge_stub(existing, generated)?;
+        assert_eq!(
+            merged,
+            r#"
+import builtins
+import typing
+# <keep>
+import numpy
+# </keep>
+
+class A:
+    x: builtins.str
+    # <keep>
+    def hand(self) -> None: ...
+    # </keep>
+
+def foo(x: builtins.int) -> builtins.str: ...
+# <keep>
+def extra() -> None: ...
+# </keep>
+# <keep>
+ORPHAN: builtins.int
+# </keep>
+"#
+        );
+        // Merging again does not change it
+        assert_eq!(merge_stub(&merged, generated)?, merged);
+        Ok(())
+    }
+
+    #[test]
+    fn test_overloads() -> Result<()> {
+        let existing = r#"import typing
+
+@typing.overload
+def f(x: int) -> int: ...
+@typing.overload
+def f(x: str) -> str: ...
+
+def g() -> None: ...
+"#;
+        let generated = r#"import typing
+
+def f(x: typing.Any) -> typing.Any: ...
+
+def g() -> None: ...
+"#;
+        assert_eq!(merge_stub(existing, generated)?, existing);
+
+        // Overloads generated from Rust take precedence
+        let generated = r#"import typing
+
+@typing.overload
+def f(x: int) -> int: ...
+@typing.overload
+def f(x: float) -> float: ...
+
+def g() -> None: ...
+"#;
+        assert_eq!(merge_stub(existing, generated)?, generated);
+        Ok(())
+    }
+
+    #[test]
+    fn test_method_overloads() -> Result<()> {
+        let existing = r#"import typing
+
+class A:
+    @typing.overload
+    def f(self, x: int) -> int: ...
+    @typing.overload
+    def f(self, x: str) -> str: ...
+    def g(self) -> None: ...
+
+def f() -> None: ...
+"#;
+        let generated = r#"import typing
+
+class A:
+    def f(self, x: typing.Any) -> typing.Any: ...
+    def g(self) -> None: ...
+
+def f() -> None: ...
+"#;
+        // Only the method is replaced, not the top-level function of the same name
+        assert_eq!(merge_stub(existing, generated)?, existing);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unclosed_keep_block() {
+        let err = merge_stub("# <keep>\nimport numpy\n", "").unwrap_err();
+        assert!(err.to_string().contains("line 1 is not closed"));
+        let err = merge_stub("import numpy\n# </keep>\n", "").unwrap_err();
+        assert!(err.to_string().contains("line 2 has no"));
+    }
+}
@@ -1,13 +1,13 @@
 use crate::generate::docstring::normalize_docstring;
 use crate::stub_type::ImportRef;
-use crate::{generate::*, rule_name::RuleName, type_info::*, TypeInfo};
-use itertools::Itertools;
+use crate::{generate::*, type_info::*, TypeInfo};
 use std::{collections::HashSet, fmt};
 
 pub use crate::type_info::MethodType;
 
 /// Definition of a class method.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MethodDef {
     pub name: &'static str,
     pub parameters: Parameters,
@@ -23,13 +23,18 @@ pub struct MethodDef {
 
 impl Import for MethodDef {
     fn import(&self) -> HashSet<ImportRef> {
-        let mut import = self.r#return.import.clone();
-        import.extend(self.parameters.import());
+        let mut import = HashSet::new();
+        self.import_into(&mut import);
+        import
+    }
+
+    fn import_into(&self, import: &mut HashSet<ImportRef>) {
+        import.extend(self.r#return.import.iter().cloned());
+        self.parameters.import_into(import);
         // Add typing_extensions import if deprecated
         if self.deprecated.is_some() {
             import.insert("typing_extensions".into());
         }
-        import
     }
 }
 
@@ -89,27 +94,7 @@ impl fmt::Display for MethodDef {
         }
         write!(f, " -> {}:", self.r#return)?;
 
-        // Calculate type: ignore comment once
-        let type_ignore_comment = if let Some(target) = &self.type_ignored {
-            match target {
-                IgnoreTarget::All => Some("  # type: ignore".to_string()),
-                IgnoreTarget::Specified(rules) => {
-                    let rules_str = rules
-                        .iter()
-                        .map(|r| {
-                            let result = r.parse::<RuleName>().unwrap();
-                            if let RuleName::Custom(custom) = &result {
-                                log::warn!("Unknown custom rule name '{custom}' used in type ignore. Ensure this is intended.");
-                            }
-                            result
-                        })
-                        .join(",");
-                    Some(format!("  # type: ignore[{rules_str}]"))
-                }
-            }
-        } else {
-            None
-        };
+        let type_ignore_comment = self.type_ignored.as_ref().map(type_ignore_comment);
 
         let doc = self.doc;
         if !doc.is_empty() {
@@ -133,6 +118,22 @@ impl fmt::Display for MethodDef {
 }
 
 impl MethodDef {
+    /// Apply `f` to all parameter and return types
+    pub(crate) fn for_each_type_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        self.for_each_type_with_role_mut(&mut |type_info, _| f(type_info));
+    }
+
+    /// Apply `f` to all parameter and return types, with where they appear
+    pub(crate) fn for_each_type_with_role_mut(
+        &mut self,
+        f: &mut impl FnMut(&mut TypeInfo, TypeRole),
+    ) {
+        for param in self.parameters.iter_entries_mut() {
+            f(&mut param.type_info, TypeRole::Input);
+        }
+        f(&mut self.r#return, TypeRole::Output);
+    }
+
     /// Format method with module-qualified type names
     ///
     /// This method uses the target module context to qualify type identifiers
@@ -185,27 +186,7 @@ impl MethodDef {
         }
         write!(f, " -> {}:", return_type)?;
 
-        // Calculate type: ignore comment once
-        let type_ignore_comment = if let Some(target) = &self.type_ignored {
-            match target {
-                IgnoreTarget::All => Some("  # type: ignore".to_string()),
-                IgnoreTarget::Specified(rules) => {
-                    let rules_str = rules
-                        .iter()
-                        .map(|r| {
-                            let result = r.parse::<RuleName>().unwrap();
-                            if let RuleName::Custom(custom) = &result {
-                                log::warn!("Unknown custom rule name '{custom}' used in type ignore. Ensure this is intended.");
-                            }
-                            result
-                        })
-                        .join(",");
-                    Some(format!("  # type: ignore[{rules_str}]"))
-                }
-            }
-        } else {
-            None
-        };
+        let type_ignore_comment = self.type_ignored.as_ref().map(type_ignore_comment);
 
         let doc = self.doc;
         if !doc.is_empty() {
@@ -1,14 +1,26 @@
-use crate::generate::*;
-use crate::stub_type::ImportRef;
+use crate::generate::{
+    python_target::{rewrite_type_expr, typing_module},
+    qualifier::{references_builtins, unqualify_builtins},
+    *,
+};
+use crate::pyproject::{
+    AllOrder, AllStyle, ItemOrder, PlatformSpecific, RenameRule, StubGenConfig,
+};
+use crate::stub_type::{ImportRef, TypeInfo};
 use itertools::Itertools;
 use std::{
     any::TypeId,
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     fmt,
 };
 
+/// Header comment of stub files if `free-threading` is enabled
+const FREE_THREADING_COMMENT: &str =
+    "# free-threading: supported, importing this module does not enable the GIL";
+
 /// Re-export from another module for __all__
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ModuleReExport {
     pub source_module: String,
     /// Items to re-export. Empty means wildcard (will be resolved).
@@ -16,17 +28,89 @@ pub struct ModuleReExport {
     /// Additional items to include with wildcard (e.g., `__version__`).
     /// These are merged into `items` after wildcard resolution.
     pub additional_items: Vec<String>,
+    /// Names the items in `items` are re-exported as, given by [crate::reexport_as!]
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl ModuleReExport {
+    /// Name `item` is exported as from the target module
+    pub fn exported_name<'a>(&'a self, item: &'a str) -> &'a str {
+        self.aliases.get(item).map_or(item, String::as_str)
+    }
+
+    /// Names exported from the target module
+    pub fn exported_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.items
+            .iter()
+            .map(|item| self.exported_name(item).to_string())
+    }
+
+    /// `from source import a, b as c` line importing the items, or `None` for unresolved wildcard
+    fn import_line(&self) -> Option<String> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let mut sorted_items: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| match self.aliases.get(item) {
+                Some(alias) => format!("{item} as {alias}"),
+                None => item.clone(),
+            })
+            .collect();
+        sorted_items.sort();
+        Some(format!(
+            "from {} import {}",
+            self.source_module,
+            sorted_items.join(", ")
+        ))
+    }
+}
+
+/// Key of a class in [Module::class]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClassId {
+    /// Rust type of the class, e.g. a `#[pyclass]` struct or complex enum
+    Rust(TypeId),
+    /// Class registered by [PyClassInfoBuilder](crate::type_info::PyClassInfoBuilder) without a Rust type,
+    /// identified by its module and name
+    Runtime { module: String, name: String },
+}
+
+impl From<TypeId> for ClassId {
+    fn from(id: TypeId) -> Self {
+        Self::Rust(id)
+    }
 }
 
 /// Type info for a Python (sub-)module. This corresponds to a single `*.pyi` file.
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Module {
     pub doc: String,
-    pub class: BTreeMap<TypeId, ClassDef>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "super::serialize_values"))]
+    pub class: BTreeMap<ClassId, ClassDef>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "super::serialize_values"))]
     pub enum_: BTreeMap<TypeId, EnumDef>,
     pub function: BTreeMap<&'static str, Vec<FunctionDef>>,
     pub variables: BTreeMap<&'static str, VariableDef>,
     pub type_aliases: BTreeMap<&'static str, TypeAliasDef>,
+    /// Old names of moved classes declared by [crate::deprecated_alias!]
+    pub deprecated_aliases: BTreeMap<&'static str, DeprecatedAliasDef>,
+    /// Type parameters declared by [crate::type_var!] and [crate::param_spec!], not included in `__all__`
+    pub type_params: BTreeMap<&'static str, TypeParamDef>,
+    /// `TypedDict`s declared by [crate::typed_dict!]
+    pub typed_dicts: BTreeMap<&'static str, TypedDictDef>,
+    /// `NamedTuple`s returned by functions with `#[gen_stub(named_tuple(...))]`
+    pub named_tuples: BTreeMap<&'static str, NamedTupleDef>,
+    /// Known keys of dict parameters given by `#[gen_stub(dict_keys(...))]`, not written to the stub file
+    pub dict_keys: Vec<DictKeysDef>,
+    /// Typing-only definitions written in Python syntax, not included in `__all__`
+    pub typing_helpers: Vec<TypingHelperDef>,
+    /// Trailing content written in Python syntax, appended after all generated items
+    pub epilogues: Vec<TypingHelperDef>,
+    /// Return type of module-level `__getattr__` declared by [crate::module_getattr!]
+    pub getattr: Option<TypeInfo>,
     pub name: String,
     pub default_module_name: String,
     /// Direct submodules of this module.
@@ -37,13 +121,46 @@ pub struct Module {
     pub verbatim_all_entries: BTreeSet<String>,
     /// Explicitly excluded entries from __all__
     pub excluded_all_entries: BTreeSet<String>,
+    /// Modules imported under `if typing.TYPE_CHECKING:`, i.e. those importing this module back directly or indirectly
+    /// and those of `type-checking-only-imports`
+    pub type_checking_modules: BTreeSet<String>,
+}
+
+/// Item of a module whose position in the stub file depends on [ItemOrder]
+enum ModuleItem<'a> {
+    Class(&'a ClassDef),
+    Enum(&'a EnumDef),
+    /// Functions sharing a name, i.e. overloads
+    Functions(&'a Vec<FunctionDef>),
+}
+
+impl ModuleItem<'_> {
+    fn location(&self) -> (&'static str, u32, u32) {
+        match self {
+            ModuleItem::Class(class) => (class.file, class.line, class.column),
+            ModuleItem::Enum(enum_) => (enum_.file, enum_.line, enum_.column),
+            ModuleItem::Functions(functions) => functions
+                .iter()
+                .map(|func| (func.file, func.line, func.column))
+                .min()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Write a `# defined in <file>:<line>` comment pointing to the Rust definition of an item
+fn write_source_comment(f: &mut fmt::Formatter, file: &str, line: u32) -> fmt::Result {
+    if file.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "# defined in {file}:{line}")
 }
 
 impl Module {
     /// Check if this module has no content to generate.
     ///
     /// Returns true if the module has no classes, enums, functions, variables,
-    /// type aliases, submodules, re-exports, docstrings, or verbatim entries.
+    /// type aliases, deprecated aliases, type parameters, `TypedDict`s, `NamedTuple`s, typing helpers, epilogues, submodules, re-exports, docstrings, or verbatim entries.
     /// Modules that are empty should be skipped during generation.
     pub fn is_empty(&self) -> bool {
         self.doc.is_empty()
@@ -52,6 +169,13 @@ impl Module {
             && self.function.is_empty()
             && self.variables.is_empty()
             && self.type_aliases.is_empty()
+            && self.deprecated_aliases.is_empty()
+            && self.type_params.is_empty()
+            && self.typed_dicts.is_empty()
+            && self.named_tuples.is_empty()
+            && self.typing_helpers.is_empty()
+            && self.epilogues.is_empty()
+            && self.getattr.is_none()
             && self.submodules.is_empty()
             && self.module_re_exports.is_empty()
             && self.verbatim_all_entries.is_empty()
@@ -60,7 +184,7 @@ impl Module {
     /// Check if this module can have `__init__.py` generated.
     ///
     /// Returns true if the module has no PyO3-generated items (classes, enums,
-    /// functions, variables, type aliases). Such modules can only contain
+    /// functions, variables, type aliases, deprecated aliases, type parameters, `TypedDict`s, `NamedTuple`s, typing helpers, epilogues). Such modules can only contain
     /// re-exports and docstrings, which can be represented in `__init__.py`.
     pub fn is_init_py_compatible(&self) -> bool {
         self.class.is_empty()
@@ -68,6 +192,69 @@ impl Module {
             && self.function.is_empty()
             && self.variables.is_empty()
             && self.type_aliases.is_empty()
+            && self.deprecated_aliases.is_empty()
+            && self.type_params.is_empty()
+            && self.typed_dicts.is_empty()
+            && self.named_tuples.is_empty()
+            && self.typing_helpers.is_empty()
+            && self.epilogues.is_empty()
+            && self.getattr.is_none()
+    }
+
+    /// Name of the module, e.g. `pkg._core`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Classes defined in this module, including complex enums, sorted by name
+    pub fn classes(&self) -> impl Iterator<Item = &ClassDef> {
+        self.class.values().sorted_by_key(|class| class.name)
+    }
+
+    /// Class of the Python name `name`
+    pub fn class_by_name(&self, name: &str) -> Option<&ClassDef> {
+        self.class.values().find(|class| class.name == name)
+    }
+
+    /// Enums defined in this module, sorted by name
+    pub fn enums(&self) -> impl Iterator<Item = &EnumDef> {
+        self.enum_.values().sorted_by_key(|enum_| enum_.name)
+    }
+
+    /// Functions defined in this module sorted by name, with all of their overloads
+    pub fn functions(&self) -> impl Iterator<Item = (&str, &[FunctionDef])> {
+        self.function
+            .iter()
+            .map(|(name, overloads)| (*name, overloads.as_slice()))
+    }
+
+    /// Module-level variables sorted by name
+    pub fn variables(&self) -> impl Iterator<Item = &VariableDef> {
+        self.variables.values()
+    }
+
+    /// Type aliases sorted by name
+    pub fn type_aliases(&self) -> impl Iterator<Item = &TypeAliasDef> {
+        self.type_aliases.values()
+    }
+
+    /// Known keys of the parameter `parameter` of a function, or of a method of the class `class`
+    pub fn dict_keys(
+        &self,
+        class: Option<&str>,
+        function: &str,
+        parameter: &str,
+    ) -> Option<&DictKeysDef> {
+        self.dict_keys.iter().find(|dict_keys| {
+            dict_keys.class == class
+                && dict_keys.function == function
+                && dict_keys.parameter == parameter
+        })
+    }
+
+    /// Names of direct submodules
+    pub fn submodules(&self) -> impl Iterator<Item = &str> {
+        self.submodules.iter().map(String::as_str)
     }
 
     /// Get the names of all declared items in this module.
@@ -94,6 +281,27 @@ impl Module {
         for alias_name in self.type_aliases.keys() {
             names.push(format!("type_alias {}", alias_name));
         }
+        for alias_name in self.deprecated_aliases.keys() {
+            names.push(format!("deprecated_alias {}", alias_name));
+        }
+        for param_name in self.type_params.keys() {
+            names.push(format!("type_param {}", param_name));
+        }
+        for typed_dict_name in self.typed_dicts.keys() {
+            names.push(format!("typed_dict {}", typed_dict_name));
+        }
+        for named_tuple_name in self.named_tuples.keys() {
+            names.push(format!("named_tuple {}", named_tuple_name));
+        }
+        if !self.typing_helpers.is_empty() {
+            names.push("typing_helper".to_string());
+        }
+        if !self.epilogues.is_empty() {
+            names.push("module_epilogue".to_string());
+        }
+        if self.getattr.is_some() {
+            names.push("module_getattr".to_string());
+        }
         for re_export in &self.module_re_exports {
             names.push(format!("re-export from {}", re_export.source_module));
         }
@@ -102,15 +310,135 @@ impl Module {
         names
     }
 
-    /// Format module with configuration for type alias syntax, returning a String
-    pub fn format_with_config(&self, use_type_statement: bool) -> String {
+    /// Apply `f` to all types appearing in this module
+    pub(crate) fn for_each_type_mut(&mut self, f: &mut impl FnMut(&mut TypeInfo)) {
+        self.for_each_type_with_role_mut(&mut |type_info, _| f(type_info));
+    }
+
+    /// Apply `f` to all types appearing in this module, with where they appear
+    pub(crate) fn for_each_type_with_role_mut(
+        &mut self,
+        f: &mut impl FnMut(&mut TypeInfo, TypeRole),
+    ) {
+        for class in self.class.values_mut() {
+            class.for_each_type_with_role_mut(f);
+        }
+        for enum_ in self.enum_.values_mut() {
+            enum_.for_each_type_with_role_mut(f);
+        }
+        for function in self.function.values_mut().flatten() {
+            function.for_each_type_with_role_mut(f);
+        }
+        for var in self.variables.values_mut() {
+            f(&mut var.type_, TypeRole::Output);
+        }
+        for alias in self.type_aliases.values_mut() {
+            f(&mut alias.type_, TypeRole::Output);
+        }
+        for alias in self.deprecated_aliases.values_mut() {
+            f(&mut alias.target, TypeRole::Output);
+        }
+        for typed_dict in self.typed_dicts.values_mut() {
+            for (_, type_) in &mut typed_dict.fields {
+                f(type_, TypeRole::Input);
+            }
+        }
+        for named_tuple in self.named_tuples.values_mut() {
+            for (_, type_) in &mut named_tuple.fields {
+                f(type_, TypeRole::Output);
+            }
+        }
+        if let Some(getattr) = &mut self.getattr {
+            f(getattr, TypeRole::Output);
+        }
+    }
+
+    /// Apply `f` to every getter, setter and class attribute of classes and enums in this module
+    pub(crate) fn for_each_member_mut(&mut self, f: &mut impl FnMut(&mut MemberDef)) {
+        for class in self.class.values_mut() {
+            class.for_each_member_mut(f);
+        }
+        for enum_ in self.enum_.values_mut() {
+            enum_.for_each_member_mut(f);
+        }
+    }
+
+    /// Names bound in the stub file of this module, including members of classes and enums,
+    /// which shadow builtins of the same name
+    pub(crate) fn defined_names(&self) -> HashSet<String> {
+        fn add_class(class: &ClassDef, names: &mut HashSet<String>) {
+            names.insert(class.name.to_string());
+            names.extend(class.getter_setters.keys().cloned());
+            names.extend(class.methods.keys().cloned());
+            names.extend(class.attrs.iter().map(|attr| attr.name.to_string()));
+            for nested in &class.classes {
+                add_class(nested, names);
+            }
+        }
+        let mut names = HashSet::new();
+        for class in self.class.values() {
+            add_class(class, &mut names);
+        }
+        for enum_ in self.enum_.values() {
+            names.insert(enum_.name.to_string());
+            names.extend(enum_.variants.iter().map(|(name, _)| name.to_string()));
+            let members = enum_
+                .attrs
+                .iter()
+                .chain(&enum_.getters)
+                .chain(&enum_.setters);
+            names.extend(members.map(|member| member.name.to_string()));
+            names.extend(enum_.methods.iter().map(|method| method.name.to_string()));
+        }
+        let items = self
+            .function
+            .keys()
+            .chain(self.variables.keys())
+            .chain(self.type_aliases.keys())
+            .chain(self.deprecated_aliases.keys())
+            .chain(self.type_params.keys())
+            .chain(self.typed_dicts.keys())
+            .chain(self.named_tuples.keys());
+        names.extend(items.map(|name| name.to_string()));
+        names.extend(self.submodules.iter().cloned());
+        for re_export in &self.module_re_exports {
+            names.extend(re_export.exported_names());
+        }
+        names
+    }
+
+    /// Whether `name` can be referred as a type in this module, i.e. a class, enum, type alias, `TypedDict`, `NamedTuple`,
+    /// type parameter, variable, re-exported name, name defined by a typing helper or epilogue, or verbatim `__all__` entry
+    pub(crate) fn defines_type(&self, name: &str) -> bool {
+        self.class.values().any(|class| class.name == name)
+            || self.enum_.values().any(|enum_| enum_.name == name)
+            || self.type_aliases.contains_key(name)
+            || self.deprecated_aliases.contains_key(name)
+            || self.typed_dicts.contains_key(name)
+            || self.named_tuples.contains_key(name)
+            || self.type_params.contains_key(name)
+            || self.variables.contains_key(name)
+            || self
+                .module_re_exports
+                .iter()
+                .any(|re_export| re_export.exported_names().any(|exported| exported == name))
+            || self
+                .typing_helpers
+                .iter()
+                .chain(&self.epilogues)
+                .any(|helper| helper.defined_names().any(|defined| defined == name))
+            || self.verbatim_all_entries.contains(name)
+    }
+
+    /// Format module with the given configuration, returning a String
+    pub fn format_with_config(&self, config: &StubGenConfig) -> String {
         use std::fmt::Write;
-        let mut output = String::new();
+        let mut output = String::with_capacity(self.estimated_stub_len());
 
         // Use a custom formatter struct
         struct ModuleFormatter<'a> {
             module: &'a Module,
-            use_type_statement: bool,
+            config: &'a StubGenConfig,
         }
 
         impl<'a> fmt::Display for ModuleFormatter<'a> {
@@ -118,17 +446,39 @@ impl Module {
                 // Write header and docstring
                 writeln!(f, "# This file is automatically generated by pyo3_stub_gen")?;
                 writeln!(f, "# ruff: noqa: E501, F401, F403, F405")?;
+                if self.config.free_threading {
+                    writeln!(f, "{FREE_THREADING_COMMENT}")?;
+                }
+                if self.config.ignore_errors.is_enabled_for(&self.module.name) {
+                    writeln!(f, "# mypy: ignore-errors")?;
+                }
                 if !self.module.doc.is_empty() {
                     docstring::write_docstring(f, &self.module.doc, "")?;
                 }
                 writeln!(f)?;
+                if self.config.future_annotations {
+                    writeln!(f, "from __future__ import annotations")?;
+                    writeln!(f)?;
+                }
 
-                let mut imports = self.module.import();
+                let split_classes = self.config.split_classes;
+                let mut imports = if split_classes {
+                    self.module.import_except_classes()
+                } else {
+                    self.module.import()
+                };
 
                 // Conditionally add TypeAlias import
-                if !self.use_type_statement && !self.module.type_aliases.is_empty() {
+                let use_type_statement = self.config.use_type_statement;
+                if self
+                    .module
+                    .type_aliases
+                    .values()
+                    .any(|alias| alias.uses_type_alias_annotation(use_type_statement))
+                {
+                    let module = typing_module("TypeAlias", self.config.python_target);
                     imports.insert(ImportRef::Type(crate::stub_type::TypeRef {
-                        module: crate::stub_type::ModuleRef::Named("typing".to_string()),
+                        module: crate::stub_type::ModuleRef::Named(module.to_string()),
                         name: "TypeAlias".to_string(),
                     }));
                 }
@@ -141,92 +491,95 @@ impl Module {
                 if any_overloaded {
                     imports.insert("typing".into());
                 }
-
-                // Generate imports (same logic as Display impl)
-                let mut type_ref_grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
-                for import_ref in imports.into_iter().sorted() {
-                    match import_ref {
-                        ImportRef::Module(module_ref) => {
-                            let name = module_ref.get().unwrap_or(&self.module.default_module_name);
-                            if name != self.module.name && !name.is_empty() {
-                                let is_internal_module = if let Some(root) =
-                                    self.module.default_module_name.split('.').next()
-                                {
-                                    name.starts_with(root)
-                                } else {
-                                    false
-                                };
-
-                                if is_internal_module && name.contains('.') {
-                                    let last_dot_pos = name.rfind('.').unwrap();
-                                    let parent_module = &name[..last_dot_pos];
-                                    let child_module = &name[last_dot_pos + 1..];
-
-                                    if !self.module.submodules.contains(child_module) {
-                                        writeln!(
-                                            f,
-                                            "from {} import {}",
-                                            parent_module, child_module
-                                        )?;
-                                    }
-                                } else {
-                                    writeln!(f, "import {name}")?;
-                                }
-                            }
-                        }
-                        ImportRef::Type(type_ref) => {
-                            let module_name = type_ref
-                                .module
-                                .get()
-                                .unwrap_or(&self.module.default_module_name);
-                            if module_name != self.module.name {
-                                type_ref_grouped
-                                    .entry(module_name.to_string())
-                                    .or_default()
-                                    .push(type_ref.name);
-                            }
+                let mut dir_return_type = "builtins.list[builtins.str]".to_string();
+                let mut getattr_name_type = "builtins.str".to_string();
+                if let Some(target) = self.config.python_target {
+                    dir_return_type = rewrite_type_expr(&dir_return_type, target);
+                    if self.config.dunder_dir && dir_return_type.starts_with("typing.") {
+                        imports.insert("typing".into());
+                    }
+                }
+                if !self.config.qualifies_builtins() {
+                    let shadowed = self.module.defined_names();
+                    for type_ in [&mut dir_return_type, &mut getattr_name_type] {
+                        if let Some(unqualified) = unqualify_builtins(type_, &shadowed) {
+                            *type_ = unqualified;
                         }
                     }
                 }
-                for (module_name, type_names) in type_ref_grouped {
-                    let mut sorted_type_names = type_names.clone();
-                    sorted_type_names.sort();
-                    writeln!(
-                        f,
-                        "from {} import {}",
-                        module_name,
-                        sorted_type_names.join(", ")
-                    )?;
+                if (self.config.dunder_dir && references_builtins(&dir_return_type))
+                    || (self.module.getattr.is_some() && references_builtins(&getattr_name_type))
+                {
+                    imports.insert("builtins".into());
+                }
+
+                self.module
+                    .write_imports(imports, &self.module.submodules, f)?;
+                if !split_classes {
+                    self.module.write_version_gated_imports(f)?;
                 }
 
                 // Add imports for module re-exports (always explicit, not wildcard)
                 let mut sorted_re_exports = self.module.module_re_exports.clone();
                 sorted_re_exports.sort_by(|a, b| a.source_module.cmp(&b.source_module));
-                for re_export in &sorted_re_exports {
-                    if re_export.items.is_empty() {
-                        continue;
-                    }
-                    let mut sorted_items = re_export.items.clone();
-                    sorted_items.sort();
-                    writeln!(
-                        f,
-                        "from {} import {}",
-                        re_export.source_module,
-                        sorted_items.join(", ")
-                    )?;
+                for line in sorted_re_exports
+                    .iter()
+                    .filter_map(ModuleReExport::import_line)
+                {
+                    writeln!(f, "{line}")?;
                 }
                 for submod in &self.module.submodules {
                     writeln!(f, "from . import {submod}")?;
                 }
+                if split_classes {
+                    // Redundant aliases mark the names as re-exported, even for private classes
+                    for name in self.module.split_class_names() {
+                        writeln!(f, "from .{} import {name} as {name}", class_stub_name(name))?;
+                    }
+                }
 
                 // Generate __all__ list
-                self.module.write_all_list(f)?;
+                let all_items = self.module.collect_all_items(self.config);
+                write_all_list(f, &all_items, self.config.all_style)?;
+                for (condition, items) in self.module.collect_conditional_all_items(self.config) {
+                    writeln!(f, "if {condition}:")?;
+                    let entries = items.iter().map(|item| format!("\"{item}\""));
+                    match self.config.all_style {
+                        AllStyle::List => writeln!(f, "    __all__ += [{}]", entries.join(", "))?,
+                        AllStyle::Tuple => writeln!(f, "    __all__ += ({},)", entries.join(", "))?,
+                    }
+                }
+                if self.config.dunder_dir {
+                    writeln!(f)?;
+                    writeln!(f, "def __dir__() -> {dir_return_type}: ...")?;
+                }
+                if let Some(getattr) = &self.module.getattr {
+                    writeln!(f)?;
+                    writeln!(
+                        f,
+                        "def __getattr__(name: {getattr_name_type}) -> {}: ...",
+                        getattr.name
+                    )?;
+                }
 
                 writeln!(f)?;
 
+                // Generate type parameters first, which may be referenced by typing helpers
+                if !self.module.type_params.is_empty() {
+                    for param in self.module.type_params.values() {
+                        write!(f, "{param}")?;
+                    }
+                    writeln!(f)?;
+                }
+
+                // Generate typing-only helper definitions
+                for helper in &self.module.typing_helpers {
+                    writeln!(f, "{helper}")?;
+                }
+
                 // Generate type aliases with configuration
                 for alias in self.module.type_aliases.values() {
-                    alias.fmt_with_config(&self.module.name, f, self.use_type_statement)?;
+                    alias.fmt_with_config(&self.module.name, f, self.config.use_type_statement)?;
                     writeln!(f)?;
                 }
 
@@ -236,32 +589,48 @@ impl Module {
                     writeln!(f)?;
                 }
 
-                // Generate classes
-                for class in self.module.class.values().sorted_by_key(|class| class.name) {
-                    class.fmt_for_module(&self.module.name, f)?;
+                for typed_dict in self.module.typed_dicts.values() {
+                    typed_dict.fmt_for_module(&self.module.name, f)?;
                 }
-
-                // Generate enums
-                for enum_ in self.module.enum_.values().sorted_by_key(|enum_| enum_.name) {
-                    enum_.fmt_for_module(&self.module.name, f)?;
+                for named_tuple in self.module.named_tuples.values() {
+                    named_tuple.fmt_for_module(&self.module.name, f)?;
                 }
 
-                // Generate functions
-                for functions in self.module.function.values() {
-                    let has_overload = functions.iter().any(|func| func.is_overload);
-                    let should_add_overload = functions.len() > 1 && has_overload;
-
-                    let mut sorted_functions = functions.clone();
-                    sorted_functions
-                        .sort_by_key(|func| (func.file, func.line, func.column, func.index));
-                    for function in sorted_functions {
-                        if should_add_overload {
-                            writeln!(f, "@typing.overload")?;
+                // Generate classes, enums and functions
+                let source_comments = self.config.source_comments;
+                for item in self.module.ordered_items(self.config.item_order) {
+                    match item {
+                        ModuleItem::Class(_) | ModuleItem::Enum(_) if split_classes => {}
+                        ModuleItem::Class(class) => {
+                            if source_comments {
+                                write_source_comment(f, class.file, class.line)?;
+                            }
+                            class.fmt_for_module(&self.module.name, f)?
+                        }
+                        ModuleItem::Enum(enum_) => {
+                            if source_comments {
+                                write_source_comment(f, enum_.file, enum_.line)?;
+                            }
+                            enum_.fmt_for_module(&self.module.name, f)?
+                        }
+                        ModuleItem::Functions(functions) => {
+                            self.module
+                                .write_function_group(functions, source_comments, f)?
                         }
-                        function.fmt_for_module(&self.module.name, f)?;
                     }
                 }
 
+                // Old names refer to the classes defined above
+                for alias in self.module.deprecated_aliases.values() {
+                    alias.fmt_for_module(&self.module.name, f)?;
+                    writeln!(f)?;
+                }
+
+                // Generate trailing content after all generated items
+                for epilogue in &self.module.epilogues {
+                    writeln!(f, "{epilogue}")?;
+                }
+
                 Ok(())
             }
         }
@@ -271,67 +640,393 @@ impl Module {
             "{}",
             ModuleFormatter {
                 module: self,
-                use_type_statement
+                config
             }
         )
         .unwrap();
         output
     }
 
-    /// Collect all items for the `__all__` list in `.pyi` stub files.
+    /// Rough length of the rendered stub, to allocate the output buffer at once
+    fn estimated_stub_len(&self) -> usize {
+        const HEADER_LEN: usize = 1024;
+        const ITEM_LEN: usize = 512;
+        let items = self.class.len()
+            + self.enum_.len()
+            + self.function.len()
+            + self.variables.len()
+            + self.type_aliases.len();
+        HEADER_LEN + ITEM_LEN * items
+    }
+
+    /// Stub files of classes and enums written separately from `__init__.pyi`
+    /// when [StubGenConfig::split_classes] is enabled.
     ///
-    /// This collects public items from classes, enums, functions, variables,
-    /// type aliases, submodules, re-exports, and verbatim entries.
-    /// Items starting with `_` are excluded unless added via `export_verbatim!`.
-    fn collect_all_items(&self) -> BTreeSet<String> {
-        let mut all_items: BTreeSet<String> = BTreeSet::new();
+    /// Returns pairs of the file stem, e.g. `_cls_Foo`, and its content.
+    /// Types defined in this module are imported from the other class stubs or from `__init__.pyi`.
+    pub fn format_class_stubs_with_config(&self, config: &StubGenConfig) -> Vec<(String, String)> {
+        struct ClassStubFormatter<'a> {
+            module: &'a Module,
+            item: ModuleItem<'a>,
+            config: &'a StubGenConfig,
+        }
 
-        // Collect public items from this module
-        for class in self.class.values() {
-            if !class.name.starts_with('_') {
-                all_items.insert(class.name.to_string());
+        impl fmt::Display for ClassStubFormatter<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                writeln!(f, "# This file is automatically generated by pyo3_stub_gen")?;
+                writeln!(f, "# ruff: noqa: E501, F401, F403, F405")?;
+                if self.config.free_threading {
+                    writeln!(f, "{FREE_THREADING_COMMENT}")?;
+                }
+                if self.config.ignore_errors.is_enabled_for(&self.module.name) {
+                    writeln!(f, "# mypy: ignore-errors")?;
+                }
+                writeln!(f)?;
+                if self.config.future_annotations {
+                    writeln!(f, "from __future__ import annotations")?;
+                    writeln!(f)?;
+                }
+
+                let mut local_names = BTreeSet::new();
+                let mut collect = |type_info: &mut TypeInfo| {
+                    self.module.collect_local_names(type_info, &mut local_names)
+                };
+                let (name, imports) = match &self.item {
+                    ModuleItem::Class(class) => {
+                        (*class).clone().for_each_type_mut(&mut collect);
+                        (class.name, class.import())
+                    }
+                    ModuleItem::Enum(enum_) => {
+                        (*enum_).clone().for_each_type_mut(&mut collect);
+                        (enum_.name, enum_.import())
+                    }
+                    ModuleItem::Functions(_) => unreachable!("functions are not split"),
+                };
+                self.module.write_imports(imports, &BTreeSet::new(), f)?;
+                let split_names = self.module.split_class_names();
+                let mut package_names = Vec::new();
+                for local in local_names.iter().filter(|local| *local != name) {
+                    if split_names.contains(&local.as_str()) {
+                        writeln!(f, "from .{} import {local}", class_stub_name(local))?;
+                    } else {
+                        package_names.push(local.as_str());
+                    }
+                }
+                if !package_names.is_empty() {
+                    writeln!(f, "from . import {}", package_names.join(", "))?;
+                }
+                match &self.item {
+                    ModuleItem::Class(class) => {
+                        if class.buffer {
+                            write!(f, "{}", class::BUFFER_IMPORT)?;
+                        }
+                        writeln!(f)?;
+                        if self.config.source_comments {
+                            write_source_comment(f, class.file, class.line)?;
+                        }
+                        class.fmt_for_module(&self.module.name, f)
+                    }
+                    ModuleItem::Enum(enum_) => {
+                        writeln!(f)?;
+                        if self.config.source_comments {
+                            write_source_comment(f, enum_.file, enum_.line)?;
+                        }
+                        enum_.fmt_for_module(&self.module.name, f)
+                    }
+                    ModuleItem::Functions(_) => unreachable!("functions are not split"),
+                }
             }
         }
-        for enum_ in self.enum_.values() {
-            if !enum_.name.starts_with('_') {
-                all_items.insert(enum_.name.to_string());
+
+        self.ordered_items(ItemOrder::Alphabetical)
+            .into_iter()
+            .filter_map(|item| {
+                let name = match &item {
+                    ModuleItem::Class(class) => class.name,
+                    ModuleItem::Enum(enum_) => enum_.name,
+                    ModuleItem::Functions(_) => return None,
+                };
+                let content = ClassStubFormatter {
+                    module: self,
+                    item,
+                    config,
+                }
+                .to_string();
+                Some((class_stub_name(name), content))
+            })
+            .collect()
+    }
+
+    /// Names of classes and enums in this module, sorted
+    fn split_class_names(&self) -> Vec<&'static str> {
+        self.class
+            .values()
+            .map(|class| class.name)
+            .chain(self.enum_.values().map(|enum_| enum_.name))
+            .sorted()
+            .collect()
+    }
+
+    /// Add identifiers in `type_info` which are defined in this module to `names`
+    fn collect_local_names(&self, type_info: &TypeInfo, names: &mut BTreeSet<String>) {
+        for (name, type_ref) in &type_info.type_refs {
+            if type_ref.module.get().unwrap_or(&self.default_module_name) == self.name {
+                names.insert(name.clone());
             }
         }
-        for func_name in self.function.keys() {
-            if !func_name.starts_with('_') {
-                all_items.insert(func_name.to_string());
+    }
+
+    /// Classes, enums and function groups in the order they are written to the stub file.
+    ///
+    /// - [ItemOrder::Alphabetical]: classes, then enums, then functions, each sorted by name
+    /// - [ItemOrder::Source]: all items interleaved by their definition position in Rust source,
+    ///   where a group of overloaded functions is placed at its earliest definition
+    fn ordered_items(&self, order: ItemOrder) -> Vec<ModuleItem<'_>> {
+        let mut items: Vec<ModuleItem> = Vec::new();
+        items.extend(
+            self.class
+                .values()
+                .sorted_by_key(|class| class.name)
+                .map(ModuleItem::Class),
+        );
+        items.extend(
+            self.enum_
+                .values()
+                .sorted_by_key(|enum_| enum_.name)
+                .map(ModuleItem::Enum),
+        );
+        items.extend(self.function.values().map(ModuleItem::Functions));
+        if order == ItemOrder::Source {
+            // Stable sort keeps alphabetical order for items sharing a location
+            items.sort_by_key(|item| item.location());
+        }
+        items
+    }
+
+    fn write_function_group(
+        &self,
+        functions: &[FunctionDef],
+        source_comments: bool,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        // Check if we should add @overload to all functions
+        let has_overload = functions.iter().any(|func| func.is_overload);
+        let should_add_overload = functions.len() > 1 && has_overload;
+
+        // Sort by source location and index for deterministic ordering
+        let mut sorted_functions = functions.to_vec();
+        sorted_functions.sort_by_key(|func| (func.file, func.line, func.column, func.index));
+
+        // Formats functions into a string, which is indented in `if` blocks
+        struct FmtAdapter<'a> {
+            functions: Vec<&'a FunctionDef>,
+            target_module: &'a str,
+            source_comments: bool,
+            should_add_overload: bool,
+        }
+        impl fmt::Display for FmtAdapter<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                for function in &self.functions {
+                    if self.source_comments {
+                        write_source_comment(f, function.file, function.line)?;
+                    }
+                    if self.should_add_overload {
+                        writeln!(f, "@typing.overload")?;
+                    }
+                    function.fmt_for_module(self.target_module, f)?;
+                }
+                Ok(())
             }
         }
-        for var_name in self.variables.keys() {
-            if !var_name.starts_with('_') {
-                all_items.insert(var_name.to_string());
+        // Consecutive functions for the same platform and Python versions share an `if` block,
+        // and the one for the complementary versions is written in the `else` block
+        let mut open_py_version = None;
+        for ((platform, py_version), group) in &sorted_functions
+            .iter()
+            .chunk_by(|func| (func.platform, func.py_version))
+        {
+            let functions: Vec<_> = group.collect();
+            let adapter = FmtAdapter {
+                functions: functions.clone(),
+                target_module: &self.name,
+                source_comments,
+                should_add_overload,
+            };
+            let Some(condition) = functions[0].condition() else {
+                open_py_version = None;
+                write!(f, "{adapter}")?;
+                continue;
+            };
+            match (open_py_version.take(), platform, py_version) {
+                (Some(open), None, Some(py_version))
+                    if is_complementary_py_version(open, py_version)
+                        || is_complementary_py_version(py_version, open) =>
+                {
+                    writeln!(f, "else:")?;
+                }
+                _ => {
+                    writeln!(f, "if {condition}:")?;
+                    if platform.is_none() {
+                        open_py_version = py_version;
+                    }
+                }
             }
+            write_indented(f, &adapter.to_string())?;
         }
-        for alias_name in self.type_aliases.keys() {
-            if !alias_name.starts_with('_') {
-                all_items.insert(alias_name.to_string());
+        Ok(())
+    }
+
+    /// Rename functions and methods by `rule` given by the `rename` configuration
+    pub(crate) fn rename(&mut self, rule: RenameRule) -> anyhow::Result<()> {
+        let mut functions: BTreeMap<&'static str, Vec<FunctionDef>> = BTreeMap::new();
+        for (name, mut overloads) in std::mem::take(&mut self.function) {
+            let renamed = rule.apply(name);
+            let renamed: &'static str = if renamed == name {
+                name
+            } else {
+                Box::leak(renamed.into_boxed_str())
+            };
+            if functions.contains_key(renamed) {
+                anyhow::bail!(
+                    "Function `{name}` in module `{}` is renamed to `{renamed}` by `rename`, which is already defined",
+                    self.name
+                );
+            }
+            for function in &mut overloads {
+                function.name = renamed;
+            }
+            functions.insert(renamed, overloads);
+        }
+        self.function = functions;
+        for class in self.class.values_mut() {
+            class.rename_methods(rule)?;
+        }
+        for dict_keys in &mut self.dict_keys {
+            let renamed = rule.apply(dict_keys.function);
+            if renamed != dict_keys.function {
+                dict_keys.function = Box::leak(renamed.into_boxed_str());
             }
         }
-        for submod in &self.submodules {
-            if !submod.starts_with('_') {
-                all_items.insert(submod.to_string());
+        for enum_ in self.enum_.values_mut() {
+            for method in &mut enum_.methods {
+                let renamed = rule.apply(method.name);
+                if renamed != method.name {
+                    method.name = Box::leak(renamed.into_boxed_str());
+                }
             }
         }
+        Ok(())
+    }
 
-        // Add items from re-exports
-        for re_export in &self.module_re_exports {
-            all_items.extend(re_export.items.iter().cloned());
+    /// Apply `mode` to functions marked by `#[gen_stub(platform = "...")]`
+    pub(crate) fn apply_platform_specific(&mut self, mode: PlatformSpecific) {
+        match mode {
+            PlatformSpecific::Guard => {}
+            PlatformSpecific::Include => {
+                for function in self.function.values_mut().flatten() {
+                    function.platform = None;
+                }
+            }
+            PlatformSpecific::Exclude => {
+                for functions in self.function.values_mut() {
+                    functions.retain(|func| func.platform.is_none());
+                }
+                self.function.retain(|_, functions| !functions.is_empty());
+            }
         }
+    }
 
-        // Add verbatim entries (allows explicit inclusion of underscore items)
-        all_items.extend(self.verbatim_all_entries.iter().cloned());
+    /// Collect all items for the `__all__` list in `.pyi` stub files.
+    ///
+    /// This collects public items from classes, enums, functions, variables,
+    /// type aliases, submodules, re-exports, and verbatim entries.
+    /// Items starting with `_` are excluded unless added via `export_verbatim!`.
+    fn collect_all_items(&self, config: &StubGenConfig) -> Vec<String> {
+        // Collect public items from this module in the order they are written
+        let mut defined: Vec<&str> = Vec::new();
+        // Type aliases defined only on some Python versions are added by `collect_conditional_all_items`
+        defined.extend(
+            self.type_aliases
+                .values()
+                .filter(|alias| alias.py_version.is_none() || alias.fallback.is_some())
+                .map(|alias| alias.name),
+        );
+        defined.extend(self.variables.keys());
+        defined.extend(self.deprecated_aliases.keys());
+        defined.extend(self.typed_dicts.keys());
+        defined.extend(self.named_tuples.keys());
+        for item in self.ordered_items(config.item_order) {
+            match item {
+                ModuleItem::Class(class) => defined.push(class.name),
+                ModuleItem::Enum(enum_) => defined.push(enum_.name),
+                // Functions defined only on some platforms or Python versions are added by `collect_conditional_all_items`
+                ModuleItem::Functions(functions) => {
+                    if is_always_defined(functions) {
+                        defined.push(functions[0].name);
+                    }
+                }
+            }
+        }
+        defined.extend(self.submodules.iter().map(String::as_str));
+        let all_items = defined
+            .into_iter()
+            .filter(|name| !name.starts_with('_'))
+            .map(str::to_string)
+            .collect();
+        self.finish_all_items(all_items, config.all_order)
+    }
 
-        // Remove explicitly excluded items
-        for excluded in &self.excluded_all_entries {
-            all_items.remove(excluded);
+    /// Items defined only on some platforms or Python versions for `__all__`,
+    /// added in `if sys.platform == ...:` or `if sys.version_info >= ...:` blocks keyed by the condition
+    fn collect_conditional_all_items(
+        &self,
+        config: &StubGenConfig,
+    ) -> BTreeMap<String, Vec<String>> {
+        let mut items: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut insert = |condition: String, name: &str| {
+            if !name.starts_with('_') && !self.excluded_all_entries.contains(name) {
+                items.entry(condition).or_default().push(name.to_string());
+            }
+        };
+        for alias in self.type_aliases.values() {
+            if let (Some(py_version), None) = (alias.py_version, &alias.fallback) {
+                insert(py_version_condition(py_version), alias.name);
+            }
         }
+        for item in self.ordered_items(config.item_order) {
+            let ModuleItem::Functions(functions) = item else {
+                continue;
+            };
+            if is_always_defined(functions) {
+                continue;
+            }
+            for func in functions {
+                if let Some(condition) = func.condition() {
+                    insert(condition, func.name);
+                }
+            }
+        }
+        for names in items.values_mut() {
+            if config.all_order == AllOrder::Alphabetical {
+                names.sort();
+            }
+            names.dedup();
+        }
+        items
+    }
 
-        all_items
+    /// Add re-exported items and verbatim entries to `items`, then remove excluded and duplicated entries
+    fn finish_all_items(&self, mut items: Vec<String>, order: AllOrder) -> Vec<String> {
+        for re_export in &self.module_re_exports {
+            items.extend(re_export.exported_names());
+        }
+        // Verbatim entries allow explicit inclusion of underscore items
+        items.extend(self.verbatim_all_entries.iter().cloned());
+        items.retain(|item| !self.excluded_all_entries.contains(item));
+        if order == AllOrder::Alphabetical {
+            items.sort();
+        }
+        items.into_iter().unique().collect()
     }
 
     /// Format module as `__init__.py` content.
@@ -340,6 +1035,11 @@ impl Module {
     /// Unlike `format_with_config()` which generates `.pyi` stub files, this generates
     /// actual Python code for runtime use.
     pub fn format_init_py(&self) -> String {
+        self.format_init_py_with_config(&StubGenConfig::default())
+    }
+
+    /// Format module as `__init__.py` content with the given configuration
+    pub fn format_init_py_with_config(&self, config: &StubGenConfig) -> String {
         use std::fmt::Write;
         let mut output = String::new();
 
@@ -356,104 +1056,40 @@ impl Module {
         // Always use explicit imports (not wildcard) for better tooling support
         let mut sorted_re_exports = self.module_re_exports.clone();
         sorted_re_exports.sort_by(|a, b| a.source_module.cmp(&b.source_module));
-        for re_export in &sorted_re_exports {
-            if re_export.items.is_empty() {
-                continue;
-            }
-            let mut sorted_items = re_export.items.clone();
-            sorted_items.sort();
-            writeln!(
-                output,
-                "from {} import {}",
-                re_export.source_module,
-                sorted_items.join(", ")
-            )
-            .unwrap();
+        for line in sorted_re_exports
+            .iter()
+            .filter_map(ModuleReExport::import_line)
+        {
+            writeln!(output, "{line}").unwrap();
         }
 
         // Collect __all__ items from re-exports only
         // (Unlike __init__.pyi, __init__.py can only contain re-exported items)
-        let mut all_items: BTreeSet<String> = BTreeSet::new();
-        for re_export in &self.module_re_exports {
-            all_items.extend(re_export.items.iter().cloned());
-        }
-        all_items.extend(self.verbatim_all_entries.iter().cloned());
-        for excluded in &self.excluded_all_entries {
-            all_items.remove(excluded);
-        }
-        if all_items.is_empty() {
-            writeln!(output, "__all__ = []").unwrap();
-        } else {
-            writeln!(output, "__all__ = [").unwrap();
-            for item in all_items {
-                writeln!(output, "    \"{}\",", item).unwrap();
-            }
-            writeln!(output, "]").unwrap();
-        }
+        let all_items = self.finish_all_items(Vec::new(), config.all_order);
+        write_all_list(&mut output, &all_items, config.all_style).unwrap();
 
         output
     }
 
-    fn write_all_list(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let all_items = self.collect_all_items();
-
-        // Always write __all__ list (even if empty for consistency)
-        if all_items.is_empty() {
-            writeln!(f, "__all__ = []")?;
-        } else {
-            writeln!(f, "__all__ = [")?;
-            for item in all_items {
-                writeln!(f, "    \"{}\",", item)?;
-            }
-            writeln!(f, "]")?;
-        }
-
-        Ok(())
-    }
-}
-
-impl Import for Module {
-    fn import(&self) -> HashSet<ImportRef> {
-        let mut imports = HashSet::new();
-        for class in self.class.values() {
-            imports.extend(class.import());
-        }
-        for enum_ in self.enum_.values() {
-            imports.extend(enum_.import());
-        }
-        for function in self.function.values().flatten() {
-            imports.extend(function.import());
-        }
-        for variable in self.variables.values() {
-            imports.extend(variable.import());
-        }
-        for type_alias in self.type_aliases.values() {
-            imports.extend(type_alias.import());
-        }
-        imports
-    }
-}
-
-impl fmt::Display for Module {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // Write header and docstring directly (no type names here)
-        writeln!(f, "# This file is automatically generated by pyo3_stub_gen")?;
-        writeln!(f, "# ruff: noqa: E501, F401, F403, F405")?;
-        if !self.doc.is_empty() {
-            docstring::write_docstring(f, &self.doc, "")?;
-        }
-        writeln!(f)?;
-
-        let mut imports = self.import();
-        // Check if any function group needs @overload decorator
-        let any_overloaded = self.function.values().any(|functions| {
-            let has_overload = functions.iter().any(|f| f.is_overload);
-            functions.len() > 1 && has_overload
-        });
-        if any_overloaded {
+    /// Write imports of types and modules referenced from this module.
+    ///
+    /// `from parent import child` is omitted for modules in `submodules`,
+    /// which are imported separately by `from . import child`.
+    /// Imports of [Module::type_checking_modules] are written under `if typing.TYPE_CHECKING:`.
+    fn write_imports(
+        &self,
+        mut imports: HashSet<ImportRef>,
+        submodules: &BTreeSet<String>,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        if imports.iter().any(|import_ref| {
+            self.type_checking_modules
+                .contains(import_ref.module_name(&self.default_module_name))
+        }) {
             imports.insert("typing".into());
         }
-
+        // Imports of modules in a cycle with this module, written at the end
+        let mut type_checking_lines = Vec::new();
         // To gather `from submod import A, B, C` style imports
         let mut type_ref_grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
         for import_ref in imports.into_iter().sorted() {
@@ -473,18 +1109,24 @@ impl fmt::Display for Module {
                         // For nested modules like "package.module.submodule" within the current package
                         // Generate: from package.module import submodule
                         // For external modules like "collections.abc", use: import collections.abc
-                        if is_internal_module && name.contains('.') {
+                        let line = if is_internal_module && name.contains('.') {
                             let last_dot_pos = name.rfind('.').unwrap();
                             let parent_module = &name[..last_dot_pos];
                             let child_module = &name[last_dot_pos + 1..];
 
                             // Skip if this is a direct submodule (already imported via submodule imports)
-                            if !self.submodules.contains(child_module) {
-                                writeln!(f, "from {} import {}", parent_module, child_module)?;
+                            if submodules.contains(child_module) {
+                                continue;
                             }
+                            format!("from {} import {}", parent_module, child_module)
                         } else {
                             // External module or top-level module - use standard import
-                            writeln!(f, "import {name}")?;
+                            format!("import {name}")
+                        };
+                        if self.type_checking_modules.contains(name) {
+                            type_checking_lines.push(line);
+                        } else {
+                            writeln!(f, "{line}")?;
                         }
                     }
                 }
@@ -502,67 +1144,127 @@ impl fmt::Display for Module {
         for (module_name, type_names) in type_ref_grouped {
             let mut sorted_type_names = type_names.clone();
             sorted_type_names.sort();
-            writeln!(
-                f,
+            let line = format!(
                 "from {} import {}",
                 module_name,
                 sorted_type_names.join(", ")
-            )?;
+            );
+            if self.type_checking_modules.contains(&module_name) {
+                type_checking_lines.push(line);
+            } else {
+                writeln!(f, "{line}")?;
+            }
         }
-        // Add imports for module re-exports (always explicit, not wildcard)
-        let mut sorted_re_exports = self.module_re_exports.clone();
-        sorted_re_exports.sort_by(|a, b| a.source_module.cmp(&b.source_module));
-        for re_export in &sorted_re_exports {
-            if re_export.items.is_empty() {
-                continue;
+        if !type_checking_lines.is_empty() {
+            writeln!(f, "if typing.TYPE_CHECKING:")?;
+            for line in type_checking_lines {
+                writeln!(f, "    {line}")?;
             }
-            let mut sorted_items = re_export.items.clone();
-            sorted_items.sort();
-            writeln!(
-                f,
-                "from {} import {}",
-                re_export.source_module,
-                sorted_items.join(", ")
-            )?;
         }
-        for submod in &self.submodules {
-            writeln!(f, "from . import {submod}")?;
+        Ok(())
+    }
+
+    /// Write imports whose source depends on the Python version, e.g. `Buffer` for `#[gen_stub(buffer)]`
+    fn write_version_gated_imports(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.class.values().any(|class| class.buffer) {
+            write!(f, "{}", class::BUFFER_IMPORT)?;
         }
+        Ok(())
+    }
+}
 
-        // Generate __all__ list
-        self.write_all_list(f)?;
+/// Prefix of the stub files classes and enums are split into
+pub(crate) const CLASS_STUB_PREFIX: &str = "_cls_";
 
-        writeln!(f)?;
+/// File stem of the stub file a class or an enum is split into
+fn class_stub_name(name: &str) -> String {
+    format!("{CLASS_STUB_PREFIX}{name}")
+}
 
-        for alias in self.type_aliases.values() {
-            alias.fmt_for_module(&self.name, f)?;
-            writeln!(f)?;
+/// Whether any of the overloads is defined regardless of platforms and Python versions,
+/// i.e. it is unconditional or defined for both of complementary Python versions like `">=3.12"` and `"<3.12"`
+fn is_always_defined(functions: &[FunctionDef]) -> bool {
+    let py_versions: Vec<&str> = functions
+        .iter()
+        .filter(|func| func.platform.is_none())
+        .filter_map(|func| func.py_version)
+        .collect();
+    functions.iter().any(|func| func.condition().is_none())
+        || py_versions.iter().any(|a| {
+            py_versions
+                .iter()
+                .any(|b| is_complementary_py_version(a, b))
+        })
+}
+
+/// Write `__all__` as a list or a tuple, one entry per line
+fn write_all_list(f: &mut impl fmt::Write, items: &[String], style: AllStyle) -> fmt::Result {
+    let (open, close) = match style {
+        AllStyle::List => ("[", "]"),
+        AllStyle::Tuple => ("(", ")"),
+    };
+    // Always write __all__ (even if empty for consistency)
+    if items.is_empty() {
+        return writeln!(f, "__all__ = {open}{close}");
+    }
+    writeln!(f, "__all__ = {open}")?;
+    for item in items {
+        writeln!(f, "    \"{}\",", item)?;
+    }
+    writeln!(f, "{close}")
+}
+
+impl Import for Module {
+    fn import(&self) -> HashSet<ImportRef> {
+        let mut imports = self.import_except_classes();
+        for class in self.class.values() {
+            class.import_into(&mut imports);
         }
-        for var in self.variables.values() {
-            var.fmt_for_module(&self.name, f)?;
-            writeln!(f)?;
+        for enum_ in self.enum_.values() {
+            enum_.import_into(&mut imports);
         }
-        for class in self.class.values().sorted_by_key(|class| class.name) {
-            class.fmt_for_module(&self.name, f)?;
+        imports
+    }
+}
+
+impl Module {
+    /// Imports required by items other than classes and enums,
+    /// i.e. those remaining in `__init__.pyi` when classes are split into their own files
+    fn import_except_classes(&self) -> HashSet<ImportRef> {
+        let mut imports = HashSet::new();
+        for function in self.function.values().flatten() {
+            function.import_into(&mut imports);
         }
-        for enum_ in self.enum_.values().sorted_by_key(|class| class.name) {
-            enum_.fmt_for_module(&self.name, f)?;
+        for variable in self.variables.values() {
+            variable.import_into(&mut imports);
         }
-        for functions in self.function.values() {
-            // Check if we should add @overload to all functions
-            let has_overload = functions.iter().any(|func| func.is_overload);
-            let should_add_overload = functions.len() > 1 && has_overload;
-
-            // Sort by source location and index for deterministic ordering
-            let mut sorted_functions = functions.clone();
-            sorted_functions.sort_by_key(|func| (func.file, func.line, func.column, func.index));
-            for function in sorted_functions {
-                if should_add_overload {
-                    writeln!(f, "@typing.overload")?;
-                }
-                function.fmt_for_module(&self.name, f)?;
-            }
+        for type_alias in self.type_aliases.values() {
+            type_alias.import_into(&mut imports);
         }
-        Ok(())
+        for alias in self.deprecated_aliases.values() {
+            alias.import_into(&mut imports);
+        }
+        for param in self.type_params.values() {
+            param.import_into(&mut imports);
+        }
+        for typed_dict in self.typed_dicts.values() {
+            typed_dict.import_into(&mut imports);
+        }
+        for named_tuple in self.named_tuples.values() {
+            named_tuple.import_into(&mut imports);
+        }
+        for helper in self.typing_helpers.iter().chain(&self.epilogues) {
+            helper.import_into(&mut imports);
+        }
+        if let Some(getattr) = &self.getattr {
+            imports.extend(getattr.import.iter().cloned());
+        }
+        imports
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.format_with_config(&StubGenConfig::default()))
     }
 }
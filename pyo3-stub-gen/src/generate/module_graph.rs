@@ -0,0 +1,174 @@
+use crate::generate::{Import, Module};
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Dependency graph of the generated modules.
+///
+/// An edge `a -> b` means the stub file of `a` imports types or modules from `b`.
+/// Nodes are the module names in sorted order, and edges are pairs of their indices.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ModuleGraph {
+    pub nodes: Vec<String>,
+    /// `(from, to)` pairs of node indices
+    pub edges: BTreeSet<(usize, usize)>,
+}
+
+impl ModuleGraph {
+    pub(crate) fn new(modules: &BTreeMap<String, Module>) -> Self {
+        let nodes: Vec<String> = modules.keys().cloned().collect();
+        let mut edges = BTreeSet::new();
+        for (from, module) in modules.values().enumerate() {
+            for import_ref in module.import() {
+                let name = import_ref.module_name(&module.default_module_name);
+                if let Ok(to) = nodes.binary_search_by(|node| node.as_str().cmp(name)) {
+                    if to != from {
+                        edges.insert((from, to));
+                    }
+                }
+            }
+        }
+        Self { nodes, edges }
+    }
+
+    /// Index of the node of a module
+    pub fn node_index(&self, module: &str) -> Option<usize> {
+        self.nodes
+            .binary_search_by(|node| node.as_str().cmp(module))
+            .ok()
+    }
+
+    /// Modules imported by `module`
+    pub fn dependencies<'a>(&'a self, module: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let index = self.node_index(module);
+        self.edges
+            .iter()
+            .filter(move |(from, _)| Some(*from) == index)
+            .map(|(_, to)| self.nodes[*to].as_str())
+    }
+
+    /// Modules importing `module`
+    pub fn dependents<'a>(&'a self, module: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let index = self.node_index(module);
+        self.edges
+            .iter()
+            .filter(move |(_, to)| Some(*to) == index)
+            .map(|(from, _)| self.nodes[*from].as_str())
+    }
+
+    /// Node indices reachable from `start` by one or more edges
+    fn reachable(&self, start: usize) -> Vec<bool> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for &(_, to) in self.edges.range((node, 0)..=(node, usize::MAX)) {
+                if !visited[to] {
+                    visited[to] = true;
+                    stack.push(to);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Groups of modules importing each other directly or indirectly,
+    /// i.e. the strongly connected components of two or more modules, sorted by name
+    pub fn cycles(&self) -> Vec<Vec<&str>> {
+        let reachable: Vec<_> = (0..self.nodes.len()).map(|i| self.reachable(i)).collect();
+        let mut assigned = vec![false; self.nodes.len()];
+        let mut cycles = Vec::new();
+        for i in 0..self.nodes.len() {
+            if assigned[i] || !reachable[i][i] {
+                continue;
+            }
+            let component: Vec<usize> = (0..self.nodes.len())
+                .filter(|&j| reachable[i][j] && reachable[j][i])
+                .collect();
+            for &j in &component {
+                assigned[j] = true;
+            }
+            cycles.push(component.iter().map(|&j| self.nodes[j].as_str()).collect());
+        }
+        cycles
+    }
+
+    /// Whether `a` and `b` are different modules importing each other directly or indirectly
+    pub fn in_same_cycle(&self, a: &str, b: &str) -> bool {
+        let (Some(a), Some(b)) = (self.node_index(a), self.node_index(b)) else {
+            return false;
+        };
+        a != b && self.reachable(a)[b] && self.reachable(b)[a]
+    }
+
+    /// Modules ordered so that every module comes after the modules it imports,
+    /// with ties broken by name.
+    ///
+    /// Returns an error naming the modules of a cycle if any.
+    pub fn topological_order(&self) -> Result<Vec<&str>> {
+        let mut remaining: Vec<usize> = vec![0; self.nodes.len()];
+        for &(from, _) in &self.edges {
+            remaining[from] += 1;
+        }
+        let mut ready: BTreeSet<usize> = (0..self.nodes.len())
+            .filter(|&i| remaining[i] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(node) = ready.pop_first() {
+            order.push(self.nodes[node].as_str());
+            for &(from, to) in &self.edges {
+                if to == node {
+                    remaining[from] -= 1;
+                    if remaining[from] == 0 {
+                        ready.insert(from);
+                    }
+                }
+            }
+        }
+        if order.len() < self.nodes.len() {
+            let cycle = self.cycles().into_iter().next().unwrap_or_default();
+            anyhow::bail!("Circular imports among modules: {}", cycle.join(", "));
+        }
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn graph(nodes: &[&str], edges: &[(usize, usize)]) -> ModuleGraph {
+        ModuleGraph {
+            nodes: nodes.iter().map(|node| node.to_string()).collect(),
+            edges: edges.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn test_topological_order() {
+        // pkg -> pkg.a -> pkg.b
+        let graph = graph(&["pkg", "pkg.a", "pkg.b"], &[(0, 1), (1, 2)]);
+        assert_eq!(
+            graph.topological_order().unwrap(),
+            ["pkg.b", "pkg.a", "pkg"]
+        );
+        assert_eq!(graph.dependencies("pkg").collect::<Vec<_>>(), ["pkg.a"]);
+        assert_eq!(graph.dependents("pkg.b").collect::<Vec<_>>(), ["pkg.a"]);
+        assert!(graph.cycles().is_empty());
+    }
+
+    #[test]
+    fn test_cycles() {
+        // pkg -> pkg.a <-> pkg.b, pkg.c
+        let graph = graph(
+            &["pkg", "pkg.a", "pkg.b", "pkg.c"],
+            &[(0, 1), (1, 2), (2, 1)],
+        );
+        assert_eq!(graph.cycles(), [vec!["pkg.a", "pkg.b"]]);
+        assert!(graph.in_same_cycle("pkg.a", "pkg.b"));
+        assert!(!graph.in_same_cycle("pkg", "pkg.a"));
+        assert_eq!(
+            graph.topological_order().unwrap_err().to_string(),
+            "Circular imports among modules: pkg.a, pkg.b"
+        );
+    }
+}
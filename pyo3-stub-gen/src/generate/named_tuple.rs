@@ -0,0 +1,66 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    generate::{docstring, indent, normalize_docstring, Import},
+    stub_type::ImportRef,
+    type_info::NamedTupleInfo,
+    TypeInfo,
+};
+
+/// `typing.NamedTuple` returned by functions with `#[gen_stub(named_tuple(...))]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NamedTupleDef {
+    pub name: &'static str,
+    pub doc: &'static str,
+    pub fields: Vec<(&'static str, TypeInfo)>,
+}
+
+impl From<&NamedTupleInfo> for NamedTupleDef {
+    fn from(info: &NamedTupleInfo) -> Self {
+        let doc = if info.doc.is_empty() {
+            ""
+        } else {
+            Box::leak(normalize_docstring(info.doc).into_boxed_str())
+        };
+        Self {
+            name: info.name,
+            doc,
+            fields: info
+                .fields
+                .iter()
+                .map(|field| (field.name, (field.r#type)()))
+                .collect(),
+        }
+    }
+}
+
+impl Import for NamedTupleDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        let mut import: HashSet<ImportRef> = HashSet::from(["typing".into()]);
+        for (_, type_) in &self.fields {
+            import.extend(type_.import.iter().cloned());
+        }
+        import
+    }
+}
+
+impl NamedTupleDef {
+    /// Format the `NamedTuple` class with module-qualified type names
+    pub fn fmt_for_module(&self, target_module: &str, f: &mut fmt::Formatter) -> fmt::Result {
+        let indent = indent();
+        writeln!(f, "class {}(typing.NamedTuple):", self.name)?;
+        docstring::write_docstring(f, self.doc, indent)?;
+        for (name, type_) in &self.fields {
+            writeln!(
+                f,
+                "{indent}{name}: {}",
+                type_.qualified_for_module(target_module)
+            )?;
+        }
+        if self.doc.is_empty() && self.fields.is_empty() {
+            writeln!(f, "{indent}...")?;
+        }
+        writeln!(f)
+    }
+}
@@ -0,0 +1,146 @@
+//! Rewrite NumPy array types into the style selected by `[tool.pyo3-stub-gen.numpy]`.
+//!
+//! Array types like `PyArray2<f64>` are rendered with full information,
+//! `numpy.ndarray[tuple[builtins.int, builtins.int], numpy.dtype[numpy.float64]]`,
+//! and rewritten here after all stub items are collected, where the position of the type is known.
+
+use crate::{pyproject::NumpyArrayStyle, stub_type::TypeInfo};
+
+const NDARRAY: &str = "numpy.ndarray[";
+const DTYPE: &str = "numpy.dtype[";
+
+/// Rewrite `numpy.ndarray[shape, numpy.dtype[scalar]]` in `type_info` into `style`
+pub(crate) fn apply_array_style(type_info: &mut TypeInfo, style: NumpyArrayStyle) {
+    if let Some(name) = rewrite_arrays(&type_info.name, style) {
+        type_info.name = name;
+        type_info.import.insert("numpy.typing".into());
+    }
+}
+
+/// Rewrite array types in a type expression.
+///
+/// Returns `None` if the expression contains no array type to be rewritten.
+pub(crate) fn rewrite_arrays(expr: &str, style: NumpyArrayStyle) -> Option<String> {
+    if style == NumpyArrayStyle::Ndarray {
+        return None;
+    }
+    let mut rewritten = false;
+    let mut result = String::new();
+    let mut rest = expr;
+    while let Some(start) = rest.find(NDARRAY) {
+        let args_start = start + NDARRAY.len();
+        let Some(args_len) = closing_bracket(&rest[args_start..]) else {
+            break;
+        };
+        let args = &rest[args_start..args_start + args_len];
+        let end = args_start + args_len + 1;
+        // Skip other names ending with `numpy.ndarray`, e.g. `mynumpy.ndarray`
+        let is_qualified = rest[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        match dtype_of(args) {
+            Some(dtype) if !is_qualified => {
+                rewritten = true;
+                result.push_str(&rest[..start]);
+                match style {
+                    NumpyArrayStyle::Ndarray => unreachable!(),
+                    NumpyArrayStyle::NDArray => {
+                        result.push_str(&format!("numpy.typing.NDArray[{dtype}]"))
+                    }
+                    NumpyArrayStyle::ArrayLike => result.push_str("numpy.typing.ArrayLike"),
+                }
+            }
+            _ => result.push_str(&rest[..end]),
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    rewritten.then_some(result)
+}
+
+/// Position of the `]` closing the bracket opened just before `s`
+fn closing_bracket(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' if depth == 0 => return Some(i),
+            ']' | ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Scalar type `T` of `shape, numpy.dtype[T]`
+fn dtype_of(args: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let comma = args.char_indices().find_map(|(i, c)| {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => return Some(i),
+            _ => {}
+        }
+        None
+    })?;
+    args[comma + 1..]
+        .trim()
+        .strip_prefix(DTYPE)?
+        .strip_suffix(']')
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ARRAY2: &str =
+        "numpy.ndarray[tuple[builtins.int, builtins.int], numpy.dtype[numpy.float64]]";
+
+    #[test]
+    fn test_ndarray_is_kept() {
+        assert_eq!(rewrite_arrays(ARRAY2, NumpyArrayStyle::Ndarray), None);
+    }
+
+    #[test]
+    fn test_ndarray_to_ndarray_alias() {
+        assert_eq!(
+            rewrite_arrays(ARRAY2, NumpyArrayStyle::NDArray).as_deref(),
+            Some("numpy.typing.NDArray[numpy.float64]")
+        );
+        assert_eq!(
+            rewrite_arrays(
+                "numpy.ndarray[tuple[builtins.int, ...], numpy.dtype[typing.Any]]",
+                NumpyArrayStyle::NDArray
+            )
+            .as_deref(),
+            Some("numpy.typing.NDArray[typing.Any]")
+        );
+    }
+
+    #[test]
+    fn test_ndarray_to_array_like() {
+        let expr = format!("typing.Optional[{ARRAY2}] | dict[builtins.str, {ARRAY2}]");
+        assert_eq!(
+            rewrite_arrays(&expr, NumpyArrayStyle::ArrayLike).as_deref(),
+            Some("typing.Optional[numpy.typing.ArrayLike] | dict[builtins.str, numpy.typing.ArrayLike]")
+        );
+    }
+
+    #[test]
+    fn test_other_types_are_kept() {
+        assert_eq!(
+            rewrite_arrays("builtins.list[builtins.int]", NumpyArrayStyle::NDArray),
+            None
+        );
+        assert_eq!(
+            rewrite_arrays(
+                "mynumpy.ndarray[builtins.int, numpy.dtype[numpy.int8]]",
+                NumpyArrayStyle::NDArray
+            ),
+            None
+        );
+    }
+}
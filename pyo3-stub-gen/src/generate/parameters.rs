@@ -1,5 +1,5 @@
 use crate::{
-    generate::Import,
+    generate::{qualifier::TypeExpressionQualifier, Import},
     stub_type::{ImportRef, ModuleRef},
     type_info::{ParameterDefault as ParameterDefaultInfo, ParameterInfo, ParameterKind},
     TypeInfo,
@@ -8,6 +8,7 @@ use std::{collections::HashSet, fmt};
 
 /// Default value of a parameter (runtime version)
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ParameterDefault {
     /// No default value
     None,
@@ -24,6 +25,7 @@ pub enum ParameterDefault {
 ///
 /// This struct represents a parameter at runtime during stub generation.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Parameter {
     /// Parameter name
     pub name: &'static str,
@@ -39,6 +41,10 @@ impl Import for Parameter {
     fn import(&self) -> HashSet<ImportRef> {
         self.type_info.import.clone()
     }
+
+    fn import_into(&self, import: &mut HashSet<ImportRef>) {
+        import.extend(self.type_info.import.iter().cloned());
+    }
 }
 
 impl From<&ParameterInfo> for Parameter {
@@ -61,24 +67,49 @@ impl From<&ParameterInfo> for Parameter {
     }
 }
 
-impl fmt::Display for Parameter {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Parameter {
+    /// Prefix marking variadic parameters, `*` for `*args` and `**` for `**kwargs`
+    fn prefix(&self) -> &'static str {
         match self.kind {
-            ParameterKind::VarPositional => {
-                write!(f, "*{}: {}", self.name, self.type_info)
-            }
-            ParameterKind::VarKeyword => {
-                write!(f, "**{}: {}", self.name, self.type_info)
-            }
-            _ => {
-                write!(f, "{}: {}", self.name, self.type_info)?;
-                match &self.default {
-                    ParameterDefault::None => Ok(()),
-                    ParameterDefault::Expr { value, .. } => write!(f, " = {}", value),
-                }
-            }
+            ParameterKind::VarPositional => "*",
+            ParameterKind::VarKeyword => "**",
+            _ => "",
         }
     }
+
+    /// Format as `name: type = default` from already rendered type and default expressions
+    ///
+    /// The `*`/`**` prefix is taken from the kind, so an overridden type of `*args` or `**kwargs`
+    /// is rendered in the same way as an inferred one. Variadic parameters never have defaults.
+    fn format_with(&self, type_repr: &str, default: Option<&str>) -> String {
+        let mut out = format!("{}{}: {}", self.prefix(), self.name, type_repr);
+        if let (Some(default), false) = (default, self.is_variadic()) {
+            out.push_str(" = ");
+            out.push_str(default);
+        }
+        out
+    }
+
+    fn is_variadic(&self) -> bool {
+        matches!(
+            self.kind,
+            ParameterKind::VarPositional | ParameterKind::VarKeyword
+        )
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let default = match &self.default {
+            ParameterDefault::None => None,
+            ParameterDefault::Expr { value, .. } => Some(value.as_str()),
+        };
+        write!(
+            f,
+            "{}",
+            self.format_with(&self.type_info.to_string(), default)
+        )
+    }
 }
 
 /// Container for parameters in a Python function/method signature
@@ -86,6 +117,7 @@ impl fmt::Display for Parameter {
 /// This struct organizes parameters into sections according to Python's signature syntax,
 /// ensuring proper ordering and placement of delimiters (`/` and `*`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Parameters {
     /// Positional-only parameters (before `/`)
     pub positional_only: Vec<Parameter>,
@@ -139,6 +171,16 @@ impl Parameters {
             .chain(self.varkw.iter())
     }
 
+    /// Iterate mutably over all parameters in signature order
+    pub fn iter_entries_mut(&mut self) -> impl Iterator<Item = &mut Parameter> {
+        self.positional_only
+            .iter_mut()
+            .chain(self.positional_or_keyword.iter_mut())
+            .chain(self.varargs.iter_mut())
+            .chain(self.keyword_only.iter_mut())
+            .chain(self.varkw.iter_mut())
+    }
+
     /// Check if there are no parameters at all
     pub fn is_empty(&self) -> bool {
         self.positional_only.is_empty()
@@ -157,116 +199,98 @@ impl Default for Parameters {
 
 impl Import for Parameters {
     fn import(&self) -> HashSet<ImportRef> {
-        self.iter_entries().flat_map(|p| p.import()).collect()
+        let mut import = HashSet::new();
+        self.import_into(&mut import);
+        import
+    }
+
+    fn import_into(&self, import: &mut HashSet<ImportRef>) {
+        for param in self.iter_entries() {
+            param.import_into(import);
+        }
     }
 }
 
-impl fmt::Display for Parameters {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Parameters {
+    /// Join formatted parameters in signature order, inserting the `/` and `*` delimiters
+    ///
+    /// Both [`fmt::Display`] and [`Parameters::fmt_for_module`] go through this
+    /// so that the placement of delimiters does not depend on how each parameter is formatted.
+    fn join_with(&self, format_param: impl Fn(&Parameter) -> String) -> String {
         let mut parts = Vec::new();
 
-        // Positional-only parameters
-        for param in &self.positional_only {
-            parts.push(param.to_string());
-        }
-
-        // Insert `/` delimiter if there are positional-only parameters
+        // Positional-only parameters, followed by `/`
+        parts.extend(self.positional_only.iter().map(&format_param));
         if !self.positional_only.is_empty() {
             parts.push("/".to_string());
         }
 
         // Positional or keyword parameters
-        for param in &self.positional_or_keyword {
-            parts.push(param.to_string());
-        }
+        parts.extend(self.positional_or_keyword.iter().map(&format_param));
 
-        // Variable positional parameter or bare `*` for keyword-only
+        // Variable positional parameter, or bare `*` if only keyword-only parameters follow
         if let Some(varargs) = &self.varargs {
-            parts.push(varargs.to_string());
+            parts.push(format_param(varargs));
         } else if !self.keyword_only.is_empty() {
-            // Need bare `*` to indicate keyword-only parameters follow
             parts.push("*".to_string());
         }
 
         // Keyword-only parameters
-        for param in &self.keyword_only {
-            parts.push(param.to_string());
-        }
+        parts.extend(self.keyword_only.iter().map(&format_param));
 
         // Variable keyword parameter
-        if let Some(varkw) = &self.varkw {
-            parts.push(varkw.to_string());
-        }
+        parts.extend(self.varkw.iter().map(&format_param));
 
-        write!(f, "{}", parts.join(", "))
+        parts.join(", ")
+    }
+
+    /// Format as `(x, /, y=2, *args, **kwargs)` without types, like `#[pyo3(text_signature)]`
+    pub fn text_signature(&self) -> String {
+        let params = self.join_with(|param| match (&param.default, param.is_variadic()) {
+            (ParameterDefault::Expr { value, .. }, false) => format!("{}={value}", param.name),
+            _ => format!("{}{}", param.prefix(), param.name),
+        });
+        format!("({params})")
     }
-}
 
-impl Parameters {
     /// Format parameters with module-qualified type names
     ///
     /// This method uses the target module context to qualify type identifiers
     /// within compound type expressions based on their source modules.
     pub fn fmt_for_module(&self, target_module: &str) -> String {
-        let mut parts = Vec::new();
-
-        // Positional-only parameters
-        for param in &self.positional_only {
-            parts.push(Self::format_param(param, target_module));
-        }
-
-        // Insert `/` delimiter if there are positional-only parameters
-        if !self.positional_only.is_empty() {
-            parts.push("/".to_string());
-        }
-
-        // Positional or keyword parameters
-        for param in &self.positional_or_keyword {
-            parts.push(Self::format_param(param, target_module));
-        }
-
-        // Variable positional parameter or bare `*` for keyword-only
-        if let Some(varargs) = &self.varargs {
-            parts.push(Self::format_param(varargs, target_module));
-        } else if !self.keyword_only.is_empty() {
-            // Need bare `*` to indicate keyword-only parameters follow
-            parts.push("*".to_string());
-        }
-
-        // Keyword-only parameters
-        for param in &self.keyword_only {
-            parts.push(Self::format_param(param, target_module));
-        }
-
-        // Variable keyword parameter
-        if let Some(varkw) = &self.varkw {
-            parts.push(Self::format_param(varkw, target_module));
-        }
-
-        parts.join(", ")
+        self.join_with(|param| Self::format_param(param, target_module))
     }
 
     /// Format a single parameter with qualified type names
     fn format_param(param: &Parameter, target_module: &str) -> String {
         let qualified_type = param.type_info.qualified_for_module(target_module);
-        match param.kind {
-            ParameterKind::VarPositional => format!("*{}: {}", param.name, qualified_type),
-            ParameterKind::VarKeyword => format!("**{}: {}", param.name, qualified_type),
-            _ => {
-                let base = format!("{}: {}", param.name, qualified_type);
-                match &param.default {
-                    ParameterDefault::None => base,
-                    ParameterDefault::Expr {
-                        value,
-                        source_module,
-                    } => {
-                        let qualified_expr =
-                            qualify_default_value(value, source_module.as_ref(), target_module);
-                        format!("{} = {}", base, qualified_expr)
-                    }
-                }
-            }
-        }
+        let qualified_default = match &param.default {
+            ParameterDefault::None => None,
+            ParameterDefault::Expr {
+                value,
+                source_module: Some(source_module),
+            } => Some(qualify_default_value(
+                value,
+                Some(source_module),
+                target_module,
+            )),
+            // The default of a compound type like `Option<Number>` is qualified like the annotation
+            ParameterDefault::Expr {
+                value,
+                source_module: None,
+            } => Some(TypeExpressionQualifier::qualify_value_expression(
+                value,
+                &param.type_info.type_refs,
+                target_module,
+            )),
+        };
+        param.format_with(&qualified_type, qualified_default.as_deref())
+    }
+}
+
+impl fmt::Display for Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.join_with(|param| param.to_string()))
     }
 }
 
@@ -378,6 +402,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_text_signature() {
+        let params = Parameters {
+            positional_only: vec![Parameter {
+                name: "token",
+                kind: ParameterKind::PositionalOnly,
+                type_info: TypeInfo::builtin("str"),
+                default: ParameterDefault::None,
+            }],
+            keyword_only: vec![Parameter {
+                name: "retries",
+                kind: ParameterKind::KeywordOnly,
+                type_info: TypeInfo::builtin("int"),
+                default: ParameterDefault::Expr {
+                    value: "3".to_string(),
+                    source_module: None,
+                },
+            }],
+            varkw: Some(Parameter {
+                name: "kwargs",
+                kind: ParameterKind::VarKeyword,
+                type_info: TypeInfo::any(),
+                default: ParameterDefault::None,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            params.text_signature(),
+            "(token, /, *, retries=3, **kwargs)"
+        );
+        assert_eq!(Parameters::new().text_signature(), "()");
+    }
+
     #[test]
     fn test_varargs_kwargs() {
         let params = Parameters {
@@ -402,6 +459,110 @@ mod tests {
         );
     }
 
+    fn param(name: &'static str, kind: ParameterKind, type_info: TypeInfo) -> Parameter {
+        Parameter {
+            name,
+            kind,
+            type_info,
+            default: ParameterDefault::None,
+        }
+    }
+
+    /// Overridden types on `*args`/`**kwargs` combined with every other section of a signature
+    #[test]
+    fn test_var_args_override_matrix() {
+        let override_type = || TypeInfo {
+            name: "typing.Mapping[str, int]".to_string(),
+            source_module: None,
+            import: ["typing".into()].into_iter().collect(),
+            type_refs: Default::default(),
+            any_fallback: None,
+        };
+        let pos_only = param("a", ParameterKind::PositionalOnly, TypeInfo::builtin("int"));
+        let pos_or_kw = param(
+            "b",
+            ParameterKind::PositionalOrKeyword,
+            TypeInfo::builtin("int"),
+        );
+        let kw_only = Parameter {
+            default: ParameterDefault::Expr {
+                value: "None".to_string(),
+                source_module: None,
+            },
+            ..param("c", ParameterKind::KeywordOnly, TypeInfo::builtin("str"))
+        };
+        let varargs = param("args", ParameterKind::VarPositional, override_type());
+        let varkw = param("kwargs", ParameterKind::VarKeyword, override_type());
+
+        let ty = "typing.Mapping[str, int]";
+        for bits in 0u8..32 {
+            let params = Parameters {
+                positional_only: (bits & 1 != 0)
+                    .then(|| pos_only.clone())
+                    .into_iter()
+                    .collect(),
+                positional_or_keyword: (bits & 2 != 0)
+                    .then(|| pos_or_kw.clone())
+                    .into_iter()
+                    .collect(),
+                varargs: (bits & 4 != 0).then(|| varargs.clone()),
+                keyword_only: (bits & 8 != 0)
+                    .then(|| kw_only.clone())
+                    .into_iter()
+                    .collect(),
+                varkw: (bits & 16 != 0).then(|| varkw.clone()),
+            };
+
+            let mut expected = Vec::new();
+            if bits & 1 != 0 {
+                expected.extend(["a: builtins.int".to_string(), "/".to_string()]);
+            }
+            if bits & 2 != 0 {
+                expected.push("b: builtins.int".to_string());
+            }
+            if bits & 4 != 0 {
+                expected.push(format!("*args: {ty}"));
+            } else if bits & 8 != 0 {
+                expected.push("*".to_string());
+            }
+            if bits & 8 != 0 {
+                expected.push("c: builtins.str = None".to_string());
+            }
+            if bits & 16 != 0 {
+                expected.push(format!("**kwargs: {ty}"));
+            }
+            let expected = expected.join(", ");
+
+            assert_eq!(params.to_string(), expected, "case {bits:05b}");
+            assert_eq!(
+                params.fmt_for_module("pkg"),
+                expected,
+                "case {bits:05b} (fmt_for_module)"
+            );
+        }
+    }
+
+    #[test]
+    fn test_var_args_ignore_default() {
+        let params = Parameters {
+            varkw: Some(Parameter {
+                default: ParameterDefault::Expr {
+                    value: "None".to_string(),
+                    source_module: None,
+                },
+                ..param(
+                    "kwargs",
+                    ParameterKind::VarKeyword,
+                    TypeInfo::builtin("str"),
+                )
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(params.to_string(), "**kwargs: builtins.str");
+        assert_eq!(params.fmt_for_module("pkg"), "**kwargs: builtins.str");
+    }
+
     #[test]
     fn test_qualify_default_value_same_module() {
         // Same module: no qualification needed
@@ -427,6 +588,32 @@ mod tests {
         assert_eq!(result, "_core.C.C1");
     }
 
+    #[test]
+    fn test_qualify_default_of_compound_type() {
+        // `Option<Number>` has no source module, so the default is qualified by the type references
+        let params = Parameters {
+            positional_or_keyword: vec![Parameter {
+                name: "num",
+                kind: ParameterKind::PositionalOrKeyword,
+                type_info: TypeInfo::optional(TypeInfo::locally_defined(
+                    "Number",
+                    "pkg.sub".into(),
+                )),
+                default: ParameterDefault::Expr {
+                    value: "Number.FLOAT".to_string(),
+                    source_module: None,
+                },
+            }],
+            ..Default::default()
+        };
+        assert!(params
+            .fmt_for_module("pkg")
+            .ends_with(" = sub.Number.FLOAT"));
+        assert!(params
+            .fmt_for_module("pkg.sub")
+            .ends_with(" = Number.FLOAT"));
+    }
+
     #[test]
     fn test_qualify_default_value_no_source_module() {
         // No source module: return value unchanged
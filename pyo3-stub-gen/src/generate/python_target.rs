@@ -0,0 +1,308 @@
+//! Rewrite type expressions for the minimum Python version selected by `python-target`.
+//!
+//! Types are rendered as written in `PyStubType` implementations and `override_type`,
+//! mixing `typing.Optional[T]` and `T | None` for example.
+//! They are rewritten here after all stub items are collected, so that each stub file uses one syntax
+//! available in the target version:
+//!
+//! - PEP 604 unions `A | B` for Python 3.10+, `typing.Union[A, B]` and `typing.Optional[A]` otherwise
+//! - PEP 585 generics `builtins.list[T]` for Python 3.9+, `typing.List[T]` otherwise
+//! - Names added to `typing` in a later version than the target are taken from `typing_extensions`
+
+use crate::{pyproject::PythonVersion, stub_type::TypeInfo};
+
+/// Builtin generics and their aliases in `typing` used before Python 3.9
+const BUILTIN_GENERICS: &[(&str, &str)] = &[
+    ("list", "List"),
+    ("dict", "Dict"),
+    ("set", "Set"),
+    ("frozenset", "FrozenSet"),
+    ("tuple", "Tuple"),
+    ("type", "Type"),
+];
+
+/// Names in `typing` and the Python version they were added in.
+/// Older versions take them from `typing_extensions`.
+const TYPING_ADDITIONS: &[(&str, PythonVersion)] = &[
+    ("Annotated", PythonVersion::new(3, 9)),
+    ("TypeAlias", PythonVersion::new(3, 10)),
+    ("ParamSpec", PythonVersion::new(3, 10)),
+    ("Concatenate", PythonVersion::new(3, 10)),
+    ("TypeGuard", PythonVersion::new(3, 10)),
+    ("Self", PythonVersion::new(3, 11)),
+    ("Never", PythonVersion::new(3, 11)),
+    ("LiteralString", PythonVersion::new(3, 11)),
+    ("Required", PythonVersion::new(3, 11)),
+    ("NotRequired", PythonVersion::new(3, 11)),
+    ("Unpack", PythonVersion::new(3, 11)),
+    ("TypeVarTuple", PythonVersion::new(3, 11)),
+    ("dataclass_transform", PythonVersion::new(3, 11)),
+    ("TypeIs", PythonVersion::new(3, 13)),
+    ("ReadOnly", PythonVersion::new(3, 13)),
+];
+
+/// Module to import `name` of `typing` from for `target`, `typing` or `typing_extensions`
+pub(crate) fn typing_module(name: &str, target: Option<PythonVersion>) -> &'static str {
+    let Some(target) = target else {
+        return "typing";
+    };
+    match TYPING_ADDITIONS.iter().find(|(n, _)| *n == name) {
+        Some((_, added)) if target < *added => "typing_extensions",
+        _ => "typing",
+    }
+}
+
+/// Rewrite `type_info` for `target`
+pub(crate) fn apply_python_target(type_info: &mut TypeInfo, target: PythonVersion) {
+    let name = rewrite_type_expr(&type_info.name, target);
+    if name == type_info.name {
+        return;
+    }
+    for module in ["typing", "typing_extensions", "builtins"] {
+        if name.contains(&format!("{module}.")) {
+            type_info.import.insert(module.into());
+        }
+    }
+    type_info.name = name;
+}
+
+/// Rewrite a type expression, e.g. `typing.Optional[builtins.list[builtins.int]]`, for `target`
+pub(crate) fn rewrite_type_expr(expr: &str, target: PythonVersion) -> String {
+    let members: Vec<String> = split_top_level(expr, '|')
+        .into_iter()
+        .map(|member| rewrite_member(member.trim(), target))
+        .collect();
+    if members.len() == 1 {
+        return members.into_iter().next().unwrap();
+    }
+    if target >= PythonVersion::new(3, 10) {
+        return members.join(" | ");
+    }
+    let (none, others): (Vec<String>, Vec<String>) =
+        members.into_iter().partition(|member| member == "None");
+    let union = if others.len() == 1 {
+        others.into_iter().next().unwrap()
+    } else {
+        format!("typing.Union[{}]", others.join(", "))
+    };
+    if none.is_empty() {
+        union
+    } else {
+        format!("typing.Optional[{union}]")
+    }
+}
+
+/// Rewrite an expression without `|` at the top level, e.g. `typing.Optional[T]` or `[A, B]`
+fn rewrite_member(expr: &str, target: PythonVersion) -> String {
+    if expr.starts_with(['"', '\'']) {
+        return expr.to_string();
+    }
+    for (open, close) in [('[', ']'), ('(', ')')] {
+        if let Some(inner) = expr
+            .strip_prefix(open)
+            .and_then(|rest| rest.strip_suffix(close))
+        {
+            return format!("{open}{}{close}", rewrite_args(inner, target));
+        }
+    }
+    let Some(start) = expr.find('[') else {
+        return rewrite_name(expr, target);
+    };
+    if closing_bracket(&expr[start + 1..]) != Some(expr.len() - start - 2) {
+        // Not a single subscription, e.g. a malformed expression
+        return expr.to_string();
+    }
+    let head = rewrite_subscripted_name(&expr[..start], target);
+    let args = rewrite_args(&expr[start + 1..expr.len() - 1], target);
+    if target >= PythonVersion::new(3, 10) {
+        match head.as_str() {
+            "typing.Optional" => return rewrite_type_expr(&format!("{args} | None"), target),
+            "typing.Union" => {
+                let members: Vec<&str> = split_top_level(&args, ',')
+                    .into_iter()
+                    .map(str::trim)
+                    .collect();
+                return rewrite_type_expr(&members.join(" | "), target);
+            }
+            _ => {}
+        }
+    }
+    format!("{head}[{args}]")
+}
+
+/// Rewrite comma-separated arguments of a subscription
+fn rewrite_args(args: &str, target: PythonVersion) -> String {
+    if args.trim().is_empty() {
+        return args.to_string();
+    }
+    split_top_level(args, ',')
+        .into_iter()
+        .map(|arg| rewrite_type_expr(arg.trim(), target))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Rewrite the name of a generic type to be subscripted, e.g. `builtins.list` in `builtins.list[T]`
+fn rewrite_subscripted_name(name: &str, target: PythonVersion) -> String {
+    let pep585 = target >= PythonVersion::new(3, 9);
+    for (builtin, alias) in BUILTIN_GENERICS {
+        let is_builtin = name == *builtin || name.strip_prefix("builtins.") == Some(builtin);
+        if !pep585 && is_builtin {
+            return format!("typing.{alias}");
+        }
+        if pep585 && name.strip_prefix("typing.") == Some(alias) {
+            return format!("builtins.{builtin}");
+        }
+    }
+    if !pep585 {
+        // `collections.abc` classes are subscriptable since Python 3.9
+        if let Some(abc) = name.strip_prefix("collections.abc.") {
+            return format!("typing.{abc}");
+        }
+    }
+    rewrite_name(name, target)
+}
+
+/// Switch a name between `typing` and `typing_extensions`
+fn rewrite_name(name: &str, target: PythonVersion) -> String {
+    let Some(base) = name
+        .strip_prefix("typing.")
+        .or_else(|| name.strip_prefix("typing_extensions."))
+    else {
+        return name.to_string();
+    };
+    if !TYPING_ADDITIONS.iter().any(|(n, _)| *n == base) {
+        return name.to_string();
+    }
+    format!("{}.{base}", typing_module(base, Some(target)))
+}
+
+/// Split `expr` by `sep` outside of brackets and string literals
+fn split_top_level(expr: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in expr.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '(') => depth += 1,
+            (None, ']' | ')') => depth = depth.saturating_sub(1),
+            (None, c) if c == sep && depth == 0 => {
+                parts.push(&expr[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&expr[start..]);
+    parts
+}
+
+/// Position of the `]` closing the bracket opened just before `s`
+fn closing_bracket(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '(') => depth += 1,
+            (None, ']' | ')') if depth == 0 => return Some(i),
+            (None, ']' | ')') => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PY38: PythonVersion = PythonVersion::new(3, 8);
+    const PY39: PythonVersion = PythonVersion::new(3, 9);
+    const PY310: PythonVersion = PythonVersion::new(3, 10);
+    const PY313: PythonVersion = PythonVersion::new(3, 13);
+
+    #[test]
+    fn test_unions() {
+        let expr = "typing.Optional[builtins.int | builtins.str]";
+        assert_eq!(
+            rewrite_type_expr(expr, PY39),
+            "typing.Optional[typing.Union[builtins.int, builtins.str]]"
+        );
+        assert_eq!(
+            rewrite_type_expr(expr, PY310),
+            "builtins.int | builtins.str | None"
+        );
+        assert_eq!(
+            rewrite_type_expr("typing.Union[A, typing.Optional[B]]", PY310),
+            "A | B | None"
+        );
+        assert_eq!(rewrite_type_expr("A | None", PY39), "typing.Optional[A]");
+        // Unions in string literals and forward references are kept
+        assert_eq!(
+            rewrite_type_expr(r#"typing.Literal["a|b"] | "C | D""#, PY39),
+            r#"typing.Union[typing.Literal["a|b"], "C | D"]"#
+        );
+    }
+
+    #[test]
+    fn test_builtin_generics() {
+        let expr = "builtins.dict[builtins.str, builtins.list[tuple[builtins.int, ...]]]";
+        assert_eq!(
+            rewrite_type_expr(expr, PY38),
+            "typing.Dict[builtins.str, typing.List[typing.Tuple[builtins.int, ...]]]"
+        );
+        assert_eq!(rewrite_type_expr(expr, PY39), expr);
+        assert_eq!(
+            rewrite_type_expr("typing.List[builtins.int]", PY39),
+            "builtins.list[builtins.int]"
+        );
+        assert_eq!(
+            rewrite_type_expr(
+                "collections.abc.Callable[[builtins.int, builtins.str], None]",
+                PY38
+            ),
+            "typing.Callable[[builtins.int, builtins.str], None]"
+        );
+        assert_eq!(rewrite_type_expr("tuple[()]", PY38), "typing.Tuple[()]");
+    }
+
+    #[test]
+    fn test_typing_extensions() {
+        assert_eq!(
+            rewrite_type_expr("typing_extensions.TypeIs[A]", PY310),
+            "typing_extensions.TypeIs[A]"
+        );
+        assert_eq!(
+            rewrite_type_expr("typing_extensions.TypeIs[A]", PY313),
+            "typing.TypeIs[A]"
+        );
+        assert_eq!(
+            rewrite_type_expr("typing.Never", PY310),
+            "typing_extensions.Never"
+        );
+        assert_eq!(rewrite_type_expr("typing.Any", PY38), "typing.Any");
+        assert_eq!(typing_module("TypeAlias", Some(PY39)), "typing_extensions");
+        assert_eq!(typing_module("TypeAlias", None), "typing");
+    }
+
+    #[test]
+    fn test_imports() {
+        let mut type_info = TypeInfo {
+            name: "builtins.int | None".to_string(),
+            source_module: None,
+            import: ["builtins".into()].into(),
+            type_refs: Default::default(),
+            any_fallback: None,
+        };
+        apply_python_target(&mut type_info, PY39);
+        assert_eq!(type_info.name, "typing.Optional[builtins.int]");
+        assert!(type_info.import.contains(&"typing".into()));
+    }
+}
@@ -5,7 +5,7 @@
 //! `typing.Optional[sub_mod.ClassA]` when ClassA is from a different module.
 
 use crate::stub_type::{ImportKind, TypeIdentifierRef};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Token types in Python type expressions
 #[derive(Debug, Clone, PartialEq)]
@@ -236,6 +236,211 @@ pub(crate) fn tokenize(expr: &str) -> Vec<Token> {
     tokens
 }
 
+/// Append the source text of `token` to `result`
+fn push_token(result: &mut String, token: &Token) {
+    match token {
+        Token::Identifier(ident) => result.push_str(ident),
+        Token::DottedPath(parts) => result.push_str(&parts.join(".")),
+        Token::OpenBracket(ch) | Token::CloseBracket(ch) => result.push(*ch),
+        Token::Comma => result.push(','),
+        Token::Pipe => result.push('|'),
+        Token::Ellipsis => result.push_str("..."),
+        Token::StringLiteral(s) => {
+            result.push('"');
+            result.push_str(s);
+            result.push('"');
+        }
+        Token::Whitespace(ws) => result.push_str(ws),
+        Token::NumericLiteral(num) => result.push_str(num),
+    }
+}
+
+/// Replace references to `name` in a type expression with `replacement`.
+///
+/// Both the bare name `name` and the qualified path `module_component.name` are replaced,
+/// but not attribute names like `x().name`, keyword arguments like `f(name=1)`, or the contents of string literals.
+/// The replacement is parenthesized if it is a union subscripted or accessed like `name[int]`.
+/// Other parts of the expression are kept as written. Returns `None` if the expression does not reference `name`.
+pub(crate) fn substitute_identifier(
+    expr: &str,
+    module_component: &str,
+    name: &str,
+    replacement: &str,
+) -> Option<String> {
+    let mut substituted = false;
+    let mut result = String::new();
+    let mut rest = expr;
+    while let Some(ch) = rest.chars().next() {
+        let end = if ch == '"' || ch == '\'' {
+            string_literal_len(rest)
+        } else if ch.is_ascii_digit() {
+            rest.find(|c: char| !c.is_alphanumeric() && c != '.')
+                .unwrap_or(rest.len())
+        } else if ch.is_alphabetic() || ch == '_' {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '.')
+                .unwrap_or(rest.len());
+            let (path, following) = rest.split_at(end);
+            let following = following.trim_start();
+            let is_reference =
+                path == name || path.split_once('.') == Some((module_component, name));
+            let is_attribute = result.trim_end().ends_with('.');
+            let is_keyword = following.starts_with('=') && !following.starts_with("==");
+            if is_reference && !is_attribute && !is_keyword {
+                substituted = true;
+                if following.starts_with(['[', '(', '.']) && has_top_level_union(replacement) {
+                    result.push('(');
+                    result.push_str(replacement);
+                    result.push(')');
+                } else {
+                    result.push_str(replacement);
+                }
+                rest = &rest[end..];
+                continue;
+            }
+            end
+        } else {
+            ch.len_utf8()
+        };
+        result.push_str(&rest[..end]);
+        rest = &rest[end..];
+    }
+    substituted.then_some(result)
+}
+
+/// Length of the string literal at the start of `s` including its quotes, or of `s` if it is not closed
+fn string_literal_len(s: &str) -> usize {
+    let mut chars = s.char_indices();
+    let Some((_, quote)) = chars.next() else {
+        return 0;
+    };
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            return i + c.len_utf8();
+        }
+    }
+    s.len()
+}
+
+/// Whether a type expression is a union at the top level, e.g. `int | str` but not `list[int | str]`
+fn has_top_level_union(expr: &str) -> bool {
+    let mut depth = 0usize;
+    tokenize(expr).iter().any(|token| {
+        match token {
+            Token::OpenBracket(_) => depth += 1,
+            Token::CloseBracket(_) => depth = depth.saturating_sub(1),
+            Token::Pipe => return depth == 0,
+            _ => {}
+        }
+        false
+    })
+}
+
+/// Drop the `builtins.` prefix from names in a type expression, e.g. `builtins.list[builtins.int]` to `list[int]`.
+///
+/// Names in `shadowed` are kept qualified since the stub file binds them to something else.
+/// Returns `None` if nothing is rewritten.
+pub(crate) fn unqualify_builtins(expr: &str, shadowed: &HashSet<String>) -> Option<String> {
+    let mut rewritten = false;
+    let mut result = String::new();
+    for token in tokenize(expr) {
+        match token {
+            Token::DottedPath(parts)
+                if parts.len() == 2 && parts[0] == "builtins" && !shadowed.contains(&parts[1]) =>
+            {
+                rewritten = true;
+                result.push_str(&parts[1]);
+            }
+            token => push_token(&mut result, &token),
+        }
+    }
+    rewritten.then_some(result)
+}
+
+/// Whether a type expression refers to a name in `builtins` with the module name
+pub(crate) fn references_builtins(expr: &str) -> bool {
+    tokenize(expr).iter().any(
+        |token| matches!(token, Token::DottedPath(parts) if parts.first().is_some_and(|p| p == "builtins")),
+    )
+}
+
+/// Quote a whole type expression as a forward reference, e.g. `numpy.ndarray | None` to `"numpy.ndarray | None"`.
+///
+/// Quoted forward references inside are unquoted first, and single quotes are used
+/// if string literals like `Literal["a"]` remain. Returns `None` if they contain both quotes.
+pub(crate) fn quote_forward_ref(expr: &str) -> Option<String> {
+    let expr = unquote_forward_refs(expr).unwrap_or_else(|| expr.to_string());
+    if !expr.contains('"') {
+        Some(format!("\"{expr}\""))
+    } else if !expr.contains('\'') {
+        Some(format!("'{expr}'"))
+    } else {
+        None
+    }
+}
+
+/// Remove quotes from forward references in a type expression, e.g. `list["ClassA"]` to `list[ClassA]`.
+///
+/// String literals in `Literal[...]` and metadata of `Annotated[...]` are values rather than types, and are kept.
+/// Returns `None` if the expression contains no quoted forward reference.
+pub(crate) fn unquote_forward_refs(expr: &str) -> Option<String> {
+    /// What the string literals enclosed by a bracket stand for
+    #[derive(Clone, Copy)]
+    enum Context {
+        Type,
+        Value,
+        /// Arguments of `Annotated[...]`, where only the first one is a type
+        Annotated {
+            commas: usize,
+        },
+    }
+
+    let mut unquoted = false;
+    let mut result = String::new();
+    let mut stack: Vec<Context> = Vec::new();
+    let mut last_name: Option<String> = None;
+    for token in tokenize(expr) {
+        let current = stack.last().copied().unwrap_or(Context::Type);
+        match &token {
+            Token::OpenBracket(_) => {
+                let context = match (current, last_name.as_deref()) {
+                    (Context::Value, _) | (Context::Annotated { commas: 1.. }, _) => Context::Value,
+                    (_, Some("Literal")) => Context::Value,
+                    (_, Some("Annotated")) => Context::Annotated { commas: 0 },
+                    _ => Context::Type,
+                };
+                stack.push(context);
+            }
+            Token::CloseBracket(_) => {
+                stack.pop();
+            }
+            Token::Comma => {
+                if let Some(Context::Annotated { commas }) = stack.last_mut() {
+                    *commas += 1;
+                }
+            }
+            Token::StringLiteral(content)
+                if matches!(current, Context::Type | Context::Annotated { commas: 0 }) =>
+            {
+                unquoted = true;
+                result.push_str(content);
+                continue;
+            }
+            _ => {}
+        }
+        match &token {
+            Token::Identifier(ident) => last_name = Some(ident.clone()),
+            Token::DottedPath(parts) => last_name = parts.last().cloned(),
+            Token::Whitespace(_) => {}
+            _ => last_name = None,
+        }
+        push_token(&mut result, &token);
+    }
+    unquoted.then_some(result)
+}
+
 /// Type expression qualifier that rewrites identifiers based on module context.
 pub(crate) struct TypeExpressionQualifier;
 
@@ -372,6 +577,68 @@ impl TypeExpressionQualifier {
         result
     }
 
+    /// Qualify a value expression like the default value `sub_mod.Number.FLOAT` of a parameter
+    ///
+    /// Unlike [Self::qualify_expression], the expression is not a type expression and may contain
+    /// arbitrary literals, so only names in `type_refs` at the start of a dotted path are rewritten
+    /// and everything else, including string literals, is kept as written.
+    pub(crate) fn qualify_value_expression(
+        expr: &str,
+        type_refs: &HashMap<String, TypeIdentifierRef>,
+        target_module: &str,
+    ) -> String {
+        let mut result = String::new();
+        let mut chars = expr.chars().peekable();
+        // Previous character, to detect identifiers following `.` or inside other names
+        let mut prev = None;
+        while let Some(ch) = chars.next() {
+            if ch == '"' || ch == '\'' {
+                // Copy string literals verbatim
+                result.push(ch);
+                while let Some(c) = chars.next() {
+                    result.push(c);
+                    if c == '\\' {
+                        if let Some(escaped) = chars.next() {
+                            result.push(escaped);
+                        }
+                    } else if c == ch {
+                        break;
+                    }
+                }
+                prev = Some(ch);
+                continue;
+            }
+            let starts_name = (ch.is_alphabetic() || ch == '_')
+                && !prev.is_some_and(|p: char| p == '.' || p.is_alphanumeric() || p == '_');
+            if !starts_name {
+                result.push(ch);
+                prev = Some(ch);
+                continue;
+            }
+            let mut name = ch.to_string();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                name.push(c);
+                chars.next();
+            }
+            if let Some(type_ref) = type_refs.get(&name) {
+                if let (ImportKind::Module, Some(module_name)) =
+                    (&type_ref.import_kind, type_ref.module.get())
+                {
+                    if module_name != target_module {
+                        result.push_str(module_name.rsplit('.').next().unwrap_or(module_name));
+                        result.push('.');
+                    }
+                }
+            }
+            result.push_str(&name);
+            prev = name.chars().last();
+        }
+        result
+    }
+
     /// Check if an identifier is a known Python builtin or typing construct
     fn is_python_builtin(identifier: &str) -> bool {
         matches!(
@@ -397,6 +664,37 @@ mod tests {
     use super::*;
     use crate::stub_type::ModuleRef;
 
+    #[test]
+    fn test_qualify_value_expression() {
+        let type_refs = HashMap::from([(
+            "Number".to_string(),
+            TypeIdentifierRef {
+                module: ModuleRef::Named("pkg.sub".to_string()),
+                import_kind: ImportKind::Module,
+            },
+        )]);
+        let qualify =
+            |expr| TypeExpressionQualifier::qualify_value_expression(expr, &type_refs, "pkg");
+        assert_eq!(qualify("Number.FLOAT"), "sub.Number.FLOAT");
+        assert_eq!(
+            qualify("[Number.FLOAT, Number.INTEGER]"),
+            "[sub.Number.FLOAT, sub.Number.INTEGER]"
+        );
+        assert_eq!(
+            qualify("{'Number': Number.FLOAT}"),
+            "{'Number': sub.Number.FLOAT}"
+        );
+        assert_eq!(qualify("MyNumber.FLOAT"), "MyNumber.FLOAT");
+        assert_eq!(
+            TypeExpressionQualifier::qualify_value_expression(
+                "Number.FLOAT",
+                &type_refs,
+                "pkg.sub"
+            ),
+            "Number.FLOAT"
+        );
+    }
+
     #[test]
     fn test_tokenize_simple() {
         let tokens = tokenize("ClassA");
@@ -616,4 +914,139 @@ mod tests {
         );
         assert_eq!(result, "builtins.int");
     }
+
+    #[test]
+    fn test_unquote_forward_refs() {
+        assert_eq!(
+            unquote_forward_refs(r#""ClassA""#),
+            Some("ClassA".to_string())
+        );
+        assert_eq!(
+            unquote_forward_refs(r#"typing.Optional["sub_mod.ClassA"]"#),
+            Some("typing.Optional[sub_mod.ClassA]".to_string())
+        );
+        assert_eq!(
+            unquote_forward_refs(r#"dict[str, "Node"] | None"#),
+            Some("dict[str, Node] | None".to_string())
+        );
+        // Values in `Literal` and metadata in `Annotated` are kept
+        assert_eq!(unquote_forward_refs(r#"typing.Literal["a", "b"]"#), None);
+        assert_eq!(
+            unquote_forward_refs(r#"typing.Annotated["Node", "meta", ("x", "y")]"#),
+            Some(r#"typing.Annotated[Node, "meta", ("x", "y")]"#.to_string())
+        );
+        assert_eq!(
+            unquote_forward_refs(r#"tuple[typing.Literal["a"], "Node"]"#),
+            Some(r#"tuple[typing.Literal["a"], Node]"#.to_string())
+        );
+        assert_eq!(unquote_forward_refs("list[int]"), None);
+    }
+
+    #[test]
+    fn test_quote_forward_ref() {
+        assert_eq!(
+            quote_forward_ref("numpy.ndarray | None"),
+            Some(r#""numpy.ndarray | None""#.to_string())
+        );
+        assert_eq!(
+            quote_forward_ref(r#"dict[str, "Node"] | pandas.DataFrame"#),
+            Some(r#""dict[str, Node] | pandas.DataFrame""#.to_string())
+        );
+        assert_eq!(
+            quote_forward_ref(r#"typing.Literal["a"] | numpy.ndarray"#),
+            Some(r#"'typing.Literal["a"] | numpy.ndarray'"#.to_string())
+        );
+        assert_eq!(quote_forward_ref(r#"typing.Literal["a", 'b']"#), None);
+    }
+
+    #[test]
+    fn test_substitute_identifier() {
+        assert_eq!(
+            substitute_identifier(
+                "typing.Optional[SmallAlias]",
+                "_core",
+                "SmallAlias",
+                "builtins.int | builtins.str"
+            ),
+            Some("typing.Optional[builtins.int | builtins.str]".to_string())
+        );
+        assert_eq!(
+            substitute_identifier(
+                "_core.SmallAlias | None",
+                "_core",
+                "SmallAlias",
+                "int | str"
+            ),
+            Some("int | str | None".to_string())
+        );
+        // Same name in another module is not substituted
+        assert_eq!(
+            substitute_identifier("other.SmallAlias", "_core", "SmallAlias", "int | str"),
+            None
+        );
+        assert_eq!(
+            substitute_identifier("SmallAliasExt", "_core", "SmallAlias", "int | str"),
+            None
+        );
+        // String literals, attribute names and keyword arguments are kept as written
+        assert_eq!(
+            substitute_identifier(
+                "typing.Literal['SmallAlias', \"a\\\"b\"] | SmallAlias",
+                "_core",
+                "SmallAlias",
+                "int | str"
+            ),
+            Some("typing.Literal['SmallAlias', \"a\\\"b\"] | int | str".to_string())
+        );
+        assert_eq!(
+            substitute_identifier(
+                "typing.Annotated[int, Meta(SmallAlias=1).SmallAlias]",
+                "_core",
+                "SmallAlias",
+                "int | str"
+            ),
+            None
+        );
+        // Unions are parenthesized where the reference binds tighter than `|`
+        assert_eq!(
+            substitute_identifier("SmallAlias[int]", "_core", "SmallAlias", "list[T] | None"),
+            Some("(list[T] | None)[int]".to_string())
+        );
+        assert_eq!(
+            substitute_identifier("SmallAlias[int]", "_core", "SmallAlias", "list[T]"),
+            Some("list[T][int]".to_string())
+        );
+        assert_eq!(
+            substitute_identifier(
+                "dict[str, SmallAlias]",
+                "_core",
+                "SmallAlias",
+                "typing.Callable[[int | str], None]"
+            ),
+            Some("dict[str, typing.Callable[[int | str], None]]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unqualify_builtins() {
+        let shadowed = HashSet::from(["int".to_string()]);
+        assert_eq!(
+            unqualify_builtins(
+                "builtins.dict[builtins.str, builtins.int | None]",
+                &HashSet::new()
+            ),
+            Some("dict[str, int | None]".to_string())
+        );
+        assert_eq!(
+            unqualify_builtins("builtins.list[builtins.int]", &shadowed),
+            Some("list[builtins.int]".to_string())
+        );
+        assert_eq!(unqualify_builtins("builtins.int", &shadowed), None);
+        assert_eq!(
+            unqualify_builtins("typing.Literal[\"builtins.str\"]", &HashSet::new()),
+            None
+        );
+        assert!(references_builtins("list[builtins.int]"));
+        assert!(!references_builtins("list[int]"));
+    }
 }
@@ -1,16 +1,155 @@
 use crate::{
-    generate::{docstring::normalize_docstring, *},
-    pyproject::{PyProject, StubGenConfig},
+    error::{DuplicateClass, StubGenError, UnresolvedType},
+    generate::{
+        docstring::normalize_docstring,
+        numpy_array::apply_array_style,
+        python_target::{apply_python_target, typing_module},
+        qualifier::{
+            quote_forward_ref, references_builtins, tokenize, unqualify_builtins,
+            unquote_forward_refs, Token,
+        },
+        *,
+    },
+    pyproject::{PyProject, PythonVersion, Strict, StubGenConfig, StubLayout},
+    stub_type::{ImportRef, ModuleRef, TypeRef},
     type_info::*,
+    TypeInfo,
 };
 use anyhow::{Context, Result};
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs,
     path::*,
 };
 
+/// Files written by [StubInfo::generate]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GenerateResult {
+    /// Files created or updated
+    pub written: Vec<PathBuf>,
+    /// Files skipped since their content is the same as the generated one
+    pub unchanged: Vec<PathBuf>,
+}
+
+impl GenerateResult {
+    fn record(&mut self, path: PathBuf, written: bool) {
+        if written {
+            self.written.push(path);
+        } else {
+            log::debug!("Skip unchanged file {}", path.display());
+            self.unchanged.push(path);
+        }
+    }
+}
+
+/// Write `content` to `dest` unless the file already has exactly the same content,
+/// so that the modification time is kept for file watchers and build tools.
+///
+/// Returns whether the file is written.
+fn write_if_changed(dest: &Path, content: &str) -> Result<bool> {
+    if fs::read(dest).is_ok_and(|existing| existing == content.as_bytes()) {
+        return Ok(false);
+    }
+    fs::write(dest, content)?;
+    Ok(true)
+}
+
+/// Remove the stub file of the other `stub-layout` left from a previous generation,
+/// since a package `sub/__init__.pyi` takes precedence over `sub.pyi` for type checkers.
+///
+/// The package directory is also removed if nothing else is left in it.
+fn remove_stale_layout(dest: &Path) -> Result<()> {
+    let (stale, package_dir) = if dest.file_name().is_some_and(|name| name == "__init__.pyi") {
+        let dir = dest.parent().context("Cannot get parent directory")?;
+        (dir.with_extension("pyi"), None)
+    } else {
+        let dir = dest.with_extension("");
+        (dir.join("__init__.pyi"), Some(dir))
+    };
+    if !stale.is_file() {
+        return Ok(());
+    }
+    log::info!(
+        "Remove stub file {} of another stub-layout",
+        stale.display()
+    );
+    fs::remove_file(&stale)?;
+    if let Some(dir) = package_dir {
+        if fs::read_dir(&dir)?.next().is_none() {
+            fs::remove_dir(&dir)?;
+        }
+    }
+    Ok(())
+}
+
+/// Merge the `generated` stub with hand-edits of the `existing` stub file for `merge`
+#[cfg(feature = "rustpython-parser")]
+fn merge_existing(existing: &str, generated: &str) -> Result<String> {
+    super::merge::merge_stub(existing, generated)
+}
+
+/// Merge the `generated` stub with hand-edits of the `existing` stub file for `merge`
+#[cfg(not(feature = "rustpython-parser"))]
+fn merge_existing(_existing: &str, _generated: &str) -> Result<String> {
+    anyhow::bail!(
+        "`merge` in [tool.pyo3-stub-gen] requires the `rustpython-parser` feature of pyo3-stub-gen"
+    )
+}
+
+/// Run the user-configured `format-command` on the files
+fn run_format_command(command: &[String], files: &[PathBuf]) -> Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        anyhow::bail!("`format-command` in [tool.pyo3-stub-gen] must not be empty");
+    };
+    if files.is_empty() {
+        return Ok(());
+    }
+    let output = std::process::Command::new(program)
+        .args(args)
+        .args(files)
+        .output()
+        .with_context(|| format!("Failed to run format command `{}`", command.join(" ")))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Format command `{}` failed with {}:\n{}",
+            command.join(" "),
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    log::debug!("Formatted {files:?} with `{}`", command.join(" "));
+    Ok(())
+}
+
+/// Format `content` by the user-configured `format-command` without writing it to `dest`.
+///
+/// The content is written to a temporary file next to `dest`, if its directory exists,
+/// so that the formatter finds the same configuration as for `dest`.
+fn format_content(command: &[String], dest: &Path, content: &str) -> Result<String> {
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let file_name = dest
+        .file_name()
+        .context("Cannot get file name")?
+        .to_string_lossy();
+    let dir = dest
+        .parent()
+        .filter(|dir| dir.is_dir())
+        .map_or_else(std::env::temp_dir, Path::to_path_buf);
+    let count = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let path = dir.join(format!(
+        ".pyo3-stub-gen-{}-{count}-{file_name}",
+        std::process::id()
+    ));
+    fs::write(&path, content)?;
+    let formatted = run_format_command(command, std::slice::from_ref(&path))
+        .and_then(|()| Ok(fs::read_to_string(&path)?));
+    fs::remove_file(&path)?;
+    formatted
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct StubInfo {
     pub modules: BTreeMap<String, Module>,
     pub python_root: PathBuf,
@@ -25,12 +164,38 @@ pub struct StubInfo {
     /// The project name (from `project.name` in pyproject.toml)
     /// Used for documentation generation as the package display name
     pub project_name: String,
+    /// Python items of Rust paths to rewrite intra-doc links in docstrings
+    pub doc_links: DocLinks,
 }
 
 impl StubInfo {
-    /// Initialize [StubInfo] from a `pyproject.toml` file in `CARGO_MANIFEST_DIR`.
+    /// Gathered modules, sorted by name
+    pub fn modules(&self) -> impl Iterator<Item = &Module> {
+        self.modules.values()
+    }
+
+    /// Module of the full name `name`, e.g. `pkg._core`
+    pub fn module(&self, name: &str) -> Option<&Module> {
+        self.modules.get(name)
+    }
+
+    /// Dependency graph of the modules by the types and modules their stub files import,
+    /// e.g. to order the modules or to find circular imports
+    pub fn module_graph(&self) -> ModuleGraph {
+        ModuleGraph::new(&self.modules)
+    }
+
+    /// Initialize [StubInfo] from a `pyproject.toml` file, usually in `CARGO_MANIFEST_DIR` or the workspace root.
     /// This is automatically set up by the [crate::define_stub_info_gatherer] macro.
-    pub fn from_pyproject_toml(path: impl AsRef<Path>) -> Result<Self> {
+    pub fn from_pyproject_toml(path: impl AsRef<Path>) -> crate::Result<Self> {
+        Self::from_pyproject_toml_with(path, RuntimeItems::default())
+    }
+
+    /// [StubInfo::from_pyproject_toml] also gathering classes and functions registered at runtime by `items`
+    pub fn from_pyproject_toml_with(
+        path: impl AsRef<Path>,
+        items: RuntimeItems,
+    ) -> crate::Result<Self> {
         let path = path.as_ref();
         let pyproject = PyProject::parse_toml(path)?;
         let mut config = pyproject.stub_gen_config();
@@ -42,7 +207,9 @@ impl StubInfo {
 
         let pyproject_dir = path.parent().map(|p| p.to_path_buf());
 
-        let mut stub_info = StubInfoBuilder::from_pyproject_toml(pyproject, config).build()?;
+        let mut stub_info = StubInfoBuilder::from_pyproject_toml(pyproject, config)
+            .with_runtime_items(items)
+            .build()?;
         stub_info.pyproject_dir = pyproject_dir;
         Ok(stub_info)
     }
@@ -55,17 +222,25 @@ impl StubInfo {
         project_root: PathBuf,
         is_mixed_layout: bool,
         config: StubGenConfig,
-    ) -> Result<Self> {
-        StubInfoBuilder::from_project_root(
+    ) -> crate::Result<Self> {
+        Ok(StubInfoBuilder::from_project_root(
             default_module_name,
             project_root,
             is_mixed_layout,
             config,
         )
-        .build()
+        .build()?)
+    }
+
+    /// Write stub files, `__init__.py` files and documentation.
+    ///
+    /// Files whose content is unchanged are not rewritten to keep their modification time,
+    /// and are listed in [GenerateResult::unchanged].
+    pub fn generate(&self) -> crate::Result<GenerateResult> {
+        Ok(self.generate_files()?)
     }
 
-    pub fn generate(&self) -> Result<()> {
+    fn generate_files(&self) -> Result<GenerateResult> {
         // Validate: Pure Rust layout can only have a single module
         if !self.is_mixed_layout && self.modules.len() > 1 {
             let module_names: Vec<_> = self.modules.keys().collect();
@@ -77,36 +252,34 @@ impl StubInfo {
                 module_names.iter().map(|s| format!("'{}'", s)).collect::<Vec<_>>().join(", ")
             );
         }
+        if !self.is_mixed_layout && self.config.split_classes {
+            anyhow::bail!(
+                "`split-classes` requires mixed Python/Rust layout since split stub files are placed in the module directory. \
+                 Please add `python-source` to [tool.maturin] in pyproject.toml, or disable `split-classes`."
+            );
+        }
 
+        let mut stubs = self.render_stubs();
+        let mut result = GenerateResult::default();
         for (name, module) in self.modules.iter() {
             // Skip empty modules (nothing to generate)
             if module.is_empty() {
                 continue;
             }
 
-            // Convert dashes to underscores for Python compatibility
-            let normalized_name = name.replace("-", "_");
-            let path = normalized_name.replace(".", "/");
-
             if self.is_pyo3_generated(name) {
                 // PyO3 module: generate .pyi stub file
-                let dest = if self.is_mixed_layout {
-                    self.python_root.join(&path).join("__init__.pyi")
-                } else {
-                    // Pure Rust: use single file at root
-                    let package_name = normalized_name
-                        .split('.')
-                        .next()
-                        .filter(|s| !s.is_empty())
-                        .ok_or_else(|| {
-                            anyhow::anyhow!(
-                                "Module name is empty after normalization: original name was `{name}`"
-                            )
-                        })?;
-                    self.python_root.join(format!("{package_name}.pyi"))
-                };
-
-                self.write_stub_file(&dest, module)?;
+                let dest = self.stub_path(name, module)?;
+                let content = stubs.remove(name.as_str()).unwrap_or_else(|| {
+                    self.doc_links
+                        .rewrite_stub(module.format_with_config(&self.config))
+                });
+                remove_stale_layout(&dest)?;
+                let written = self.write_stub_file(&dest, module, &content)?;
+                if self.config.split_classes {
+                    self.write_class_stub_files(&dest, module, &mut result)?;
+                }
+                result.record(dest, written);
             } else {
                 // Pure Python module: needs __init__.py generation
                 if !module.is_init_py_compatible() {
@@ -132,18 +305,23 @@ impl StubInfo {
                 }
 
                 // Generate __init__.py only (no .pyi - types resolve through re-exports)
-                let dir = self.python_root.join(&path);
+                let dir = self
+                    .python_root
+                    .join(name.replace('-', "_").replace('.', "/"));
                 if !dir.exists() {
                     fs::create_dir_all(&dir)?;
                 }
 
                 let init_py_dest = dir.join("__init__.py");
-                let init_py_content = module.format_init_py();
-                fs::write(&init_py_dest, init_py_content)?;
-                log::info!(
-                    "Generate __init__.py for module `{name}` at {dest}",
-                    dest = init_py_dest.display()
-                );
+                let init_py_content = module.format_init_py_with_config(&self.config);
+                let written = self.write_formatted(&init_py_dest, &init_py_content)?;
+                if written {
+                    log::info!(
+                        "Generate __init__.py for module `{name}` at {dest}",
+                        dest = init_py_dest.display()
+                    );
+                }
+                result.record(init_py_dest, written);
             }
         }
 
@@ -152,22 +330,279 @@ impl StubInfo {
             self.generate_docs(doc_config)?;
         }
 
+        Ok(result)
+    }
+
+    /// Compare modules with the module tree built at runtime by `add_submodule`.
+    ///
+    /// `module` is the root module imported or built at runtime, e.g. `py.import("pkg._core")?`.
+    /// Modules found at runtime but missing in stubs are created when `create_missing` is set,
+    /// so that they appear in `from . import ...` and `__all__` of their parent.
+    /// Other mismatches, i.e. stub modules or items not present at runtime, are reported as an error.
+    pub fn reconcile_runtime_modules(
+        &mut self,
+        module: &pyo3::Bound<pyo3::types::PyModule>,
+        create_missing: bool,
+    ) -> crate::Result<()> {
+        let tree = crate::runtime::module_tree(module, &self.default_module_name)?;
+        Ok(self.reconcile_module_tree(&tree, create_missing)?)
+    }
+
+    fn reconcile_module_tree(
+        &mut self,
+        tree: &BTreeMap<String, BTreeSet<String>>,
+        create_missing: bool,
+    ) -> Result<()> {
+        let mut mismatches = Vec::new();
+        // Parents come before their children since `tree` is sorted
+        for name in tree.keys() {
+            if self.modules.contains_key(name) {
+                continue;
+            }
+            if !create_missing {
+                mismatches.push(format!("Module `{name}` exists at runtime but has no stub"));
+                continue;
+            }
+            log::info!("Create stub of module `{name}` found at runtime");
+            self.modules.insert(
+                name.clone(),
+                Module {
+                    name: name.clone(),
+                    default_module_name: self.default_module_name.clone(),
+                    ..Default::default()
+                },
+            );
+            if let Some((parent, child)) = name.rsplit_once('.') {
+                if let Some(parent) = self.modules.get_mut(parent) {
+                    parent.submodules.insert(child.to_string());
+                }
+            }
+        }
+
+        for (name, module) in &self.modules {
+            if !self.is_pyo3_generated(name) {
+                continue;
+            }
+            let Some(attrs) = tree.get(name) else {
+                mismatches.push(format!(
+                    "Module `{name}` has a stub but is not added by `add_submodule` at runtime"
+                ));
+                continue;
+            };
+            let items = module
+                .class
+                .values()
+                .map(|class| class.name)
+                .chain(module.enum_.values().map(|enum_| enum_.name))
+                .chain(module.function.keys().copied())
+                .chain(module.submodules.iter().map(String::as_str));
+            for item in items {
+                if !attrs.contains(item) {
+                    mismatches.push(format!(
+                        "`{item}` is in the stub of `{name}` but not in the module at runtime"
+                    ));
+                }
+            }
+        }
+
+        if !mismatches.is_empty() {
+            anyhow::bail!(
+                "Modules differ from the runtime module tree:\n  {}",
+                mismatches.join("\n  ")
+            );
+        }
         Ok(())
     }
 
-    fn write_stub_file(&self, dest: &std::path::Path, module: &module::Module) -> Result<()> {
+    /// Path of the stub file of the PyO3 module `name`, following `stub-layout`.
+    ///
+    /// Modules are placed in package directories like `pkg/sub/__init__.pyi` in mixed layout,
+    /// and the only module is written to `pkg.pyi` in pure Rust layout, unless specified otherwise.
+    fn stub_path(&self, name: &str, module: &module::Module) -> Result<PathBuf> {
+        // Convert dashes to underscores for Python compatibility
+        let normalized_name = name.replace('-', "_");
+        let path = if self.is_mixed_layout {
+            normalized_name.replace('.', "/")
+        } else {
+            // Pure Rust: the module is placed at the root, named by the package
+            normalized_name
+                .split('.')
+                .next()
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Module name is empty after normalization: original name was `{name}`"
+                    )
+                })?
+                .to_string()
+        };
+        let default = if self.is_mixed_layout {
+            StubLayout::Package
+        } else {
+            StubLayout::Flat
+        };
+        let layout = self
+            .config
+            .stub_layout
+            .as_ref()
+            .and_then(|config| config.layout_for(name))
+            .unwrap_or(default);
+        match layout {
+            StubLayout::Package => Ok(self.python_root.join(path).join("__init__.pyi")),
+            StubLayout::Flat => {
+                if self.is_mixed_layout && !module.submodules.is_empty() {
+                    anyhow::bail!(
+                        "Module `{name}` has submodules, so its stub cannot be a single file. \
+                         Use `stub-layout = \"package\"` for this module."
+                    );
+                }
+                if self.is_mixed_layout && self.config.split_classes {
+                    anyhow::bail!(
+                        "`split-classes` places stub files in the module directory, \
+                         so the stub of module `{name}` cannot be a single file by `stub-layout = \"flat\"`."
+                    );
+                }
+                Ok(self.python_root.join(format!("{path}.pyi")))
+            }
+        }
+    }
+
+    /// Render the stub of the PyO3 module `name` as [StubInfo::generate] writes it,
+    /// i.e. merged with the existing stub file for `merge` and formatted by `format-command`
+    pub(crate) fn render_stub_as_written(
+        &self,
+        name: &str,
+        module: &module::Module,
+    ) -> Result<String> {
+        let content = self.format_stub(module);
+        if !self.config.merge && self.config.format_command.is_none() {
+            return Ok(content);
+        }
+        let dest = self.stub_path(name, module)?;
+        let content = match fs::read_to_string(&dest) {
+            Ok(existing) if self.config.merge => merge_existing(&existing, &content)
+                .with_context(|| format!("Failed to merge stub file {}", dest.display()))?,
+            _ => content,
+        };
+        match &self.config.format_command {
+            Some(command) => format_content(command, &dest, &content),
+            None => Ok(content),
+        }
+    }
+
+    /// Stub of `module` with intra-doc links rewritten
+    fn format_stub(&self, module: &module::Module) -> String {
+        self.doc_links
+            .rewrite_stub(module.format_with_config(&self.config))
+    }
+
+    /// Non-empty PyO3 modules to render stubs of
+    fn stub_modules(&self) -> Vec<(&str, &module::Module)> {
+        self.modules
+            .iter()
+            .filter(|(name, module)| !module.is_empty() && self.is_pyo3_generated(name))
+            .map(|(name, module)| (name.as_str(), module))
+            .collect()
+    }
+
+    /// Render the stubs of the non-empty PyO3 modules, in parallel with the `rayon` feature
+    #[cfg(feature = "rayon")]
+    fn render_stubs(&self) -> BTreeMap<&str, String> {
+        use rayon::prelude::*;
+        self.stub_modules()
+            .into_par_iter()
+            .map(|(name, module)| (name, self.format_stub(module)))
+            .collect()
+    }
+
+    /// Render the stubs of the non-empty PyO3 modules one by one
+    #[cfg(not(feature = "rayon"))]
+    fn render_stubs(&self) -> BTreeMap<&str, String> {
+        self.render_stubs_sequentially()
+    }
+
+    #[cfg(any(test, not(feature = "rayon")))]
+    fn render_stubs_sequentially(&self) -> BTreeMap<&str, String> {
+        self.stub_modules()
+            .into_iter()
+            .map(|(name, module)| (name, self.format_stub(module)))
+            .collect()
+    }
+
+    /// Write `content` formatted by `format-command` to `dest`,
+    /// unless the file already has the same formatted content
+    fn write_formatted(&self, dest: &Path, content: &str) -> Result<bool> {
+        match &self.config.format_command {
+            Some(command) => write_if_changed(dest, &format_content(command, dest, content)?),
+            None => write_if_changed(dest, content),
+        }
+    }
+
+    /// Write the stub file of `module`, returning whether it is written
+    fn write_stub_file(
+        &self,
+        dest: &std::path::Path,
+        module: &module::Module,
+        content: &str,
+    ) -> Result<bool> {
         let dir = dest.parent().context("Cannot get parent directory")?;
         if !dir.exists() {
             fs::create_dir_all(dir)?;
         }
 
-        let content = module.format_with_config(self.config.use_type_statement);
-        fs::write(dest, content)?;
-        log::info!(
-            "Generate stub file of a module `{}` at {dest}",
-            module.name,
-            dest = dest.display()
-        );
+        let content = match fs::read_to_string(dest) {
+            Ok(existing) if self.config.merge => merge_existing(&existing, content)
+                .with_context(|| format!("Failed to merge stub file {}", dest.display()))?,
+            _ => content.to_string(),
+        };
+        let written = self.write_formatted(dest, &content)?;
+        if written {
+            log::info!(
+                "Generate stub file of a module `{}` at {dest}",
+                module.name,
+                dest = dest.display()
+            );
+        }
+        Ok(written)
+    }
+
+    /// Write stub files of classes and enums next to `init_dest` for `split-classes`,
+    /// removing the ones left from classes which no longer exist
+    fn write_class_stub_files(
+        &self,
+        init_dest: &Path,
+        module: &module::Module,
+        result: &mut GenerateResult,
+    ) -> Result<()> {
+        let dir = init_dest.parent().context("Cannot get parent directory")?;
+        let mut class_stubs = Vec::new();
+        for (stem, content) in module.format_class_stubs_with_config(&self.config) {
+            let content = self.doc_links.rewrite_stub(content);
+            let dest = dir.join(format!("{stem}.pyi"));
+            let written = self.write_formatted(&dest, &content)?;
+            if written {
+                log::info!(
+                    "Generate stub file of a class `{stem}` in module `{}` at {dest}",
+                    module.name,
+                    dest = dest.display()
+                );
+            }
+            class_stubs.push(dest.clone());
+            result.record(dest, written);
+        }
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_class_stub =
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(module::CLASS_STUB_PREFIX) && name.ends_with(".pyi")
+                    });
+            if is_class_stub && !class_stubs.contains(&path) {
+                log::info!("Remove stale stub file {}", path.display());
+                fs::remove_file(&path)?;
+            }
+        }
         Ok(())
     }
 
@@ -197,6 +632,29 @@ impl StubInfo {
         // 1. Build DocPackage IR
         let doc_package = crate::docgen::builder::DocPackageBuilder::new(self).build()?;
 
+        if let Some(manifest_output) = &config.manifest_output {
+            let manifest = crate::docgen::export::json::render_manifest(&doc_package)?;
+            fs::create_dir_all(&config.output_dir)?;
+            fs::write(config.output_dir.join(manifest_output), manifest)?;
+        }
+
+        if config.format == crate::docgen::DocFormat::Markdown {
+            fs::create_dir_all(&config.output_dir)?;
+            crate::docgen::render::markdown::generate_module_pages(
+                &doc_package,
+                &config.output_dir,
+            )?;
+            if config.generate_index {
+                crate::docgen::render::markdown::generate_index_md(
+                    &doc_package,
+                    &config.output_dir,
+                    config,
+                )?;
+            }
+            log::info!("Generated Markdown API docs at {:?}", config.output_dir);
+            return Ok(());
+        }
+
         // 2. Render to JSON
         let json_output = crate::docgen::render::render_to_json(&doc_package)?;
 
@@ -229,13 +687,54 @@ impl StubInfo {
     }
 }
 
+/// Fill placeholders of `module-doc-template` for the module of the full name `module`
+fn format_module_doc(template: &str, module: &str, project: &str, version: &str) -> Result<String> {
+    let mut doc = String::new();
+    let mut rest = template;
+    while let Some(pos) = rest.find(['{', '}']) {
+        doc.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if let Some(after) = rest.strip_prefix("{{") {
+            doc.push('{');
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("}}") {
+            doc.push('}');
+            rest = after;
+            continue;
+        }
+        let Some(end) = rest.find('}').filter(|_| rest.starts_with('{')) else {
+            anyhow::bail!("Unmatched brace in `module-doc-template`: {template}");
+        };
+        let value = match &rest[1..end] {
+            "module" => module,
+            "name" => module.rsplit('.').next().unwrap_or(module),
+            "project" => project,
+            "version" => version,
+            placeholder => anyhow::bail!(
+                "Unknown placeholder `{{{placeholder}}}` in `module-doc-template`, \
+                 expected `{{module}}`, `{{name}}`, `{{project}}` or `{{version}}`"
+            ),
+        };
+        doc.push_str(value);
+        rest = &rest[end + 1..];
+    }
+    doc.push_str(rest);
+    Ok(doc)
+}
+
 struct StubInfoBuilder {
     modules: BTreeMap<String, Module>,
     default_module_name: String,
     project_name: String,
+    /// Static version in pyproject.toml
+    project_version: Option<String>,
     python_root: PathBuf,
     is_mixed_layout: bool,
     config: StubGenConfig,
+    /// Classes and functions registered at runtime, gathered with the ones submitted to [inventory]
+    runtime_items: RuntimeItems,
 }
 
 impl StubInfoBuilder {
@@ -249,12 +748,19 @@ impl StubInfoBuilder {
             modules: BTreeMap::new(),
             default_module_name: pyproject.module_name().to_string(),
             project_name: pyproject.project.name.clone(),
+            project_version: pyproject.project.version.clone(),
             python_root,
             is_mixed_layout,
             config,
+            runtime_items: RuntimeItems::default(),
         }
     }
 
+    fn with_runtime_items(mut self, items: RuntimeItems) -> Self {
+        self.runtime_items = items;
+        self
+    }
+
     fn from_project_root(
         default_module_name: String,
         project_root: PathBuf,
@@ -272,9 +778,11 @@ impl StubInfoBuilder {
             modules: BTreeMap::new(),
             default_module_name,
             project_name,
+            project_version: None,
             python_root: project_root,
             is_mixed_layout,
             config,
+            runtime_items: RuntimeItems::default(),
         }
     }
 
@@ -349,7 +857,23 @@ impl StubInfoBuilder {
         class_def.resolve_default_modules(&self.default_module_name);
         self.get_module(info.module)
             .class
-            .insert((info.struct_id)(), class_def);
+            .insert((info.struct_id)().into(), class_def);
+    }
+
+    /// Add a class registered by [PyClassInfoBuilder], keyed by its Rust type if given,
+    /// otherwise by its module and name
+    fn add_runtime_class(&mut self, class: PyClassInfoBuilder) {
+        let (rust_type, mut class_def) = class.build();
+        class_def.resolve_default_modules(&self.default_module_name);
+        let module = class_def.module;
+        let id = match rust_type {
+            Some(id) => ClassId::Rust(id),
+            None => ClassId::Runtime {
+                module: module.unwrap_or(&self.default_module_name).to_string(),
+                name: class_def.name.to_string(),
+            },
+        };
+        self.get_module(module).class.insert(id, class_def);
     }
 
     fn add_complex_enum(&mut self, info: &PyComplexEnumInfo) {
@@ -357,7 +881,7 @@ impl StubInfoBuilder {
         class_def.resolve_default_modules(&self.default_module_name);
         self.get_module(info.module)
             .class
-            .insert((info.enum_id)(), class_def);
+            .insert((info.enum_id)().into(), class_def);
     }
 
     fn add_enum(&mut self, info: &PyEnumInfo) {
@@ -366,27 +890,184 @@ impl StubInfoBuilder {
             .insert((info.enum_id)(), EnumDef::from(info));
     }
 
+    /// Fail if classes and enums of different Rust types share a Python name in a module,
+    /// except for ones in `allow-shadowing`, listing all of them with their source locations
+    fn check_duplicate_classes(&self) -> Result<()> {
+        let mut duplicates = Vec::new();
+        for (module_name, module) in &self.modules {
+            let mut definitions: BTreeMap<&str, Vec<(&str, u32)>> = BTreeMap::new();
+            for class in module.class.values() {
+                definitions
+                    .entry(class.name)
+                    .or_default()
+                    .push((class.file, class.line));
+            }
+            for enum_ in module.enum_.values() {
+                definitions
+                    .entry(enum_.name)
+                    .or_default()
+                    .push((enum_.file, enum_.line));
+            }
+            for (name, mut locations) in definitions {
+                let fqn = format!("{module_name}.{name}");
+                if locations.len() < 2 || self.config.allow_shadowing.contains(&fqn) {
+                    continue;
+                }
+                locations.sort();
+                duplicates.push(DuplicateClass {
+                    name: fqn,
+                    locations,
+                });
+            }
+        }
+        if !duplicates.is_empty() {
+            return Err(StubGenError::DuplicateClasses(duplicates).into());
+        }
+        Ok(())
+    }
+
+    /// Replace base classes registered by [crate::external_class!] by the ones imported from their public module.
+    /// Base classes are matched by their name and the module they refer to,
+    /// and kept if a class of the name is defined in that module.
+    fn resolve_external_bases(&mut self, external_classes: &[&ExternalClassInfo]) {
+        if external_classes.is_empty() {
+            return;
+        }
+        let defined: HashSet<(String, &str)> = self
+            .modules
+            .iter()
+            .flat_map(|(module_name, module)| {
+                let classes = module.class.values().map(|class| class.name);
+                let enums = module.enum_.values().map(|enum_| enum_.name);
+                classes
+                    .chain(enums)
+                    .map(move |name| (module_name.clone(), name))
+            })
+            .collect();
+        let is_defined = |base: &TypeInfo, name: &str| match base.source_module.as_ref() {
+            Some(module) => module
+                .get()
+                .is_some_and(|module| defined.contains(&(module.to_string(), name))),
+            None => defined.iter().any(|(_, defined)| *defined == name),
+        };
+
+        let mut replaced = Vec::new();
+        for module in self.modules.values() {
+            for (id, class) in &module.class {
+                for (index, base) in class.bases.iter().enumerate() {
+                    let name = base.name.rsplit('.').next().unwrap_or(&base.name);
+                    let base_module = base.source_module.as_ref().and_then(ModuleRef::get);
+                    let Some(external) = external_classes.iter().find(|external| {
+                        external.name == name && external.defined_in == base_module
+                    }) else {
+                        continue;
+                    };
+                    if !is_defined(base, name) {
+                        replaced.push((id.clone(), index, *external));
+                    }
+                }
+            }
+        }
+        for (id, index, external) in replaced {
+            let Some(class) = self
+                .modules
+                .values_mut()
+                .find_map(|module| module.class.get_mut(&id))
+            else {
+                continue;
+            };
+            class.bases[index] = TypeInfo {
+                name: external.name.to_string(),
+                source_module: None,
+                import: HashSet::from([ImportRef::Type(TypeRef::new(
+                    external.module.into(),
+                    external.name.to_string(),
+                ))]),
+                type_refs: HashMap::new(),
+                any_fallback: None,
+            };
+        }
+    }
+
+    /// Log names of properties derived from `#[getter]`, `#[setter]` and `#[pyo3(get, set)]`,
+    /// to check how `#[pyo3(name = "...")]` and `rename_all` are resolved
+    fn log_property_names(&self) {
+        for module in self.modules.values() {
+            for class in module.class.values() {
+                if class.getter_setters.is_empty() {
+                    continue;
+                }
+                let properties: Vec<String> = class
+                    .getter_setters
+                    .iter()
+                    .map(|(name, accessors)| match accessors {
+                        (Some(_), Some(_)) => format!("{name} (get, set)"),
+                        (Some(_), None) => format!("{name} (get)"),
+                        (None, _) => format!("{name} (set)"),
+                    })
+                    .collect();
+                log::debug!(
+                    "Properties of `{}.{}`: {}",
+                    module.name,
+                    class.name,
+                    properties.join(", ")
+                );
+            }
+        }
+    }
+
+    /// Write builtin types as bare `int` for `qualify-builtins = false`,
+    /// keeping the ones shadowed by names defined in the module qualified
+    fn unqualify_builtins(&mut self) {
+        for module in self.modules.values_mut() {
+            let shadowed = module.defined_names();
+            module.for_each_type_mut(&mut |type_info| {
+                let Some(name) = unqualify_builtins(&type_info.name, &shadowed) else {
+                    return;
+                };
+                if !references_builtins(&name) {
+                    type_info.import.remove(&ImportRef::from("builtins"));
+                }
+                type_info.name = name;
+            });
+        }
+    }
+
     fn add_function(&mut self, info: &PyFunctionInfo) -> Result<()> {
-        // Clone default_module_name to avoid borrow checker issues
-        let default_module_name = self.default_module_name.clone();
+        // Classes are registered before functions, so exceptions are resolvable here
+        let raises = info
+            .raises
+            .iter()
+            .map(|raises| self.raised_exception(raises))
+            .collect();
 
+        let mut new_func = FunctionDef::from(info);
+        new_func.raises = raises;
+        self.insert_function(info.module, new_func)
+    }
+
+    /// Add a function registered by [PyFunctionInfoBuilder]
+    fn add_runtime_function(&mut self, function: PyFunctionInfoBuilder) -> Result<()> {
+        let (module, new_func) = function.build();
+        self.insert_function(module.as_deref(), new_func)
+    }
+
+    fn insert_function(&mut self, module: Option<&str>, mut new_func: FunctionDef) -> Result<()> {
+        new_func.resolve_default_modules(&self.default_module_name);
         let target = self
-            .get_module(info.module)
+            .get_module(module)
             .function
-            .entry(info.name)
+            .entry(new_func.name)
             .or_default();
 
         // Validation: Check for multiple non-overload functions
-        let mut new_func = FunctionDef::from(info);
-        new_func.resolve_default_modules(&default_module_name);
-
         if !new_func.is_overload {
             let non_overload_count = target.iter().filter(|f| !f.is_overload).count();
             if non_overload_count > 0 {
                 anyhow::bail!(
                     "Multiple functions with name '{}' found without @overload decorator. \
                      Please add @overload decorator to all variants.",
-                    info.name
+                    new_func.name
                 );
             }
         }
@@ -395,62 +1076,780 @@ impl StubInfoBuilder {
         Ok(())
     }
 
+    /// Exception class listed by `#[gen_stub(raises(...))]`
+    fn raised_exception(&self, raises: &RaisesInfo) -> TypeInfo {
+        match raises {
+            RaisesInfo::Builtin(name) => TypeInfo::builtin(name),
+            RaisesInfo::Type { id, name } => {
+                let exception = crate::exception::exception_of(id());
+                self.modules
+                    .iter()
+                    .find_map(|(module_name, module)| {
+                        let class = module.class.get(&ClassId::Rust(exception))?;
+                        Some(TypeInfo::locally_defined(
+                            class.name,
+                            module_name.as_str().into(),
+                        ))
+                    })
+                    .unwrap_or_else(|| {
+                        log::warn!(
+                            "`{name}` in `raises(...)` is neither an exception class nor registered by `map_error_to_exception!`"
+                        );
+                        TypeInfo::unqualified(name.rsplit("::").next().unwrap_or(name))
+                    })
+            }
+        }
+    }
+
     fn add_variable(&mut self, info: &PyVariableInfo) {
         self.get_module(Some(info.module))
             .variables
             .insert(info.name, VariableDef::from(info));
     }
 
-    fn add_type_alias(&mut self, info: &TypeAliasInfo) {
-        self.get_module(Some(info.module))
-            .type_aliases
-            .insert(info.name, TypeAliasDef::from(info));
-    }
-
-    fn add_module_doc(&mut self, info: &ModuleDocInfo) {
-        let raw_doc = (info.doc)();
-        self.get_module(Some(info.module)).doc = normalize_docstring(&raw_doc);
+    fn add_deprecated_alias(&mut self, info: &DeprecatedAliasInfo) -> Result<()> {
+        let mut alias = DeprecatedAliasDef::from(info);
+        alias
+            .target
+            .resolve_default_module(&self.default_module_name);
+        let module = self.get_module(Some(info.module));
+        if module.defines_type(info.name) {
+            anyhow::bail!(
+                "Deprecated alias `{}` in module `{}` conflicts with another item of the same name",
+                info.name,
+                info.module
+            );
+        }
+        module.deprecated_aliases.insert(info.name, alias);
+        Ok(())
     }
 
-    fn add_module_export(&mut self, info: &ReexportModuleMembers) {
-        use crate::type_info::ReexportItems;
-
-        let (items, additional_items) = match info.items {
-            ReexportItems::Wildcard => (Vec::new(), Vec::new()),
-            ReexportItems::Explicit(items) => {
-                (items.iter().map(|s| s.to_string()).collect(), Vec::new())
+    fn add_type_alias(&mut self, info: &TypeAliasInfo) -> Result<()> {
+        if let Some(py_version) = info.py_version {
+            if !is_valid_py_version(py_version) {
+                anyhow::bail!(
+                    "Invalid py_version `{py_version}` of type alias `{}`, expected `>=X.Y` or `<X.Y`",
+                    info.name
+                );
             }
-            ReexportItems::WildcardPlus(additional) => (
-                Vec::new(),
-                additional.iter().map(|s| s.to_string()).collect(),
-            ),
+        }
+        let alias = TypeAliasDef::from(info);
+        let type_aliases = &mut self.get_module(Some(info.module)).type_aliases;
+        let Some(existing) = type_aliases.get_mut(info.name) else {
+            type_aliases.insert(info.name, alias);
+            return Ok(());
         };
-
-        self.get_module(Some(info.target_module))
-            .module_re_exports
-            .push(ModuleReExport {
-                source_module: info.source_module.to_string(),
-                items,
-                additional_items,
-            });
+        // Aliases for complementary Python versions are written in `if` and `else` blocks
+        if existing.py_version.is_some() || alias.py_version.is_some() {
+            if existing.merge_fallback(alias).is_err() {
+                anyhow::bail!(
+                    "Type alias `{}` in module `{}` is declared more than once for overlapping Python versions",
+                    info.name,
+                    info.module
+                );
+            }
+        } else {
+            *existing = alias;
+        }
+        Ok(())
     }
 
-    fn add_verbatim_export(&mut self, info: &ExportVerbatim) {
-        self.get_module(Some(info.target_module))
-            .verbatim_all_entries
-            .insert(info.name.to_string());
+    fn add_type_param(&mut self, info: &TypeParamInfo) -> Result<()> {
+        let module = self.get_module(Some(info.module));
+        if module.type_params.contains_key(info.name) {
+            anyhow::bail!(
+                "Type parameter `{}` is declared more than once in module `{}`",
+                info.name,
+                info.module
+            );
+        }
+        module
+            .type_params
+            .insert(info.name, TypeParamDef::from(info));
+        Ok(())
     }
 
-    fn add_exclude(&mut self, info: &ExcludeFromAll) {
-        self.get_module(Some(info.target_module))
-            .excluded_all_entries
-            .insert(info.name.to_string());
+    fn add_typed_dict(&mut self, info: &TypedDictInfo) -> Result<()> {
+        let module = self.get_module(Some(info.module));
+        if module.typed_dicts.contains_key(info.name) {
+            anyhow::bail!(
+                "TypedDict `{}` is declared more than once in module `{}`",
+                info.name,
+                info.module
+            );
+        }
+        module
+            .typed_dicts
+            .insert(info.name, TypedDictDef::from(info));
+        Ok(())
     }
 
-    fn resolve_wildcard_re_exports(&mut self) -> Result<()> {
-        // Collect wildcard re-exports and their resolved items for __all__
-        // (module_name, re_export_idx, resolved_items, additional_items_to_merge)
-        let mut resolutions: Vec<(String, usize, Vec<String>, Vec<String>)> = Vec::new();
+    /// Functions returning the same `NamedTuple` share it, while different definitions of a name conflict
+    fn add_named_tuple(&mut self, info: &NamedTupleInfo) -> Result<()> {
+        let def = NamedTupleDef::from(info);
+        let module = self.get_module(info.module);
+        if let Some(existing) = module.named_tuples.get(info.name) {
+            if *existing != def {
+                anyhow::bail!(
+                    "NamedTuple `{}` is declared with different fields in module `{}`",
+                    info.name,
+                    module.name
+                );
+            }
+            return Ok(());
+        }
+        module.named_tuples.insert(info.name, def);
+        Ok(())
+    }
+
+    /// Attach the keys given by `#[gen_stub(dict_keys(...))]` to the module of the function or the class,
+    /// together with the `TypedDict` given by `typed_dict = "..."`
+    fn add_dict_keys(&mut self, info: &DictKeysInfo) -> Result<()> {
+        let has_parameter = |parameters: &Parameters| {
+            parameters
+                .iter_entries()
+                .any(|param| param.name == info.parameter)
+        };
+        let (module, class) = match info.struct_id {
+            Some(struct_id) => {
+                let struct_id = struct_id();
+                let found = self.modules.iter().find_map(|(module_name, module)| {
+                    if let Some(class) = module.class.get(&ClassId::Rust(struct_id)) {
+                        let found = class.methods.get(info.function).is_some_and(|methods| {
+                            methods
+                                .iter()
+                                .any(|method| has_parameter(&method.parameters))
+                        });
+                        Some((module_name.clone(), class.name, found))
+                    } else {
+                        let enum_ = module.enum_.get(&struct_id)?;
+                        let found = enum_.methods.iter().any(|method| {
+                            method.name == info.function && has_parameter(&method.parameters)
+                        });
+                        Some((module_name.clone(), enum_.name, found))
+                    }
+                });
+                let Some((module_name, class, true)) = found else {
+                    anyhow::bail!(
+                        "`dict_keys` is given to the parameter `{}` of the method `{}`, which is not found",
+                        info.parameter,
+                        info.function
+                    );
+                };
+                (self.get_module(Some(&module_name)), Some(class))
+            }
+            None => {
+                let module = self.get_module(info.module);
+                let found = module.function.get(info.function).is_some_and(|functions| {
+                    functions
+                        .iter()
+                        .any(|function| has_parameter(&function.parameters))
+                });
+                if !found {
+                    anyhow::bail!(
+                        "`dict_keys` is given to the parameter `{}` of the function `{}`, which is not found in module `{}`",
+                        info.parameter,
+                        info.function,
+                        module.name
+                    );
+                }
+                (module, None)
+            }
+        };
+        let def = DictKeysDef::new(class, info);
+        if let Some(name) = info.typed_dict {
+            let typed_dict = def.to_typed_dict(name);
+            match module.typed_dicts.get(name) {
+                Some(existing) if *existing != typed_dict => anyhow::bail!(
+                    "TypedDict `{name}` is declared with different keys in module `{}`",
+                    module.name
+                ),
+                Some(_) => {}
+                None => {
+                    module.typed_dicts.insert(name, typed_dict);
+                }
+            }
+        }
+        module.dict_keys.push(def);
+        Ok(())
+    }
+
+    /// Write imports of modules importing each other under `if typing.TYPE_CHECKING:`
+    fn guard_circular_imports(&mut self) {
+        let graph = ModuleGraph::new(&self.modules);
+        for cycle in graph.cycles() {
+            log::debug!("Circular imports among modules: {}", cycle.join(", "));
+        }
+        for (name, module) in &mut self.modules {
+            module.type_checking_modules = graph
+                .dependencies(name)
+                .filter(|dependency| graph.in_same_cycle(name, dependency))
+                .map(str::to_string)
+                .collect();
+        }
+    }
+
+    /// Write imports of `type-checking-only-imports` under `if typing.TYPE_CHECKING:`,
+    /// and quote annotations referencing them unless `from __future__ import annotations` is written
+    fn guard_type_checking_only_imports(&mut self) {
+        if self.config.type_checking_only_imports.is_empty() {
+            return;
+        }
+        let config = &self.config;
+        for module in self.modules.values_mut() {
+            let default_module_name = module.default_module_name.clone();
+            let guarded: Vec<String> = module
+                .import()
+                .iter()
+                .map(|import_ref| import_ref.module_name(&default_module_name))
+                .filter(|name| config.is_type_checking_only(name))
+                .map(str::to_string)
+                .collect();
+            module.type_checking_modules.extend(guarded);
+            if config.future_annotations {
+                continue;
+            }
+            module.for_each_type_mut(&mut |type_info| {
+                let references_guarded = type_info.import.iter().any(|import_ref| {
+                    config.is_type_checking_only(import_ref.module_name(&default_module_name))
+                });
+                if references_guarded {
+                    if let Some(quoted) = quote_forward_ref(&type_info.name) {
+                        type_info.name = quoted;
+                    }
+                }
+            });
+        }
+    }
+
+    /// Python items of the Rust paths registered by the proc-macros, after functions are renamed.
+    ///
+    /// Paths of items not found, e.g. skipped by `#[gen_stub(skip)]`, are dropped.
+    fn resolve_doc_links(&self) -> DocLinks {
+        let mut targets = Vec::new();
+        for info in inventory::iter::<RustPathInfo> {
+            let found = match info.target {
+                RustPathTarget::Type(struct_id) => {
+                    let struct_id = struct_id();
+                    self.modules.values().find_map(|module| {
+                        let name = module
+                            .class
+                            .get(&ClassId::Rust(struct_id))
+                            .map(|class| class.name)
+                            .or_else(|| module.enum_.get(&struct_id).map(|enum_| enum_.name))?;
+                        Some((module.name.clone(), name.to_string(), DocLinkKind::Class))
+                    })
+                }
+                RustPathTarget::Function { module, name } => {
+                    let module = module.unwrap_or(&self.default_module_name);
+                    let name = match self.config.rename {
+                        Some(rule) => rule.apply(name),
+                        None => name.to_string(),
+                    };
+                    self.modules
+                        .get(module)
+                        .filter(|module| module.function.contains_key(name.as_str()))
+                        // Moved to the module it is added to by `#[gen_stub_pymodule]`
+                        .or_else(|| {
+                            self.modules
+                                .values()
+                                .find(|module| module.function.contains_key(name.as_str()))
+                        })
+                        .map(|module| (module.name.clone(), name, DocLinkKind::Function))
+                }
+            };
+            if let Some((module, name, kind)) = found {
+                targets.push(DocLinkTarget {
+                    rust_path: (info.rust_path)(),
+                    module,
+                    name,
+                    kind,
+                });
+            }
+        }
+        DocLinks { targets }
+    }
+
+    /// Warn about `**kwargs: typing_extensions.Unpack[...]` given by `#[gen_stub(kwargs_unpack = "...")]`
+    /// referring to a `TypedDict` not declared by [crate::typed_dict!] in the modules being generated
+    fn check_kwargs_unpack(&self) {
+        for module in self.modules.values() {
+            let functions = module
+                .function
+                .values()
+                .flatten()
+                .map(|func| (func.name, &func.parameters));
+            let methods = module
+                .class
+                .values()
+                .flat_map(|class| class.methods.values().flatten())
+                .map(|method| (method.name, &method.parameters));
+            for (name, parameters) in functions.chain(methods) {
+                let Some(typed_dict) = parameters.varkw.as_ref().and_then(|varkw| {
+                    varkw
+                        .type_info
+                        .name
+                        .strip_prefix("typing_extensions.Unpack[")?
+                        .strip_suffix(']')
+                }) else {
+                    continue;
+                };
+                let (module_name, typed_dict_name) = typed_dict
+                    .rsplit_once('.')
+                    .unwrap_or((module.name.as_str(), typed_dict));
+                // TypedDicts of other packages cannot be checked
+                let Some(target) = self.modules.get(module_name) else {
+                    continue;
+                };
+                if !target.typed_dicts.contains_key(typed_dict_name) {
+                    log::warn!(
+                        "`**kwargs` of `{name}` in module `{}` is unpacked as `{typed_dict}`, \
+                         which is not declared by `typed_dict!` in module `{module_name}`",
+                        module.name
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check that types referred by type aliases are defined in the gathered modules
+    ///
+    /// Fails if the module of a target is gathered but does not define it, e.g. a class with `module = "..."`
+    /// different from the one given to `#[pymodule]`. Targets in modules not gathered are only warned,
+    /// since they may be defined in Python files of a mixed layout project.
+    fn check_type_alias_targets(&self) -> Result<()> {
+        let mut missing = Vec::new();
+        for (module_name, module) in &self.modules {
+            for alias in module.type_aliases.values() {
+                let definitions = std::iter::once(alias).chain(alias.fallback.as_deref());
+                for (name, type_ref) in definitions.flat_map(|def| &def.type_.type_refs) {
+                    let target_module = type_ref
+                        .module
+                        .get()
+                        .filter(|module| !module.is_empty())
+                        .unwrap_or(&self.default_module_name);
+                    // Nested classes like `Shape.Circle` are checked by their outermost class
+                    let target = name.split('.').next().unwrap_or(name);
+                    match self.modules.get(target_module) {
+                        Some(target_mod) if target_mod.defines_type(target) => {}
+                        Some(_) => missing.push(UnresolvedType {
+                            alias: format!("{module_name}.{}", alias.name),
+                            target: format!("{target_module}.{name}"),
+                            module: target_module.to_string(),
+                        }),
+                        None => log::warn!(
+                            "Type alias `{module_name}.{}` refers to `{target_module}.{name}`, \
+                             but module `{target_module}` has no stub generated",
+                            alias.name
+                        ),
+                    }
+                }
+            }
+        }
+        if missing.is_empty() {
+            return Ok(());
+        }
+        missing.sort();
+        missing.dedup();
+        Err(StubGenError::UnresolvedTypes(missing).into())
+    }
+
+    /// Report parameters, return types and properties of `#[gen_stub_*]` items falling back to `typing.Any`
+    /// according to `strict`, i.e. of types with [TypeInfo::any_fallback] like `Bound<PyAny>`.
+    /// `typing.Any` written explicitly by `#[gen_stub(override_type(...))]` is not reported.
+    fn check_any_fallbacks(
+        &self,
+        functions: &[&PyFunctionInfo],
+        methods: &[&PyMethodsInfo],
+        classes: &[&PyClassInfo],
+    ) -> Result<()> {
+        if self.config.strict == Strict::Off {
+            return Ok(());
+        }
+        fn members<'a>(
+            kind: &'a str,
+            owner: &'a str,
+            members: &'a [MemberInfo],
+        ) -> impl Iterator<Item = (String, TypeInfo)> + 'a {
+            members.iter().map(move |member| {
+                (
+                    format!("{kind} `{owner}.{}`", member.name),
+                    (member.r#type)(),
+                )
+            })
+        }
+
+        // (file, line, what the type is of, type)
+        let mut types: Vec<(&str, u32, String, TypeInfo)> = Vec::new();
+        for info in functions {
+            let module = info.module.unwrap_or(&self.default_module_name);
+            let fqn = format!("{module}.{}", info.name);
+            for param in info.parameters {
+                let what = format!("parameter `{}` of `{fqn}`", param.name);
+                types.push((info.file, info.line, what, (param.type_info)()));
+            }
+            let what = format!("return type of `{fqn}`");
+            types.push((info.file, info.line, what, (info.r#return)()));
+        }
+        for info in classes {
+            let module = info.module.unwrap_or(&self.default_module_name);
+            let fqn = format!("{module}.{}", info.pyclass_name);
+            for (what, type_info) in
+                members("getter", &fqn, info.getters).chain(members("setter", &fqn, info.setters))
+            {
+                types.push((info.file, info.line, what, type_info));
+            }
+        }
+        for info in methods {
+            let owner = match &info.python_class {
+                Some(class) => class.name.to_string(),
+                None => {
+                    let struct_id = (info.struct_id)();
+                    self.modules
+                        .iter()
+                        .find_map(|(module_name, module)| {
+                            let name = module
+                                .class
+                                .get(&ClassId::Rust(struct_id))
+                                .map(|class| class.name)
+                                .or_else(|| module.enum_.get(&struct_id).map(|enum_| enum_.name))?;
+                            Some(format!("{module_name}.{name}"))
+                        })
+                        .unwrap_or_default()
+                }
+            };
+            for (what, type_info) in members("class attribute", &owner, info.attrs)
+                .chain(members("getter", &owner, info.getters))
+                .chain(members("setter", &owner, info.setters))
+            {
+                types.push((info.file, info.line, what, type_info));
+            }
+            for method in info.methods {
+                let fqn = format!("{owner}.{}", method.name);
+                for param in method.parameters {
+                    let what = format!("parameter `{}` of `{fqn}`", param.name);
+                    types.push((info.file, info.line, what, (param.type_info)()));
+                }
+                let what = format!("return type of `{fqn}`");
+                types.push((info.file, info.line, what, (method.r#return)()));
+            }
+        }
+
+        // Types written explicitly as `typing.Any`, e.g. by `override_type`, have no reason and are not reported
+        let mut fallbacks: Vec<(String, String, String, &str)> = types
+            .into_iter()
+            .filter_map(|(file, line, what, type_info)| {
+                let reason = type_info.any_fallback?;
+                Some((format!("{file}:{line}"), what, type_info.name, reason))
+            })
+            .collect();
+        if fallbacks.is_empty() {
+            return Ok(());
+        }
+
+        const HINT: &str = "Specify the type by `#[gen_stub(override_type(...))]` or `#[gen_stub(override_return_type(...))]`, \
+                            implement `PyStubType` for the Rust type, or use `object` if any value is accepted";
+        fallbacks.sort();
+        if self.config.strict == Strict::Warn {
+            for (location, what, type_name, reason) in &fallbacks {
+                log::warn!("{location}: {what} falls back to `{type_name}` since {reason}. {HINT}");
+            }
+            return Ok(());
+        }
+        anyhow::bail!(
+            "{} types fall back to `typing.Any` in strict mode. {HINT}:\n{}",
+            fallbacks.len(),
+            fallbacks
+                .iter()
+                .map(|(location, what, type_name, reason)| {
+                    format!("  {location}: {what} is `{type_name}` since {reason}")
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    fn add_typing_helper(&mut self, info: &TypingHelperInfo) {
+        self.get_module(Some(info.module))
+            .typing_helpers
+            .push(TypingHelperDef::from(info));
+    }
+
+    fn add_module_epilogue(&mut self, info: &ModuleEpilogueInfo) {
+        self.get_module(Some(info.module))
+            .epilogues
+            .push(TypingHelperDef::from(info));
+    }
+
+    fn add_module_getattr(&mut self, info: &ModuleGetattrInfo) -> Result<()> {
+        let mut import: HashSet<ImportRef> =
+            info.imports.iter().map(|&module| module.into()).collect();
+        // Import modules of qualified names, e.g. `typing` of `typing.Any`
+        for token in tokenize(info.return_type) {
+            if let Token::DottedPath(parts) = token {
+                import.insert(parts[..parts.len() - 1].join(".").as_str().into());
+            }
+        }
+        let module = self.get_module(Some(info.module));
+        if module.getattr.is_some() {
+            anyhow::bail!(
+                "`module_getattr!` is used more than once for module `{}`",
+                info.module
+            );
+        }
+        module.getattr = Some(TypeInfo {
+            name: info.return_type.to_string(),
+            source_module: None,
+            import,
+            type_refs: HashMap::new(),
+            any_fallback: None,
+        });
+        Ok(())
+    }
+
+    /// Check that classes and functions registered in `#[gen_stub_pymodule]` are generated into the same module.
+    ///
+    /// Returns the Python names of the registered functions declared without `module = ...`
+    /// and the modules they are added to, to be placed by [Self::place_registered_functions].
+    fn add_pymodule(&mut self, info: &PyModuleInfo) -> Result<Vec<(&'static str, String)>> {
+        let default_module_name = self.default_module_name.clone();
+        let root_name = default_module_name
+            .rsplit('.')
+            .next()
+            .unwrap_or(&default_module_name);
+        if info.root != root_name {
+            anyhow::bail!(
+                "#[gen_stub_pymodule] on `{}` does not match the module name `{}`",
+                info.root,
+                default_module_name
+            );
+        }
+        let module_name = if info.path.is_empty() {
+            default_module_name.clone()
+        } else {
+            format!("{}.{}", default_module_name, info.path)
+        };
+        // Make sure that the submodules exist even if nothing is generated into them
+        self.get_module(Some(&module_name));
+
+        for struct_id in info.classes {
+            let struct_id = struct_id();
+            let found = self.modules.iter().find_map(|(name, module)| {
+                module
+                    .class
+                    .get(&ClassId::Rust(struct_id))
+                    .map(|class| (name, class.name))
+                    .or_else(|| module.enum_.get(&struct_id).map(|enum_| (name, enum_.name)))
+            });
+            if let Some((name, class_name)) = found {
+                if *name != module_name {
+                    anyhow::bail!(
+                        "Class `{class_name}` is added to `{module_name}` by `add_class`, but its stub is generated into `{name}`. \
+                         Specify `#[pyclass(module = \"{module_name}\")]`."
+                    );
+                }
+            }
+        }
+        let mut registered = Vec::new();
+        for rust_path in info.functions {
+            let rust_path = rust_path();
+            // Functions without `#[gen_stub_pyfunction]` have no stubs
+            let Some((module, function_name)) = inventory::iter::<RustPathInfo>
+                .into_iter()
+                .find_map(|path| match path.target {
+                    RustPathTarget::Function { module, name }
+                        if (path.rust_path)() == rust_path =>
+                    {
+                        Some((module, name))
+                    }
+                    _ => None,
+                })
+            else {
+                continue;
+            };
+            if module.is_none() {
+                registered.push((function_name, module_name.clone()));
+                continue;
+            }
+            if self.modules[&module_name]
+                .function
+                .contains_key(function_name)
+            {
+                continue;
+            }
+            if let Some(name) = self.modules.iter().find_map(|(name, module)| {
+                module.function.contains_key(function_name).then_some(name)
+            }) {
+                anyhow::bail!(
+                    "Function `{function_name}` is added to `{module_name}` by `add_function`, but its stub is generated into `{name}`. \
+                     Specify `#[gen_stub_pyfunction(module = \"{module_name}\")]`, or list all modules it is added to."
+                );
+            }
+        }
+        Ok(registered)
+    }
+
+    /// Move functions without `module = ...` from the default module to the modules they are added to by `add_function`,
+    /// given as pairs of the Python name and the module. They are kept in the default module if also added to it.
+    fn place_registered_functions(&mut self, registered: Vec<(&'static str, String)>) {
+        let mut targets: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+        for (name, module) in registered {
+            targets.entry(name).or_default().insert(module);
+        }
+        let default_module_name = self.default_module_name.clone();
+        for (name, mut modules) in targets {
+            let keep = modules.remove(&default_module_name);
+            if modules.is_empty() {
+                continue;
+            }
+            let Some(default_module) = self.modules.get_mut(&default_module_name) else {
+                continue;
+            };
+            let defs = if keep {
+                default_module.function.get(name).cloned()
+            } else {
+                default_module.function.remove(name)
+            };
+            let Some(defs) = defs else {
+                continue;
+            };
+            for module in modules {
+                self.get_module(Some(&module))
+                    .function
+                    .entry(name)
+                    .or_default()
+                    .extend(defs.iter().cloned());
+            }
+        }
+    }
+
+    fn add_module_doc(&mut self, info: &ModuleDocInfo) {
+        let raw_doc = (info.doc)();
+        self.get_module(Some(info.module)).doc = normalize_docstring(&raw_doc);
+    }
+
+    /// Set docstrings of modules without `module_doc!` by `module-doc-template`.
+    /// Empty modules are kept empty not to generate stub files only with the docstring.
+    fn apply_module_doc_template(&mut self, template: &str) -> Result<()> {
+        let version = self
+            .project_version
+            .clone()
+            // Set by `cargo run` and `cargo test` for the `dynamic` version taken from Cargo.toml
+            .or_else(|| std::env::var("CARGO_PKG_VERSION").ok())
+            .unwrap_or_default();
+        for (name, module) in &mut self.modules {
+            if !module.doc.is_empty() || module.is_empty() {
+                continue;
+            }
+            let doc = format_module_doc(template, name, &self.project_name, &version)?;
+            module.doc = normalize_docstring(&doc);
+        }
+        Ok(())
+    }
+
+    fn add_module_export(&mut self, info: &ReexportModuleMembers) {
+        use crate::type_info::ReexportItems;
+
+        let mut aliases = BTreeMap::new();
+        let (items, additional_items) = match info.items {
+            ReexportItems::Wildcard => (Vec::new(), Vec::new()),
+            ReexportItems::Renamed(renamed) => {
+                aliases.extend(
+                    renamed
+                        .iter()
+                        .map(|(name, alias)| (name.to_string(), alias.to_string())),
+                );
+                (
+                    renamed.iter().map(|(name, _)| name.to_string()).collect(),
+                    Vec::new(),
+                )
+            }
+            ReexportItems::Explicit(items) => {
+                (items.iter().map(|s| s.to_string()).collect(), Vec::new())
+            }
+            ReexportItems::WildcardPlus(additional) => (
+                Vec::new(),
+                additional.iter().map(|s| s.to_string()).collect(),
+            ),
+        };
+
+        self.get_module(Some(info.target_module))
+            .module_re_exports
+            .push(ModuleReExport {
+                source_module: info.source_module.to_string(),
+                items,
+                additional_items,
+                aliases,
+            });
+    }
+
+    fn add_verbatim_export(&mut self, info: &ExportVerbatim) {
+        self.get_module(Some(info.target_module))
+            .verbatim_all_entries
+            .insert(info.name.to_string());
+    }
+
+    fn add_exclude(&mut self, info: &ExcludeFromAll) {
+        self.get_module(Some(info.target_module))
+            .excluded_all_entries
+            .insert(info.name.to_string());
+    }
+
+    /// Replace references to type aliases selected by `inline-type-aliases` with their definitions.
+    ///
+    /// The alias definitions themselves are kept in their modules.
+    fn inline_type_aliases(&mut self) {
+        let mut aliases: Vec<(String, &'static str, TypeInfo)> = self
+            .modules
+            .iter()
+            .flat_map(|(module_name, module)| {
+                module
+                    .type_aliases
+                    .values()
+                    .map(move |alias| (module_name, alias))
+            })
+            .filter(|(module_name, alias)| {
+                self.config
+                    .inline_type_aliases
+                    .is_enabled_for(module_name, alias.name)
+            })
+            .map(|(module_name, alias)| (module_name.clone(), alias.name, alias.type_.clone()))
+            .collect();
+        if aliases.is_empty() {
+            return;
+        }
+
+        // Expand aliases referring to other inlined aliases first.
+        // Bounded by the number of aliases to stop on recursive definitions.
+        for _ in 0..aliases.len() {
+            let mut changed = false;
+            for i in 0..aliases.len() {
+                for j in 0..aliases.len() {
+                    if i == j {
+                        continue;
+                    }
+                    let (module, name, type_) = aliases[j].clone();
+                    changed |= aliases[i].2.inline_alias(&module, name, &type_);
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        for module in self.modules.values_mut() {
+            module.for_each_type_mut(&mut |type_info| {
+                for (alias_module, alias_name, alias_type) in &aliases {
+                    type_info.inline_alias(alias_module, alias_name, alias_type);
+                }
+            });
+        }
+    }
+
+    fn resolve_wildcard_re_exports(&mut self) -> Result<()> {
+        // Collect wildcard re-exports and their resolved items for __all__
+        // (module_name, re_export_idx, resolved_items, additional_items_to_merge)
+        let mut resolutions: Vec<(String, usize, Vec<String>, Vec<String>)> = Vec::new();
 
         for (module_name, module) in &self.modules {
             for (idx, re_export) in module.module_re_exports.iter().enumerate() {
@@ -484,6 +1883,16 @@ impl StubInfoBuilder {
                                 items.push(alias_name.to_string());
                             }
                         }
+                        for typed_dict_name in source_mod.typed_dicts.keys() {
+                            if !typed_dict_name.starts_with('_') {
+                                items.push(typed_dict_name.to_string());
+                            }
+                        }
+                        for named_tuple_name in source_mod.named_tuples.keys() {
+                            if !named_tuple_name.starts_with('_') {
+                                items.push(named_tuple_name.to_string());
+                            }
+                        }
                         for submod in &source_mod.submodules {
                             if !submod.starts_with('_') {
                                 items.push(submod.to_string());
@@ -522,43 +1931,64 @@ impl StubInfoBuilder {
     }
 
     fn add_methods(&mut self, info: &PyMethodsInfo) -> Result<()> {
-        let struct_id = (info.struct_id)();
+        let class_id = match &info.python_class {
+            Some(class) => self.find_class_by_name(class)?,
+            None => (info.struct_id)().into(),
+        };
+        // Enums are keyed by their Rust type only
+        let enum_id = match &class_id {
+            ClassId::Rust(id) => Some(*id),
+            ClassId::Runtime { .. } => None,
+        };
         for module in self.modules.values_mut() {
-            if let Some(entry) = module.class.get_mut(&struct_id) {
+            if let Some(entry) = module.class.get_mut(&class_id) {
                 for attr in info.attrs {
                     entry.attrs.push(MemberDef {
                         name: attr.name,
                         r#type: (attr.r#type)(),
                         doc: attr.doc,
                         default: attr.default.map(|f| f()),
+                        default_symbol: attr.default_symbol,
                         deprecated: attr.deprecated.clone(),
+                        cached: attr.cached,
+                        type_ignored: None,
                     });
                 }
                 for getter in info.getters {
-                    entry
+                    let slot = &mut entry
                         .getter_setters
                         .entry(getter.name.to_string())
                         .or_default()
-                        .0 = Some(MemberDef {
+                        .0;
+                    let member = MemberDef {
                         name: getter.name,
                         r#type: (getter.r#type)(),
                         doc: getter.doc,
                         default: getter.default.map(|f| f()),
+                        default_symbol: getter.default_symbol,
                         deprecated: getter.deprecated.clone(),
-                    });
+                        cached: getter.cached,
+                        type_ignored: None,
+                    };
+                    merge_accessor(slot, member, entry.name, "getter")?;
                 }
                 for setter in info.setters {
-                    entry
+                    let slot = &mut entry
                         .getter_setters
                         .entry(setter.name.to_string())
                         .or_default()
-                        .1 = Some(MemberDef {
+                        .1;
+                    let member = MemberDef {
                         name: setter.name,
                         r#type: (setter.r#type)(),
                         doc: setter.doc,
                         default: setter.default.map(|f| f()),
+                        default_symbol: setter.default_symbol,
                         deprecated: setter.deprecated.clone(),
-                    });
+                        cached: setter.cached,
+                        type_ignored: None,
+                    };
+                    merge_accessor(slot, member, entry.name, "setter")?;
                 }
                 for method in info.methods {
                     let entries = entry.methods.entry(method.name.to_string()).or_default();
@@ -579,14 +2009,17 @@ impl StubInfoBuilder {
                     entries.push(new_method);
                 }
                 return Ok(());
-            } else if let Some(entry) = module.enum_.get_mut(&struct_id) {
+            } else if let Some(entry) = enum_id.and_then(|id| module.enum_.get_mut(&id)) {
                 for attr in info.attrs {
                     entry.attrs.push(MemberDef {
                         name: attr.name,
                         r#type: (attr.r#type)(),
                         doc: attr.doc,
                         default: attr.default.map(|f| f()),
+                        default_symbol: attr.default_symbol,
                         deprecated: attr.deprecated.clone(),
+                        cached: attr.cached,
+                        type_ignored: None,
                     });
                 }
                 for getter in info.getters {
@@ -595,7 +2028,10 @@ impl StubInfoBuilder {
                         r#type: (getter.r#type)(),
                         doc: getter.doc,
                         default: getter.default.map(|f| f()),
+                        default_symbol: getter.default_symbol,
                         deprecated: getter.deprecated.clone(),
+                        cached: getter.cached,
+                        type_ignored: None,
                     });
                 }
                 for setter in info.setters {
@@ -604,7 +2040,10 @@ impl StubInfoBuilder {
                         r#type: (setter.r#type)(),
                         doc: setter.doc,
                         default: setter.default.map(|f| f()),
+                        default_symbol: setter.default_symbol,
                         deprecated: setter.deprecated.clone(),
+                        cached: setter.cached,
+                        type_ignored: None,
                     });
                 }
                 for method in info.methods {
@@ -630,27 +2069,131 @@ impl StubInfoBuilder {
                 return Ok(());
             }
         }
-        unreachable!("Missing struct_id/enum_id = {:?}", struct_id);
+        unreachable!("Missing struct_id/enum_id = {:?}", class_id);
+    }
+
+    /// Key of the class or enum which methods are attached to by its Python name
+    fn find_class_by_name(&self, class: &PythonClassRef) -> Result<ClassId> {
+        let found: Vec<_> = self
+            .modules
+            .iter()
+            .filter(|(name, _)| class.module.is_none_or(|module| module == name.as_str()))
+            .flat_map(|(name, module)| {
+                let classes = module
+                    .class
+                    .iter()
+                    .filter(|(_, def)| def.name == class.name)
+                    .map(|(id, _)| id.clone());
+                let enums = module
+                    .enum_
+                    .iter()
+                    .filter(|(_, def)| def.name == class.name)
+                    .map(|(id, _)| ClassId::Rust(*id));
+                classes.chain(enums).map(move |id| (name, id))
+            })
+            .collect();
+        match found.as_slice() {
+            [(_, id)] => Ok(id.clone()),
+            [] => Err(StubGenError::ClassNotFound {
+                name: class.name.to_string(),
+                module: class.module.map(str::to_string),
+            }
+            .into()),
+            _ => anyhow::bail!(
+                "Class `{}` to add methods by name is found in several modules: {}. Specify `module` to choose one.",
+                class.name,
+                found
+                    .iter()
+                    .map(|(name, _)| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    fn add_class_verbatim(&mut self, info: &PyClassVerbatimInfo) -> Result<()> {
+        let struct_id = (info.struct_id)();
+        let Some(class) = self
+            .modules
+            .values_mut()
+            .find_map(|module| module.class.get_mut(&ClassId::Rust(struct_id)))
+        else {
+            anyhow::bail!(
+                "class_verbatim! at {}:{} is used for a type which is not a #[gen_stub_pyclass] or #[gen_stub_pyclass_complex_enum]",
+                info.file,
+                info.line
+            );
+        };
+        class.verbatim.push(TypingHelperDef::from(info));
+        Ok(())
     }
 
     fn build(mut self) -> Result<StubInfo> {
-        for info in inventory::iter::<PyClassInfo> {
+        if cfg!(pyo3_stub_gen_disabled) {
+            anyhow::bail!(
+                "pyo3-stub-gen is built with `--cfg pyo3_stub_gen_disabled`, which compiles out all type information. \
+                 Build the stub generator without it."
+            );
+        }
+        let classes: Vec<&PyClassInfo> = inventory::iter::<PyClassInfo>().collect();
+        let functions: Vec<&PyFunctionInfo> = inventory::iter::<PyFunctionInfo>().collect();
+        let runtime_items = std::mem::take(&mut self.runtime_items);
+        for &info in &classes {
             self.add_class(info);
         }
+        for class in runtime_items.classes {
+            self.add_runtime_class(class);
+        }
         for info in inventory::iter::<PyComplexEnumInfo> {
             self.add_complex_enum(info);
         }
         for info in inventory::iter::<PyEnumInfo> {
             self.add_enum(info);
         }
-        for info in inventory::iter::<PyFunctionInfo> {
+        self.check_duplicate_classes()?;
+        let external_classes: Vec<&ExternalClassInfo> =
+            inventory::iter::<ExternalClassInfo>().collect();
+        self.resolve_external_bases(&external_classes);
+        for &info in &functions {
             self.add_function(info)?;
         }
+        for function in runtime_items.functions {
+            self.add_runtime_function(function)?;
+        }
         for info in inventory::iter::<PyVariableInfo> {
             self.add_variable(info);
         }
         for info in inventory::iter::<TypeAliasInfo> {
-            self.add_type_alias(info);
+            self.add_type_alias(info)?;
+        }
+        for info in inventory::iter::<DeprecatedAliasInfo> {
+            self.add_deprecated_alias(info)?;
+        }
+        for info in inventory::iter::<TypedDictInfo> {
+            self.add_typed_dict(info)?;
+        }
+        for info in inventory::iter::<NamedTupleInfo> {
+            self.add_named_tuple(info)?;
+        }
+        for info in inventory::iter::<TypeParamInfo> {
+            self.add_type_param(info)?;
+        }
+        // Sort typing helpers by their code for deterministic output
+        let mut typing_helpers: Vec<&TypingHelperInfo> =
+            inventory::iter::<TypingHelperInfo>().collect();
+        typing_helpers.sort_by_key(|info| info.code);
+        for info in typing_helpers {
+            self.add_typing_helper(info);
+        }
+        // Sort epilogues by source location to keep them in the written order
+        let mut epilogues: Vec<&ModuleEpilogueInfo> =
+            inventory::iter::<ModuleEpilogueInfo>().collect();
+        epilogues.sort_by_key(|info| (info.file, info.line));
+        for info in epilogues {
+            self.add_module_epilogue(info);
+        }
+        for info in inventory::iter::<ModuleGetattrInfo> {
+            self.add_module_getattr(info)?;
         }
         for info in inventory::iter::<ModuleDocInfo> {
             self.add_module_doc(info);
@@ -658,9 +2201,43 @@ impl StubInfoBuilder {
         // Sort PyMethodsInfo by source location for deterministic IndexMap insertion order
         let mut methods_infos: Vec<&PyMethodsInfo> = inventory::iter::<PyMethodsInfo>().collect();
         methods_infos.sort_by_key(|info| (info.file, info.line, info.column));
-        for info in methods_infos {
+        for &info in &methods_infos {
             self.add_methods(info)?;
         }
+        // After functions and methods are added, and before they are renamed
+        for info in inventory::iter::<DictKeysInfo> {
+            self.add_dict_keys(info)?;
+        }
+        self.check_any_fallbacks(&functions, &methods_infos, &classes)?;
+        // Sort by source location to keep fragments for a class in the written order
+        let mut verbatim_infos: Vec<&PyClassVerbatimInfo> =
+            inventory::iter::<PyClassVerbatimInfo>().collect();
+        verbatim_infos.sort_by_key(|info| (info.file, info.line));
+        for info in verbatim_infos {
+            self.add_class_verbatim(info)?;
+        }
+        // After `#[pymethods]` so that explicitly defined pickle methods and `__hash__` are kept as is
+        for module in self.modules.values_mut() {
+            for class in module.class.values_mut() {
+                if class.picklable {
+                    class.add_pickle_methods();
+                }
+                class.add_iterator_methods();
+                if class.unhashable {
+                    class.add_unhashable_marker();
+                }
+                if self.config.class_signature_in_docstring {
+                    class.add_signature_to_doc();
+                }
+                if self.config.mark_final {
+                    class.mark_final();
+                }
+                if self.config.self_in_new {
+                    class.return_self_from_new();
+                }
+                class.drop_write_only_properties();
+            }
+        }
         // Collect __all__ export directives
         for info in inventory::iter::<ReexportModuleMembers> {
             self.add_module_export(info);
@@ -671,24 +2248,128 @@ impl StubInfoBuilder {
         for info in inventory::iter::<ExcludeFromAll> {
             self.add_exclude(info);
         }
+        let mut registered = Vec::new();
+        for info in inventory::iter::<PyModuleInfo> {
+            registered.extend(self.add_pymodule(info)?);
+        }
+        self.place_registered_functions(registered);
         self.register_submodules();
+        // After submodules are registered so that parent modules only with submodules get the docstring
+        if let Some(template) = self.config.module_doc_template.clone() {
+            self.apply_module_doc_template(&template)?;
+        }
+
+        // Before re-exports so that excluded functions are not re-exported, and renamed ones are re-exported by new names
+        for module in self.modules.values_mut() {
+            module.apply_platform_specific(self.config.platform_specific);
+            if let Some(rule) = self.config.rename {
+                module.rename(rule)?;
+            }
+        }
 
         // Resolve wildcard re-exports
         self.resolve_wildcard_re_exports()?;
 
-        Ok(StubInfo {
-            modules: self.modules,
-            python_root: self.python_root,
-            is_mixed_layout: self.is_mixed_layout,
-            config: self.config,
-            pyproject_dir: None, // Will be set by from_pyproject_toml()
-            default_module_name: self.default_module_name,
-            project_name: self.project_name,
-        })
-    }
-}
+        self.check_kwargs_unpack();
+        self.check_type_alias_targets()?;
 
-#[cfg(test)]
+        self.inline_type_aliases();
+
+        if self.config.future_annotations {
+            for module in self.modules.values_mut() {
+                module.for_each_type_mut(&mut |type_info| {
+                    if let Some(name) = unquote_forward_refs(&type_info.name) {
+                        type_info.name = name;
+                    }
+                });
+            }
+        }
+
+        let numpy = self.config.numpy;
+        for module in self.modules.values_mut() {
+            module.for_each_type_with_role_mut(&mut |type_info, role| {
+                let style = match role {
+                    TypeRole::Input => numpy.input_style(),
+                    TypeRole::Output => numpy.array,
+                };
+                apply_array_style(type_info, style);
+            });
+        }
+
+        if let Some(target) = self.config.python_target {
+            if self.config.use_type_statement && target < PythonVersion::new(3, 12) {
+                anyhow::bail!(
+                    "`use-type-statement` requires `python-target` 3.12 or later, but {target} is set"
+                );
+            }
+            for module in self.modules.values_mut() {
+                module.for_each_type_mut(&mut |type_info| apply_python_target(type_info, target));
+                for param in module.type_params.values_mut() {
+                    param.typing_module = typing_module(param.constructor(), Some(target));
+                }
+                for class in module.class.values_mut() {
+                    if let Some(dataclass_transform) = &mut class.dataclass_transform {
+                        dataclass_transform.typing_module =
+                            typing_module("dataclass_transform", Some(target));
+                    }
+                }
+            }
+        }
+
+        if !self.config.qualifies_builtins() {
+            self.unqualify_builtins();
+        }
+
+        let member_defaults = self.config.member_defaults;
+        for module in self.modules.values_mut() {
+            module.for_each_member_mut(&mut |member| member.resolve_default(member_defaults));
+        }
+
+        if log::log_enabled!(log::Level::Debug) {
+            self.log_property_names();
+        }
+
+        self.guard_circular_imports();
+        self.guard_type_checking_only_imports();
+        let doc_links = self.resolve_doc_links();
+        Ok(StubInfo {
+            modules: self.modules,
+            python_root: self.python_root,
+            is_mixed_layout: self.is_mixed_layout,
+            config: self.config,
+            pyproject_dir: None, // Will be set by from_pyproject_toml()
+            default_module_name: self.default_module_name,
+            project_name: self.project_name,
+            doc_links,
+        })
+    }
+}
+
+/// Set the getter or setter of a property given by `#[pymethods]`, which may be also given by `#[pyo3(get, set)]`
+///
+/// The same accessor defined twice must have the same type, since only one of them can be shown in the stub file.
+fn merge_accessor(
+    slot: &mut Option<MemberDef>,
+    new: MemberDef,
+    class: &str,
+    kind: &str,
+) -> Result<()> {
+    if let Some(existing) = slot {
+        if existing.r#type.name != new.r#type.name {
+            anyhow::bail!(
+                "Property `{}` of class `{class}` has multiple {kind}s with different types: `{}` and `{}`. \
+                 Remove one of them, or hide it from the stub file by `#[gen_stub(skip)]`.",
+                new.name,
+                existing.r#type.name,
+                new.r#type.name
+            );
+        }
+    }
+    *slot = Some(new);
+    Ok(())
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -759,46 +2440,1931 @@ mod tests {
     }
 
     #[test]
-    fn test_pure_layout_rejects_multiple_modules() {
-        // Pure Rust layout should reject multiple modules (whether submodules or top-level)
-        let stub_info = StubInfo {
+    fn test_reconcile_module_tree() {
+        let mut stub_info = StubInfo {
             modules: {
                 let mut map = BTreeMap::new();
                 map.insert(
-                    "mymodule".to_string(),
-                    Module {
-                        name: "mymodule".to_string(),
-                        default_module_name: "mymodule".to_string(),
-                        // Add some content so module is not empty
-                        doc: "Test module".to_string(),
-                        ..Default::default()
-                    },
-                );
-                map.insert(
-                    "mymodule.sub".to_string(),
+                    "pkg._core".to_string(),
                     Module {
-                        name: "mymodule.sub".to_string(),
-                        default_module_name: "mymodule".to_string(),
-                        // Add some content so module is not empty
-                        doc: "Test submodule".to_string(),
+                        name: "pkg._core".to_string(),
+                        default_module_name: "pkg._core".to_string(),
                         ..Default::default()
                     },
                 );
                 map
             },
             python_root: PathBuf::from("/tmp"),
-            is_mixed_layout: false,
+            is_mixed_layout: true,
             config: StubGenConfig::default(),
             pyproject_dir: None,
-            default_module_name: "mymodule".to_string(),
-            project_name: "mymodule".to_string(),
+            default_module_name: "pkg._core".to_string(),
+            project_name: "pkg".to_string(),
+            doc_links: Default::default(),
         };
 
-        let result = stub_info.generate();
-        assert!(result.is_err());
-        let err_msg = result.unwrap_err().to_string();
+        let mut tree: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        tree.insert("pkg._core".to_string(), ["sub".to_string()].into());
+        tree.insert("pkg._core.sub".to_string(), ["deep".to_string()].into());
+        tree.insert("pkg._core.sub.deep".to_string(), BTreeSet::new());
+
+        let err = stub_info
+            .clone()
+            .reconcile_module_tree(&tree, false)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Module `pkg._core.sub` exists at runtime but has no stub"));
+
+        stub_info.reconcile_module_tree(&tree, true).unwrap();
+        assert!(stub_info.modules["pkg._core"].submodules.contains("sub"));
+        assert!(stub_info.modules["pkg._core.sub"]
+            .submodules
+            .contains("deep"));
+
+        stub_info.modules.insert(
+            "pkg._core.missing".to_string(),
+            Module {
+                name: "pkg._core.missing".to_string(),
+                default_module_name: "pkg._core".to_string(),
+                ..Default::default()
+            },
+        );
+        let err = stub_info.reconcile_module_tree(&tree, true).unwrap_err();
+        assert!(err.to_string().contains(
+            "Module `pkg._core.missing` has a stub but is not added by `add_submodule` at runtime"
+        ));
+    }
+
+    #[test]
+    fn test_exception_base_in_other_module() {
+        fn base_error() -> TypeInfo {
+            TypeInfo::locally_defined("BaseError", "pkg.errors".into())
+        }
+        static READ_ERROR: PyClassInfo = PyClassInfo {
+            module: Some("pkg.io"),
+            bases: &[base_error],
+            subclass: true,
+            ..PyClassInfo::fixture("ReadError")
+        };
+
+        let mut module = Module {
+            name: "pkg.io".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        module.class.insert(
+            std::any::TypeId::of::<()>().into(),
+            ClassDef::from(&READ_ERROR),
+        );
+        let output = module.format_with_config(&StubGenConfig::default());
+        assert!(output.contains("from pkg import errors\n"));
+        assert!(output.contains("class ReadError(errors.BaseError):\n"));
+
+        let errors = Module {
+            name: "pkg.errors".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        let modules = BTreeMap::from([
+            ("pkg.errors".to_string(), errors),
+            ("pkg.io".to_string(), module.clone()),
+        ]);
+        let graph = ModuleGraph::new(&modules);
+        assert_eq!(
+            graph.dependencies("pkg.io").collect::<Vec<_>>(),
+            ["pkg.errors"]
+        );
+        assert_eq!(graph.topological_order().unwrap(), ["pkg.errors", "pkg.io"]);
+
+        // Imports of a module in a cycle are only for type checkers
+        module
+            .type_checking_modules
+            .insert("pkg.errors".to_string());
+        let output = module.format_with_config(&StubGenConfig::default());
+        assert!(output.contains("import typing\n"));
+        assert!(output.contains("if typing.TYPE_CHECKING:\n    from pkg import errors\n"));
+    }
+
+    #[test]
+    fn test_deprecated_alias() {
+        static OLD_NAME: DeprecatedAliasInfo = DeprecatedAliasInfo {
+            name: "OldName",
+            module: "pkg",
+            target: || TypeInfo::locally_defined("NewClass", "pkg.models".into()),
+            deprecated: DeprecatedInfo {
+                since: Some("2.0"),
+                note: Some("moved to pkg.models"),
+            },
+        };
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            false,
+            StubGenConfig::default(),
+        );
+        builder.add_deprecated_alias(&OLD_NAME).unwrap();
+        let output = builder.modules["pkg"].format_with_config(&StubGenConfig::default());
+        assert!(output.contains("import models\n"));
+        assert!(output.contains("\"OldName\""));
+        assert!(output
+            .contains("# Deprecated since 2.0: moved to pkg.models\nOldName = models.NewClass\n"));
+        // The old name must not shadow another item
+        assert!(builder.add_deprecated_alias(&OLD_NAME).is_err());
+    }
+
+    #[test]
+    fn test_raises() {
+        static PARSE_ERROR: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u8>,
+            module: Some("pkg.errors"),
+            subclass: true,
+            ..PyClassInfo::fixture("ParseError")
+        };
+        static PARSE: PyFunctionInfo = PyFunctionInfo {
+            raises: &[
+                RaisesInfo::Type {
+                    id: std::any::TypeId::of::<u8>,
+                    name: "ParseError",
+                },
+                RaisesInfo::Builtin("ValueError"),
+            ],
+            doc: "Parse the input",
+            module: Some("pkg"),
+            ..PyFunctionInfo::fixture("parse", no_return_type_output)
+        };
+
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        builder.add_class(&PARSE_ERROR);
+        builder.add_function(&PARSE).unwrap();
+
+        let function = &builder.modules["pkg"].function["parse"][0];
+        assert_eq!(
+            function
+                .raises
+                .iter()
+                .map(|exception| exception.name.as_str())
+                .collect::<Vec<_>>(),
+            ["errors.ParseError", "builtins.ValueError"]
+        );
+        let output = function.to_string();
+        assert!(output.contains("    Parse the input\n"));
+        assert!(output.contains("    Raises:\n        ParseError\n        ValueError\n"));
+    }
+
+    #[test]
+    fn test_any_fallbacks() {
+        use crate::PyStubType;
+
+        fn list_of_any() -> TypeInfo {
+            <Vec<::pyo3::Py<::pyo3::PyAny>> as PyStubType>::type_output()
+        }
+        static PARAMETERS: [ParameterInfo; 3] = [
+            ParameterInfo {
+                name: "obj",
+                kind: ParameterKind::PositionalOrKeyword,
+                type_info: <::pyo3::Py<::pyo3::PyAny> as PyStubType>::type_input,
+                default: ParameterDefault::None,
+            },
+            // Written explicitly, e.g. by `#[gen_stub(override_type(type_repr = "typing.Any"))]`
+            ParameterInfo {
+                name: "value",
+                kind: ParameterKind::PositionalOrKeyword,
+                type_info: TypeInfo::any,
+                default: ParameterDefault::None,
+            },
+            ParameterInfo {
+                name: "items",
+                kind: ParameterKind::PositionalOrKeyword,
+                type_info: list_of_any,
+                default: ParameterDefault::None,
+            },
+        ];
+        static CONVERT: PyFunctionInfo = PyFunctionInfo {
+            parameters: &PARAMETERS,
+            module: Some("pkg"),
+            file: "src/lib.rs",
+            line: 10,
+            ..PyFunctionInfo::fixture("convert", no_return_type_output)
+        };
+
+        let mut config = StubGenConfig::default();
+        let builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            config.clone(),
+        );
+        assert!(builder.check_any_fallbacks(&[&CONVERT], &[], &[]).is_ok());
+
+        config.strict = Strict::Error;
+        let builder =
+            StubInfoBuilder::from_project_root("pkg".to_string(), "/tmp".into(), true, config);
+        let message = builder
+            .check_any_fallbacks(&[&CONVERT], &[], &[])
+            .unwrap_err()
+            .to_string();
+        assert!(message.starts_with("2 types fall back to `typing.Any`"));
+        assert!(message.contains(
+            "\n  src/lib.rs:10: parameter `items` of `pkg.convert` is `builtins.list[typing.Any]` \
+             since `PyAny` has no specific Python type"
+        ));
+        assert!(message.contains(
+            "\n  src/lib.rs:10: parameter `obj` of `pkg.convert` is `typing.Any` \
+             since `PyAny` has no specific Python type"
+        ));
+        assert!(!message.contains("`value`"));
+        assert!(!message.contains("return type"));
+    }
+
+    #[test]
+    fn test_platform_specific() {
+        const fn function_info(
+            name: &'static str,
+            platform: Option<&'static str>,
+        ) -> PyFunctionInfo {
+            PyFunctionInfo {
+                module: Some("pkg"),
+                platform,
+                ..PyFunctionInfo::fixture(name, no_return_type_output)
+            }
+        }
+        static GETUID: PyFunctionInfo = function_info("getuid", Some("!win32"));
+        static GETPID: PyFunctionInfo = function_info("getpid", None);
+
+        let mut module = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        for info in [&GETUID, &GETPID] {
+            module
+                .function
+                .insert(info.name, vec![FunctionDef::from(info)]);
+        }
+
+        let output = module.format_with_config(&StubGenConfig::default());
+        assert!(output.contains("import sys\n"));
+        assert!(output.contains("__all__ = [\n    \"getpid\",\n]\n"));
+        assert!(output.contains("if sys.platform != \"win32\":\n    __all__ += [\"getuid\"]\n"));
+        assert!(output.contains("if sys.platform != \"win32\":\n    def getuid() -> None: ...\n"));
+
+        let mut excluded = module.clone();
+        excluded.apply_platform_specific(crate::pyproject::PlatformSpecific::Exclude);
+        assert_eq!(excluded.function.keys().collect::<Vec<_>>(), [&"getpid"]);
+
+        module.apply_platform_specific(crate::pyproject::PlatformSpecific::Include);
+        let output = module.format_with_config(&StubGenConfig::default());
+        assert!(!output.contains("sys.platform"));
+        assert!(output.contains("\ndef getuid() -> None: ...\n"));
+    }
+
+    #[test]
+    fn test_py_version() -> Result<()> {
+        const fn function_info(
+            name: &'static str,
+            py_version: Option<&'static str>,
+        ) -> PyFunctionInfo {
+            PyFunctionInfo {
+                module: Some("pkg"),
+                py_version,
+                ..PyFunctionInfo::fixture(name, no_return_type_output)
+            }
+        }
+        static BATCHED_NEW: PyFunctionInfo = function_info("batched", Some(">=3.12"));
+        static BATCHED_OLD: PyFunctionInfo = function_info("batched", Some("<3.12"));
+        static ONLY_NEW: PyFunctionInfo = function_info("only_new", Some(">=3.13"));
+
+        fn int_type() -> TypeInfo {
+            TypeInfo::builtin("int")
+        }
+        fn str_type() -> TypeInfo {
+            TypeInfo::builtin("str")
+        }
+        const fn alias_info(
+            r#type: fn() -> TypeInfo,
+            py_version: Option<&'static str>,
+        ) -> TypeAliasInfo {
+            TypeAliasInfo {
+                name: "Vector",
+                module: "pkg",
+                r#type,
+                doc: "",
+                py_version,
+            }
+        }
+        static VECTOR_OLD: TypeAliasInfo = alias_info(str_type, Some("<3.12"));
+        static VECTOR_NEW: TypeAliasInfo = alias_info(int_type, Some(">=3.12"));
+        static VECTOR_DUPLICATED: TypeAliasInfo = alias_info(int_type, Some(">=3.10"));
+
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        // The alias for the newer versions comes first regardless of the order of registration
+        builder.add_type_alias(&VECTOR_OLD)?;
+        builder.add_type_alias(&VECTOR_NEW)?;
+        assert!(builder.add_type_alias(&VECTOR_DUPLICATED).is_err());
+
+        let module = builder.get_module(Some("pkg"));
+        for info in [&BATCHED_OLD, &BATCHED_NEW] {
+            module
+                .function
+                .entry(info.name)
+                .or_default()
+                .push(FunctionDef::from(info));
+        }
+        module
+            .function
+            .insert(ONLY_NEW.name, vec![FunctionDef::from(&ONLY_NEW)]);
+
+        let output = module.format_with_config(&StubGenConfig::default());
+        assert!(output.contains(
+            "import sys
+"
+        ));
+        assert!(output.contains(
+            "__all__ = [\n    \"Vector\",\n    \"batched\",\n]\nif sys.version_info >= (3, 13):\n    __all__ += [\"only_new\"]\n"
+        ));
+        assert!(output.contains(
+            "if sys.version_info >= (3, 12):\n    type Vector = builtins.int\nelse:\n    Vector: TypeAlias = builtins.str\n"
+        ));
+        assert!(output.contains(
+            "if sys.version_info < (3, 12):\n    def batched() -> None: ...\nelse:\n    def batched() -> None: ...\n"
+        ));
         assert!(
-            err_msg.contains("Pure Rust layout does not support multiple modules or submodules")
+            output.contains("if sys.version_info >= (3, 13):\n    def only_new() -> None: ...\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_reexport_as() {
+        static REEXPORT: ReexportModuleMembers = ReexportModuleMembers {
+            target_module: "pkg",
+            source_module: "pkg._core",
+            items: ReexportItems::Renamed(&[
+                ("RustCounter", "Counter"),
+                ("new_counter", "counter"),
+            ]),
+        };
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        builder.add_module_export(&REEXPORT);
+
+        let module = &builder.modules["pkg"];
+        let expected_import =
+            "from pkg._core import RustCounter as Counter, new_counter as counter\n";
+        let expected_all = "__all__ = [\n    \"Counter\",\n    \"counter\",\n]\n";
+        let output = module.format_with_config(&StubGenConfig::default());
+        assert!(output.contains(expected_import));
+        assert!(output.contains(expected_all));
+        let init_py = module.format_init_py();
+        assert!(init_py.contains(expected_import));
+        assert!(init_py.contains(expected_all));
+    }
+
+    #[test]
+    fn test_duplicate_classes() {
+        static COUNTER: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u8>,
+            module: Some("pkg"),
+            file: "src/lib.rs",
+            line: 10,
+            ..PyClassInfo::fixture("Counter")
+        };
+        static OTHER_COUNTER: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u16>,
+            line: 20,
+            ..COUNTER
+        };
+
+        let mut config = StubGenConfig::default();
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            config.clone(),
+        );
+        builder.add_class(&COUNTER);
+        builder.add_class(&OTHER_COUNTER);
+        let err = builder.check_duplicate_classes().unwrap_err();
+        let Some(StubGenError::DuplicateClasses(duplicates)) = err.downcast_ref() else {
+            panic!("Unexpected error: {err:?}");
+        };
+        assert_eq!(
+            duplicates,
+            &[DuplicateClass {
+                name: "pkg.Counter".to_string(),
+                locations: vec![("src/lib.rs", 10), ("src/lib.rs", 20)],
+            }]
+        );
+        let message = err.to_string();
+        assert!(message.contains("pkg.Counter"));
+        assert!(message.contains("src/lib.rs:10\n"));
+        assert!(message.ends_with("src/lib.rs:20"));
+
+        config.allow_shadowing = vec!["pkg.Counter".to_string()];
+        let mut builder =
+            StubInfoBuilder::from_project_root("pkg".to_string(), "/tmp".into(), true, config);
+        builder.add_class(&COUNTER);
+        builder.add_class(&OTHER_COUNTER);
+        assert!(builder.check_duplicate_classes().is_ok());
+    }
+
+    #[test]
+    fn test_rename() -> Result<()> {
+        static GET_VALUE: PyFunctionInfo = PyFunctionInfo {
+            module: Some("pkg"),
+            ..PyFunctionInfo::fixture("get_value", no_return_type_output)
+        };
+        static COUNTER: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            ..PyClassInfo::fixture("Counter")
+        };
+
+        let mut class = ClassDef::from(&COUNTER);
+        for name in ["__len__", "add_one"] {
+            class.methods.insert(
+                name.to_string(),
+                vec![MethodDef {
+                    name,
+                    parameters: Parameters::new(),
+                    r#return: TypeInfo::none(),
+                    doc: "",
+                    r#type: MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_overload: false,
+                }],
+            );
+        }
+        let mut module = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        module
+            .function
+            .insert("get_value", vec![FunctionDef::from(&GET_VALUE)]);
+        module.class.insert(std::any::TypeId::of::<()>(), class);
+
+        module.rename(crate::pyproject::RenameRule::CamelCase)?;
+        let output = module.format_with_config(&StubGenConfig::default());
+        assert!(output.contains("    \"getValue\",\n"));
+        assert!(output.contains("\ndef getValue() -> None: ...\n"));
+        assert!(output.contains("    def __len__(self) -> None: ...\n"));
+        assert!(output.contains("    def addOne(self) -> None: ...\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pickle_methods() {
+        static SNAPSHOT: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            picklable: true,
+            ..PyClassInfo::fixture("Snapshot")
+        };
+
+        let mut class = ClassDef::from(&SNAPSHOT);
+        // Given by `#[pymethods]`
+        class.methods.insert(
+            "__getstate__".to_string(),
+            vec![MethodDef {
+                name: "__getstate__",
+                parameters: Parameters::new(),
+                r#return: TypeInfo::builtin("bytes"),
+                doc: "",
+                r#type: MethodType::Instance,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+            }],
+        );
+        class.add_pickle_methods();
+
+        let output = class.to_string();
+        assert!(output.contains("def __getstate__(self) -> builtins.bytes: ...\n"));
+        assert!(output.contains("def __setstate__(self, state: typing.Any, /) -> None: ...\n"));
+        assert!(output.contains("def __reduce__(self) -> builtins.tuple[typing.Any, ...]: ...\n"));
+        assert_eq!(class.methods["__getstate__"].len(), 1);
+    }
+
+    #[test]
+    fn test_slots() {
+        static POINT: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            slots: Some(&["x", "y"]),
+            ..PyClassInfo::fixture("Point")
+        };
+
+        let class = ClassDef::from(&POINT);
+        assert!(class.to_string().contains(
+            "__slots__: typing.ClassVar[builtins.tuple[builtins.str, ...]] = (\"x\", \"y\")\n"
+        ));
+    }
+
+    #[test]
+    fn test_match_args() {
+        const fn field(name: &'static str) -> MemberInfo {
+            MemberInfo::fixture(name, f64::type_output)
+        }
+        const fn variant(
+            pyclass_name: &'static str,
+            fields: &'static [MemberInfo],
+            match_args: bool,
+        ) -> VariantInfo {
+            VariantInfo {
+                pyclass_name,
+                module: None,
+                doc: "",
+                fields,
+                form: &VariantForm::Struct,
+                constr_args: &[],
+                match_args,
+            }
+        }
+        static RECTANGLE: [MemberInfo; 2] = [field("width"), field("height")];
+        static CIRCLE: [MemberInfo; 1] = [field("radius")];
+        static SQUARE: [MemberInfo; 1] = [field("side")];
+        static VARIANTS: [VariantInfo; 4] = [
+            variant("Rectangle", &RECTANGLE, true),
+            variant("Circle", &CIRCLE, true),
+            variant("Nothing", &[], true),
+            // `#[gen_stub(no_match_args)]`
+            variant("Square", &SQUARE, false),
+        ];
+        static SHAPE: PyComplexEnumInfo = PyComplexEnumInfo {
+            enum_id: std::any::TypeId::of::<()>,
+            pyclass_name: "Shape",
+            module: Some("pkg"),
+            doc: "",
+            variants: &VARIANTS,
+            file: "",
+            line: 0,
+            column: 0,
+        };
+
+        let class = ClassDef::from(&SHAPE);
+        let stub = class.to_string();
+        assert!(stub.contains(
+            "__match_args__: typing.ClassVar[builtins.tuple[typing.Literal[\"width\"], typing.Literal[\"height\"]]] = (\"width\", \"height\")\n"
+        ), "{stub}");
+        assert!(stub.contains(
+            "__match_args__: typing.ClassVar[builtins.tuple[typing.Literal[\"radius\"]]] = (\"radius\",)\n"
+        ), "{stub}");
+        assert_eq!(stub.matches("__match_args__").count(), 2, "{stub}");
+        assert!(class.import().contains(&"typing".into()));
+    }
+
+    #[test]
+    fn test_dataclass_transform() {
+        static MODEL: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            subclass: true,
+            dataclass_transform: Some(DataclassTransformInfo {
+                eq_default: Some(true),
+                order_default: None,
+                kw_only_default: Some(true),
+                frozen_default: None,
+            }),
+            ..PyClassInfo::fixture("Model")
+        };
+
+        let class = ClassDef::from(&MODEL);
+        assert!(class.to_string().starts_with(
+            "@typing.dataclass_transform(eq_default=True, kw_only_default=True)\nclass Model:\n"
+        ));
+        assert!(class.import().contains(&"typing".into()));
+    }
+
+    #[test]
+    fn test_iterator_methods() {
+        static ROWS: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            iterator: Some(IteratorInfo {
+                item: "builtins.int",
+                send: None,
+                r#return: None,
+                imports: &["builtins"],
+            }),
+            ..PyClassInfo::fixture("Rows")
+        };
+        static ACCUMULATOR: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            iterator: Some(IteratorInfo {
+                item: "builtins.int",
+                send: Some("builtins.int"),
+                r#return: None,
+                imports: &["builtins"],
+            }),
+            ..PyClassInfo::fixture("Accumulator")
+        };
+
+        let mut class = ClassDef::from(&ROWS);
+        class.add_iterator_methods();
+        let output = class.to_string();
+        assert!(output.contains("class Rows(collections.abc.Iterator[builtins.int]):"));
+        assert!(output.contains("def __next__(self) -> builtins.int: ...\n"));
+        assert!(class.import().contains(&ImportRef::from("collections.abc")));
+
+        // `__next__` given by `#[pymethods]` keeps its signature except for the return type
+        let mut class = ClassDef::from(&ROWS);
+        class.methods.insert(
+            "__next__".to_string(),
+            vec![MethodDef {
+                name: "__next__",
+                parameters: Parameters::new(),
+                r#return: TypeInfo::optional(TypeInfo::any()),
+                doc: "Next row",
+                r#type: MethodType::Instance,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+            }],
+        );
+        class.add_iterator_methods();
+        assert_eq!(class.methods["__next__"].len(), 1);
+        assert_eq!(class.methods["__next__"][0].doc, "Next row");
+        assert_eq!(class.methods["__next__"][0].r#return.name, "builtins.int");
+
+        // A generator with `send`, whose omitted return type is `None`
+        let mut class = ClassDef::from(&ACCUMULATOR);
+        class.methods.insert(
+            "send".to_string(),
+            vec![MethodDef {
+                name: "send",
+                parameters: Parameters::new(),
+                r#return: TypeInfo::any(),
+                doc: "",
+                r#type: MethodType::Instance,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+            }],
+        );
+        class.add_iterator_methods();
+        let output = class.to_string();
+        assert!(output.contains(
+            "class Accumulator(collections.abc.Generator[builtins.int, builtins.int, None]):"
+        ));
+        assert_eq!(class.methods["send"][0].r#return.name, "builtins.int");
+        assert_eq!(class.methods["__next__"][0].r#return.name, "builtins.int");
+    }
+
+    #[test]
+    fn test_class_signature_in_docstring() {
+        static POINT: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            doc: "A point",
+            ..PyClassInfo::fixture("Point")
+        };
+
+        let mut class = ClassDef::from(&POINT);
+        class.methods.insert(
+            "__new__".to_string(),
+            vec![MethodDef {
+                name: "__new__",
+                parameters: Parameters {
+                    positional_or_keyword: vec![Parameter {
+                        name: "x",
+                        kind: ParameterKind::PositionalOrKeyword,
+                        type_info: TypeInfo::builtin("int"),
+                        default: crate::generate::ParameterDefault::Expr {
+                            value: "0".to_string(),
+                            source_module: None,
+                        },
+                    }],
+                    ..Default::default()
+                },
+                r#return: TypeInfo::none(),
+                doc: "",
+                r#type: MethodType::New,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+            }],
+        );
+        class.add_signature_to_doc();
+        assert_eq!(class.doc, "Point(x=0)\n\nA point");
+        // Not duplicated
+        class.add_signature_to_doc();
+        assert_eq!(class.doc, "Point(x=0)\n\nA point");
+    }
+
+    #[test]
+    fn test_mark_final() {
+        static POINT: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            ..PyClassInfo::fixture("Point")
+        };
+
+        let mut class = ClassDef::from(&POINT);
+        assert!(!class.to_string().contains("@typing.final"));
+        assert!(!class.import().contains(&ImportRef::from("typing")));
+
+        let mut base = ClassDef {
+            name: "Base",
+            subclass: true,
+            classes: vec![class.clone()],
+            ..class.clone()
+        };
+        base.mark_final();
+        assert!(!base.r#final);
+        assert!(base.classes[0].r#final);
+
+        class.mark_final();
+        assert!(class.to_string().starts_with("@typing.final\nclass Point:"));
+        assert!(class.import().contains(&ImportRef::from("typing")));
+    }
+
+    #[test]
+    fn test_self_in_new() {
+        static BASE: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            subclass: true,
+            ..PyClassInfo::fixture("Base")
+        };
+        let new = |r#return: TypeInfo| MethodDef {
+            name: "__new__",
+            parameters: Parameters::new(),
+            r#return,
+            doc: "",
+            r#type: MethodType::New,
+            is_async: false,
+            deprecated: None,
+            type_ignored: None,
+            is_overload: false,
+        };
+
+        let mut class = ClassDef::from(&BASE);
+        class.methods.insert(
+            "__new__".to_string(),
+            vec![new(TypeInfo::locally_defined("Base", "pkg".into()))],
+        );
+        let mut sealed = ClassDef {
+            name: "Sealed",
+            subclass: false,
+            ..class.clone()
+        };
+        sealed.methods["__new__"][0].r#return = TypeInfo::locally_defined("Sealed", "pkg".into());
+        class.return_self_from_new();
+        sealed.return_self_from_new();
+        assert_eq!(class.methods["__new__"][0].r#return.name, "typing.Self");
+        assert!(class.import().contains(&ImportRef::from("typing")));
+        assert_eq!(sealed.methods["__new__"][0].r#return.name, "pkg.Sealed");
+
+        // Constructors returning another type are kept
+        class.methods["__new__"][0].r#return = TypeInfo::optional(TypeInfo::any());
+        class.return_self_from_new();
+        assert_eq!(
+            class.methods["__new__"][0].r#return.name,
+            "typing.Optional[typing.Any]"
         );
     }
+
+    #[test]
+    fn test_property_accessors() {
+        fn member(type_: &str) -> MemberDef {
+            MemberDef {
+                name: "x",
+                r#type: TypeInfo::builtin(type_),
+                doc: "",
+                default: None,
+                default_symbol: None,
+                deprecated: None,
+                cached: false,
+                type_ignored: None,
+            }
+        }
+        let mut slot = None;
+        merge_accessor(&mut slot, member("int"), "A", "getter").unwrap();
+        merge_accessor(&mut slot, member("int"), "A", "getter").unwrap();
+        let err = merge_accessor(&mut slot, member("str"), "A", "getter").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("multiple getters with different types"));
+
+        let mut class = ClassDef::from(&PyClassInfo {
+            module: Some("pkg"),
+            ..PyClassInfo::fixture("A")
+        });
+        // Getter and setter with different types are shown as they are
+        class
+            .getter_setters
+            .insert("x".to_string(), (Some(member("int")), Some(member("str"))));
+        class.drop_write_only_properties();
+        let output = class.to_string();
+        assert!(output.contains("def x(self) -> builtins.int: ..."));
+        assert!(output.contains("def x(self, value: builtins.str) -> None: ..."));
+    }
+
+    #[test]
+    fn test_write_only_property() {
+        // A field with `#[pyo3(get, set)]` and `#[gen_stub(skip_getter)]` only submits the setter
+        static SETTERS: [MemberInfo; 1] = [MemberInfo::fixture("x", i32::type_input)];
+        static POINT: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            setters: &SETTERS,
+            ..PyClassInfo::fixture("Point")
+        };
+
+        let mut class = ClassDef::from(&POINT);
+        assert!(class.getter_setters["x"].0.is_none());
+        class.drop_write_only_properties();
+        assert!(class.getter_setters.is_empty());
+        assert!(!class.to_string().contains("def x("));
+    }
+
+    #[test]
+    fn test_unhashable_marker() {
+        static POINT: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            has_eq: true,
+            ..PyClassInfo::fixture("Point")
+        };
+
+        let mut class = ClassDef::from(&POINT);
+        assert!(class.unhashable);
+        class.add_unhashable_marker();
+        let output = class.to_string();
+        assert!(
+            output.contains("    __hash__: typing.ClassVar[None]  # type: ignore[assignment]\n")
+        );
+        assert!(
+            output.contains("def __eq__(self, other: builtins.object, /) -> builtins.bool: ...\n")
+        );
+
+        // `__hash__` given by `#[pymethods]` is kept
+        let mut class = ClassDef::from(&POINT);
+        class.methods.insert(
+            "__hash__".to_string(),
+            vec![MethodDef {
+                name: "__hash__",
+                parameters: Parameters::new(),
+                r#return: TypeInfo::builtin("int"),
+                doc: "",
+                r#type: MethodType::Instance,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+            }],
+        );
+        class.add_unhashable_marker();
+        assert!(class.attrs.is_empty());
+
+        let hashable = PyClassInfo {
+            has_hash: true,
+            ..POINT
+        };
+        assert!(!ClassDef::from(&hashable).unhashable);
+    }
+
+    #[test]
+    fn test_split_classes() {
+        fn base() -> TypeInfo {
+            TypeInfo::locally_defined("Base", "pkg._core".into())
+        }
+        static BASE: PyClassInfo = PyClassInfo {
+            module: Some("pkg._core"),
+            subclass: true,
+            ..PyClassInfo::fixture("Base")
+        };
+        static CHILD: PyClassInfo = PyClassInfo {
+            module: Some("pkg._core"),
+            bases: &[base],
+            subclass: true,
+            ..PyClassInfo::fixture("Child")
+        };
+
+        let mut module = Module {
+            name: "pkg._core".to_string(),
+            default_module_name: "pkg._core".to_string(),
+            ..Default::default()
+        };
+        module
+            .class
+            .insert(std::any::TypeId::of::<u8>().into(), ClassDef::from(&BASE));
+        module
+            .class
+            .insert(std::any::TypeId::of::<u16>().into(), ClassDef::from(&CHILD));
+        let mut config = StubGenConfig::default();
+        config.split_classes = true;
+
+        let init = module.format_with_config(&config);
+        assert!(init.contains("from ._cls_Base import Base as Base\n"));
+        assert!(init.contains("from ._cls_Child import Child as Child\n"));
+        assert!(!init.contains("class "));
+
+        let stubs = module.format_class_stubs_with_config(&config);
+        assert_eq!(
+            stubs
+                .iter()
+                .map(|(stem, _)| stem.as_str())
+                .collect::<Vec<_>>(),
+            ["_cls_Base", "_cls_Child"]
+        );
+        let child = &stubs[1].1;
+        assert!(child.contains("from ._cls_Base import Base\n"));
+        assert!(child.contains("class Child(Base):\n"));
+    }
+
+    #[test]
+    fn test_buffer_protocol() {
+        static BYTES: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            buffer: true,
+            ..PyClassInfo::fixture("Bytes")
+        };
+        const BUFFER_IMPORT: &str = "\
+if sys.version_info >= (3, 12):
+    from collections.abc import Buffer
+else:
+    from typing_extensions import Buffer
+";
+        fn assert_buffer_class(stub: &str) {
+            assert!(stub.contains("import sys\n"));
+            assert!(stub.contains(BUFFER_IMPORT));
+            assert!(stub.contains("class Bytes(Buffer):\n"));
+            assert!(stub.contains(
+                "def __buffer__(self, flags: builtins.int, /) -> builtins.memoryview: ...\n"
+            ));
+            assert!(stub.contains(
+                "def __release_buffer__(self, buffer: builtins.memoryview, /) -> None: ...\n"
+            ));
+        }
+
+        let mut module = Module {
+            name: "pkg".to_string(),
+            default_module_name: "pkg".to_string(),
+            ..Default::default()
+        };
+        module
+            .class
+            .insert(std::any::TypeId::of::<u8>().into(), ClassDef::from(&BYTES));
+        assert_buffer_class(&module.format_with_config(&StubGenConfig::default()));
+
+        // With split classes, `Buffer` is imported by the stub of the class only
+        let config = StubGenConfig {
+            split_classes: true,
+            ..Default::default()
+        };
+        let init = module.format_with_config(&config);
+        assert!(!init.contains("import Buffer"));
+        assert!(!init.contains("import sys\n"));
+        assert!(init.contains("from ._cls_Bytes import Bytes as Bytes\n"));
+        let stubs = module.format_class_stubs_with_config(&config);
+        assert_eq!(stubs.len(), 1);
+        assert_eq!(stubs[0].0, "_cls_Bytes");
+        assert_buffer_class(&stubs[0].1);
+    }
+
+    #[test]
+    fn test_exception_with_attributes() {
+        use crate::PyStubType;
+        // `#[gen_stub_pyclass]` on `#[pyclass(extends=PyValueError)]` with `#[pyo3(get)]` fields
+        static GETTERS: [MemberInfo; 2] = [
+            MemberInfo::fixture("lower", i64::type_output),
+            MemberInfo::fixture("upper", i64::type_output),
+        ];
+        static RANGE_ERROR: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            getters: &GETTERS,
+            bases: &[<pyo3::exceptions::PyValueError as PyStubType>::type_output],
+            ..PyClassInfo::fixture("RangeError")
+        };
+
+        let output = ClassDef::from(&RANGE_ERROR).to_string();
+        assert!(output.contains("class RangeError(builtins.ValueError):\n"));
+        assert!(output.contains("    @property\n    def lower(self) -> builtins.int: ...\n"));
+        assert!(output.contains("    @property\n    def upper(self) -> builtins.int: ...\n"));
+    }
+
+    #[test]
+    fn test_runtime_items() -> Result<()> {
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        builder.add_runtime_class(
+            PyClassInfoBuilder::new("Dynamic").getter("x", TypeInfo::builtin("int")),
+        );
+        builder.add_runtime_class(PyClassInfoBuilder::new("Dynamic").module("pkg.sub"));
+        builder.add_runtime_function(
+            PyFunctionInfoBuilder::new("make").parameter("x", TypeInfo::builtin("int")),
+        )?;
+
+        // Classes without a Rust type are told apart by their module and name
+        for module in ["pkg", "pkg.sub"] {
+            let id = ClassId::Runtime {
+                module: module.to_string(),
+                name: "Dynamic".to_string(),
+            };
+            assert!(builder.modules[module].class.contains_key(&id));
+        }
+        let found = builder.find_class_by_name(&PythonClassRef {
+            name: "Dynamic",
+            module: Some("pkg.sub"),
+        })?;
+        assert!(matches!(found, ClassId::Runtime { module, .. } if module == "pkg.sub"));
+
+        let output = builder.modules["pkg"].format_with_config(&StubGenConfig::default());
+        assert!(output.contains("class Dynamic:"));
+        assert!(output.contains("def make(x: builtins.int) -> None: ..."));
+
+        // Functions without overloads cannot share a name
+        assert!(builder
+            .add_runtime_function(PyFunctionInfoBuilder::new("make"))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_place_registered_functions() -> Result<()> {
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        for name in ["moved", "shared", "kept"] {
+            builder.add_runtime_function(PyFunctionInfoBuilder::new(name))?;
+        }
+        builder.place_registered_functions(vec![
+            ("moved", "pkg.sub".to_string()),
+            ("shared", "pkg".to_string()),
+            ("shared", "pkg.sub".to_string()),
+            ("kept", "pkg".to_string()),
+        ]);
+        let functions = |module: &str| -> Vec<&str> {
+            builder.modules[module].function.keys().copied().collect()
+        };
+        assert_eq!(functions("pkg"), ["kept", "shared"]);
+        assert_eq!(functions("pkg.sub"), ["moved", "shared"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_class_by_name() {
+        static FOO_A: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u8>,
+            module: Some("pkg.a"),
+            ..PyClassInfo::fixture("Foo")
+        };
+        static FOO_B: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u16>,
+            module: Some("pkg.b"),
+            ..PyClassInfo::fixture("Foo")
+        };
+
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        builder.add_class(&FOO_A);
+        builder.add_class(&FOO_B);
+
+        let found = builder.find_class_by_name(&PythonClassRef {
+            name: "Foo",
+            module: Some("pkg.b"),
+        });
+        assert_eq!(found.unwrap(), std::any::TypeId::of::<u16>().into());
+
+        let err = builder
+            .find_class_by_name(&PythonClassRef {
+                name: "Foo",
+                module: None,
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("found in several modules"));
+
+        let err = builder
+            .find_class_by_name(&PythonClassRef {
+                name: "Bar",
+                module: None,
+            })
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Class `Bar` to add methods by name is not found"));
+        assert!(matches!(
+            err.downcast_ref::<StubGenError>(),
+            Some(StubGenError::ClassNotFound { module: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_external_bases() {
+        fn external_base() -> TypeInfo {
+            TypeInfo::locally_defined("Base", "other_pkg._core".into())
+        }
+        fn local_base() -> TypeInfo {
+            TypeInfo::locally_defined("Base", "pkg.local".into())
+        }
+        fn third_base() -> TypeInfo {
+            TypeInfo::locally_defined("Base", "third_pkg._core".into())
+        }
+        static CHILD: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u8>,
+            module: Some("pkg"),
+            bases: &[external_base],
+            ..PyClassInfo::fixture("Child")
+        };
+        static BASE: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u16>,
+            module: Some("pkg.local"),
+            subclass: true,
+            ..PyClassInfo::fixture("Base")
+        };
+        static LOCAL_CHILD: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u32>,
+            module: Some("pkg.local"),
+            bases: &[local_base],
+            ..PyClassInfo::fixture("LocalChild")
+        };
+        static THIRD_CHILD: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u64>,
+            module: Some("pkg"),
+            bases: &[third_base],
+            ..PyClassInfo::fixture("ThirdChild")
+        };
+        static EXTERNAL: ExternalClassInfo = ExternalClassInfo {
+            module: "other_pkg",
+            name: "Base",
+            defined_in: Some("other_pkg._core"),
+        };
+
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        builder.add_class(&CHILD);
+        builder.add_class(&BASE);
+        builder.add_class(&LOCAL_CHILD);
+        builder.add_class(&THIRD_CHILD);
+        builder.resolve_external_bases(&[&EXTERNAL]);
+
+        let module = &builder.modules["pkg"];
+        let child = &module.class[&ClassId::Rust(std::any::TypeId::of::<u8>())];
+        assert_eq!(child.bases[0].name, "Base");
+        assert_eq!(child.bases[0].source_module, None);
+        assert!(child.bases[0]
+            .import
+            .contains(&ImportRef::Type(TypeRef::new(
+                "other_pkg".into(),
+                "Base".to_string()
+            ))));
+        // `Base` defined in the module the base class refers to is kept
+        let local_child =
+            &builder.modules["pkg.local"].class[&ClassId::Rust(std::any::TypeId::of::<u32>())];
+        assert_eq!(
+            local_child.bases[0]
+                .source_module
+                .as_ref()
+                .and_then(|module| module.get()),
+            Some("pkg.local")
+        );
+        // `Base` of another package of the same name is kept
+        let third_child = &module.class[&ClassId::Rust(std::any::TypeId::of::<u64>())];
+        assert_eq!(
+            third_child.bases[0]
+                .source_module
+                .as_ref()
+                .and_then(|module| module.get()),
+            Some("third_pkg._core")
+        );
+
+        let stub = module.format_with_config(&StubGenConfig::default());
+        assert!(stub.contains("from other_pkg import Base\n"));
+        assert!(stub.contains("class Child(Base):\n"));
+    }
+
+    #[test]
+    fn test_render_stubs() {
+        let module = |name: &str, doc: &str| Module {
+            name: name.to_string(),
+            default_module_name: "pkg".to_string(),
+            doc: doc.to_string(),
+            ..Default::default()
+        };
+        let stub_info = StubInfo {
+            modules: (0..16)
+                .map(|i| format!("pkg.sub{i}"))
+                .chain(["pkg".to_string(), "pkg.empty".to_string()])
+                .map(|name| {
+                    let doc = if name == "pkg.empty" { "" } else { "Module" };
+                    (name.clone(), module(&name, doc))
+                })
+                .collect(),
+            python_root: PathBuf::from("/tmp"),
+            is_mixed_layout: true,
+            config: StubGenConfig::default(),
+            pyproject_dir: None,
+            default_module_name: "pkg".to_string(),
+            project_name: "pkg".to_string(),
+            doc_links: Default::default(),
+        };
+
+        // Rendering in parallel with the `rayon` feature gives the same stubs
+        let stubs = stub_info.render_stubs();
+        assert_eq!(stubs, stub_info.render_stubs_sequentially());
+        assert_eq!(stubs.len(), 17);
+        assert!(!stubs.contains_key("pkg.empty"));
+    }
+
+    #[test]
+    fn test_pure_layout_rejects_multiple_modules() {
+        // Pure Rust layout should reject multiple modules (whether submodules or top-level)
+        let stub_info = StubInfo {
+            modules: {
+                let mut map = BTreeMap::new();
+                map.insert(
+                    "mymodule".to_string(),
+                    Module {
+                        name: "mymodule".to_string(),
+                        default_module_name: "mymodule".to_string(),
+                        // Add some content so module is not empty
+                        doc: "Test module".to_string(),
+                        ..Default::default()
+                    },
+                );
+                map.insert(
+                    "mymodule.sub".to_string(),
+                    Module {
+                        name: "mymodule.sub".to_string(),
+                        default_module_name: "mymodule".to_string(),
+                        // Add some content so module is not empty
+                        doc: "Test submodule".to_string(),
+                        ..Default::default()
+                    },
+                );
+                map
+            },
+            python_root: PathBuf::from("/tmp"),
+            is_mixed_layout: false,
+            config: StubGenConfig::default(),
+            pyproject_dir: None,
+            default_module_name: "mymodule".to_string(),
+            project_name: "mymodule".to_string(),
+            doc_links: Default::default(),
+        };
+
+        let result = stub_info.generate();
+        assert!(result.is_err());
+        let err_msg = result.unwrap_err().to_string();
+        assert!(
+            err_msg.contains("Pure Rust layout does not support multiple modules or submodules")
+        );
+    }
+
+    #[test]
+    fn test_stub_path() -> Result<()> {
+        let module = |name: &str, submodules: &[&str]| Module {
+            name: name.to_string(),
+            default_module_name: "pkg._core".to_string(),
+            submodules: submodules.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        let mut stub_info = StubInfo {
+            modules: BTreeMap::new(),
+            python_root: PathBuf::from("/tmp/python"),
+            is_mixed_layout: true,
+            config: StubGenConfig::default(),
+            pyproject_dir: None,
+            default_module_name: "pkg._core".to_string(),
+            project_name: "pkg".to_string(),
+            doc_links: Default::default(),
+        };
+        let core = module("pkg._core", &["sub"]);
+        let sub = module("pkg._core.sub", &[]);
+        assert_eq!(
+            stub_info.stub_path("pkg._core.sub", &sub)?,
+            PathBuf::from("/tmp/python/pkg/_core/sub/__init__.pyi")
+        );
+
+        stub_info.config.stub_layout = Some(crate::pyproject::StubLayoutConfig::Modules(
+            [("pkg._core.sub".to_string(), StubLayout::Flat)].into(),
+        ));
+        assert_eq!(
+            stub_info.stub_path("pkg._core.sub", &sub)?,
+            PathBuf::from("/tmp/python/pkg/_core/sub.pyi")
+        );
+        assert_eq!(
+            stub_info.stub_path("pkg._core", &core)?,
+            PathBuf::from("/tmp/python/pkg/_core/__init__.pyi")
+        );
+
+        // A module with submodules has to be a package
+        stub_info.config.stub_layout =
+            Some(crate::pyproject::StubLayoutConfig::All(StubLayout::Flat));
+        assert!(stub_info.stub_path("pkg._core", &core).is_err());
+
+        stub_info.is_mixed_layout = false;
+        stub_info.config.stub_layout = None;
+        assert_eq!(
+            stub_info.stub_path("pkg", &module("pkg", &[]))?,
+            PathBuf::from("/tmp/python/pkg.pyi")
+        );
+        stub_info.config.stub_layout =
+            Some(crate::pyproject::StubLayoutConfig::All(StubLayout::Package));
+        assert_eq!(
+            stub_info.stub_path("pkg", &module("pkg", &[]))?,
+            PathBuf::from("/tmp/python/pkg/__init__.pyi")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_if_changed() {
+        let dir = std::env::temp_dir().join(format!("pyo3_stub_gen_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("write_if_changed.pyi");
+        let _ = fs::remove_file(&dest);
+
+        assert!(write_if_changed(&dest, "a = 1\n").unwrap());
+        assert!(!write_if_changed(&dest, "a = 1\n").unwrap());
+        assert!(write_if_changed(&dest, "a = 2\n").unwrap());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "a = 2\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_stale_layout() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("pyo3_stub_gen_layout_{}", std::process::id()));
+        let package = dir.join("sub").join("__init__.pyi");
+        let flat = dir.join("sub.pyi");
+        fs::create_dir_all(dir.join("sub"))?;
+
+        // Switched to `flat`: the package stub and its empty directory are removed
+        fs::write(&package, "")?;
+        remove_stale_layout(&flat)?;
+        assert!(!dir.join("sub").exists());
+
+        // Switched to `package`: the flat stub is removed
+        fs::write(&flat, "")?;
+        remove_stale_layout(&package)?;
+        assert!(!flat.exists());
+
+        // Other files in the package directory are kept
+        fs::create_dir_all(dir.join("sub"))?;
+        fs::write(&package, "")?;
+        fs::write(dir.join("sub").join("py.typed"), "")?;
+        remove_stale_layout(&flat)?;
+        assert!(!package.exists() && dir.join("sub").join("py.typed").exists());
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_format_content() {
+        let dir = std::env::temp_dir().join(format!("pyo3_stub_gen_format_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let command = ["sed", "-i", "s/1/2/"].map(String::from);
+        let formatted = format_content(&command, &dir.join("mymodule.pyi"), "a = 1\n").unwrap();
+        assert_eq!(formatted, "a = 2\n");
+        // The temporary file is removed, and `dest` is not written
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_formatted() {
+        let dir =
+            std::env::temp_dir().join(format!("pyo3_stub_gen_formatted_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("mymodule.pyi");
+        let config = StubGenConfig {
+            format_command: Some(["sed", "-i", "s/1/2/"].map(String::from).to_vec()),
+            ..Default::default()
+        };
+        let stub_info = StubInfo {
+            modules: BTreeMap::new(),
+            python_root: dir.clone(),
+            is_mixed_layout: true,
+            config,
+            pyproject_dir: None,
+            default_module_name: "mymodule".to_string(),
+            project_name: "mymodule".to_string(),
+            doc_links: Default::default(),
+        };
+
+        assert!(stub_info.write_formatted(&dest, "a = 1\n").unwrap());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "a = 2\n");
+        // The formatted output is compared, not the generated one
+        assert!(!stub_info.write_formatted(&dest, "a = 1\n").unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_format_command_rejects_empty_command() {
+        let result = run_format_command(&[], &[PathBuf::from("/tmp/mymodule.pyi")]);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("`format-command` in [tool.pyo3-stub-gen] must not be empty"));
+    }
+
+    #[test]
+    fn test_class_type_ignore() {
+        static DYNAMIC: PyClassInfo = PyClassInfo {
+            module: Some("pkg"),
+            subclass: true,
+            type_ignored: Some(IgnoreTarget::Specified(&["misc"])),
+            ..PyClassInfo::fixture("Dynamic")
+        };
+        let output = ClassDef::from(&DYNAMIC).to_string();
+        assert!(output.starts_with("class Dynamic:  # type: ignore[misc]\n"));
+
+        let mut module = Module {
+            name: "pkg".to_string(),
+            ..Default::default()
+        };
+        let config = StubGenConfig {
+            ignore_errors: crate::pyproject::IgnoreErrors::Modules(vec!["pkg".to_string()]),
+            ..Default::default()
+        };
+        assert!(module
+            .format_with_config(&config)
+            .contains("# mypy: ignore-errors\n"));
+        module.name = "pkg.other".to_string();
+        assert!(!module
+            .format_with_config(&config)
+            .contains("# mypy: ignore-errors"));
+    }
+
+    #[test]
+    fn test_free_threading() {
+        let module = Module {
+            name: "pkg".to_string(),
+            ..Default::default()
+        };
+        assert!(!module
+            .format_with_config(&StubGenConfig::default())
+            .contains("# free-threading"));
+        let config = StubGenConfig {
+            free_threading: true,
+            ..Default::default()
+        };
+        assert!(module.format_with_config(&config).contains(
+            "# ruff: noqa: E501, F401, F403, F405\n\
+             # free-threading: supported, importing this module does not enable the GIL\n"
+        ));
+    }
+
+    #[test]
+    fn test_module_getattr() -> Result<()> {
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            false,
+            StubGenConfig::default(),
+        );
+        builder.add_module_getattr(&ModuleGetattrInfo {
+            module: "pkg",
+            return_type: "typing.Any",
+            imports: &[],
+        })?;
+        let output = builder.modules["pkg"].to_string();
+        assert!(output.contains("import builtins\n"));
+        assert!(output.contains("import typing\n"));
+        assert!(output.contains("\ndef __getattr__(name: builtins.str) -> typing.Any: ...\n"));
+
+        assert!(builder
+            .add_module_getattr(&ModuleGetattrInfo {
+                module: "pkg",
+                return_type: "builtins.int",
+                imports: &[],
+            })
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_checking_only_imports() -> Result<()> {
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            false,
+            StubGenConfig {
+                type_checking_only_imports: vec!["numpy".to_string()],
+                ..Default::default()
+            },
+        );
+        builder.add_module_getattr(&ModuleGetattrInfo {
+            module: "pkg",
+            return_type: "numpy.ndarray",
+            imports: &["numpy"],
+        })?;
+        builder.guard_type_checking_only_imports();
+        let output = builder.modules["pkg"].to_string();
+        assert!(output.contains("import typing\n"));
+        assert!(output.contains("if typing.TYPE_CHECKING:\n    import numpy\n"));
+        assert!(
+            output.contains("\ndef __getattr__(name: builtins.str) -> \"numpy.ndarray\": ...\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unqualify_builtins() -> Result<()> {
+        fn int_output() -> TypeInfo {
+            TypeInfo::builtin("int")
+        }
+        fn str_output() -> TypeInfo {
+            TypeInfo::builtin("str")
+        }
+        const fn function_info(
+            name: &'static str,
+            module: &'static str,
+            r#return: fn() -> TypeInfo,
+        ) -> PyFunctionInfo {
+            PyFunctionInfo {
+                module: Some(module),
+                ..PyFunctionInfo::fixture(name, r#return)
+            }
+        }
+        static COUNT: PyFunctionInfo = function_info("count", "pkg", int_output);
+        // `str` defined in `pkg` shadows the builtin
+        static STR: PyFunctionInfo = function_info("str", "pkg", str_output);
+        static NAME: PyFunctionInfo = function_info("name", "pkg.sub", str_output);
+
+        let config = StubGenConfig {
+            qualify_builtins: Some(false),
+            ..Default::default()
+        };
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            config.clone(),
+        );
+        for info in [&COUNT, &STR, &NAME] {
+            builder.add_function(info)?;
+        }
+        builder.unqualify_builtins();
+
+        let output = builder.modules["pkg"].format_with_config(&config);
+        assert!(output.contains("def count() -> int: ...\n"));
+        assert!(output.contains("def str() -> builtins.str: ...\n"));
+        assert!(output.contains("import builtins\n"));
+
+        let output = builder.modules["pkg.sub"].format_with_config(&config);
+        assert!(output.contains("def name() -> str: ...\n"));
+        assert!(!output.contains("import builtins\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_module_accessors() {
+        static COUNTER: PyClassInfo = PyClassInfo {
+            struct_id: std::any::TypeId::of::<u8>,
+            module: Some("pkg"),
+            doc: "A counter",
+            has_eq: true,
+            file: "src/lib.rs",
+            line: 10,
+            ..PyClassInfo::fixture("Counter")
+        };
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            false,
+            StubGenConfig::default(),
+        );
+        builder.add_class(&COUNTER);
+        let module = &builder.modules["pkg"];
+        assert_eq!(module.name(), "pkg");
+        assert_eq!(
+            module.classes().map(ClassDef::name).collect::<Vec<_>>(),
+            ["Counter"]
+        );
+        let class = module.class_by_name("Counter").unwrap();
+        assert_eq!(class.doc(), "A counter");
+        assert!(class.methods().any(|(name, _)| name == "__eq__"));
+        assert!(module.class_by_name("Missing").is_none());
+        assert_eq!(module.functions().count(), 0);
+    }
+
+    #[test]
+    fn test_type_param() -> Result<()> {
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            false,
+            StubGenConfig::default(),
+        );
+        builder.add_type_param(&TypeParamInfo {
+            module: "pkg",
+            name: "R",
+            kind: TypeParamKind::TypeVar,
+        })?;
+        builder.add_type_param(&TypeParamInfo {
+            module: "pkg",
+            name: "P",
+            kind: TypeParamKind::ParamSpec,
+        })?;
+        builder.add_typing_helper(&TypingHelperInfo {
+            module: "pkg",
+            code: "class Wrapped(typing.Protocol[P, R]): ...",
+            imports: &["typing"],
+        });
+        let output = builder.modules["pkg"].to_string();
+        assert!(output.contains("import typing\n"));
+        assert!(output.contains(
+            "P = typing.ParamSpec(\"P\")\nR = typing.TypeVar(\"R\")\n\nclass Wrapped(typing.Protocol[P, R]): ...\n"
+        ));
+
+        assert!(builder
+            .add_type_param(&TypeParamInfo {
+                module: "pkg",
+                name: "P",
+                kind: TypeParamKind::TypeVar,
+            })
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_dict() -> Result<()> {
+        use crate::PyStubType;
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            false,
+            StubGenConfig::default(),
+        );
+        let info = TypedDictInfo {
+            module: "pkg",
+            name: "SearchOptions",
+            doc: " Options of `search`\n",
+            fields: &[
+                TypedDictFieldInfo {
+                    name: "limit",
+                    r#type: u32::type_input,
+                    required: true,
+                },
+                TypedDictFieldInfo {
+                    name: "case_sensitive",
+                    r#type: bool::type_input,
+                    required: false,
+                },
+            ],
+        };
+        builder.add_typed_dict(&info)?;
+        let output = builder.modules["pkg"].to_string();
+        assert!(output.contains("import typing\nimport typing_extensions\n"));
+        assert!(output.contains("    \"SearchOptions\",\n"));
+        assert!(output.contains(
+            r#"class SearchOptions(typing.TypedDict):
+    r"""
+    Options of `search`
+    """
+    limit: builtins.int
+    case_sensitive: typing_extensions.NotRequired[builtins.bool]
+"#
+        ));
+        assert!(builder.add_typed_dict(&info).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_named_tuple() -> Result<()> {
+        use crate::PyStubType;
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            false,
+            StubGenConfig::default(),
+        );
+        let info = NamedTupleInfo {
+            module: None,
+            name: "Stats",
+            doc: "",
+            fields: &[
+                NamedTupleFieldInfo {
+                    name: "min",
+                    r#type: f64::type_output,
+                },
+                NamedTupleFieldInfo {
+                    name: "count",
+                    r#type: usize::type_output,
+                },
+            ],
+        };
+        builder.add_named_tuple(&info)?;
+        // Shared by another function returning the same tuple
+        builder.add_named_tuple(&info)?;
+        let output = builder.modules["pkg"].to_string();
+        assert!(output.contains("    \"Stats\",\n"));
+        assert!(output.contains(
+            "class Stats(typing.NamedTuple):\n    min: builtins.float\n    count: builtins.int\n"
+        ));
+
+        let conflicting = NamedTupleInfo {
+            module: Some("pkg"),
+            name: "Stats",
+            doc: "",
+            fields: &[],
+        };
+        assert!(builder.add_named_tuple(&conflicting).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_dict_keys() -> Result<()> {
+        fn config_type() -> TypeInfo {
+            TypeInfo::unqualified("ServerConfig")
+        }
+        static PARAMETERS: [ParameterInfo; 1] = [ParameterInfo {
+            name: "config",
+            kind: ParameterKind::PositionalOrKeyword,
+            type_info: config_type,
+            default: ParameterDefault::None,
+        }];
+        static CONNECT: PyFunctionInfo = PyFunctionInfo {
+            parameters: &PARAMETERS,
+            ..PyFunctionInfo::fixture("connect", no_return_type_output)
+        };
+        const KEYS: &[DictKeyInfo] = &[
+            DictKeyInfo {
+                name: "host",
+                type_repr: "str",
+            },
+            DictKeyInfo {
+                name: "timeout",
+                type_repr: "datetime.timedelta",
+            },
+        ];
+        let info = DictKeysInfo {
+            struct_id: None,
+            module: None,
+            function: "connect",
+            parameter: "config",
+            typed_dict: Some("ServerConfig"),
+            keys: KEYS,
+        };
+
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            false,
+            StubGenConfig::default(),
+        );
+        builder.add_function(&CONNECT)?;
+        builder.add_dict_keys(&info)?;
+        let module = &builder.modules["pkg"];
+        let keys = module.dict_keys(None, "connect", "config").unwrap();
+        assert_eq!(
+            keys.keys
+                .iter()
+                .map(|(name, type_)| (*name, type_.name.as_str()))
+                .collect::<Vec<_>>(),
+            [("host", "str"), ("timeout", "datetime.timedelta")]
+        );
+        let output = module.to_string();
+        assert!(output.contains("import datetime\n"));
+        assert!(output.contains(
+            "class ServerConfig(typing.TypedDict):\n    host: str\n    timeout: datetime.timedelta\n"
+        ));
+        assert!(output.contains("def connect(config: ServerConfig) -> None: ...\n"));
+
+        // The same keys given to another parameter share the `TypedDict`
+        builder.add_dict_keys(&info)?;
+        let conflicting = DictKeysInfo {
+            keys: &KEYS[..1],
+            ..info
+        };
+        assert!(builder.add_dict_keys(&conflicting).is_err());
+        let missing = DictKeysInfo {
+            parameter: "options",
+            typed_dict: None,
+            ..info
+        };
+        assert!(builder.add_dict_keys(&missing).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_type_alias_targets() {
+        fn alias(name: &'static str, type_: TypeInfo) -> TypeAliasDef {
+            TypeAliasDef {
+                name,
+                type_,
+                doc: "",
+                py_version: None,
+                fallback: None,
+            }
+        }
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        let module = builder.get_module(Some("pkg"));
+        module.type_aliases.insert(
+            "Points",
+            alias("Points", TypeInfo::locally_defined("Point", "pkg".into())),
+        );
+        let err = builder.check_type_alias_targets().unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("type alias `pkg.Points` refers to `pkg.Point`"));
+
+        let module = builder.get_module(Some("pkg"));
+        module
+            .type_aliases
+            .insert("Point", alias("Point", TypeInfo::builtin("int")));
+        // Types of modules without stubs cannot be checked
+        module.type_aliases.insert(
+            "Other",
+            alias(
+                "Other",
+                TypeInfo::locally_defined("Other", "pkg.pure_python".into()),
+            ),
+        );
+        assert!(builder.check_type_alias_targets().is_ok());
+
+        // Names defined by typing helpers, epilogues and verbatim `__all__` entries are known as well
+        let module = builder.get_module(Some("pkg"));
+        for name in ["Helper", "Epilogue", "Verbatim"] {
+            module.type_aliases.insert(
+                name,
+                alias(name, TypeInfo::locally_defined(name, "pkg.sub".into())),
+            );
+        }
+        let sub = builder.get_module(Some("pkg.sub"));
+        sub.typing_helpers.push(TypingHelperDef {
+            code: "Helper = typing.TypeVar(\"Helper\")".to_string(),
+            imports: Default::default(),
+        });
+        sub.epilogues.push(TypingHelperDef {
+            code: "class Epilogue: ...".to_string(),
+            imports: Default::default(),
+        });
+        sub.verbatim_all_entries.insert("Verbatim".to_string());
+        assert!(builder.check_type_alias_targets().is_ok());
+    }
+
+    #[test]
+    fn test_module_doc_template() -> Result<()> {
+        assert_eq!(
+            format_module_doc(
+                "{{{name}}} of {project} {version}: {module}",
+                "pkg.sub",
+                "pkg",
+                "1.0"
+            )?,
+            "{sub} of pkg 1.0: pkg.sub"
+        );
+        assert!(format_module_doc("{unknown}", "pkg", "pkg", "1.0").is_err());
+        assert!(format_module_doc("{module", "pkg", "pkg", "1.0").is_err());
+
+        let mut builder = StubInfoBuilder::from_project_root(
+            "pkg".to_string(),
+            "/tmp".into(),
+            true,
+            StubGenConfig::default(),
+        );
+        builder.project_version = Some("1.0".to_string());
+        builder.get_module(Some("pkg")).doc = "Documented".to_string();
+        builder
+            .get_module(Some("pkg.sub"))
+            .submodules
+            .insert("deep".to_string());
+        builder.get_module(Some("pkg.empty"));
+        builder.apply_module_doc_template("Module `{module}` of {project} {version}")?;
+        assert_eq!(builder.modules["pkg"].doc, "Documented");
+        assert_eq!(
+            builder.modules["pkg.sub"].doc,
+            "Module `pkg.sub` of pkg 1.0"
+        );
+        assert!(builder.modules["pkg.empty"].doc.is_empty());
+        Ok(())
+    }
 }
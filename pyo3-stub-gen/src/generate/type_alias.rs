@@ -1,17 +1,26 @@
 use std::{collections::HashSet, fmt};
 
 use crate::{
-    generate::{docstring, Import},
+    generate::{
+        docstring, is_complementary_py_version, py_version_at_least, py_version_condition,
+        write_indented, Import,
+    },
     stub_type::ImportRef,
     type_info::TypeAliasInfo,
     TypeInfo,
 };
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TypeAliasDef {
     pub name: &'static str,
     pub type_: TypeInfo,
     pub doc: &'static str,
+    /// Python versions the alias is available on, e.g. `">=3.12"`.
+    /// The alias is written in an `if sys.version_info >= ...:` block.
+    pub py_version: Option<&'static str>,
+    /// Definition for the complementary versions written in the `else` block, e.g. for `"<3.12"`
+    pub fallback: Option<Box<TypeAliasDef>>,
 }
 
 impl From<&TypeAliasInfo> for TypeAliasDef {
@@ -20,6 +29,8 @@ impl From<&TypeAliasInfo> for TypeAliasDef {
             name: info.name,
             type_: (info.r#type)(),
             doc: info.doc,
+            py_version: info.py_version,
+            fallback: None,
         }
     }
 }
@@ -28,7 +39,14 @@ impl Import for TypeAliasDef {
     fn import(&self) -> HashSet<ImportRef> {
         // Only return imports from the type itself
         // TypeAlias will be handled conditionally by Module
-        self.type_.import.clone()
+        let mut import = self.type_.import.clone();
+        if self.py_version.is_some() {
+            import.insert("sys".into());
+        }
+        if let Some(fallback) = &self.fallback {
+            import.extend(fallback.import());
+        }
+        import
     }
 }
 
@@ -40,11 +58,82 @@ impl fmt::Display for TypeAliasDef {
 
 impl TypeAliasDef {
     /// Format type alias with module-qualified names and syntax based on configuration
+    ///
+    /// An alias with `py_version` is written in an `if sys.version_info ...:` block, followed by the `else` block of `fallback`.
+    /// The `type` statement is used in the block if the condition ensures Python 3.12+.
     pub fn fmt_with_config(
         &self,
         target_module: &str,
         f: &mut fmt::Formatter,
         use_type_statement: bool,
+    ) -> fmt::Result {
+        let Some(py_version) = self.py_version else {
+            return self.fmt_definition(target_module, f, use_type_statement);
+        };
+        writeln!(f, "if {}:", py_version_condition(py_version))?;
+        write_indented(
+            f,
+            &Definition(
+                self,
+                target_module,
+                self.uses_type_statement(use_type_statement),
+            )
+            .to_string(),
+        )?;
+        if let Some(fallback) = &self.fallback {
+            writeln!(f, "else:")?;
+            write_indented(
+                f,
+                &Definition(
+                    fallback,
+                    target_module,
+                    fallback.uses_type_statement(use_type_statement),
+                )
+                .to_string(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Whether the `type` statement is used instead of `TypeAlias`
+    pub(crate) fn uses_type_statement(&self, use_type_statement: bool) -> bool {
+        use_type_statement
+            || self
+                .py_version
+                .is_some_and(|py_version| py_version_at_least(py_version, 3, 12))
+    }
+
+    /// Whether `TypeAlias` is used by this alias or its fallback
+    pub(crate) fn uses_type_alias_annotation(&self, use_type_statement: bool) -> bool {
+        !self.uses_type_statement(use_type_statement)
+            || self
+                .fallback
+                .as_ref()
+                .is_some_and(|fallback| fallback.uses_type_alias_annotation(use_type_statement))
+    }
+
+    /// Set `other` of the same name as the fallback if their `py_version` are complementary like `">=3.12"` and `"<3.12"`.
+    /// Returns `other` back otherwise.
+    pub(crate) fn merge_fallback(&mut self, other: TypeAliasDef) -> Result<(), TypeAliasDef> {
+        match (self.py_version, other.py_version) {
+            (Some(a), Some(b)) if self.fallback.is_none() && is_complementary_py_version(a, b) => {
+                self.fallback = Some(Box::new(other));
+                Ok(())
+            }
+            (Some(a), Some(b)) if self.fallback.is_none() && is_complementary_py_version(b, a) => {
+                let primary = std::mem::replace(self, other);
+                self.fallback = Some(Box::new(primary));
+                Ok(())
+            }
+            _ => Err(other),
+        }
+    }
+
+    fn fmt_definition(
+        &self,
+        target_module: &str,
+        f: &mut fmt::Formatter,
+        use_type_statement: bool,
     ) -> fmt::Result {
         let qualified_type = self.type_.qualified_for_module(target_module);
 
@@ -70,6 +159,16 @@ impl TypeAliasDef {
     }
 }
 
+/// Formats the definition of an alias into a string, which is indented in `if` blocks
+struct Definition<'a>(&'a TypeAliasDef, &'a str, bool);
+
+impl fmt::Display for Definition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_definition(self.1, f, self.2)?;
+        writeln!(f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,6 +180,8 @@ mod tests {
             name: "MyAlias",
             type_: TypeInfo::builtin("int"),
             doc: "",
+            py_version: None,
+            fallback: None,
         };
         let mut output = String::new();
         write!(
@@ -98,6 +199,8 @@ mod tests {
             name: "MyAlias",
             type_: TypeInfo::builtin("int"),
             doc: "",
+            py_version: None,
+            fallback: None,
         };
         let mut output = String::new();
         write!(
@@ -0,0 +1,83 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    generate::Import,
+    stub_type::ImportRef,
+    type_info::{TypeParamInfo, TypeParamKind},
+};
+
+/// Module-level declaration of a type parameter, e.g. `P = typing.ParamSpec("P")`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TypeParamDef {
+    pub name: &'static str,
+    pub kind: TypeParamKind,
+    /// Module the constructor is taken from, `typing` or `typing_extensions` selected by `python-target`
+    pub typing_module: &'static str,
+}
+
+impl From<&TypeParamInfo> for TypeParamDef {
+    fn from(info: &TypeParamInfo) -> Self {
+        Self {
+            name: info.name,
+            kind: info.kind,
+            typing_module: "typing",
+        }
+    }
+}
+
+impl Import for TypeParamDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        HashSet::from([self.typing_module.into()])
+    }
+}
+
+impl TypeParamDef {
+    /// Name of the constructor in `typing`, `TypeVar` or `ParamSpec`
+    pub fn constructor(&self) -> &'static str {
+        match self.kind {
+            TypeParamKind::TypeVar => "TypeVar",
+            TypeParamKind::ParamSpec => "ParamSpec",
+        }
+    }
+}
+
+impl fmt::Display for TypeParamDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} = {}.{}(\"{}\")",
+            self.name,
+            self.typing_module,
+            self.constructor(),
+            self.name
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_type_param() {
+        let def = TypeParamDef {
+            name: "P",
+            kind: TypeParamKind::ParamSpec,
+            typing_module: "typing",
+        };
+        assert_eq!(def.to_string(), "P = typing.ParamSpec(\"P\")\n");
+        let def = TypeParamDef {
+            name: "R",
+            kind: TypeParamKind::TypeVar,
+            typing_module: "typing",
+        };
+        assert_eq!(def.to_string(), "R = typing.TypeVar(\"R\")\n");
+        let def = TypeParamDef {
+            name: "P",
+            kind: TypeParamKind::ParamSpec,
+            typing_module: "typing_extensions",
+        };
+        assert_eq!(def.to_string(), "P = typing_extensions.ParamSpec(\"P\")\n");
+    }
+}
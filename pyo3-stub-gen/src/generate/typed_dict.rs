@@ -0,0 +1,76 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    generate::{docstring, indent, normalize_docstring, Import},
+    stub_type::ImportRef,
+    type_info::TypedDictInfo,
+    TypeInfo,
+};
+
+/// `TypedDict` declared by [crate::typed_dict!], e.g. to annotate `**kwargs: Unpack[Options]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TypedDictDef {
+    pub name: &'static str,
+    pub doc: &'static str,
+    /// Keys and their value types, wrapped by `typing_extensions.NotRequired` for optional keys
+    pub fields: Vec<(&'static str, TypeInfo)>,
+}
+
+impl From<&TypedDictInfo> for TypedDictDef {
+    fn from(info: &TypedDictInfo) -> Self {
+        let doc = if info.doc.is_empty() {
+            ""
+        } else {
+            Box::leak(normalize_docstring(info.doc).into_boxed_str())
+        };
+        let fields = info
+            .fields
+            .iter()
+            .map(|field| {
+                let type_ = (field.r#type)();
+                let type_ = if field.required {
+                    type_
+                } else {
+                    TypeInfo::not_required(type_)
+                };
+                (field.name, type_)
+            })
+            .collect();
+        Self {
+            name: info.name,
+            doc,
+            fields,
+        }
+    }
+}
+
+impl Import for TypedDictDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        let mut import: HashSet<ImportRef> = HashSet::from(["typing".into()]);
+        for (_, type_) in &self.fields {
+            import.extend(type_.import.iter().cloned());
+        }
+        import
+    }
+}
+
+impl TypedDictDef {
+    /// Format the `TypedDict` class with module-qualified type names
+    pub fn fmt_for_module(&self, target_module: &str, f: &mut fmt::Formatter) -> fmt::Result {
+        let indent = indent();
+        writeln!(f, "class {}(typing.TypedDict):", self.name)?;
+        docstring::write_docstring(f, self.doc, indent)?;
+        for (name, type_) in &self.fields {
+            writeln!(
+                f,
+                "{indent}{name}: {}",
+                type_.qualified_for_module(target_module)
+            )?;
+        }
+        if self.doc.is_empty() && self.fields.is_empty() {
+            writeln!(f, "{indent}...")?;
+        }
+        writeln!(f)
+    }
+}
@@ -0,0 +1,166 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    generate::Import,
+    stub_type::ImportRef,
+    type_info::{ModuleEpilogueInfo, PyClassVerbatimInfo, TypingHelperInfo},
+};
+
+/// Typing-only definitions written in Python syntax, e.g. `TypeVar` or `Protocol`,
+/// or hand-written content registered by [crate::class_verbatim!] and [crate::module_epilogue!]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TypingHelperDef {
+    pub code: String,
+    pub imports: HashSet<ImportRef>,
+}
+
+impl From<&TypingHelperInfo> for TypingHelperDef {
+    fn from(info: &TypingHelperInfo) -> Self {
+        Self {
+            code: dedent(info.code),
+            imports: info.imports.iter().map(|&module| module.into()).collect(),
+        }
+    }
+}
+
+impl From<&PyClassVerbatimInfo> for TypingHelperDef {
+    fn from(info: &PyClassVerbatimInfo) -> Self {
+        Self {
+            code: dedent(info.code),
+            imports: info.imports.iter().map(|&module| module.into()).collect(),
+        }
+    }
+}
+
+impl From<&ModuleEpilogueInfo> for TypingHelperDef {
+    fn from(info: &ModuleEpilogueInfo) -> Self {
+        Self {
+            code: dedent(info.code),
+            imports: info.imports.iter().map(|&module| module.into()).collect(),
+        }
+    }
+}
+
+impl TypingHelperDef {
+    /// Names defined at the top level of the code,
+    /// e.g. `T` of `T = typing.TypeVar("T")` and `SupportsRead` of `class SupportsRead(...)`
+    pub(crate) fn defined_names(&self) -> impl Iterator<Item = &str> {
+        self.code.lines().filter_map(|line| {
+            if line.starts_with(char::is_whitespace) {
+                return None;
+            }
+            let (line, is_definition) = ["class ", "def ", "async def ", "type "]
+                .iter()
+                .find_map(|keyword| line.strip_prefix(keyword))
+                .map_or((line, false), |rest| (rest.trim_start(), true));
+            let end = line
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(line.len());
+            let (name, rest) = line.split_at(end);
+            if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+                return None;
+            }
+            // `X = ...` or `X: ...`, but not `X == ...` or `else:`
+            let rest = rest.trim_start();
+            let is_assignment = (rest.starts_with('=') && !rest.starts_with("=="))
+                || (rest.starts_with(':') && !rest[1..].trim().is_empty());
+            (is_definition || is_assignment).then_some(name)
+        })
+    }
+}
+
+impl Import for TypingHelperDef {
+    fn import(&self) -> HashSet<ImportRef> {
+        self.imports.clone()
+    }
+}
+
+impl fmt::Display for TypingHelperDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in self.code.lines() {
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Remove common leading whitespace and surrounding blank lines
+/// so that the definitions can be written in an indented raw string literal
+fn dedent(code: &str) -> String {
+    let lines: Vec<&str> = code
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .collect();
+    let lines = match lines.iter().rposition(|line| !line.trim().is_empty()) {
+        Some(last) => &lines[..=last],
+        None => return String::new(),
+    };
+    let min_indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(min_indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dedent() {
+        let info = TypingHelperInfo {
+            module: "my_module",
+            code: r#"
+            T = typing.TypeVar("T")
+
+            class SupportsRead(typing.Protocol[T]):
+                def read(self, n: int = -1, /) -> T: ...
+            "#,
+            imports: &["typing"],
+        };
+        let def = TypingHelperDef::from(&info);
+        assert_eq!(
+            def.to_string(),
+            r#"T = typing.TypeVar("T")
+
+class SupportsRead(typing.Protocol[T]):
+    def read(self, n: int = -1, /) -> T: ...
+"#
+        );
+        assert_eq!(def.import(), HashSet::from(["typing".into()]));
+        assert_eq!(
+            def.defined_names().collect::<Vec<_>>(),
+            vec!["T", "SupportsRead"]
+        );
+    }
+
+    #[test]
+    fn test_defined_names() {
+        let def = TypingHelperDef {
+            code: dedent(
+                r#"
+                Alias: typing.TypeAlias = int
+                type Pair[T] = tuple[T, T]
+                def helper(x: int) -> int: ...
+                if sys.version_info >= (3, 11):
+                    Nested = int
+                else:
+                    Nested = str
+                assert x == 1
+                "#,
+            ),
+            imports: HashSet::new(),
+        };
+        assert_eq!(
+            def.defined_names().collect::<Vec<_>>(),
+            vec!["Alias", "Pair", "helper"]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::{collections::HashSet, fmt};
 use crate::{generate::Import, stub_type::ImportRef, type_info::PyVariableInfo, TypeInfo};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct VariableDef {
     pub name: &'static str,
     pub type_: TypeInfo,
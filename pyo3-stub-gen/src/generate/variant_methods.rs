@@ -24,6 +24,7 @@ pub(super) fn get_variant_methods(
                 source_module: None,
                 import: HashSet::new(),
                 type_refs: HashMap::new(),
+                any_fallback: None,
             },
             doc: "",
             r#type: MethodType::New,
@@ -42,14 +42,18 @@
 //!                 r#type: <String as ::pyo3_stub_gen::PyStubType>::type_output,
 //!                 doc: "Name docstring",
 //!                 default: None,
+//!                 default_symbol: None,
 //!                 deprecated: None,
+//!                 cached: false,
 //!             },
 //!             MemberInfo {
 //!                 name: "description",
 //!                 r#type: <Option<String> as ::pyo3_stub_gen::PyStubType>::type_output,
 //!                 doc: "Description docstring",
 //!                 default: None,
+//!                 default_symbol: None,
 //!                 deprecated: None,
+//!                 cached: false,
 //!             },
 //!         ],
 //!
@@ -68,6 +72,24 @@
 //!         has_str: false,
 //!         // Decorated with `#[pyclass(subclass)]`
 //!         subclass: false,
+//!         // Decorated with `#[gen_stub(buffer)]`
+//!         buffer: false,
+//!         // Decorated with `#[gen_stub(picklable)]`
+//!         picklable: false,
+//!         // Decorated with `#[gen_stub(slots)]`
+//!         slots: None,
+//!         // Decorated with `#[gen_stub(getitem(...), setitem(...), delitem(...))]`
+//!         item_access: &[],
+//!         // Decorated with `#[gen_stub(iterator(item = "..."))]`
+//!         iterator: None,
+//!         type_ignored: None,
+//!         // Decorated with `#[gen_stub(dataclass_transform(...))]`
+//!         dataclass_transform: None,
+//!
+//!         // Source location used to order items by definition position
+//!         file: file!(),
+//!         line: line!(),
+//!         column: column!(),
 //!     }
 //! }
 //! ```
@@ -107,6 +129,9 @@
 //! However, we need to gather these [type_info::PyClassInfo] definitions to generate stub files,
 //! and the above [inventory::submit] is for it.
 //!
+//! Code generators registering items at runtime can use [type_info::PyClassInfoBuilder] and [type_info::PyFunctionInfoBuilder]
+//! instead of writing these `&'static` structs by hand, and give them to [StubInfo::from_pyproject_toml_with].
+//!
 //! Gather type information into [StubInfo]
 //! ----------------------------------------
 //! [inventory] crate provides a mechanism to gather [inventory::submit]ted information when the library is loaded.
@@ -179,40 +204,154 @@
 
 pub use inventory;
 pub use pyo3_stub_gen_derive as derive; // re-export to use in generated code
+pub use pyo3_stub_gen_derive::PyStubType;
 
 pub mod docgen;
+pub mod error;
 pub mod exception;
 pub mod generate;
+pub mod maturin;
 pub mod pyproject;
 pub mod rule_name;
 pub mod runtime;
 mod stub_type;
+pub mod testing;
 pub mod type_info;
 pub mod util;
 
-pub use generate::StubInfo;
+pub use error::StubGenError;
+pub use generate::{GenerateResult, StubInfo};
 pub use pyproject::StubGenConfig;
+pub use stub_type::PyCallable;
 pub use stub_type::{ImportKind, ImportRef, ModuleRef, PyStubType, TypeIdentifierRef, TypeInfo};
+pub use stub_type::{PyAsyncIterable, PyAsyncIterator};
+#[doc(hidden)]
+pub use stub_type::{RegisteredStubType, StubTypeFor, StubTypeOf};
+pub use stub_type::{TzAware, TzNaive};
+
+/// Result of [StubInfo] and [pyproject] functions, see [StubGenError]
+pub type Result<T> = std::result::Result<T, StubGenError>;
+
+/// Submit type information to [inventory] like [inventory::submit!],
+/// but compiled out with `--cfg pyo3_stub_gen_disabled`.
+///
+/// All macros of this crate submit through it, and [gen_function_from_python!](crate::derive::gen_function_from_python)
+/// or [gen_methods_from_python!](crate::derive::gen_methods_from_python) can be wrapped by it instead of [inventory::submit!]
+/// to keep release builds free of stub metadata:
+///
+/// ```rust,ignore
+/// pyo3_stub_gen::submit! {
+///     gen_function_from_python! {
+///         r#"
+///             def sum(v: list[int]) -> int: ...
+///         "#
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! submit {
+    ($($info:tt)*) => {
+        $crate::stub_metadata! {
+            $crate::inventory::submit! { $($info)* }
+        }
+    };
+}
 
-pub type Result<T> = anyhow::Result<T>;
+/// Items submitting stub metadata, expanded by the macros of this crate.
+///
+/// The cfg is evaluated here rather than in the proc-macro crate,
+/// since `RUSTFLAGS` may not reach proc-macros built for the host.
+#[doc(hidden)]
+#[cfg(not(pyo3_stub_gen_disabled))]
+#[macro_export]
+macro_rules! stub_metadata {
+    ($($tokens:tt)*) => {
+        $($tokens)*
+    };
+}
+
+/// Items submitting stub metadata, compiled out with `--cfg pyo3_stub_gen_disabled`.
+#[doc(hidden)]
+#[cfg(pyo3_stub_gen_disabled)]
+#[macro_export]
+macro_rules! stub_metadata {
+    ($($tokens:tt)*) => {};
+}
 
-/// Create a function to initialize [StubInfo] from `pyproject.toml` in `CARGO_MANIFEST_DIR`.
+/// Create a function to initialize [StubInfo] from `pyproject.toml`.
+///
+/// `pyproject.toml` is searched from `CARGO_MANIFEST_DIR` upward to the workspace root by [pyproject::find_pyproject_toml],
+/// so that it can be placed at the root of a Cargo workspace.
+/// Its location can also be given explicitly relative to `CARGO_MANIFEST_DIR`, as a directory or a file:
+///
+/// ```rust
+/// pyo3_stub_gen::define_stub_info_gatherer!(stub_info, pyproject = "../..");
+/// ```
 ///
-/// If `pyproject.toml` is in another place, you need to create a function to call [StubInfo::from_pyproject_toml] manually.
 /// This must be placed in your PyO3 library crate, i.e. same crate where [inventory::submit]ted,
 /// not in `gen_stub` executables due to [inventory] mechanism.
-///
 #[macro_export]
 macro_rules! define_stub_info_gatherer {
     ($function_name:ident) => {
         /// Auto-generated function to gather information to generate stub files
         pub fn $function_name() -> $crate::Result<$crate::StubInfo> {
             let manifest_dir: &::std::path::Path = env!("CARGO_MANIFEST_DIR").as_ref();
-            $crate::StubInfo::from_pyproject_toml(manifest_dir.join("pyproject.toml"))
+            $crate::StubInfo::from_pyproject_toml($crate::pyproject::find_pyproject_toml(
+                manifest_dir,
+            )?)
+        }
+    };
+    ($function_name:ident, pyproject = $path:expr) => {
+        /// Auto-generated function to gather information to generate stub files
+        pub fn $function_name() -> $crate::Result<$crate::StubInfo> {
+            let manifest_dir: &::std::path::Path = env!("CARGO_MANIFEST_DIR").as_ref();
+            let mut path = manifest_dir.join($path);
+            if path.is_dir() {
+                path.push("pyproject.toml");
+            }
+            $crate::StubInfo::from_pyproject_toml(path)
         }
     };
 }
 
+/// Assert that the stub of a module rendered in memory is the same as a checked-in snapshot file.
+///
+/// The first argument is [StubInfo] or `Result<StubInfo>` returned by the function defined with [define_stub_info_gatherer!].
+/// The second one is the path of the snapshot relative to `CARGO_MANIFEST_DIR`.
+/// The default module is compared unless another one is given by `module = "..."`.
+///
+/// ```rust,ignore
+/// #[test]
+/// fn stub_snapshot() {
+///     pyo3_stub_gen::assert_stub_snapshot!(my_crate::stub_info(), "tests/snapshots/my_module.pyi");
+///     pyo3_stub_gen::assert_stub_snapshot!(
+///         my_crate::stub_info(),
+///         "tests/snapshots/my_module.sub.pyi",
+///         module = "my_module.sub"
+///     );
+/// }
+/// ```
+///
+/// The assertion fails with a diff if they differ. Run the test with [testing::BLESS_ENV] set,
+/// i.e. `PYO3_STUB_GEN_BLESS=1 cargo test`, to create or update the snapshot.
+#[macro_export]
+macro_rules! assert_stub_snapshot {
+    ($stub_info:expr, $snapshot:expr $(,)?) => {
+        $crate::testing::assert_stub_snapshot_impl(
+            &$stub_info,
+            None,
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($snapshot),
+        )
+    };
+    ($stub_info:expr, $snapshot:expr, module = $module:expr $(,)?) => {
+        $crate::testing::assert_stub_snapshot_impl(
+            &$stub_info,
+            Some($module),
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($snapshot),
+        )
+    };
+}
+
 /// Add module-level documention using interpolation of runtime expressions.
 /// The first argument `module_doc!` receives is the full module name;
 /// the second and followings are a format string, same to `format!`.
@@ -227,7 +366,7 @@ macro_rules! define_stub_info_gatherer {
 #[macro_export]
 macro_rules! module_doc {
     ($module:literal, $($fmt:tt)+) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ModuleDocInfo {
                 module: $module,
                 doc: {
@@ -251,7 +390,7 @@ macro_rules! module_doc {
 #[macro_export]
 macro_rules! module_variable {
     ($module:expr, $name:expr, $ty:ty) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::PyVariableInfo{
                 name: $name,
                 module: $module,
@@ -261,7 +400,7 @@ macro_rules! module_variable {
         }
     };
     ($module:expr, $name:expr, $ty:ty, $value:expr) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::PyVariableInfo{
                 name: $name,
                 module: $module,
@@ -295,8 +434,17 @@ macro_rules! module_variable {
 ///
 /// // With documentation
 /// pyo3_stub_gen::type_alias!("module.name", MyAlias = TypeA | TypeB, "Documentation string");
+///
+/// // Only on some Python versions, written in an `if sys.version_info >= (3, 12):` block
+/// pyo3_stub_gen::type_alias!("module.name", #[gen_stub(py_version = ">=3.12")] MyAlias = TypeA | TypeB);
+///
+/// // Private, written as `_MyAlias` and excluded from `__all__`
+/// pyo3_stub_gen::type_alias!("module.name", #[private] MyAlias = TypeA | TypeB);
 /// ```
 ///
+/// An alias for the complementary versions, e.g. `"<3.12"`, of the same name is written in the `else` block.
+/// Declare it in a different Rust module since both define a struct of the name.
+///
 /// # Examples
 ///
 /// ```rust
@@ -327,8 +475,8 @@ macro_rules! module_variable {
 /// ```
 #[macro_export]
 macro_rules! type_alias {
-    // Pattern 1: Union types with docstring - must come first
-    ($module:expr, $name:ident = $($base:ty)|+, $doc:expr) => {
+    // Internal: union types with the Python versions the alias is available on and the Python name
+    (@alias $module:expr, $py_version:expr, $py_name:expr, $name:ident = $($base:ty)|+, $doc:expr) => {
         /// Type alias generated by `type_alias!` macro.
         pub struct $name;
 
@@ -342,7 +490,7 @@ macro_rules! type_alias {
         }
 
         impl $crate::runtime::PyTypeAlias for $name {
-            const NAME: &'static str = stringify!($name);
+            const NAME: &'static str = $py_name;
             const MODULE: &'static str = $module;
 
             fn create_type_object(py: ::pyo3::Python<'_>) -> ::pyo3::PyResult<::pyo3::Bound<'_, ::pyo3::PyAny>> {
@@ -353,16 +501,22 @@ macro_rules! type_alias {
             }
         }
 
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::TypeAliasInfo {
-                name: stringify!($name),
+                name: $py_name,
                 module: $module,
                 r#type: <$name as $crate::PyStubType>::type_output,
                 doc: $doc,
+                py_version: $py_version,
             }
         }
     };
 
+    // Pattern 1: Union types with docstring
+    ($module:expr, $name:ident = $($base:ty)|+, $doc:expr) => {
+        $crate::type_alias!(@alias $module, ::std::option::Option::None, stringify!($name), $name = $($base)|+, $doc);
+    };
+
     // Pattern 2: Union types without docstring (backward compatible)
     ($module:expr, $name:ident = $($base:ty)|+) => {
         $crate::type_alias!($module, $name = $($base)|+, "");
@@ -391,12 +545,13 @@ macro_rules! type_alias {
             }
         }
 
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::TypeAliasInfo {
                 name: stringify!($name),
                 module: $module,
                 r#type: <$name as $crate::PyStubType>::type_output,
                 doc: $doc,
+                py_version: ::std::option::Option::None,
             }
         }
     };
@@ -405,6 +560,261 @@ macro_rules! type_alias {
     ($module:expr, $name:ident = $ty:ty) => {
         $crate::type_alias!($module, $name = $ty, "");
     };
+
+    // Pattern 5: Alias available only on some Python versions, with docstring
+    ($module:expr, #[gen_stub(py_version = $py_version:literal)] $name:ident = $($base:ty)|+, $doc:expr) => {
+        $crate::type_alias!(@alias $module, ::std::option::Option::Some($py_version), stringify!($name), $name = $($base)|+, $doc);
+    };
+
+    // Pattern 6: Alias available only on some Python versions, without docstring
+    ($module:expr, #[gen_stub(py_version = $py_version:literal)] $name:ident = $($base:ty)|+) => {
+        $crate::type_alias!(@alias $module, ::std::option::Option::Some($py_version), stringify!($name), $name = $($base)|+, "");
+    };
+
+    // Pattern 7: Private alias named with a leading `_`, excluded from `__all__`, with docstring
+    ($module:expr, #[private] $name:ident = $($base:ty)|+, $doc:expr) => {
+        $crate::type_alias!(@alias $module, ::std::option::Option::None, concat!("_", stringify!($name)), $name = $($base)|+, $doc);
+    };
+
+    // Pattern 8: Private alias named with a leading `_`, excluded from `__all__`, without docstring
+    ($module:expr, #[private] $name:ident = $($base:ty)|+) => {
+        $crate::type_alias!(@alias $module, ::std::option::Option::None, concat!("_", stringify!($name)), $name = $($base)|+, "");
+    };
+}
+
+/// Declare a module-level `ParamSpec` as a Rust marker type.
+///
+/// The marker type is used as the parameters of [PyCallable] to forward the signature of a callback,
+/// and `P = typing.ParamSpec("P")` is written into the stub file of the module.
+/// Other modules referring to it import it from the module.
+///
+/// ```rust
+/// use pyo3::prelude::*;
+/// use pyo3_stub_gen::{derive::*, PyCallable};
+///
+/// pyo3_stub_gen::param_spec!("my_module", P);
+/// pyo3_stub_gen::type_var!("my_module", R);
+///
+/// // def traced(f: collections.abc.Callable[P, R]) -> collections.abc.Callable[P, R]: ...
+/// #[gen_stub_pyfunction(module = "my_module")]
+/// #[pyfunction]
+/// fn traced(f: PyCallable<P, R>) -> PyCallable<P, R> {
+///     f
+/// }
+/// ```
+#[macro_export]
+macro_rules! param_spec {
+    ($module:expr, $name:ident) => {
+        $crate::__type_param!($module, $name, ParamSpec);
+    };
+}
+
+/// Declare a module-level `TypeVar` as a Rust marker type.
+///
+/// `T = typing.TypeVar("T")` is written into the stub file of the module.
+/// See [param_spec!] for an example.
+#[macro_export]
+macro_rules! type_var {
+    ($module:expr, $name:ident) => {
+        $crate::__type_param!($module, $name, TypeVar);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __type_param {
+    ($module:expr, $name:ident, $kind:ident) => {
+        pub struct $name;
+
+        impl $crate::PyStubType for $name {
+            fn type_output() -> $crate::TypeInfo {
+                $crate::TypeInfo::locally_defined(stringify!($name), $module.into())
+            }
+        }
+
+        $crate::submit! {
+            $crate::type_info::TypeParamInfo {
+                module: $module,
+                name: stringify!($name),
+                kind: $crate::type_info::TypeParamKind::$kind,
+            }
+        }
+    };
+}
+
+/// Declare a module-level `TypedDict` as a Rust marker type.
+///
+/// This is mainly used to annotate `**kwargs` taking options by `#[gen_stub(kwargs_unpack = "...")]`,
+/// so that the keys are completed by editors. Keys marked by `#[not_required]` may be omitted.
+/// Doc comments are written as the docstring of the class.
+///
+/// ```rust
+/// use pyo3::{prelude::*, types::PyDict};
+/// use pyo3_stub_gen::derive::*;
+///
+/// pyo3_stub_gen::typed_dict!(
+///     "my_module",
+///     /// Options of `search`
+///     SearchOptions {
+///         limit: usize,
+///         #[not_required]
+///         case_sensitive: bool,
+///     }
+/// );
+///
+/// // def search(query: builtins.str, **options: typing_extensions.Unpack[SearchOptions]) -> builtins.int: ...
+/// #[gen_stub_pyfunction(module = "my_module")]
+/// #[pyfunction]
+/// #[pyo3(signature = (query, **options))]
+/// #[gen_stub(kwargs_unpack = "SearchOptions")]
+/// fn search(query: &str, options: Option<&Bound<'_, PyDict>>) -> usize {
+///     query.len() + options.map_or(0, |options| options.len())
+/// }
+/// ```
+#[macro_export]
+macro_rules! typed_dict {
+    ($module:expr, $(#[doc = $doc:literal])* $name:ident { $($(#[$marker:ident])? $field:ident : $ty:ty),* $(,)? }) => {
+        $(#[doc = $doc])*
+        pub struct $name;
+
+        impl $crate::PyStubType for $name {
+            fn type_output() -> $crate::TypeInfo {
+                $crate::TypeInfo::locally_defined(stringify!($name), $module.into())
+            }
+        }
+
+        $crate::submit! {
+            $crate::type_info::TypedDictInfo {
+                module: $module,
+                name: stringify!($name),
+                doc: concat!($($doc, "\n"),*),
+                fields: &[$(
+                    $crate::type_info::TypedDictFieldInfo {
+                        name: stringify!($field),
+                        r#type: <$ty as $crate::PyStubType>::type_input,
+                        required: $crate::__typed_dict_required!($($marker)?),
+                    }
+                ),*],
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __typed_dict_required {
+    () => {
+        true
+    };
+    (not_required) => {
+        false
+    };
+}
+
+/// Append content written in Python syntax to the end of a module stub, after all generated items.
+///
+/// This is useful for module-level declarations which must follow the generated content,
+/// e.g. extending `__all__` conditionally or a module-level `__getattr__` fallback.
+/// The content is emitted as-is (common indentation is removed), in the order the macros appear in the source.
+///
+/// Modules required by the content can be listed with `imports = [...]`.
+///
+/// ```rust
+/// pyo3_stub_gen::module_epilogue!(
+///     "my_module",
+///     r#"
+///     if sys.version_info >= (3, 11):
+///         __all__ += ["ExceptionGroupHelper"]
+///
+///     def __getattr__(name: str) -> typing.Any: ...
+///     "#,
+///     imports = ["sys", "typing"]
+/// );
+/// ```
+#[macro_export]
+macro_rules! module_epilogue {
+    ($module:expr, $code:expr) => {
+        $crate::module_epilogue!($module, $code, imports = []);
+    };
+    ($module:expr, $code:expr, imports = [$($import:expr),* $(,)?]) => {
+        $crate::submit! {
+            $crate::type_info::ModuleEpilogueInfo {
+                module: $module,
+                code: $code,
+                imports: &[$($import),*],
+                file: ::std::file!(),
+                line: ::std::line!(),
+            }
+        }
+    };
+}
+
+/// Declare a module-level `__getattr__`, e.g. for deprecated names loaded lazily.
+///
+/// Type checkers then accept access to any attribute of the module, which has the given return type.
+/// The return type is written in Python syntax and defaults to `typing.Any`.
+/// Modules referenced by it like `typing` are imported automatically, and others can be listed with `imports = [...]`.
+///
+/// ```rust
+/// // def __getattr__(name: builtins.str) -> typing.Any: ...
+/// pyo3_stub_gen::module_getattr!("my_module");
+/// pyo3_stub_gen::module_getattr!("my_module.sub", return_type = "typing.Any");
+/// ```
+#[macro_export]
+macro_rules! module_getattr {
+    ($module:expr) => {
+        $crate::module_getattr!($module, return_type = "typing.Any");
+    };
+    ($module:expr, return_type = $return_type:expr) => {
+        $crate::module_getattr!($module, return_type = $return_type, imports = []);
+    };
+    ($module:expr, return_type = $return_type:expr, imports = [$($import:expr),* $(,)?]) => {
+        $crate::submit! {
+            $crate::type_info::ModuleGetattrInfo {
+                module: $module,
+                return_type: $return_type,
+                imports: &[$($import),*],
+            }
+        }
+    };
+}
+
+/// Append hand-written members written in Python syntax to the stub of a class.
+///
+/// This is useful for members the macros cannot infer, e.g. `__slots__`, `__version__`, or a `ClassVar`.
+/// The first argument is the Rust type of the `#[pyclass]`, and the lines are emitted after the generated members
+/// with the class indentation (common indentation is removed).
+///
+/// Modules required by the members can be listed with `imports = [...]`.
+///
+/// ```rust
+/// struct MyClass;
+///
+/// pyo3_stub_gen::class_verbatim!(
+///     MyClass,
+///     r#"
+///     __slots__ = ("value",)
+///     VERSION: typing.ClassVar[str]
+///     "#,
+///     imports = ["typing"]
+/// );
+/// ```
+#[macro_export]
+macro_rules! class_verbatim {
+    ($class:ty, $code:expr) => {
+        $crate::class_verbatim!($class, $code, imports = []);
+    };
+    ($class:ty, $code:expr, imports = [$($import:expr),* $(,)?]) => {
+        $crate::submit! {
+            $crate::type_info::PyClassVerbatimInfo {
+                struct_id: ::std::any::TypeId::of::<$class>,
+                code: $code,
+                imports: &[$($import),*],
+                file: ::std::file!(),
+                line: ::std::line!(),
+            }
+        }
+    };
 }
 
 /// Re-export items from another module into __all__
@@ -441,7 +851,7 @@ macro_rules! type_alias {
 macro_rules! reexport_module_members {
     // New syntax: Wildcard - reexport_module_members!("target" from "source")
     ($target:literal from $source:literal) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ReexportModuleMembers {
                 target_module: $target,
                 source_module: $source,
@@ -451,7 +861,7 @@ macro_rules! reexport_module_members {
     };
     // New syntax: Explicit wildcard - reexport_module_members!("target" from "source"; *)
     ($target:literal from $source:literal; *) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ReexportModuleMembers {
                 target_module: $target,
                 source_module: $source,
@@ -461,7 +871,7 @@ macro_rules! reexport_module_members {
     };
     // New syntax: Wildcard + additional items - reexport_module_members!("target" from "source"; *, "item1", "item2")
     ($target:literal from $source:literal; *, $($item:literal),+) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ReexportModuleMembers {
                 target_module: $target,
                 source_module: $source,
@@ -471,7 +881,7 @@ macro_rules! reexport_module_members {
     };
     // New syntax: Specific items only - reexport_module_members!("target" from "source"; "item1", "item2")
     ($target:literal from $source:literal; $($item:literal),+) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ReexportModuleMembers {
                 target_module: $target,
                 source_module: $source,
@@ -481,7 +891,7 @@ macro_rules! reexport_module_members {
     };
     // Legacy syntax: Wildcard - reexport_module_members!("target", "source")
     ($target:expr, $source:expr) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ReexportModuleMembers {
                 target_module: $target,
                 source_module: $source,
@@ -491,7 +901,7 @@ macro_rules! reexport_module_members {
     };
     // Legacy syntax: Specific items - reexport_module_members!("target", "source", "item1", "item2")
     ($target:expr, $source:expr, $($item:expr),+) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ReexportModuleMembers {
                 target_module: $target,
                 source_module: $source,
@@ -501,6 +911,96 @@ macro_rules! reexport_module_members {
     };
 }
 
+/// Re-export items from another module under other names into __all__
+///
+/// This writes `from source.module import Foo as Bar` and adds `Bar` to `__all__` of the target module.
+///
+/// # Example
+/// ```rust
+/// pyo3_stub_gen::reexport_as!("target.module", "source.module", Foo as Bar, baz as qux);
+/// ```
+#[macro_export]
+macro_rules! reexport_as {
+    ($target:expr, $source:expr, $($name:ident as $alias:ident),+ $(,)?) => {
+        $crate::submit! {
+            $crate::type_info::ReexportModuleMembers {
+                target_module: $target,
+                source_module: $source,
+                items: $crate::type_info::ReexportItems::Renamed(&[
+                    $((stringify!($name), stringify!($alias))),+
+                ]),
+            }
+        }
+    };
+}
+
+/// Keep the old name of a moved or renamed class type-checkable
+///
+/// This writes `OldName = NewClass` with a deprecation comment to the stub file of the module,
+/// adds `OldName` to `__all__`, and documents it with the deprecation note in docgen.
+/// The name has to be bound at runtime too, e.g. by `m.add("OldName", m.py().get_type::<NewClass>())?`.
+///
+/// # Example
+/// ```rust
+/// use pyo3::prelude::*;
+/// use pyo3_stub_gen::derive::gen_stub_pyclass;
+///
+/// #[gen_stub_pyclass]
+/// #[pyclass(module = "my_module.models")]
+/// struct Model {}
+///
+/// pyo3_stub_gen::deprecated_alias!("my_module", OldModel = Model, note = "moved to my_module.models");
+/// pyo3_stub_gen::deprecated_alias!("my_module", LegacyModel = Model, since = "2.0", note = "use Model");
+/// ```
+#[macro_export]
+macro_rules! deprecated_alias {
+    (@opt) => { ::std::option::Option::None };
+    (@opt $value:expr) => { ::std::option::Option::Some($value) };
+    ($module:expr, $name:ident = $target:ty $(, since = $since:expr)? $(, note = $note:expr)? $(,)?) => {
+        $crate::submit! {
+            $crate::type_info::DeprecatedAliasInfo {
+                name: stringify!($name),
+                module: $module,
+                target: <$target as $crate::PyStubType>::type_output,
+                deprecated: $crate::type_info::DeprecatedInfo {
+                    since: $crate::deprecated_alias!(@opt $($since)?),
+                    note: $crate::deprecated_alias!(@opt $($note)?),
+                },
+            }
+        }
+    };
+}
+
+/// Import a base class defined in another package from its public module
+///
+/// A class extending a class of another crate, e.g. `#[pyclass(extends = other_crate::Base)]`,
+/// refers to the base class by the module it is defined in, which may be private like `other_pkg._core`,
+/// or not known at all if the other crate does not set `#[pyclass(module = "...")]`.
+/// This makes base classes named `Base` referring to the module given by `from`
+/// written as `Base` with `from other_pkg import Base` instead.
+/// Without `from`, base classes named `Base` without a module are replaced.
+/// Base classes of the name defined in the gathered modules are not changed.
+///
+/// # Example
+/// ```rust
+/// pyo3_stub_gen::external_class!("other_pkg", "Base", from = "other_pkg._core");
+/// pyo3_stub_gen::external_class!("another_pkg", "Mixin");
+/// ```
+#[macro_export]
+macro_rules! external_class {
+    (@opt) => { ::std::option::Option::None };
+    (@opt $value:expr) => { ::std::option::Option::Some($value) };
+    ($module:expr, $name:expr $(, from = $from:expr)? $(,)?) => {
+        $crate::submit! {
+            $crate::type_info::ExternalClassInfo {
+                module: $module,
+                name: $name,
+                defined_in: $crate::external_class!(@opt $($from)?),
+            }
+        }
+    };
+}
+
 /// Add verbatim entry to __all__
 ///
 /// # Example
@@ -510,7 +1010,7 @@ macro_rules! reexport_module_members {
 #[macro_export]
 macro_rules! export_verbatim {
     ($module:expr, $name:expr) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ExportVerbatim {
                 target_module: $module,
                 name: $name,
@@ -528,7 +1028,7 @@ macro_rules! export_verbatim {
 #[macro_export]
 macro_rules! exclude_from_all {
     ($module:expr, $name:expr) => {
-        $crate::inventory::submit! {
+        $crate::submit! {
             $crate::type_info::ExcludeFromAll {
                 target_module: $module,
                 name: $name,
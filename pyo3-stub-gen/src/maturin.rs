@@ -0,0 +1,72 @@
+//! Regenerate stub files as a step after `maturin develop`.
+//!
+//! Stub information is collected by [inventory] from the compiled crate,
+//! so it is not available to `build.rs`, which runs before the crate is compiled.
+//! Stub files are never written when the extension module is imported, either.
+//! Instead, call [write_stubs_on_develop] from the `stub_gen` binary
+//! and run it explicitly after `maturin develop`, e.g. in a task of your project:
+//!
+//! ```rust,ignore
+//! // src/bin/stub_gen.rs
+//! fn main() -> pyo3_stub_gen::Result<()> {
+//!     pyo3_stub_gen::maturin::write_stubs_on_develop(my_module::stub_info)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ```sh
+//! maturin develop && cargo run --bin stub_gen
+//! ```
+//!
+//! For the mixed layout installed in editable mode, the regenerated stubs are visible without reinstalling.
+//! Otherwise they are included from the next `maturin develop`.
+
+use crate::{Result, StubGenError, StubInfo};
+
+/// Set this environment variable to `0` to disable [write_stubs_on_develop]
+pub const DISABLE_ENV: &str = "PYO3_STUB_GEN_ON_DEVELOP";
+
+/// Write stub files gathered by `stub_info`, usually the function defined by [define_stub_info_gatherer!](crate::define_stub_info_gatherer).
+///
+/// Returns whether stub files are generated. Unchanged files are not rewritten.
+/// It is a no-op if `pyproject.toml` is not found, i.e. the source tree is not available,
+/// or if [DISABLE_ENV] is set to `0`, so that the step can be chained after every build.
+pub fn write_stubs_on_develop(stub_info: impl FnOnce() -> Result<StubInfo>) -> Result<bool> {
+    if std::env::var_os(DISABLE_ENV).is_some_and(|v| v == "0") {
+        return Ok(false);
+    }
+    let stub = match stub_info() {
+        Ok(stub) => stub,
+        Err(StubGenError::PyprojectNotFound { searched }) => {
+            log::info!("pyproject.toml is not found in {searched:?}, skip generating stubs");
+            return Ok(false);
+        }
+        Err(err) => return Err(err),
+    };
+    let result = stub.generate()?;
+    if !result.written.is_empty() {
+        log::info!(
+            "Regenerated {} stub file(s) for maturin develop",
+            result.written.len()
+        );
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_no_op_without_pyproject() -> Result<()> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("no-such-dir");
+        let stub_info = || {
+            Err(StubGenError::PyprojectNotFound {
+                searched: vec![dir.join("pyproject.toml")],
+            })
+        };
+        assert!(!write_stubs_on_develop(stub_info)?);
+        Ok(())
+    }
+}
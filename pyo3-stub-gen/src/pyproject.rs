@@ -10,9 +10,52 @@
 //! ).unwrap();
 //! ```
 
-use anyhow::{bail, Result};
+use crate::StubGenError;
+use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use std::{fs, path::*};
+use std::{collections::BTreeMap, fs, path::*};
+
+/// Find `pyproject.toml` in `start_dir` or its ancestors, e.g. at the root of a Cargo workspace.
+///
+/// The search stops at the root of the Cargo workspace, i.e. the directory of `Cargo.toml` with `[workspace]`,
+/// or at the package itself if it is not in a workspace,
+/// so that an unrelated `pyproject.toml` outside the project is not used.
+/// Only `start_dir` is searched if it is not in a Cargo package.
+/// The error lists the searched paths if it is not found.
+pub fn find_pyproject_toml(start_dir: impl AsRef<Path>) -> crate::Result<PathBuf> {
+    let start_dir = start_dir.as_ref();
+    let root = workspace_root(start_dir);
+    let mut searched = Vec::new();
+    for dir in start_dir.ancestors() {
+        let path = dir.join("pyproject.toml");
+        if path.is_file() {
+            return Ok(path);
+        }
+        searched.push(path);
+        if root.is_none_or(|root| dir == root) {
+            break;
+        }
+    }
+    Err(StubGenError::PyprojectNotFound { searched })
+}
+
+/// The nearest ancestor of `dir` whose `Cargo.toml` has `[workspace]`, or the nearest one with `Cargo.toml` if none has
+fn workspace_root(dir: &Path) -> Option<&Path> {
+    let mut package = None;
+    for dir in dir.ancestors() {
+        let Ok(manifest) = fs::read_to_string(dir.join("Cargo.toml")) else {
+            continue;
+        };
+        if toml::from_str::<toml::Table>(&manifest)
+            .is_ok_and(|table| table.contains_key("workspace"))
+        {
+            return Some(dir);
+        }
+        package.get_or_insert(dir);
+    }
+    package
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PyProject {
@@ -24,12 +67,20 @@ pub struct PyProject {
 }
 
 impl PyProject {
-    pub fn parse_toml(path: impl AsRef<Path>) -> Result<Self> {
+    pub fn parse_toml(path: impl AsRef<Path>) -> crate::Result<Self> {
         let path = path.as_ref();
         if path.file_name() != Some("pyproject.toml".as_ref()) {
-            bail!("{} is not a pyproject.toml", path.display())
+            return Err(anyhow!("{} is not a pyproject.toml", path.display()).into());
         }
-        let mut out: PyProject = toml::de::from_str(&fs::read_to_string(path)?)?;
+        let content = fs::read_to_string(path).map_err(|source| StubGenError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut out: PyProject =
+            toml::de::from_str(&content).map_err(|source| StubGenError::InvalidPyproject {
+                path: path.to_path_buf(),
+                source: Box::new(source),
+            })?;
         out.toml_path = path.to_path_buf();
         Ok(out)
     }
@@ -90,6 +141,9 @@ impl PyProject {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Project {
     pub name: String,
+    /// Static version of the project, unset if it is `dynamic`
+    #[serde(default)]
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -175,6 +229,479 @@ pub struct StubGenConfig {
     /// Configuration for `__init__.py` generation
     #[serde(rename = "generate-init-py", default)]
     pub generate_init_py: GenerateInitPy,
+    /// Order of classes, enums and functions in generated stub files
+    #[serde(rename = "item-order", default)]
+    pub item_order: ItemOrder,
+    /// Type aliases to be replaced by their definition wherever they are referenced
+    #[serde(rename = "inline-type-aliases", default)]
+    pub inline_type_aliases: InlineTypeAliases,
+    /// Whether to annotate classes, enums and functions with the Rust source location they are defined at.
+    /// Default is `false`.
+    #[serde(rename = "source-comments", default)]
+    pub source_comments: bool,
+    /// Command to post-process generated files with, e.g. `["ruff", "format"]`.
+    /// Paths of the generated `.pyi` and `__init__.py` files are appended as arguments.
+    #[serde(rename = "format-command", default)]
+    pub format_command: Option<Vec<String>>,
+    /// How defaults of getters, setters and class attributes given by `#[gen_stub(default = ...)]` are shown
+    #[serde(rename = "member-defaults", default)]
+    pub member_defaults: MemberDefaults,
+    /// Whether to add `from __future__ import annotations` to each stub file and remove quotes from forward references.
+    /// Default is `false`.
+    #[serde(rename = "future-annotations", default)]
+    pub future_annotations: bool,
+    /// Order of entries in `__all__` of generated stub files and `__init__.py`
+    #[serde(rename = "all-order", default)]
+    pub all_order: AllOrder,
+    /// Whether `__all__` is written as a list or a tuple
+    #[serde(rename = "all-style", default)]
+    pub all_style: AllStyle,
+    /// Whether to add `def __dir__() -> list[str]: ...` to each stub file.
+    /// Default is `false`.
+    #[serde(rename = "dunder-dir", default)]
+    pub dunder_dir: bool,
+    /// Whether to write each class and enum into its own stub file, e.g. `_core/_cls_Foo.pyi`,
+    /// re-exported from `_core/__init__.pyi`. This keeps stub files small for modules with many classes.
+    /// Only supported in mixed Python/Rust layout. Default is `false`.
+    #[serde(rename = "split-classes", default)]
+    pub split_classes: bool,
+    /// Modules whose stub files start with `# mypy: ignore-errors`
+    #[serde(rename = "ignore-errors", default)]
+    pub ignore_errors: IgnoreErrors,
+    /// Rendering of NumPy arrays, configured in `[tool.pyo3-stub-gen.numpy]`
+    #[serde(default)]
+    pub numpy: NumpyConfig,
+    /// Whether to prepend the constructor signature like `Foo(x, y=2)` to class docstrings,
+    /// which documentation tools like pdoc read. Default is `false`.
+    #[serde(rename = "class-signature-in-docstring", default)]
+    pub class_signature_in_docstring: bool,
+    /// Whether to decorate classes without `#[pyclass(subclass)]` by `@typing.final`,
+    /// since they cannot be subclassed at runtime. Default is `false`.
+    #[serde(rename = "mark-final", default)]
+    pub mark_final: bool,
+    /// Whether `__new__` of classes with `#[pyclass(subclass)]` returns `typing.Self` instead of the class,
+    /// so that constructors of subclasses are typed as the subclass. Default is `false`.
+    #[serde(rename = "self-in-new", default)]
+    pub self_in_new: bool,
+    /// Whether the extension module supports free-threaded Python builds, i.e. it is declared by
+    /// `#[pymodule(gil_used = false)]` and importing it does not enable the GIL.
+    /// Written as a comment at the top of each stub file and noted in the generated documentation.
+    /// Default is `false`.
+    #[serde(rename = "free-threading", default)]
+    pub free_threading: bool,
+    /// Whether to merge stub files of modules with the existing ones instead of overwriting them,
+    /// keeping blocks between `# <keep>` and `# </keep>` lines and hand-written overloads of generated functions.
+    /// Requires the `rustpython-parser` feature. Default is `false`.
+    #[serde(default)]
+    pub merge: bool,
+    /// Minimum Python version the generated stubs are written for, e.g. `"3.9"`.
+    /// Selects between `typing.Optional`/`typing.Union` and PEP 604 `|`, `typing.List` and `list`,
+    /// and names of `typing` or their fallbacks in `typing_extensions`.
+    /// Default is unset, which keeps types as they are written.
+    #[serde(rename = "python-target", default)]
+    pub python_target: Option<PythonVersion>,
+    /// How functions marked by `#[gen_stub(platform = "...")]` are written
+    #[serde(rename = "platform-specific", default)]
+    pub platform_specific: PlatformSpecific,
+    /// Naming convention applied to names of functions and methods in stub files and `__all__`,
+    /// e.g. `"camelCase"`. Default is unset, which keeps the names given by PyO3.
+    ///
+    /// The renamed names must exist at runtime, e.g. by [crate::runtime::PyModuleRenameExt::add_renamed_items].
+    #[serde(default)]
+    pub rename: Option<RenameRule>,
+    /// Fully qualified names of classes and enums allowed to be defined more than once in a module, e.g. `"pkg.Foo"`.
+    /// Such definitions are all written to the stub file, and the last one shadows the others.
+    #[serde(rename = "allow-shadowing", default)]
+    pub allow_shadowing: Vec<String>,
+    /// Reporting of parameters and return types falling back to `typing.Any`. Default is `"off"`.
+    #[serde(default)]
+    pub strict: Strict,
+    /// Whether to write builtin types with the module name, e.g. `builtins.int`, or as bare `int`.
+    /// Names bound to something else in the stub file, e.g. a submodule `int`, stay qualified.
+    /// Default is unset, which is the same as `true`. Use [StubGenConfig::qualifies_builtins] to read.
+    #[serde(rename = "qualify-builtins", default)]
+    pub qualify_builtins: Option<bool>,
+    /// Placement of stub files of PyO3 modules, for all modules or per module.
+    /// Default is unset, which writes `pkg/sub/__init__.pyi` in mixed layout and `pkg.pyi` in pure Rust layout.
+    #[serde(rename = "stub-layout", default)]
+    pub stub_layout: Option<StubLayoutConfig>,
+    /// Docstring of modules without `module_doc!`, e.g. `"Type stubs of `{module}` in {project} {version}"`.
+    /// `{module}` is the full module name, `{name}` is its last component, `{project}` is the project name,
+    /// and `{version}` is the project version. `{{` and `}}` are literal braces.
+    /// Default is unset, which leaves such modules without a docstring.
+    #[serde(rename = "module-doc-template", default)]
+    pub module_doc_template: Option<String>,
+    /// Modules imported only under `if typing.TYPE_CHECKING:`, e.g. `["numpy", "pandas"]` of optional extras,
+    /// including their submodules like `numpy.typing`. Annotations referencing them are quoted
+    /// unless `future-annotations` is enabled.
+    #[serde(rename = "type-checking-only-imports", default)]
+    pub type_checking_only_imports: Vec<String>,
+}
+
+impl StubGenConfig {
+    /// Whether builtin types are written as `builtins.int` rather than `int`, following `qualify-builtins`
+    pub fn qualifies_builtins(&self) -> bool {
+        self.qualify_builtins.unwrap_or(true)
+    }
+
+    /// Whether `module` is imported only for type checkers by `type-checking-only-imports`
+    pub fn is_type_checking_only(&self, module: &str) -> bool {
+        self.type_checking_only_imports.iter().any(|only| {
+            module
+                .strip_prefix(only.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    }
+}
+
+/// Python version `major.minor`, e.g. `"3.9"` in pyproject.toml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PythonVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl PythonVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl std::str::FromStr for PythonVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse = || {
+            let (major, minor) = s.trim().split_once('.')?;
+            Some(Self::new(major.parse().ok()?, minor.parse().ok()?))
+        };
+        parse().with_context(|| format!("Invalid Python version `{s}`, expected like `3.9`"))
+    }
+}
+
+impl TryFrom<String> for PythonVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<PythonVersion> for String {
+    fn from(version: PythonVersion) -> Self {
+        version.to_string()
+    }
+}
+
+impl std::fmt::Display for PythonVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Configuration for inlining type aliases at their use sites.
+///
+/// This can be:
+/// - `false` or unset: Keep references to type aliases (default)
+/// - `true`: Inline all type aliases
+/// - `["SmallAlias", "pkg._core.OtherAlias"]`: Inline specific aliases, by name or fully qualified name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum InlineTypeAliases {
+    /// Enable/disable for all type aliases
+    All(bool),
+    /// Inline specific type aliases only
+    Names(Vec<String>),
+}
+
+impl Default for InlineTypeAliases {
+    fn default() -> Self {
+        InlineTypeAliases::All(false)
+    }
+}
+
+impl InlineTypeAliases {
+    /// Check if the type alias `name` defined in `module` should be inlined.
+    pub fn is_enabled_for(&self, module: &str, name: &str) -> bool {
+        match self {
+            InlineTypeAliases::All(enabled) => *enabled,
+            InlineTypeAliases::Names(names) => names
+                .iter()
+                .any(|n| n == name || *n == format!("{module}.{name}")),
+        }
+    }
+}
+
+/// Modules to suppress type checker errors in, by `# mypy: ignore-errors` at the top of stub files.
+///
+/// This can be:
+/// - `false` or unset: Check all modules (default)
+/// - `true`: Suppress errors in all modules
+/// - `["pkg._dynamic"]`: Suppress errors in specific modules, by fully qualified name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IgnoreErrors {
+    /// Enable/disable for all modules
+    All(bool),
+    /// Specific modules only
+    Modules(Vec<String>),
+}
+
+impl Default for IgnoreErrors {
+    fn default() -> Self {
+        IgnoreErrors::All(false)
+    }
+}
+
+impl IgnoreErrors {
+    /// Check if errors in `module` should be ignored.
+    pub fn is_enabled_for(&self, module: &str) -> bool {
+        match self {
+            IgnoreErrors::All(enabled) => *enabled,
+            IgnoreErrors::Modules(modules) => modules.iter().any(|m| m == module),
+        }
+    }
+}
+
+/// Placement of the stub file of a module `pkg.sub`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StubLayout {
+    /// Single file `pkg/sub.pyi`. Not available for modules with submodules.
+    Flat,
+    /// Package directory `pkg/sub/__init__.pyi`
+    Package,
+}
+
+/// Configuration of stub file placement.
+///
+/// This can be:
+/// - `"flat"` or `"package"`: Apply to all modules
+/// - `{ "pkg._core" = "flat" }`: Apply to specific modules, by fully qualified name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StubLayoutConfig {
+    /// Layout of all modules
+    All(StubLayout),
+    /// Layout of specific modules, others following the default
+    Modules(BTreeMap<String, StubLayout>),
+}
+
+impl StubLayoutConfig {
+    /// Layout specified for `module`, if any
+    pub fn layout_for(&self, module: &str) -> Option<StubLayout> {
+        match self {
+            StubLayoutConfig::All(layout) => Some(*layout),
+            StubLayoutConfig::Modules(modules) => modules.get(module).copied(),
+        }
+    }
+}
+
+/// Order of classes, enums and functions in generated stub files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemOrder {
+    /// Classes, then enums, then functions, each sorted by name (default)
+    #[default]
+    Alphabetical,
+    /// Definition order in Rust source, using the file and line captured by `#[gen_stub_*]` macros
+    Source,
+}
+
+/// Rendering of defaults of getters, setters and class attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberDefaults {
+    /// Show the Rust expression evaluated into a Python literal,
+    /// falling back to `#[gen_stub(default_symbol = "...")]` if it cannot be represented (default)
+    #[default]
+    Evaluated,
+    /// Always show `#[gen_stub(default_symbol = "...")]`, or `...` if not given
+    Symbolic,
+}
+
+/// Reporting of types in `#[gen_stub_*]` items rendered as `typing.Any`,
+/// e.g. for `Bound<PyAny>` without `#[gen_stub(override_type(...))]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strict {
+    /// Do not report them (default)
+    #[default]
+    Off,
+    /// Log a warning for each of them
+    Warn,
+    /// Fail the stub generation, listing all of them
+    Error,
+}
+
+/// Handling of functions available only on some platforms, marked by `#[gen_stub(platform = "...")]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlatformSpecific {
+    /// Write them in `if sys.platform == "...":` blocks, which type checkers and stubtest evaluate (default)
+    #[default]
+    Guard,
+    /// Write them as available on all platforms
+    Include,
+    /// Omit them from the stub files
+    Exclude,
+}
+
+/// Naming convention of functions and methods, `rename = "camelCase"`.
+///
+/// Names starting with `_`, e.g. `__init__` or private ones, are kept as is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RenameRule {
+    #[serde(rename = "camelCase")]
+    CamelCase,
+    #[serde(rename = "PascalCase")]
+    PascalCase,
+    #[serde(rename = "snake_case")]
+    SnakeCase,
+    #[serde(rename = "SCREAMING_SNAKE_CASE")]
+    ScreamingSnakeCase,
+    #[serde(rename = "lowercase")]
+    Lowercase,
+    #[serde(rename = "UPPERCASE")]
+    Uppercase,
+}
+
+impl RenameRule {
+    /// Rename `name`, keeping trailing `_` used to avoid Python keywords, e.g. `from_`
+    pub fn apply(self, name: &str) -> String {
+        if name.starts_with('_') {
+            return name.to_string();
+        }
+        let stem = name.trim_end_matches('_');
+        let suffix = &name[stem.len()..];
+        let words = split_words(stem);
+        let renamed = match self {
+            RenameRule::CamelCase => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            RenameRule::PascalCase => words.iter().map(|word| capitalize(word)).collect(),
+            RenameRule::SnakeCase => words.iter().map(|word| word.to_lowercase()).join("_"),
+            RenameRule::ScreamingSnakeCase => {
+                words.iter().map(|word| word.to_uppercase()).join("_")
+            }
+            RenameRule::Lowercase => stem.to_lowercase(),
+            RenameRule::Uppercase => stem.to_uppercase(),
+        };
+        renamed + suffix
+    }
+}
+
+/// Split `name` into words at `_` and case boundaries, e.g. `["get", "HTTP", "Server"]` for `get_HTTPServer`
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// `Word` for `word` or `WORD`
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Order of entries in `__all__`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AllOrder {
+    /// Sorted by name (default)
+    #[default]
+    Alphabetical,
+    /// Order in which items are written in the stub file, following `item-order`,
+    /// then submodules, re-exported items and verbatim entries in declaration order
+    Definition,
+}
+
+/// Rendering of `__all__`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AllStyle {
+    /// `__all__ = ["A", "B"]` (default)
+    #[default]
+    List,
+    /// `__all__ = ("A", "B")`, typed as `tuple[str, ...]`
+    Tuple,
+}
+
+/// Configuration of NumPy array types, `[tool.pyo3-stub-gen.numpy]`
+///
+/// ```toml
+/// [tool.pyo3-stub-gen.numpy]
+/// array = "ndarray"
+/// array-input = "ArrayLike"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct NumpyConfig {
+    /// How arrays are rendered. Default is `"NDArray"`.
+    #[serde(default)]
+    pub array: NumpyArrayStyle,
+    /// How arrays taken from Python, i.e. in parameters and setters, are rendered.
+    /// Defaults to `array`.
+    #[serde(rename = "array-input", default)]
+    pub array_input: Option<NumpyArrayStyle>,
+}
+
+impl NumpyConfig {
+    /// Style of arrays in parameters and setters
+    pub fn input_style(&self) -> NumpyArrayStyle {
+        self.array_input.unwrap_or(self.array)
+    }
+}
+
+/// Rendering of NumPy arrays like `PyArray2<f64>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NumpyArrayStyle {
+    /// `numpy.ndarray[tuple[builtins.int, builtins.int], numpy.dtype[numpy.float64]]`, including the number of dimensions
+    #[serde(rename = "ndarray")]
+    Ndarray,
+    /// `numpy.typing.NDArray[numpy.float64]` (default)
+    #[default]
+    NDArray,
+    /// `numpy.typing.ArrayLike`, accepting anything convertible to arrays like lists.
+    /// Mainly for `array-input`, since Rust functions cannot return it.
+    ArrayLike,
 }
 
 #[cfg(test)]
@@ -229,6 +756,32 @@ mod tests {
         assert!(!pyproject.stub_gen_config().use_type_statement);
     }
 
+    #[test]
+    fn test_python_target() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            python-target = "3.9"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            pyproject.stub_gen_config().python_target,
+            Some(PythonVersion::new(3, 9))
+        );
+        assert!(PythonVersion::new(3, 9) < PythonVersion::new(3, 10));
+
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            python-target = "3"
+        "#;
+        assert!(toml::from_str::<PyProject>(toml_str).is_err());
+    }
+
     #[test]
     fn test_generate_init_py_default() {
         let toml_str = r#"
@@ -321,4 +874,467 @@ mod tests {
         let config = pyproject.stub_gen_config();
         assert!(!config.generate_init_py.is_enabled());
     }
+
+    #[test]
+    fn test_item_order_default() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            pyproject.stub_gen_config().item_order,
+            ItemOrder::Alphabetical
+        );
+    }
+
+    #[test]
+    fn test_item_order_source() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            item-order = "source"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert_eq!(pyproject.stub_gen_config().item_order, ItemOrder::Source);
+    }
+
+    #[test]
+    fn test_platform_specific() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            platform-specific = "exclude"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            pyproject.stub_gen_config().platform_specific,
+            PlatformSpecific::Exclude
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            strict = "error"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert_eq!(pyproject.stub_gen_config().strict, Strict::Error);
+    }
+
+    #[test]
+    fn test_qualify_builtins() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            qualify-builtins = false
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(!pyproject.stub_gen_config().qualifies_builtins());
+        assert!(StubGenConfig::default().qualifies_builtins());
+    }
+
+    #[test]
+    fn test_stub_layout() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            stub-layout = "flat"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let layout = pyproject.stub_gen_config().stub_layout.unwrap();
+        assert_eq!(layout.layout_for("pkg.sub"), Some(StubLayout::Flat));
+
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen.stub-layout]
+            "pkg._core" = "package"
+            "pkg._core.sub" = "flat"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let layout = pyproject.stub_gen_config().stub_layout.unwrap();
+        assert_eq!(layout.layout_for("pkg._core"), Some(StubLayout::Package));
+        assert_eq!(layout.layout_for("pkg._core.sub"), Some(StubLayout::Flat));
+        assert_eq!(layout.layout_for("pkg"), None);
+    }
+
+    #[test]
+    fn test_rename() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            rename = "camelCase"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let rule = pyproject.stub_gen_config().rename.unwrap();
+        assert_eq!(rule, RenameRule::CamelCase);
+        assert_eq!(rule.apply("get_value"), "getValue");
+        assert_eq!(rule.apply("to_utf8"), "toUtf8");
+        assert_eq!(rule.apply("from_"), "from_");
+        assert_eq!(rule.apply("parse_HTTPResponse"), "parseHttpResponse");
+        assert_eq!(rule.apply("__init__"), "__init__");
+        assert_eq!(rule.apply("_private_helper"), "_private_helper");
+        assert_eq!(RenameRule::PascalCase.apply("get_value"), "GetValue");
+        assert_eq!(RenameRule::SnakeCase.apply("getValue"), "get_value");
+        assert_eq!(
+            RenameRule::ScreamingSnakeCase.apply("getValue"),
+            "GET_VALUE"
+        );
+    }
+
+    #[test]
+    fn test_source_comments() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            source-comments = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(pyproject.stub_gen_config().source_comments);
+    }
+
+    #[test]
+    fn test_format_command() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            format-command = ["ruff", "format", "--quiet"]
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            pyproject.stub_gen_config().format_command,
+            Some(vec![
+                "ruff".to_string(),
+                "format".to_string(),
+                "--quiet".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_member_defaults() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            member-defaults = "symbolic"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            pyproject.stub_gen_config().member_defaults,
+            MemberDefaults::Symbolic
+        );
+    }
+
+    #[test]
+    fn test_ignore_errors() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            ignore-errors = ["pkg._dynamic"]
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let ignore_errors = pyproject.stub_gen_config().ignore_errors;
+        assert!(ignore_errors.is_enabled_for("pkg._dynamic"));
+        assert!(!ignore_errors.is_enabled_for("pkg"));
+        assert!(!IgnoreErrors::default().is_enabled_for("pkg"));
+        assert!(IgnoreErrors::All(true).is_enabled_for("pkg"));
+    }
+
+    #[test]
+    fn test_numpy() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen.numpy]
+            array = "ndarray"
+            array-input = "ArrayLike"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let numpy = pyproject.stub_gen_config().numpy;
+        assert_eq!(numpy.array, NumpyArrayStyle::Ndarray);
+        assert_eq!(numpy.input_style(), NumpyArrayStyle::ArrayLike);
+
+        let default = NumpyConfig::default();
+        assert_eq!(default.array, NumpyArrayStyle::NDArray);
+        assert_eq!(default.input_style(), NumpyArrayStyle::NDArray);
+    }
+
+    #[test]
+    fn test_module_doc_template() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+            version = "1.2.3"
+
+            [tool.pyo3-stub-gen]
+            module-doc-template = "Stubs of `{module}`"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert_eq!(pyproject.project.version.as_deref(), Some("1.2.3"));
+        assert_eq!(
+            pyproject.stub_gen_config().module_doc_template.as_deref(),
+            Some("Stubs of `{module}`")
+        );
+    }
+
+    #[test]
+    fn test_future_annotations() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            future-annotations = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(pyproject.stub_gen_config().future_annotations);
+    }
+
+    #[test]
+    fn test_type_checking_only_imports() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            type-checking-only-imports = ["numpy", "pandas"]
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let config = pyproject.stub_gen_config();
+        assert!(config.is_type_checking_only("numpy"));
+        assert!(config.is_type_checking_only("numpy.typing"));
+        assert!(!config.is_type_checking_only("numpyro"));
+        assert!(!config.is_type_checking_only("typing"));
+    }
+
+    #[test]
+    fn test_dunder_all() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let config = pyproject.stub_gen_config();
+        assert_eq!(config.all_order, AllOrder::Alphabetical);
+        assert_eq!(config.all_style, AllStyle::List);
+        assert!(!config.dunder_dir);
+
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            all-order = "definition"
+            all-style = "tuple"
+            dunder-dir = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let config = pyproject.stub_gen_config();
+        assert_eq!(config.all_order, AllOrder::Definition);
+        assert_eq!(config.all_style, AllStyle::Tuple);
+        assert!(config.dunder_dir);
+    }
+
+    #[test]
+    fn test_mark_final() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(!pyproject.stub_gen_config().mark_final);
+
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            mark-final = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(pyproject.stub_gen_config().mark_final);
+    }
+
+    #[test]
+    fn test_self_in_new() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(!pyproject.stub_gen_config().self_in_new);
+
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            self-in-new = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(pyproject.stub_gen_config().self_in_new);
+    }
+
+    #[test]
+    fn test_free_threading() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(!pyproject.stub_gen_config().free_threading);
+
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            free-threading = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(pyproject.stub_gen_config().free_threading);
+    }
+
+    #[test]
+    fn test_merge() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            merge = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(pyproject.stub_gen_config().merge);
+    }
+
+    #[test]
+    fn test_split_classes() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            split-classes = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(pyproject.stub_gen_config().split_classes);
+    }
+
+    #[test]
+    fn test_inline_type_aliases() {
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            inline-type-aliases = ["SmallAlias", "pkg._core.OtherAlias"]
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        let config = pyproject.stub_gen_config();
+        assert!(config
+            .inline_type_aliases
+            .is_enabled_for("pkg._core", "SmallAlias"));
+        assert!(config
+            .inline_type_aliases
+            .is_enabled_for("pkg._core", "OtherAlias"));
+        assert!(!config
+            .inline_type_aliases
+            .is_enabled_for("pkg.other", "OtherAlias"));
+
+        let toml_str = r#"
+            [project]
+            name = "test"
+
+            [tool.pyo3-stub-gen]
+            inline-type-aliases = true
+        "#;
+        let pyproject: PyProject = toml::from_str(toml_str).unwrap();
+        assert!(pyproject
+            .stub_gen_config()
+            .inline_type_aliases
+            .is_enabled_for("pkg", "AnyAlias"));
+    }
+
+    #[test]
+    fn test_find_pyproject_toml() {
+        let outside = std::env::temp_dir().join(format!(
+            "pyo3_stub_gen_find_pyproject_{}",
+            std::process::id()
+        ));
+        let workspace = outside.join("workspace");
+        let member = workspace.join("crates").join("member");
+        fs::create_dir_all(&member).unwrap();
+        fs::write(member.join("Cargo.toml"), "[package]\nname = \"member\"\n").unwrap();
+        fs::write(
+            workspace.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        // Not used since it is outside of the workspace
+        fs::write(outside.join("pyproject.toml"), "").unwrap();
+
+        let err = find_pyproject_toml(&member).unwrap_err();
+        let StubGenError::PyprojectNotFound { searched } = &err else {
+            panic!("Unexpected error: {err:?}");
+        };
+        assert_eq!(searched.first(), Some(&member.join("pyproject.toml")));
+        assert_eq!(searched.last(), Some(&workspace.join("pyproject.toml")));
+        let err = err.to_string();
+        assert!(err.contains(&member.join("pyproject.toml").display().to_string()));
+        assert!(err.contains(&workspace.join("pyproject.toml").display().to_string()));
+        assert!(matches!(
+            PyProject::parse_toml(member.join("pyproject.toml")),
+            Err(StubGenError::Io { .. })
+        ));
+
+        fs::write(workspace.join("pyproject.toml"), "").unwrap();
+        assert!(matches!(
+            PyProject::parse_toml(workspace.join("pyproject.toml")),
+            Err(StubGenError::InvalidPyproject { .. })
+        ));
+        assert_eq!(
+            find_pyproject_toml(&member).unwrap(),
+            workspace.join("pyproject.toml")
+        );
+        fs::write(member.join("pyproject.toml"), "").unwrap();
+        assert_eq!(
+            find_pyproject_toml(&member).unwrap(),
+            member.join("pyproject.toml")
+        );
+
+        // Only the start directory is searched outside of Cargo packages
+        let err = find_pyproject_toml(outside.join("crates")).unwrap_err();
+        let StubGenError::PyprojectNotFound { searched } = &err else {
+            panic!("Unexpected error: {err:?}");
+        };
+        assert_eq!(searched, &[outside.join("crates").join("pyproject.toml")]);
+
+        fs::remove_dir_all(&outside).unwrap();
+    }
 }
@@ -0,0 +1,150 @@
+//! Runtime support for docstrings.
+//!
+//! Doc tools reading `__doc__` at runtime see the raw rustdoc comments PyO3 attaches,
+//! while stub files contain normalized docstrings and [`module_doc!`](crate::module_doc) contents.
+//! [`PyModuleDocExt::set_stub_docs`] sets `__doc__` from the same sources used for stub generation
+//! so that both agree.
+//!
+//! ```rust,ignore
+//! use pyo3::prelude::*;
+//! use pyo3_stub_gen::runtime::PyModuleDocExt;
+//!
+//! pyo3_stub_gen::module_doc!("my_module", "Documentation of my_module");
+//!
+//! #[pymodule]
+//! fn my_module(m: &Bound<PyModule>) -> PyResult<()> {
+//!     m.add_class::<MyClass>()?;
+//!     m.set_stub_docs("my_module")?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::generate::normalize_docstring;
+use crate::type_info::{ModuleDocInfo, PyClassInfo, PyComplexEnumInfo, PyEnumInfo};
+use ::pyo3::prelude::*;
+use ::pyo3::types::{PyModule, PyType};
+
+/// `Py_TPFLAGS_HEAPTYPE`, set for classes created at runtime
+const HEAP_TYPE: u64 = 1 << 9;
+/// `Py_TPFLAGS_IMMUTABLETYPE`, set for classes whose attributes cannot be set since Python 3.10
+const IMMUTABLE_TYPE: u64 = 1 << 8;
+
+/// Whether `__doc__` of the class can be set, i.e. it is neither a static nor an immutable type
+fn is_doc_settable(class: &Bound<'_, PyType>) -> PyResult<bool> {
+    let flags: u64 = class.getattr("__flags__")?.extract()?;
+    Ok(flags & HEAP_TYPE != 0 && flags & IMMUTABLE_TYPE == 0)
+}
+
+/// Extension trait for `Bound<PyModule>` to set docstrings as they appear in stub files.
+pub trait PyModuleDocExt {
+    /// Sets `__doc__` of this module and of the classes and enums added to it.
+    ///
+    /// `module` is the fully qualified module name used for stub generation, e.g. `"pkg.sub"`.
+    /// The module docstring is taken from [`module_doc!`](crate::module_doc) if registered.
+    /// Classes and enums are matched by their Python name among the attributes of this module,
+    /// so this should be called after they are added.
+    /// Immutable types, e.g. `#[pyclass(immutable_type)]`, do not allow setting `__doc__`,
+    /// so they keep the docstrings given by PyO3.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if setting `__doc__` fails.
+    fn set_stub_docs(&self, module: &str) -> PyResult<()>;
+}
+
+impl PyModuleDocExt for Bound<'_, PyModule> {
+    fn set_stub_docs(&self, module: &str) -> PyResult<()> {
+        if let Some(info) = inventory::iter::<ModuleDocInfo>().find(|info| info.module == module) {
+            self.setattr("__doc__", normalize_docstring(&(info.doc)()))?;
+        }
+
+        let classes = inventory::iter::<PyClassInfo>()
+            .map(|info| (info.pyclass_name, info.module, info.doc))
+            .chain(
+                inventory::iter::<PyComplexEnumInfo>()
+                    .map(|info| (info.pyclass_name, info.module, info.doc)),
+            )
+            .chain(
+                inventory::iter::<PyEnumInfo>()
+                    .map(|info| (info.pyclass_name, info.module, info.doc)),
+            );
+        for (name, class_module, doc) in classes {
+            // Classes without `#[pyclass(module = ...)]` belong to the default module,
+            // which is not known at runtime, so they are matched by name only
+            if doc.is_empty() || class_module.is_some_and(|m| m != module) {
+                continue;
+            }
+            let Ok(class) = self.getattr(name) else {
+                continue;
+            };
+            let Ok(class) = class.cast_into::<PyType>() else {
+                continue;
+            };
+            if is_doc_settable(&class)? {
+                class.setattr("__doc__", normalize_docstring(doc))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::module_doc!("test_doc_module", "\n    Module documentation\n    ");
+
+    #[pyclass(module = "test_doc_module")]
+    struct DocClass {}
+
+    #[pyclass(module = "test_doc_module", immutable_type)]
+    struct ImmutableDocClass {}
+
+    inventory::submit! {
+        PyClassInfo {
+            module: Some("test_doc_module"),
+            doc: "\n    Class documentation\n    ",
+            ..PyClassInfo::fixture("DocClass")
+        }
+    }
+
+    inventory::submit! {
+        PyClassInfo {
+            module: Some("test_doc_module"),
+            doc: "Immutable class documentation",
+            ..PyClassInfo::fixture("ImmutableDocClass")
+        }
+    }
+
+    #[test]
+    fn test_set_stub_docs_module() {
+        pyo3::Python::initialize();
+        Python::attach(|py| {
+            let m = PyModule::new(py, "test_doc_module").unwrap();
+            m.set_stub_docs("test_doc_module").unwrap();
+            let doc: String = m.getattr("__doc__").unwrap().extract().unwrap();
+            assert_eq!(doc, "Module documentation");
+        });
+    }
+    #[test]
+    fn test_set_stub_docs_classes() {
+        pyo3::Python::initialize();
+        Python::attach(|py| {
+            let m = PyModule::new(py, "test_doc_module").unwrap();
+            m.add_class::<DocClass>().unwrap();
+            m.add_class::<ImmutableDocClass>().unwrap();
+            m.set_stub_docs("test_doc_module").unwrap();
+
+            let doc: String = m
+                .getattr("DocClass")
+                .unwrap()
+                .getattr("__doc__")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(doc, "Class documentation");
+            // Immutable types are skipped instead of raising `TypeError`
+            m.getattr("ImmutableDocClass").unwrap();
+        });
+    }
+}
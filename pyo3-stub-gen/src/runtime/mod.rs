@@ -1,8 +1,13 @@
-//! Runtime support for type aliases.
+//! Runtime support for type aliases and docstrings.
 //!
 //! This module provides traits and utilities for registering type aliases
 //! in Python modules at runtime, enabling type aliases defined with
 //! [`type_alias!`](crate::type_alias) to be importable from Python.
+//! See [`PyModuleDocExt`] for keeping runtime docstrings consistent with stub files,
+//! [`PyModuleNamedTupleExt`] for the classes of `#[gen_stub(named_tuple(...))]`,
+//! [`PyModuleRenameExt`] for the names given by the `rename` configuration,
+//! and [`StubInfo::reconcile_runtime_modules`](crate::StubInfo::reconcile_runtime_modules)
+//! for checking the module tree built at runtime.
 //!
 //! # Example
 //!
@@ -22,6 +27,18 @@
 //! }
 //! ```
 
+mod doc;
+mod named_tuple;
+mod rename;
+mod tree;
+
+pub use doc::PyModuleDocExt;
+#[doc(hidden)]
+pub use named_tuple::NamedTuple;
+pub use named_tuple::PyModuleNamedTupleExt;
+pub use rename::PyModuleRenameExt;
+pub(crate) use tree::module_tree;
+
 use ::pyo3::prelude::*;
 use ::pyo3::types::PyModule;
 
@@ -339,9 +356,16 @@ mod tests {
         });
     }
 
+    crate::type_alias!(
+        "test_module",
+        #[private]
+        IntOrStr = i32 | String
+    );
+
     #[test]
     fn test_py_type_alias_constants() {
         assert_eq!(CustomTypeOrInt::NAME, "CustomTypeOrInt");
         assert_eq!(CustomTypeOrInt::MODULE, "test_module");
+        assert_eq!(IntOrStr::NAME, "_IntOrStr");
     }
 }
@@ -0,0 +1,182 @@
+//! Runtime support for `#[gen_stub(named_tuple(...))]`.
+//!
+//! A function with `#[gen_stub(named_tuple(name = "Stats", fields("min", "max")))]` returns
+//! an instance of a `collections.namedtuple` class instead of a plain tuple,
+//! so that `stats(...).min` works as the stub declares.
+//! [`PyModuleNamedTupleExt::add_named_tuples`] adds the classes to the module,
+//! so that they can be imported from it as well:
+//!
+//! ```rust,ignore
+//! use pyo3::prelude::*;
+//! use pyo3_stub_gen::runtime::PyModuleNamedTupleExt;
+//!
+//! #[pymodule]
+//! fn my_module(m: &Bound<PyModule>) -> PyResult<()> {
+//!     m.add_function(wrap_pyfunction!(stats, m)?)?;
+//!     m.add_named_tuples("my_module")?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::type_info::NamedTupleInfo;
+use ::pyo3::prelude::*;
+use ::pyo3::types::{PyDict, PyModule, PyTuple, PyType};
+use ::pyo3::BoundObject;
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, PoisonError},
+};
+
+/// Classes created by [named_tuple_class], by their name and fields
+static CLASSES: Mutex<BTreeMap<(&str, Vec<&str>), Py<PyType>>> = Mutex::new(BTreeMap::new());
+
+/// `collections.namedtuple` class of the name and fields, created once and shared by all functions returning it
+fn named_tuple_class<'py>(
+    py: Python<'py>,
+    module: Option<&str>,
+    name: &'static str,
+    fields: Vec<&'static str>,
+) -> PyResult<Bound<'py, PyType>> {
+    let key = (name, fields);
+    if let Some(class) = CLASSES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        return Ok(class.bind(py).clone());
+    }
+
+    // Create the class without holding the lock, since calling Python may switch threads
+    let kwargs = PyDict::new(py);
+    if let Some(module) = module {
+        kwargs.set_item("module", module)?;
+    }
+    let class = py
+        .import("collections")?
+        .getattr("namedtuple")?
+        .call((name, key.1.clone()), Some(&kwargs))?
+        .cast_into::<PyType>()?;
+    let mut classes = CLASSES.lock().unwrap_or_else(PoisonError::into_inner);
+    let class = classes.entry(key).or_insert_with(|| class.unbind());
+    Ok(class.bind(py).clone())
+}
+
+/// Tuple returned by a function with `#[gen_stub(named_tuple(...))]`,
+/// converted into an instance of the `collections.namedtuple` class of the name and fields.
+///
+/// The macro makes the function return this in place of the tuple, so it is not written by hand.
+#[doc(hidden)]
+pub struct NamedTuple<T> {
+    module: Option<&'static str>,
+    name: &'static str,
+    fields: &'static [&'static str],
+    values: T,
+}
+
+impl<T> NamedTuple<T> {
+    pub const fn new(
+        module: Option<&'static str>,
+        name: &'static str,
+        fields: &'static [&'static str],
+        values: T,
+    ) -> Self {
+        Self {
+            module,
+            name,
+            fields,
+            values,
+        }
+    }
+}
+
+impl<'py, T> IntoPyObject<'py> for NamedTuple<T>
+where
+    T: IntoPyObject<'py, Target = PyTuple>,
+    T::Error: Into<PyErr>,
+{
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        let class = named_tuple_class(py, self.module, self.name, self.fields.to_vec())?;
+        let values = self.values.into_pyobject(py).map_err(Into::into)?;
+        class.call1(values.into_bound())
+    }
+}
+
+/// Extension trait for `Bound<PyModule>` to add the classes of `typing.NamedTuple`s returned by functions.
+pub trait PyModuleNamedTupleExt {
+    /// Adds the classes of `#[gen_stub(named_tuple(...))]` of the functions in `module`.
+    ///
+    /// `module` is the fully qualified module name used for stub generation, e.g. `"pkg.sub"`.
+    /// Functions without a module belong to the default module, which is not known at runtime,
+    /// so their classes are added to any module.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if creating a class or adding it to the module fails.
+    fn add_named_tuples(&self, module: &str) -> PyResult<()>;
+}
+
+impl PyModuleNamedTupleExt for Bound<'_, PyModule> {
+    fn add_named_tuples(&self, module: &str) -> PyResult<()> {
+        for info in inventory::iter::<NamedTupleInfo>() {
+            if info.module.is_some_and(|m| m != module) {
+                continue;
+            }
+            let fields = info.fields.iter().map(|field| field.name).collect();
+            let class = named_tuple_class(self.py(), Some(module), info.name, fields)?;
+            // The class may be created by a function call before, without knowing the module
+            class.setattr("__module__", module)?;
+            self.add(info.name, class)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::NamedTupleFieldInfo;
+
+    inventory::submit! {
+        NamedTupleInfo {
+            module: Some("test_named_tuple_module"),
+            name: "Stats",
+            doc: "",
+            fields: &[
+                NamedTupleFieldInfo {
+                    name: "min",
+                    r#type: <f64 as crate::PyStubType>::type_output,
+                },
+                NamedTupleFieldInfo {
+                    name: "count",
+                    r#type: <usize as crate::PyStubType>::type_output,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_named_tuple() {
+        pyo3::Python::initialize();
+        Python::attach(|py| {
+            let value = NamedTuple::new(None, "Stats", &["min", "count"], (1.5, 3))
+                .into_pyobject(py)
+                .unwrap();
+            let min: f64 = value.getattr("min").unwrap().extract().unwrap();
+            let count: usize = value.getattr("count").unwrap().extract().unwrap();
+            assert_eq!((min, count), (1.5, 3));
+            assert!(value.is_instance_of::<PyTuple>());
+
+            // The module has the class of the returned values
+            let m = PyModule::new(py, "test_named_tuple_module").unwrap();
+            m.add_named_tuples("test_named_tuple_module").unwrap();
+            let class = m.getattr("Stats").unwrap();
+            assert!(value.get_type().is(&class));
+            let module: String = class.getattr("__module__").unwrap().extract().unwrap();
+            assert_eq!(module, "test_named_tuple_module");
+        });
+    }
+}
@@ -0,0 +1,215 @@
+//! Runtime support for the `rename` configuration.
+//!
+//! `rename = "camelCase"` in `[tool.pyo3-stub-gen]` writes functions and methods by the renamed names,
+//! e.g. `getValue` for `get_value`, while PyO3 exposes them by the names in Rust.
+//! [`PyModuleRenameExt::add_renamed_items`] adds them to the module and its classes by the renamed names too,
+//! so that the names in stub files are found at runtime:
+//!
+//! ```rust,ignore
+//! use pyo3::prelude::*;
+//! use pyo3_stub_gen::{pyproject::RenameRule, runtime::PyModuleRenameExt};
+//!
+//! #[pymodule]
+//! fn my_module(m: &Bound<PyModule>) -> PyResult<()> {
+//!     m.add_function(wrap_pyfunction!(get_value, m)?)?;
+//!     m.add_class::<Counter>()?;
+//!     // The same rule as `rename` in pyproject.toml
+//!     m.add_renamed_items("my_module", RenameRule::CamelCase)?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::pyproject::RenameRule;
+use crate::type_info::{PyClassInfo, PyComplexEnumInfo, PyEnumInfo, PyFunctionInfo, PyMethodsInfo};
+use ::pyo3::prelude::*;
+use ::pyo3::types::PyModule;
+use std::{any::TypeId, collections::HashMap};
+
+/// Extension trait for `Bound<PyModule>` to expose functions and methods by the names given by `rename`.
+pub trait PyModuleRenameExt {
+    /// Adds the functions in `module` and the methods of its classes by the names renamed by `rule`,
+    /// keeping the original names.
+    ///
+    /// `module` is the fully qualified module name used for stub generation, e.g. `"pkg.sub"`,
+    /// and `rule` must be the same as `rename` in pyproject.toml.
+    /// Functions and classes without a module belong to the default module, which is not known at runtime,
+    /// so they are renamed in any module. Items not added to this module are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if setting an attribute of the module or a class fails,
+    /// e.g. for a class declared by `#[pyclass(immutable_type)]`.
+    fn add_renamed_items(&self, module: &str, rule: RenameRule) -> PyResult<()>;
+}
+
+impl PyModuleRenameExt for Bound<'_, PyModule> {
+    fn add_renamed_items(&self, module: &str, rule: RenameRule) -> PyResult<()> {
+        let in_module = |m: Option<&str>| m.is_none_or(|m| m == module);
+
+        for info in inventory::iter::<PyFunctionInfo>() {
+            let renamed = rule.apply(info.name);
+            if !in_module(info.module) || renamed == info.name {
+                continue;
+            }
+            if let Ok(function) = self.getattr(info.name) {
+                self.setattr(renamed.as_str(), function)?;
+            }
+        }
+
+        let mut classes: HashMap<TypeId, &str> = HashMap::new();
+        for info in inventory::iter::<PyClassInfo>() {
+            if in_module(info.module) {
+                classes.insert((info.struct_id)(), info.pyclass_name);
+            }
+        }
+        for info in inventory::iter::<PyComplexEnumInfo>() {
+            if in_module(info.module) {
+                classes.insert((info.enum_id)(), info.pyclass_name);
+            }
+        }
+        for info in inventory::iter::<PyEnumInfo>() {
+            if in_module(info.module) {
+                classes.insert((info.enum_id)(), info.pyclass_name);
+            }
+        }
+        for info in inventory::iter::<PyMethodsInfo>() {
+            let class_name = match &info.python_class {
+                Some(class) if in_module(class.module) => class.name,
+                Some(_) => continue,
+                None => match classes.get(&(info.struct_id)()) {
+                    Some(name) => name,
+                    None => continue,
+                },
+            };
+            let Ok(class) = self.getattr(class_name) else {
+                continue;
+            };
+            // Copy the descriptors in the class dict, so that static and class methods keep their kinds
+            let dict = class.getattr("__dict__")?;
+            for method in info.methods {
+                let renamed = rule.apply(method.name);
+                if renamed == method.name {
+                    continue;
+                }
+                if let Ok(descriptor) = dict.get_item(method.name) {
+                    class.setattr(renamed.as_str(), descriptor)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::{MethodInfo, MethodType};
+    use crate::TypeInfo;
+
+    #[pyfunction]
+    fn get_value() -> i32 {
+        42
+    }
+
+    #[pyclass(module = "test_rename_module")]
+    struct Counter;
+
+    #[pymethods]
+    impl Counter {
+        #[new]
+        fn new() -> Self {
+            Counter
+        }
+
+        fn add_one(&self, value: i32) -> i32 {
+            value + 1
+        }
+
+        #[staticmethod]
+        fn from_zero() -> i32 {
+            0
+        }
+    }
+
+    inventory::submit! {
+        PyFunctionInfo {
+            module: Some("test_rename_module"),
+            ..PyFunctionInfo::fixture("get_value", TypeInfo::none)
+        }
+    }
+
+    inventory::submit! {
+        PyClassInfo {
+            struct_id: TypeId::of::<Counter>,
+            module: Some("test_rename_module"),
+            ..PyClassInfo::fixture("Counter")
+        }
+    }
+
+    inventory::submit! {
+        PyMethodsInfo {
+            struct_id: TypeId::of::<Counter>,
+            python_class: None,
+            attrs: &[],
+            getters: &[],
+            setters: &[],
+            methods: &[
+                MethodInfo {
+                    name: "add_one",
+                    parameters: &[],
+                    r#return: TypeInfo::none,
+                    doc: "",
+                    r#type: MethodType::Instance,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_overload: false,
+                },
+                MethodInfo {
+                    name: "from_zero",
+                    parameters: &[],
+                    r#return: TypeInfo::none,
+                    doc: "",
+                    r#type: MethodType::Static,
+                    is_async: false,
+                    deprecated: None,
+                    type_ignored: None,
+                    is_overload: false,
+                },
+            ],
+            file: "",
+            line: 0,
+            column: 0,
+        }
+    }
+
+    #[test]
+    fn test_add_renamed_items() {
+        pyo3::Python::initialize();
+        Python::attach(|py| {
+            let m = PyModule::new(py, "test_rename_module").unwrap();
+            m.add_function(wrap_pyfunction!(get_value, &m).unwrap())
+                .unwrap();
+            m.add_class::<Counter>().unwrap();
+            m.add_renamed_items("test_rename_module", RenameRule::CamelCase)
+                .unwrap();
+
+            // The names written in stub files by `rename = "camelCase"`, and the original ones
+            for name in ["getValue", "get_value"] {
+                let value: i32 = m.getattr(name).unwrap().call0().unwrap().extract().unwrap();
+                assert_eq!(value, 42);
+            }
+            let counter = m.getattr("Counter").unwrap();
+            let value: i32 = counter
+                .call0()
+                .unwrap()
+                .call_method1("addOne", (1,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(value, 2);
+            let value: i32 = counter.call_method0("fromZero").unwrap().extract().unwrap();
+            assert_eq!(value, 0);
+        });
+    }
+}
@@ -0,0 +1,99 @@
+//! Runtime support for comparing the module tree with stub files.
+//!
+//! Submodules are usually created in helper functions called from `#[pymodule]`,
+//! which the stub generator cannot see. [`module_tree`] walks the module actually built
+//! by `add_submodule` so that [`StubInfo::reconcile_runtime_modules`](crate::StubInfo::reconcile_runtime_modules)
+//! can compare it with the declared modules.
+
+use ::pyo3::prelude::*;
+use ::pyo3::types::{PyDict, PyModule};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Public attribute names of each module, keyed by dotted module path
+pub(crate) type ModuleTree = BTreeMap<String, BTreeSet<String>>;
+
+/// Collect `module` named `name` and its submodules reachable by attributes.
+///
+/// Modules imported from elsewhere, e.g. `sys` set as an attribute, are not treated as submodules.
+pub(crate) fn module_tree(module: &Bound<PyModule>, name: &str) -> PyResult<ModuleTree> {
+    let sys_modules = module
+        .py()
+        .import("sys")?
+        .getattr("modules")?
+        .cast_into::<PyDict>()?;
+    let mut tree = ModuleTree::new();
+    collect(module, name.to_string(), &sys_modules, &mut tree)?;
+    Ok(tree)
+}
+
+fn collect(
+    module: &Bound<PyModule>,
+    path: String,
+    sys_modules: &Bound<PyDict>,
+    tree: &mut ModuleTree,
+) -> PyResult<()> {
+    let mut attrs = BTreeSet::new();
+    let mut submodules = Vec::new();
+    for attr in module.dir()? {
+        let attr: String = attr.extract()?;
+        if attr.starts_with("__") {
+            continue;
+        }
+        let value = module.getattr(attr.as_str())?;
+        if let Ok(submodule) = value.cast_into::<PyModule>() {
+            let sub_path = format!("{path}.{attr}");
+            let sub_name: String = submodule.name()?.extract()?;
+            // `add_submodule` registers a module by its `__name__`,
+            // which is either the bare name or the fully qualified one put into `sys.modules`
+            let imported = sys_modules
+                .get_item(&sub_name)?
+                .is_some_and(|m| m.is(&submodule));
+            if !(sub_name == sub_path || (sub_name == attr && !imported)) {
+                continue;
+            }
+            submodules.push((sub_path, submodule));
+        }
+        attrs.insert(attr);
+    }
+    tree.insert(path, attrs);
+    for (sub_path, submodule) in submodules {
+        if !tree.contains_key(&sub_path) {
+            collect(&submodule, sub_path, sys_modules, tree)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_tree() {
+        pyo3::Python::initialize();
+        Python::attach(|py| {
+            let m = PyModule::new(py, "tree_root").unwrap();
+            m.add("value", 1).unwrap();
+            m.add("sys", py.import("sys").unwrap()).unwrap();
+            let sub = PyModule::new(py, "sub").unwrap();
+            let deep = PyModule::new(py, "deep").unwrap();
+            deep.add("item", 2).unwrap();
+            sub.add_submodule(&deep).unwrap();
+            m.add_submodule(&sub).unwrap();
+
+            let tree = module_tree(&m, "pkg.tree_root").unwrap();
+            assert_eq!(
+                tree.keys().collect::<Vec<_>>(),
+                [
+                    "pkg.tree_root",
+                    "pkg.tree_root.sub",
+                    "pkg.tree_root.sub.deep"
+                ]
+            );
+            assert!(tree["pkg.tree_root"].contains("value"));
+            assert!(tree["pkg.tree_root"].contains("sub"));
+            assert!(!tree["pkg.tree_root"].contains("sys"));
+            assert!(tree["pkg.tree_root.sub.deep"].contains("item"));
+        });
+    }
+}
@@ -1,5 +1,9 @@
+mod async_iter;
 mod builtins;
+mod callable;
 mod collections;
+mod datetime;
+mod foreign;
 mod pyo3;
 
 #[cfg(feature = "numpy")]
@@ -11,6 +15,20 @@ mod either;
 #[cfg(feature = "rust_decimal")]
 mod rust_decimal;
 
+#[cfg(feature = "uuid")]
+mod uuid;
+
+#[cfg(feature = "ipnet")]
+mod ipnet;
+
+#[cfg(feature = "url")]
+mod url;
+
+pub use async_iter::{PyAsyncIterable, PyAsyncIterator};
+pub use callable::PyCallable;
+pub use datetime::{TzAware, TzNaive};
+pub use foreign::{RegisteredStubType, StubTypeFor, StubTypeOf};
+
 use maplit::hashset;
 use std::cmp::Ordering;
 use std::{
@@ -22,6 +40,7 @@ use std::{
 /// Module: The purpose is to import the entire module(eg import builtins).
 /// Type: The purpose is to import the types in the module(eg from moduleX import typeX).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ImportRef {
     Module(ModuleRef),
     Type(TypeRef),
@@ -33,6 +52,17 @@ impl From<&str> for ImportRef {
     }
 }
 
+impl ImportRef {
+    /// Name of the imported module or the module of the imported type, `default` for [ModuleRef::Default]
+    pub(crate) fn module_name<'a>(&'a self, default: &'a str) -> &'a str {
+        match self {
+            ImportRef::Module(module_ref) => module_ref.get(),
+            ImportRef::Type(type_ref) => type_ref.module.get(),
+        }
+        .unwrap_or(default)
+    }
+}
+
 impl PartialOrd for ImportRef {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -51,6 +81,7 @@ impl Ord for ImportRef {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ModuleRef {
     Named(String),
 
@@ -85,6 +116,7 @@ impl From<&str> for ModuleRef {
 /// from module import type.
 /// name, type name. module, module name(which type defined).
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TypeRef {
     pub module: ModuleRef,
     pub name: String,
@@ -101,6 +133,7 @@ impl TypeRef {
 
 /// Represents how a type identifier should be qualified in stub files.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ImportKind {
     /// Type is imported by name (from module import Type).
     /// It can be used unqualified in the target module.
@@ -116,6 +149,7 @@ pub enum ImportKind {
 /// Represents a reference to a type identifier within a compound type expression.
 /// Tracks which module the type comes from and how it should be qualified.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TypeIdentifierRef {
     /// The module where this type is defined.
     pub module: ModuleRef,
@@ -125,6 +159,7 @@ pub struct TypeIdentifierRef {
 
 /// Type information for creating Python stub files annotated by [PyStubType] trait.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TypeInfo {
     /// The Python type name.
     pub name: String,
@@ -151,6 +186,13 @@ pub struct TypeInfo {
     /// - Key: bare identifier (e.g., "ClassA")
     /// - Value: TypeIdentifierRef containing module and import kind
     pub type_refs: HashMap<String, TypeIdentifierRef>,
+
+    /// Why the type falls back to `typing.Any`, reported by `strict` in `[tool.pyo3-stub-gen]`.
+    ///
+    /// `None` for types written explicitly, e.g. [TypeInfo::any] or `#[gen_stub(override_type(...))]`.
+    /// Compound types like `list[typing.Any]` inherit the reason of their inner types.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub any_fallback: Option<&'static str>,
 }
 
 impl fmt::Display for TypeInfo {
@@ -169,6 +211,7 @@ impl TypeInfo {
             source_module: None,
             import: HashSet::new(),
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 
@@ -179,122 +222,111 @@ impl TypeInfo {
             source_module: None,
             import: hashset! { "typing".into() },
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 
     /// A `list[Type]` type annotation.
-    pub fn list_of<T: PyStubType>() -> Self {
-        let inner = T::type_output();
-        let mut import = inner.import.clone();
-        import.insert("builtins".into());
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    /// let list = TypeInfo::list_of(u32::type_output());
+    /// assert_eq!(list.name, "builtins.list[builtins.int]");
+    /// ```
+    pub fn list_of(inner: TypeInfo) -> Self {
+        let name = format!("builtins.list[{}]", inner.name);
+        Self::compound(name, "builtins", &[inner])
+    }
 
-        // Build type_refs from inner type
-        let mut type_refs = HashMap::new();
-        if let Some(ref source_module) = inner.source_module {
-            if let Some(_module_name) = source_module.get() {
-                // Extract bare type identifier from the (potentially qualified) name
-                let bare_name = inner
-                    .name
-                    .split('[')
-                    .next()
-                    .unwrap_or(&inner.name)
-                    .split('.')
-                    .next_back()
-                    .unwrap_or(&inner.name);
-                type_refs.insert(
-                    bare_name.to_string(),
-                    TypeIdentifierRef {
-                        module: source_module.clone(),
-                        import_kind: ImportKind::Module,
-                    },
-                );
-            }
-        }
-        type_refs.extend(inner.type_refs);
+    /// A `set[Type]` type annotation.
+    pub fn set_of(inner: TypeInfo) -> Self {
+        let name = format!("builtins.set[{}]", inner.name);
+        Self::compound(name, "builtins", &[inner])
+    }
 
-        TypeInfo {
-            name: format!("builtins.list[{}]", inner.name),
-            source_module: None,
-            import,
-            type_refs,
-        }
+    /// A `dict[Key, Value]` type annotation.
+    pub fn dict_of(key: TypeInfo, value: TypeInfo) -> Self {
+        let name = format!("builtins.dict[{}, {}]", key.name, value.name);
+        Self::compound(name, "builtins", &[key, value])
     }
 
-    /// A `set[Type]` type annotation.
-    pub fn set_of<T: PyStubType>() -> Self {
-        let inner = T::type_output();
-        let mut import = inner.import.clone();
-        import.insert("builtins".into());
+    /// A `typing.Optional[Type]` type annotation.
+    pub fn optional(inner: TypeInfo) -> Self {
+        let name = format!("typing.Optional[{}]", inner.name);
+        Self::compound(name, "typing", &[inner])
+    }
 
-        // Build type_refs from inner type
-        let mut type_refs = HashMap::new();
-        if let Some(ref source_module) = inner.source_module {
-            if let Some(_module_name) = source_module.get() {
-                let bare_name = inner
-                    .name
-                    .split('[')
-                    .next()
-                    .unwrap_or(&inner.name)
-                    .split('.')
-                    .next_back()
-                    .unwrap_or(&inner.name);
-                type_refs.insert(
-                    bare_name.to_string(),
-                    TypeIdentifierRef {
-                        module: source_module.clone(),
-                        import_kind: ImportKind::Module,
-                    },
-                );
-            }
-        }
-        type_refs.extend(inner.type_refs);
+    /// A `typing_extensions.NotRequired[Type]` annotation of an optional key of a `TypedDict`.
+    pub fn not_required(inner: TypeInfo) -> Self {
+        let name = format!("typing_extensions.NotRequired[{}]", inner.name);
+        Self::compound(name, "typing_extensions", &[inner])
+    }
 
-        TypeInfo {
-            name: format!("builtins.set[{}]", inner.name),
-            source_module: None,
-            import,
-            type_refs,
-        }
+    /// A `collections.abc.Callable[[Args, ...], Return]` type annotation.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    /// let callback = TypeInfo::callable([String::type_input()], bool::type_output());
+    /// assert_eq!(
+    ///     callback.name,
+    ///     "collections.abc.Callable[[builtins.str], builtins.bool]"
+    /// );
+    /// ```
+    pub fn callable(args: impl IntoIterator<Item = TypeInfo>, ret: TypeInfo) -> Self {
+        let mut inners: Vec<TypeInfo> = args.into_iter().collect();
+        let args = inners
+            .iter()
+            .map(|arg| arg.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let name = format!("collections.abc.Callable[[{args}], {}]", ret.name);
+        inners.push(ret);
+        Self::compound(name, "collections.abc", &inners)
     }
 
-    /// A `dict[Type]` type annotation.
-    pub fn dict_of<K: PyStubType, V: PyStubType>() -> Self {
-        let inner_k = K::type_output();
-        let inner_v = V::type_output();
-        let mut import = inner_k.import.clone();
-        import.extend(inner_v.import.clone());
-        import.insert("builtins".into());
+    /// A `collections.abc.Callable[P, Return]` type annotation forwarding the parameters `P`,
+    /// which is a `typing.ParamSpec` declared by [crate::param_spec!]
+    pub fn callable_with_param_spec(params: TypeInfo, ret: TypeInfo) -> Self {
+        let name = format!("collections.abc.Callable[{}, {}]", params.name, ret.name);
+        Self::compound(name, "collections.abc", &[params, ret])
+    }
 
-        // Build type_refs from both inner types
+    /// A union `A | B | ...` of the given types, equivalent to joining them with `|`.
+    ///
+    /// An empty union is `typing.NoReturn`, the type without any value.
+    ///
+    /// ```
+    /// use pyo3_stub_gen::{PyStubType, TypeInfo};
+    /// let union = TypeInfo::union([u32::type_output(), String::type_output(), TypeInfo::none()]);
+    /// assert_eq!(union.name, "builtins.int | builtins.str | None");
+    /// ```
+    pub fn union(members: impl IntoIterator<Item = TypeInfo>) -> Self {
+        members
+            .into_iter()
+            .reduce(|acc, member| acc | member)
+            .unwrap_or_else(|| Self {
+                name: "typing.NoReturn".to_string(),
+                source_module: None,
+                import: hashset! { "typing".into() },
+                type_refs: HashMap::new(),
+                any_fallback: None,
+            })
+    }
+
+    /// A type expression `name` built from `inners`, whose imports and type references are merged.
+    fn compound(name: String, import: &str, inners: &[TypeInfo]) -> Self {
+        let mut imports = hashset! { import.into() };
         let mut type_refs = HashMap::new();
-        for inner in [&inner_k, &inner_v] {
-            if let Some(ref source_module) = inner.source_module {
-                if let Some(_module_name) = source_module.get() {
-                    let bare_name = inner
-                        .name
-                        .split('[')
-                        .next()
-                        .unwrap_or(&inner.name)
-                        .split('.')
-                        .next_back()
-                        .unwrap_or(&inner.name);
-                    type_refs.insert(
-                        bare_name.to_string(),
-                        TypeIdentifierRef {
-                            module: source_module.clone(),
-                            import_kind: ImportKind::Module,
-                        },
-                    );
-                }
-            }
-            type_refs.extend(inner.type_refs.clone());
+        for inner in inners {
+            imports.extend(inner.import.iter().cloned());
+            type_refs.extend(collections::build_type_refs_from_inner(inner));
         }
-
-        TypeInfo {
-            name: format!("builtins.dict[{}, {}]", inner_k.name, inner_v.name),
+        Self {
+            name,
             source_module: None,
-            import,
+            import: imports,
             type_refs,
+            any_fallback: inners.iter().find_map(|inner| inner.any_fallback),
         }
     }
 
@@ -305,6 +337,7 @@ impl TypeInfo {
             source_module: None,
             import: hashset! { "builtins".into() },
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 
@@ -315,6 +348,7 @@ impl TypeInfo {
             source_module: None,
             import: hashset! {},
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 
@@ -331,6 +365,7 @@ impl TypeInfo {
             source_module: Some(module),
             import,
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 
@@ -390,6 +425,7 @@ impl TypeInfo {
             source_module: Some(module),
             import,
             type_refs,
+            any_fallback: None,
         }
     }
 
@@ -511,6 +547,44 @@ impl TypeInfo {
             }
         }
     }
+
+    /// Replace references to the type alias `alias_module.alias_name` with its definition `alias_type`.
+    ///
+    /// Returns `true` if this type referenced the alias.
+    pub fn inline_alias(
+        &mut self,
+        alias_module: &str,
+        alias_name: &str,
+        alias_type: &TypeInfo,
+    ) -> bool {
+        use crate::generate::qualifier::substitute_identifier;
+        let module_component = alias_module.rsplit('.').next().unwrap_or(alias_module);
+        let Some(name) =
+            substitute_identifier(&self.name, module_component, alias_name, &alias_type.name)
+        else {
+            return false;
+        };
+        self.name = name;
+        // The expression is no longer a single type defined in a module
+        self.source_module = None;
+        self.import.retain(|import| match import {
+            ImportRef::Type(type_ref) => {
+                type_ref.name != alias_name
+                    || type_ref.module.get().is_some_and(|m| m != alias_module)
+            }
+            ImportRef::Module(_) => true,
+        });
+        self.import.extend(alias_type.import.iter().cloned());
+        self.type_refs.remove(alias_name);
+        self.type_refs.extend(
+            alias_type
+                .type_refs
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone())),
+        );
+        self.any_fallback = self.any_fallback.or(alias_type.any_fallback);
+        true
+    }
 }
 
 impl ops::BitOr for TypeInfo {
@@ -526,6 +600,7 @@ impl ops::BitOr for TypeInfo {
             source_module: None, // Union types are synthetic, have no source module
             import: self.import,
             type_refs: merged_type_refs,
+            any_fallback: self.any_fallback.or(rhs.any_fallback),
         }
     }
 }
@@ -600,6 +675,12 @@ macro_rules! impl_stub_type {
 ///
 /// This trait is used to generate Python stub files (`.pyi`) from Rust types.
 /// For runtime type alias support, see [`runtime::PyRuntimeType`](crate::runtime::PyRuntimeType).
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no Python type for stub files",
+    label = "`PyStubType` is not implemented for `{Self}`",
+    note = "annotate the type by `#[gen_stub_pyclass]` or similar macros, or implement `PyStubType` manually",
+    note = "Rust types in `pyo3_stub_gen.RustType[\"...\"]` markers of Python stubs must implement it as well"
+)]
 pub trait PyStubType {
     /// The type to be used in the output signature, i.e. return type of the Python function or methods.
     fn type_output() -> TypeInfo;
@@ -622,6 +703,12 @@ mod test {
 
     #[test_case(bool::type_input(), "builtins.bool", hashset! { "builtins".into() } ; "bool_input")]
     #[test_case(<&str>::type_input(), "builtins.str", hashset! { "builtins".into() } ; "str_input")]
+    #[test_case(<std::borrow::Cow<'static, str>>::type_output(), "builtins.str", hashset! { "builtins".into() } ; "Cow_str_output")]
+    #[test_case(<std::borrow::Cow<'static, std::ffi::OsStr>>::type_input(), "builtins.str", hashset! { "builtins".into() } ; "Cow_OsStr_input")]
+    #[test_case(<std::sync::Arc<str>>::type_output(), "builtins.str", hashset! { "builtins".into() } ; "Arc_str_output")]
+    #[test_case(<Box<[u32; 2]>>::type_output(), "tuple[builtins.int, builtins.int]", hashset! { "builtins".into() } ; "Box_array_output")]
+    #[test_case(<std::sync::Arc<std::sync::Mutex<Vec<u32>>>>::type_output(), "builtins.list[builtins.int]", hashset! { "builtins".into() } ; "Arc_Mutex_Vec_u32_output")]
+    #[test_case(<std::rc::Rc<std::sync::RwLock<String>>>::type_input(), "builtins.str", hashset! { "builtins".into() } ; "Rc_RwLock_String_input")]
     #[test_case(Vec::<u32>::type_input(), "typing.Sequence[builtins.int]", hashset! { "typing".into(), "builtins".into() } ; "Vec_u32_input")]
     #[test_case(Vec::<u32>::type_output(), "builtins.list[builtins.int]", hashset! {  "builtins".into() } ; "Vec_u32_output")]
     #[test_case(HashMap::<u32, String>::type_input(), "typing.Mapping[builtins.int, builtins.str]", hashset! { "typing".into(), "builtins".into() } ; "HashMap_u32_String_input")]
@@ -630,9 +717,17 @@ mod test {
     #[test_case(indexmap::IndexMap::<u32, String>::type_output(), "builtins.dict[builtins.int, builtins.str]", hashset! { "builtins".into() } ; "IndexMap_u32_String_output")]
     #[test_case(HashMap::<u32, Vec<u32>>::type_input(), "typing.Mapping[builtins.int, typing.Sequence[builtins.int]]", hashset! { "builtins".into(), "typing".into() } ; "HashMap_u32_Vec_u32_input")]
     #[test_case(HashMap::<u32, Vec<u32>>::type_output(), "builtins.dict[builtins.int, builtins.list[builtins.int]]", hashset! { "builtins".into() } ; "HashMap_u32_Vec_u32_output")]
+    #[test_case(<[f64; 3]>::type_output(), "builtins.list[builtins.float]", hashset! { "builtins".into() } ; "array_f64_3_output")]
+    #[test_case(<[f64; 3]>::type_input(), "collections.abc.Sequence[builtins.float]", hashset! { "collections.abc".into(), "builtins".into() } ; "array_f64_3_input")]
+    #[test_case(<[Vec<u32>; 2]>::type_input(), "collections.abc.Sequence[typing.Sequence[builtins.int]]", hashset! { "collections.abc".into(), "typing".into(), "builtins".into() } ; "array_Vec_u32_2_input")]
     #[test_case(HashSet::<u32>::type_input(), "builtins.set[builtins.int]", hashset! { "builtins".into() } ; "HashSet_u32_input")]
     #[test_case(indexmap::IndexSet::<u32>::type_input(), "builtins.set[builtins.int]", hashset! { "builtins".into() } ; "IndexSet_u32_input")]
-    #[test_case(TypeInfo::dict_of::<u32, String>(), "builtins.dict[builtins.int, builtins.str]", hashset! { "builtins".into() } ; "dict_of_u32_String")]
+    #[test_case(TypeInfo::dict_of(u32::type_output(), String::type_output()), "builtins.dict[builtins.int, builtins.str]", hashset! { "builtins".into() } ; "dict_of_u32_String")]
+    #[test_case(TypeInfo::optional(Vec::<u32>::type_input()), "typing.Optional[typing.Sequence[builtins.int]]", hashset! { "typing".into(), "builtins".into() } ; "optional_Vec_u32_input")]
+    #[test_case(TypeInfo::callable([u32::type_input(), TypeInfo::any()], TypeInfo::none()), "collections.abc.Callable[[builtins.int, typing.Any], None]", hashset! { "collections.abc".into(), "builtins".into(), "typing".into() } ; "callable_int_any")]
+    #[test_case(TypeInfo::callable([], bool::type_output()), "collections.abc.Callable[[], builtins.bool]", hashset! { "collections.abc".into(), "builtins".into() } ; "callable_no_args")]
+    #[test_case(TypeInfo::union([u32::type_output(), String::type_output()]), "builtins.int | builtins.str", hashset! { "builtins".into() } ; "union_int_str")]
+    #[test_case(TypeInfo::union([]), "typing.NoReturn", hashset! { "typing".into() } ; "union_empty")]
     fn test(tinfo: TypeInfo, name: &str, import: HashSet<ImportRef>) {
         assert_eq!(tinfo.name, name);
         if import.is_empty() {
@@ -641,4 +736,23 @@ mod test {
             assert_eq!(tinfo.import, import);
         }
     }
+
+    #[test]
+    fn test_combinators_track_type_refs() {
+        let class_a = || TypeInfo::locally_defined("ClassA", "pkg.sub_mod".into());
+        let callback = TypeInfo::callable([class_a()], TypeInfo::list_of(class_a()));
+        assert_eq!(
+            callback.name,
+            "collections.abc.Callable[[sub_mod.ClassA], builtins.list[sub_mod.ClassA]]"
+        );
+        assert!(callback.import.contains(&"pkg.sub_mod".into()));
+        assert_eq!(
+            callback.type_refs["ClassA"].module,
+            ModuleRef::from("pkg.sub_mod")
+        );
+        assert_eq!(
+            callback.qualified_for_module("pkg.sub_mod"),
+            "collections.abc.Callable[[ClassA], builtins.list[ClassA]]"
+        );
+    }
 }
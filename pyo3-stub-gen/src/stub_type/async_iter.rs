@@ -0,0 +1,100 @@
+use super::collections::build_type_refs_from_inner;
+use crate::stub_type::*;
+use ::pyo3::{Bound, IntoPyObject, Py, PyAny, Python};
+use std::{convert::Infallible, marker::PhantomData};
+
+macro_rules! impl_async_wrapper {
+    ($(#[$meta:meta])* $name:ident, $abc:literal) => {
+        $(#[$meta])*
+        pub struct $name<T> {
+            inner: Py<PyAny>,
+            item: PhantomData<fn() -> T>,
+        }
+
+        impl<T> $name<T> {
+            /// Wrap a Python object, e.g. an instance of `#[pyclass]` implementing `__aiter__`
+            pub fn new<C>(object: Bound<'_, C>) -> Self {
+                Self {
+                    inner: object.into_any().unbind(),
+                    item: PhantomData,
+                }
+            }
+
+            /// Returns the wrapped Python object
+            pub fn into_inner(self) -> Py<PyAny> {
+                self.inner
+            }
+        }
+
+        impl<T> From<Py<PyAny>> for $name<T> {
+            fn from(inner: Py<PyAny>) -> Self {
+                Self {
+                    inner,
+                    item: PhantomData,
+                }
+            }
+        }
+
+        impl<'py, T> IntoPyObject<'py> for $name<T> {
+            type Target = PyAny;
+            type Output = Bound<'py, PyAny>;
+            type Error = Infallible;
+
+            fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+                Ok(self.inner.into_bound(py))
+            }
+        }
+
+        impl<T: PyStubType> PyStubType for $name<T> {
+            fn type_output() -> TypeInfo {
+                let inner = T::type_output();
+                let mut import = inner.import.clone();
+                import.insert("collections.abc".into());
+                TypeInfo {
+                    name: format!("collections.abc.{}[{}]", $abc, inner.name),
+                    source_module: None,
+                    import,
+                    type_refs: build_type_refs_from_inner(&inner),
+                    any_fallback: inner.any_fallback,
+                }
+            }
+        }
+    };
+}
+
+impl_async_wrapper!(
+    /// Python object implementing `__aiter__` and `__anext__` which yields `T`,
+    /// shown as `collections.abc.AsyncIterator[T]` in stub files
+    ///
+    /// ```rust,ignore
+    /// #[gen_stub_pyfunction]
+    /// #[pyfunction]
+    /// fn stream(py: Python<'_>) -> PyResult<PyAsyncIterator<Py<PyBytes>>> {
+    ///     Ok(PyAsyncIterator::new(Bound::new(py, ByteStream::default())?))
+    /// }
+    /// ```
+    PyAsyncIterator,
+    "AsyncIterator"
+);
+
+impl_async_wrapper!(
+    /// Python object implementing `__aiter__` which yields `T`,
+    /// shown as `collections.abc.AsyncIterable[T]` in stub files
+    PyAsyncIterable,
+    "AsyncIterable"
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_async_iterator_type() {
+        let info = PyAsyncIterator::<String>::type_output();
+        assert_eq!(info.name, "collections.abc.AsyncIterator[builtins.str]");
+        assert!(info.import.contains(&"collections.abc".into()));
+
+        let info = PyAsyncIterable::<i64>::type_output();
+        assert_eq!(info.name, "collections.abc.AsyncIterable[builtins.int]");
+    }
+}
@@ -9,7 +9,7 @@ use std::{
     ffi::{OsStr, OsString},
     path::PathBuf,
     rc::Rc,
-    sync::Arc,
+    sync::{Arc, Mutex, RwLock},
     time::SystemTime,
 };
 
@@ -83,8 +83,10 @@ impl_builtin!(&str, "str", PyString);
 impl_builtin!(OsStr, "str", PyString);
 impl_builtin!(String, "str", PyString);
 impl_builtin!(OsString, "str", PyString);
+impl_builtin!(Box<str>, "str", PyString);
+impl_builtin!(Rc<str>, "str", PyString);
+impl_builtin!(Arc<str>, "str", PyString);
 impl_builtin!(Cow<'_, str>, "str", PyString);
-impl_builtin!(Cow<'_, OsStr>, "str", PyString);
 impl_builtin!(Cow<'_, [u8]>, "bytes", ::pyo3::types::PyBytes);
 
 #[cfg(feature = "ordered-float")]
@@ -178,7 +180,7 @@ impl<T: PyRuntimeType> PyRuntimeType for &T {
     }
 }
 
-impl<T: PyStubType> PyStubType for Rc<T> {
+impl<T: PyStubType + ?Sized> PyStubType for Rc<T> {
     fn type_input() -> TypeInfo {
         T::type_input()
     }
@@ -186,13 +188,13 @@ impl<T: PyStubType> PyStubType for Rc<T> {
         T::type_output()
     }
 }
-impl<T: PyRuntimeType> PyRuntimeType for Rc<T> {
+impl<T: PyRuntimeType + ?Sized> PyRuntimeType for Rc<T> {
     fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
         T::runtime_type_object(py)
     }
 }
 
-impl<T: PyStubType> PyStubType for Arc<T> {
+impl<T: PyStubType + ?Sized> PyStubType for Arc<T> {
     fn type_input() -> TypeInfo {
         T::type_input()
     }
@@ -200,7 +202,51 @@ impl<T: PyStubType> PyStubType for Arc<T> {
         T::type_output()
     }
 }
-impl<T: PyRuntimeType> PyRuntimeType for Arc<T> {
+impl<T: PyRuntimeType + ?Sized> PyRuntimeType for Arc<T> {
+    fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        T::runtime_type_object(py)
+    }
+}
+
+// `Cow<'_, str>` and `Cow<'_, [u8]>` are defined above since `str` and `[u8]` are not `PyStubType`
+impl<B: PyStubType + ToOwned + ?Sized> PyStubType for Cow<'_, B> {
+    fn type_input() -> TypeInfo {
+        B::type_input()
+    }
+    fn type_output() -> TypeInfo {
+        B::type_output()
+    }
+}
+impl<B: PyRuntimeType + ToOwned + ?Sized> PyRuntimeType for Cow<'_, B> {
+    fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        B::runtime_type_object(py)
+    }
+}
+
+// Shared state like `Arc<Mutex<T>>` is exposed as `T`, e.g. by getters taking the lock
+impl<T: PyStubType + ?Sized> PyStubType for Mutex<T> {
+    fn type_input() -> TypeInfo {
+        T::type_input()
+    }
+    fn type_output() -> TypeInfo {
+        T::type_output()
+    }
+}
+impl<T: PyRuntimeType + ?Sized> PyRuntimeType for Mutex<T> {
+    fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        T::runtime_type_object(py)
+    }
+}
+
+impl<T: PyStubType + ?Sized> PyStubType for RwLock<T> {
+    fn type_input() -> TypeInfo {
+        T::type_input()
+    }
+    fn type_output() -> TypeInfo {
+        T::type_output()
+    }
+}
+impl<T: PyRuntimeType + ?Sized> PyRuntimeType for RwLock<T> {
     fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
         T::runtime_type_object(py)
     }
@@ -0,0 +1,111 @@
+use crate::stub_type::*;
+use ::pyo3::{exceptions::PyTypeError, prelude::*};
+use std::{convert::Infallible, marker::PhantomData};
+
+/// Python callable taking the parameters `P` and returning `R`,
+/// shown as `collections.abc.Callable[P, R]` in stub files
+///
+/// `P` is a `typing.ParamSpec` declared by [crate::param_spec!], so that a function taking a callback
+/// and returning a wrapper of it keeps the signature of the callback:
+///
+/// ```rust,ignore
+/// pyo3_stub_gen::param_spec!("my_module", P);
+/// pyo3_stub_gen::type_var!("my_module", R);
+///
+/// // def traced(f: collections.abc.Callable[P, R]) -> collections.abc.Callable[P, R]: ...
+/// #[gen_stub_pyfunction]
+/// #[pyfunction]
+/// fn traced(f: PyCallable<P, R>) -> PyCallable<P, R> {
+///     f
+/// }
+/// ```
+pub struct PyCallable<P, R> {
+    inner: Py<PyAny>,
+    signature: PhantomData<fn() -> (P, R)>,
+}
+
+impl<P, R> PyCallable<P, R> {
+    /// Wrap a Python callable object
+    pub fn new<C>(object: Bound<'_, C>) -> Self {
+        Self {
+            inner: object.into_any().unbind(),
+            signature: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped Python object
+    pub fn into_inner(self) -> Py<PyAny> {
+        self.inner
+    }
+
+    /// Borrow the wrapped Python object, e.g. to call it
+    pub fn bind<'py>(&self, py: Python<'py>) -> &Bound<'py, PyAny> {
+        self.inner.bind(py)
+    }
+}
+
+impl<P, R> From<Py<PyAny>> for PyCallable<P, R> {
+    fn from(inner: Py<PyAny>) -> Self {
+        Self {
+            inner,
+            signature: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'py, P, R> FromPyObject<'a, 'py> for PyCallable<P, R> {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'a, 'py, PyAny>) -> Result<Self, Self::Error> {
+        if !ob.is_callable() {
+            return Err(PyTypeError::new_err(format!(
+                "'{}' object is not callable",
+                ob.get_type().name()?
+            )));
+        }
+        Ok(Self::from(ob.to_owned().unbind()))
+    }
+}
+
+impl<'py, P, R> IntoPyObject<'py> for PyCallable<P, R> {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(self.inner.into_bound(py))
+    }
+}
+
+impl<P: PyStubType, R: PyStubType> PyStubType for PyCallable<P, R> {
+    fn type_output() -> TypeInfo {
+        TypeInfo::callable_with_param_spec(P::type_output(), R::type_output())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    crate::param_spec!("my_module", P);
+    crate::type_var!("my_module", R);
+
+    #[test]
+    fn test_callable_type() {
+        let info = PyCallable::<P, R>::type_output();
+        assert_eq!(
+            info.name,
+            "collections.abc.Callable[my_module.P, my_module.R]"
+        );
+        assert!(info.import.contains(&"collections.abc".into()));
+        assert!(info.import.contains(&"my_module".into()));
+        assert!(info.type_refs.contains_key("P"));
+        assert!(info.type_refs.contains_key("R"));
+
+        let info = PyCallable::<P, i32>::type_output();
+        assert_eq!(
+            info.name,
+            "collections.abc.Callable[my_module.P, builtins.int]"
+        );
+    }
+}
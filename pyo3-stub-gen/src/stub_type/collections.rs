@@ -25,7 +25,7 @@ fn extract_type_identifier(type_name: &str) -> Option<&str> {
 /// Build type_refs HashMap from inner TypeInfo for compound types
 ///
 /// If the inner type is locally-defined and qualified, track it for context-aware rendering.
-fn build_type_refs_from_inner(inner: &TypeInfo) -> HashMap<String, TypeIdentifierRef> {
+pub(super) fn build_type_refs_from_inner(inner: &TypeInfo) -> HashMap<String, TypeIdentifierRef> {
     let mut type_refs = HashMap::new();
 
     // If inner type is locally defined with a module, track it
@@ -52,34 +52,10 @@ fn build_type_refs_from_inner(inner: &TypeInfo) -> HashMap<String, TypeIdentifie
 
 impl<T: PyStubType> PyStubType for Option<T> {
     fn type_input() -> TypeInfo {
-        let inner = T::type_input();
-        let name = inner.name.clone();
-        let mut import = inner.import.clone();
-        import.insert("typing".into());
-
-        let type_refs = build_type_refs_from_inner(&inner);
-
-        TypeInfo {
-            name: format!("typing.Optional[{name}]"),
-            source_module: None,
-            import,
-            type_refs,
-        }
+        TypeInfo::optional(T::type_input())
     }
     fn type_output() -> TypeInfo {
-        let inner = T::type_output();
-        let name = inner.name.clone();
-        let mut import = inner.import.clone();
-        import.insert("typing".into());
-
-        let type_refs = build_type_refs_from_inner(&inner);
-
-        TypeInfo {
-            name: format!("typing.Optional[{name}]"),
-            source_module: None,
-            import,
-            type_refs,
-        }
+        TypeInfo::optional(T::type_output())
     }
 }
 impl<T: PyRuntimeType> PyRuntimeType for Option<T> {
@@ -91,7 +67,7 @@ impl<T: PyRuntimeType> PyRuntimeType for Option<T> {
     }
 }
 
-impl<T: PyStubType> PyStubType for Box<T> {
+impl<T: PyStubType + ?Sized> PyStubType for Box<T> {
     fn type_input() -> TypeInfo {
         T::type_input()
     }
@@ -99,7 +75,7 @@ impl<T: PyStubType> PyStubType for Box<T> {
         T::type_output()
     }
 }
-impl<T: PyRuntimeType> PyRuntimeType for Box<T> {
+impl<T: PyRuntimeType + ?Sized> PyRuntimeType for Box<T> {
     fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
         T::runtime_type_object(py)
     }
@@ -133,10 +109,11 @@ impl<T: PyStubType> PyStubType for Vec<T> {
             source_module: None,
             import,
             type_refs,
+            any_fallback: inner.any_fallback,
         }
     }
     fn type_output() -> TypeInfo {
-        TypeInfo::list_of::<T>()
+        TypeInfo::list_of(T::type_output())
     }
 }
 impl<T> PyRuntimeType for Vec<T> {
@@ -146,24 +123,15 @@ impl<T> PyRuntimeType for Vec<T> {
     }
 }
 
+/// Arrays are converted from any sequence of the same length, and into `list` by PyO3
 impl<T: PyStubType, const N: usize> PyStubType for [T; N] {
     fn type_input() -> TypeInfo {
         let inner = T::type_input();
-        let name = inner.name.clone();
-        let mut import = inner.import.clone();
-        import.insert("typing".into());
-
-        let type_refs = build_type_refs_from_inner(&inner);
-
-        TypeInfo {
-            name: format!("typing.Sequence[{name}]"),
-            source_module: None,
-            import,
-            type_refs,
-        }
+        let name = format!("collections.abc.Sequence[{}]", inner.name);
+        TypeInfo::compound(name, "collections.abc", &[inner])
     }
     fn type_output() -> TypeInfo {
-        TypeInfo::list_of::<T>()
+        TypeInfo::list_of(T::type_output())
     }
 }
 impl<T, const N: usize> PyRuntimeType for [T; N] {
@@ -174,7 +142,7 @@ impl<T, const N: usize> PyRuntimeType for [T; N] {
 
 impl<T: PyStubType, State> PyStubType for HashSet<T, State> {
     fn type_output() -> TypeInfo {
-        TypeInfo::set_of::<T>()
+        TypeInfo::set_of(T::type_output())
     }
 }
 impl<T, State> PyRuntimeType for HashSet<T, State> {
@@ -185,7 +153,7 @@ impl<T, State> PyRuntimeType for HashSet<T, State> {
 
 impl<T: PyStubType> PyStubType for BTreeSet<T> {
     fn type_output() -> TypeInfo {
-        TypeInfo::set_of::<T>()
+        TypeInfo::set_of(T::type_output())
     }
 }
 impl<T> PyRuntimeType for BTreeSet<T> {
@@ -196,7 +164,7 @@ impl<T> PyRuntimeType for BTreeSet<T> {
 
 impl<T: PyStubType> PyStubType for indexmap::IndexSet<T> {
     fn type_output() -> TypeInfo {
-        TypeInfo::set_of::<T>()
+        TypeInfo::set_of(T::type_output())
     }
 }
 impl<T> PyRuntimeType for indexmap::IndexSet<T> {
@@ -223,25 +191,11 @@ macro_rules! impl_map_stub_type {
                 source_module: None,
                 import,
                 type_refs,
+                any_fallback: key_info.any_fallback.or(value_info.any_fallback),
             }
         }
         fn type_output() -> TypeInfo {
-            let key_info = Key::type_output();
-            let value_info = Value::type_output();
-
-            let mut import = key_info.import.clone();
-            import.extend(value_info.import.clone());
-            import.insert("builtins".into());
-
-            let mut type_refs = build_type_refs_from_inner(&key_info);
-            type_refs.extend(build_type_refs_from_inner(&value_info));
-
-            TypeInfo {
-                name: format!("builtins.dict[{}, {}]", key_info.name, value_info.name),
-                source_module: None,
-                import,
-                type_refs,
-            }
+            TypeInfo::dict_of(Key::type_output(), Value::type_output())
         }
     };
 }
@@ -282,9 +236,11 @@ macro_rules! impl_tuple_stub_type {
                 let mut merged = HashSet::new();
                 let mut names = Vec::new();
                 let mut type_refs = HashMap::new();
+                let mut any_fallback = None;
                 $(
                 let info = $T::type_output();
                 type_refs.extend(build_type_refs_from_inner(&info));
+                any_fallback = any_fallback.or(info.any_fallback);
                 names.push(info.name);
                 merged.extend(info.import);
                 )*
@@ -293,15 +249,18 @@ macro_rules! impl_tuple_stub_type {
                     source_module: None,
                     import: merged,
                     type_refs,
+                    any_fallback,
                 }
             }
             fn type_input() -> TypeInfo {
                 let mut merged = HashSet::new();
                 let mut names = Vec::new();
                 let mut type_refs = HashMap::new();
+                let mut any_fallback = None;
                 $(
                 let info = $T::type_input();
                 type_refs.extend(build_type_refs_from_inner(&info));
+                any_fallback = any_fallback.or(info.any_fallback);
                 names.push(info.name);
                 merged.extend(info.import);
                 )*
@@ -310,6 +269,7 @@ macro_rules! impl_tuple_stub_type {
                     source_module: None,
                     import: merged,
                     type_refs,
+                    any_fallback,
                 }
             }
         }
@@ -0,0 +1,145 @@
+use super::collections::build_type_refs_from_inner;
+use crate::runtime::PyRuntimeType;
+use crate::stub_type::*;
+use ::pyo3::{Borrowed, Bound, FromPyObject, IntoPyObject, PyAny, PyResult, Python};
+
+/// `typing.Annotated[T, "<meta>"]`
+fn annotated(inner: TypeInfo, meta: &str) -> TypeInfo {
+    let mut import = inner.import.clone();
+    import.insert("typing".into());
+    TypeInfo {
+        name: format!("typing.Annotated[{}, \"{meta}\"]", inner.name),
+        source_module: None,
+        import,
+        type_refs: build_type_refs_from_inner(&inner),
+        any_fallback: inner.any_fallback,
+    }
+}
+
+macro_rules! impl_tz_wrapper {
+    ($(#[$meta:meta])* $name:ident, $annotation:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name<T>(pub T);
+
+        impl<T> $name<T> {
+            /// Returns the wrapped value
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> From<T> for $name<T> {
+            fn from(inner: T) -> Self {
+                Self(inner)
+            }
+        }
+
+        impl<'a, 'py, T: FromPyObject<'a, 'py>> FromPyObject<'a, 'py> for $name<T> {
+            type Error = T::Error;
+
+            fn extract(ob: Borrowed<'a, 'py, PyAny>) -> Result<Self, Self::Error> {
+                T::extract(ob).map(Self)
+            }
+        }
+
+        impl<'py, T: IntoPyObject<'py>> IntoPyObject<'py> for $name<T> {
+            type Target = T::Target;
+            type Output = T::Output;
+            type Error = T::Error;
+
+            fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+                self.0.into_pyobject(py)
+            }
+        }
+
+        impl<T: PyStubType> PyStubType for $name<T> {
+            fn type_output() -> TypeInfo {
+                annotated(T::type_output(), $annotation)
+            }
+
+            fn type_input() -> TypeInfo {
+                annotated(T::type_input(), $annotation)
+            }
+        }
+
+        impl<T: PyRuntimeType> PyRuntimeType for $name<T> {
+            fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+                T::runtime_type_object(py)
+            }
+        }
+    };
+}
+
+impl_tz_wrapper!(
+    /// Timezone-aware `datetime.datetime` (or `datetime.time`) `T`,
+    /// shown as `typing.Annotated[datetime.datetime, "aware"]` in stub files
+    ///
+    /// It is converted from and into Python objects as `T` is, e.g. by the `chrono` feature of PyO3,
+    /// so it can be used for parameters and return values. Whether the value is aware is checked by
+    /// the conversion of `T`, like `chrono::DateTime<Tz>` rejecting naive values.
+    ///
+    /// ```rust,ignore
+    /// #[gen_stub_pyfunction]
+    /// #[pyfunction]
+    /// fn now() -> TzAware<chrono::DateTime<chrono::Utc>> {
+    ///     TzAware(chrono::Utc::now())
+    /// }
+    /// ```
+    TzAware,
+    "aware"
+);
+
+impl_tz_wrapper!(
+    /// Naive `datetime.datetime` (or `datetime.time`) `T` without `tzinfo`,
+    /// shown as `typing.Annotated[datetime.datetime, "naive"]` in stub files
+    ///
+    /// It is converted from and into Python objects as `T` is, like [TzAware].
+    TzNaive,
+    "naive"
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tz_wrapper_type() {
+        let info = TzAware::<chrono::DateTime<chrono::Utc>>::type_output();
+        assert_eq!(info.name, r#"typing.Annotated[datetime.datetime, "aware"]"#);
+        assert!(info.import.contains(&"typing".into()));
+        assert!(info.import.contains(&"datetime".into()));
+
+        let info = TzNaive::<chrono::NaiveDateTime>::type_input();
+        assert_eq!(info.name, r#"typing.Annotated[datetime.datetime, "naive"]"#);
+
+        let info = Option::<TzAware<time::OffsetDateTime>>::type_output();
+        assert_eq!(
+            info.name,
+            r#"typing.Optional[typing.Annotated[datetime.datetime, "aware"]]"#
+        );
+    }
+
+    #[test]
+    fn test_tz_wrapper_conversion() {
+        use ::pyo3::types::{PyAnyMethods, PyDateTime};
+
+        Python::initialize();
+        Python::attach(|py| {
+            let now = py
+                .import("datetime")
+                .unwrap()
+                .getattr("datetime")
+                .unwrap()
+                .call_method0("now")
+                .unwrap();
+            let aware: TzAware<Bound<PyDateTime>> = now.extract().unwrap();
+            let object = aware.into_pyobject(py).unwrap();
+            assert!(object.is(&now));
+
+            // The conversion of the wrapped type decides which objects are accepted
+            let none = py.None().into_bound(py);
+            assert!(none.extract::<TzNaive<Bound<PyDateTime>>>().is_err());
+        });
+    }
+}
@@ -59,6 +59,7 @@ impl<L: PyStubType, R: PyStubType> PyStubType for either::Either<L, R> {
             source_module: None,
             import,
             type_refs,
+            any_fallback: info_l.any_fallback.or(info_r.any_fallback),
         }
     }
     fn type_output() -> TypeInfo {
@@ -81,6 +82,7 @@ impl<L: PyStubType, R: PyStubType> PyStubType for either::Either<L, R> {
             source_module: None,
             import,
             type_refs,
+            any_fallback: info_l.any_fallback.or(info_r.any_fallback),
         }
     }
 }
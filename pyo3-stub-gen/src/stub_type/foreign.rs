@@ -0,0 +1,220 @@
+//! Stub types referred to from the code generated by `#[gen_stub_*]` macros,
+//! and [crate::register_stub_type!] to give Python types to Rust types from other crates.
+//!
+//! The orphan rule forbids implementing [PyStubType] for a type defined in another crate,
+//! but implementing [RegisteredStubType] with a marker type local to the registering crate is allowed.
+//! `#[gen_stub_*]` macros refer to the type `T` through `StubTypeOf::<T>::new().type_input()`,
+//! whose marker parameter is inferred from the only implementation of [StubTypeFor] for `T`:
+//! the one for [PyStubType], or the one for a registered type.
+
+use crate::{PyStubType, TypeInfo};
+use std::marker::PhantomData;
+
+/// Stub type of `T` referred to by `#[gen_stub_*]` macros as `StubTypeOf::<T>::new().type_input()`.
+///
+/// The methods require `T: StubTypeFor<_>`, so that a type neither implementing [PyStubType]
+/// nor registered by [crate::register_stub_type!] is a compile error at the argument, return value or member using it.
+#[doc(hidden)]
+pub struct StubTypeOf<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> StubTypeOf<T> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    pub fn type_input<Marker>(&self) -> TypeInfo
+    where
+        T: StubTypeFor<Marker>,
+    {
+        <T as StubTypeFor<Marker>>::type_input()
+    }
+
+    pub fn type_output<Marker>(&self) -> TypeInfo
+    where
+        T: StubTypeFor<Marker>,
+    {
+        <T as StubTypeFor<Marker>>::type_output()
+    }
+}
+
+/// Stub type of a Rust type implementing [PyStubType] or registered by [crate::register_stub_type!],
+/// distinguished by `Marker` so that both implementations can coexist
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no Python type for stub files",
+    label = "`PyStubType` is not implemented for `{Self}`",
+    note = "annotate the type by `#[gen_stub_pyclass]` or similar macros, or implement `PyStubType` manually",
+    note = "register the type by `pyo3_stub_gen::register_stub_type!` if it is defined in another crate"
+)]
+pub trait StubTypeFor<Marker> {
+    fn type_output() -> TypeInfo;
+    fn type_input() -> TypeInfo;
+}
+
+/// Marker of [StubTypeFor] for [PyStubType]
+#[doc(hidden)]
+pub struct Implemented;
+
+impl<T: PyStubType + ?Sized> StubTypeFor<Implemented> for T {
+    fn type_output() -> TypeInfo {
+        <T as PyStubType>::type_output()
+    }
+
+    fn type_input() -> TypeInfo {
+        <T as PyStubType>::type_input()
+    }
+}
+
+/// Marker of [StubTypeFor] for [RegisteredStubType]
+#[doc(hidden)]
+pub struct Registered<Marker>(PhantomData<Marker>);
+
+impl<T: RegisteredStubType<Marker>, Marker> StubTypeFor<Registered<Marker>> for T {
+    fn type_output() -> TypeInfo {
+        <T as RegisteredStubType<Marker>>::type_output()
+    }
+
+    fn type_input() -> TypeInfo {
+        <T as RegisteredStubType<Marker>>::type_input()
+    }
+}
+
+/// Stub type of a Rust type registered by [crate::register_stub_type!] with a `Marker` defined by the macro.
+///
+/// This is also implemented for `Option` and `Vec` of registered types, with `Option<Marker>` and `Vec<Marker>`.
+#[doc(hidden)]
+pub trait RegisteredStubType<Marker> {
+    fn type_output() -> TypeInfo;
+
+    fn type_input() -> TypeInfo {
+        Self::type_output()
+    }
+}
+
+/// A registered type as [PyStubType] to render the types containing it as [PyStubType] does
+struct AsPyStubType<T, Marker>(PhantomData<(T, Marker)>);
+
+impl<T: RegisteredStubType<Marker>, Marker> PyStubType for AsPyStubType<T, Marker> {
+    fn type_output() -> TypeInfo {
+        <T as RegisteredStubType<Marker>>::type_output()
+    }
+
+    fn type_input() -> TypeInfo {
+        <T as RegisteredStubType<Marker>>::type_input()
+    }
+}
+
+impl<T: RegisteredStubType<Marker>, Marker> RegisteredStubType<Option<Marker>> for Option<T> {
+    fn type_output() -> TypeInfo {
+        <Option<AsPyStubType<T, Marker>> as PyStubType>::type_output()
+    }
+
+    fn type_input() -> TypeInfo {
+        <Option<AsPyStubType<T, Marker>> as PyStubType>::type_input()
+    }
+}
+
+impl<T: RegisteredStubType<Marker>, Marker> RegisteredStubType<Vec<Marker>> for Vec<T> {
+    fn type_output() -> TypeInfo {
+        <Vec<AsPyStubType<T, Marker>> as PyStubType>::type_output()
+    }
+
+    fn type_input() -> TypeInfo {
+        <Vec<AsPyStubType<T, Marker>> as PyStubType>::type_input()
+    }
+}
+
+/// Give a Python type to a Rust type, including a type defined in another crate.
+///
+/// The orphan rule forbids implementing [PyStubType] for a type from another crate,
+/// e.g. a type converted by PyO3 but not supported by pyo3-stub-gen.
+/// This registers the name of its Python type and the modules to import instead:
+///
+/// ```rust
+/// use pyo3_stub_gen::register_stub_type;
+///
+/// // Converted from and into `int` by PyO3
+/// register_stub_type!(std::num::NonZeroU32 => "builtins.int", imports = ["builtins"]);
+/// register_stub_type!(std::time::Instant => "float");
+/// ```
+///
+/// The registered type is resolved as an argument, return value or member type of annotated items,
+/// also in `Option` and `Vec` of it, e.g. `Option<Vec<NonZeroU32>>`.
+/// For other generic types, use `#[gen_stub(override_type(...))]`.
+/// A type can be registered once, and only if it does not implement [PyStubType].
+#[macro_export]
+macro_rules! register_stub_type {
+    ($ty:ty => $name:expr) => {
+        $crate::register_stub_type!($ty => $name, imports = []);
+    };
+    ($ty:ty => $name:expr, imports = [$($import:expr),* $(,)?]) => {
+        const _: () = {
+            // Local to the registering crate, which makes the implementation below legal for a foreign type
+            pub struct Marker;
+
+            impl $crate::RegisteredStubType<Marker> for $ty {
+                fn type_output() -> $crate::TypeInfo {
+                    $crate::TypeInfo {
+                        name: ::std::string::String::from($name),
+                        source_module: ::std::option::Option::None,
+                        import: ::std::collections::HashSet::from([$($crate::ImportRef::from($import)),*]),
+                        type_refs: ::std::collections::HashMap::new(),
+                        any_fallback: ::std::option::Option::None,
+                    }
+                }
+            }
+        };
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ImportRef;
+    use std::collections::HashSet;
+
+    // Types from other crates, which cannot implement `PyStubType` outside of pyo3-stub-gen
+    crate::register_stub_type!(std::thread::ThreadId => "foo.ThreadId", imports = ["foo"]);
+    crate::register_stub_type!(std::time::Instant => "builtins.float");
+
+    #[test]
+    fn test_resolve_implemented() {
+        assert_eq!(StubTypeOf::<i32>::new().type_output().name, "builtins.int");
+        assert_eq!(
+            StubTypeOf::<Vec<i32>>::new().type_input().name,
+            "typing.Sequence[builtins.int]"
+        );
+        assert_eq!(StubTypeOf::<str>::new().type_output().name, "builtins.str");
+    }
+
+    #[test]
+    fn test_register_stub_type() {
+        let type_info = StubTypeOf::<std::thread::ThreadId>::new().type_input();
+        assert_eq!(type_info.name, "foo.ThreadId");
+        assert_eq!(type_info.import, HashSet::from([ImportRef::from("foo")]));
+        let type_info = StubTypeOf::<std::time::Instant>::new().type_output();
+        assert_eq!(type_info.name, "builtins.float");
+        assert!(type_info.import.is_empty());
+    }
+
+    #[test]
+    fn test_register_stub_type_nested() {
+        // Input and output types follow the implementations of `PyStubType`
+        let type_info = StubTypeOf::<Vec<std::thread::ThreadId>>::new().type_output();
+        assert_eq!(type_info.name, "builtins.list[foo.ThreadId]");
+        assert!(type_info.import.contains(&ImportRef::from("foo")));
+        assert_eq!(
+            StubTypeOf::<Vec<std::thread::ThreadId>>::new()
+                .type_input()
+                .name,
+            "typing.Sequence[foo.ThreadId]"
+        );
+        assert_eq!(
+            StubTypeOf::<Option<Vec<std::time::Instant>>>::new()
+                .type_output()
+                .name,
+            "typing.Optional[builtins.list[builtins.float]]"
+        );
+    }
+}
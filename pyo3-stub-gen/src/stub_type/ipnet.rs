@@ -0,0 +1,61 @@
+use super::{PyStubType, TypeInfo};
+use crate::runtime::{union_type, PyRuntimeType};
+use ::pyo3::prelude::*;
+
+impl PyStubType for ipnet::Ipv4Net {
+    fn type_output() -> TypeInfo {
+        TypeInfo::with_module("ipaddress.IPv4Network", "ipaddress".into())
+    }
+}
+impl PyRuntimeType for ipnet::Ipv4Net {
+    fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let ipaddress = py.import("ipaddress")?;
+        ipaddress.getattr("IPv4Network")
+    }
+}
+
+impl PyStubType for ipnet::Ipv6Net {
+    fn type_output() -> TypeInfo {
+        TypeInfo::with_module("ipaddress.IPv6Network", "ipaddress".into())
+    }
+}
+impl PyRuntimeType for ipnet::Ipv6Net {
+    fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let ipaddress = py.import("ipaddress")?;
+        ipaddress.getattr("IPv6Network")
+    }
+}
+
+impl PyStubType for ipnet::IpNet {
+    fn type_output() -> TypeInfo {
+        ipnet::Ipv4Net::type_output() | ipnet::Ipv6Net::type_output()
+    }
+}
+impl PyRuntimeType for ipnet::IpNet {
+    fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let types = [
+            ipnet::Ipv4Net::runtime_type_object(py)?,
+            ipnet::Ipv6Net::runtime_type_object(py)?,
+        ];
+        union_type(py, &types)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ipnet_type() {
+        let info = ipnet::Ipv4Net::type_output();
+        assert_eq!(info.name, "ipaddress.IPv4Network");
+        assert!(info.import.contains(&"ipaddress".into()));
+
+        let info = ipnet::Ipv6Net::type_input();
+        assert_eq!(info.name, "ipaddress.IPv6Network");
+
+        let info = ipnet::IpNet::type_output();
+        assert_eq!(info.name, "ipaddress.IPv4Network | ipaddress.IPv6Network");
+        assert_eq!(info.import, ["ipaddress".into()].into_iter().collect());
+    }
+}
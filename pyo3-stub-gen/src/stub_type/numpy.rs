@@ -22,6 +22,7 @@ macro_rules! impl_numpy_scalar {
                     source_module: None,
                     import: hashset!["numpy".into()],
                     type_refs: HashMap::new(),
+                    any_fallback: None,
                 }
             }
         }
@@ -41,17 +42,29 @@ impl_numpy_scalar!(f64, "float64");
 impl_numpy_scalar!(num_complex::Complex32, "complex64");
 impl_numpy_scalar!(num_complex::Complex64, "complex128");
 
-impl<T: NumPyScalar, D> PyStubType for PyArray<T, D> {
+/// Shape of arrays of dimension `D`, e.g. `tuple[builtins.int, builtins.int]` for `Ix2`
+fn shape_type<D: Dimension>() -> String {
+    match D::NDIM {
+        Some(0) => "tuple[()]".into(),
+        Some(ndim) => format!("tuple[{}]", vec!["builtins.int"; ndim].join(", ")),
+        None => "tuple[builtins.int, ...]".into(),
+    }
+}
+
+/// Arrays are rendered as `numpy.ndarray[shape, numpy.dtype[scalar]]`,
+/// and rewritten into the style selected by `[tool.pyo3-stub-gen.numpy]` when stubs are generated.
+impl<T: NumPyScalar, D: Dimension> PyStubType for PyArray<T, D> {
     fn type_output() -> TypeInfo {
         let TypeInfo {
             name, mut import, ..
         } = T::type_();
-        import.insert("numpy.typing".into());
+        import.insert("builtins".into());
         TypeInfo {
-            name: format!("numpy.typing.NDArray[{name}]"),
+            name: format!("numpy.ndarray[{}, numpy.dtype[{name}]]", shape_type::<D>()),
             source_module: None,
             import,
             type_refs: HashMap::new(), // TODO: Track type refs for compound types
+            any_fallback: None,
         }
     }
 }
@@ -65,10 +78,11 @@ impl<T, D> PyRuntimeType for PyArray<T, D> {
 impl PyStubType for PyUntypedArray {
     fn type_output() -> TypeInfo {
         TypeInfo {
-            name: "numpy.typing.NDArray[typing.Any]".into(),
+            name: "numpy.ndarray[tuple[builtins.int, ...], numpy.dtype[typing.Any]]".into(),
             source_module: None,
-            import: hashset!["numpy.typing".into(), "typing".into()],
+            import: hashset!["builtins".into(), "numpy".into(), "typing".into()],
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 }
@@ -126,6 +140,7 @@ impl PyStubType for PyArrayDescr {
             source_module: None,
             import: hashset!["numpy".into()],
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 }
@@ -17,6 +17,7 @@ impl PyStubType for PyAny {
             source_module: None,
             import: hashset! { "typing".into() },
             type_refs: HashMap::new(),
+            any_fallback: Some("`PyAny` has no specific Python type"),
         }
     }
 }
@@ -92,6 +93,7 @@ macro_rules! impl_builtin {
                     source_module: None,
                     import: HashSet::new(),
                     type_refs: HashMap::new(),
+                    any_fallback: None,
                 }
             }
         }
@@ -125,6 +127,7 @@ impl PyStubType for PyBackedStr {
             source_module: None,
             import: HashSet::new(),
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 }
@@ -141,6 +144,7 @@ impl PyStubType for PyBackedBytes {
             source_module: None,
             import: HashSet::new(),
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 }
@@ -158,6 +162,7 @@ impl PyStubType for CompareOp {
             source_module: None,
             import: HashSet::new(),
             type_refs: HashMap::new(),
+            any_fallback: None,
         }
     }
 }
@@ -176,6 +181,7 @@ macro_rules! impl_simple {
                     source_module: None,
                     import: hashset! { $mod.into() },
                     type_refs: HashMap::new(),
+                    any_fallback: None,
                 }
             }
         }
@@ -0,0 +1,30 @@
+use super::{PyStubType, TypeInfo};
+use crate::runtime::PyRuntimeType;
+use ::pyo3::{prelude::*, types::PyString};
+
+// URLs are converted from/to Python `str` since Python has no dedicated URL type
+impl PyStubType for url::Url {
+    fn type_output() -> TypeInfo {
+        TypeInfo::builtin("str")
+    }
+}
+impl PyRuntimeType for url::Url {
+    fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        Ok(py.get_type::<PyString>().into_any())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_url_type() {
+        let info = url::Url::type_output();
+        assert_eq!(info.name, "builtins.str");
+        assert!(info.import.contains(&"builtins".into()));
+
+        let info = Vec::<url::Url>::type_input();
+        assert_eq!(info.name, "typing.Sequence[builtins.str]");
+    }
+}
@@ -0,0 +1,31 @@
+use super::{PyStubType, TypeInfo};
+use crate::runtime::PyRuntimeType;
+use ::pyo3::prelude::*;
+
+impl PyStubType for uuid::Uuid {
+    fn type_output() -> TypeInfo {
+        TypeInfo::with_module("uuid.UUID", "uuid".into())
+    }
+}
+impl PyRuntimeType for uuid::Uuid {
+    fn runtime_type_object(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let uuid = py.import("uuid")?;
+        uuid.getattr("UUID")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_uuid_type() {
+        let info = uuid::Uuid::type_output();
+        assert_eq!(info.name, "uuid.UUID");
+        assert!(info.import.contains(&"uuid".into()));
+
+        let info = Option::<uuid::Uuid>::type_input();
+        assert_eq!(info.name, "typing.Optional[uuid.UUID]");
+        assert!(info.import.contains(&"uuid".into()));
+    }
+}
@@ -0,0 +1,13 @@
+//! Helpers to test generated stubs from `cargo test`
+//!
+//! - [assert_stub_snapshot!](crate::assert_stub_snapshot) compares a module stub rendered in memory with a checked-in snapshot.
+//! - `run_stubtest` checks generated stub files against the runtime module with mypy stubtest.
+//!   This requires the `stubtest` feature.
+
+mod snapshot;
+#[cfg(feature = "stubtest")]
+mod stubtest;
+
+pub use snapshot::*;
+#[cfg(feature = "stubtest")]
+pub use stubtest::*;
@@ -0,0 +1,244 @@
+//! Compare module stubs rendered in memory with checked-in snapshots
+
+use crate::{StubGenError, StubInfo};
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use similar::{Algorithm, ChangeTag, TextDiff};
+use std::{fs, io, path::Path};
+
+/// Environment variable to overwrite snapshots by the rendered stubs instead of comparing them,
+/// e.g. `PYO3_STUB_GEN_BLESS=1 cargo test`
+pub const BLESS_ENV: &str = "PYO3_STUB_GEN_BLESS";
+
+/// Number of unchanged lines shown around changed lines in a diff
+const DIFF_CONTEXT: usize = 3;
+
+/// Stub info accepted by [assert_stub_snapshot!](crate::assert_stub_snapshot)
+///
+/// This is implemented for [StubInfo] and [crate::Result]`<StubInfo>`
+/// returned by the function defined with [define_stub_info_gatherer!](crate::define_stub_info_gatherer),
+/// as well as [anyhow::Result]`<StubInfo>`.
+pub trait SnapshotSource {
+    fn stub_info(&self) -> Result<&StubInfo>;
+}
+
+impl SnapshotSource for StubInfo {
+    fn stub_info(&self) -> Result<&StubInfo> {
+        Ok(self)
+    }
+}
+
+impl SnapshotSource for Result<StubInfo, StubGenError> {
+    fn stub_info(&self) -> Result<&StubInfo> {
+        self.as_ref()
+            .map_err(|err| anyhow::anyhow!("Failed to gather stub info: {err:?}"))
+    }
+}
+
+impl SnapshotSource for Result<StubInfo> {
+    fn stub_info(&self) -> Result<&StubInfo> {
+        self.as_ref()
+            .map_err(|err| anyhow::anyhow!("Failed to gather stub info: {err:#}"))
+    }
+}
+
+impl<T: SnapshotSource + ?Sized> SnapshotSource for &T {
+    fn stub_info(&self) -> Result<&StubInfo> {
+        (**self).stub_info()
+    }
+}
+
+/// Render the stub of `module`, or of the default module if `None`, as written by [StubInfo::generate],
+/// i.e. merged with the existing stub file for `merge` and formatted by `format-command` if configured
+pub fn render_module_stub(stub_info: &StubInfo, module: Option<&str>) -> Result<String> {
+    let name = module.unwrap_or(&stub_info.default_module_name);
+    let Some(module) = stub_info.module(name) else {
+        bail!(
+            "Module `{name}` is not found. Gathered modules: {}",
+            stub_info
+                .modules
+                .keys()
+                .map(|name| format!("`{name}`"))
+                .join(", ")
+        );
+    };
+    stub_info.render_stub_as_written(name, module)
+}
+
+/// Compare the rendered stub of `module`, or of the default module if `None`, with the snapshot file `snapshot`.
+///
+/// Returns a line diff from the snapshot to the rendered stub if they differ.
+/// If [BLESS_ENV] is set, the snapshot is overwritten by the rendered stub instead, and `None` is returned.
+pub fn compare_stub_snapshot(
+    stub_info: &StubInfo,
+    module: Option<&str>,
+    snapshot: &Path,
+) -> Result<Option<String>> {
+    let rendered = render_module_stub(stub_info, module)?;
+    if bless() {
+        if let Some(dir) = snapshot.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if fs::read_to_string(snapshot).ok().as_deref() != Some(rendered.as_str()) {
+            fs::write(snapshot, &rendered)
+                .with_context(|| format!("Failed to write snapshot {}", snapshot.display()))?;
+            log::info!("Update stub snapshot {}", snapshot.display());
+        }
+        return Ok(None);
+    }
+    let expected = match fs::read_to_string(snapshot) {
+        Ok(expected) => expected,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => bail!(
+            "Snapshot {} does not exist. Run the test with `{BLESS_ENV}=1` to create it.",
+            snapshot.display()
+        ),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("Failed to read snapshot {}", snapshot.display()))
+        }
+    };
+    // Snapshots may be checked out with CRLF on Windows
+    let expected = expected.replace("\r\n", "\n");
+    if expected == rendered {
+        Ok(None)
+    } else {
+        Ok(Some(line_diff(&expected, &rendered)))
+    }
+}
+
+fn bless() -> bool {
+    std::env::var_os(BLESS_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Implementation of [assert_stub_snapshot!](crate::assert_stub_snapshot)
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_stub_snapshot_impl(
+    source: &impl SnapshotSource,
+    module: Option<&str>,
+    snapshot: &Path,
+) {
+    let result = source
+        .stub_info()
+        .and_then(|stub_info| compare_stub_snapshot(stub_info, module, snapshot));
+    match result {
+        Ok(None) => {}
+        Ok(Some(diff)) => panic!(
+            "Stub differs from snapshot {} (-snapshot +generated):\n{diff}\n\
+             Run the test with `{BLESS_ENV}=1` to update the snapshot.",
+            snapshot.display()
+        ),
+        Err(err) => panic!("{err:#}"),
+    }
+}
+
+/// Line-based diff from `old` to `new` by the Myers algorithm, showing [DIFF_CONTEXT] unchanged lines around changes
+fn line_diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let lines: Vec<(char, &str)> = TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .diff_slices(&old, &new)
+        .iter_all_changes()
+        .map(|change| {
+            let tag = match change.tag() {
+                ChangeTag::Equal => ' ',
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+            };
+            (tag, change.value())
+        })
+        .collect();
+
+    let changed: Vec<usize> = lines.iter().positions(|(tag, _)| *tag != ' ').collect();
+    if changed.is_empty() {
+        return "(only trailing newlines differ)".to_string();
+    }
+    let shown = |index: usize| {
+        changed
+            .iter()
+            .any(|&change| index + DIFF_CONTEXT >= change && index <= change + DIFF_CONTEXT)
+    };
+    let mut diff = String::new();
+    let mut skipped = false;
+    for (index, (tag, line)) in lines.iter().enumerate() {
+        if !shown(index) {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            diff.push_str("...\n");
+            skipped = false;
+        }
+        diff.push_str(format!("{tag} {line}").trim_end());
+        diff.push('\n');
+    }
+    if skipped {
+        diff.push_str("...\n");
+    }
+    diff
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generate::Module;
+
+    #[test]
+    fn test_line_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let new = "a\nb\nc\nd\ne\nF\ng\nh\ni\nj\n";
+        assert_eq!(
+            line_diff(old, new),
+            "...\n  c\n  d\n  e\n- f\n+ F\n  g\n  h\n  i\n+ j\n"
+        );
+        assert_eq!(line_diff("a\n", "a"), "(only trailing newlines differ)");
+    }
+
+    #[test]
+    fn test_line_diff_large() {
+        // Large stubs are compared without a table of all pairs of lines
+        let old = (0..100_000)
+            .map(|i| format!("x{i}: int\n"))
+            .collect::<String>();
+        let new = old.replace("x50000: int", "x50000: str");
+        assert_eq!(
+            line_diff(&old, &new),
+            "...\n  x49997: int\n  x49998: int\n  x49999: int\n- x50000: int\n+ x50000: str\n  x50001: int\n  x50002: int\n  x50003: int\n...\n"
+        );
+    }
+
+    #[test]
+    fn test_compare_stub_snapshot() -> Result<()> {
+        let mut stub_info = StubInfo {
+            modules: Default::default(),
+            python_root: Default::default(),
+            is_mixed_layout: false,
+            config: Default::default(),
+            pyproject_dir: None,
+            default_module_name: "pkg".to_string(),
+            project_name: "pkg".to_string(),
+            doc_links: Default::default(),
+        };
+        stub_info.modules.insert(
+            "pkg".to_string(),
+            Module {
+                name: "pkg".to_string(),
+                default_module_name: "pkg".to_string(),
+                ..Default::default()
+            },
+        );
+        let rendered = render_module_stub(&stub_info, None)?;
+        assert!(render_module_stub(&stub_info, Some("pkg.sub")).is_err());
+
+        let snapshot =
+            std::env::temp_dir().join(format!("pyo3-stub-gen-snapshot-{}.pyi", std::process::id()));
+        fs::write(&snapshot, rendered.replace('\n', "\r\n"))?;
+        assert_eq!(compare_stub_snapshot(&stub_info, None, &snapshot)?, None);
+        fs::write(&snapshot, format!("{rendered}x: int\n"))?;
+        let diff = compare_stub_snapshot(&stub_info, Some("pkg"), &snapshot)?;
+        fs::remove_file(&snapshot)?;
+        assert!(diff.is_some_and(|diff| diff.contains("- x: int")));
+        Ok(())
+    }
+}
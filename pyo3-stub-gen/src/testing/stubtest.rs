@@ -0,0 +1,300 @@
+//! Check generated stub files against the runtime module with [mypy stubtest](https://mypy.readthedocs.io/en/stable/stubtest.html).
+//!
+//! This requires the `stubtest` feature. The module must be importable by the Python interpreter,
+//! e.g. installed by `maturin develop`, and `mypy` must be installed in the same environment:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn stubtest() -> anyhow::Result<()> {
+//!     let report = pyo3_stub_gen::testing::run_stubtest("my_module", &["my_module.internal.*"])?;
+//!     assert!(report.is_success(), "{report}");
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The Python interpreter is taken from the `PYTHON` environment variable,
+//! the active virtual environment, or `python3` in `PATH` in this order.
+
+use anyhow::{bail, Context, Result};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// Flags passed to stubtest for modules built by PyO3 and maturin, if the installed stubtest knows them
+///
+/// - `--ignore-missing-stub`: the native module re-exported by `__init__.py` has no stub file of its own
+/// - `--ignore-disjoint-bases`: `@typing.disjoint_base` is not generated for PyO3 classes.
+///   Only mypy 1.18 or later has this flag, and older ones do not check disjoint bases either.
+pub const DEFAULT_FLAGS: &[&str] = &["--ignore-missing-stub", "--ignore-disjoint-bases"];
+
+/// An error reported by stubtest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StubtestFailure {
+    /// Dotted path of the object, e.g. `my_module.MyClass.method`
+    pub object: String,
+    /// Description of the error, e.g. `is not present at runtime`
+    pub message: String,
+    /// The stub definition with its location, if shown by stubtest
+    pub stub: String,
+    /// The runtime definition with its location, if shown by stubtest
+    pub runtime: String,
+}
+
+impl fmt::Display for StubtestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.object, self.message)
+    }
+}
+
+/// Result of [run_stubtest]
+#[derive(Debug, Clone, Default)]
+pub struct StubtestReport {
+    pub failures: Vec<StubtestFailure>,
+    /// Whole output of stubtest
+    pub output: String,
+}
+
+impl StubtestReport {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for StubtestReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.failures.is_empty() {
+            return writeln!(f, "stubtest succeeded");
+        }
+        writeln!(f, "stubtest found {} error(s):", self.failures.len())?;
+        for failure in &self.failures {
+            writeln!(f, "  {failure}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `python -m mypy.stubtest` for `module_name` with [DEFAULT_FLAGS] supported by the installed version.
+///
+/// `extra_allowlist` is a list of allowlist entries of stubtest, i.e. object paths or regular expressions,
+/// used in addition to `.stubtest-allowlist` in the current directory if it exists.
+///
+/// Errors reported by stubtest are returned in [StubtestReport], not as `Err`.
+/// `Err` is returned if stubtest itself fails, e.g. when `mypy` is not installed.
+pub fn run_stubtest(module_name: &str, extra_allowlist: &[&str]) -> Result<StubtestReport> {
+    let python = python_interpreter();
+    let flags = supported_flags(&python)?;
+    let mut command = Command::new(&python);
+    command
+        .args(["-m", "mypy.stubtest", module_name])
+        .args(flags)
+        // Avoid colored output to be parsed
+        .env("NO_COLOR", "1")
+        .env("TERM", "dumb");
+
+    let default_allowlist = PathBuf::from(".stubtest-allowlist");
+    if default_allowlist.is_file() {
+        command.arg("--allowlist").arg(&default_allowlist);
+    }
+    let extra_allowlist_path = if extra_allowlist.is_empty() {
+        None
+    } else {
+        let path = std::env::temp_dir().join(format!(
+            "pyo3-stub-gen-stubtest-{}-{}.txt",
+            module_name,
+            std::process::id()
+        ));
+        std::fs::write(&path, extra_allowlist.join("\n") + "\n")
+            .with_context(|| format!("Failed to write allowlist: {}", path.display()))?;
+        command.arg("--allowlist").arg(&path);
+        Some(path)
+    };
+
+    let output = command.output();
+    if let Some(path) = extra_allowlist_path {
+        let _ = std::fs::remove_file(path);
+    }
+    let output = output.with_context(|| format!("Failed to run {}", python.display()))?;
+    report_of(output)
+}
+
+/// [DEFAULT_FLAGS] listed in `stubtest --help`, since stubtest fails immediately with an unknown flag
+fn supported_flags(python: &Path) -> Result<Vec<&'static str>> {
+    let output = Command::new(python)
+        .args(["-m", "mypy.stubtest", "--help"])
+        .output()
+        .with_context(|| format!("Failed to run {}", python.display()))?;
+    if !output.status.success() {
+        bail!(
+            "stubtest is not available in {}:\n{}",
+            python.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(flags_in_help(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn flags_in_help(help: &str) -> Vec<&'static str> {
+    DEFAULT_FLAGS
+        .iter()
+        .copied()
+        .filter(|flag| {
+            help.split(|c: char| c.is_whitespace() || c == '[' || c == ']')
+                .any(|word| word == *flag)
+        })
+        .collect()
+}
+
+fn python_interpreter() -> PathBuf {
+    if let Some(python) = std::env::var_os("PYTHON") {
+        return python.into();
+    }
+    if let Some(venv) = std::env::var_os("VIRTUAL_ENV") {
+        let venv = PathBuf::from(venv);
+        let python = if cfg!(windows) {
+            venv.join("Scripts").join("python.exe")
+        } else {
+            venv.join("bin").join("python")
+        };
+        if python.is_file() {
+            return python;
+        }
+    }
+    "python3".into()
+}
+
+fn report_of(output: Output) -> Result<StubtestReport> {
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let failures = parse_output(&stdout);
+    if !output.status.success() && failures.is_empty() {
+        bail!(
+            "stubtest failed with {}:\n{}{}",
+            output.status,
+            stdout,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(StubtestReport {
+        failures,
+        output: stdout,
+    })
+}
+
+/// Parse errors in the (non-concise) output of stubtest
+///
+/// ```text
+/// error: my_module.f is inconsistent, stub argument "x" differs from runtime argument "y"
+/// Stub: in file /path/to/my_module.pyi:3
+/// def (x: builtins.int)
+/// Runtime: in file /path/to/my_module.so
+/// def (y)
+///
+/// Found 1 error (checked 1 module)
+/// ```
+fn parse_output(output: &str) -> Vec<StubtestFailure> {
+    enum Section {
+        None,
+        Stub,
+        Runtime,
+    }
+    let mut failures: Vec<StubtestFailure> = Vec::new();
+    let mut section = Section::None;
+    for line in output.lines() {
+        if let Some(error) = line.strip_prefix("error: ") {
+            let (object, message) = error.split_once(' ').unwrap_or((error, ""));
+            failures.push(StubtestFailure {
+                object: object.to_string(),
+                message: message.to_string(),
+                stub: String::new(),
+                runtime: String::new(),
+            });
+            section = Section::None;
+            continue;
+        }
+        let Some(failure) = failures.last_mut() else {
+            continue;
+        };
+        if line.trim().is_empty() {
+            section = Section::None;
+        } else if let Some(rest) = line.strip_prefix("Stub:") {
+            failure.stub = rest.trim().to_string();
+            section = Section::Stub;
+        } else if let Some(rest) = line.strip_prefix("Runtime:") {
+            failure.runtime = rest.trim().to_string();
+            section = Section::Runtime;
+        } else {
+            let target = match section {
+                Section::Stub => &mut failure.stub,
+                Section::Runtime => &mut failure.runtime,
+                Section::None => continue,
+            };
+            if !target.is_empty() {
+                target.push('\n');
+            }
+            target.push_str(line);
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_output() {
+        let output = r#"error: pure.f is inconsistent, stub argument "x" differs from runtime argument "y"
+Stub: in file /tmp/pure.pyi:3
+def (x: builtins.int)
+Runtime: in file /tmp/pure.so
+def (y)
+
+error: pure.Missing is not present at runtime
+Stub: in file /tmp/pure.pyi:10
+class Missing:
+    ...
+Runtime:
+MISSING
+
+Found 2 errors (checked 1 module)
+"#;
+        let failures = parse_output(output);
+        assert_eq!(
+            failures,
+            vec![
+                StubtestFailure {
+                    object: "pure.f".to_string(),
+                    message:
+                        r#"is inconsistent, stub argument "x" differs from runtime argument "y""#
+                            .to_string(),
+                    stub: "in file /tmp/pure.pyi:3\ndef (x: builtins.int)".to_string(),
+                    runtime: "in file /tmp/pure.so\ndef (y)".to_string(),
+                },
+                StubtestFailure {
+                    object: "pure.Missing".to_string(),
+                    message: "is not present at runtime".to_string(),
+                    stub: "in file /tmp/pure.pyi:10\nclass Missing:\n    ...".to_string(),
+                    runtime: "MISSING".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flags_in_help() {
+        let help = "usage: stubtest [-h] [--concise] [--ignore-missing-stub] [--ignore-positional-only]\n\n\
+                    options:\n  --ignore-missing-stub  Ignore errors for stub missing things\n";
+        assert_eq!(flags_in_help(help), ["--ignore-missing-stub"]);
+
+        let help = format!(
+            "{help}  --ignore-disjoint-bases  Disable checks for PEP 800 @disjoint_base classes\n"
+        );
+        assert_eq!(flags_in_help(&help), DEFAULT_FLAGS);
+    }
+
+    #[test]
+    fn test_parse_success() {
+        assert!(parse_output("Success: no issues found in 1 module\n").is_empty());
+    }
+}
@@ -13,6 +13,7 @@
 //! - [PyMethodsInfo] stores information obtained from `#[pymethods]` macro
 //!
 //! and others are their components.
+//! [PyClassInfoBuilder] and [PyFunctionInfoBuilder] register classes and functions at runtime instead, see [RuntimeItems].
 //!
 //! Gathering metadata and generating stub file (runtime)
 //! -------------------------------------------------------
@@ -21,11 +22,16 @@
 //! This process is done at runtime in [gen_stub](../../gen_stub) executable.
 //!
 
+mod builder;
+
+pub use builder::*;
+
 use crate::{stub_type::ModuleRef, PyStubType, TypeInfo};
 use std::any::TypeId;
 
 /// Represents the target of type ignore comments
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum IgnoreTarget {
     /// Ignore all type checking errors `(# type: ignore)`
     All,
@@ -35,6 +41,7 @@ pub enum IgnoreTarget {
 
 /// Information about deprecated items
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DeprecatedInfo {
     pub since: Option<&'static str>,
     pub note: Option<&'static str>,
@@ -50,8 +57,27 @@ pub fn no_return_type_output() -> TypeInfo {
     TypeInfo::none()
 }
 
+/// Identifier of a converter function, the type-id of its function item type
+///
+/// Every function has its own item type, so two converters of the same name in different modules are told apart.
+pub fn converter_id<F: 'static>(_converter: &F) -> TypeId {
+    TypeId::of::<F>()
+}
+
+/// Input type of an argument converted by `#[pyo3(from_py_with = converter)]`,
+/// given by `#[gen_stub_converter]` on the converter or `fallback` of the Rust type if not annotated
+///
+/// `converter` is the [converter_id] of the converter function.
+pub fn converter_input(converter: TypeId, fallback: fn() -> TypeInfo) -> TypeInfo {
+    inventory::iter::<PyConverterInfo>
+        .into_iter()
+        .find(|info| (info.converter)() == converter)
+        .map_or_else(fallback, |info| (info.input)())
+}
+
 /// Kind of parameter in Python function signature
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ParameterKind {
     /// Positional-only parameter (before `/`)
     PositionalOnly,
@@ -112,6 +138,7 @@ pub struct ParameterInfo {
 
 /// Type of a method
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum MethodType {
     Instance,
     Static,
@@ -141,7 +168,20 @@ pub struct MemberInfo {
     pub r#type: fn() -> TypeInfo,
     pub doc: &'static str,
     pub default: Option<fn() -> String>,
+    /// Python expression shown instead of the evaluated `default`, specified by `#[gen_stub(default_symbol = "...")]`
+    pub default_symbol: Option<&'static str>,
     pub deprecated: Option<DeprecatedInfo>,
+    /// Whether the getter is shown as `functools.cached_property`, specified by `#[gen_stub(cached_property)]`
+    pub cached: bool,
+}
+
+/// Python class which [PyMethodsInfo] is attached to by its name
+#[derive(Debug)]
+pub struct PythonClassRef {
+    /// Python class name
+    pub name: &'static str,
+    /// Module of the class, required only if classes of the same name exist in several modules
+    pub module: Option<&'static str>,
 }
 
 /// Info of `#[pymethod]`
@@ -149,6 +189,9 @@ pub struct MemberInfo {
 pub struct PyMethodsInfo {
     // The Rust struct type-id of `impl` block where `#[pymethod]` acts on
     pub struct_id: fn() -> TypeId,
+    /// Class to attach the methods to by name instead of `struct_id`,
+    /// e.g. for classes defined in other crates by `gen_methods_from_python!`
+    pub python_class: Option<PythonClassRef>,
     /// Method/Const with `#[classattr]`
     pub attrs: &'static [MemberInfo],
     /// Methods decorated with `#[getter]`
@@ -192,10 +235,74 @@ pub struct PyClassInfo {
     pub has_str: bool,
     /// Whether the class has subclass attribute `#[pyclass(subclass)]`
     pub subclass: bool,
+    /// Whether the class implements the buffer protocol, specified by `#[gen_stub(buffer)]`
+    pub buffer: bool,
+    /// Whether the class supports pickling, specified by `#[gen_stub(picklable)]`
+    pub picklable: bool,
+    /// Names listed in `__slots__`, specified by `#[gen_stub(slots)]`
+    pub slots: Option<&'static [&'static str]>,
+    /// Typed item access, specified by `#[gen_stub(getitem(...), setitem(...), delitem(...))]`
+    pub item_access: &'static [ItemAccessInfo],
+    /// Item type of an iterator class, specified by `#[gen_stub(iterator(item = "..."))]`
+    pub iterator: Option<IteratorInfo>,
+    /// Type checker rules to ignore on the class line, specified by `#[gen_stub(type_ignore)]`
+    pub type_ignored: Option<IgnoreTarget>,
+    /// Parameters of `@typing.dataclass_transform(...)`, specified by `#[gen_stub(dataclass_transform(...))]`
+    pub dataclass_transform: Option<DataclassTransformInfo>,
+    /// Source file location for ordering items by definition position
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
 }
 
 inventory::collect!(PyClassInfo);
 
+/// Parameters of `@typing.dataclass_transform(...)` on a base class, see PEP 681.
+/// Unspecified parameters are omitted to use the defaults of type checkers.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DataclassTransformInfo {
+    pub eq_default: Option<bool>,
+    pub order_default: Option<bool>,
+    pub kw_only_default: Option<bool>,
+    pub frozen_default: Option<bool>,
+}
+
+/// Item access method of a class with key and value types written in Python syntax
+#[derive(Debug)]
+pub enum ItemAccessInfo {
+    /// `def __getitem__(self, key: <key>, /) -> <value>: ...`
+    Get {
+        key: &'static str,
+        value: &'static str,
+        imports: &'static [&'static str],
+    },
+    /// `def __setitem__(self, key: <key>, value: <value>, /) -> None: ...`
+    Set {
+        key: &'static str,
+        value: &'static str,
+        imports: &'static [&'static str],
+    },
+    /// `def __delitem__(self, key: <key>, /) -> None: ...`
+    Del {
+        key: &'static str,
+        imports: &'static [&'static str],
+    },
+}
+
+/// Item type of an iterator class written in Python syntax
+///
+/// A class with `send` or `return` is a `collections.abc.Generator` of them, whose omitted ones are `None`.
+#[derive(Debug)]
+pub struct IteratorInfo {
+    pub item: &'static str,
+    /// Type of values accepted by `send()` of a generator
+    pub send: Option<&'static str>,
+    /// Type of the value returned when a generator finishes
+    pub r#return: Option<&'static str>,
+    pub imports: &'static [&'static str],
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum VariantForm {
     Unit,
@@ -212,6 +319,8 @@ pub struct VariantInfo {
     pub fields: &'static [MemberInfo],
     pub form: &'static VariantForm,
     pub constr_args: &'static [ParameterInfo],
+    /// Declares `__match_args__` of the fields, disabled by `#[gen_stub(no_match_args)]`
+    pub match_args: bool,
 }
 
 /// Info of a `#[pyclass]` with a rich (structured) Rust enum
@@ -227,6 +336,10 @@ pub struct PyComplexEnumInfo {
     pub doc: &'static str,
     /// static members by `#[pyo3(get, set)]`
     pub variants: &'static [VariantInfo],
+    /// Source file location for ordering items by definition position
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
 }
 
 inventory::collect!(PyComplexEnumInfo);
@@ -244,6 +357,10 @@ pub struct PyEnumInfo {
     pub doc: &'static str,
     /// Variants of enum (name, doc)
     pub variants: &'static [(&'static str, &'static str)],
+    /// Source file location for ordering items by definition position
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
 }
 
 inventory::collect!(PyEnumInfo);
@@ -254,6 +371,10 @@ pub struct PyFunctionInfo {
     pub name: &'static str,
     pub parameters: &'static [ParameterInfo],
     pub r#return: fn() -> TypeInfo,
+    /// Rust error type `E` of `Result<T, E>` returned by the function, other than `PyErr`
+    pub error: Option<fn() -> TypeId>,
+    /// Exceptions listed by `#[gen_stub(raises(...))]`
+    pub raises: &'static [RaisesInfo],
     pub doc: &'static str,
     pub module: Option<&'static str>,
     pub is_async: bool,
@@ -261,6 +382,12 @@ pub struct PyFunctionInfo {
     pub type_ignored: Option<IgnoreTarget>,
     /// Whether this function is marked as an overload variant
     pub is_overload: bool,
+    /// `sys.platform` given by `#[gen_stub(platform = "...")]`, negated by a `!` prefix like `"!win32"`
+    pub platform: Option<&'static str>,
+    /// Python versions given by `#[gen_stub(py_version = "...")]`, e.g. `">=3.12"`
+    pub py_version: Option<&'static str>,
+    /// Releases the GIL while running, e.g. by `py.detach`, given by `#[gen_stub(releases_gil)]`
+    pub releases_gil: bool,
     /// Source file location for deterministic ordering
     pub file: &'static str,
     pub line: u32,
@@ -287,10 +414,243 @@ pub struct TypeAliasInfo {
     pub module: &'static str,
     pub r#type: fn() -> TypeInfo,
     pub doc: &'static str,
+    /// Python versions the alias is available on, e.g. `">=3.12"`
+    pub py_version: Option<&'static str>,
 }
 
 inventory::collect!(TypeAliasInfo);
 
+/// Old name of a moved or renamed class kept for compatibility, declared by [crate::deprecated_alias!]
+#[derive(Debug)]
+pub struct DeprecatedAliasInfo {
+    pub name: &'static str,
+    pub module: &'static str,
+    /// The class the old name refers to
+    pub target: fn() -> TypeInfo,
+    pub deprecated: DeprecatedInfo,
+}
+
+inventory::collect!(DeprecatedAliasInfo);
+
+/// Base class defined in another package, registered by [crate::external_class!]
+#[derive(Debug)]
+pub struct ExternalClassInfo {
+    /// Public module the class is imported from, e.g. `other_pkg`
+    pub module: &'static str,
+    /// Class name, e.g. `Base`
+    pub name: &'static str,
+    /// Module the base class refers to, e.g. `other_pkg._core`,
+    /// or `None` if the other crate does not set `#[pyclass(module = "...")]`
+    pub defined_in: Option<&'static str>,
+}
+
+inventory::collect!(ExternalClassInfo);
+
+/// Exception raised for a Rust error type, registered by [crate::map_error_to_exception!]
+#[derive(Debug)]
+pub struct ErrorExceptionInfo {
+    pub error: fn() -> TypeId,
+    pub exception: fn() -> TypeId,
+}
+
+inventory::collect!(ErrorExceptionInfo);
+
+/// Exception listed by `#[gen_stub(raises(...))]`
+#[derive(Debug)]
+pub enum RaisesInfo {
+    /// Exception class, or Rust error type registered by [crate::map_error_to_exception!]
+    Type {
+        id: fn() -> TypeId,
+        /// Rust type name, used when the exception class is not found
+        name: &'static str,
+    },
+    /// Python built-in exception, e.g. `ValueError`
+    Builtin(&'static str),
+}
+
+/// Typing-only definitions in Python syntax registered by [gen_typing_helpers_from_python!](crate::derive::gen_typing_helpers_from_python)
+#[derive(Debug)]
+pub struct TypingHelperInfo {
+    pub module: &'static str,
+    pub code: &'static str,
+    /// Modules to be imported, e.g. `typing`
+    pub imports: &'static [&'static str],
+}
+
+inventory::collect!(TypingHelperInfo);
+
+/// Kind of a type parameter declared by [crate::type_var!] or [crate::param_spec!]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TypeParamKind {
+    /// `T = typing.TypeVar("T")`
+    TypeVar,
+    /// `P = typing.ParamSpec("P")`
+    ParamSpec,
+}
+
+/// Module-level type parameter declared by [crate::type_var!] or [crate::param_spec!]
+#[derive(Debug)]
+pub struct TypeParamInfo {
+    pub module: &'static str,
+    pub name: &'static str,
+    pub kind: TypeParamKind,
+}
+
+inventory::collect!(TypeParamInfo);
+
+/// `TypedDict` declared by [crate::typed_dict!]
+#[derive(Debug)]
+pub struct TypedDictInfo {
+    pub module: &'static str,
+    pub name: &'static str,
+    pub doc: &'static str,
+    pub fields: &'static [TypedDictFieldInfo],
+}
+
+inventory::collect!(TypedDictInfo);
+
+/// Key of a `TypedDict` declared by [crate::typed_dict!]
+#[derive(Debug)]
+pub struct TypedDictFieldInfo {
+    pub name: &'static str,
+    pub r#type: fn() -> TypeInfo,
+    /// `false` for keys marked by `#[not_required]`, which may be omitted
+    pub required: bool,
+}
+
+/// `typing.NamedTuple` returned by a function with `#[gen_stub(named_tuple(...))]`
+#[derive(Debug)]
+pub struct NamedTupleInfo {
+    /// Module of the function, the default module if `None`
+    pub module: Option<&'static str>,
+    pub name: &'static str,
+    pub doc: &'static str,
+    pub fields: &'static [NamedTupleFieldInfo],
+}
+
+inventory::collect!(NamedTupleInfo);
+
+/// Field of a `typing.NamedTuple`, typed by the corresponding element of the returned Rust tuple
+#[derive(Debug)]
+pub struct NamedTupleFieldInfo {
+    pub name: &'static str,
+    pub r#type: fn() -> TypeInfo,
+}
+
+/// Known keys of a dict parameter annotated by `#[gen_stub(dict_keys(...))]`
+#[derive(Debug)]
+pub struct DictKeysInfo {
+    /// Rust type-id of the `#[pyclass]` for a method, `None` for a function
+    pub struct_id: Option<fn() -> TypeId>,
+    /// Module of the function, the default module if `None`. Not used for methods.
+    pub module: Option<&'static str>,
+    /// Python name of the function or method
+    pub function: &'static str,
+    pub parameter: &'static str,
+    /// `TypedDict` of the keys written to the stub and used as the parameter type, given by `typed_dict = "..."`
+    pub typed_dict: Option<&'static str>,
+    pub keys: &'static [DictKeyInfo],
+}
+
+inventory::collect!(DictKeysInfo);
+
+/// Key of a dict parameter and its value type written in Python syntax, e.g. `host = "str"`
+#[derive(Debug)]
+pub struct DictKeyInfo {
+    pub name: &'static str,
+    pub type_repr: &'static str,
+}
+
+/// Rust path of a `#[pyclass]`, enum or `#[pyfunction]` to resolve intra-doc links like `[Foo]` in docstrings
+#[derive(Debug)]
+pub struct RustPathInfo {
+    /// [std::any::type_name] of the Rust item, e.g. `my_crate::bar::Baz`
+    pub rust_path: fn() -> &'static str,
+    pub target: RustPathTarget,
+}
+
+inventory::collect!(RustPathInfo);
+
+/// Python item of [RustPathInfo]
+#[derive(Debug)]
+pub enum RustPathTarget {
+    /// Class or enum of the Rust type-id
+    Type(fn() -> TypeId),
+    /// Function of the Python name before `rename`, in the default module if `module` is `None`
+    Function {
+        module: Option<&'static str>,
+        name: &'static str,
+    },
+}
+
+/// Python type accepted by a `#[pyo3(from_py_with = ...)]` converter function, registered by `#[gen_stub_converter]`
+#[derive(Debug)]
+pub struct PyConverterInfo {
+    /// [converter_id] of the converter function
+    pub converter: fn() -> TypeId,
+    pub input: fn() -> TypeInfo,
+}
+
+inventory::collect!(PyConverterInfo);
+
+/// Hand-written members of a class in Python syntax registered by [crate::class_verbatim!]
+#[derive(Debug)]
+pub struct PyClassVerbatimInfo {
+    /// Rust type-id of the `#[pyclass]` the members are appended to
+    pub struct_id: fn() -> TypeId,
+    pub code: &'static str,
+    /// Modules to be imported, e.g. `typing`
+    pub imports: &'static [&'static str],
+    /// Source file location for deterministic ordering
+    pub file: &'static str,
+    pub line: u32,
+}
+
+inventory::collect!(PyClassVerbatimInfo);
+
+/// Trailing content of a module in Python syntax registered by [crate::module_epilogue!]
+#[derive(Debug)]
+pub struct ModuleEpilogueInfo {
+    pub module: &'static str,
+    pub code: &'static str,
+    /// Modules to be imported, e.g. `typing`
+    pub imports: &'static [&'static str],
+    /// Source file location for deterministic ordering
+    pub file: &'static str,
+    pub line: u32,
+}
+
+inventory::collect!(ModuleEpilogueInfo);
+
+/// Module-level `__getattr__` registered by [crate::module_getattr!]
+#[derive(Debug)]
+pub struct ModuleGetattrInfo {
+    pub module: &'static str,
+    /// Return type in Python syntax, e.g. `typing.Any`
+    pub return_type: &'static str,
+    /// Modules to be imported in addition to those referenced by `return_type`
+    pub imports: &'static [&'static str],
+}
+
+inventory::collect!(ModuleGetattrInfo);
+
+/// Classes and functions registered to a module in `#[pymodule]` function, recorded by `#[gen_stub_pymodule]`
+#[derive(Debug)]
+pub struct PyModuleInfo {
+    /// Python name of the `#[pymodule]` function
+    pub root: &'static str,
+    /// Dotted path of the module relative to `root` given by `add_submodule`, empty for `root` itself
+    pub path: &'static str,
+    /// Rust type-ids of classes added by `add_class`
+    pub classes: &'static [fn() -> TypeId],
+    /// [std::any::type_name_of_val] of functions added by `add_function(wrap_pyfunction!(...))`,
+    /// matched with [RustPathInfo] of `#[gen_stub_pyfunction]` for their Python names
+    pub functions: &'static [fn() -> &'static str],
+}
+
+inventory::collect!(PyModuleInfo);
+
 #[derive(Debug)]
 pub struct ModuleDocInfo {
     pub module: &'static str,
@@ -308,6 +668,8 @@ pub enum ReexportItems {
     Explicit(&'static [&'static str]),
     /// Re-export all public items plus additional specified items (e.g., `__version__`)
     WildcardPlus(&'static [&'static str]),
+    /// Re-export the specified items under other names as `(name, alias)`
+    Renamed(&'static [(&'static str, &'static str)]),
 }
 
 /// Re-export items from another module into __all__
@@ -337,3 +699,78 @@ pub struct ExcludeFromAll {
 }
 
 inventory::collect!(ExcludeFromAll);
+
+/// Infos with default values for tests, completed by the struct update syntax, e.g.
+/// `PyClassInfo { has_eq: true, ..PyClassInfo::fixture("Foo") }`
+#[cfg(test)]
+mod fixture {
+    use super::*;
+
+    impl PyClassInfo {
+        pub(crate) const fn fixture(pyclass_name: &'static str) -> Self {
+            Self {
+                struct_id: TypeId::of::<()>,
+                pyclass_name,
+                module: None,
+                doc: "",
+                getters: &[],
+                setters: &[],
+                bases: &[],
+                has_eq: false,
+                has_ord: false,
+                has_hash: false,
+                has_str: false,
+                subclass: false,
+                buffer: false,
+                picklable: false,
+                slots: None,
+                item_access: &[],
+                iterator: None,
+                type_ignored: None,
+                dataclass_transform: None,
+                file: "",
+                line: 0,
+                column: 0,
+            }
+        }
+    }
+
+    impl MemberInfo {
+        pub(crate) const fn fixture(name: &'static str, r#type: fn() -> TypeInfo) -> Self {
+            Self {
+                name,
+                r#type,
+                doc: "",
+                default: None,
+                default_symbol: None,
+                deprecated: None,
+                cached: false,
+            }
+        }
+    }
+
+    impl PyFunctionInfo {
+        pub(crate) const fn fixture(name: &'static str, r#return: fn() -> TypeInfo) -> Self {
+            Self {
+                name,
+                parameters: &[],
+                r#return,
+                error: None,
+                raises: &[],
+                doc: "",
+                module: None,
+                is_async: false,
+                deprecated: None,
+                type_ignored: None,
+                is_overload: false,
+                platform: None,
+                py_version: None,
+                releases_gil: false,
+                file: "",
+                line: 0,
+                column: 0,
+                index: 0,
+            }
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! Builders of classes and functions for registering type information at runtime
+//!
+//! [inventory::submit!] only accepts constant expressions with `&'static` references and `fn` pointers,
+//! which is inconvenient for code generators registering items dynamically.
+//! The builders take owned values instead, including [TypeInfo]s computed at runtime,
+//! and are collected in [RuntimeItems] given to [StubInfo::from_pyproject_toml_with](crate::StubInfo::from_pyproject_toml_with)
+//! to be gathered together with the ones submitted to [inventory]:
+//!
+//! ```
+//! use pyo3_stub_gen::{type_info::*, PyStubType};
+//!
+//! let mut items = RuntimeItems::default();
+//! items.add_class(
+//!     PyClassInfoBuilder::new("Point")
+//!         .module("my_module")
+//!         .doc("A point in 2D space")
+//!         .getter("x", f64::type_output())
+//!         .getter("y", f64::type_output()),
+//! );
+//! items.add_function(
+//!     PyFunctionInfoBuilder::new("distance")
+//!         .module("my_module")
+//!         .parameter("a", <Vec<f64>>::type_input())
+//!         .parameter("b", <Vec<f64>>::type_input())
+//!         .returns(f64::type_output()),
+//! );
+//! ```
+//!
+//! Names and docstrings have the `'static` lifetime in the generated definitions, so they are leaked once per item.
+
+use super::*;
+use crate::generate::{self, normalize_docstring, ClassDef, FunctionDef, MemberDef, Parameter};
+
+fn leak_str(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+fn leak_doc(doc: &str) -> &'static str {
+    if doc.is_empty() {
+        ""
+    } else {
+        leak_str(normalize_docstring(doc))
+    }
+}
+
+fn member(name: String, r#type: TypeInfo) -> MemberDef {
+    MemberDef {
+        name: leak_str(name),
+        r#type,
+        doc: "",
+        default: None,
+        default_symbol: None,
+        deprecated: None,
+        cached: false,
+    }
+}
+
+/// Classes and functions registered at runtime, see [the module documentation](self)
+#[derive(Debug, Default)]
+pub struct RuntimeItems {
+    pub(crate) classes: Vec<PyClassInfoBuilder>,
+    pub(crate) functions: Vec<PyFunctionInfoBuilder>,
+}
+
+impl RuntimeItems {
+    pub fn add_class(&mut self, class: PyClassInfoBuilder) -> &mut Self {
+        self.classes.push(class);
+        self
+    }
+
+    pub fn add_function(&mut self, function: PyFunctionInfoBuilder) -> &mut Self {
+        self.functions.push(function);
+        self
+    }
+}
+
+/// Builder of a class from owned values
+#[derive(Debug)]
+pub struct PyClassInfoBuilder {
+    rust_type: Option<TypeId>,
+    pyclass_name: String,
+    module: Option<String>,
+    doc: String,
+    getters: Vec<MemberDef>,
+    setters: Vec<MemberDef>,
+    bases: Vec<TypeInfo>,
+    has_eq: bool,
+    has_ord: bool,
+    has_hash: bool,
+    has_str: bool,
+    subclass: bool,
+    picklable: bool,
+    slots: Option<Vec<String>>,
+    dataclass_transform: Option<DataclassTransformInfo>,
+}
+
+impl PyClassInfoBuilder {
+    /// Class of the Python name `name`, identified by its module and name unless [rust_type](Self::rust_type) is set
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            rust_type: None,
+            pyclass_name: name.into(),
+            module: None,
+            doc: String::new(),
+            getters: Vec::new(),
+            setters: Vec::new(),
+            bases: Vec::new(),
+            has_eq: false,
+            has_ord: false,
+            has_hash: false,
+            has_str: false,
+            subclass: false,
+            picklable: false,
+            slots: None,
+            dataclass_transform: None,
+        }
+    }
+
+    /// Rust type of the class, so that methods submitted by `#[gen_stub_pymethods]` for `T` are attached to it
+    pub fn rust_type<T: 'static>(mut self) -> Self {
+        self.rust_type = Some(TypeId::of::<T>());
+        self
+    }
+
+    /// Module of the class, the default module if not set
+    pub fn module(mut self, module: impl Into<String>) -> Self {
+        self.module = Some(module.into());
+        self
+    }
+
+    pub fn doc(mut self, doc: impl Into<String>) -> Self {
+        self.doc = doc.into();
+        self
+    }
+
+    /// Property readable from Python, like `#[pyo3(get)]`
+    pub fn getter(mut self, name: impl Into<String>, r#type: TypeInfo) -> Self {
+        self.getters.push(member(name.into(), r#type));
+        self
+    }
+
+    /// Property writable from Python, like `#[pyo3(set)]`
+    pub fn setter(mut self, name: impl Into<String>, r#type: TypeInfo) -> Self {
+        self.setters.push(member(name.into(), r#type));
+        self
+    }
+
+    /// Base class, like `#[pyclass(extends = ...)]`
+    pub fn base(mut self, base: TypeInfo) -> Self {
+        self.bases.push(base);
+        self
+    }
+
+    /// Like `#[pyclass(eq)]`
+    pub fn eq(mut self) -> Self {
+        self.has_eq = true;
+        self
+    }
+
+    /// Like `#[pyclass(ord)]`
+    pub fn ord(mut self) -> Self {
+        self.has_ord = true;
+        self
+    }
+
+    /// Like `#[pyclass(hash)]`
+    pub fn hash(mut self) -> Self {
+        self.has_hash = true;
+        self
+    }
+
+    /// Like `#[pyclass(str)]`
+    pub fn str(mut self) -> Self {
+        self.has_str = true;
+        self
+    }
+
+    /// Like `#[pyclass(subclass)]`
+    pub fn subclass(mut self) -> Self {
+        self.subclass = true;
+        self
+    }
+
+    /// Like `#[gen_stub(picklable)]`
+    pub fn picklable(mut self) -> Self {
+        self.picklable = true;
+        self
+    }
+
+    /// Names listed in `__slots__`, like `#[gen_stub(slots)]`
+    pub fn slots(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.slots = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Like `#[gen_stub(dataclass_transform(...))]`
+    pub fn dataclass_transform(mut self, info: DataclassTransformInfo) -> Self {
+        self.dataclass_transform = Some(info);
+        self
+    }
+
+    /// Rust type of the class if set, and its definition
+    pub(crate) fn build(self) -> (Option<TypeId>, ClassDef) {
+        // Reuse the conversion of `#[gen_stub_pyclass]` for the methods implied by the flags
+        let info = PyClassInfo {
+            struct_id: TypeId::of::<()>,
+            pyclass_name: leak_str(self.pyclass_name),
+            module: self.module.map(leak_str),
+            doc: "",
+            getters: &[],
+            setters: &[],
+            bases: &[],
+            has_eq: self.has_eq,
+            has_ord: self.has_ord,
+            has_hash: self.has_hash,
+            has_str: self.has_str,
+            subclass: self.subclass,
+            buffer: false,
+            picklable: self.picklable,
+            slots: None,
+            item_access: &[],
+            iterator: None,
+            type_ignored: None,
+            dataclass_transform: self.dataclass_transform,
+            file: "",
+            line: 0,
+            column: 0,
+        };
+        let mut class = ClassDef::from(&info);
+        class.doc = leak_doc(&self.doc);
+        class.bases.extend(self.bases);
+        for getter in self.getters {
+            class
+                .getter_setters
+                .insert(getter.name.to_string(), (Some(getter), None));
+        }
+        for setter in self.setters {
+            class
+                .getter_setters
+                .entry(setter.name.to_string())
+                .or_default()
+                .1 = Some(setter);
+        }
+        if let Some(slots) = &self.slots {
+            class.add_slots(&slots.iter().map(String::as_str).collect::<Vec<_>>());
+        }
+        (self.rust_type, class)
+    }
+}
+
+/// Builder of a function from owned values
+#[derive(Debug)]
+pub struct PyFunctionInfoBuilder {
+    name: String,
+    module: Option<String>,
+    doc: String,
+    parameters: Vec<Parameter>,
+    r#return: TypeInfo,
+    is_async: bool,
+    releases_gil: bool,
+}
+
+impl PyFunctionInfoBuilder {
+    /// Function of the Python name `name` returning `None` unless [returns](Self::returns) is set
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            module: None,
+            doc: String::new(),
+            parameters: Vec::new(),
+            r#return: TypeInfo::none(),
+            is_async: false,
+            releases_gil: false,
+        }
+    }
+
+    /// Module of the function, the default module if not set
+    pub fn module(mut self, module: impl Into<String>) -> Self {
+        self.module = Some(module.into());
+        self
+    }
+
+    pub fn doc(mut self, doc: impl Into<String>) -> Self {
+        self.doc = doc.into();
+        self
+    }
+
+    /// Positional or keyword parameter without default
+    pub fn parameter(self, name: impl Into<String>, type_info: TypeInfo) -> Self {
+        self.parameter_of_kind(name, ParameterKind::PositionalOrKeyword, type_info)
+    }
+
+    /// Parameter of the kind `kind` without default
+    pub fn parameter_of_kind(
+        mut self,
+        name: impl Into<String>,
+        kind: ParameterKind,
+        type_info: TypeInfo,
+    ) -> Self {
+        self.parameters.push(Parameter {
+            name: leak_str(name.into()),
+            kind,
+            type_info,
+            default: generate::ParameterDefault::None,
+        });
+        self
+    }
+
+    pub fn returns(mut self, r#return: TypeInfo) -> Self {
+        self.r#return = r#return;
+        self
+    }
+
+    /// Declare as `async def`
+    pub fn asynchronous(mut self) -> Self {
+        self.is_async = true;
+        self
+    }
+
+    /// Note in the documentation that the function releases the GIL while running
+    pub fn releases_gil(mut self) -> Self {
+        self.releases_gil = true;
+        self
+    }
+
+    /// Module of the function if set, and its definition
+    pub(crate) fn build(self) -> (Option<String>, FunctionDef) {
+        let mut parameters = generate::Parameters::new();
+        for parameter in self.parameters {
+            match parameter.kind {
+                ParameterKind::PositionalOnly => parameters.positional_only.push(parameter),
+                ParameterKind::PositionalOrKeyword => {
+                    parameters.positional_or_keyword.push(parameter)
+                }
+                ParameterKind::KeywordOnly => parameters.keyword_only.push(parameter),
+                ParameterKind::VarPositional => parameters.varargs = Some(parameter),
+                ParameterKind::VarKeyword => parameters.varkw = Some(parameter),
+            }
+        }
+        let function = FunctionDef {
+            name: leak_str(self.name),
+            parameters,
+            r#return: self.r#return,
+            error: None,
+            raises: Vec::new(),
+            doc: leak_doc(&self.doc),
+            is_async: self.is_async,
+            deprecated: None,
+            type_ignored: None,
+            is_overload: false,
+            platform: None,
+            py_version: None,
+            releases_gil: self.releases_gil,
+            file: "",
+            line: 0,
+            column: 0,
+            index: 0,
+        };
+        (self.module, function)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builders() {
+        struct Point;
+        let (id, class) = PyClassInfoBuilder::new("Point")
+            .rust_type::<Point>()
+            .module("pkg")
+            .getter("x", f64::type_output())
+            .setter("x", f64::type_input())
+            .eq()
+            .build();
+        assert_eq!(class.name, "Point");
+        assert_eq!(class.module, Some("pkg"));
+        assert_eq!(id, Some(TypeId::of::<Point>()));
+        let (getter, setter) = &class.getter_setters["x"];
+        assert!(getter.is_some() && setter.is_some());
+        assert!(class.methods.contains_key("__eq__") && class.unhashable);
+
+        // Types computed at runtime are accepted as they are
+        let mut point = TypeInfo::locally_defined("Point", "pkg".into());
+        point.name = format!("{}3D", point.name);
+        let (module, function) = PyFunctionInfoBuilder::new(format!("scale_{}", 2))
+            .parameter("point", point)
+            .parameter_of_kind("factor", ParameterKind::KeywordOnly, f64::type_input())
+            .returns(f64::type_output())
+            .build();
+        assert_eq!(function.name, "scale_2");
+        assert_eq!(module, None);
+        assert_eq!(
+            function.parameters.positional_or_keyword[0].type_info.name,
+            "Point3D"
+        );
+        assert_eq!(function.parameters.keyword_only[0].name, "factor");
+        assert_eq!(function.r#return.name, "builtins.float");
+
+        let (id, _) = PyClassInfoBuilder::new("Dynamic").build();
+        assert_eq!(id, None);
+    }
+}